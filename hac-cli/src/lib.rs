@@ -1,6 +1,6 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 
 /// How the runtime should behave. Dictated by the flags provided to  `Cli`
 #[derive(Debug, PartialEq)]
@@ -9,20 +9,101 @@ pub enum RuntimeBehavior {
     /// that means. Will print wether or not HAC_CONFIG is set, and if so where
     /// it points to, will print `$XDG_CONFIG_HOME`, and also `$HOME/.config`
     PrintConfigPath,
-    /// will print all directories `HAC` is looking for collections, this will
-    /// also print the path specified on the configuration file, if any.
+    /// will print the directories `HAC` stores collections, cache and state
+    /// on, which `HAC_DATA_DIR`, `HAC_CACHE_DIR` and `HAC_STATE_DIR` override.
     PrintDataPath,
     /// will dump the default configuration to stdout instead of running the
     /// application.
     DumpDefaultConfig,
+    /// will print the configuration every layer adds up to, and which layer
+    /// each changed value comes from, instead of running the application.
+    PrintResolvedConfig,
+    /// will print the version, and with `verbose` the commit, features and
+    /// grammars it was built with, instead of running the application.
+    PrintVersion { verbose: bool },
     /// will run the application with all disk-synchronization disabled. That
     /// means `HAC` wont't save any files or changes to collection to disk.
     DryRun,
+    /// will export the documentation of a collection instead of running the
+    /// application, printing it to stdout when no output file is given.
+    ExportDocs {
+        collection: String,
+        format: ExportFormat,
+        output: Option<PathBuf>,
+    },
+    /// will resolve a request against two environments and print how they
+    /// differ instead of running the application.
+    CompareEnvironments {
+        collection: String,
+        request: String,
+        left: String,
+        right: String,
+    },
+    /// will send a request once for every row of a data file and print a
+    /// table of the results instead of running the application.
+    Iterate {
+        collection: String,
+        request: String,
+        data: PathBuf,
+        results: Option<PathBuf>,
+    },
+    /// will send a request and save its body to a file, resuming an earlier
+    /// download that was interrupted, instead of running the application.
+    Download {
+        collection: String,
+        request: String,
+        path: PathBuf,
+    },
+    /// will create a collection out of a REST Client (`.http`), hurl, WSDL or
+    /// OpenAPI file, or a bruno collection directory, instead of running the
+    /// application.
+    Import(PathBuf),
+    /// will export the requests of a collection as a REST Client or hurl
+    /// file instead of running the application, printing it to stdout when
+    /// no output file is given.
+    ExportRequests {
+        collection: String,
+        format: RequestFileFormat,
+        output: Option<PathBuf>,
+    },
+    /// will check the variables used by a collection and print every
+    /// problem found instead of running the application.
+    Lint(String),
+    /// will print which requests of a collection have to be sent before
+    /// which others instead of running the application.
+    Flow(String),
+    /// will print a summary of what needs attention on a collection instead
+    /// of running the application.
+    Report(String),
+    /// will print the header and claims of a json web token instead of
+    /// running the application.
+    DecodeJwt(String),
+    /// will sign the given claims with HS256 and print the token instead of
+    /// running the application.
+    SignJwt { claims: String, secret: String },
     /// the default running behavior of the application, this is the default
     /// behavior for `HAC`.
     Run,
 }
 
+/// formats the documentation of a collection can be exported to
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+}
+
+/// request file formats of other tools collections can be exported to
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum RequestFileFormat {
+    /// VS Code REST Client `.http` files
+    Http,
+    Hurl,
+    /// a bruno collection, with a `.bru` file for each request, written to
+    /// the directory given as output
+    Bruno,
+}
+
 #[derive(Parser, Debug)]
 pub struct Cli {
     /// prints the directory in which the config file is being loaded from
@@ -31,13 +112,106 @@ pub struct Cli {
     /// dumps the default configuration to stdout.
     #[arg(long)]
     config_dump: bool,
-    /// prints the directory in which the collections are being stored
+    /// prints the configuration after merging the default, system, user and
+    /// workspace (`.hac/hac.toml`) layers, in that order of precedence.
+    #[arg(long)]
+    config_resolved: bool,
+    /// prints the version of hac.
+    #[arg(long, short = 'V')]
+    version: bool,
+    /// also prints the commit, target, features and grammar versions hac
+    /// was built with, to be included in bug reports.
+    #[arg(long, requires = "version")]
+    verbose: bool,
+    /// prints the directories in which the collections, cache and state are
+    /// being stored
     #[arg(long)]
     data_dir: bool,
     /// wether or not we should sync changes to the disk, when --dry-run is
     /// specified, no collection, request, or anything will be saved to disk.
     #[arg(long)]
     dry_run: bool,
+    /// exports the documentation of the collection with the given name (or
+    /// file name) instead of running the application.
+    #[arg(long, value_name = "COLLECTION", group = "export")]
+    export_docs: Option<String>,
+    /// format of the exported documentation.
+    #[arg(long, value_enum, default_value_t = ExportFormat::Markdown, requires = "export_docs")]
+    format: ExportFormat,
+    /// exports the requests of the collection with the given name (or file
+    /// name) as a file other tools can send, instead of running the
+    /// application.
+    #[arg(long, value_name = "COLLECTION", group = "export", requires = "to")]
+    export_requests: Option<String>,
+    /// format of the exported requests.
+    #[arg(long, value_enum, requires = "export_requests")]
+    to: Option<RequestFileFormat>,
+    /// creates a collection from a REST Client (`.http` or `.rest`) or hurl
+    /// (`.hurl`) file, variables declared on it become an environment. A
+    /// bruno collection is imported when given its directory, the SOAP
+    /// operations of a `.wsdl` file become requests with envelope templates,
+    /// and the operations of an OpenAPI `.json` document become requests
+    /// with bodies built from their examples or schemas.
+    #[arg(long, value_name = "FILE")]
+    import: Option<PathBuf>,
+    /// file to write the exported documentation or requests to, defaults to
+    /// stdout.
+    #[arg(long, short, requires = "export")]
+    output: Option<PathBuf>,
+    /// resolves a request against two environments and prints the
+    /// differences on the uri, headers and body.
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["LEFT", "RIGHT"],
+        requires_all = ["collection", "request"]
+    )]
+    compare_envs: Option<Vec<String>>,
+    /// sends a request once for every row of a `.csv` or `.json` data file,
+    /// with the values of the row as variables, and prints a table of the
+    /// results. Columns of a csv file and keys of the objects of a json
+    /// array name the variables.
+    #[arg(long, value_name = "FILE", requires_all = ["collection", "request"])]
+    iterate: Option<PathBuf>,
+    /// also writes the results of --iterate to a `.csv` or `.json` file,
+    /// with the status, duration, failure and captured values of each row.
+    #[arg(long, value_name = "FILE", requires = "iterate")]
+    results: Option<PathBuf>,
+    /// sends a request and saves its body to a file, printing the progress.
+    /// An interrupted download is resumed from where it stopped when the
+    /// server supports ranges and the body didn't change.
+    #[arg(long, value_name = "FILE", requires_all = ["collection", "request"])]
+    download: Option<PathBuf>,
+    /// checks the collection with the given name (or file name) for
+    /// undefined and unused variables instead of running the application.
+    #[arg(long, value_name = "COLLECTION")]
+    lint: Option<String>,
+    /// prints how the requests of the collection with the given name (or
+    /// file name) depend on each other instead of running the application.
+    #[arg(long, value_name = "COLLECTION")]
+    flow: Option<String>,
+    /// prints the number of requests per folder of the collection with the
+    /// given name (or file name), which requests lack docs or checks, unused
+    /// variables, duplicate names and how each request did the last time it
+    /// was sent.
+    #[arg(long, value_name = "COLLECTION")]
+    report: Option<String>,
+    /// decodes a json web token and prints its header, claims and expiry.
+    #[arg(long, value_name = "TOKEN")]
+    jwt_decode: Option<String>,
+    /// signs the given json claims with HS256, to generate tokens for local
+    /// development.
+    #[arg(long, value_name = "CLAIMS", requires = "secret")]
+    jwt_sign: Option<String>,
+    /// secret used to sign tokens with --jwt-sign.
+    #[arg(long, requires = "jwt_sign")]
+    secret: Option<String>,
+    /// name (or file name) of the collection the request lives in.
+    #[arg(long)]
+    collection: Option<String>,
+    /// name or id of the request to compare, iterate or download.
+    #[arg(long)]
+    request: Option<String>,
 }
 
 impl Cli {
@@ -53,6 +227,76 @@ impl Cli {
         if args.config_dump {
             return RuntimeBehavior::DumpDefaultConfig;
         }
+        if args.config_resolved {
+            return RuntimeBehavior::PrintResolvedConfig;
+        }
+        if args.version {
+            return RuntimeBehavior::PrintVersion {
+                verbose: args.verbose,
+            };
+        }
+        if let Some(collection) = args.export_docs {
+            return RuntimeBehavior::ExportDocs {
+                collection,
+                format: args.format,
+                output: args.output,
+            };
+        }
+        if let (Some(collection), Some(format)) = (args.export_requests, args.to) {
+            return RuntimeBehavior::ExportRequests {
+                collection,
+                format,
+                output: args.output,
+            };
+        }
+        if let Some(path) = args.import {
+            return RuntimeBehavior::Import(path);
+        }
+        if let (Some(data), Some(collection), Some(request)) =
+            (args.iterate, args.collection.clone(), args.request.clone())
+        {
+            return RuntimeBehavior::Iterate {
+                collection,
+                request,
+                data,
+                results: args.results,
+            };
+        }
+        if let (Some(path), Some(collection), Some(request)) =
+            (args.download, args.collection.clone(), args.request.clone())
+        {
+            return RuntimeBehavior::Download {
+                collection,
+                request,
+                path,
+            };
+        }
+        if let (Some(envs), Some(collection), Some(request)) =
+            (args.compare_envs, args.collection, args.request)
+        {
+            let [left, right] = <[String; 2]>::try_from(envs).expect("clap requires two values");
+            return RuntimeBehavior::CompareEnvironments {
+                collection,
+                request,
+                left,
+                right,
+            };
+        }
+        if let Some(collection) = args.lint {
+            return RuntimeBehavior::Lint(collection);
+        }
+        if let Some(collection) = args.flow {
+            return RuntimeBehavior::Flow(collection);
+        }
+        if let Some(collection) = args.report {
+            return RuntimeBehavior::Report(collection);
+        }
+        if let Some(token) = args.jwt_decode {
+            return RuntimeBehavior::DecodeJwt(token);
+        }
+        if let (Some(claims), Some(secret)) = (args.jwt_sign, args.secret) {
+            return RuntimeBehavior::SignJwt { claims, secret };
+        }
         if args.dry_run {
             return RuntimeBehavior::DryRun;
         }
@@ -60,7 +304,7 @@ impl Cli {
         RuntimeBehavior::Run
     }
 
-    pub fn print_data_path<P>(data_path: P)
+    pub fn print_data_path<P>(data_path: P, cache_path: P, state_path: P)
     where
         P: AsRef<Path>,
     {
@@ -68,7 +312,17 @@ impl Cli {
             "collections are being stored at: {}",
             data_path.as_ref().to_string_lossy()
         );
-        println!("you can change this on the configuration file by specifying `collections_dir`");
+        println!(
+            "cache is being stored at: {}",
+            cache_path.as_ref().to_string_lossy()
+        );
+        println!(
+            "state is being stored at: {}",
+            state_path.as_ref().to_string_lossy()
+        );
+        println!(
+            "you can change these by setting `HAC_DATA_DIR`, `HAC_CACHE_DIR` or `HAC_STATE_DIR`"
+        );
     }
 
     pub fn print_config_path<P>(maybe_path: Option<P>, usual_path: P)
@@ -93,4 +347,12 @@ impl Cli {
     pub fn print_default_config(config_as_str: &str) {
         println!("{}", config_as_str)
     }
+
+    pub fn print_resolved_config(rendered: &str) {
+        print!("{}", rendered)
+    }
+
+    pub fn print_version(rendered: &str) {
+        print!("{}", rendered)
+    }
 }
@@ -1,19 +1,31 @@
-use hac_core::{collection::Collection, command::Command};
+use hac_core::{collection::Collection, command::Command, update};
 
 use crate::event_pool::{Event, EventPool};
+use crate::external::{self, ExternalProgram};
+use crate::hyperlinks;
 use crate::pages::{Eventful, Renderable};
 use crate::screen_manager::ScreenManager;
 
 use std::io::Stdout;
+use std::time::{Duration, Instant};
 
 use ratatui::{backend::CrosstermBackend, Terminal};
 use tokio::sync::mpsc;
 
+/// how often the configuration files are checked for changes
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 pub struct App<'app> {
     event_pool: EventPool,
     terminal: Terminal<CrosstermBackend<Stdout>>,
     should_quit: bool,
+    hyperlinks: bool,
+    diff_tool: String,
     screen_manager: ScreenManager<'app>,
+    config_watcher: hac_config::ConfigWatcher,
+    config_checked_at: Instant,
+    /// whether to look for a newer release once the app starts
+    check_updates: bool,
 }
 
 impl<'app> App<'app> {
@@ -34,10 +46,51 @@ impl<'app> App<'app> {
             )?,
             event_pool: EventPool::new(60f64, 30f64),
             should_quit: false,
+            hyperlinks: config.hyperlinks,
+            diff_tool: config.diff_tool.clone(),
+            config_watcher: hac_config::ConfigWatcher::new(),
+            config_checked_at: Instant::now(),
+            check_updates: config.check_updates && !config.offline && !dry_run,
             terminal,
         })
     }
 
+    /// applies the configuration once any of its files change, a
+    /// configuration that fails to load is reported and the current one is
+    /// kept
+    fn watch_config(&mut self) {
+        if self.config_checked_at.elapsed() < CONFIG_POLL_INTERVAL {
+            return;
+        }
+        self.config_checked_at = Instant::now();
+        if !self.config_watcher.changed() {
+            return;
+        }
+
+        let resolved = hac_config::resolve_config();
+        if !resolved.issues.is_empty() {
+            let issues = resolved
+                .issues
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            tracing::error!("failed to reload config: {:?}", issues);
+            self.screen_manager.config_failed(issues.join("\n"));
+            return;
+        }
+
+        // every component borrows the config and colors for as long as the
+        // app runs, so the reloaded ones are leaked to outlive them, one of
+        // each per reload. This only happens when the user edits the
+        // configuration, so it is small
+        let config: &'static hac_config::Config = Box::leak(Box::new(resolved.config));
+        let colors: &'static hac_colors::Colors = Box::leak(Box::new(build_colors(config)));
+        self.hyperlinks = config.hyperlinks;
+        self.diff_tool.clone_from(&config.diff_tool);
+        self.screen_manager
+            .reload_config(colors, config, resolved.files.len());
+    }
+
     /// this is the main method which starts the event loop task, listen for events and commands
     /// to pass them down the chain, and render the terminal screen
     pub async fn run(&mut self) -> anyhow::Result<()> {
@@ -48,12 +101,36 @@ impl<'app> App<'app> {
 
         self.screen_manager
             .register_command_handler(command_tx.clone())?;
+        if self.check_updates {
+            spawn_update_check(command_tx.clone());
+        }
 
         loop {
             {
                 while let Ok(command) = command_rx.try_recv() {
                     match command {
                         Command::Quit => self.should_quit = true,
+                        Command::OpenExternal {
+                            contents,
+                            extension,
+                            edit,
+                        } => {
+                            let result = self.open_external(&contents, &extension, edit)?;
+                            self.screen_manager
+                                .handle_command(Command::ExternalClosed(result));
+                        }
+                        Command::DiffExternal {
+                            left,
+                            right,
+                            extension,
+                        } => {
+                            let result = self.suspend(|app| {
+                                external::diff(&app.diff_tool, &left, &right, &extension)
+                                    .map(|_| None)
+                            })?;
+                            self.screen_manager
+                                .handle_command(Command::ExternalClosed(result));
+                        }
                         _ => self.screen_manager.handle_command(command),
                     }
                 }
@@ -61,10 +138,13 @@ impl<'app> App<'app> {
 
             if let Some(event) = self.event_pool.next().await {
                 match event {
-                    Event::Tick => self.screen_manager.handle_tick()?,
+                    Event::Tick => {
+                        self.watch_config();
+                        self.screen_manager.handle_tick()?
+                    }
                     Event::Resize(new_size) => self.screen_manager.resize(new_size),
                     Event::Render => {
-                        self.terminal.draw(|f| {
+                        let frame = self.terminal.draw(|f| {
                             let result = self.screen_manager.draw(f, f.size());
                             if let Err(e) = result {
                                 command_tx
@@ -72,6 +152,11 @@ impl<'app> App<'app> {
                                     .expect("failed to send command through channel");
                             }
                         })?;
+
+                        if self.hyperlinks {
+                            let links = hyperlinks::find_hyperlinks(frame.buffer);
+                            hyperlinks::write_hyperlinks(self.terminal.backend_mut(), &links)?;
+                        }
                     }
                     event => {
                         if let Some(command) =
@@ -93,13 +178,62 @@ impl<'app> App<'app> {
         shutdown()?;
         Ok(())
     }
+
+    /// hands the terminal over to the editor or pager until it exits
+    fn open_external(
+        &mut self,
+        contents: &str,
+        extension: &str,
+        edit: bool,
+    ) -> anyhow::Result<Result<Option<String>, String>> {
+        let program = match edit {
+            true => ExternalProgram::Editor,
+            false => ExternalProgram::Pager,
+        };
+
+        self.suspend(|_| {
+            external::open(&program.command(), contents, extension)
+                .map(|edited| edit.then_some(edited))
+        })
+    }
+
+    /// restores the terminal to how it was before hac started and runs `f`,
+    /// taking the terminal back afterwards. Failing to run the external
+    /// program is not fatal, so its error is returned to be displayed
+    fn suspend<F>(&mut self, f: F) -> anyhow::Result<Result<Option<String>, String>>
+    where
+        F: FnOnce(&Self) -> anyhow::Result<Option<String>>,
+    {
+        self.event_pool.stop();
+        shutdown()?;
+        let result = f(self).map_err(|e| e.to_string());
+        startup()?;
+        self.terminal.clear()?;
+        self.event_pool.start();
+
+        Ok(result)
+    }
+}
+
+/// the colors of the configured palette, with symbols shown when asked for
+pub fn build_colors(config: &hac_config::Config) -> hac_colors::Colors {
+    let mut colors = match config.theme.palette {
+        hac_config::Palette::Default => hac_colors::Colors::default(),
+        hac_config::Palette::ColorBlind => hac_colors::Colors::color_blind(),
+    };
+    colors.indicators.symbols |= config.theme.symbols;
+    colors
 }
 
 /// before initializing the app, we must setup the terminal to enable all the features
 /// we need, such as raw mode and entering the alternate screen
 fn startup() -> anyhow::Result<()> {
     crossterm::terminal::enable_raw_mode()?;
-    crossterm::execute!(std::io::stdout(), crossterm::terminal::EnterAlternateScreen)?;
+    crossterm::execute!(
+        std::io::stdout(),
+        crossterm::terminal::EnterAlternateScreen,
+        crossterm::event::EnableBracketedPaste
+    )?;
 
     std::panic::set_hook(Box::new(|info| {
         tracing::error!("{info:?}");
@@ -112,6 +246,23 @@ fn startup() -> anyhow::Result<()> {
 /// them have a usable terminal
 fn shutdown() -> anyhow::Result<()> {
     crossterm::terminal::disable_raw_mode()?;
-    crossterm::execute!(std::io::stdout(), crossterm::terminal::LeaveAlternateScreen)?;
+    crossterm::execute!(
+        std::io::stdout(),
+        crossterm::event::DisableBracketedPaste,
+        crossterm::terminal::LeaveAlternateScreen
+    )?;
     Ok(())
 }
+
+/// looks for a newer release without blocking the interface, failing to do
+/// so is only logged as it doesn't affect using hac
+fn spawn_update_check(command_tx: mpsc::UnboundedSender<Command>) {
+    tokio::spawn(async move {
+        let path = hac_config::update_check_file();
+        match update::check(&path, env!("CARGO_PKG_VERSION")).await {
+            Ok(Some(latest)) => _ = command_tx.send(Command::UpdateAvailable(latest)),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("failed to check for updates: {e}"),
+        }
+    });
+}
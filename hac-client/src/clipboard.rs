@@ -0,0 +1,41 @@
+use std::io::Write;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+/// copies the text to the clipboard of the terminal emulator through an OSC 52
+/// escape sequence. As it is the terminal setting the clipboard, this works
+/// over ssh and without any clipboard utility installed, as long as the
+/// terminal supports it
+pub fn copy(text: &str) -> anyhow::Result<()> {
+    let inside_tmux = std::env::var_os("TMUX").is_some();
+    let mut stdout = std::io::stdout();
+    stdout.write_all(osc52(text, inside_tmux).as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// builds the sequence to set the clipboard to the text, tmux swallows escape
+/// sequences it doesn't know, so inside of it the sequence is wrapped to be
+/// passed through to the outer terminal
+pub fn osc52(text: &str, inside_tmux: bool) -> String {
+    let sequence = format!("\x1b]52;c;{}\x07", STANDARD.encode(text));
+    match inside_tmux {
+        true => format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b")),
+        false => sequence,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52() {
+        assert_eq!(osc52("hello", false), "\x1b]52;c;aGVsbG8=\x07");
+        assert_eq!(
+            osc52("hello", true),
+            "\x1bPtmux;\x1b\x1b]52;c;aGVsbG8=\x07\x1b\\"
+        );
+    }
+}
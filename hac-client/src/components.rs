@@ -1,2 +1,3 @@
 pub mod component_styles;
 pub mod list_item;
+pub mod variable_preview;
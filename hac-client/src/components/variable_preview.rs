@@ -0,0 +1,109 @@
+use hac_core::collection::environment::{VariablePreview, VariableScope};
+use hac_core::collection::local;
+
+use crate::i18n::Message;
+use crate::pages::collection_viewer::collection_store::CollectionStore;
+
+use std::collections::BTreeMap;
+use std::ops::Add;
+
+use ratatui::layout::Rect;
+use ratatui::style::{Style, Stylize};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+/// the preview of the variable at the end of a single line input, where its
+/// cursor is. It is only resolved again when the text of the input changes
+#[derive(Debug)]
+pub struct InputVariablePreview {
+    environment: Option<String>,
+    local_variables: BTreeMap<String, String>,
+    /// the text the preview was resolved for
+    text: Option<String>,
+    preview: Option<VariablePreview>,
+}
+
+impl InputVariablePreview {
+    pub fn new(environment: Option<&str>) -> Self {
+        InputVariablePreview {
+            environment: environment.map(str::to_string),
+            local_variables: local::workspace_variables(environment),
+            text: None,
+            preview: None,
+        }
+    }
+
+    pub fn resolve(&mut self, store: &CollectionStore, text: &str) -> Option<&VariablePreview> {
+        if self.text.as_deref().ne(&Some(text)) {
+            // the cursor is past the last char, which closes the variable
+            self.preview = text.chars().count().checked_sub(1).and_then(|char_idx| {
+                store.preview_variable(
+                    self.environment.as_deref(),
+                    &self.local_variables,
+                    text,
+                    char_idx,
+                )
+            });
+            self.text = Some(text.to_string());
+        }
+        self.preview.as_ref()
+    }
+}
+
+/// draws what the variable under the cursor resolves to on a popup below
+/// the cursor, moving it above when there is no room left on `bounds`
+pub fn draw_variable_preview(
+    frame: &mut Frame,
+    preview: &VariablePreview,
+    (cursor_col, cursor_row): (u16, u16),
+    bounds: Rect,
+    colors: &hac_colors::Colors,
+) {
+    let (content, color) = match (preview.value.as_ref(), preview.scope.as_ref()) {
+        (Some(value), Some(VariableScope::Environment(env))) => {
+            (format!("{value} ({env})"), colors.normal.green)
+        }
+        (Some(value), Some(VariableScope::Local)) => {
+            (Message::VariableLocal.format(&[value]), colors.normal.green)
+        }
+        (Some(value), Some(VariableScope::Session)) => (
+            Message::VariableSession.format(&[value]),
+            colors.normal.green,
+        ),
+        (Some(value), Some(VariableScope::Function)) => (
+            Message::VariableRandom.format(&[value]),
+            colors.normal.green,
+        ),
+        _ if preview.defined_in.is_empty() => (
+            Message::VariableUndefined.text().to_string(),
+            colors.normal.red,
+        ),
+        _ => (
+            Message::VariableOnlyOn.format(&[&preview.defined_in.join(", ")]),
+            colors.normal.yellow,
+        ),
+    };
+
+    let width = (content.chars().count().max(preview.name.len()) as u16)
+        .add(4)
+        .min(bounds.width);
+    let height = 3;
+
+    let y = match cursor_row.add(1).add(height).le(&bounds.bottom()) {
+        true => cursor_row.add(1),
+        false => cursor_row.saturating_sub(height).max(bounds.y),
+    };
+    let x = cursor_col.min(bounds.right().saturating_sub(width));
+    let area = Rect::new(x, y, width, height.min(bounds.height));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(content).fg(color).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(preview.name.clone())
+                .border_style(Style::default().fg(colors.bright.black)),
+        ),
+        area,
+    );
+}
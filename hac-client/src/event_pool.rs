@@ -7,6 +7,8 @@ use std::ops::Div;
 pub enum Event {
     Key(crossterm::event::KeyEvent),
     Resize(Rect),
+    /// text pasted on the terminal, which arrives at once instead of as keys
+    Paste(String),
     Tick,
     Render,
 }
@@ -19,6 +21,7 @@ pub struct EventPool {
     event_tx: tokio::sync::mpsc::UnboundedSender<Event>,
     frame_rate: f64,
     tick_rate: f64,
+    task: Option<tokio::task::JoinHandle<()>>,
 }
 
 impl EventPool {
@@ -30,6 +33,7 @@ impl EventPool {
             event_tx,
             frame_rate,
             tick_rate,
+            task: None,
         }
     }
 
@@ -39,7 +43,7 @@ impl EventPool {
         let tick_delay = std::time::Duration::from_secs_f64(1.0.div(self.tick_rate));
 
         let event_tx = self.event_tx.clone();
-        tokio::spawn(async move {
+        self.task = Some(tokio::spawn(async move {
             let mut reader = crossterm::event::EventStream::new();
             let mut render_interval = tokio::time::interval(render_delay);
             let mut tick_interval = tokio::time::interval(tick_delay);
@@ -60,6 +64,9 @@ impl EventPool {
                             Some(Ok(CrosstermEvent::Resize(width, height))) => event_tx
                                 .send(Event::Resize(Rect::new(0, 0, width, height)))
                                 .expect("failed to send event through channel"),
+                            Some(Ok(CrosstermEvent::Paste(text))) => event_tx
+                                .send(Event::Paste(text))
+                                .expect("failed to send event through channel"),
                             _ => {}
                         }
                     }
@@ -71,7 +78,16 @@ impl EventPool {
                     },
                 }
             }
-        });
+        }));
+    }
+
+    /// stops reading events from the terminal, so another program can take
+    /// over it, `start` resumes it
+    #[cfg_attr(test, mutants::skip)]
+    pub fn stop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
     }
 
     #[cfg_attr(test, mutants::skip)]
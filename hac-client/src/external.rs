@@ -0,0 +1,90 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// programs the interface can be suspended for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExternalProgram {
+    Editor,
+    Pager,
+}
+
+impl ExternalProgram {
+    /// the command configured on the environment, falling back to programs
+    /// that are available pretty much everywhere
+    pub fn command(&self) -> String {
+        let var = |name| std::env::var(name).ok().filter(|value| !value.is_empty());
+        match self {
+            ExternalProgram::Editor => var("VISUAL")
+                .or_else(|| var("EDITOR"))
+                .unwrap_or_else(|| "vi".into()),
+            ExternalProgram::Pager => var("PAGER").unwrap_or_else(|| "less".into()),
+        }
+    }
+}
+
+/// writes the contents to a temporary file and opens it with the command,
+/// waiting for it to exit. Returns what the file contains afterwards, so
+/// editors can be used to change it. The command can have arguments, like
+/// `code --wait`, and the file is always passed as the last one
+pub fn open(command: &str, contents: &str, extension: &str) -> anyhow::Result<String> {
+    let path = temp_file("", contents, extension)?;
+    let status = run(command, &[&path]);
+    let edited = std::fs::read_to_string(&path);
+    _ = std::fs::remove_file(&path);
+
+    status?;
+    Ok(edited?)
+}
+
+/// writes both texts to temporary files and opens them with the diff tool,
+/// waiting for it to exit. The files are passed as the last two arguments,
+/// left first
+pub fn diff(command: &str, left: &str, right: &str, extension: &str) -> anyhow::Result<()> {
+    let left = temp_file("-left", left, extension)?;
+    let right = temp_file("-right", right, extension)?;
+    let status = run(command, &[&left, &right]);
+    _ = std::fs::remove_file(&left);
+    _ = std::fs::remove_file(&right);
+
+    status
+}
+
+fn temp_file(suffix: &str, contents: &str, extension: &str) -> anyhow::Result<PathBuf> {
+    let name = format!("hac-{}{suffix}.{extension}", uuid::Uuid::new_v4());
+    let path = std::env::temp_dir().join(name);
+    std::fs::write(&path, contents)?;
+    Ok(path)
+}
+
+fn run(command: &str, paths: &[&Path]) -> anyhow::Result<()> {
+    let mut args = command.split_whitespace();
+    let Some(program) = args.next() else {
+        anyhow::bail!("no program to open the file with");
+    };
+
+    let status = Command::new(program)
+        .args(args)
+        .args(paths)
+        .status()
+        .map_err(|e| anyhow::anyhow!("failed to run {program}: {e}"))?;
+    if !status.success() {
+        anyhow::bail!("{program} exited with {status}");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open() {
+        assert_eq!(open("true", "{}", "json").unwrap(), "{}");
+        assert!(open("false", "{}", "json").is_err());
+        assert!(open("", "{}", "json").is_err());
+
+        assert!(diff("cmp -s", "same", "same", "txt").is_ok());
+        assert!(diff("cmp -s", "left", "right", "txt").is_err());
+    }
+}
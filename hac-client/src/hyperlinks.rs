@@ -0,0 +1,168 @@
+use std::io::Write;
+
+use crossterm::cursor::{MoveTo, RestorePosition, SavePosition};
+use crossterm::queue;
+use crossterm::style::{Attribute, Print, ResetColor, SetAttribute, SetBackgroundColor};
+use crossterm::style::{SetForegroundColor, SetUnderlineColor};
+use ratatui::buffer::{Buffer, Cell};
+use ratatui::style::{Color, Modifier};
+
+/// a url found on the screen, along with the cells it spans so it can be
+/// printed again looking just like it did
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hyperlink {
+    pub x: u16,
+    pub y: u16,
+    pub url: String,
+    cells: Vec<Cell>,
+}
+
+/// finds every `http` or `https` url on the buffer. Urls are not followed
+/// across lines, as a line break on the screen is usually a border
+pub fn find_hyperlinks(buffer: &Buffer) -> Vec<Hyperlink> {
+    let area = buffer.area;
+    let mut hyperlinks = vec![];
+
+    for y in area.top()..area.bottom() {
+        let row = (area.left()..area.right())
+            .map(|x| buffer.get(x, y))
+            .collect::<Vec<_>>();
+        let line = row.iter().map(|cell| cell.symbol()).collect::<Vec<_>>();
+
+        let mut start = 0;
+        while start < line.len() {
+            let rest = line[start..].concat();
+            let Some(offset) = ["https://", "http://"]
+                .iter()
+                .filter_map(|scheme| rest.find(scheme))
+                .min()
+            else {
+                break;
+            };
+
+            // the offset is in bytes, but we need it in cells
+            let mut begin = start;
+            let mut bytes = 0;
+            while bytes < offset {
+                bytes += line[begin].len();
+                begin += 1;
+            }
+
+            let mut end = begin;
+            while end < line.len() && is_url_symbol(line[end]) {
+                end += 1;
+            }
+            while end > begin && line[end - 1].ends_with(['.', ',', ';', ':', '!', '?']) {
+                end -= 1;
+            }
+
+            let url = line[begin..end].concat();
+            if !url.ends_with("//") {
+                hyperlinks.push(Hyperlink {
+                    x: area.left() + begin as u16,
+                    y,
+                    url,
+                    cells: row[begin..end].iter().map(|cell| (*cell).clone()).collect(),
+                });
+            }
+            start = end.max(begin + 1);
+        }
+    }
+
+    hyperlinks
+}
+
+fn is_url_symbol(symbol: &str) -> bool {
+    symbol.chars().next().is_some_and(|c| {
+        c.is_ascii_graphic() && !matches!(c, '"' | '\'' | '<' | '>' | '`' | '{' | '}' | '|')
+    })
+}
+
+/// prints the urls again, wrapped on OSC 8 escape sequences so terminals that
+/// support them make the urls clickable. The cursor is restored afterwards
+pub fn write_hyperlinks<W>(writer: &mut W, hyperlinks: &[Hyperlink]) -> std::io::Result<()>
+where
+    W: Write,
+{
+    if hyperlinks.is_empty() {
+        return Ok(());
+    }
+
+    queue!(writer, SavePosition)?;
+    for hyperlink in hyperlinks {
+        queue!(
+            writer,
+            MoveTo(hyperlink.x, hyperlink.y),
+            Print(format!("\x1b]8;;{}\x1b\\", hyperlink.url))
+        )?;
+        for cell in hyperlink.cells.iter() {
+            queue!(
+                writer,
+                SetForegroundColor(to_crossterm(cell.fg)),
+                SetBackgroundColor(to_crossterm(cell.bg)),
+                SetUnderlineColor(to_crossterm(cell.fg)),
+            )?;
+            if cell.modifier.contains(Modifier::BOLD) {
+                queue!(writer, SetAttribute(Attribute::Bold))?;
+            }
+            queue!(writer, Print(cell.symbol()), SetAttribute(Attribute::Reset))?;
+        }
+        queue!(writer, Print("\x1b]8;;\x1b\\"))?;
+    }
+    queue!(writer, ResetColor, RestorePosition)?;
+    writer.flush()
+}
+
+/// ratatui talks to an older version of crossterm than ours, so its colors
+/// are converted by hand, the same way its backend does
+fn to_crossterm(color: Color) -> crossterm::style::Color {
+    use crossterm::style::Color as C;
+    match color {
+        Color::Reset => C::Reset,
+        Color::Black => C::Black,
+        Color::Red => C::DarkRed,
+        Color::Green => C::DarkGreen,
+        Color::Yellow => C::DarkYellow,
+        Color::Blue => C::DarkBlue,
+        Color::Magenta => C::DarkMagenta,
+        Color::Cyan => C::DarkCyan,
+        Color::Gray => C::Grey,
+        Color::DarkGray => C::DarkGrey,
+        Color::LightRed => C::Red,
+        Color::LightGreen => C::Green,
+        Color::LightBlue => C::Blue,
+        Color::LightYellow => C::Yellow,
+        Color::LightMagenta => C::Magenta,
+        Color::LightCyan => C::Cyan,
+        Color::White => C::White,
+        Color::Indexed(idx) => C::AnsiValue(idx),
+        Color::Rgb(r, g, b) => C::Rgb { r, g, b },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_hyperlinks() {
+        let buffer = Buffer::with_lines(vec![
+            "│ see https://example.com/docs. │",
+            "│ \"http://a.b/c?d=1\", http:// │",
+            "│ no links here                │",
+        ]);
+
+        let hyperlinks = find_hyperlinks(&buffer)
+            .into_iter()
+            .map(|link| (link.x, link.y, link.url))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            hyperlinks,
+            vec![
+                (6, 0, "https://example.com/docs".to_string()),
+                (3, 1, "http://a.b/c?d=1".to_string()),
+            ]
+        );
+    }
+}
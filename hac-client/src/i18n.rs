@@ -0,0 +1,679 @@
+use hac_config::Locale;
+
+use std::sync::OnceLock;
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+/// sets the language every message is shown in, this is meant to be called
+/// once, while starting, and later calls have no effect
+pub fn set_locale(locale: Locale) {
+    if LOCALE.set(locale).is_err() {
+        tracing::warn!("tried to change the locale after it was set");
+    }
+}
+
+/// the language messages are shown in, english until one is set
+pub fn locale() -> Locale {
+    LOCALE.get().copied().unwrap_or_default()
+}
+
+/// every user facing message of the interface. Messages can have `{}`
+/// placeholders, filled in order by `Message::format`, so each language can
+/// place them where it reads best
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Message {
+    TerminalTooSmall,
+    MinimumSize,
+    Width,
+    Height,
+    Yes,
+    No,
+    Ok,
+    Example,
+    Modified,
+    Status,
+    Time,
+    Size,
+    Monitor,
+    MonitorFailing,
+    MonitorRecovered,
+    Digest,
+    DigestWritten,
+    Jwt,
+    NoTokenFound,
+    Requests,
+    Uri,
+    RequestEditor,
+    Response,
+    PaneFocused,
+    PaneEditing,
+    NotificationFailed,
+    RequestSummary,
+    ResponseSummary,
+    RequestFailed,
+    Clipboard,
+    CopiedBody,
+    CopiedCurl,
+    NothingToCopy,
+    ExternalProgram,
+    NothingToOpen,
+    NothingToCompare,
+    SignIn,
+    SignInRequesting,
+    SignInInstructions,
+    SignInCancel,
+    SignedIn,
+    SignInCancelled,
+    TokenRefreshing,
+    TokenRefreshed,
+    TokenExpiresIn,
+    TokenNeverExpires,
+    TokenExpired,
+    NotSignedIn,
+    SignInAgain,
+    SignInOffline,
+    Subscription,
+    SubscriptionStopped,
+    NoSubscription,
+    Streaming,
+    Following,
+    InterimResponses,
+    Offline,
+    OfflineOn,
+    OfflineOff,
+    OfflineExample,
+    Tls,
+    TlsInspecting,
+    TlsNotInspected,
+    TlsOffline,
+    Untrusted,
+    IssuedBy,
+    ExpiresIn,
+    ExpiredAgo,
+    Body,
+    BodyTruncated,
+    BodySaved,
+    Pinned,
+    NoValue,
+    PinsSaved,
+    NotJson,
+    NotATable,
+    NoPreviousResponse,
+    NothingChanged,
+    ChangedSincePrevious,
+    FilteredBy,
+    UrlBuilder,
+    Config,
+    ConfigReloaded,
+    TemplateSaved,
+    TemplateFrom,
+    UpdateAvailable,
+    UpdateVersion,
+    Undo,
+    Undone,
+    NothingToUndo,
+    ChangeCreateRequest,
+    ChangeCreateDirectory,
+    ChangeEdit,
+    ChangeDelete,
+    ChangeRename,
+    ChangeUri,
+    SyncConflict,
+    SyncConflictDescription,
+    SyncConflictOptions,
+    CollectionMerged,
+    CollectionReloaded,
+    CollectionOverwritten,
+    ImageSize,
+    SaveBinaryBody,
+    ReplaceMatch,
+    Substitutions,
+    RecordingMacro,
+    StrippedLines,
+    Completions,
+    HeaderSuggestions,
+    Name,
+    Value,
+    Description,
+    Create,
+    Cancel,
+    Enabled,
+    NewCollectionNamePlaceholder,
+    NewCollectionDescriptionPlaceholder,
+    NewCollectionHint,
+    HeadersHint,
+    HeadersHintWide,
+    HelpMoveDown,
+    HelpMoveUp,
+    HelpDeleteHeader,
+    HelpToggleHeader,
+    HelpEditHeader,
+    HelpShowHelp,
+    CloseDialog,
+    NoHeaders,
+    DeleteHeaderPrompt,
+    ConfirmOrCancel,
+    AuthHint,
+    NewAuthHint,
+    NoAuth,
+    SelectAuthMethod,
+    NoRequestDocs,
+    NoDirectoryDocs,
+    DocsEditingHint,
+    DocsDirectoryHint,
+    DocsHint,
+    Filter,
+    FilterHint,
+    PinnedFields,
+    PinnedHint,
+    ModifiedForThisSend,
+    Override,
+    OverridesHint,
+    OverridesSendHint,
+    VariableLocal,
+    VariableSession,
+    VariableRandom,
+    VariableUndefined,
+    VariableOnlyOn,
+    BodyDigest,
+    Scheme,
+    Host,
+    Path,
+    Query,
+    Fragment,
+    Preview,
+    UrlBuilderHint,
+    RenameFind,
+    RenameReplace,
+    RenamePrefix,
+    RenameSuffix,
+    NothingToRename,
+    MoreLines,
+    RenamingNamesAndUrls,
+    RenamingNames,
+    RenameHint,
+    TimeoutMs,
+    NoTimeout,
+    DuplicateRequest,
+    ReadOnly,
+    Prerequisite,
+    UrlHostPlaceholder,
+    UriTitle,
+    CommonCauses,
+    HeadersToCheck,
+    NotOnResponse,
+    Error,
+    Quota,
+    Reset,
+    OverridesPlaceholder,
+    JsonPath,
+    DigestHint,
+    ConfirmHint,
+    DeleteDirectory,
+    DeleteDirectoryRequests,
+    DeleteRequest,
+    Parent,
+    Headers,
+    NoBody,
+}
+
+impl Message {
+    /// the message on the configured language
+    pub fn text(self) -> &'static str {
+        self.translate(locale())
+    }
+
+    pub fn translate(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::English => english(self),
+            Locale::BrazilianPortuguese => brazilian_portuguese(self),
+        }
+    }
+
+    /// the message on the configured language with its placeholders replaced
+    /// by the arguments, in order
+    pub fn format(self, args: &[&str]) -> String {
+        fill(self.text(), args)
+    }
+}
+
+fn fill(template: &str, args: &[&str]) -> String {
+    let mut args = args.iter();
+    let mut pieces = template.split("{}");
+    let mut result = pieces.next().unwrap_or_default().to_string();
+    for piece in pieces {
+        result.push_str(args.next().copied().unwrap_or_default());
+        result.push_str(piece);
+    }
+    result
+}
+
+fn english(message: Message) -> &'static str {
+    match message {
+        Message::TerminalTooSmall => "Terminal is too small:",
+        Message::MinimumSize => "Minimum size needed:",
+        Message::Width => "Width = ",
+        Message::Height => "Height = ",
+        Message::Yes => "(y)es",
+        Message::No => "(n)o",
+        Message::Ok => "(O)k",
+        Message::Example => "Example: ",
+        Message::Modified => "Modified: ",
+        Message::Status => "Status: ",
+        Message::Time => "Time: ",
+        Message::Size => "Size: ",
+        Message::Monitor => "Monitor",
+        Message::MonitorFailing => "{} is failing: {}",
+        Message::MonitorRecovered => "{} recovered",
+        Message::Digest => "Digest",
+        Message::DigestWritten => "digest of the body was written",
+        Message::Jwt => "JWT",
+        Message::NoTokenFound => "no token found on the request or response",
+        Message::Requests => "requests",
+        Message::Uri => "uri",
+        Message::RequestEditor => "request editor",
+        Message::Response => "response",
+        Message::PaneFocused => "{} focused",
+        Message::PaneEditing => "editing {}",
+        Message::NotificationFailed => "{} failed: {}",
+        Message::RequestSummary => "request {} {}",
+        Message::ResponseSummary => "response {} in {}ms",
+        Message::RequestFailed => "request failed: {}",
+        Message::Clipboard => "Clipboard",
+        Message::CopiedBody => "response body copied",
+        Message::CopiedCurl => "request copied as curl",
+        Message::NothingToCopy => "nothing to copy, send the request first",
+        Message::ExternalProgram => "External program",
+        Message::NothingToOpen => "nothing to open, send the request first",
+        Message::NothingToCompare => "send the request and save an example to compare them",
+        Message::SignIn => "Sign in",
+        Message::SignInRequesting => "requesting a code to sign in...",
+        Message::SignInInstructions => "open {} and enter the code {}",
+        Message::SignInCancel => "[Esc: Cancel]",
+        Message::SignedIn => "signed in, sending the request",
+        Message::SignInCancelled => "the sign in was cancelled",
+        Message::TokenRefreshing => "refreshing the token...",
+        Message::TokenRefreshed => "token refreshed, sending the request",
+        Message::TokenExpiresIn => "signed in, the token expires in {}",
+        Message::TokenNeverExpires => "signed in, the token never expires",
+        Message::TokenExpired => "the token expired {} ago, it is renewed on the next send",
+        Message::NotSignedIn => "not signed in, sending the request signs in",
+        Message::SignInAgain => "[r: Sign in again]",
+        Message::SignInOffline => {
+            "signing in isn't available offline, the request is answered without a token"
+        }
+        Message::Subscription => "Subscription",
+        Message::SubscriptionStopped => "the subscription was stopped",
+        Message::NoSubscription => "no subscription is running",
+        Message::Streaming => "streaming",
+        Message::Following => "following",
+        Message::InterimResponses => "Interim responses",
+        Message::Offline => "Offline",
+        Message::OfflineOn => "requests are answered with their examples",
+        Message::OfflineOff => "requests are sent again",
+        Message::OfflineExample => "Offline example: ",
+        Message::Tls => "TLS",
+        Message::TlsInspecting => "handshaking with {} to inspect its session",
+        Message::TlsNotInspected => "only https requests that were sent can be inspected",
+        Message::TlsOffline => "servers aren't inspected while offline",
+        Message::Untrusted => "Untrusted: ",
+        Message::IssuedBy => "issued by ",
+        Message::ExpiresIn => "expires in {} days",
+        Message::ExpiredAgo => "expired {} days ago",
+        Message::Body => "Body",
+        Message::BodyTruncated => "showing {} of {} [F: load the whole body] [W: save to a file]",
+        Message::BodySaved => "saved to {}",
+        Message::Pinned => "Pinned",
+        Message::NoValue => "no value",
+        Message::PinsSaved => "{} pinned fields",
+        Message::NotJson => "The body is not JSON",
+        Message::NotATable => "The body is not a list of objects or a csv",
+        Message::NoPreviousResponse => "No previous send to compare with",
+        Message::NothingChanged => "Nothing changed since the previous send",
+        Message::ChangedSincePrevious => "Since the previous send: +{} -{}",
+        Message::FilteredBy => "Filtered by {}",
+        Message::UrlBuilder => "URL builder",
+        Message::Config => "Config",
+        Message::ConfigReloaded => "Reloaded from {} files",
+        Message::TemplateSaved => "Template saved",
+        Message::TemplateFrom => "New requests start like {}",
+        Message::UpdateAvailable => "Update available",
+        Message::UpdateVersion => "hac {} is available, you have {}",
+        Message::Undo => "Undo",
+        Message::Undone => "Undone",
+        Message::NothingToUndo => "Nothing to undo",
+        Message::ChangeCreateRequest => "creation of a request",
+        Message::ChangeCreateDirectory => "creation of a directory",
+        Message::ChangeEdit => "changes to {}",
+        Message::ChangeDelete => "deletion of {}",
+        Message::ChangeRename => "renaming on {}",
+        Message::ChangeUri => "uri of {}",
+        Message::SyncConflict => "Collection changed on disk",
+        Message::SyncConflictDescription => {
+            "{} was changed by another session, your changes were not saved yet"
+        }
+        Message::SyncConflictOptions => "[m: Merge] [r: Reload] [o: Overwrite]",
+        Message::CollectionMerged => "changes of both sessions were merged",
+        Message::CollectionReloaded => "the collection was reloaded from disk",
+        Message::CollectionOverwritten => "your changes replaced the ones on disk",
+        Message::ImageSize => "{} x {} pixels",
+        Message::SaveBinaryBody => "[W: Save the body to a file]",
+        Message::ReplaceMatch => "replace with {}? [y: Yes] [n: No] [a: All] [q: Quit]",
+        Message::Substitutions => "{} substitutions",
+        Message::RecordingMacro => "recording @{}",
+        Message::StrippedLines => "trailing whitespace removed from {} lines",
+        Message::Completions => "[Pick: C-n/C-p] [Accept: C-y] [Cancel: C-e]",
+        Message::HeaderSuggestions => "Suggestions [Pick: ↓/↑] [Accept: →] [Cancel: Esc]",
+        Message::Name => "Name",
+        Message::Value => "Value",
+        Message::Description => "Description",
+        Message::Create => "Create",
+        Message::Cancel => "Cancel",
+        Message::Enabled => "Enabled",
+        Message::NewCollectionNamePlaceholder => "My awesome API",
+        Message::NewCollectionDescriptionPlaceholder => "Request testing",
+        Message::NewCollectionHint => "[Tab] to switch focus [Enter] to select a button",
+        Message::HeadersHint => "[j/k -> move down/up] [enter -> select] [space -> enable/disable] [? -> help]",
+        Message::HeadersHintWide => "[j/k -> move down/up] [enter -> select] [space -> enable/disable] [d -> delete] [? -> help]",
+        Message::HelpMoveDown => "- move down",
+        Message::HelpMoveUp => "- move up",
+        Message::HelpDeleteHeader => "- deletes header",
+        Message::HelpToggleHeader => "- enables or disables header",
+        Message::HelpEditHeader => "- select header for editing",
+        Message::HelpShowHelp => "- shows this help message",
+        Message::CloseDialog => "press any key to close this dialog",
+        Message::NoHeaders => "No headers",
+        Message::DeleteHeaderPrompt => "are you sure you want to delete this header?",
+        Message::ConfirmOrCancel => "Press enter to confirm, press esc to cancel",
+        Message::AuthHint => "[e: Change method] [Tab: Change focus] [?: Help]",
+        Message::NewAuthHint => "[n: New auth method]",
+        Message::NoAuth => "No authentication method",
+        Message::SelectAuthMethod => "Select an authentication method below",
+        Message::NoRequestDocs => "No documentation for this request",
+        Message::NoDirectoryDocs => "No documentation for this directory",
+        Message::DocsEditingHint => "[Esc: Finish editing]",
+        Message::DocsDirectoryHint => "[i: Edit request docs] [f: Edit directory docs] [j/k: Scroll]",
+        Message::DocsHint => "[i: Edit request docs] [j/k: Scroll]",
+        Message::Filter => "Filter",
+        Message::FilterHint => "[Apply: Enter] [Clear: Enter on empty] [Cancel: Esc]",
+        Message::PinnedFields => "Pinned fields",
+        Message::PinnedHint => "[Add: Enter] [Save: Enter on empty] [Unpin last: Backspace] [Cancel: Esc]",
+        Message::ModifiedForThisSend => "Modified for this send",
+        Message::Override => "Override",
+        Message::OverridesHint => "[Add: Enter] [Cancel: Esc]",
+        Message::OverridesSendHint => "[Add: Enter] [Send: Enter on empty] [Cancel: Esc]",
+        Message::VariableLocal => "{} (local)",
+        Message::VariableSession => "{} (session)",
+        Message::VariableRandom => "{} (random)",
+        Message::VariableUndefined => "undefined",
+        Message::VariableOnlyOn => "only on {}",
+        Message::BodyDigest => "Body digest",
+        Message::Scheme => "Scheme",
+        Message::Host => "Host",
+        Message::Path => "Path",
+        Message::Query => "Query",
+        Message::Fragment => "Fragment",
+        Message::Preview => "Preview",
+        Message::UrlBuilderHint => "[Save: Enter] [Switch: Tab] [Cancel: Esc]",
+        Message::RenameFind => "Find (regex)",
+        Message::RenameReplace => "Replace",
+        Message::RenamePrefix => "Prefix",
+        Message::RenameSuffix => "Suffix",
+        Message::NothingToRename => "nothing to rename",
+        Message::MoreLines => "+{} lines",
+        Message::RenamingNamesAndUrls => "Renaming {} (names and urls)",
+        Message::RenamingNames => "Renaming {} (names)",
+        Message::RenameHint => "[Apply: Enter] [Switch: Tab] [Urls: <C-u>] [Cancel: Esc]",
+        Message::TimeoutMs => "Timeout (ms)",
+        Message::NoTimeout => "No timeout",
+        Message::DuplicateRequest => "{} {} already exists as \"{}\" [Open it: <C-o>] [Create anyway: Enter]",
+        Message::ReadOnly => " [read-only]",
+        Message::Prerequisite => " (prerequisite)",
+        Message::UrlHostPlaceholder => "api.example.com or {{base_url}}",
+        Message::UriTitle => "Uri",
+        Message::CommonCauses => "Common causes",
+        Message::HeadersToCheck => "Headers to check",
+        Message::NotOnResponse => "not on the response",
+        Message::Error => "Error",
+        Message::Quota => " Quota: ",
+        Message::Reset => " Reset: ",
+        Message::OverridesPlaceholder => "header Name: value | var name=value | body contents",
+        Message::JsonPath => "JSONPath",
+        Message::DigestHint => "md5 | sha256 | hmac-sha256 <key>, then header <name> | var <name> [base64]",
+        Message::ConfirmHint => "[Confirm: Enter] [Cancel: Esc]",
+        Message::DeleteDirectory => "Are you sure you want to delete the directory?",
+        Message::DeleteDirectoryRequests => "This will delete all the requests inside",
+        Message::DeleteRequest => "Are you sure you want to delete the request?",
+        Message::Parent => "Parent",
+        Message::Headers => "Headers",
+        Message::NoBody => "No body",
+    }
+}
+
+fn brazilian_portuguese(message: Message) -> &'static str {
+    match message {
+        Message::TerminalTooSmall => "O terminal é pequeno demais:",
+        Message::MinimumSize => "Tamanho mínimo necessário:",
+        Message::Width => "Largura = ",
+        Message::Height => "Altura = ",
+        Message::Yes => "(y) sim",
+        Message::No => "(n) não",
+        Message::Ok => "(O) ok",
+        Message::Example => "Exemplo: ",
+        Message::Modified => "Modificado: ",
+        Message::Status => "Status: ",
+        Message::Time => "Tempo: ",
+        Message::Size => "Tamanho: ",
+        Message::Monitor => "Monitor",
+        Message::MonitorFailing => "{} está falhando: {}",
+        Message::MonitorRecovered => "{} se recuperou",
+        Message::Digest => "Digest",
+        Message::DigestWritten => "o digest do corpo foi escrito",
+        Message::Jwt => "JWT",
+        Message::NoTokenFound => "nenhum token encontrado na requisição ou resposta",
+        Message::Requests => "requisições",
+        Message::Uri => "uri",
+        Message::RequestEditor => "editor de requisição",
+        Message::Response => "resposta",
+        Message::PaneFocused => "{} em foco",
+        Message::PaneEditing => "editando {}",
+        Message::NotificationFailed => "{} falhou: {}",
+        Message::RequestSummary => "requisição {} {}",
+        Message::ResponseSummary => "resposta {} em {}ms",
+        Message::RequestFailed => "a requisição falhou: {}",
+        Message::Clipboard => "Área de transferência",
+        Message::CopiedBody => "corpo da resposta copiado",
+        Message::CopiedCurl => "requisição copiada como curl",
+        Message::NothingToCopy => "nada para copiar, envie a requisição primeiro",
+        Message::ExternalProgram => "Programa externo",
+        Message::NothingToOpen => "nada para abrir, envie a requisição primeiro",
+        Message::NothingToCompare => "envie a requisição e salve um exemplo para compará-los",
+        Message::SignIn => "Entrar",
+        Message::SignInRequesting => "solicitando um código para entrar...",
+        Message::SignInInstructions => "abra {} e digite o código {}",
+        Message::SignInCancel => "[Esc: Cancelar]",
+        Message::SignedIn => "autenticado, enviando a requisição",
+        Message::SignInCancelled => "a autenticação foi cancelada",
+        Message::TokenRefreshing => "renovando o token...",
+        Message::TokenRefreshed => "token renovado, enviando a requisição",
+        Message::TokenExpiresIn => "autenticado, o token expira em {}",
+        Message::TokenNeverExpires => "autenticado, o token nunca expira",
+        Message::TokenExpired => "o token expirou há {}, ele é renovado no próximo envio",
+        Message::NotSignedIn => "não autenticado, enviar a requisição inicia a autenticação",
+        Message::SignInAgain => "[r: Entrar novamente]",
+        Message::SignInOffline => {
+            "não é possível se autenticar offline, a requisição é respondida sem token"
+        }
+        Message::Subscription => "Assinatura",
+        Message::SubscriptionStopped => "a assinatura foi encerrada",
+        Message::NoSubscription => "nenhuma assinatura em andamento",
+        Message::Streaming => "recebendo",
+        Message::Following => "acompanhando",
+        Message::InterimResponses => "Respostas provisórias",
+        Message::Offline => "Offline",
+        Message::OfflineOn => "as requisições são respondidas com seus exemplos",
+        Message::OfflineOff => "as requisições voltam a ser enviadas",
+        Message::OfflineExample => "Exemplo offline: ",
+        Message::Tls => "TLS",
+        Message::TlsInspecting => "negociando com {} para inspecionar a sessão",
+        Message::TlsNotInspected => {
+            "só requisições https que foram enviadas podem ser inspecionadas"
+        }
+        Message::TlsOffline => "servidores não são inspecionados offline",
+        Message::Untrusted => "Não confiável: ",
+        Message::IssuedBy => "emitido por ",
+        Message::ExpiresIn => "expira em {} dias",
+        Message::ExpiredAgo => "expirou há {} dias",
+        Message::Body => "Corpo",
+        Message::BodyTruncated => {
+            "exibindo {} de {} [F: carregar o corpo inteiro] [W: salvar em um arquivo]"
+        }
+        Message::BodySaved => "salvo em {}",
+        Message::Pinned => "Fixados",
+        Message::NoValue => "sem valor",
+        Message::PinsSaved => "{} campos fixados",
+        Message::NotJson => "O corpo não é JSON",
+        Message::NotATable => "O corpo não é uma lista de objetos ou um csv",
+        Message::NoPreviousResponse => "Nenhum envio anterior para comparar",
+        Message::NothingChanged => "Nada mudou desde o envio anterior",
+        Message::ChangedSincePrevious => "Desde o envio anterior: +{} -{}",
+        Message::FilteredBy => "Filtrado por {}",
+        Message::UrlBuilder => "Construtor de URL",
+        Message::Config => "Configuração",
+        Message::ConfigReloaded => "Recarregada de {} arquivos",
+        Message::TemplateSaved => "Modelo salvo",
+        Message::TemplateFrom => "Novas requisições começam como {}",
+        Message::UpdateAvailable => "Atualização disponível",
+        Message::UpdateVersion => "hac {} está disponível, você tem {}",
+        Message::Undo => "Desfazer",
+        Message::Undone => "Desfeito",
+        Message::NothingToUndo => "Nada para desfazer",
+        Message::ChangeCreateRequest => "criação de uma requisição",
+        Message::ChangeCreateDirectory => "criação de um diretório",
+        Message::ChangeEdit => "alterações em {}",
+        Message::ChangeDelete => "remoção de {}",
+        Message::ChangeRename => "renomeação em {}",
+        Message::ChangeUri => "uri de {}",
+        Message::SyncConflict => "Coleção alterada no disco",
+        Message::SyncConflictDescription => {
+            "{} foi alterada por outra sessão, suas alterações ainda não foram salvas"
+        }
+        Message::SyncConflictOptions => "[m: Mesclar] [r: Recarregar] [o: Sobrescrever]",
+        Message::CollectionMerged => "as alterações das duas sessões foram mescladas",
+        Message::CollectionReloaded => "a coleção foi recarregada do disco",
+        Message::CollectionOverwritten => "suas alterações substituíram as do disco",
+        Message::ImageSize => "{} x {} pixels",
+        Message::SaveBinaryBody => "[W: Salvar o corpo em um arquivo]",
+        Message::ReplaceMatch => "substituir por {}? [y: Sim] [n: Não] [a: Todas] [q: Sair]",
+        Message::Substitutions => "{} substituições",
+        Message::RecordingMacro => "gravando @{}",
+        Message::StrippedLines => "espaços finais removidos de {} linhas",
+        Message::Completions => "[Escolher: C-n/C-p] [Aceitar: C-y] [Cancelar: C-e]",
+        Message::HeaderSuggestions => "Sugestões [Escolher: ↓/↑] [Aceitar: →] [Cancelar: Esc]",
+        Message::Name => "Nome",
+        Message::Value => "Valor",
+        Message::Description => "Descrição",
+        Message::Create => "Criar",
+        Message::Cancel => "Cancelar",
+        Message::Enabled => "Ativo",
+        Message::NewCollectionNamePlaceholder => "Minha API incrível",
+        Message::NewCollectionDescriptionPlaceholder => "Testes de requisições",
+        Message::NewCollectionHint => "[Tab] para trocar o foco [Enter] para selecionar um botão",
+        Message::HeadersHint => "[j/k -> descer/subir] [enter -> selecionar] [space -> ativar/desativar] [? -> ajuda]",
+        Message::HeadersHintWide => "[j/k -> descer/subir] [enter -> selecionar] [space -> ativar/desativar] [d -> excluir] [? -> ajuda]",
+        Message::HelpMoveDown => "- desce",
+        Message::HelpMoveUp => "- sobe",
+        Message::HelpDeleteHeader => "- exclui o cabeçalho",
+        Message::HelpToggleHeader => "- ativa ou desativa o cabeçalho",
+        Message::HelpEditHeader => "- seleciona o cabeçalho para edição",
+        Message::HelpShowHelp => "- mostra esta mensagem de ajuda",
+        Message::CloseDialog => "pressione qualquer tecla para fechar esta janela",
+        Message::NoHeaders => "Nenhum cabeçalho",
+        Message::DeleteHeaderPrompt => "tem certeza de que deseja excluir este cabeçalho?",
+        Message::ConfirmOrCancel => "Pressione enter para confirmar, esc para cancelar",
+        Message::AuthHint => "[e: Trocar método] [Tab: Trocar foco] [?: Ajuda]",
+        Message::NewAuthHint => "[n: Novo método de autenticação]",
+        Message::NoAuth => "Nenhum método de autenticação",
+        Message::SelectAuthMethod => "Selecione um método de autenticação abaixo",
+        Message::NoRequestDocs => "Nenhuma documentação para esta requisição",
+        Message::NoDirectoryDocs => "Nenhuma documentação para este diretório",
+        Message::DocsEditingHint => "[Esc: Terminar edição]",
+        Message::DocsDirectoryHint => "[i: Editar docs da requisição] [f: Editar docs do diretório] [j/k: Rolar]",
+        Message::DocsHint => "[i: Editar docs da requisição] [j/k: Rolar]",
+        Message::Filter => "Filtro",
+        Message::FilterHint => "[Aplicar: Enter] [Limpar: Enter vazio] [Cancelar: Esc]",
+        Message::PinnedFields => "Campos fixados",
+        Message::PinnedHint => "[Adicionar: Enter] [Salvar: Enter vazio] [Desafixar último: Backspace] [Cancelar: Esc]",
+        Message::ModifiedForThisSend => "Modificado para este envio",
+        Message::Override => "Substituição",
+        Message::OverridesHint => "[Adicionar: Enter] [Cancelar: Esc]",
+        Message::OverridesSendHint => "[Adicionar: Enter] [Enviar: Enter vazio] [Cancelar: Esc]",
+        Message::VariableLocal => "{} (local)",
+        Message::VariableSession => "{} (sessão)",
+        Message::VariableRandom => "{} (aleatória)",
+        Message::VariableUndefined => "indefinida",
+        Message::VariableOnlyOn => "somente em {}",
+        Message::BodyDigest => "Digest do corpo",
+        Message::Scheme => "Esquema",
+        Message::Host => "Host",
+        Message::Path => "Caminho",
+        Message::Query => "Consulta",
+        Message::Fragment => "Fragmento",
+        Message::Preview => "Prévia",
+        Message::UrlBuilderHint => "[Salvar: Enter] [Trocar: Tab] [Cancelar: Esc]",
+        Message::RenameFind => "Buscar (regex)",
+        Message::RenameReplace => "Substituir",
+        Message::RenamePrefix => "Prefixo",
+        Message::RenameSuffix => "Sufixo",
+        Message::NothingToRename => "nada para renomear",
+        Message::MoreLines => "+{} linhas",
+        Message::RenamingNamesAndUrls => "Renomeando {} (nomes e urls)",
+        Message::RenamingNames => "Renomeando {} (nomes)",
+        Message::RenameHint => "[Aplicar: Enter] [Trocar: Tab] [Urls: <C-u>] [Cancelar: Esc]",
+        Message::TimeoutMs => "Tempo limite (ms)",
+        Message::NoTimeout => "Sem tempo limite",
+        Message::DuplicateRequest => "{} {} já existe como \"{}\" [Abrir: <C-o>] [Criar mesmo assim: Enter]",
+        Message::ReadOnly => " [somente leitura]",
+        Message::Prerequisite => " (pré-requisito)",
+        Message::UrlHostPlaceholder => "api.example.com ou {{base_url}}",
+        Message::UriTitle => "Uri",
+        Message::CommonCauses => "Causas comuns",
+        Message::HeadersToCheck => "Cabeçalhos para verificar",
+        Message::NotOnResponse => "ausente na resposta",
+        Message::Error => "Erro",
+        Message::Quota => " Cota: ",
+        Message::Reset => " Renova: ",
+        Message::OverridesPlaceholder => "header Nome: valor | var nome=valor | conteúdo do corpo",
+        Message::JsonPath => "JSONPath",
+        Message::DigestHint => "md5 | sha256 | hmac-sha256 <chave>, depois header <nome> | var <nome> [base64]",
+        Message::ConfirmHint => "[Confirmar: Enter] [Cancelar: Esc]",
+        Message::DeleteDirectory => "Tem certeza de que deseja excluir o diretório?",
+        Message::DeleteDirectoryRequests => "Isso excluirá todas as requisições dentro dele",
+        Message::DeleteRequest => "Tem certeza de que deseja excluir a requisição?",
+        Message::Parent => "Pai",
+        Message::Headers => "Cabeçalhos",
+        Message::NoBody => "Sem corpo",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate_messages() {
+        assert_eq!(Message::Time.translate(Locale::English), "Time: ");
+        assert_eq!(
+            Message::Time.translate(Locale::BrazilianPortuguese),
+            "Tempo: "
+        );
+
+        let template = Message::MonitorFailing.translate(Locale::BrazilianPortuguese);
+        assert_eq!(
+            fill(template, &["login", "got status 500"]),
+            "login está falhando: got status 500"
+        );
+        assert_eq!(fill("{} and {}", &["a"]), "a and ");
+        assert_eq!(fill("no placeholders", &["a"]), "no placeholders");
+    }
+}
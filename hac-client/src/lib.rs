@@ -1,7 +1,11 @@
 pub mod app;
 mod ascii;
+pub mod clipboard;
 mod components;
 pub mod event_pool;
+pub mod external;
+pub mod hyperlinks;
+pub mod i18n;
 pub mod pages;
 pub mod screen_manager;
 pub mod utils;
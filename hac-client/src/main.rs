@@ -1,6 +1,16 @@
-use hac_cli::RuntimeBehavior;
-use hac_client::app;
-use hac_core::collection::collection;
+use hac_cli::{ExportFormat, RequestFileFormat, RuntimeBehavior};
+use hac_client::{app, i18n};
+use hac_core::collection::types::{find_request_by, Request, RequestKind};
+use hac_core::collection::{
+    collection, dependencies, diagnostics, environment, local, report, Collection,
+};
+use hac_core::docs::DocsFormat;
+use hac_core::net::download::{self, DownloadEvent};
+use hac_core::net::latency_history::LatencyHistory;
+use hac_core::version::VersionInfo;
+use hac_core::{interop, iteration};
+
+use std::path::{Path, PathBuf};
 
 fn setup_tracing() -> anyhow::Result<tracing_appender::non_blocking::WorkerGuard> {
     let (data_dir, logfile) = hac_config::log_file();
@@ -17,31 +27,374 @@ fn setup_tracing() -> anyhow::Result<tracing_appender::non_blocking::WorkerGuard
     Ok(guard)
 }
 
+fn find_collection(name: &str) -> anyhow::Result<Collection> {
+    let collections = collection::get_collections_from_config()?;
+    let Some(collection) = collections.into_iter().find(|collection| {
+        collection.info.name.eq(name)
+            || collection
+                .path
+                .file_stem()
+                .is_some_and(|stem| stem.to_string_lossy().eq(name))
+    }) else {
+        anyhow::bail!("no collection named {:?} was found", name);
+    };
+
+    Ok(collection)
+}
+
+fn export_docs(name: &str, format: ExportFormat, output: Option<PathBuf>) -> anyhow::Result<()> {
+    let collection = find_collection(name)?;
+
+    let format = match format {
+        ExportFormat::Markdown => DocsFormat::Markdown,
+        ExportFormat::Html => DocsFormat::Html,
+    };
+    let docs = hac_core::docs::export(&collection, format);
+
+    match output {
+        Some(path) => std::fs::write(path, docs)?,
+        None => print!("{}", docs),
+    }
+
+    Ok(())
+}
+
+fn import_requests(path: &Path) -> anyhow::Result<()> {
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let has_extension = |expected: &str| {
+        path.extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case(expected))
+    };
+    let mut collection = match interop::RequestFileFormat::from_path(path) {
+        _ if path.is_dir() => interop::import_bruno(path)?,
+        _ if has_extension("wsdl") => interop::import_wsdl(&std::fs::read_to_string(path)?, &name)?,
+        _ if has_extension("json") => {
+            interop::import_openapi(&std::fs::read_to_string(path)?, &name)?
+        }
+        Some(format) => interop::import(&std::fs::read_to_string(path)?, format, &name)?,
+        None => anyhow::bail!(
+            "{:?} is not a .http, .rest, .hurl, .wsdl or OpenAPI .json file nor a bruno collection",
+            path
+        ),
+    };
+
+    let file_name = format!(
+        "{}.json",
+        collection.info.name.to_lowercase().replace(' ', "_")
+    );
+    collection.path = hac_config::get_or_create_collections_dir().join(file_name);
+    if collection.path.exists() {
+        anyhow::bail!("a collection already exists at {:?}", collection.path);
+    }
+    std::fs::write(&collection.path, serde_json::to_string(&collection)?)?;
+
+    let requests = collection.requests.clone().unwrap_or_default();
+    println!(
+        "imported {} requests into {:?}",
+        requests.read().unwrap().len(),
+        collection.path
+    );
+    Ok(())
+}
+
+fn export_requests(
+    name: &str,
+    format: RequestFileFormat,
+    output: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let collection = find_collection(name)?;
+    let config = hac_config::load_config();
+
+    // the configured environment is exported when set, so the file works
+    // the same way the collection does on hac
+    let environment = config
+        .environment
+        .as_deref()
+        .and_then(|name| environment::find_environment(&collection, name))
+        .or(collection.environments.first());
+    let format = match format {
+        RequestFileFormat::Http => interop::RequestFileFormat::RestClient,
+        RequestFileFormat::Hurl => interop::RequestFileFormat::Hurl,
+        RequestFileFormat::Bruno => {
+            let Some(dir) = output else {
+                anyhow::bail!("bruno collections are directories, set one with --output");
+            };
+            interop::export_bruno(&collection, &dir)?;
+            println!("exported {:?} to {:?}", collection.info.name, dir);
+            return Ok(());
+        }
+    };
+    let exported = interop::export(&collection, environment, format);
+
+    match output {
+        Some(path) => std::fs::write(path, exported)?,
+        None => print!("{}", exported),
+    }
+
+    Ok(())
+}
+
+fn find_request(requests: &[RequestKind], name: &str) -> Option<Request> {
+    find_request_by(requests, |req| req.name.eq(name) || req.id.eq(name))
+        .map(|req| req.read().unwrap().clone())
+}
+
+fn compare_environments(
+    collection: &str,
+    request: &str,
+    left: &str,
+    right: &str,
+) -> anyhow::Result<()> {
+    let collection = find_collection(collection)?;
+    let requests = collection.requests.clone().unwrap_or_default();
+    let Some(request) = find_request(&requests.read().unwrap(), request) else {
+        anyhow::bail!("no request named {:?} was found", request);
+    };
+
+    let find = |name: &str| {
+        environment::find_environment(&collection, name)
+            .ok_or_else(|| anyhow::anyhow!("no environment named {:?} was found", name))
+    };
+    let comparison = environment::compare(&request, find(left)?, find(right)?);
+    print!("{}", environment::render_comparison(&comparison));
+
+    Ok(())
+}
+
+async fn iterate(
+    collection: &str,
+    request: &str,
+    data: &Path,
+    output: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let Some(format) = iteration::DataFormat::from_path(data) else {
+        anyhow::bail!("{:?} is not a .csv or .json file", data);
+    };
+    let output = match output {
+        Some(path) => match iteration::DataFormat::from_path(&path) {
+            Some(format) => Some((path, format)),
+            None => anyhow::bail!("{:?} is not a .csv or .json file", path),
+        },
+        None => None,
+    };
+    let rows = iteration::parse_rows(&std::fs::read_to_string(data)?, format)?;
+
+    let collection = find_collection(collection)?;
+    let requests = collection.requests.clone().unwrap_or_default();
+    let Some(request) = find_request(&requests.read().unwrap(), request) else {
+        anyhow::bail!("no request named {:?} was found", request);
+    };
+
+    let config = hac_config::load_config();
+    hac_core::faker::set_locale(config.locale);
+    let variables = environment::environment_variables(&collection, config.environment.as_deref());
+    let protobuf = hac_core::protobuf::load_schema(&collection)?;
+
+    let (result_tx, mut result_rx) = tokio::sync::mpsc::unbounded_channel();
+    iteration::run_iteration(request, rows, variables, &config, protobuf, result_tx);
+    let mut results = vec![];
+    while let Some(result) = result_rx.recv().await {
+        results.push(result);
+    }
+    print!("{}", iteration::render_results(&results));
+    if let Some((path, format)) = output {
+        std::fs::write(path, iteration::export_results(&results, format))?;
+    }
+
+    let failed = results.iter().filter(|result| !result.is_success()).count();
+    if failed > 0 {
+        anyhow::bail!("{} of {} iterations failed", failed, results.len());
+    }
+
+    Ok(())
+}
+
+async fn download(collection: &str, request: &str, path: PathBuf) -> anyhow::Result<()> {
+    let collection = find_collection(collection)?;
+    let requests = collection.requests.clone().unwrap_or_default();
+    let Some(request) = find_request(&requests.read().unwrap(), request) else {
+        anyhow::bail!("no request named {:?} was found", request);
+    };
+
+    let config = hac_config::load_config();
+    hac_core::faker::set_locale(config.locale);
+    let variables = environment::environment_variables(&collection, config.environment.as_deref());
+    let request = environment::resolve_request(&request, &variables);
+
+    let (events_tx, mut events_rx) = tokio::sync::mpsc::unbounded_channel();
+    download::run_download(request, path, &config, events_tx);
+    while let Some(event) = events_rx.recv().await {
+        match event {
+            DownloadEvent::Resumed(from) => eprintln!("resuming after {} bytes", from),
+            DownloadEvent::Progress(progress) => eprint!("\r\x1b[2K{}", progress),
+            DownloadEvent::Finished(path, size) => {
+                eprintln!();
+                println!("saved {} bytes to {:?}", size, path);
+            }
+            DownloadEvent::Failed(cause) => {
+                eprintln!();
+                anyhow::bail!(
+                    "the download failed, sending it again resumes it: {}",
+                    cause
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn lint(name: &str) -> anyhow::Result<()> {
+    let collection = find_collection(name)?;
+    let config = hac_config::load_config();
+    let environment = config.environment.as_deref();
+    let local = local::workspace_variables(environment);
+    let diagnostics = diagnostics::analyze(&collection, environment, &local);
+
+    diagnostics
+        .iter()
+        .for_each(|diagnostic| println!("{}", diagnostic));
+
+    if !diagnostics.is_empty() {
+        anyhow::bail!("found {} problems on {:?}", diagnostics.len(), name);
+    }
+
+    Ok(())
+}
+
+fn flow(name: &str) -> anyhow::Result<()> {
+    let collection = find_collection(name)?;
+    let requests = collection.requests.clone().unwrap_or_default();
+    let graph = dependencies::flow_graph(&requests.read().unwrap());
+
+    if graph.nodes.is_empty() {
+        println!("no request on {:?} depends on another", name);
+        return Ok(());
+    }
+
+    let rendered = dependencies::render_flow(&graph).map_err(|err| anyhow::anyhow!("{err}"))?;
+    print!("{}", rendered);
+    graph.validate().map_err(|err| anyhow::anyhow!("{err}"))?;
+
+    Ok(())
+}
+
+fn report(name: &str) -> anyhow::Result<()> {
+    let collection = find_collection(name)?;
+    let config = hac_config::load_config();
+    let history = LatencyHistory::load(hac_config::latency_history_file());
+    let report = report::build(&collection, config.environment.as_deref(), &history);
+
+    print!("{}", report::render(&report));
+
+    Ok(())
+}
+
+fn decode_jwt(token: &str) -> anyhow::Result<()> {
+    let decoded = hac_core::jwt::decode(token)?;
+    print!("{}", decoded.render(hac_core::jwt::now()));
+    Ok(())
+}
+
+fn sign_jwt(claims: &str, secret: &str) -> anyhow::Result<()> {
+    let mut claims: serde_json::Value = serde_json::from_str(claims)?;
+    let Some(map) = claims.as_object_mut() else {
+        anyhow::bail!("claims must be a json object");
+    };
+    map.entry("iat")
+        .or_insert_with(|| hac_core::jwt::now().into());
+
+    println!("{}", hac_core::jwt::sign_hs256(&claims, secret));
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let runtime_behavior = hac_cli::Cli::parse_args();
+    // every command reads collections from the new layout, so files of the
+    // old one are moved first, before the log on the state directory opens
+    let migrated = hac_config::migrate_legacy_layout();
 
     match runtime_behavior {
         RuntimeBehavior::PrintConfigPath => hac_cli::Cli::print_config_path(
             hac_config::get_config_dir_path(),
             hac_config::get_usual_path(),
         ),
-        RuntimeBehavior::PrintDataPath => {
-            hac_cli::Cli::print_data_path(hac_config::get_collections_dir())
-        }
+        RuntimeBehavior::PrintDataPath => hac_cli::Cli::print_data_path(
+            hac_config::get_collections_dir(),
+            hac_config::DataDir::Cache.path(),
+            hac_config::DataDir::State.path(),
+        ),
         RuntimeBehavior::DumpDefaultConfig => {
             hac_cli::Cli::print_default_config(hac_config::default_as_str())
         }
+        RuntimeBehavior::PrintResolvedConfig => {
+            hac_cli::Cli::print_resolved_config(&hac_config::resolve_config().render())
+        }
+        RuntimeBehavior::PrintVersion { verbose } => {
+            let version = VersionInfo::current(env!("CARGO_PKG_VERSION"));
+            hac_cli::Cli::print_version(&version.render(verbose));
+            return Ok(());
+        }
+        RuntimeBehavior::ExportDocs {
+            ref collection,
+            format,
+            ref output,
+        } => return export_docs(collection, format, output.clone()),
+        RuntimeBehavior::ExportRequests {
+            ref collection,
+            format,
+            ref output,
+        } => return export_requests(collection, format, output.clone()),
+        RuntimeBehavior::Import(ref path) => return import_requests(path),
+        RuntimeBehavior::CompareEnvironments {
+            ref collection,
+            ref request,
+            ref left,
+            ref right,
+        } => return compare_environments(collection, request, left, right),
+        RuntimeBehavior::Iterate {
+            ref collection,
+            ref request,
+            ref data,
+            ref results,
+        } => return iterate(collection, request, data, results.clone()).await,
+        RuntimeBehavior::Download {
+            ref collection,
+            ref request,
+            ref path,
+        } => return download(collection, request, path.clone()).await,
+        RuntimeBehavior::Lint(ref collection) => return lint(collection),
+        RuntimeBehavior::Flow(ref collection) => return flow(collection),
+        RuntimeBehavior::Report(ref collection) => return report(collection),
+        RuntimeBehavior::DecodeJwt(ref token) => return decode_jwt(token),
+        RuntimeBehavior::SignJwt {
+            ref claims,
+            ref secret,
+        } => return sign_jwt(claims, secret),
         _ => {}
     }
 
     let dry_run = runtime_behavior.eq(&RuntimeBehavior::DryRun);
 
+    hac_config::get_or_create_data_dirs();
     let _guard = setup_tracing()?;
-    hac_config::get_or_create_data_dir();
-    let config = hac_config::load_config();
+    match migrated {
+        Ok(moved) => moved
+            .iter()
+            .for_each(|(from, to)| tracing::info!("moved {from:?} to {to:?}")),
+        Err(e) => tracing::error!("failed to move files to the new data layout: {e}"),
+    }
+    // the interface refuses to start on a broken config, so a typo doesn't
+    // silently turn into the defaults
+    let config = hac_config::load_config_strict()?;
+    i18n::set_locale(config.locale);
+    hac_core::faker::set_locale(config.locale);
 
-    let colors = hac_colors::Colors::default();
+    let colors = app::build_colors(&config);
     let mut collections = collection::get_collections_from_config()?;
     collections.sort_by_key(|key| key.info.name.clone());
     let mut app = app::App::new(&colors, collections, &config, dry_run)?;
@@ -9,7 +9,7 @@ pub mod terminal_too_small;
 mod under_construction;
 
 use crate::event_pool::Event;
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyCode, KeyEvent};
 use hac_core::command::Command;
 use ratatui::{layout::Rect, Frame};
 use tokio::sync::mpsc::UnboundedSender;
@@ -47,13 +47,34 @@ pub trait Eventful {
     fn handle_event(&mut self, event: Option<Event>) -> anyhow::Result<Option<Self::Result>> {
         match event {
             Some(Event::Key(key_event)) => self.handle_key_event(key_event),
+            Some(Event::Paste(text)) => self.handle_paste(&text),
             _ => Ok(None),
         }
     }
 
+    /// pasted text is typed key by key on a single line by default, as most
+    /// inputs can't hold line breaks
+    fn handle_paste(&mut self, text: &str) -> anyhow::Result<Option<Self::Result>> {
+        type_pasted(self, text)
+    }
+
     /// when we get a key_event, this will be called for the eventful component to handle it
     #[allow(unused_variables)]
     fn handle_key_event(&mut self, key_event: KeyEvent) -> anyhow::Result<Option<Self::Result>> {
         Ok(None)
     }
 }
+
+/// types pasted text as keys, joined into a single line so line breaks aren't
+/// taken as enter. Stops at the first key that produces a result
+pub fn type_pasted<E>(eventful: &mut E, text: &str) -> anyhow::Result<Option<E::Result>>
+where
+    E: Eventful + ?Sized,
+{
+    for c in hac_core::paste::single_line(text).chars() {
+        if let Some(result) = eventful.handle_key_event(KeyEvent::from(KeyCode::Char(c)))? {
+            return Ok(Some(result));
+        }
+    }
+    Ok(None)
+}
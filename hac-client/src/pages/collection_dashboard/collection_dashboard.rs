@@ -44,6 +44,8 @@ pub struct CollectionDashboard<'a> {
     pane_focus: PaneFocus,
     pub command_sender: Option<UnboundedSender<Command>>,
     error_message: String,
+    /// tells a newer release exists, shown next to the hints
+    update_notice: Option<String>,
     dry_run: bool,
 }
 
@@ -80,11 +82,22 @@ impl<'a> CollectionDashboard<'a> {
             filter: String::new(),
             command_sender: None,
             error_message: String::default(),
+            update_notice: None,
             pane_focus: PaneFocus::List,
             dry_run,
         })
     }
 
+    /// draws the dashboard with other colors from now on
+    pub fn set_colors(&mut self, colors: &'a hac_colors::Colors) {
+        self.colors = colors;
+        self.list = CollectionList::new(colors);
+    }
+
+    pub fn set_update_notice(&mut self, notice: String) {
+        self.update_notice = Some(notice);
+    }
+
     pub fn display_error(&mut self, message: String) {
         self.pane_focus = PaneFocus::Error;
         self.error_message = message;
@@ -334,10 +347,14 @@ impl<'a> CollectionDashboard<'a> {
     }
 
     fn draw_hint_text(&self, frame: &mut Frame) {
-        let hint =
+        let mut hint = Line::from(
             "[h/j/k/l to move] [n -> new] [enter -> select item] [? -> help] [<C-c> -> quit]"
-                .fg(self.colors.normal.magenta)
-                .into_centered_line();
+                .fg(self.colors.normal.magenta),
+        );
+        if let Some(notice) = self.update_notice.as_ref() {
+            hint.push_span(format!(" [{notice}]").fg(self.colors.normal.yellow));
+        }
+        let hint = hint.centered();
 
         frame.render_widget(hint, self.layout.hint_pane);
     }
@@ -185,8 +185,16 @@ mod tests {
             info: Info {
                 name: String::from("any_name"),
                 description: None,
+                read_only: false,
             },
             path: "any_path".into(),
+            unlocked: false,
+            protos: vec![],
+            highlights: vec![],
+            template: None,
+            format_on_save: false,
+            synced: Default::default(),
+            environments: vec![],
             requests: None,
         }
     }
@@ -225,8 +233,16 @@ mod tests {
             info: Info {
                 name: String::from("any_name"),
                 description: None,
+                read_only: false,
             },
             path: "any_path".into(),
+            unlocked: false,
+            protos: vec![],
+            highlights: vec![],
+            template: None,
+            format_on_save: false,
+            synced: Default::default(),
+            environments: vec![],
             requests: None,
         }];
         let state = CollectionListState::new(collections.clone());
@@ -1,3 +1,4 @@
+use crate::i18n::Message;
 use crate::pages::input::Input;
 
 use ratatui::buffer::Buffer;
@@ -96,11 +97,11 @@ impl StatefulWidget for NewCollectionForm<'_> {
         let layout = self.build_layout(&size);
         Clear.render(size, buf);
 
-        let mut name_input =
-            Input::new(self.colors, "Name".into()).placeholder("My awesome API".into());
+        let mut name_input = Input::new(self.colors, Message::Name.text().into())
+            .placeholder(Message::NewCollectionNamePlaceholder.text().into());
 
-        let mut desc_input =
-            Input::new(self.colors, "Description".into()).placeholder("Request testing".into());
+        let mut desc_input = Input::new(self.colors, Message::Description.text().into())
+            .placeholder(Message::NewCollectionDescriptionPlaceholder.text().into());
 
         match state.focused_field {
             FormFocus::Name => name_input.focus(),
@@ -109,11 +110,12 @@ impl StatefulWidget for NewCollectionForm<'_> {
         };
 
         let cancel_text = if state.focused_field.eq(&FormFocus::Cancel) {
-            "Cancel"
+            Message::Cancel
+                .text()
                 .fg(self.colors.normal.white)
                 .bg(self.colors.normal.red)
         } else {
-            "Cancel".fg(self.colors.normal.white)
+            Message::Cancel.text().fg(self.colors.normal.white)
         };
 
         let cancel_button = Paragraph::new(Line::from(cancel_text).centered()).block(
@@ -124,11 +126,12 @@ impl StatefulWidget for NewCollectionForm<'_> {
         );
 
         let confirm_text = if state.focused_field.eq(&FormFocus::Confirm) {
-            "Create"
+            Message::Create
+                .text()
                 .fg(self.colors.normal.white)
                 .bg(self.colors.normal.magenta)
         } else {
-            "Create".fg(self.colors.normal.white)
+            Message::Create.text().fg(self.colors.normal.white)
         };
 
         let confirm_button = Paragraph::new(Line::from(confirm_text).centered()).block(
@@ -142,7 +145,7 @@ impl StatefulWidget for NewCollectionForm<'_> {
             .padding(Padding::uniform(1))
             .style(Style::default().bg(self.colors.primary.background));
 
-        let hint = Paragraph::new("[Tab] to switch focus [Enter] to select a button")
+        let hint = Paragraph::new(Message::NewCollectionHint.text())
             .centered()
             .fg(self.colors.normal.magenta);
 
@@ -1,11 +1,21 @@
+use hac_core::collection::dependencies::find_request;
+use hac_core::collection::environment::{self, VariablePreview};
+use hac_core::collection::history::{self, CollectionHistory};
+use hac_core::collection::session::Session;
 use hac_core::collection::types::{Request, RequestKind};
 use hac_core::collection::Collection;
+use hac_core::completion::CompletionEngine;
+use hac_core::header_history::HeaderHistory;
+use hac_core::monitor::MonitorStats;
+use hac_core::net::auth::oauth2::TokenStore;
+use hac_core::net::latency_history::LatencyHistory;
+use hac_core::viewer_preferences::ViewerPreferencesStore;
 
 use crate::pages::collection_viewer::collection_viewer::CollectionViewerOverlay;
 use crate::pages::collection_viewer::collection_viewer::PaneFocus;
 
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::rc::Rc;
 use std::sync::{Arc, RwLock};
 
@@ -15,6 +25,14 @@ pub struct CollectionState {
     hovered_request: Option<String>,
     selected_request: Option<Arc<RwLock<Request>>>,
     dirs_expanded: Rc<RefCell<HashMap<String, bool>>>,
+    monitor_stats: Rc<RefCell<HashMap<String, MonitorStats>>>,
+    latency_history: Rc<RefCell<LatencyHistory>>,
+    header_history: Rc<RefCell<HeaderHistory>>,
+    completions: Rc<RefCell<CompletionEngine>>,
+    viewer_preferences: Rc<RefCell<ViewerPreferencesStore>>,
+    tokens: Rc<RefCell<TokenStore>>,
+    session: Rc<RefCell<Session>>,
+    history: CollectionHistory,
     selected_pane: Option<PaneFocus>,
     focused_pane: PaneFocus,
     has_pending_request: bool,
@@ -37,6 +55,10 @@ pub enum CollectionStoreAction {
     SetFocusedPane(PaneFocus),
     SetSelectedPane(Option<PaneFocus>),
     SetPendingRequest(bool),
+    /// unlocks or locks back a read only collection for the current session
+    SetUnlocked(bool),
+    /// indexes the collection again for completions, as it changes
+    RefreshCompletions,
 }
 
 impl CollectionStore {
@@ -60,6 +82,14 @@ impl CollectionStore {
             selected_request,
             hovered_request,
             dirs_expanded: Rc::new(RefCell::new(HashMap::default())),
+            monitor_stats: Rc::new(RefCell::new(HashMap::default())),
+            latency_history: Rc::new(RefCell::new(LatencyHistory::default())),
+            header_history: Rc::new(RefCell::new(HeaderHistory::default())),
+            completions: Rc::new(RefCell::new(build_completions(&collection))),
+            viewer_preferences: Rc::new(RefCell::new(ViewerPreferencesStore::default())),
+            tokens: Rc::new(RefCell::new(TokenStore::default())),
+            session: Rc::new(RefCell::new(Session::default())),
+            history: CollectionHistory::default(),
             collection: Rc::new(RefCell::new(collection)),
             focused_pane: PaneFocus::Sidebar,
             selected_pane: None,
@@ -80,10 +110,13 @@ impl CollectionStore {
                     state.borrow_mut().hovered_request = maybe_req_id
                 }
                 CollectionStoreAction::InsertRequest(request_kind) => {
-                    state
-                        .borrow_mut()
-                        .collection
-                        .borrow_mut()
+                    let state = state.borrow_mut();
+                    let mut collection = state.collection.borrow_mut();
+                    if let Err(e) = collection.ensure_writable() {
+                        tracing::warn!("refused to insert request: {e}");
+                        return;
+                    }
+                    collection
                         .requests
                         .get_or_insert_with(|| Arc::new(RwLock::new(vec![])))
                         .write()
@@ -107,6 +140,14 @@ impl CollectionStore {
                 CollectionStoreAction::SetPendingRequest(is_pending) => {
                     state.borrow_mut().has_pending_request = is_pending;
                 }
+                CollectionStoreAction::SetUnlocked(unlocked) => {
+                    state.borrow_mut().collection.borrow_mut().unlocked = unlocked;
+                }
+                CollectionStoreAction::RefreshCompletions => {
+                    let state = state.borrow();
+                    let engine = build_completions(&state.collection.borrow());
+                    *state.completions.borrow_mut() = engine;
+                }
             }
         }
     }
@@ -149,6 +190,82 @@ impl CollectionStore {
             .map(|state| state.borrow().dirs_expanded.clone())
     }
 
+    /// history of every monitored request that ran since the collection was
+    /// opened, keyed by request id
+    pub fn get_monitor_stats(&self) -> Option<Rc<RefCell<HashMap<String, MonitorStats>>>> {
+        self.state
+            .as_ref()
+            .map(|state| state.borrow().monitor_stats.clone())
+    }
+
+    /// durations of past sends of every request, shared with whoever records
+    /// new ones so every component sees the same history
+    pub fn get_latency_history(&self) -> Option<Rc<RefCell<LatencyHistory>>> {
+        self.state
+            .as_ref()
+            .map(|state| state.borrow().latency_history.clone())
+    }
+
+    /// values sent on headers across every collection, used to suggest them
+    /// when typing a header value
+    pub fn get_header_history(&self) -> Option<Rc<RefCell<HeaderHistory>>> {
+        self.state
+            .as_ref()
+            .map(|state| state.borrow().header_history.clone())
+    }
+
+    /// words suggested while typing on the body and header editors
+    pub fn get_completions(&self) -> Option<Rc<RefCell<CompletionEngine>>> {
+        self.state
+            .as_ref()
+            .map(|state| state.borrow().completions.clone())
+    }
+
+    /// how the responses of every request were last looked at, shared with
+    /// whoever persists them
+    pub fn get_viewer_preferences(&self) -> Option<Rc<RefCell<ViewerPreferencesStore>>> {
+        self.state
+            .as_ref()
+            .map(|state| state.borrow().viewer_preferences.clone())
+    }
+
+    /// tokens acquired by signing in while the collection is open, shared so
+    /// the auth editor can show when they expire
+    pub fn get_tokens(&self) -> Option<Rc<RefCell<TokenStore>>> {
+        self.state
+            .as_ref()
+            .map(|state| state.borrow().tokens.clone())
+    }
+
+    /// variables captured from responses since the collection was opened,
+    /// they are gone once hac exits
+    pub fn get_session(&self) -> Option<Rc<RefCell<Session>>> {
+        self.state
+            .as_ref()
+            .map(|state| state.borrow().session.clone())
+    }
+
+    /// what the variable at the given char index of `text` resolves to, for
+    /// the inputs that show a preview of it
+    pub fn preview_variable(
+        &self,
+        environment: Option<&str>,
+        local: &BTreeMap<String, String>,
+        text: &str,
+        char_idx: usize,
+    ) -> Option<VariablePreview> {
+        let (collection, session) = (self.get_collection()?, self.get_session()?);
+        let (collection, session) = (collection.borrow(), session.borrow());
+        environment::preview_variable(
+            &collection,
+            environment,
+            session.variables(),
+            local,
+            text,
+            char_idx,
+        )
+    }
+
     pub fn push_overlay(&mut self, overlay: CollectionViewerOverlay) {
         if let Some(state) = self.state.as_mut() {
             state.borrow_mut().overlay_stack.push(overlay)
@@ -193,6 +310,104 @@ impl CollectionStore {
         })
     }
 
+    /// whether the collection is read only and was not unlocked, components
+    /// must not modify the collection or its requests while this is true
+    pub fn is_read_only(&self) -> bool {
+        self.state
+            .as_ref()
+            .is_some_and(|state| state.borrow().collection.borrow().is_read_only())
+    }
+
+    /// a copy of the requests as they are now, to be recorded as a change
+    /// once it is confirmed
+    pub fn snapshot_requests(&self) -> Vec<RequestKind> {
+        self.get_requests()
+            .map(|requests| history::deep_clone(&requests.read().unwrap()))
+            .unwrap_or_default()
+    }
+
+    /// records the requests as they are now, before `label` changes them
+    pub fn record_change<L>(&mut self, label: L)
+    where
+        L: Into<String>,
+    {
+        let snapshot = self.snapshot_requests();
+        self.record_snapshot(label, snapshot);
+    }
+
+    /// records requests copied before `label` changed them
+    pub fn record_snapshot<L>(&mut self, label: L, snapshot: Vec<RequestKind>)
+    where
+        L: Into<String>,
+    {
+        if let Some(state) = self.state.as_ref() {
+            state.borrow_mut().history.record(label, &snapshot);
+        }
+    }
+
+    /// restores the requests as they were before the last recorded change,
+    /// returning what was undone. Selection and hover are kept on the items
+    /// that still exist
+    pub fn undo(&mut self) -> Option<String> {
+        if self.is_read_only() {
+            tracing::warn!("refused to undo changes on a read only collection");
+            return None;
+        }
+
+        let (label, restored) = self.state.as_ref()?.borrow_mut().history.undo()?;
+        self.restore_requests(restored);
+        Some(label)
+    }
+
+    /// replaces the collection by another version of it, like the one another
+    /// session wrote to its file
+    pub fn replace_collection(&mut self, mut collection: Collection) {
+        let Some(state) = self.state.clone() else {
+            return;
+        };
+        let requests = collection
+            .requests
+            .take()
+            .map(|requests| requests.read().unwrap().clone())
+            .unwrap_or_default();
+        {
+            let state = state.borrow();
+            let mut current = state.collection.borrow_mut();
+            collection.requests = current.requests.clone();
+            *current = collection;
+        }
+        self.restore_requests(requests);
+    }
+
+    /// replaces every request of the collection, keeping the selection and
+    /// hover on the items that still exist
+    fn restore_requests(&mut self, restored: Vec<RequestKind>) {
+        let Some(state) = self.state.clone() else {
+            return;
+        };
+        let selected = self
+            .get_selected_request()
+            .and_then(|req| find_request(&restored, &req.read().unwrap().id));
+
+        match self.get_requests() {
+            Some(requests) => *requests.write().unwrap() = restored,
+            None => {
+                let state = state.borrow();
+                state.collection.borrow_mut().requests = Some(Arc::new(RwLock::new(restored)));
+            }
+        }
+
+        self.dispatch(CollectionStoreAction::SetSelectedRequest(selected));
+        let requests = self.get_requests().unwrap();
+        let requests = requests.read().unwrap();
+        let dirs_expanded = self.get_dirs_expanded().unwrap();
+        let hovered = self
+            .get_hovered_request()
+            .filter(|id| is_visible(&requests, &dirs_expanded.borrow(), id))
+            .or_else(|| requests.first().map(|req| req.get_id()));
+        self.dispatch(CollectionStoreAction::SetHoveredRequest(hovered));
+    }
+
     pub fn has_pending_request(&self) -> bool {
         self.state
             .as_ref()
@@ -258,6 +473,11 @@ impl CollectionStore {
     }
 
     pub fn remove_item(&mut self, item_id: String) {
+        if self.is_read_only() {
+            tracing::warn!("refused to remove {item_id} from a read only collection");
+            return;
+        }
+
         if let Some(request) = self.get_selected_request() {
             if request.read().unwrap().id.eq(&item_id) {
                 self.dispatch(CollectionStoreAction::SetSelectedRequest(None));
@@ -280,6 +500,31 @@ impl CollectionStore {
     }
 }
 
+/// indexes every word used on the collection, along with the variables of
+/// its environments that no request uses yet
+fn build_completions(collection: &Collection) -> CompletionEngine {
+    let mut engine = CompletionEngine::from_collection(collection);
+    engine.add_variables(
+        collection
+            .environments
+            .iter()
+            .flat_map(|env| env.variables.keys().cloned()),
+    );
+    engine
+}
+
+/// whether the item exists and is not inside a collapsed directory
+fn is_visible(requests: &[RequestKind], dirs_expanded: &HashMap<String, bool>, id: &str) -> bool {
+    requests.iter().any(|item| match item {
+        RequestKind::Single(_) => item.get_id().eq(id),
+        RequestKind::Nested(dir) => {
+            dir.id.eq(id)
+                || (dirs_expanded.get(&dir.id).is_some_and(|expanded| *expanded)
+                    && is_visible(&dir.requests.read().unwrap(), dirs_expanded, id))
+        }
+    })
+}
+
 #[derive(PartialEq)]
 enum VisitNode {
     Next,
@@ -395,28 +640,20 @@ mod tests {
     fn create_root_one() -> RequestKind {
         RequestKind::Single(Arc::new(RwLock::new(Request {
             id: "root".to_string(),
-            method: RequestMethod::Get,
             name: "Root1".to_string(),
-            auth_method: None,
-            parent: None,
-            headers: None,
             uri: "/root1".to_string(),
-            body_type: None,
-            body: None,
+            ..Default::default()
         })))
     }
 
     fn create_child_one() -> RequestKind {
         RequestKind::Single(Arc::new(RwLock::new(Request {
             id: "child_one".to_string(),
-            auth_method: None,
             parent: Some(String::from("dir")),
             method: RequestMethod::Post,
             name: "Child1".to_string(),
             uri: "/nested1/child1".to_string(),
-            headers: None,
-            body_type: None,
-            body: None,
+            ..Default::default()
         })))
     }
 
@@ -424,13 +661,10 @@ mod tests {
         RequestKind::Single(Arc::new(RwLock::new(Request {
             id: "child_two".to_string(),
             method: RequestMethod::Put,
-            auth_method: None,
             name: "Child2".to_string(),
-            headers: None,
             parent: Some(String::from("dir")),
             uri: "/nested1/child2".to_string(),
-            body_type: None,
-            body: None,
+            ..Default::default()
         })))
     }
 
@@ -439,12 +673,8 @@ mod tests {
             id: "not_used".to_string(),
             method: RequestMethod::Put,
             name: "NotUsed".to_string(),
-            parent: None,
-            auth_method: None,
-            headers: None,
             uri: "/not/used".to_string(),
-            body_type: None,
-            body: None,
+            ..Default::default()
         })))
     }
 
@@ -453,6 +683,7 @@ mod tests {
             id: "dir".to_string(),
             name: "Nested1".to_string(),
             requests: Arc::new(RwLock::new(vec![create_child_one(), create_child_two()])),
+            docs: None,
         }
     }
 
@@ -464,13 +695,9 @@ mod tests {
         RequestKind::Single(Arc::new(RwLock::new(Request {
             id: "root_two".to_string(),
             method: RequestMethod::Delete,
-            auth_method: None,
-            headers: None,
-            parent: None,
             name: "Root2".to_string(),
             uri: "/root2".to_string(),
-            body_type: None,
-            body: None,
+            ..Default::default()
         })))
     }
 
@@ -556,4 +783,43 @@ mod tests {
 
         assert!(next.is_none());
     }
+
+    #[test]
+    fn test_refresh_completions() {
+        let collection = serde_json::from_str::<Collection>(
+            r#"{
+                "info": { "name": "api" },
+                "requests": [],
+                "environments": [{ "name": "dev", "variables": { "token": "abc" } }]
+            }"#,
+        )
+        .unwrap();
+        let mut store = CollectionStore::default();
+        store.set_state(collection);
+
+        let variables = |store: &CollectionStore| {
+            store
+                .get_completions()
+                .unwrap()
+                .borrow()
+                .complete_body("{{")
+                .unwrap()
+                .candidates
+                .into_iter()
+                .map(|candidate| candidate.label)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(variables(&store), vec!["token"]);
+
+        store.dispatch(CollectionStoreAction::InsertRequest(RequestKind::Single(
+            Arc::new(RwLock::new(Request {
+                uri: "{{host}}/users".to_string(),
+                ..Default::default()
+            })),
+        )));
+        assert_eq!(variables(&store), vec!["token"]);
+
+        store.dispatch(CollectionStoreAction::RefreshCompletions);
+        assert_eq!(variables(&store), vec!["host", "token"]);
+    }
 }
@@ -1,30 +1,71 @@
+use hac_colors::Indicator;
+use hac_core::collection::conditions::RunContext;
+use hac_core::collection::dependencies;
+use hac_core::collection::environment;
+use hac_core::collection::overrides::RequestOverrides;
 use hac_core::collection::types::*;
+use hac_core::collection::validation;
+use hac_core::collection::variables;
 use hac_core::command::Command;
+use hac_core::digest::{self, DigestAlgorithm, DigestCommand, DigestTarget};
+use hac_core::fs::error::FsError;
+use hac_core::header_history::HeaderHistory;
+use hac_core::jwt;
+use hac_core::monitor::{self, MonitorTransition};
+use hac_core::net::auth::oauth2::{self, DeviceAuthorization, DeviceFlowEvent, TokenState};
+use hac_core::net::body_limit::{self, BodyLimit, BodySource, FullBody};
+use hac_core::net::curl;
+use hac_core::net::curl_config::CurlDefaults;
+use hac_core::net::graphql_ws::{self, Subscription};
+use hac_core::net::latency_history::LatencyHistory;
 use hac_core::net::request_manager::Response;
+use hac_core::net::tls_inspection::{self, TlsInfo};
+use hac_core::protobuf;
+use hac_core::syntax::format;
+use hac_core::viewer_preferences::ViewerPreferencesStore;
 
+use crate::clipboard;
+use crate::i18n::Message;
 use crate::pages::collection_viewer::collection_store::{CollectionStore, CollectionStoreAction};
+use crate::pages::collection_viewer::digest_form::{DigestForm, DigestFormEvent};
+use crate::pages::collection_viewer::filter_form::{FilterForm, FilterFormEvent};
+use crate::pages::collection_viewer::overrides_form::{OverridesForm, OverridesFormEvent};
+use crate::pages::collection_viewer::pinned_form::{PinnedForm, PinnedFormEvent};
 use crate::pages::collection_viewer::request_editor::{RequestEditor, RequestEditorEvent};
 use crate::pages::collection_viewer::request_uri::{RequestUri, RequestUriEvent};
-use crate::pages::collection_viewer::response_viewer::{ResponseViewer, ResponseViewerEvent};
+use crate::pages::collection_viewer::response_viewer::{
+    BodyUse, ResponseViewer, ResponseViewerEvent,
+};
 use crate::pages::collection_viewer::sidebar::{self, Sidebar, SidebarEvent};
-use crate::pages::{Eventful, Renderable};
+use crate::pages::collection_viewer::url_builder_form::{UrlBuilderForm, UrlBuilderFormEvent};
+use crate::pages::{self, Eventful, Renderable};
 
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::ops::{Add, Div};
 use std::rc::Rc;
+use std::sync::Arc;
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
 use ratatui::style::Stylize;
-use ratatui::widgets::{Block, Clear};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
 use ratatui::Frame;
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 
+/// below this width there is no room for the sidebar next to the request
+/// panes, so it collapses and is only shown, over them, while focused
+const COLLAPSE_SIDEBAR_WIDTH: u16 = 100;
+/// below this width the request editor and the response are stacked
+const STACK_PANES_WIDTH: u16 = 120;
+
 #[derive(Debug, PartialEq)]
 pub struct ExplorerLayout {
     pub hint_pane: Rect,
     pub sidebar: Rect,
+    /// whether the sidebar is drawn as an overlay instead of its own pane
+    pub sidebar_collapsed: bool,
     pub req_uri: Rect,
     pub req_editor: Rect,
     pub response_preview: Rect,
@@ -44,6 +85,18 @@ pub enum CollectionViewerOverlay {
     ChangeAuthMethod,
     HeadersForm(usize, bool),
     DeleteSidebarItem(String),
+    SendOverrides,
+    BodyDigest,
+    PinnedFields,
+    ResponseFilter,
+    UrlBuilder,
+    BulkRename,
+    /// shows the decoded header and claims of the given json web token
+    InspectJwt(String),
+    /// shows the code to sign in with while the device code flow runs
+    DeviceCode,
+    /// asks how to handle changes another session wrote to the collection
+    SyncConflict,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -80,6 +133,13 @@ pub struct CollectionViewer<'cv> {
     request_editor: RequestEditor<'cv>,
     request_uri: RequestUri<'cv>,
     sidebar: Sidebar<'cv>,
+    overrides_form: OverridesForm<'cv>,
+    digest_form: DigestForm<'cv>,
+    pinned_form: PinnedForm<'cv>,
+    filter_form: FilterForm<'cv>,
+    url_builder_form: UrlBuilderForm<'cv>,
+    /// the viewer preferences as they were last written to disk
+    saved_viewer_preferences: ViewerPreferencesStore,
 
     colors: &'cv hac_colors::Colors,
     config: &'cv hac_config::Config,
@@ -89,12 +149,59 @@ pub struct CollectionViewer<'cv> {
     collection_store: Rc<RefCell<CollectionStore>>,
 
     responses_map: HashMap<String, Rc<RefCell<Response>>>,
+    /// the response each request had before it was last sent, to compare
+    /// the current one with
+    previous_responses: HashMap<String, Rc<RefCell<Response>>>,
     response_rx: UnboundedReceiver<Response>,
     request_tx: UnboundedSender<Response>,
+    monitor_rx: UnboundedReceiver<(String, Response)>,
+    monitor_tx: UnboundedSender<(String, Response)>,
+    captures_rx: UnboundedReceiver<BTreeMap<String, String>>,
+    captures_tx: UnboundedSender<BTreeMap<String, String>>,
+    /// message to briefly show on the corner of the screen, along with its
+    /// title, whether it is a failure and when it was raised
+    notification: Option<(&'static str, String, bool, std::time::Instant)>,
+    /// summary of the overrides the last request was sent with, so the
+    /// response can be flagged as not coming from the saved request
+    sent_overrides: Option<String>,
+    device_flow: Option<DeviceFlow>,
+    device_flow_rx: UnboundedReceiver<DeviceFlowEvent>,
+    device_flow_tx: UnboundedSender<DeviceFlowEvent>,
+    /// the GraphQL subscription streaming its events into the response of
+    /// its request, only one runs at a time
+    subscription: Option<Subscription>,
+    subscription_rx: UnboundedReceiver<Response>,
+    subscription_tx: UnboundedSender<Response>,
+    /// sessions inspected on demand, with the id of the request whose
+    /// response they are shown on
+    tls_rx: UnboundedReceiver<(String, Result<TlsInfo, String>)>,
+    tls_tx: UnboundedSender<(String, Result<TlsInfo, String>)>,
+    /// whole bodies loaded back off the interface, with the id of the
+    /// request whose response they belong to and where they were loaded from
+    body_rx: UnboundedReceiver<(String, BodySource, Result<FullBody, String>)>,
+    body_tx: UnboundedSender<(String, BodySource, Result<FullBody, String>)>,
+    /// what the bodies being loaded back are used for once they are loaded,
+    /// by the id of their request
+    pending_bodies: HashMap<String, Vec<BodyUse>>,
+    /// requests are answered with their examples instead of being sent,
+    /// starts as set on the config
+    offline: bool,
 
     dry_run: bool,
 }
 
+/// a sign in through the device code flow, after which the request that
+/// started it is sent again with the same overrides
+#[derive(Debug)]
+struct DeviceFlow {
+    task: tokio::task::JoinHandle<()>,
+    /// whether an expiring token is being refreshed, before falling back to
+    /// signing in
+    refreshing: bool,
+    authorization: Option<DeviceAuthorization>,
+    overrides: RequestOverrides,
+}
+
 impl<'cv> CollectionViewer<'cv> {
     pub fn new(
         size: Rect,
@@ -103,8 +210,27 @@ impl<'cv> CollectionViewer<'cv> {
         config: &'cv hac_config::Config,
         dry_run: bool,
     ) -> Self {
-        let layout = build_layout(size);
+        let layout = build_layout(size, config.accessibility.plain);
         let (request_tx, response_rx) = unbounded_channel::<Response>();
+        let (monitor_tx, monitor_rx) = unbounded_channel::<(String, Response)>();
+        let (captures_tx, captures_rx) = unbounded_channel::<BTreeMap<String, String>>();
+        let (device_flow_tx, device_flow_rx) = unbounded_channel::<DeviceFlowEvent>();
+        let (subscription_tx, subscription_rx) = unbounded_channel::<Response>();
+        let (tls_tx, tls_rx) = unbounded_channel::<(String, Result<TlsInfo, String>)>();
+        let (body_tx, body_rx) =
+            unbounded_channel::<(String, BodySource, Result<FullBody, String>)>();
+
+        if let Some(history) = collection_store.borrow().get_latency_history() {
+            *history.borrow_mut() = LatencyHistory::load(hac_config::latency_history_file());
+        }
+        if let Some(history) = collection_store.borrow().get_header_history() {
+            *history.borrow_mut() = HeaderHistory::load(hac_config::header_history_file());
+        }
+        let saved_viewer_preferences =
+            ViewerPreferencesStore::load(hac_config::viewer_preferences_file());
+        if let Some(preferences) = collection_store.borrow().get_viewer_preferences() {
+            *preferences.borrow_mut() = saved_viewer_preferences.clone();
+        }
 
         let sidebar = sidebar::Sidebar::new(colors, collection_store.clone());
 
@@ -116,28 +242,99 @@ impl<'cv> CollectionViewer<'cv> {
             collection_store.clone(),
             None,
             layout.response_preview,
+            config.limits.display_bytes,
         );
 
-        let request_uri = RequestUri::new(colors, collection_store.clone(), layout.req_uri);
+        let request_uri = RequestUri::new(colors, config, collection_store.clone(), layout.req_uri);
 
-        CollectionViewer {
+        let mut viewer = CollectionViewer {
             request_editor,
             response_viewer,
             sidebar,
             request_uri,
+            overrides_form: OverridesForm::new(colors),
+            digest_form: DigestForm::new(colors),
+            pinned_form: PinnedForm::new(colors),
+            filter_form: FilterForm::new(colors),
+            url_builder_form: UrlBuilderForm::new(colors),
+            saved_viewer_preferences,
             colors,
             layout,
             config,
             global_command_sender: None,
             collection_sync_timer: std::time::Instant::now(),
             responses_map: HashMap::default(),
+            previous_responses: HashMap::default(),
             response_rx,
             request_tx,
+            monitor_rx,
+            monitor_tx,
+            captures_rx,
+            captures_tx,
+            notification: None,
+            sent_overrides: None,
+            device_flow: None,
+            device_flow_rx,
+            device_flow_tx,
+            subscription: None,
+            subscription_rx,
+            subscription_tx,
+            tls_rx,
+            tls_tx,
+            body_rx,
+            body_tx,
+            pending_bodies: HashMap::default(),
+            offline: config.offline,
             dry_run,
             collection_store,
+        };
+        viewer.schedule_monitors();
+        viewer
+    }
+
+    /// applies a configuration that changed while running. The panes are
+    /// built again with it, while responses, monitors and subscriptions keep
+    /// going
+    pub fn reload_config(
+        &mut self,
+        colors: &'cv hac_colors::Colors,
+        config: &'cv hac_config::Config,
+        size: Rect,
+    ) {
+        // the body being edited lives on the editor that is about to be
+        // replaced, so it is written to the request first
+        self.sync_collection_changes();
+
+        // offline can be toggled while running, so it only follows the
+        // config when the config itself changed it
+        if config.offline.ne(&self.config.offline) {
+            self.offline = config.offline;
+        }
+        self.colors = colors;
+        self.config = config;
+        self.layout = build_layout(size, config.accessibility.plain);
+        self.overrides_form = OverridesForm::new(colors);
+        self.digest_form = DigestForm::new(colors);
+        self.pinned_form = PinnedForm::new(colors);
+        self.filter_form = FilterForm::new(colors);
+        self.url_builder_form = UrlBuilderForm::new(colors);
+        self.rebuild_everything();
+
+        let selected = self.collection_store.borrow().get_selected_request();
+        let response = selected
+            .and_then(|request| self.responses_map.get(&request.read().unwrap().id).cloned());
+        if let Some(response) = response {
+            self.response_viewer.update(Some(response));
+            self.response_viewer
+                .set_overrides(self.sent_overrides.clone());
         }
     }
 
+    /// briefly shows a message on the corner of the screen
+    pub fn notify(&mut self, title: Message, message: String, is_failure: bool) {
+        self.notification = Some((title.text(), message, is_failure, std::time::Instant::now()));
+    }
+
     fn rebuild_everything(&mut self) {
         self.sidebar = sidebar::Sidebar::new(self.colors, self.collection_store.clone());
         self.request_editor = RequestEditor::new(
@@ -151,9 +348,11 @@ impl<'cv> CollectionViewer<'cv> {
             self.collection_store.clone(),
             None,
             self.layout.response_preview,
+            self.config.limits.display_bytes,
         );
         self.request_uri = RequestUri::new(
             self.colors,
+            self.config,
             self.collection_store.clone(),
             self.layout.req_uri,
         );
@@ -174,6 +373,17 @@ impl<'cv> CollectionViewer<'cv> {
     // if we have, so we can drain all the responses and update accordingly
     fn drain_responses_channel(&mut self) {
         while let Ok(res) = self.response_rx.try_recv() {
+            // streamed bodies are shown as they arrive, but the request is
+            // only done once the last of it arrived
+            let streaming = res.streaming;
+            if !res.is_error && !streaming {
+                self.record_latency(res.duration, res.status);
+            }
+            let request_id = self
+                .collection_store
+                .borrow()
+                .get_selected_request()
+                .map(|request| request.read().unwrap().id.clone());
             let res = Rc::new(RefCell::new(res));
             self.collection_store
                 .borrow()
@@ -183,8 +393,16 @@ impl<'cv> CollectionViewer<'cv> {
                     self.responses_map
                         .insert(req.read().unwrap().id.to_string(), Rc::clone(&res))
                 });
+            self.response_viewer.set_previous(
+                request_id
+                    .as_ref()
+                    .and_then(|id| self.previous_responses.get(id))
+                    .cloned(),
+            );
             self.response_viewer.update(Some(Rc::clone(&res)));
-            self.response_rx.is_empty().then(|| {
+            self.response_viewer
+                .set_overrides(self.sent_overrides.clone());
+            (self.response_rx.is_empty() && !streaming).then(|| {
                 self.collection_store
                     .borrow_mut()
                     .dispatch(CollectionStoreAction::SetPendingRequest(false));
@@ -192,7 +410,304 @@ impl<'cv> CollectionViewer<'cv> {
         }
     }
 
+    /// moves the body of the previous response of the request out of the way
+    /// when it was kept on disk, so the new one doesn't overwrite it
+    fn keep_previous_body(&mut self, request_id: &str, path: &std::path::Path) {
+        let Some(previous) = self.previous_responses.get(request_id) else {
+            return;
+        };
+        let mut previous = previous.borrow_mut();
+        if previous.body_file.as_deref().ne(&Some(path)) {
+            return;
+        }
+        let previous_path = path.with_extension("previous.body");
+        match std::fs::rename(path, &previous_path) {
+            Ok(()) => previous.body_file = Some(previous_path),
+            Err(e) => tracing::warn!("failed to keep the body of the previous response: {e}"),
+        }
+    }
+
+    /// writes the viewer preferences to disk when they changed since they
+    /// were last written
+    fn save_viewer_preferences(&mut self) {
+        let Some(preferences) = self.collection_store.borrow().get_viewer_preferences() else {
+            return;
+        };
+        let preferences = preferences.borrow();
+        if self.dry_run || preferences.eq(&self.saved_viewer_preferences) {
+            return;
+        }
+
+        if let Err(e) = preferences.save(hac_config::viewer_preferences_file()) {
+            tracing::error!("failed to save viewer preferences: {e}");
+        }
+        // failures are not retried on every tick
+        self.saved_viewer_preferences = preferences.clone();
+    }
+
+    /// stores how long the selected request took, so its history can be shown
+    /// next to the response, along with the status it got
+    fn record_latency(
+        &mut self,
+        duration: std::time::Duration,
+        status: Option<reqwest::StatusCode>,
+    ) {
+        let store = self.collection_store.borrow();
+        let (Some(request), Some(history)) =
+            (store.get_selected_request(), store.get_latency_history())
+        else {
+            return;
+        };
+
+        let mut history = history.borrow_mut();
+        let request_id = request.read().unwrap().id.clone();
+        history.record(&request_id, duration);
+        if let Some(status) = status {
+            history.record_status(&request_id, status.as_u16());
+        }
+
+        if self.dry_run {
+            return;
+        }
+
+        if let Err(e) = history.save(hac_config::latency_history_file()) {
+            tracing::error!("failed to save latency history: {e}");
+        }
+    }
+
+    /// remembers the values sent on the headers of the request, so they are
+    /// suggested when typing them again
+    fn record_headers(&self, request: &Request) {
+        let Some(history) = self.collection_store.borrow().get_header_history() else {
+            return;
+        };
+
+        let mut history = history.borrow_mut();
+        history.record_request(request);
+
+        if self.dry_run {
+            return;
+        }
+
+        if let Err(e) = history.save(hac_config::header_history_file()) {
+            tracing::error!("failed to save header history: {e}");
+        }
+    }
+
+    /// parses the schedules of the monitors that changed since the last time,
+    /// so ticks only have to compare when each monitor runs next
+    fn schedule_monitors(&mut self) {
+        let store = self.collection_store.borrow();
+        let (Some(requests), Some(stats)) = (store.get_requests(), store.get_monitor_stats())
+        else {
+            return;
+        };
+
+        let now = std::time::Instant::now();
+        let schedules = monitor::monitor_schedules(&requests.read().unwrap())
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+        let mut stats = stats.borrow_mut();
+        for id in schedules.keys() {
+            stats.entry(id.clone()).or_default();
+        }
+        for (id, entry) in stats.iter_mut() {
+            entry.set_schedule(schedules.get(id).map(String::as_str), now);
+        }
+    }
+
+    /// sends every monitored request whose schedule says it is time to
+    fn run_due_monitors(&mut self) {
+        // examples say nothing about how the api is doing
+        if self.offline {
+            return;
+        }
+        let store = self.collection_store.borrow();
+        let (Some(requests), Some(stats)) = (store.get_requests(), store.get_monitor_stats())
+        else {
+            return;
+        };
+
+        let now = std::time::Instant::now();
+        let due = stats
+            .borrow_mut()
+            .iter_mut()
+            .filter(|(_, entry)| entry.is_due(now))
+            .map(|(id, entry)| {
+                entry.mark_run(now);
+                id.clone()
+            })
+            .collect::<Vec<_>>();
+        if due.is_empty() {
+            return;
+        }
+
+        let variables = self.active_variables();
+        let schema = self.protobuf_schema().map_err(|e| e.to_string());
+        for id in due {
+            let Some(request) = dependencies::find_request(&requests.read().unwrap(), &id) else {
+                continue;
+            };
+            let protobuf = match schema.as_ref() {
+                Ok(protobuf) => protobuf.clone(),
+                Err(e) => {
+                    _ = self.monitor_tx.send((id, Response::from_error(e.clone())));
+                    continue;
+                }
+            };
+            let request = environment::resolve_request(&request.read().unwrap(), &variables);
+            monitor::run_monitor(request, self.config, protobuf, self.monitor_tx.clone());
+        }
+    }
+
+    /// records the result of every monitor run that finished, notifying when
+    /// a monitor starts failing or recovers
+    fn drain_monitor_channel(&mut self) {
+        let mut changed = false;
+
+        while let Ok((id, response)) = self.monitor_rx.try_recv() {
+            let notification = {
+                let store = self.collection_store.borrow();
+                let (Some(requests), Some(stats)) =
+                    (store.get_requests(), store.get_monitor_stats())
+                else {
+                    continue;
+                };
+                let Some(request) = dependencies::find_request(&requests.read().unwrap(), &id)
+                else {
+                    continue;
+                };
+                let request = request.read().unwrap();
+                let Some(monitor) = request.monitor.as_ref() else {
+                    continue;
+                };
+
+                let sample = monitor::Sample {
+                    latency: response.duration,
+                    failure: monitor::check(monitor, &response).err(),
+                };
+                let transition = stats.borrow_mut().entry(id).or_default().record(sample);
+                match transition {
+                    Some(MonitorTransition::Failing(reason)) => {
+                        tracing::warn!("monitor {:?} is failing: {reason}", request.name);
+                        let message = Message::MonitorFailing.format(&[&request.name, &reason]);
+                        Some((message, true))
+                    }
+                    Some(MonitorTransition::Recovered) => {
+                        Some((Message::MonitorRecovered.format(&[&request.name]), false))
+                    }
+                    None => None,
+                }
+            };
+            if let Some((message, is_failure)) = notification {
+                self.notify(Message::Monitor, message, is_failure);
+            }
+            changed = true;
+        }
+
+        if changed {
+            self.sidebar.rebuild_tree_view();
+        }
+    }
+
+    fn draw_notification(&mut self, frame: &mut Frame, size: Rect) {
+        let Some((title, message, is_failure, raised_at)) = self.notification.as_ref() else {
+            return;
+        };
+
+        if raised_at.elapsed().as_secs().ge(&5) {
+            self.notification = None;
+            return;
+        }
+
+        // on plain mode notifications are announced on the status line
+        if self.config.accessibility.plain {
+            return;
+        }
+
+        let indicator = match is_failure {
+            true => Indicator::Failure,
+            false => Indicator::Success,
+        };
+        let message = self.colors.indicate(indicator, message.to_string());
+        let color = message.style.fg.unwrap_or_default();
+        let width = (message.width() as u16).add(4).min(size.width.div(2));
+        let area = Rect::new(size.right().saturating_sub(width), size.y, width, 3);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(message).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(*title)
+                    .border_style(Style::default().fg(color)),
+            ),
+            area,
+        );
+    }
+
+    /// on plain mode, describes what is focused and announces notifications
+    /// on the bottom line, so there is a single place to read state from
+    fn draw_status_line(&self, frame: &mut Frame) {
+        let store = self.collection_store.borrow();
+        let pane = match store.get_focused_pane() {
+            PaneFocus::Sidebar => Message::Requests.text(),
+            PaneFocus::ReqUri => Message::Uri.text(),
+            PaneFocus::Editor => Message::RequestEditor.text(),
+            PaneFocus::Preview => Message::Response.text(),
+        };
+
+        let mut announcements = vec![];
+        if let Some((title, message, is_failure, _)) = self.notification.as_ref() {
+            match is_failure {
+                true => announcements.push(Message::NotificationFailed.format(&[title, message])),
+                false => announcements.push(format!("{title}: {message}")),
+            }
+        }
+
+        match store.get_selected_pane() {
+            Some(_) => announcements.push(Message::PaneEditing.format(&[pane])),
+            None => announcements.push(Message::PaneFocused.format(&[pane])),
+        }
+
+        if let Some(request) = store.get_selected_request() {
+            let request = request.read().unwrap();
+            announcements.push(
+                Message::RequestSummary.format(&[&request.method.to_string(), &request.name]),
+            );
+
+            if let Some(response) = self.responses_map.get(&request.id) {
+                let response = response.borrow();
+                match (response.status, response.cause.as_ref()) {
+                    (Some(status), _) => announcements.push(Message::ResponseSummary.format(&[
+                        &status.to_string(),
+                        &response.duration.as_millis().to_string(),
+                    ])),
+                    (None, cause) => announcements.push(
+                        Message::RequestFailed
+                            .format(&[cause.map(String::as_str).unwrap_or_default()]),
+                    ),
+                }
+            }
+        }
+
+        frame.render_widget(
+            Paragraph::new(announcements.join(". ")).fg(self.colors.normal.white),
+            self.layout.hint_pane,
+        );
+    }
+
     fn sync_collection_changes(&mut self) {
+        // read only collections cannot be changed, so there is nothing to
+        // sync, and nothing is synced until a conflict is resolved
+        let store = self.collection_store.borrow();
+        if store.is_read_only() || store.peek_overlay() == CollectionViewerOverlay::SyncConflict {
+            drop(store);
+            self.collection_sync_timer = std::time::Instant::now();
+            return;
+        }
+        drop(store);
+
         let sender = self
             .global_command_sender
             .as_ref()
@@ -209,12 +724,19 @@ impl<'cv> CollectionViewer<'cv> {
             .clone();
         if let Some(request) = self.collection_store.borrow().get_selected_request() {
             let request = request.clone();
-            let body = self.request_editor.body().to_string();
-            // this is not the best idea for when we start implementing other kinds of
-            // body types like GraphQL
+            let body = self.request_editor.body();
+            // bodies are JSON unless the request was given another type, like
+            // the GraphQL of subscriptions
             if !body.is_empty() {
-                request.write().unwrap().body = Some(body);
-                request.write().unwrap().body_type = Some(BodyType::Json)
+                let mut request = request.write().unwrap();
+                let body_type = request.body_type.get_or_insert(BodyType::Json);
+                // only what is stored is formatted, the editor is left as is
+                // so nothing moves while typing
+                let body = match collection.format_on_save {
+                    true => format::format_body(&body, Some(body_type)).unwrap_or(body),
+                    false => body,
+                };
+                request.body = Some(body);
             }
 
             // we might later on decide to keep track of the actual dir/request index
@@ -245,6 +767,10 @@ impl<'cv> CollectionViewer<'cv> {
                 });
         }
 
+        self.collection_store
+            .borrow_mut()
+            .dispatch(CollectionStoreAction::RefreshCompletions);
+        self.schedule_monitors();
         self.collection_sync_timer = std::time::Instant::now();
 
         if self.dry_run {
@@ -254,6 +780,10 @@ impl<'cv> CollectionViewer<'cv> {
         tokio::spawn(async move {
             match hac_core::fs::sync_collection(collection).await {
                 Ok(_) => {}
+                Err(FsError::Conflict(path)) => {
+                    tracing::warn!("collection {path} was changed by another session");
+                    _ = sender.send(Command::CollectionConflict);
+                }
                 Err(e) => {
                     if sender.send(Command::Error(e.to_string())).is_err() {
                         tracing::error!("failed to send error command through channel");
@@ -264,6 +794,912 @@ impl<'cv> CollectionViewer<'cv> {
         });
     }
 
+    /// replaces the pinned paths of the selected request, showing their
+    /// values on the current response right away
+    fn save_pins(&mut self, pins: Vec<String>) {
+        let store = self.collection_store.borrow();
+        let (Some(request), Some(collection)) =
+            (store.get_selected_request(), store.get_collection())
+        else {
+            return;
+        };
+
+        if let Err(e) = collection.borrow().ensure_writable() {
+            drop(store);
+            self.notify(Message::Pinned, e.to_string(), true);
+            return;
+        }
+
+        let count = pins.len();
+        request.write().unwrap().pinned = pins;
+        drop(store);
+        self.response_viewer.refresh_pinned();
+        self.notify(
+            Message::Pinned,
+            Message::PinsSaved.format(&[&count.to_string()]),
+            false,
+        );
+    }
+
+    /// replaces the uri of the selected request with the one composed on the
+    /// url builder
+    fn save_uri(&mut self, uri: String) {
+        let store = self.collection_store.borrow();
+        let (Some(request), Some(collection)) =
+            (store.get_selected_request(), store.get_collection())
+        else {
+            return;
+        };
+
+        if let Err(e) = collection.borrow().ensure_writable() {
+            drop(store);
+            self.notify(Message::UrlBuilder, e.to_string(), true);
+            return;
+        }
+
+        request.write().unwrap().uri = uri;
+    }
+
+    /// unlocks a read only collection so it can be edited during this session,
+    /// or locks it back, syncing whatever changed while it was unlocked
+    fn toggle_lock(&mut self) {
+        let Some(collection) = self.collection_store.borrow().get_collection() else {
+            return;
+        };
+
+        if !collection.borrow().info.read_only {
+            return;
+        }
+
+        let unlocked = collection.borrow().unlocked;
+        if unlocked {
+            self.sync_collection_changes();
+        }
+
+        self.collection_store
+            .borrow_mut()
+            .dispatch(CollectionStoreAction::SetUnlocked(!unlocked));
+    }
+
+    /// variables of the environment set as active on the config, when the
+    /// collection has an environment with that name, with the local ones of
+    /// the workspace and the session ones on top of them
+    fn active_variables(&self) -> BTreeMap<String, String> {
+        let store = self.collection_store.borrow();
+        let variables = match store.get_collection() {
+            Some(collection) => environment::environment_variables(
+                &collection.borrow(),
+                self.config.environment.as_deref(),
+            ),
+            None => BTreeMap::default(),
+        };
+
+        match store.get_session() {
+            Some(session) => session.borrow().layered(variables),
+            None => variables,
+        }
+    }
+
+    /// keeps the values captured from responses on the session
+    fn drain_captures_channel(&mut self) {
+        while let Ok(captured) = self.captures_rx.try_recv() {
+            if let Some(session) = self.collection_store.borrow().get_session() {
+                session.borrow_mut().extend(captured);
+            }
+        }
+    }
+
+    /// the messages declared on the `.proto` files of the collection, read
+    /// again on every send so changes to them are picked up
+    fn protobuf_schema(&self) -> anyhow::Result<Option<Arc<protobuf::Schema>>> {
+        match self.collection_store.borrow().get_collection() {
+            Some(collection) => protobuf::load_schema(&collection.borrow()),
+            None => Ok(None),
+        }
+    }
+
+    /// copies the body of the last response of the selected request, or the
+    /// request itself as a curl command, to the clipboard
+    fn copy_to_clipboard(&mut self, as_curl: bool) {
+        if !as_curl && self.load_full_body(BodyUse::Copy) {
+            return;
+        }
+        let text = self
+            .collection_store
+            .borrow()
+            .get_selected_request()
+            .and_then(|request| {
+                let request = request.read().unwrap();
+                match as_curl {
+                    true => {
+                        let resolved =
+                            environment::resolve_request(&request, &self.active_variables());
+                        Some(curl::to_curl(&resolved, self.config))
+                    }
+                    false => {
+                        let response = self.responses_map.get(&request.id)?.borrow();
+                        body_limit::full_body(&response)
+                    }
+                }
+            });
+
+        let (message, is_failure) = match text.map(|text| clipboard::copy(&text)) {
+            Some(Ok(())) if as_curl => (Message::CopiedCurl.text().to_string(), false),
+            Some(Ok(())) => (Message::CopiedBody.text().to_string(), false),
+            Some(Err(e)) => (e.to_string(), true),
+            None => (Message::NothingToCopy.text().to_string(), true),
+        };
+        self.notify(Message::Clipboard, message, is_failure);
+    }
+
+    /// asks for the request body to be opened on the editor, or the response
+    /// body on the pager, which suspends the interface until they exit
+    fn open_external(&mut self, edit: bool) {
+        if !edit && self.load_full_body(BodyUse::Pager) {
+            return;
+        }
+        let contents = match edit {
+            true => self
+                .collection_store
+                .borrow()
+                .get_selected_request()
+                .map(|_| self.request_editor.body()),
+            false => self
+                .collection_store
+                .borrow()
+                .get_selected_request()
+                .and_then(|request| {
+                    let response = self.responses_map.get(&request.read().unwrap().id)?;
+                    let response = response.borrow();
+                    response
+                        .pretty_body
+                        .as_ref()
+                        .map(ToString::to_string)
+                        .or_else(|| response.body.clone())
+                }),
+        };
+
+        let Some(contents) = contents else {
+            self.notify(
+                Message::ExternalProgram,
+                Message::NothingToOpen.text().into(),
+                true,
+            );
+            return;
+        };
+
+        // the extension is only a hint for the program to pick a syntax
+        let is_json = edit || serde_json::from_str::<serde_json::Value>(&contents).is_ok();
+        let extension = match is_json {
+            true => "json",
+            false => "txt",
+        };
+        if let Some(sender) = self.global_command_sender.as_ref() {
+            _ = sender.send(Command::OpenExternal {
+                contents,
+                extension: extension.into(),
+                edit,
+            });
+        }
+    }
+
+    /// compares the example being displayed, or the first example when none
+    /// is, against the last response of the selected request on the diff tool
+    fn diff_with_example(&mut self) {
+        if self.load_full_body(BodyUse::Diff) {
+            return;
+        }
+        let texts = self
+            .collection_store
+            .borrow()
+            .get_selected_request()
+            .and_then(|request| {
+                let request = request.read().unwrap();
+                let idx = self.response_viewer.example_index().unwrap_or_default();
+                let example = request.examples.get(idx)?.body.clone().unwrap_or_default();
+                let response = self.responses_map.get(&request.id)?.borrow();
+                let response = body_limit::full_body(&response)?;
+                Some((example, response))
+            });
+
+        let Some((example, response)) = texts else {
+            self.notify(
+                Message::ExternalProgram,
+                Message::NothingToCompare.text().into(),
+                true,
+            );
+            return;
+        };
+
+        // both sides are formatted the same way, so only actual changes show up
+        let pretty = |text: String| match serde_json::from_str::<serde_json::Value>(&text) {
+            Ok(value) => serde_json::to_string_pretty(&value).map_err(|_| text),
+            Err(_) => Err(text),
+        };
+        let (left, right, extension) = match (pretty(example), pretty(response)) {
+            (Ok(left), Ok(right)) => (left, right, "json"),
+            (left, right) => (
+                left.unwrap_or_else(|text| text),
+                right.unwrap_or_else(|text| text),
+                "txt",
+            ),
+        };
+
+        if let Some(sender) = self.global_command_sender.as_ref() {
+            _ = sender.send(Command::DiffExternal {
+                left,
+                right,
+                extension: extension.into(),
+            });
+        }
+    }
+
+    /// another session changed the collection file, so the changes of this one
+    /// are kept until the user decides how to handle them
+    pub fn sync_conflict(&mut self) {
+        let mut store = self.collection_store.borrow_mut();
+        if store.peek_overlay() != CollectionViewerOverlay::SyncConflict {
+            store.push_overlay(CollectionViewerOverlay::SyncConflict);
+        }
+    }
+
+    /// merges, reloads or overwrites the collection after a sync conflict
+    fn resolve_sync_conflict(&mut self, key_event: KeyEvent) {
+        let Some(collection) = self.collection_store.borrow().get_collection() else {
+            return;
+        };
+        let collection = collection.borrow().clone();
+        let result = match key_event.code {
+            KeyCode::Char('m') => hac_core::fs::merge_collection(&collection)
+                .map(|merged| (Some(merged), Message::CollectionMerged)),
+            KeyCode::Char('r') => hac_core::fs::reload_collection(&collection)
+                .map(|reloaded| (Some(reloaded), Message::CollectionReloaded)),
+            KeyCode::Char('o') => hac_core::fs::overwrite_collection(&collection)
+                .map(|_| (None, Message::CollectionOverwritten)),
+            _ => return,
+        };
+
+        match result {
+            Ok((replacement, message)) => {
+                self.collection_store.borrow_mut().pop_overlay();
+                if let Some(replacement) = replacement {
+                    self.collection_store
+                        .borrow_mut()
+                        .replace_collection(replacement);
+                    self.rebuild_everything();
+                }
+                self.sync_collection_changes();
+                self.notify(Message::SyncConflict, message.text().into(), false);
+            }
+            Err(e) => self.notify(Message::SyncConflict, e.to_string(), true),
+        }
+    }
+
+    fn draw_sync_conflict(&self, frame: &mut Frame, size: Rect) {
+        let name = self
+            .collection_store
+            .borrow()
+            .get_collection()
+            .map(|collection| collection.borrow().info.name.clone())
+            .unwrap_or_default();
+        let content = format!(
+            "{}\n\n{}",
+            Message::SyncConflictDescription.format(&[&name]),
+            Message::SyncConflictOptions.text()
+        );
+
+        let width = 60.min(size.width);
+        let height = 7.min(size.height);
+        let area = Rect::new(
+            size.x.add(size.width.saturating_sub(width).div(2)),
+            size.y.add(size.height.saturating_sub(height).div(2)),
+            width,
+            height,
+        );
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(content)
+                .fg(self.colors.normal.white)
+                .centered()
+                .wrap(Wrap { trim: true })
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(Message::SyncConflict.text())
+                        .border_style(Style::default().fg(self.colors.normal.yellow)),
+                ),
+            area,
+        );
+    }
+
+    /// the editor or pager opened by `open_external` exited, when it was the
+    /// editor, the request body is replaced by what was saved
+    pub fn external_closed(&mut self, result: Result<Option<String>, String>) {
+        match result {
+            // most editors end files with a newline, which the body didn't have
+            Ok(Some(body)) => self
+                .request_editor
+                .replace_body(body.strip_suffix('\n').unwrap_or(&body)),
+            Ok(None) => {}
+            Err(e) => self.notify(Message::ExternalProgram, e, true),
+        }
+    }
+
+    fn send_request(&mut self) {
+        self.send_request_with(RequestOverrides::default());
+    }
+
+    /// sends the selected request, preceded by every request it depends on,
+    /// when the dependencies cannot be resolved the error is shown as the
+    /// response instead. The overrides only apply to the selected request and
+    /// are never saved
+    fn send_request_with(&mut self, overrides: RequestOverrides) {
+        self.sent_overrides = (!overrides.is_empty()).then(|| overrides.summary());
+        let store = self.collection_store.borrow();
+        let Some(request) = store.get_selected_request() else {
+            return;
+        };
+
+        let request_id = request.read().unwrap().id.clone();
+        if let Some(response) = self.responses_map.get(&request_id) {
+            self.previous_responses
+                .insert(request_id.clone(), Rc::clone(response));
+        }
+
+        let requests = store.get_requests().unwrap_or_default();
+        let dependencies =
+            dependencies::resolve_dependencies(&requests.read().unwrap(), &request.read().unwrap());
+
+        let variables = self.active_variables();
+        // conditions are only evaluated on the dependencies, which are
+        // resolved without the overrides
+        let context = RunContext::new(self.config.environment.clone(), variables.clone());
+        let mut overridden_variables = variables.clone();
+        overrides.merge_variables(&mut overridden_variables);
+
+        let dependencies = dependencies
+            .map_err(|e| anyhow::anyhow!(e.to_string()))
+            .and_then(|dependencies| Ok((dependencies, self.protobuf_schema()?)));
+        let (dependencies, protobuf) = match dependencies {
+            Ok(dependencies) => dependencies,
+            Err(e) => {
+                if self
+                    .request_tx
+                    .send(Response::from_error(e.to_string()))
+                    .is_err()
+                {
+                    tracing::error!("failed to send response through channel");
+                    std::process::abort();
+                }
+                return;
+            }
+        };
+
+        let applied = overrides.apply(&request.read().unwrap());
+        let mut request = environment::resolve_request(&applied, &overridden_variables);
+        let issues = validation::validate_request(&request);
+        if !issues.is_empty() {
+            let cause = issues
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n");
+            if self.request_tx.send(Response::from_error(cause)).is_err() {
+                tracing::error!("failed to send response through channel");
+                std::process::abort();
+            }
+            return;
+        }
+        self.record_headers(&applied);
+        let mut dependencies = dependencies
+            .iter()
+            .map(|dependency| environment::resolve_request(dependency, &variables))
+            .collect::<Vec<_>>();
+        drop(store);
+
+        dependencies.iter_mut().for_each(|dependency| {
+            self.attach_token(dependency);
+        });
+        if let Some((config, refresh_token)) = self.attach_token(&mut request) {
+            // there is no signing in while offline, the request is answered
+            // by its examples without a token
+            if !self.offline {
+                self.start_device_flow(config, refresh_token, overrides);
+                return;
+            }
+            self.notify(Message::SignIn, Message::SignInOffline.text().into(), true);
+        }
+
+        if let Some(mut subscription) = self.subscription.take() {
+            subscription.stop();
+        }
+        if request.is_subscription() && !self.offline {
+            self.subscription = Some(graphql_ws::subscribe(request, self.subscription_tx.clone()));
+            return;
+        }
+
+        // large bodies are kept on disk, so responses of past requests
+        // don't pile up in memory
+        let path = hac_config::bodies_dir().join(format!("{request_id}.body"));
+        self.keep_previous_body(&request_id, &path);
+        let body_limit = BodyLimit::new(&self.config.limits, path);
+
+        let config = hac_config::Config {
+            offline: self.offline,
+            ..self.config.clone()
+        };
+        hac_core::net::handle_request(
+            request,
+            dependencies,
+            context,
+            &config,
+            protobuf,
+            Some(body_limit),
+            self.request_tx.clone(),
+            self.captures_tx.clone(),
+        );
+    }
+
+    /// adds the token of the device code config of the request, when it
+    /// signs in that way, yielding the config when there is no valid token
+    /// for it yet, along with the refresh token of the expiring one
+    fn attach_token(&self, request: &mut Request) -> Option<(DeviceCodeConfig, Option<String>)> {
+        if request.auth_method != Some(AuthMethod::DeviceCode) {
+            return None;
+        }
+        let config = request.auth.as_ref()?.device_code.clone()?;
+        let tokens = self.collection_store.borrow().get_tokens()?;
+        let tokens = tokens.borrow();
+        match tokens.state(&config.key(), chrono::Utc::now()) {
+            TokenState::Valid(token) => {
+                oauth2::authorize(request, token);
+                None
+            }
+            TokenState::Refresh(refresh_token) => Some((config, Some(refresh_token.into()))),
+            TokenState::SignIn => Some((config, None)),
+        }
+    }
+
+    /// refreshes the token or signs in through the device code flow, the
+    /// request is sent once there is a valid token
+    fn start_device_flow(
+        &mut self,
+        config: DeviceCodeConfig,
+        refresh_token: Option<String>,
+        overrides: RequestOverrides,
+    ) {
+        let client = match CurlDefaults::load(&self.config.curl)
+            .client(&self.config.connection, &self.config.pins)
+        {
+            Ok(client) => client,
+            Err(e) => {
+                let cause = format!("failed to apply the settings of .curlrc: {e}");
+                _ = self.request_tx.send(Response::from_error(cause));
+                return;
+            }
+        };
+
+        if let Some(flow) = self.device_flow.take() {
+            flow.task.abort();
+        }
+        self.device_flow = Some(DeviceFlow {
+            refreshing: refresh_token.is_some(),
+            task: oauth2::run_device_flow(
+                config,
+                client,
+                refresh_token,
+                self.device_flow_tx.clone(),
+            ),
+            authorization: None,
+            overrides,
+        });
+        self.collection_store
+            .borrow_mut()
+            .push_overlay(CollectionViewerOverlay::DeviceCode);
+    }
+
+    /// follows the device code flow, sending the request once the user
+    /// signed in or showing why it failed as its response
+    fn drain_device_flow_channel(&mut self) {
+        while let Ok(event) = self.device_flow_rx.try_recv() {
+            match event {
+                DeviceFlowEvent::Started(authorization) => {
+                    if let Some(flow) = self.device_flow.as_mut() {
+                        flow.refreshing = false;
+                        flow.authorization = Some(authorization);
+                    }
+                }
+                DeviceFlowEvent::Authorized(key, token) => {
+                    if let Some(tokens) = self.collection_store.borrow().get_tokens() {
+                        tokens.borrow_mut().insert(key, token);
+                    }
+                    let Some(flow) = self.finish_device_flow() else {
+                        continue;
+                    };
+                    let message = match flow.refreshing {
+                        true => Message::TokenRefreshed,
+                        false => Message::SignedIn,
+                    };
+                    self.notify(Message::SignIn, message.text().into(), false);
+                    self.send_request_with(flow.overrides);
+                }
+                DeviceFlowEvent::Failed(cause) => {
+                    if self.finish_device_flow().is_some() {
+                        _ = self.request_tx.send(Response::from_error(cause));
+                    }
+                }
+            }
+        }
+    }
+
+    /// shows every event of the subscription as they arrive, on the response
+    /// of the request that subscribed even when another one is selected
+    fn drain_subscription_channel(&mut self) {
+        while let Ok(response) = self.subscription_rx.try_recv() {
+            let Some(request_id) = self.subscription.as_ref().map(|s| s.request_id.clone()) else {
+                continue;
+            };
+            let response = Rc::new(RefCell::new(response));
+            self.responses_map
+                .insert(request_id.clone(), Rc::clone(&response));
+
+            let mut store = self.collection_store.borrow_mut();
+            let selected = store
+                .get_selected_request()
+                .is_some_and(|request| request.read().unwrap().id.eq(&request_id));
+            store.dispatch(CollectionStoreAction::SetPendingRequest(false));
+            drop(store);
+            if selected {
+                self.response_viewer.update(Some(response));
+                self.response_viewer
+                    .set_overrides(self.sent_overrides.clone());
+            }
+        }
+
+        if self
+            .subscription
+            .as_ref()
+            .is_some_and(|subscription| subscription.is_finished())
+            && self.subscription_rx.is_empty()
+        {
+            self.subscription = None;
+        }
+    }
+
+    /// handshakes with the server of the selected request to show the
+    /// session it negotiates. It is done on demand, on a connection of its
+    /// own, as the client requests are sent with doesn't expose its
+    /// connections
+    fn inspect_tls(&mut self) {
+        if self.offline {
+            self.notify(Message::Tls, Message::TlsOffline.text().into(), true);
+            return;
+        }
+        let store = self.collection_store.borrow();
+        let Some(request) = store.get_selected_request() else {
+            return;
+        };
+        let request =
+            environment::resolve_request(&request.read().unwrap(), &self.active_variables());
+        drop(store);
+        let sent = self.responses_map.contains_key(&request.id);
+        if !sent || !request.uri.trim_start().starts_with("https://") {
+            self.notify(Message::Tls, Message::TlsNotInspected.text().into(), true);
+            return;
+        }
+
+        let message = Message::TlsInspecting.format(&[request.uri.trim()]);
+        self.notify(Message::Tls, message, false);
+        let tls_tx = self.tls_tx.clone();
+        tokio::spawn(async move {
+            let tls = tls_inspection::inspect(request.uri.trim())
+                .await
+                .map_err(|e| e.to_string());
+            _ = tls_tx.send((request.id, tls));
+        });
+    }
+
+    /// shows inspected sessions along with the response of their request
+    fn drain_tls_channel(&mut self) {
+        while let Ok((request_id, tls)) = self.tls_rx.try_recv() {
+            let tls = match tls {
+                Ok(tls) => tls,
+                Err(e) => {
+                    self.notify(Message::Tls, e, true);
+                    continue;
+                }
+            };
+            let Some(response) = self.responses_map.get(&request_id).cloned() else {
+                continue;
+            };
+            response.borrow_mut().tls = Some(tls);
+            let selected = self
+                .collection_store
+                .borrow()
+                .get_selected_request()
+                .is_some_and(|request| request.read().unwrap().id.eq(&request_id));
+            if selected {
+                self.response_viewer.update(Some(response));
+            }
+        }
+    }
+
+    /// loads the whole body of the response of the selected request back
+    /// off the interface, when only its start is in memory, to be used once
+    /// it is loaded. Returns whether it has to be loaded before being used
+    fn load_full_body(&mut self, body_use: BodyUse) -> bool {
+        let Some(request_id) = self
+            .collection_store
+            .borrow()
+            .get_selected_request()
+            .map(|request| request.read().unwrap().id.clone())
+        else {
+            return false;
+        };
+        let Some(response) = self.responses_map.get(&request_id) else {
+            return false;
+        };
+        let response = response.borrow();
+        // only showing the body needs all of it pretty printed
+        let pretty = matches!(body_use, BodyUse::Show | BodyUse::Pager);
+        if !pretty && response.body_file.is_none() {
+            return false;
+        }
+        let Some(source) = BodySource::of(&response) else {
+            return false;
+        };
+        let headers = response.headers.clone();
+        drop(response);
+
+        let pending = self.pending_bodies.entry(request_id.clone()).or_default();
+        let loading = !pending.is_empty();
+        if !pending.contains(&body_use) {
+            pending.push(body_use);
+        }
+        if loading {
+            return true;
+        }
+        let body_tx = self.body_tx.clone();
+        tokio::task::spawn_blocking(move || {
+            let body = source
+                .clone()
+                .load(headers.as_ref())
+                .map_err(|e| e.to_string());
+            _ = body_tx.send((request_id, source, body));
+        });
+        true
+    }
+
+    /// puts loaded bodies back on their responses, then uses them as was
+    /// asked while they were loaded
+    fn drain_body_channel(&mut self) {
+        while let Ok((request_id, source, body)) = self.body_rx.try_recv() {
+            let uses = self.pending_bodies.remove(&request_id).unwrap_or_default();
+            let body = match body {
+                Ok(body) => body,
+                Err(e) => {
+                    self.notify(Message::Body, e, true);
+                    continue;
+                }
+            };
+            // a newer response may have arrived while it was loaded
+            let Some(response) = self.responses_map.get(&request_id).cloned() else {
+                continue;
+            };
+            if BodySource::of(&response.borrow()).ne(&Some(source)) {
+                continue;
+            }
+            body.apply(&mut response.borrow_mut());
+            let selected = self
+                .collection_store
+                .borrow()
+                .get_selected_request()
+                .is_some_and(|request| request.read().unwrap().id.eq(&request_id));
+            if selected {
+                uses.into_iter()
+                    .for_each(|body_use| self.use_full_body(body_use));
+            }
+        }
+    }
+
+    /// uses the whole body of the response of the selected request, right
+    /// away when it is already in memory
+    fn with_full_body(&mut self, body_use: BodyUse) {
+        if !self.load_full_body(body_use) {
+            self.use_full_body(body_use);
+        }
+    }
+
+    fn use_full_body(&mut self, body_use: BodyUse) {
+        match body_use {
+            BodyUse::Show => self.response_viewer.show_full_body(),
+            BodyUse::Refresh => self.response_viewer.refresh(),
+            BodyUse::Save => {
+                if let Some(ResponseViewerEvent::Notify(title, message, failed)) =
+                    self.response_viewer.save_body()
+                {
+                    self.notify(title, message, failed);
+                }
+            }
+            BodyUse::Copy => self.copy_to_clipboard(false),
+            BodyUse::Diff => self.diff_with_example(),
+            BodyUse::Pager => self.open_external(false),
+        }
+    }
+
+    fn toggle_offline(&mut self) {
+        self.offline = !self.offline;
+        let message = match self.offline {
+            true => Message::OfflineOn,
+            false => Message::OfflineOff,
+        };
+        self.notify(Message::Offline, message.text().into(), false);
+    }
+
+    fn stop_subscription(&mut self) {
+        let message = match self.subscription.as_mut() {
+            Some(subscription) => {
+                subscription.stop();
+                Message::SubscriptionStopped
+            }
+            None => Message::NoSubscription,
+        };
+        self.notify(
+            Message::Subscription,
+            message.text().into(),
+            message == Message::NoSubscription,
+        );
+    }
+
+    fn finish_device_flow(&mut self) -> Option<DeviceFlow> {
+        let flow = self.device_flow.take()?;
+        let mut store = self.collection_store.borrow_mut();
+        if store.peek_overlay() == CollectionViewerOverlay::DeviceCode {
+            store.pop_overlay();
+        }
+        Some(flow)
+    }
+
+    fn draw_device_code(&self, frame: &mut Frame, size: Rect) {
+        let Some(flow) = self.device_flow.as_ref() else {
+            return;
+        };
+        let content = match flow.authorization.as_ref() {
+            Some(authorization) => {
+                let uri = authorization
+                    .verification_uri_complete
+                    .as_ref()
+                    .unwrap_or(&authorization.verification_uri);
+                Message::SignInInstructions.format(&[uri, &authorization.user_code])
+            }
+            None if flow.refreshing => Message::TokenRefreshing.text().into(),
+            None => Message::SignInRequesting.text().into(),
+        };
+        let content = format!("{content}\n\n{}", Message::SignInCancel.text());
+
+        let width = (content.lines().map(|line| line.chars().count()).max())
+            .unwrap_or_default()
+            .add(4)
+            .max(40)
+            .min(size.width as usize) as u16;
+        let height = 6.min(size.height);
+        let area = Rect::new(
+            size.x.add(size.width.saturating_sub(width).div(2)),
+            size.y.add(size.height.saturating_sub(height).div(2)),
+            width,
+            height,
+        );
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(content)
+                .fg(self.colors.normal.white)
+                .centered()
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(Message::SignIn.text())
+                        .border_style(Style::default().fg(self.colors.bright.blue)),
+                ),
+            area,
+        );
+    }
+
+    /// the first json web token found on the authorization header of the
+    /// selected request, or on the body of its last response
+    fn find_jwt(&self) -> Option<String> {
+        let request = self.collection_store.borrow().get_selected_request()?;
+        let request = request.read().unwrap();
+        let variables = self.active_variables();
+
+        let from_header = request
+            .headers
+            .iter()
+            .flatten()
+            .filter(|header| header.enabled)
+            .find(|header| header.pair.0.eq_ignore_ascii_case("authorization"))
+            .map(|header| environment::substitute(&header.pair.1, &variables))
+            .and_then(|value| jwt::find_token(&value).map(String::from));
+
+        from_header.or_else(|| {
+            let response = self.responses_map.get(&request.id)?.borrow();
+            jwt::find_token(response.body.as_ref()?).map(String::from)
+        })
+    }
+
+    fn draw_jwt(&self, token: &str, frame: &mut Frame, size: Rect) {
+        let (content, color) = match jwt::decode(token) {
+            Ok(decoded) => (decoded.render(jwt::now()), self.colors.normal.white),
+            Err(e) => (e.to_string(), self.colors.normal.red),
+        };
+
+        let height = (content.lines().count() as u16).add(2).min(size.height);
+        let width = size.width.div(2).max(40).min(size.width);
+        let area = Rect::new(
+            size.x.add(size.width.saturating_sub(width).div(2)),
+            size.y.add(size.height.saturating_sub(height).div(2)),
+            width,
+            height,
+        );
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(content).fg(color).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(Message::Jwt.text())
+                    .border_style(Style::default().fg(self.colors.bright.blue)),
+            ),
+            area,
+        );
+    }
+
+    /// computes a digest over the resolved body of the selected request and
+    /// writes it to a header of the request or a variable of the active
+    /// environment
+    fn apply_digest(&mut self, command: DigestCommand) -> anyhow::Result<()> {
+        let store = self.collection_store.borrow();
+        let (Some(request), Some(collection)) =
+            (store.get_selected_request(), store.get_collection())
+        else {
+            anyhow::bail!("no request is selected");
+        };
+
+        let variables = self.active_variables();
+        let body = request.read().unwrap().body.clone().unwrap_or_default();
+        let mut command = command;
+        if let DigestAlgorithm::HmacSha256(key) = &command.algorithm {
+            command.algorithm =
+                DigestAlgorithm::HmacSha256(environment::substitute(key, &variables));
+        }
+        let value = command.compute(&environment::substitute(&body, &variables));
+
+        let mut collection = collection.borrow_mut();
+        match &command.target {
+            DigestTarget::Header(name) => {
+                collection
+                    .ensure_writable()
+                    .map_err(|e| anyhow::anyhow!("{e}"))?;
+                digest::set_header(&mut request.write().unwrap(), name, value);
+            }
+            DigestTarget::Variable(name) => {
+                let Some(env) = self.config.environment.as_ref() else {
+                    anyhow::bail!("set an active environment to store variables");
+                };
+                variables::import_variables(
+                    &mut collection,
+                    env,
+                    [(name.clone(), value)].into(),
+                    true,
+                )
+                .map_err(|e| anyhow::anyhow!("{e}"))?;
+            }
+        }
+
+        Ok(())
+    }
+
     fn update_selection(&mut self, pane_to_select: Option<PaneFocus>) {
         self.collection_store
             .borrow_mut()
@@ -285,14 +1721,36 @@ impl Renderable for CollectionViewer<'_> {
         frame.render_widget(Clear, size);
         frame.render_widget(Block::default().bg(self.colors.primary.background), size);
 
+        self.drain_captures_channel();
         self.drain_responses_channel();
+        self.drain_monitor_channel();
+        self.drain_device_flow_channel();
+        self.drain_subscription_channel();
+        self.drain_tls_channel();
+        self.drain_body_channel();
 
-        self.sidebar.draw(frame, self.layout.sidebar)?;
+        if !self.layout.sidebar_collapsed {
+            self.sidebar.draw(frame, self.layout.sidebar)?;
+        }
         self.response_viewer
             .draw(frame, self.layout.response_preview)?;
+        // views over the body narrow it down as a whole
+        if self.response_viewer.take_body_wanted() {
+            self.load_full_body(BodyUse::Refresh);
+        }
         self.request_editor.draw(frame, self.layout.req_editor)?;
         self.request_uri.draw(frame, self.layout.req_uri)?;
 
+        let sidebar_focused = self
+            .collection_store
+            .borrow()
+            .get_focused_pane()
+            .eq(&PaneFocus::Sidebar);
+        if self.layout.sidebar_collapsed && sidebar_focused {
+            frame.render_widget(Clear, self.layout.sidebar);
+            self.sidebar.draw(frame, self.layout.sidebar)?;
+        }
+
         let overlay = self.collection_store.borrow().peek_overlay();
         match overlay {
             CollectionViewerOverlay::CreateRequest => {
@@ -313,6 +1771,9 @@ impl Renderable for CollectionViewer<'_> {
             CollectionViewerOverlay::DeleteSidebarItem(_) => {
                 self.sidebar.draw_overlay(frame, overlay)?;
             }
+            CollectionViewerOverlay::BulkRename => {
+                self.sidebar.draw_overlay(frame, overlay)?;
+            }
             CollectionViewerOverlay::HeadersHelp => {
                 self.request_editor.draw_overlay(frame, overlay)?;
             }
@@ -325,9 +1786,32 @@ impl Renderable for CollectionViewer<'_> {
             CollectionViewerOverlay::ChangeAuthMethod => {
                 self.request_editor.draw_overlay(frame, overlay)?;
             }
+            CollectionViewerOverlay::SendOverrides => {
+                self.overrides_form.draw(frame, size)?;
+            }
+            CollectionViewerOverlay::BodyDigest => {
+                self.digest_form.draw(frame, size)?;
+            }
+            CollectionViewerOverlay::PinnedFields => {
+                self.pinned_form.draw(frame, size)?;
+            }
+            CollectionViewerOverlay::ResponseFilter => {
+                self.filter_form.draw(frame, size)?;
+            }
+            CollectionViewerOverlay::UrlBuilder => {
+                self.url_builder_form.draw(frame, size)?;
+            }
+            CollectionViewerOverlay::InspectJwt(ref token) => self.draw_jwt(token, frame, size),
+            CollectionViewerOverlay::DeviceCode => self.draw_device_code(frame, size),
+            CollectionViewerOverlay::SyncConflict => self.draw_sync_conflict(frame, size),
             CollectionViewerOverlay::None => {}
         }
 
+        self.draw_notification(frame, size);
+        if self.config.accessibility.plain {
+            self.draw_status_line(frame);
+        }
+
         if self
             .collection_store
             .borrow()
@@ -351,12 +1835,15 @@ impl Renderable for CollectionViewer<'_> {
                 .get_selected_request()
                 .as_ref()
             {
+                // long uris are cut at the border, so the cursor stays inside it
+                let uri_len = request.read().unwrap().uri.chars().count() as u16;
                 frame.set_cursor(
                     self.layout
                         .req_uri
                         .x
-                        .add(request.read().unwrap().uri.chars().count() as u16)
-                        .add(1),
+                        .add(uri_len)
+                        .add(1)
+                        .min(self.layout.req_uri.right().saturating_sub(2)),
                     self.layout.req_uri.y.add(1),
                 )
             }
@@ -366,9 +1853,11 @@ impl Renderable for CollectionViewer<'_> {
     }
 
     fn handle_tick(&mut self) -> anyhow::Result<()> {
+        self.run_due_monitors();
         if self.collection_sync_timer.elapsed().as_secs().ge(&5) {
             self.sync_collection_changes();
         }
+        self.save_viewer_preferences();
         Ok(())
     }
 
@@ -378,7 +1867,7 @@ impl Renderable for CollectionViewer<'_> {
     }
 
     fn resize(&mut self, new_size: Rect) {
-        let new_layout = build_layout(new_size);
+        let new_layout = build_layout(new_size, self.config.accessibility.plain);
         self.request_editor.resize(new_layout.req_editor);
         self.response_viewer.resize(new_layout.response_preview);
         self.layout = new_layout;
@@ -388,6 +1877,20 @@ impl Renderable for CollectionViewer<'_> {
 impl Eventful for CollectionViewer<'_> {
     type Result = Command;
 
+    /// the body and docs of the selected editor take pasted text as is,
+    /// everything else gets it typed on a single line
+    fn handle_paste(&mut self, text: &str) -> anyhow::Result<Option<Command>> {
+        let store = self.collection_store.borrow();
+        let on_editor =
+            !store.has_overlay() && store.get_selected_pane().eq(&Some(PaneFocus::Editor));
+        drop(store);
+
+        if on_editor && self.request_editor.paste(text) {
+            return Ok(None);
+        }
+        pages::type_pasted(self, text)
+    }
+
     fn handle_key_event(&mut self, key_event: KeyEvent) -> anyhow::Result<Option<Command>> {
         if let (
             None,
@@ -403,6 +1906,128 @@ impl Eventful for CollectionViewer<'_> {
             return Ok(Some(Command::Quit));
         }
 
+        if self
+            .collection_store
+            .borrow()
+            .peek_overlay()
+            .eq(&CollectionViewerOverlay::SendOverrides)
+        {
+            match self.overrides_form.handle_key_event(key_event)? {
+                Some(OverridesFormEvent::Send(overrides)) => {
+                    self.collection_store.borrow_mut().pop_overlay();
+                    self.send_request_with(overrides);
+                }
+                Some(OverridesFormEvent::Cancel) => {
+                    self.collection_store.borrow_mut().pop_overlay();
+                }
+                None => {}
+            }
+            return Ok(None);
+        }
+
+        let overlay = self.collection_store.borrow().peek_overlay();
+        if let CollectionViewerOverlay::InspectJwt(_) = overlay {
+            self.collection_store.borrow_mut().pop_overlay();
+            return Ok(None);
+        }
+
+        if overlay == CollectionViewerOverlay::SyncConflict {
+            self.resolve_sync_conflict(key_event);
+            return Ok(None);
+        }
+
+        if overlay == CollectionViewerOverlay::DeviceCode {
+            let cancel = matches!(key_event.code, KeyCode::Esc | KeyCode::Char('q'))
+                || (key_event.code == KeyCode::Char('c')
+                    && key_event.modifiers.contains(KeyModifiers::CONTROL));
+            if let Some(flow) = cancel.then(|| self.finish_device_flow()).flatten() {
+                flow.task.abort();
+                let cause = Message::SignInCancelled.text();
+                _ = self.request_tx.send(Response::from_error(cause));
+            }
+            return Ok(None);
+        }
+
+        if self
+            .collection_store
+            .borrow()
+            .peek_overlay()
+            .eq(&CollectionViewerOverlay::BodyDigest)
+        {
+            match self.digest_form.handle_key_event(key_event)? {
+                Some(DigestFormEvent::Confirm(command)) => match self.apply_digest(command) {
+                    Ok(()) => {
+                        self.digest_form.reset();
+                        self.collection_store.borrow_mut().pop_overlay();
+                        self.notify(Message::Digest, Message::DigestWritten.text().into(), false);
+                    }
+                    Err(e) => self.digest_form.set_error(e.to_string()),
+                },
+                Some(DigestFormEvent::Cancel) => {
+                    self.collection_store.borrow_mut().pop_overlay();
+                }
+                None => {}
+            }
+            return Ok(None);
+        }
+
+        if self
+            .collection_store
+            .borrow()
+            .peek_overlay()
+            .eq(&CollectionViewerOverlay::PinnedFields)
+        {
+            match self.pinned_form.handle_key_event(key_event)? {
+                Some(PinnedFormEvent::Save(pins)) => {
+                    self.collection_store.borrow_mut().pop_overlay();
+                    self.save_pins(pins);
+                }
+                Some(PinnedFormEvent::Cancel) => {
+                    self.collection_store.borrow_mut().pop_overlay();
+                }
+                None => {}
+            }
+            return Ok(None);
+        }
+
+        if self
+            .collection_store
+            .borrow()
+            .peek_overlay()
+            .eq(&CollectionViewerOverlay::ResponseFilter)
+        {
+            match self.filter_form.handle_key_event(key_event)? {
+                Some(FilterFormEvent::Confirm(filter)) => {
+                    self.collection_store.borrow_mut().pop_overlay();
+                    self.response_viewer.set_filter(filter);
+                }
+                Some(FilterFormEvent::Cancel) => {
+                    self.collection_store.borrow_mut().pop_overlay();
+                }
+                None => {}
+            }
+            return Ok(None);
+        }
+
+        if self
+            .collection_store
+            .borrow()
+            .peek_overlay()
+            .eq(&CollectionViewerOverlay::UrlBuilder)
+        {
+            match self.url_builder_form.handle_key_event(key_event)? {
+                Some(UrlBuilderFormEvent::Save(uri)) => {
+                    self.collection_store.borrow_mut().pop_overlay();
+                    self.save_uri(uri);
+                }
+                Some(UrlBuilderFormEvent::Cancel) => {
+                    self.collection_store.borrow_mut().pop_overlay();
+                }
+                None => {}
+            }
+            return Ok(None);
+        }
+
         if self.collection_store.borrow().get_selected_pane().is_none() {
             match key_event.code {
                 KeyCode::Char('r') => {
@@ -421,6 +2046,71 @@ impl Eventful for CollectionViewer<'_> {
                     self.update_focus(PaneFocus::Editor);
                     self.update_selection(Some(PaneFocus::Editor));
                 }
+                KeyCode::Char('L') => self.toggle_lock(),
+                KeyCode::Char('E') => self.open_external(true),
+                KeyCode::Char('P') => self.open_external(false),
+                KeyCode::Char('D') => self.diff_with_example(),
+                KeyCode::Char('y') => self.copy_to_clipboard(false),
+                KeyCode::Char('Y') => self.copy_to_clipboard(true),
+                KeyCode::Char('J') => match self.find_jwt() {
+                    Some(token) => self
+                        .collection_store
+                        .borrow_mut()
+                        .push_overlay(CollectionViewerOverlay::InspectJwt(token)),
+                    None => self.notify(Message::Jwt, Message::NoTokenFound.text().into(), true),
+                },
+                KeyCode::Char('#') => {
+                    if self
+                        .collection_store
+                        .borrow()
+                        .get_selected_request()
+                        .is_some()
+                    {
+                        self.collection_store
+                            .borrow_mut()
+                            .push_overlay(CollectionViewerOverlay::BodyDigest);
+                    }
+                }
+                KeyCode::Char('O') => {
+                    if self
+                        .collection_store
+                        .borrow()
+                        .get_selected_request()
+                        .is_some()
+                    {
+                        self.collection_store
+                            .borrow_mut()
+                            .push_overlay(CollectionViewerOverlay::SendOverrides);
+                    }
+                }
+                KeyCode::Char('f') => {
+                    let pins = self
+                        .collection_store
+                        .borrow()
+                        .get_selected_request()
+                        .map(|request| request.read().unwrap().pinned.clone());
+                    if let Some(pins) = pins {
+                        self.pinned_form.set_pins(pins);
+                        self.collection_store
+                            .borrow_mut()
+                            .push_overlay(CollectionViewerOverlay::PinnedFields);
+                    }
+                }
+                KeyCode::Char('U') => {
+                    let uri = self
+                        .collection_store
+                        .borrow()
+                        .get_selected_request()
+                        .map(|request| request.read().unwrap().uri.clone());
+                    if let Some(uri) = uri {
+                        self.url_builder_form.set_uri(&uri, self.active_variables());
+                        self.collection_store
+                            .borrow_mut()
+                            .push_overlay(CollectionViewerOverlay::UrlBuilder);
+                    }
+                }
+                KeyCode::Char('X') => self.stop_subscription(),
+                KeyCode::Char('o') => self.toggle_offline(),
                 KeyCode::Tab => self.focus_next(),
                 KeyCode::BackTab => self.focus_prev(),
                 KeyCode::Enter => {
@@ -456,6 +2146,10 @@ impl Eventful for CollectionViewer<'_> {
                         .collection_store
                         .borrow_mut()
                         .push_overlay(CollectionViewerOverlay::DeleteSidebarItem(item_id)),
+                    Some(SidebarEvent::BulkRename) => self
+                        .collection_store
+                        .borrow_mut()
+                        .push_overlay(CollectionViewerOverlay::BulkRename),
                     Some(SidebarEvent::RemoveSelection) => self.update_selection(None),
                     Some(SidebarEvent::SelectNext) => {
                         self.update_selection(None);
@@ -466,6 +2160,22 @@ impl Eventful for CollectionViewer<'_> {
                         self.focus_prev();
                     }
                     Some(SidebarEvent::SyncCollection) => self.sync_collection_changes(),
+                    Some(SidebarEvent::TemplateSaved(name)) => {
+                        self.sync_collection_changes();
+                        self.notify(
+                            Message::TemplateSaved,
+                            Message::TemplateFrom.format(&[&name]),
+                            false,
+                        );
+                    }
+                    Some(SidebarEvent::Undone(Some(change))) => {
+                        self.rebuild_everything();
+                        self.sync_collection_changes();
+                        self.notify(Message::Undone, change, false);
+                    }
+                    Some(SidebarEvent::Undone(None)) => {
+                        self.notify(Message::Undo, Message::NothingToUndo.text().into(), false)
+                    }
                     Some(SidebarEvent::Quit) => return Ok(Some(Command::Quit)),
                     Some(SidebarEvent::RebuildView) => self.rebuild_everything(),
                     // when theres no event we do nothing
@@ -473,14 +2183,7 @@ impl Eventful for CollectionViewer<'_> {
                 },
                 PaneFocus::ReqUri => match self.request_uri.handle_key_event(key_event)? {
                     Some(RequestUriEvent::Quit) => return Ok(Some(Command::Quit)),
-                    Some(RequestUriEvent::SendRequest) => hac_core::net::handle_request(
-                        self.collection_store
-                            .borrow()
-                            .get_selected_request()
-                            .as_ref()
-                            .unwrap(),
-                        self.request_tx.clone(),
-                    ),
+                    Some(RequestUriEvent::SendRequest) => self.send_request(),
                     Some(RequestUriEvent::RemoveSelection) => self.update_selection(None),
                     Some(RequestUriEvent::SelectNext) => {
                         self.update_selection(None);
@@ -496,6 +2199,17 @@ impl Eventful for CollectionViewer<'_> {
                 PaneFocus::Preview => match self.response_viewer.handle_key_event(key_event)? {
                     Some(ResponseViewerEvent::RemoveSelection) => self.update_selection(None),
                     Some(ResponseViewerEvent::Quit) => return Ok(Some(Command::Quit)),
+                    Some(ResponseViewerEvent::Notify(title, message, failed)) => {
+                        self.notify(title, message, failed);
+                    }
+                    Some(ResponseViewerEvent::EditFilter(filter)) => {
+                        self.filter_form.set_filter(filter);
+                        self.collection_store
+                            .borrow_mut()
+                            .push_overlay(CollectionViewerOverlay::ResponseFilter);
+                    }
+                    Some(ResponseViewerEvent::InspectTls) => self.inspect_tls(),
+                    Some(ResponseViewerEvent::LoadBody(body_use)) => self.with_full_body(body_use),
                     // when theres no event we do nothing
                     None => {}
                 },
@@ -512,23 +2226,37 @@ impl Eventful for CollectionViewer<'_> {
     }
 }
 
-pub fn build_layout(size: Rect) -> ExplorerLayout {
+/// on plain mode every pane is laid out on a single column, as that is how
+/// screen readers go through the screen anyway
+pub fn build_layout(size: Rect, plain: bool) -> ExplorerLayout {
     let [top_pane, hint_pane] = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Fill(1), Constraint::Length(1)])
         .areas(size);
 
-    let [sidebar, right_pane] = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Length(30), Constraint::Fill(1)])
-        .areas(top_pane);
+    let sidebar_collapsed = plain || size.width < COLLAPSE_SIDEBAR_WIDTH;
+    let [sidebar, right_pane] = match sidebar_collapsed {
+        true => [
+            Rect::new(
+                top_pane.x,
+                top_pane.y,
+                top_pane.width.min(30),
+                top_pane.height,
+            ),
+            top_pane,
+        ],
+        false => Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(30), Constraint::Fill(1)])
+            .areas(top_pane),
+    };
 
     let [req_uri, req_builder] = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Length(3), Constraint::Fill(1)])
         .areas(right_pane);
 
-    let [req_editor, response_preview] = if size.width < 120 {
+    let [req_editor, response_preview] = if plain || size.width < STACK_PANES_WIDTH {
         Layout::default()
             .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
             .direction(Direction::Vertical)
@@ -550,9 +2278,135 @@ pub fn build_layout(size: Rect) -> ExplorerLayout {
     ExplorerLayout {
         hint_pane,
         sidebar,
+        sidebar_collapsed,
         req_uri,
         req_editor,
         response_preview,
         create_req_form,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyEventKind, KeyEventState};
+    use hac_core::collection::Collection;
+    use ratatui::{backend::TestBackend, Terminal};
+    use std::sync::{Arc, RwLock};
+
+    fn sample_collection() -> Collection {
+        Collection {
+            info: Info {
+                name: "sample collection".into(),
+                description: None,
+                read_only: false,
+            },
+            path: "any_path".into(),
+            unlocked: false,
+            protos: vec![],
+            highlights: vec![],
+            template: None,
+            format_on_save: false,
+            synced: Default::default(),
+            environments: vec![],
+            requests: Some(Arc::new(RwLock::new(vec![RequestKind::Single(Arc::new(
+                RwLock::new(Request {
+                    id: "id".into(),
+                    method: RequestMethod::Post,
+                    name: "a request with a name that doesn't fit anywhere".into(),
+                    uri: format!("https://example.com/{}", "long/".repeat(40)),
+                    body: Some("{\n  \"name\": \"john\"\n}".into()),
+                    body_type: Some(BodyType::Json),
+                    ..Default::default()
+                }),
+            ))]))),
+        }
+    }
+
+    #[test]
+    fn test_build_layout() {
+        let wide = build_layout(Rect::new(0, 0, 160, 40), false);
+        assert!(!wide.sidebar_collapsed);
+        assert_eq!(wide.sidebar, Rect::new(0, 0, 30, 39));
+        assert_eq!(wide.req_editor.y, wide.response_preview.y);
+
+        let narrow = build_layout(Rect::new(0, 0, 80, 24), false);
+        assert!(narrow.sidebar_collapsed);
+        assert_eq!(narrow.sidebar, Rect::new(0, 0, 30, 23));
+        assert_eq!(narrow.req_uri, Rect::new(0, 0, 80, 3));
+        assert_eq!(narrow.req_editor.x, narrow.response_preview.x);
+
+        let plain = build_layout(Rect::new(0, 0, 160, 40), true);
+        assert!(plain.sidebar_collapsed);
+        assert_eq!(plain.req_editor.x, plain.response_preview.x);
+        assert_eq!(plain.hint_pane, Rect::new(0, 39, 160, 1));
+    }
+
+    #[test]
+    fn test_draw_on_small_terminals() {
+        let colors = hac_colors::Colors::default();
+        let config = hac_config::load_config();
+
+        for (width, height) in [(50, 16), (80, 24), (100, 30), (160, 40)] {
+            let size = Rect::new(0, 0, width, height);
+            let mut store = CollectionStore::default();
+            store.set_state(sample_collection());
+            let mut viewer =
+                CollectionViewer::new(size, Rc::new(RefCell::new(store)), &colors, &config, false);
+            let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+
+            // cycle through every pane, selecting each of them, drawing on every step
+            for code in [KeyCode::Tab, KeyCode::Enter, KeyCode::Esc].repeat(4) {
+                viewer
+                    .handle_key_event(KeyEvent {
+                        code,
+                        modifiers: KeyModifiers::NONE,
+                        kind: KeyEventKind::Press,
+                        state: KeyEventState::NONE,
+                    })
+                    .unwrap();
+                viewer.draw(&mut terminal.get_frame(), size).unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_no_sign_in_while_offline() {
+        let colors = hac_colors::Colors::default();
+        let config = hac_config::Config {
+            offline: true,
+            ..hac_config::load_config()
+        };
+        let mut collection = sample_collection();
+        let request = Arc::new(RwLock::new(Request {
+            id: "id".into(),
+            uri: "https://example.com/me".into(),
+            auth_method: Some(AuthMethod::DeviceCode),
+            auth: Some(AuthConfig {
+                device_code: Some(DeviceCodeConfig {
+                    device_authorization_url: "https://example.com/device".into(),
+                    token_url: "https://example.com/token".into(),
+                    client_id: "hac".into(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }));
+        collection.requests = Some(Arc::new(RwLock::new(vec![RequestKind::Single(
+            request.clone(),
+        )])));
+        let mut store = CollectionStore::default();
+        store.set_state(collection);
+        store.dispatch(CollectionStoreAction::SetSelectedRequest(Some(request)));
+        let size = Rect::new(0, 0, 80, 24);
+        let mut viewer =
+            CollectionViewer::new(size, Rc::new(RefCell::new(store)), &colors, &config, false);
+
+        viewer.send_request();
+        assert!(viewer.device_flow.is_none());
+        let (_, message, failed, _) = viewer.notification.unwrap();
+        assert_eq!(message, Message::SignInOffline.text());
+        assert!(failed);
+    }
+}
@@ -0,0 +1,92 @@
+use hac_core::digest::DigestCommand;
+
+use crate::i18n::Message;
+use crate::pages::collection_viewer::input_form::draw_input_form;
+use crate::pages::input::Input;
+use crate::pages::{Eventful, Renderable};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::Rect;
+use ratatui::Frame;
+
+pub enum DigestFormEvent {
+    /// computes the digest over the body of the selected request and writes
+    /// it to the target of the command
+    Confirm(DigestCommand),
+    Cancel,
+}
+
+/// prompts for a digest to compute over the body of the selected request,
+/// like `sha256 header X-Checksum` or `hmac-sha256 {{secret}} var signature`
+#[derive(Debug)]
+pub struct DigestForm<'df> {
+    colors: &'df hac_colors::Colors,
+    input: String,
+    error: Option<String>,
+}
+
+impl<'df> DigestForm<'df> {
+    pub fn new(colors: &'df hac_colors::Colors) -> Self {
+        DigestForm {
+            colors,
+            input: String::default(),
+            error: None,
+        }
+    }
+
+    /// shows why the last command could not be applied, keeping the form open
+    /// so it can be fixed
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    pub fn reset(&mut self) {
+        self.input.clear();
+        self.error = None;
+    }
+}
+
+impl Renderable for DigestForm<'_> {
+    fn draw(&mut self, frame: &mut Frame, _: Rect) -> anyhow::Result<()> {
+        let input = Input::new(self.colors, Message::BodyDigest.text().into())
+            .placeholder("sha256 header X-Checksum".into());
+        draw_input_form(
+            frame,
+            self.colors,
+            None,
+            input,
+            &mut self.input,
+            Message::DigestHint.text(),
+            self.error.as_ref(),
+        );
+
+        Ok(())
+    }
+}
+
+impl Eventful for DigestForm<'_> {
+    type Result = DigestFormEvent;
+
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> anyhow::Result<Option<Self::Result>> {
+        if let (KeyCode::Char('c'), KeyModifiers::CONTROL) = (key_event.code, key_event.modifiers) {
+            self.reset();
+            return Ok(Some(DigestFormEvent::Cancel));
+        }
+
+        match key_event.code {
+            KeyCode::Esc => {
+                self.reset();
+                return Ok(Some(DigestFormEvent::Cancel));
+            }
+            KeyCode::Enter => match DigestCommand::parse(&self.input) {
+                Ok(command) => return Ok(Some(DigestFormEvent::Confirm(command))),
+                Err(e) => self.error = Some(e.to_string()),
+            },
+            KeyCode::Char(c) => self.input.push(c),
+            KeyCode::Backspace => _ = self.input.pop(),
+            _ => {}
+        }
+
+        Ok(None)
+    }
+}
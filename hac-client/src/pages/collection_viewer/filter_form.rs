@@ -0,0 +1,89 @@
+use hac_core::json_path;
+
+use crate::i18n::Message;
+use crate::pages::collection_viewer::input_form::draw_input_form;
+use crate::pages::input::Input;
+use crate::pages::{Eventful, Renderable};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::Rect;
+use ratatui::Frame;
+
+pub enum FilterFormEvent {
+    /// narrows the body of the responses down to the path, or shows all of
+    /// it again when there is none
+    Confirm(Option<String>),
+    Cancel,
+}
+
+/// prompts for a JSONPath the body of the responses of the selected request
+/// is narrowed down to, like `$.data[*].name`
+#[derive(Debug)]
+pub struct FilterForm<'ff> {
+    colors: &'ff hac_colors::Colors,
+    input: String,
+    error: Option<String>,
+}
+
+impl<'ff> FilterForm<'ff> {
+    pub fn new(colors: &'ff hac_colors::Colors) -> Self {
+        FilterForm {
+            colors,
+            input: String::default(),
+            error: None,
+        }
+    }
+
+    /// starts editing the filter the request already has
+    pub fn set_filter(&mut self, filter: Option<String>) {
+        self.input = filter.unwrap_or_default();
+        self.error = None;
+    }
+}
+
+impl Renderable for FilterForm<'_> {
+    fn draw(&mut self, frame: &mut Frame, _: Rect) -> anyhow::Result<()> {
+        let input = Input::new(self.colors, Message::Filter.text().into())
+            .placeholder("$.data[*].name".into());
+        draw_input_form(
+            frame,
+            self.colors,
+            None,
+            input,
+            &mut self.input,
+            Message::FilterHint.text(),
+            self.error.as_ref(),
+        );
+
+        Ok(())
+    }
+}
+
+impl Eventful for FilterForm<'_> {
+    type Result = FilterFormEvent;
+
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> anyhow::Result<Option<Self::Result>> {
+        if let (KeyCode::Char('c'), KeyModifiers::CONTROL) = (key_event.code, key_event.modifiers) {
+            return Ok(Some(FilterFormEvent::Cancel));
+        }
+
+        match key_event.code {
+            KeyCode::Esc => return Ok(Some(FilterFormEvent::Cancel)),
+            KeyCode::Enter if self.input.trim().is_empty() => {
+                return Ok(Some(FilterFormEvent::Confirm(None)));
+            }
+            KeyCode::Enter => match json_path::parse(&self.input) {
+                Ok(_) => {
+                    let filter = self.input.trim().to_string();
+                    return Ok(Some(FilterFormEvent::Confirm(Some(filter))));
+                }
+                Err(e) => self.error = Some(e.to_string()),
+            },
+            KeyCode::Char(c) => self.input.push(c),
+            KeyCode::Backspace => _ = self.input.pop(),
+            _ => {}
+        }
+
+        Ok(None)
+    }
+}
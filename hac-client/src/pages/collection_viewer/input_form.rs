@@ -0,0 +1,102 @@
+use crate::pages::input::Input;
+use crate::pages::overlay::make_overlay;
+
+use std::ops::{Add, Div, Sub};
+
+use ratatui::layout::Rect;
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+/// what was entered so far on a form that takes many entries, listed above
+/// its input
+pub struct InputFormList<'a> {
+    pub title: &'a str,
+    pub entries: &'a [String],
+    /// shown in place of the entries while there are none
+    pub placeholder: &'a str,
+}
+
+/// draws a form centered over the screen: the list of entries, when there
+/// is one, then a focused input holding `value`, a hint and the error of
+/// the last entry below it
+pub fn draw_input_form(
+    frame: &mut Frame,
+    colors: &hac_colors::Colors,
+    list: Option<InputFormList>,
+    mut input: Input,
+    value: &mut String,
+    hint: &str,
+    error: Option<&String>,
+) {
+    make_overlay(colors, colors.normal.black, 0.1, frame);
+
+    let size = frame.size();
+    let size = match list.as_ref() {
+        Some(list) => {
+            let width = size.width.div(2).max(40).min(size.width);
+            let height = (list.entries.len() as u16).add(8).min(size.height);
+            Rect::new(
+                size.width.sub(width).div(2),
+                size.height.sub(height).div(2),
+                width,
+                height,
+            )
+        }
+        None => {
+            let width = size.width.div(2).max(50).min(size.width);
+            Rect::new(
+                size.width.sub(width).div(2),
+                size.height.div(2).saturating_sub(3),
+                width,
+                5,
+            )
+        }
+    };
+
+    let list_size = Rect::new(size.x, size.y, size.width, size.height.sub(5));
+    let input_size = Rect::new(size.x, list_size.bottom(), size.width, 3);
+    let hint_size = Rect::new(size.x, input_size.bottom(), size.width, 1);
+    let error_size = Rect::new(size.x, hint_size.bottom(), size.width, 1);
+
+    frame.render_widget(Clear, size);
+    if let Some(list) = list {
+        let mut lines = list
+            .entries
+            .iter()
+            .map(|entry| Line::from(format!(" {entry}")).fg(colors.normal.yellow))
+            .collect::<Vec<_>>();
+        if lines.is_empty() {
+            lines.push(Line::from(format!(" {}", list.placeholder)).fg(colors.bright.black));
+        }
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(list.title)
+                    .border_style(Style::default().fg(colors.normal.yellow)),
+            ),
+            list_size,
+        );
+    }
+
+    input.focus();
+    frame.render_stateful_widget(input, input_size, value);
+
+    frame.render_widget(
+        Line::from(hint).fg(colors.bright.black).centered(),
+        hint_size,
+    );
+    if let Some(error) = error {
+        frame.render_widget(
+            Line::from(error.as_str()).fg(colors.normal.red).centered(),
+            error_size,
+        );
+    }
+
+    frame.set_cursor(
+        input_size.x.add(value.chars().count() as u16).add(1),
+        input_size.y.add(1),
+    );
+}
@@ -1,9 +1,15 @@
 pub mod collection_store;
 #[allow(clippy::module_inception)]
 pub mod collection_viewer;
+mod digest_form;
+mod filter_form;
+mod input_form;
+mod overrides_form;
+mod pinned_form;
 mod request_editor;
 mod request_uri;
 mod response_viewer;
 mod sidebar;
+mod url_builder_form;
 
 pub use collection_viewer::CollectionViewer;
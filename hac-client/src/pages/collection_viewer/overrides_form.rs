@@ -0,0 +1,109 @@
+use hac_core::collection::overrides::RequestOverrides;
+
+use crate::i18n::Message;
+use crate::pages::collection_viewer::input_form::{draw_input_form, InputFormList};
+use crate::pages::input::Input;
+use crate::pages::{Eventful, Renderable};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::Rect;
+use ratatui::Frame;
+
+pub enum OverridesFormEvent {
+    /// sends the selected request once with the given overrides
+    Send(RequestOverrides),
+    Cancel,
+}
+
+/// lets the user change headers, variables or the body of the selected
+/// request for a single send, without touching the saved request
+#[derive(Debug)]
+pub struct OverridesForm<'of> {
+    colors: &'of hac_colors::Colors,
+    input: String,
+    entries: Vec<String>,
+    overrides: RequestOverrides,
+    error: Option<String>,
+}
+
+impl<'of> OverridesForm<'of> {
+    pub fn new(colors: &'of hac_colors::Colors) -> Self {
+        OverridesForm {
+            colors,
+            input: String::default(),
+            entries: vec![],
+            overrides: RequestOverrides::default(),
+            error: None,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.input.clear();
+        self.entries.clear();
+        self.overrides = RequestOverrides::default();
+        self.error = None;
+    }
+}
+
+impl Renderable for OverridesForm<'_> {
+    fn draw(&mut self, frame: &mut Frame, _: Rect) -> anyhow::Result<()> {
+        let list = InputFormList {
+            title: Message::ModifiedForThisSend.text(),
+            entries: &self.entries,
+            placeholder: Message::OverridesPlaceholder.text(),
+        };
+        let hint = match self.entries.is_empty() {
+            true => Message::OverridesHint.text(),
+            false => Message::OverridesSendHint.text(),
+        };
+        draw_input_form(
+            frame,
+            self.colors,
+            Some(list),
+            Input::new(self.colors, Message::Override.text().into()),
+            &mut self.input,
+            hint,
+            self.error.as_ref(),
+        );
+
+        Ok(())
+    }
+}
+
+impl Eventful for OverridesForm<'_> {
+    type Result = OverridesFormEvent;
+
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> anyhow::Result<Option<Self::Result>> {
+        if let (KeyCode::Char('c'), KeyModifiers::CONTROL) = (key_event.code, key_event.modifiers) {
+            self.reset();
+            return Ok(Some(OverridesFormEvent::Cancel));
+        }
+
+        match key_event.code {
+            KeyCode::Esc => {
+                self.reset();
+                return Ok(Some(OverridesFormEvent::Cancel));
+            }
+            KeyCode::Enter if self.input.trim().is_empty() => {
+                if self.overrides.is_empty() {
+                    return Ok(None);
+                }
+                let overrides = std::mem::take(&mut self.overrides);
+                self.reset();
+                return Ok(Some(OverridesFormEvent::Send(overrides)));
+            }
+            KeyCode::Enter => match self.overrides.push(&self.input) {
+                Ok(()) => {
+                    self.entries.push(std::mem::take(&mut self.input));
+                    self.error = None;
+                }
+                Err(e) => self.error = Some(e.to_string()),
+            },
+            KeyCode::Char(c) => self.input.push(c),
+            KeyCode::Backspace => _ = self.input.pop(),
+            _ => {}
+        }
+
+        Ok(None)
+    }
+}
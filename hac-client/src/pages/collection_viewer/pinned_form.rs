@@ -0,0 +1,96 @@
+use hac_core::json_path;
+
+use crate::i18n::Message;
+use crate::pages::collection_viewer::input_form::{draw_input_form, InputFormList};
+use crate::pages::input::Input;
+use crate::pages::{Eventful, Renderable};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::Rect;
+use ratatui::Frame;
+
+pub enum PinnedFormEvent {
+    /// replaces the pinned paths of the selected request
+    Save(Vec<String>),
+    Cancel,
+}
+
+/// edits the JSONPath expressions pinned to the selected request, whose
+/// values are shown above the body of its responses
+#[derive(Debug)]
+pub struct PinnedForm<'pf> {
+    colors: &'pf hac_colors::Colors,
+    input: String,
+    pins: Vec<String>,
+    error: Option<String>,
+}
+
+impl<'pf> PinnedForm<'pf> {
+    pub fn new(colors: &'pf hac_colors::Colors) -> Self {
+        PinnedForm {
+            colors,
+            input: String::default(),
+            pins: vec![],
+            error: None,
+        }
+    }
+
+    /// starts editing the pins the request already has
+    pub fn set_pins(&mut self, pins: Vec<String>) {
+        self.input.clear();
+        self.pins = pins;
+        self.error = None;
+    }
+}
+
+impl Renderable for PinnedForm<'_> {
+    fn draw(&mut self, frame: &mut Frame, _: Rect) -> anyhow::Result<()> {
+        let list = InputFormList {
+            title: Message::PinnedFields.text(),
+            entries: &self.pins,
+            placeholder: "$.data.id | $.meta.next_cursor | $.items[*].name",
+        };
+        draw_input_form(
+            frame,
+            self.colors,
+            Some(list),
+            Input::new(self.colors, Message::JsonPath.text().into()),
+            &mut self.input,
+            Message::PinnedHint.text(),
+            self.error.as_ref(),
+        );
+
+        Ok(())
+    }
+}
+
+impl Eventful for PinnedForm<'_> {
+    type Result = PinnedFormEvent;
+
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> anyhow::Result<Option<Self::Result>> {
+        if let (KeyCode::Char('c'), KeyModifiers::CONTROL) = (key_event.code, key_event.modifiers) {
+            return Ok(Some(PinnedFormEvent::Cancel));
+        }
+
+        match key_event.code {
+            KeyCode::Esc => return Ok(Some(PinnedFormEvent::Cancel)),
+            KeyCode::Enter if self.input.trim().is_empty() => {
+                return Ok(Some(PinnedFormEvent::Save(std::mem::take(&mut self.pins))));
+            }
+            KeyCode::Enter => match json_path::parse(&self.input) {
+                Ok(_) => {
+                    self.pins.push(self.input.trim().to_string());
+                    self.input.clear();
+                    self.error = None;
+                }
+                Err(e) => self.error = Some(e.to_string()),
+            },
+            KeyCode::Char(c) => self.input.push(c),
+            KeyCode::Backspace if self.input.is_empty() => _ = self.pins.pop(),
+            KeyCode::Backspace => _ = self.input.pop(),
+            _ => {}
+        }
+
+        Ok(None)
+    }
+}
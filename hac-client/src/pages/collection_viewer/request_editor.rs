@@ -1,12 +1,13 @@
 mod auth_editor;
 mod body_editor;
+mod docs_editor;
 mod headers_editor;
 
 use auth_editor::{AuthEditor, AuthEditorEvent};
 use body_editor::{BodyEditor, BodyEditorEvent};
+use docs_editor::{DocsEditor, DocsEditorEvent};
 use hac_config::EditorMode;
 use hac_core::collection::types::{Request, RequestMethod};
-use hac_core::text_object::{TextObject, Write};
 use headers_editor::{HeadersEditor, HeadersEditorEvent};
 
 use crate::pages::collection_viewer::collection_store::CollectionStore;
@@ -45,15 +46,17 @@ pub enum ReqEditorTabs {
     Headers,
     Query,
     Auth,
+    Docs,
 }
 
 impl ReqEditorTabs {
     pub fn prev(&self) -> Self {
         match self {
-            ReqEditorTabs::Body => ReqEditorTabs::Auth,
+            ReqEditorTabs::Body => ReqEditorTabs::Docs,
             ReqEditorTabs::Headers => ReqEditorTabs::Body,
             ReqEditorTabs::Query => ReqEditorTabs::Headers,
             ReqEditorTabs::Auth => ReqEditorTabs::Query,
+            ReqEditorTabs::Docs => ReqEditorTabs::Auth,
         }
     }
 
@@ -62,7 +65,8 @@ impl ReqEditorTabs {
             ReqEditorTabs::Body => ReqEditorTabs::Headers,
             ReqEditorTabs::Headers => ReqEditorTabs::Query,
             ReqEditorTabs::Query => ReqEditorTabs::Auth,
-            ReqEditorTabs::Auth => ReqEditorTabs::Body,
+            ReqEditorTabs::Auth => ReqEditorTabs::Docs,
+            ReqEditorTabs::Docs => ReqEditorTabs::Body,
         }
     }
 }
@@ -80,6 +84,7 @@ impl Display for ReqEditorTabs {
             ReqEditorTabs::Headers => f.write_str("Headers"),
             ReqEditorTabs::Query => f.write_str("Query"),
             ReqEditorTabs::Auth => f.write_str("Auth"),
+            ReqEditorTabs::Docs => f.write_str("Docs"),
         }
     }
 }
@@ -97,6 +102,7 @@ pub struct RequestEditor<'re> {
     body_editor: BodyEditor<'re>,
     headers_editor: HeadersEditor<'re>,
     auth_editor: AuthEditor<'re>,
+    docs_editor: DocsEditor<'re>,
     layout: ReqEditorLayout,
     curr_tab: ReqEditorTabs,
 }
@@ -132,10 +138,12 @@ impl<'re> RequestEditor<'re> {
             ),
             headers_editor: HeadersEditor::new(
                 colors,
+                config,
                 collection_store.clone(),
                 layout.content_pane,
             ),
             auth_editor: AuthEditor::new(colors, collection_store.clone()),
+            docs_editor: DocsEditor::new(colors, collection_store.clone(), layout.content_pane),
             layout,
             curr_tab,
             collection_store,
@@ -143,19 +151,39 @@ impl<'re> RequestEditor<'re> {
     }
 
     pub fn maybe_draw_cursor(&self, frame: &mut Frame) {
-        if self.curr_tab.eq(&ReqEditorTabs::Body) {
-            self.body_editor.draw_cursor(frame);
+        match self.curr_tab {
+            ReqEditorTabs::Body => self.body_editor.draw_cursor(frame),
+            ReqEditorTabs::Docs => self.docs_editor.draw_cursor(frame),
+            _ => {}
         }
     }
 
-    pub fn body(&self) -> &TextObject<Write> {
+    pub fn body(&self) -> String {
         self.body_editor.body()
     }
 
+    /// pastes on the body or the docs, which can hold line breaks, returning
+    /// whether the text was used
+    pub fn paste(&mut self, text: &str) -> bool {
+        match self.curr_tab {
+            ReqEditorTabs::Body => {
+                self.body_editor.paste(text);
+                true
+            }
+            ReqEditorTabs::Docs => self.docs_editor.paste(text),
+            _ => false,
+        }
+    }
+
+    pub fn replace_body(&mut self, content: &str) {
+        self.body_editor.replace_body(content);
+    }
+
     pub fn resize(&mut self, new_size: Rect) {
         self.layout = build_layout(new_size);
         self.headers_editor.resize(self.layout.content_pane);
         self.body_editor.resize(self.layout.content_pane);
+        self.docs_editor.resize(self.layout.content_pane);
     }
 
     fn draw_current_tab(&mut self, frame: &mut Frame, size: Rect) -> anyhow::Result<()> {
@@ -164,18 +192,20 @@ impl<'re> RequestEditor<'re> {
             ReqEditorTabs::Headers => self.headers_editor.draw(frame, size)?,
             ReqEditorTabs::Query => UnderConstruction::new(self.colors).draw(frame, size)?,
             ReqEditorTabs::Auth => self.auth_editor.draw(frame, size)?,
+            ReqEditorTabs::Docs => self.docs_editor.draw(frame, size)?,
         }
 
         Ok(())
     }
 
     fn draw_tabs(&self, frame: &mut Frame, size: Rect) {
-        let tabs = vec!["Body", "Headers", "Query", "Auth"];
+        let tabs = vec!["Body", "Headers", "Query", "Auth", "Docs"];
         let active = match self.curr_tab {
             ReqEditorTabs::Body => 0,
             ReqEditorTabs::Headers => 1,
             ReqEditorTabs::Query => 2,
             ReqEditorTabs::Auth => 3,
+            ReqEditorTabs::Docs => 4,
         };
 
         frame.render_widget(
@@ -225,6 +255,7 @@ impl<'re> RequestEditor<'re> {
             ReqEditorTabs::Headers => self.headers_editor.draw_overlay(frame, overlay),
             ReqEditorTabs::Query => todo!(),
             ReqEditorTabs::Auth => self.auth_editor.draw_overlay(frame, overlay),
+            ReqEditorTabs::Docs => Ok(()),
         }
     }
 }
@@ -251,14 +282,17 @@ impl Eventful for RequestEditor<'_> {
             "sent a key_event to the editor while it was not selected"
         );
 
+        // while typing on the body or the docs, tab belongs to the current
+        // tab instead of switching between tabs
+        let is_typing = match self.curr_tab {
+            ReqEditorTabs::Body => self.body_editor.mode().eq(&EditorMode::Insert),
+            ReqEditorTabs::Docs => self.docs_editor.is_editing(),
+            _ => false,
+        };
+
         if let KeyCode::Tab = key_event.code {
             let store = self.collection_store.borrow_mut();
-            if self.curr_tab.eq(&ReqEditorTabs::Body)
-                && self.body_editor.mode().eq(&EditorMode::Insert)
-            {
-                return Ok(None);
-            }
-            if !store.has_overlay() {
+            if !is_typing && !store.has_overlay() {
                 self.curr_tab = self.curr_tab.next();
             }
             drop(store);
@@ -266,9 +300,7 @@ impl Eventful for RequestEditor<'_> {
 
         if let KeyCode::BackTab = key_event.code {
             let store = self.collection_store.borrow_mut();
-            if self.curr_tab.eq(&ReqEditorTabs::Body)
-                && self.body_editor.mode().eq(&EditorMode::Insert)
-            {
+            if is_typing {
                 return Ok(None);
             }
             if !store.has_overlay() {
@@ -300,6 +332,13 @@ impl Eventful for RequestEditor<'_> {
                 Some(AuthEditorEvent::Quit) => return Ok(Some(RequestEditorEvent::Quit)),
                 None => {}
             },
+            ReqEditorTabs::Docs => match self.docs_editor.handle_key_event(key_event)? {
+                Some(DocsEditorEvent::RemoveSelection) => {
+                    return Ok(Some(RequestEditorEvent::RemoveSelection))
+                }
+                Some(DocsEditorEvent::Quit) => return Ok(Some(RequestEditorEvent::Quit)),
+                None => {}
+            },
         }
 
         Ok(None)
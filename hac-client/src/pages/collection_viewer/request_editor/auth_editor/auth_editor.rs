@@ -1,4 +1,5 @@
 use super::auth_kind_prompt::{AuthKindPrompt, AuthKindPromptEvent};
+use crate::i18n::Message;
 use crate::pages::collection_viewer::collection_store::CollectionStore;
 use crate::pages::collection_viewer::collection_viewer::CollectionViewerOverlay;
 use crate::pages::{Eventful, Renderable};
@@ -8,7 +9,8 @@ use std::ops::{Add, Sub};
 use std::rc::Rc;
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use hac_core::collection::types::AuthMethod;
+use hac_core::collection::types::{AuthMethod, Request};
+use hac_core::jwt::format_countdown;
 use ratatui::layout::Rect;
 use ratatui::style::Stylize;
 use ratatui::widgets::{Block, Borders, Paragraph};
@@ -43,15 +45,15 @@ impl<'ae> AuthEditor<'ae> {
         Rect::new(0, size.height.sub(1), size.width, 1)
     }
 
-    fn draw_hint(&self, frame: &mut Frame, has_auth: bool) {
+    fn draw_hint(&self, frame: &mut Frame, has_auth: bool, signs_in: bool) {
         let hint_size = self.get_hint_size(frame);
-        let hint = if has_auth {
-            match hint_size.width {
-                w if w.le(&100) => "[e: Change method] [Tab: Change focus] [?: Help]",
-                _ => "[e: Change method] [Tab: Change focus] [?: Help]",
-            }
-        } else {
-            "[n: New auth method]"
+        let hint = match has_auth {
+            true => Message::AuthHint.text(),
+            false => Message::NewAuthHint.text(),
+        };
+        let hint = match signs_in {
+            true => format!("{} {hint}", Message::SignInAgain.text()),
+            false => hint.to_string(),
         };
         frame.render_widget(
             Paragraph::new(hint).fg(self.colors.bright.black).centered(),
@@ -69,6 +71,25 @@ impl<'ae> AuthEditor<'ae> {
         }
         Ok(())
     }
+
+    /// describes the token the request signs in with, so stale tokens are
+    /// noticed before they cause a `401`
+    fn token_status(&self, request: &Request) -> Option<String> {
+        let config = request.auth.as_ref()?.device_code.as_ref()?;
+        let tokens = self.collection_store.borrow().get_tokens()?;
+        let tokens = tokens.borrow();
+        let Some(token) = tokens.get(&config.key()) else {
+            return Some(Message::NotSignedIn.text().into());
+        };
+        let status = match token.expires_in(chrono::Utc::now()) {
+            Some(secs) if secs.gt(&0) => {
+                Message::TokenExpiresIn.format(&[&format_countdown(secs as u64)])
+            }
+            Some(secs) => Message::TokenExpired.format(&[&format_countdown(secs.unsigned_abs())]),
+            None => Message::TokenNeverExpires.text().into(),
+        };
+        Some(status)
+    }
 }
 
 impl Renderable for AuthEditor<'_> {
@@ -84,10 +105,11 @@ impl Renderable for AuthEditor<'_> {
             .auth_method
             .as_ref()
             .is_some_and(|method| !matches!(method, AuthMethod::None));
-        self.draw_hint(frame, has_auth);
+        let signs_in = request.auth_method == Some(AuthMethod::DeviceCode);
+        self.draw_hint(frame, has_auth, signs_in);
 
         if !has_auth {
-            let no_request = "No authentication method".fg(self.colors.bright.black);
+            let no_request = Message::NoAuth.text().fg(self.colors.bright.black);
             let no_request = Paragraph::new(no_request).centered().block(
                 Block::default()
                     .fg(self.colors.normal.white)
@@ -99,6 +121,20 @@ impl Renderable for AuthEditor<'_> {
             return Ok(());
         }
 
+        if let Some(status) = signs_in.then(|| self.token_status(&request)).flatten() {
+            let status = Paragraph::new(status.fg(self.colors.normal.white))
+                .centered()
+                .block(
+                    Block::default()
+                        .fg(self.colors.bright.black)
+                        .title(Message::SignIn.text())
+                        .borders(Borders::ALL),
+                );
+
+            let size = Rect::new(size.x.add(5), size.y, size.width.sub(10), 3);
+            frame.render_widget(status, size);
+        }
+
         Ok(())
     }
 }
@@ -135,7 +171,20 @@ impl Eventful for AuthEditor<'_> {
             return Ok(None);
         }
 
-        if let KeyCode::Char('n') = key_event.code {
+        if let (KeyCode::Char('r'), Some(AuthMethod::DeviceCode)) =
+            (key_event.code, request.auth_method.as_ref())
+        {
+            let config = request
+                .auth
+                .as_ref()
+                .and_then(|auth| auth.device_code.as_ref());
+            if let (Some(config), Some(tokens)) = (config, store.get_tokens()) {
+                tokens.borrow_mut().forget(&config.key());
+            }
+            return Ok(None);
+        }
+
+        if let (KeyCode::Char('n'), false) = (key_event.code, store.is_read_only()) {
             return Ok(Some(AuthEditorEvent::ChangeAuthMethod));
         }
 
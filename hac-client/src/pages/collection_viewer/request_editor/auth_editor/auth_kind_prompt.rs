@@ -1,6 +1,7 @@
 use crate::ascii::LOGO_ASCII;
 use crate::components::component_styles::ComponentBorder;
 use crate::components::list_item::{list_item, ListItemKind};
+use crate::i18n::Message;
 use crate::pages::{overlay::make_overlay, Eventful, Renderable};
 
 use crossterm::event::{KeyCode, KeyEvent};
@@ -93,7 +94,7 @@ impl Renderable for AuthKindPrompt<'_> {
             frame.render_widget(item, layout[idx]);
         }
 
-        let header = Span::from("Select an authentication method below")
+        let header = Span::from(Message::SelectAuthMethod.text())
             .into_centered_line()
             .fg(self.colors.bright.black);
 
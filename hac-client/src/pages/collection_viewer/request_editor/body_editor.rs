@@ -1,28 +1,117 @@
 use hac_config::{Action, EditorMode, KeyAction};
+use hac_core::abbreviation::is_word_char;
+use hac_core::collection::environment::VariablePreview;
+use hac_core::collection::local;
+use hac_core::collection::types::BodyType;
+use hac_core::completion::{Completion, CompletionKind};
+use hac_core::paste;
+use hac_core::snippet::{SnippetRegistry, TabStop};
 use hac_core::syntax::highlighter::HIGHLIGHTER;
-use hac_core::text_object::{cursor::Cursor, TextObject, Write};
-
+use hac_core::text_object::hex_buffer::BYTES_PER_ROW;
+use hac_core::text_object::line_numbers;
+use hac_core::text_object::{
+    cursor::Cursor, EditRecorder, HexBuffer, HexWrite, Macros, Marks, Registers, SearchDirection,
+    SearchMatch, StringEdit, Substitute, SubstituteMatch, TextObject, TextScope, Viewport, Write,
+};
+use hac_core::timestamp;
+use hac_core::transform::Transform;
+
+use crate::components::variable_preview::draw_variable_preview;
+use crate::i18n::Message;
 use crate::pages::{collection_viewer::collection_store::CollectionStore, Eventful, Renderable};
-use crate::utils::build_syntax_highlighted_lines;
+use crate::utils::{build_syntax_highlighted_lines, show_whitespace};
 
 use std::cell::RefCell;
+use std::collections::{BTreeMap, VecDeque};
 use std::ops::{Add, Div, Mul, Sub};
 use std::rc::Rc;
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
-use ratatui::style::Stylize;
+use ratatui::style::{Style, Stylize};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::Paragraph;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 use ratatui::Frame;
 use tree_sitter::Tree;
 
+/// how many snapshots of the body are kept to be undone
+const MAX_UNDO: usize = 100;
+/// how many suggestions are shown while typing
+const MAX_COMPLETIONS: usize = 5;
+
 pub enum BodyEditorEvent {
     RemoveSelection,
     Quit,
 }
 
+/// an expanded snippet that still has tab stops to be visited, offsets are
+/// absolute char indexes on the body and are shifted as the body is edited
+#[derive(Debug)]
+struct SnippetSession {
+    tab_stops: VecDeque<TabStop>,
+    /// the tab stop the cursor is currently at, when it has a placeholder the
+    /// placeholder is replaced by the first char typed
+    current: Option<TabStop>,
+}
+
+impl SnippetSession {
+    fn shift(&mut self, edit_at: usize, delta: isize) {
+        self.tab_stops
+            .iter_mut()
+            .chain(self.current.iter_mut())
+            .filter(|stop| stop.offset.ge(&edit_at))
+            .for_each(|stop| stop.offset = stop.offset.saturating_add_signed(delta));
+    }
+}
+
+/// a string of the body opened on the editor without its escapes, the body
+/// gets the string escaped back when it is closed
+#[derive(Debug)]
+struct StringSession {
+    edit: StringEdit,
+    /// undo history of the body, the string has one of its own while open
+    undo_stack: Vec<(String, Cursor)>,
+    /// marks of the body, as positions on the string mean something else
+    marks: Marks,
+}
+
+/// a search being typed on the statusline, the cursor jumps to the first
+/// match as the pattern is typed
+#[derive(Debug)]
+struct SearchPrompt {
+    pattern: String,
+    direction: SearchDirection,
+    /// where the cursor was when the search started, it goes back there
+    /// when the search is cancelled or nothing matches
+    origin: Cursor,
+}
+
+/// a substitution of the command line waiting for each of its matches to be
+/// confirmed, accepted matches are replaced at once when it ends
+#[derive(Debug)]
+struct SubstituteConfirm {
+    matches: Vec<SubstituteMatch>,
+    current: usize,
+    accepted: Vec<SubstituteMatch>,
+}
+
+/// suggestions for what is being typed before the cursor, shown on insert
+/// mode until one is accepted or the popup is cancelled
+#[derive(Debug)]
+struct CompletionPopup {
+    completion: Completion,
+    selected: usize,
+}
+
+/// what the editor was fed while a macro was recorded, fed again as the
+/// macro is played
+#[derive(Debug, Clone)]
+enum MacroInput {
+    Key(KeyEvent),
+    Paste(String),
+}
+
 #[derive(Debug)]
 pub struct BodyEditor<'be> {
     body: TextObject<Write>,
@@ -30,8 +119,8 @@ pub struct BodyEditor<'be> {
     cursor: Cursor,
     styled_display: Vec<Line<'static>>,
     editor_mode: EditorMode,
-    row_scroll: usize,
-    col_scroll: usize,
+    /// the rows and columns of the body in view
+    viewport: Viewport,
     colors: &'be hac_colors::Colors,
     config: &'be hac_config::Config,
 
@@ -43,7 +132,53 @@ pub struct BodyEditor<'be> {
     /// Only KeyAction::Complex are stored here as any other kind of key action can be acted upon
     /// instantly
     keymap_buffer: Option<KeyAction>,
-    _collection_store: Rc<RefCell<CollectionStore>>,
+    collection_store: Rc<RefCell<CollectionStore>>,
+    snippets: SnippetRegistry,
+    snippet_session: Option<SnippetSession>,
+    /// snapshots of the body and cursor taken before each edit, typing on
+    /// insert mode is grouped with the edit that entered insert mode
+    undo_stack: Vec<(String, Cursor)>,
+    /// text deleted from the body, to be pasted back
+    registers: Registers,
+    /// the bytes of binary bodies, edited on the hex editor. The body holds
+    /// them as base64 while it is open
+    hex: Option<HexBuffer>,
+    /// whether bytes typed on the hex editor replace the ones under the
+    /// cursor or are inserted
+    hex_write: HexWrite,
+    /// whether the hex editor types on the ASCII gutter instead of the bytes
+    hex_ascii: bool,
+    /// the string being edited unescaped, the editor holds its text instead
+    /// of the body while it is open
+    string_session: Option<StringSession>,
+    /// the search being typed, if any
+    search_prompt: Option<SearchPrompt>,
+    /// the last search, repeated by `n` and `N`
+    last_search: Option<(String, SearchDirection)>,
+    /// matches of the search on the body, highlighted until another search
+    search_matches: Vec<SearchMatch>,
+    /// the command being typed after `:`, if any
+    command_line: Option<String>,
+    substitute_confirm: Option<SubstituteConfirm>,
+    /// the outcome of the last command, shown on the statusline until the
+    /// next key
+    command_message: Option<String>,
+    /// local variables of the workspace, read once as the preview of the
+    /// variable under the cursor is resolved on every edit
+    local_variables: BTreeMap<String, String>,
+    /// what the variable under the cursor resolves to
+    variable_preview: Option<VariablePreview>,
+    /// the last edit made on normal mode, repeated by `.`
+    recorder: EditRecorder,
+    macros: Macros<MacroInput>,
+    /// the action waiting for the name of its register or mark, typed next
+    name_prompt: Option<Action>,
+    /// whether a macro is being played, macros played by it are skipped as
+    /// one playing itself would never end
+    playing_macro: bool,
+    /// named marks and the jumplist, following the text they point at
+    marks: Marks,
+    completion: Option<CompletionPopup>,
 }
 
 impl<'be> BodyEditor<'be> {
@@ -55,62 +190,348 @@ impl<'be> BodyEditor<'be> {
     ) -> Self {
         let (body, tree) = make_body(&collection_store);
         let content = body.to_string();
+        let is_binary = collection_store
+            .borrow()
+            .get_selected_request()
+            .is_some_and(|request| {
+                request
+                    .read()
+                    .unwrap()
+                    .body_type
+                    .eq(&Some(BodyType::Binary))
+            });
+        let hex = is_binary.then(|| HexBuffer::from_base64(&content));
         let styled_display = build_syntax_highlighted_lines(&content, tree.as_ref(), colors);
 
-        Self {
+        let mut editor = Self {
             body,
             tree,
-            _collection_store: collection_store,
+            collection_store,
             styled_display,
             cursor: Cursor::default(),
             editor_mode: EditorMode::Normal,
-            row_scroll: 0,
-            col_scroll: 0,
+            viewport: Viewport::new(
+                size.height.saturating_sub(1).into(),
+                size.width.into(),
+                config.scrolloff,
+            ),
             size,
             colors,
             config,
             keymap_buffer: None,
-        }
+            snippets: SnippetRegistry::new(&config.snippets),
+            snippet_session: None,
+            undo_stack: vec![],
+            registers: Registers::default(),
+            hex,
+            hex_write: HexWrite::default(),
+            hex_ascii: false,
+            string_session: None,
+            search_prompt: None,
+            last_search: None,
+            search_matches: vec![],
+            command_line: None,
+            substitute_confirm: None,
+            command_message: None,
+            local_variables: local::workspace_variables(config.environment.as_deref()),
+            variable_preview: None,
+            recorder: EditRecorder::default(),
+            macros: Macros::default(),
+            name_prompt: None,
+            playing_macro: false,
+            marks: Marks::default(),
+            completion: None,
+        };
+        editor.update_variable_preview();
+        editor
     }
 
     pub fn mode(&self) -> &EditorMode {
         &self.editor_mode
     }
 
-    pub fn body(&self) -> &TextObject<Write> {
-        &self.body
+    /// the body of the request, with the string being edited escaped back
+    /// into it
+    pub fn body(&self) -> String {
+        match self.string_session.as_ref() {
+            Some(session) => session.edit.apply(&self.body.to_string()),
+            None => self.body.to_string(),
+        }
     }
 
     pub fn draw_cursor(&self, frame: &mut Frame) {
-        // the editor status bar occupies 1 row, so we have to subtract it to prevent the
-        // cursor from going out of the intended spacing, we also subtract the bottom border.
-        let mut editor_position = self.size;
-        let statusbar_size = 1;
-        let border_size = 1;
-        editor_position.height = editor_position.height.sub(statusbar_size).sub(border_size);
-
-        let row_with_offset = u16::min(
-            editor_position
-                .y
-                .add(self.cursor.row_with_offset() as u16)
-                .saturating_sub(self.row_scroll as u16),
-            editor_position.y.add(editor_position.height),
-        );
-        let col_with_offset = u16::min(
-            editor_position
-                .x
-                .add(self.cursor.col_with_offset() as u16)
-                .saturating_sub(self.col_scroll as u16),
-            editor_position.x.add(editor_position.width),
+        if let Some(hex) = self.hex.as_ref() {
+            self.draw_hex_cursor(hex, frame);
+            return;
+        }
+
+        // while searching the cursor is after the pattern on the statusline
+        let prompt = self
+            .search_prompt
+            .as_ref()
+            .map(|prompt| &prompt.pattern)
+            .or(self.command_line.as_ref());
+        if let Some(prompt) = prompt {
+            let col = prompt.chars().count().add(1) as u16;
+            frame.set_cursor(
+                self.size.x.add(col).min(self.size.right()),
+                self.size.bottom().saturating_sub(1),
+            );
+            return;
+        }
+
+        if let Some((col, row)) = self.cursor_on_screen(self.size) {
+            frame.set_cursor(col, row);
+        }
+    }
+
+    /// where the cursor is on the screen, past the line numbers of the given
+    /// area, `None` while it is scrolled out of view
+    fn cursor_on_screen(&self, area: Rect) -> Option<(u16, u16)> {
+        let (col, row) = self
+            .viewport
+            .screen_position(self.cursor.row(), self.cursor.col())?;
+        Some((
+            area.x.add(self.gutter_width()).add(col as u16),
+            area.y.add(row as u16),
+        ))
+    }
+
+    /// resolves the variable under the cursor, only while on normal mode as
+    /// the preview would get in the way of typing. It is kept until the body
+    /// or the cursor change, instead of being resolved on every frame
+    fn update_variable_preview(&mut self) {
+        self.variable_preview = match self.editor_mode {
+            EditorMode::Normal => self.collection_store.borrow().preview_variable(
+                self.config.environment.as_deref(),
+                &self.local_variables,
+                &self.body.to_string(),
+                self.body.char_idx(&self.cursor),
+            ),
+            EditorMode::Insert => None,
+        };
+    }
+
+    fn draw_variable_preview(&self, frame: &mut Frame, size: Rect) {
+        let Some(preview) = self.variable_preview.as_ref() else {
+            return;
+        };
+        let Some(cursor) = self.cursor_on_screen(size) else {
+            return;
+        };
+
+        draw_variable_preview(frame, preview, cursor, size, self.colors);
+    }
+
+    fn draw_completion(&self, frame: &mut Frame, size: Rect) {
+        let Some(popup) = self.completion.as_ref() else {
+            return;
+        };
+        let Some((cursor_col, cursor_row)) = self.cursor_on_screen(size) else {
+            return;
+        };
+
+        let title = Message::Completions.text();
+        let lines = popup
+            .completion
+            .candidates
+            .iter()
+            .enumerate()
+            .map(|(idx, candidate)| {
+                let label = match candidate.kind {
+                    CompletionKind::Variable => format!("{{{{{}}}}}", candidate.label),
+                    _ => candidate.label.clone(),
+                };
+                match idx.eq(&popup.selected) {
+                    true => {
+                        Line::from(label.fg(self.colors.normal.black)).bg(self.colors.normal.blue)
+                    }
+                    false => Line::from(label.fg(self.colors.normal.white)),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let width = lines
+            .iter()
+            .map(Line::width)
+            .chain(std::iter::once(title.chars().count()))
+            .max()
+            .unwrap_or_default()
+            .add(2)
+            .min(size.width.into()) as u16;
+        let height = (lines.len() as u16).add(2);
+
+        // below the cursor when it fits, above it otherwise
+        let y = match cursor_row.add(1).add(height).le(&size.bottom()) {
+            true => cursor_row.add(1),
+            false => cursor_row.saturating_sub(height).max(size.y),
+        };
+        let x = cursor_col.min(size.right().saturating_sub(width));
+        let area = Rect::new(x, y, width, height.min(size.height));
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(title)
+                    .border_style(Style::default().fg(self.colors.bright.black)),
+            ),
+            area,
         );
-        frame.set_cursor(col_with_offset, row_with_offset);
+    }
+
+    /// suggests variables after an unclosed `{{`, and json keys inside of
+    /// strings, for what was typed before the cursor on insert mode
+    fn update_completion(&mut self) {
+        self.completion = None;
+        if !self.editor_mode.eq(&EditorMode::Insert) || self.hex.is_some() {
+            return;
+        }
+
+        let Some(engine) = self.collection_store.borrow().get_completions() else {
+            return;
+        };
+        let before_cursor = self.body.text_before_cursor(&self.cursor);
+        let Some(mut completion) = engine.borrow().complete_body(&before_cursor) else {
+            return;
+        };
+
+        // what was typed already is not worth suggesting
+        completion
+            .candidates
+            .retain(|candidate| candidate.label.ne(&completion.prefix));
+        completion.candidates.truncate(MAX_COMPLETIONS);
+        if !completion.candidates.is_empty() {
+            self.completion = Some(CompletionPopup {
+                completion,
+                selected: 0,
+            });
+        }
+    }
+
+    /// picks, accepts or cancels a suggestion while they are shown, returning
+    /// whether the key was one of those
+    fn handle_completion_key(&mut self, key_event: KeyEvent) -> bool {
+        let Some(popup) = self.completion.as_mut() else {
+            return false;
+        };
+
+        let total = popup.completion.candidates.len();
+        match (key_event.code, key_event.modifiers) {
+            (KeyCode::Down, _) | (KeyCode::Char('n'), KeyModifiers::CONTROL) => {
+                popup.selected = popup.selected.add(1) % total
+            }
+            (KeyCode::Up, _) | (KeyCode::Char('p'), KeyModifiers::CONTROL) => {
+                popup.selected = popup.selected.add(total).sub(1) % total
+            }
+            (KeyCode::Char('y'), KeyModifiers::CONTROL) => self.accept_completion(),
+            (KeyCode::Char('e'), KeyModifiers::CONTROL) => self.completion = None,
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// replaces what was typed of the selected suggestion by it, closing the
+    /// variable when it wasn't closed yet
+    fn accept_completion(&mut self) {
+        let Some(popup) = self.completion.take() else {
+            return;
+        };
+        if self.collection_store.borrow().is_read_only() {
+            return;
+        }
+
+        let candidate = &popup.completion.candidates[popup.selected];
+        let mut text = candidate.label.clone();
+        let cursor_idx = self.body.char_idx(&self.cursor);
+        let after_cursor = self
+            .body
+            .to_string()
+            .chars()
+            .skip(cursor_idx)
+            .take_while(|c| c.ne(&'\n'))
+            .collect::<String>();
+        if candidate.kind.eq(&CompletionKind::Variable) && !after_cursor.starts_with("}}") {
+            text.push_str("}}");
+        }
+
+        let typed = self.cursor.col().saturating_sub(popup.completion.start);
+        let start = cursor_idx.sub(typed);
+        let len_before = self.body.len_chars();
+        self.body.remove_range(start, cursor_idx);
+        self.cursor.move_left(typed);
+        self.body.insert_str(&text, &self.cursor);
+        self.cursor.move_right(text.chars().count());
+        if let Some(session) = self.snippet_session.as_mut() {
+            let delta = self.body.len_chars() as isize - len_before as isize;
+            session.shift(start, delta);
+        }
+        self.maybe_scroll_view();
     }
 
     fn draw_statusline(&self, frame: &mut Frame, size: Rect) {
+        let command_line = match (
+            self.command_line.as_ref(),
+            self.substitute_confirm.as_ref(),
+            self.command_message.as_ref(),
+        ) {
+            (Some(command), _, _) => Some(Line::from(vec![
+                ":".fg(self.colors.bright.blue),
+                command.clone().fg(self.colors.normal.white),
+            ])),
+            (_, Some(confirm), _) => confirm.matches.get(confirm.current).map(|found| {
+                Line::from(
+                    Message::ReplaceMatch
+                        .format(&[&found.replacement])
+                        .fg(self.colors.normal.yellow),
+                )
+            }),
+            (_, _, Some(message)) => Some(Line::from(message.clone().fg(self.colors.normal.white))),
+            _ => None,
+        };
+        if let Some(line) = command_line {
+            frame.render_widget(Paragraph::new(line), size);
+            return;
+        }
+
+        if let Some(prompt) = self.search_prompt.as_ref() {
+            let symbol = match prompt.direction {
+                SearchDirection::Forward => "/",
+                SearchDirection::Backward => "?",
+            };
+            let line = Line::from(vec![
+                symbol.fg(self.colors.bright.blue),
+                prompt.pattern.clone().fg(self.colors.normal.white),
+            ]);
+            frame.render_widget(Paragraph::new(line), size);
+            return;
+        }
+
         let cursor_pos = self.cursor.readable_position();
 
-        let mut mode = Span::from(format!(" {} ", self.editor_mode));
-        let mut cursor = Span::from(format!(" {}:{} ", cursor_pos.1, cursor_pos.0));
+        let (mut mode, mut cursor) = match self.hex.as_ref() {
+            Some(hex) => {
+                let mode = match (&self.editor_mode, self.hex_write) {
+                    (EditorMode::Normal, _) => "HEX".to_string(),
+                    (_, HexWrite::Insert) => format!("HEX {}", self.editor_mode),
+                    (_, HexWrite::Overwrite) => "HEX REPLACE".to_string(),
+                };
+                (
+                    Span::from(format!(" {mode} ")),
+                    Span::from(format!(" {:#x}/{:#x} ", hex.cursor(), hex.bytes().len())),
+                )
+            }
+            None if self.string_session.is_some() => (
+                Span::from(format!(" STRING {} ", self.editor_mode)),
+                Span::from(format!(" {}:{} ", cursor_pos.1, cursor_pos.0)),
+            ),
+            None => (
+                Span::from(format!(" {} ", self.editor_mode)),
+                Span::from(format!(" {}:{} ", cursor_pos.1, cursor_pos.0)),
+            ),
+        };
 
         let mut percentage = Span::from(format!(
             " {}% ",
@@ -119,9 +540,18 @@ impl<'be> BodyEditor<'be> {
                 .mul(100.0) as usize
         ));
 
+        let recording = Span::from(
+            self.macros
+                .recording()
+                .map(|name| format!(" {} ", Message::RecordingMacro.format(&[&name.to_string()])))
+                .unwrap_or_default(),
+        )
+        .fg(self.colors.normal.yellow);
+
         let content_len = mode
             .content
             .len()
+            .add(recording.content.len())
             .add(cursor.content.len())
             .add(percentage.content.len());
 
@@ -153,17 +583,80 @@ impl<'be> BodyEditor<'be> {
         };
 
         frame.render_widget(
-            Paragraph::new(Line::from(vec![mode, padding, percentage, cursor])),
+            Paragraph::new(Line::from(vec![
+                mode, recording, padding, percentage, cursor,
+            ])),
             size,
         )
     }
 
     fn handle_action(&mut self, action: &Action) {
+        if action.modifies_buffer() && self.collection_store.borrow().is_read_only() {
+            return;
+        }
+
+        // macros feed keys back to the editor, so they work on the hex
+        // editor and opened strings as well
+        match action {
+            Action::RecordMacro if self.macros.recording().is_some() => {
+                self.macros.stop();
+                return;
+            }
+            Action::RecordMacro | Action::PlayMacro => {
+                self.name_prompt = Some(action.clone());
+                return;
+            }
+            _ => {}
+        }
+
+        // the body holds base64 while the hex editor is open, so editing it
+        // as text would break it
+        if self.hex.is_some() {
+            match action {
+                Action::ToggleHexMode => self.toggle_hex_mode(),
+                Action::Undo => {
+                    self.undo();
+                    self.hex = Some(HexBuffer::from_base64(&self.body.to_string()));
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        // tab is what drives snippets, so it is the only action that shouldn't
+        // shift the tab stops of an active snippet
+        if action.eq(&Action::InsertTab) {
+            self.insert_tab();
+            return;
+        }
+
+        if action.eq(&Action::EditString) {
+            self.toggle_string_edit();
+            return;
+        }
+
+        if self.should_snapshot(action) {
+            self.snapshot();
+        }
+
+        let edit_at = self.body.char_idx(&self.cursor);
+        let len_before = self.body.len_chars();
+
+        if is_jump(action) {
+            self.push_jump(&self.cursor.clone());
+        }
+
+        match action {
+            Action::InsertChar(c) if !is_word_char(*c) => self.maybe_expand_abbreviation(),
+            Action::InsertLine => self.maybe_expand_abbreviation(),
+            _ => {}
+        }
+
         match action {
             Action::InsertChar(c) => self.insert_char(*c),
             Action::DeletePreviousChar => self.erase_previous_char(),
             Action::InsertLine => self.insert_newline(),
-            Action::InsertTab => self.insert_tab(),
+            Action::InsertTab => unreachable!("tab is handled before any other action"),
             Action::EnterMode(EditorMode::Normal) => self.enter_normal_mode(),
             Action::EnterMode(EditorMode::Insert) => self.enter_insert_mode(),
             Action::MoveToLineStart => self.move_to_line_start(),
@@ -195,45 +688,698 @@ impl<'be> BodyEditor<'be> {
             Action::JumpToClosing => self.jump_to_opposing_token(),
             Action::JumpToEmptyLineBelow => self.jump_to_empty_line_below(),
             Action::JumpToEmptyLineAbove => self.jump_to_empty_line_above(),
-            Action::Undo => {}
-            Action::FindNext => {}
-            Action::FindPrevious => {}
-            Action::PasteBelow => {}
+            Action::NextVariable => self.jump_to_next_variable(),
+            Action::PreviousVariable => self.jump_to_previous_variable(),
+            Action::Base64Encode => self.transform_line(Transform::Base64Encode),
+            Action::Base64Decode => self.transform_line(Transform::Base64Decode),
+            Action::UrlEncode => self.transform_line(Transform::UrlEncode),
+            Action::UrlDecode => self.transform_line(Transform::UrlDecode),
+            Action::JsonEscape => self.transform_line(Transform::JsonEscape),
+            Action::JsonUnescape => self.transform_line(Transform::JsonUnescape),
+            Action::ConvertTimestamp => self.convert_timestamp(),
+            Action::GenerateFromSchema => self.generate_from_schema(),
+            Action::Undo => self.undo(),
+            Action::FindNext => self.repeat_search(false),
+            Action::FindPrevious => self.repeat_search(true),
+            Action::SearchForward => self.start_search(SearchDirection::Forward),
+            Action::SearchBackward => self.start_search(SearchDirection::Backward),
+            Action::CommandLine => self.command_line = Some(String::default()),
+            Action::PasteBelow => self.paste_after(),
+            Action::ToggleHexMode => self.toggle_hex_mode(),
+            Action::EditString => unreachable!("strings are opened before any other action"),
+            Action::RepeatEdit => self.repeat_last_edit(),
+            Action::RecordMacro | Action::PlayMacro => {
+                unreachable!("macros are handled before any other action")
+            }
+            Action::SetMark | Action::JumpToMark => self.name_prompt = Some(action.clone()),
+            Action::JumpBack => self.jump_back(),
+            Action::JumpForward => self.jump_forward(),
+            Action::ScrollCursorCenter => self.viewport.center(self.cursor.row()),
+            Action::ScrollCursorTop => self.viewport.scroll_to_top(self.cursor.row()),
+            Action::ScrollCursorBottom => self.viewport.scroll_to_bottom(self.cursor.row()),
+        }
+
+        if let Some(session) = self.snippet_session.as_mut() {
+            let delta = self.body.len_chars() as isize - len_before as isize;
+            session.shift(edit_at, delta);
         }
     }
 
-    fn maybe_scroll_view(&mut self) {
-        self.cursor
-            .row()
-            .saturating_sub(self.row_scroll)
-            .gt(&self.size.height.sub(2).into())
-            .then(|| self.row_scroll = self.cursor.row().sub(self.size.height.sub(2) as usize));
+    /// runs the actions of a key press, recording them as the last edit
+    /// when they change the body
+    fn run_actions(&mut self, actions: &[Action]) {
+        let mode_before = self.editor_mode.clone();
+        actions.iter().for_each(|action| self.handle_action(action));
+        // edits of the hex editor aren't done to the text of the body
+        if self.hex.is_none() {
+            self.recorder
+                .record(actions, &mode_before, &self.editor_mode);
+        }
+    }
+
+    /// runs the last edit again at the cursor, undone as a single edit
+    fn repeat_last_edit(&mut self) {
+        let actions = self.recorder.last_edit().to_vec();
+        actions.iter().for_each(|action| self.handle_action(action));
+        if self.editor_mode.eq(&EditorMode::Insert) {
+            self.enter_normal_mode();
+        }
+    }
+
+    /// completes `q`, `@`, `m` or `` ` `` with the name of the register or
+    /// mark typed after it, any key that isn't a char cancels it
+    fn handle_name_key(&mut self, key_event: KeyEvent) {
+        let (Some(action), KeyCode::Char(name)) = (self.name_prompt.take(), key_event.code) else {
+            return;
+        };
+        match action {
+            Action::RecordMacro => _ = self.macros.start(name),
+            Action::PlayMacro => self.play_macro(name),
+            Action::SetMark => _ = self.marks.set(name, self.body.char_idx(&self.cursor)),
+            _ => self.jump_to_mark(name),
+        }
+    }
+
+    /// moves the marks to where their text is after the last edits
+    fn anchor_marks(&mut self) {
+        self.marks.anchor(&self.body.to_string());
+    }
+
+    /// remembers where a jump starts, to come back to it with `ctrl-o`
+    fn push_jump(&mut self, from: &Cursor) {
+        self.anchor_marks();
+        self.marks.push_jump(self.body.char_idx(from));
+    }
+
+    fn jump_to_mark(&mut self, name: char) {
+        self.anchor_marks();
+        let Some(position) = self.marks.get(name) else {
+            return;
+        };
+        self.push_jump(&self.cursor.clone());
+        self.move_to_position(position);
+    }
+
+    fn jump_back(&mut self) {
+        self.anchor_marks();
+        let current = self.body.char_idx(&self.cursor);
+        if let Some(position) = self.marks.jump_back(current) {
+            self.move_to_position(position);
+        }
+    }
+
+    fn jump_forward(&mut self) {
+        self.anchor_marks();
+        if let Some(position) = self.marks.jump_forward() {
+            self.move_to_position(position);
+        }
+    }
+
+    /// moves the cursor to a char index of the body
+    fn move_to_position(&mut self, position: usize) {
+        let (col, row) = self.body.position_of(position);
+        self.cursor.move_to_row(row);
+        self.cursor.move_to_col(col);
+        self.cursor.maybe_snap_to_col(self.body.line_len(row));
+        self.maybe_scroll_view();
+    }
+
+    /// feeds the keys and pastes recorded on the register to the editor, as
+    /// if they were typed again
+    fn play_macro(&mut self, name: char) {
+        if self.playing_macro {
+            return;
+        }
+        let Some(inputs) = self.macros.play(name) else {
+            return;
+        };
+
+        self.playing_macro = true;
+        for input in inputs {
+            match input {
+                MacroInput::Key(key_event) => _ = self.handle_key(key_event),
+                MacroInput::Paste(text) => self.insert_pasted(&text),
+            }
+        }
+        self.playing_macro = false;
+    }
+
+    fn should_snapshot(&self, action: &Action) -> bool {
+        let is_typing = self.editor_mode.eq(&EditorMode::Insert)
+            && matches!(
+                action,
+                Action::InsertChar(_)
+                    | Action::InsertLine
+                    | Action::DeletePreviousChar
+                    | Action::DeleteBack
+            );
+        action.modifies_buffer() && !is_typing && action.ne(&Action::Undo)
+    }
 
+    fn snapshot(&mut self) {
+        let content = self.body.to_string();
+        if self
+            .undo_stack
+            .last()
+            .is_some_and(|(last, _)| last.eq(&content))
+        {
+            return;
+        }
+
+        self.undo_stack.push((content, self.cursor.clone()));
+        if self.undo_stack.len().gt(&MAX_UNDO) {
+            self.undo_stack.remove(0);
+        }
+    }
+
+    /// replaces the whole body, like when it was edited outside of hac, the
+    /// previous body can still be restored with undo
+    pub fn replace_body(&mut self, content: &str) {
+        if let Some(session) = self.string_session.take() {
+            let body = session.edit.apply(&self.body.to_string());
+            self.body = TextObject::from(&body).with_write();
+            self.undo_stack = session.undo_stack;
+            self.marks = session.marks;
+        }
+        self.anchor_marks();
+        self.snapshot();
+        if self.hex.is_some() {
+            self.hex = Some(HexBuffer::from_base64(content));
+        }
+        self.body = TextObject::from(content).with_write();
+        self.cursor = Cursor::default();
+        self.snippet_session = None;
+        self.viewport.reset();
+        self.tree = HIGHLIGHTER.write().unwrap().parse(content);
+        self.styled_display =
+            build_syntax_highlighted_lines(content, self.tree.as_ref(), self.colors);
+        self.refresh_search_matches();
+        self.update_variable_preview();
+    }
+
+    /// types on the command line, running the command on enter. The only
+    /// commands are substitutions, like `%s/foo/bar/g`
+    fn handle_command_key(&mut self, key_event: KeyEvent) {
+        let Some(command) = self.command_line.as_mut() else {
+            return;
+        };
+
+        match key_event.code {
+            KeyCode::Esc => self.command_line = None,
+            KeyCode::Backspace if command.is_empty() => self.command_line = None,
+            KeyCode::Backspace => _ = command.pop(),
+            KeyCode::Char(c) => command.push(c),
+            KeyCode::Enter => {
+                let command = self.command_line.take().unwrap_or_default();
+                if command.trim().eq("strip") {
+                    self.strip_trailing_whitespace();
+                    return;
+                }
+                match Substitute::parse(&command, self.cursor.row()) {
+                    Ok(substitute) => self.run_substitute(&substitute),
+                    Err(e) => self.command_message = Some(e.to_string()),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn strip_trailing_whitespace(&mut self) {
+        self.snapshot();
+        let stripped = self.body.strip_trailing_whitespace();
+        self.command_message = Some(Message::StrippedLines.format(&[&stripped.to_string()]));
         self.cursor
-            .row()
-            .saturating_sub(self.row_scroll)
-            .eq(&0)
-            .then(|| {
-                self.row_scroll = self
-                    .row_scroll
-                    .saturating_sub(self.row_scroll.saturating_sub(self.cursor.row()))
+            .maybe_snap_to_col(self.body.line_len(self.cursor.row()));
+        self.rehighlight();
+    }
+
+    fn run_substitute(&mut self, substitute: &Substitute) {
+        let matches = self.body.substitute_matches(substitute);
+        if substitute.confirm && !matches.is_empty() {
+            self.substitute_confirm = Some(SubstituteConfirm {
+                matches,
+                current: 0,
+                accepted: vec![],
             });
+            self.show_substitute_match();
+            return;
+        }
 
+        self.snapshot();
+        self.body.apply_substitutions(&matches);
+        self.command_message = Some(Message::Substitutions.format(&[&matches.len().to_string()]));
         self.cursor
-            .col()
-            .saturating_sub(self.col_scroll)
-            .eq(&0)
-            .then(|| {
-                self.col_scroll = self
-                    .col_scroll
-                    .saturating_sub(self.col_scroll.saturating_sub(self.cursor.col()))
-            });
+            .maybe_snap_to_col(self.body.line_len(self.cursor.row()));
+        self.rehighlight();
+    }
 
+    /// answers whether the current match of the substitution is replaced,
+    /// like the `c` flag of vim. The accepted matches are replaced when no
+    /// match is left or the confirmation is stopped
+    fn handle_substitute_key(&mut self, key_event: KeyEvent) {
+        let Some(confirm) = self.substitute_confirm.as_mut() else {
+            return;
+        };
+
+        let current = confirm.matches[confirm.current].clone();
+        match key_event.code {
+            KeyCode::Char('y') => {
+                confirm.accepted.push(current);
+                confirm.current = confirm.current.add(1);
+            }
+            KeyCode::Char('n') => confirm.current = confirm.current.add(1),
+            KeyCode::Char('a') => {
+                confirm
+                    .accepted
+                    .extend(confirm.matches.drain(confirm.current..));
+                confirm.current = confirm.matches.len();
+            }
+            KeyCode::Char('q') | KeyCode::Esc => confirm.current = confirm.matches.len(),
+            _ => {}
+        }
+
+        if confirm.current.lt(&confirm.matches.len()) {
+            self.show_substitute_match();
+            return;
+        }
+
+        let accepted = std::mem::take(&mut confirm.accepted);
+        self.substitute_confirm = None;
+        self.search_matches.clear();
+        if !accepted.is_empty() {
+            self.snapshot();
+            self.body.apply_substitutions(&accepted);
+        }
+        self.command_message = Some(Message::Substitutions.format(&[&accepted.len().to_string()]));
         self.cursor
-            .col()
-            .saturating_sub(self.col_scroll)
-            .gt(&self.size.width.sub(1).into())
-            .then(|| self.col_scroll = self.cursor.col().sub(self.size.width.sub(1) as usize));
+            .maybe_snap_to_col(self.body.line_len(self.cursor.row()));
+        self.rehighlight();
+    }
+
+    /// puts the cursor on the match being confirmed, highlighting only it
+    fn show_substitute_match(&mut self) {
+        let Some(found) = self
+            .substitute_confirm
+            .as_ref()
+            .and_then(|confirm| confirm.matches.get(confirm.current))
+        else {
+            return;
+        };
+        self.cursor.move_to_row(found.row);
+        self.cursor.move_to_col(found.start);
+        self.search_matches = vec![SearchMatch {
+            row: found.row,
+            start: found.start,
+            end: found.end,
+        }];
+        self.maybe_scroll_view();
+    }
+
+    /// highlights the syntax of the body again, and the matches of the last
+    /// search on it
+    fn rehighlight(&mut self) {
+        self.tree = HIGHLIGHTER.write().unwrap().parse(&self.body.to_string());
+        self.styled_display =
+            build_syntax_highlighted_lines(&self.body.to_string(), self.tree.as_ref(), self.colors);
+        self.refresh_search_matches();
+    }
+
+    fn start_search(&mut self, direction: SearchDirection) {
+        self.search_prompt = Some(SearchPrompt {
+            pattern: String::default(),
+            direction,
+            origin: self.cursor.clone(),
+        });
+    }
+
+    /// types on the search being typed, moving the cursor to the first match
+    /// from where the search started
+    fn handle_search_key(&mut self, key_event: KeyEvent) {
+        let Some(prompt) = self.search_prompt.as_mut() else {
+            return;
+        };
+
+        match key_event.code {
+            KeyCode::Esc => {
+                self.cursor = prompt.origin.clone();
+                self.search_prompt = None;
+            }
+            KeyCode::Enter => {
+                let origin = prompt.origin.clone();
+                if !prompt.pattern.is_empty() {
+                    self.last_search = Some((prompt.pattern.clone(), prompt.direction));
+                }
+                self.search_prompt = None;
+                self.push_jump(&origin);
+            }
+            KeyCode::Backspace if prompt.pattern.is_empty() => self.search_prompt = None,
+            KeyCode::Backspace => _ = prompt.pattern.pop(),
+            KeyCode::Char(c) => prompt.pattern.push(c),
+            _ => {}
+        }
+        self.update_search();
+    }
+
+    /// moves the cursor to the first match of the pattern being typed, or
+    /// back to where the search started
+    fn update_search(&mut self) {
+        let Some(prompt) = self.search_prompt.as_ref() else {
+            self.refresh_search_matches();
+            self.maybe_scroll_view();
+            return;
+        };
+        self.cursor = prompt.origin.clone();
+        if let Some((col, row)) =
+            self.body
+                .search(&prompt.pattern, &prompt.origin, prompt.direction)
+        {
+            self.cursor.move_to_row(row);
+            self.cursor.move_to_col(col);
+        }
+        self.search_matches = self.body.search_matches(&prompt.pattern);
+        self.maybe_scroll_view();
+    }
+
+    /// jumps to the next match of the last search, on the direction it was
+    /// typed on or the opposite one
+    fn repeat_search(&mut self, reverse: bool) {
+        let Some((pattern, direction)) = self.last_search.as_ref() else {
+            return;
+        };
+        let direction = match reverse {
+            true => direction.reverse(),
+            false => *direction,
+        };
+        if let Some((col, row)) = self.body.search(pattern, &self.cursor, direction) {
+            self.cursor.move_to_row(row);
+            self.cursor.move_to_col(col);
+            self.maybe_scroll_view();
+        }
+    }
+
+    /// finds the matches of the last search again, as the body changed
+    fn refresh_search_matches(&mut self) {
+        self.search_matches = match self.last_search.as_ref() {
+            Some((pattern, _)) => self.body.search_matches(pattern),
+            None => vec![],
+        };
+    }
+
+    /// inserts pasted text at the cursor as a single edit, skipping the
+    /// abbreviations, snippets and keymaps typing would go through. Inside of
+    /// a JSON string the text is escaped, so it stays a valid string
+    pub fn paste(&mut self, text: &str) {
+        self.macros.record(MacroInput::Paste(text.to_string()));
+        self.insert_pasted(text);
+        self.update_variable_preview();
+    }
+
+    fn insert_pasted(&mut self, text: &str) {
+        self.keymap_buffer = None;
+        self.anchor_marks();
+        if let Some(prompt) = self.search_prompt.as_mut() {
+            prompt.pattern.push_str(&paste::single_line(text));
+            self.update_search();
+            return;
+        }
+        if let Some(command) = self.command_line.as_mut() {
+            command.push_str(&paste::single_line(text));
+            return;
+        }
+
+        // the matches waiting to be confirmed would be moved by the paste
+        if self.substitute_confirm.is_some() || self.collection_store.borrow().is_read_only() {
+            return;
+        }
+
+        if self.hex.is_some() {
+            if self.editor_mode.eq(&EditorMode::Insert) {
+                let (write, ascii) = (self.hex_write, self.hex_ascii);
+                self.edit_hex(|hex| {
+                    text.chars().for_each(|c| match ascii {
+                        true => hex.type_char(c, write),
+                        false => hex.type_nibble(c, write),
+                    })
+                });
+            }
+            return;
+        }
+
+        self.snapshot();
+        let text = match self.is_inside_string() {
+            true => paste::json_string(text),
+            false => paste::normalize_line_breaks(text),
+        };
+        let start = self.body.char_idx(&self.cursor);
+        self.body.insert_str(&text, &self.cursor);
+        self.recorder.record_text(&text);
+        let (col, row) = self.body.position_of(start.add(text.chars().count()));
+        self.cursor.move_to_row(row);
+        self.cursor.move_to_col(col);
+        self.snippet_session = None;
+        self.maybe_scroll_view();
+        // typing after the paste is undone on its own, so the paste is a
+        // single entry on the undo history
+        self.snapshot();
+        self.rehighlight();
+    }
+
+    /// whether text typed at the cursor lands between the quotes of a JSON
+    /// string. An opened string is edited unescaped, so it never is
+    fn is_inside_string(&self) -> bool {
+        if self.string_session.is_some() {
+            return false;
+        }
+        let idx = self.body.char_idx(&self.cursor);
+        self.body
+            .scope_range(&self.cursor, TextScope::Quote('"'), false)
+            .is_some_and(|range| range.start.le(&idx) && range.end.ge(&idx))
+    }
+
+    /// restores the body to before the last edit, snapshots that match the
+    /// current body are skipped as they wouldn't change anything
+    fn undo(&mut self) {
+        let content = self.body.to_string();
+        while let Some((snapshot, cursor)) = self.undo_stack.pop() {
+            if snapshot.eq(&content) {
+                continue;
+            }
+
+            self.body = TextObject::from(&snapshot).with_write();
+            self.cursor = cursor;
+            self.snippet_session = None;
+            self.maybe_scroll_view();
+            return;
+        }
+    }
+
+    /// transforms the contents of the current line, leaving its indentation
+    /// untouched, text that cannot be transformed is left as is
+    fn transform_line(&mut self, transform: Transform) {
+        let content = self.body.to_string();
+        let Some(line) = content.lines().nth(self.cursor.row()) else {
+            return;
+        };
+
+        let text = line.trim();
+        if text.is_empty() {
+            return;
+        }
+
+        match transform.apply(text) {
+            Ok(transformed) => {
+                let indentation = line.chars().take_while(|c| c.is_whitespace()).count();
+                self.cursor.move_to_col(indentation);
+                let start = self.body.char_idx(&self.cursor);
+                self.body
+                    .remove_range(start, start.add(text.chars().count()));
+                self.body.insert_str(&transformed, &self.cursor);
+            }
+            Err(e) => tracing::warn!("failed to apply {transform:?} to line: {e}"),
+        }
+    }
+
+    /// replaces a JSON Schema written on the body with an example of it
+    fn generate_from_schema(&mut self) {
+        match hac_core::json_schema::example_body(&self.body.to_string()) {
+            Ok(example) => self.replace_body(&example),
+            Err(e) => tracing::warn!("failed to generate a body from the schema: {e}"),
+        }
+    }
+
+    /// flips the value under the cursor between an epoch timestamp and an
+    /// RFC 3339 date, the value ends at quotes, commas, brackets or spaces
+    fn convert_timestamp(&mut self) {
+        let content = self.body.to_string();
+        let Some(line) = content.lines().nth(self.cursor.row()) else {
+            return;
+        };
+
+        let chars = line.chars().collect::<Vec<_>>();
+        let is_boundary =
+            |c: &char| c.is_whitespace() || matches!(c, '"' | '\'' | ',' | '{' | '}' | '[' | ']');
+        let col = self.cursor.col().min(chars.len().saturating_sub(1));
+        if chars.get(col).is_none_or(is_boundary) {
+            return;
+        }
+
+        let start = chars[..col]
+            .iter()
+            .rposition(is_boundary)
+            .map(|idx| idx.add(1))
+            .unwrap_or_default();
+        let end = chars[col..]
+            .iter()
+            .position(is_boundary)
+            .map(|idx| idx.add(col))
+            .unwrap_or(chars.len());
+        let value = chars[start..end].iter().collect::<String>();
+
+        let Some(converted) = timestamp::convert(&value) else {
+            return;
+        };
+
+        self.cursor.move_to_col(start);
+        let start_idx = self.body.char_idx(&self.cursor);
+        self.body
+            .remove_range(start_idx, start_idx.add(end.sub(start)));
+        self.body.insert_str(&converted, &self.cursor);
+    }
+
+    /// replaces the word before the cursor by its expansion when it is one of
+    /// the configured abbreviations
+    fn maybe_expand_abbreviation(&mut self) {
+        let word = self.body.word_before_cursor(&self.cursor);
+        let Some(expansion) = self.config.abbreviations.get(&word) else {
+            return;
+        };
+
+        let word_len = word.chars().count();
+        let word_end = self.body.char_idx(&self.cursor);
+        self.body.remove_range(word_end.sub(word_len), word_end);
+        self.cursor.move_left(word_len);
+        self.body.insert_str(expansion, &self.cursor);
+        self.cursor.move_right(expansion.chars().count());
+    }
+
+    /// tries to expand the word before the cursor into a snippet, returning
+    /// whether a snippet was expanded or not
+    fn maybe_expand_snippet(&mut self) -> bool {
+        let trigger = self.body.word_before_cursor(&self.cursor);
+        let Some(snippet) = self.snippets.get(&trigger) else {
+            return false;
+        };
+
+        let trigger_len = trigger.chars().count();
+        let trigger_end = self.body.char_idx(&self.cursor);
+        let start = trigger_end.sub(trigger_len);
+        self.body.remove_range(start, trigger_end);
+        self.cursor.move_left(trigger_len);
+
+        let indentation = self.body.line_indentation(self.cursor.row());
+        let snippet = snippet.indented(&indentation);
+        self.body.insert_str(&snippet.text, &self.cursor);
+
+        let tab_stops = snippet
+            .tab_stops
+            .into_iter()
+            .map(|stop| TabStop {
+                offset: start.add(stop.offset),
+                ..stop
+            })
+            .collect::<VecDeque<_>>();
+
+        let snippet_end = start.add(snippet.text.chars().count());
+        self.snippet_session = Some(SnippetSession {
+            tab_stops,
+            current: None,
+        });
+
+        if !self.jump_to_next_tab_stop() {
+            let (col, row) = self.body.position_of(snippet_end);
+            self.cursor.move_to_row(row);
+            self.cursor.move_to_col(col);
+            self.maybe_scroll_view();
+        }
+
+        true
+    }
+
+    /// moves the cursor to the next tab stop of the active snippet, returning
+    /// whether there was a tab stop to jump to
+    fn jump_to_next_tab_stop(&mut self) -> bool {
+        let Some(session) = self.snippet_session.as_mut() else {
+            return false;
+        };
+
+        let Some(tab_stop) = session.tab_stops.pop_front() else {
+            self.snippet_session = None;
+            return false;
+        };
+
+        let (col, row) = self.body.position_of(tab_stop.offset);
+        session.current = Some(tab_stop);
+
+        self.cursor.move_to_row(row);
+        self.cursor.move_to_col(col);
+        self.maybe_scroll_view();
+
+        true
+    }
+
+    /// when the cursor is at a tab stop that still holds its placeholder text,
+    /// the placeholder is removed so typing replaces it. This is only called
+    /// from within `handle_action`, which shifts the remaining tab stops
+    fn maybe_clear_placeholder(&mut self) {
+        let cursor_idx = self.body.char_idx(&self.cursor);
+        let Some(session) = self.snippet_session.as_mut() else {
+            return;
+        };
+
+        if let Some(stop) = session.current.take() {
+            if stop.offset.eq(&cursor_idx) && stop.len.gt(&0) {
+                self.body
+                    .remove_range(stop.offset, stop.offset.add(stop.len));
+            }
+        }
+
+        // the snippet ends once its last tab stop is reached
+        if session.tab_stops.is_empty() {
+            self.snippet_session = None;
+        }
+    }
+
+    fn maybe_scroll_view(&mut self) {
+        // the statusline takes a row, and the line numbers some columns
+        let text_width = self.size.width.saturating_sub(self.gutter_width()).max(1);
+        self.viewport
+            .resize(self.size.height.saturating_sub(1).into(), text_width.into());
+        self.viewport
+            .follow(self.cursor.row(), self.cursor.col(), self.body.len_lines());
+    }
+
+    /// columns taken by the line numbers, none when they are off
+    fn gutter_width(&self) -> u16 {
+        line_numbers::gutter_width(self.config.line_numbers, self.body.len_lines()) as u16
+    }
+
+    fn draw_line_numbers(&self, frame: &mut Frame, size: Rect) {
+        let width = size.width.into();
+        let lines = self
+            .body
+            .line_numbers(self.viewport.top(), size.height.into(), &self.cursor)
+            .into_iter()
+            .map(|number| {
+                let label = format!("{} ", number.label(self.config.line_numbers, width));
+                match number.is_cursor_line() {
+                    true => label.fg(self.colors.normal.yellow),
+                    false => label.fg(self.colors.bright.black),
+                }
+            })
+            .map(Line::from)
+            .collect::<Vec<_>>();
+
+        frame.render_widget(Paragraph::new(lines), size);
     }
 
     fn jump_to_empty_line_below(&mut self) {
@@ -252,9 +1398,25 @@ impl<'be> BodyEditor<'be> {
         self.cursor.maybe_snap_to_col(line_len);
     }
 
+    fn jump_to_next_variable(&mut self) {
+        if let Some((new_col, new_row)) = self.body.find_next_variable(&self.cursor) {
+            self.cursor.move_to_row(new_row);
+            self.cursor.move_to_col(new_col);
+            self.maybe_scroll_view();
+        }
+    }
+
+    fn jump_to_previous_variable(&mut self) {
+        if let Some((new_col, new_row)) = self.body.find_previous_variable(&self.cursor) {
+            self.cursor.move_to_row(new_row);
+            self.cursor.move_to_col(new_col);
+            self.maybe_scroll_view();
+        }
+    }
+
     fn page_up(&mut self) {
-        let half_height = self.size.height.saturating_sub(2).div(2);
-        self.cursor.move_up(half_height.into());
+        let half_height = self.viewport.height().div(2);
+        self.cursor.move_up(half_height);
         self.maybe_scroll_view();
         let line_len = self.body.line_len(self.cursor.row());
         self.cursor.maybe_snap_to_col(line_len);
@@ -268,9 +1430,9 @@ impl<'be> BodyEditor<'be> {
     }
 
     fn page_down(&mut self) {
-        let half_height = self.size.height.saturating_sub(2).div(2);
+        let half_height = self.viewport.height().div(2);
         let len_lines = self.body.len_lines().saturating_sub(1);
-        let increment = usize::min(len_lines, self.cursor.row().add(half_height as usize));
+        let increment = usize::min(len_lines, self.cursor.row().add(half_height));
         self.cursor.move_to_row(increment);
         self.maybe_scroll_view();
         let line_len = self.body.line_len(self.cursor.row());
@@ -295,7 +1457,7 @@ impl<'be> BodyEditor<'be> {
     }
 
     fn delete_word(&mut self) {
-        self.body.delete_word(&self.cursor);
+        self.body.delete_word(&self.cursor, &mut self.registers);
     }
 
     fn delete_word_backwards(&mut self) {
@@ -305,12 +1467,13 @@ impl<'be> BodyEditor<'be> {
     }
 
     fn insert_char(&mut self, c: char) {
+        self.maybe_clear_placeholder();
         self.body.insert_char(c, &self.cursor);
         self.cursor.move_right(1);
     }
 
     fn delete_line(&mut self, line: usize) {
-        self.body.delete_line(line);
+        self.body.delete_line(line, &mut self.registers);
         let len_lines = self.body.len_lines();
         if self.cursor.row().ge(&len_lines.saturating_sub(1)) {
             self.cursor.move_to_row(len_lines.saturating_sub(1));
@@ -341,7 +1504,7 @@ impl<'be> BodyEditor<'be> {
     }
 
     fn erase_until_eol(&mut self) {
-        self.body.erase_until_eol(&self.cursor);
+        self.body.erase_until_eol(&self.cursor, &mut self.registers);
     }
 
     fn insert_at_eol(&mut self) {
@@ -376,6 +1539,239 @@ impl<'be> BodyEditor<'be> {
         self.editor_mode = EditorMode::Insert;
     }
 
+    /// opens the JSON string under the cursor to be edited unescaped, or
+    /// closes the open one escaping it back into the body
+    fn toggle_string_edit(&mut self) {
+        match self.string_session.take() {
+            Some(session) => {
+                let body = session.edit.apply(&self.body.to_string());
+                self.body = TextObject::from(&body).with_write();
+                self.cursor = session.edit.cursor().clone();
+                self.undo_stack = session.undo_stack;
+                self.marks = session.marks;
+            }
+            None => {
+                let Some((edit, text)) = StringEdit::open(&self.body, &self.cursor) else {
+                    return;
+                };
+                // undoing after the string is closed restores the body from
+                // before it was opened
+                self.snapshot();
+                let undo_stack = std::mem::take(&mut self.undo_stack);
+                let marks = std::mem::take(&mut self.marks);
+                self.string_session = Some(StringSession {
+                    edit,
+                    undo_stack,
+                    marks,
+                });
+                self.body = TextObject::from(&text).with_write();
+                self.cursor = Cursor::default();
+            }
+        }
+        self.editor_mode = EditorMode::Normal;
+        self.snippet_session = None;
+        self.viewport.reset();
+        self.maybe_scroll_view();
+    }
+
+    /// switches the body between text and the bytes of a binary body, which
+    /// are stored as base64
+    fn toggle_hex_mode(&mut self) {
+        // the string being edited is not the whole body
+        if self.string_session.is_some() {
+            return;
+        }
+        let (content, body_type) = match self.hex.take() {
+            Some(hex) => (
+                String::from_utf8_lossy(hex.bytes()).to_string(),
+                BodyType::Json,
+            ),
+            None => {
+                let hex = HexBuffer::new(self.body.to_string().into_bytes());
+                let content = hex.to_base64();
+                self.hex = Some(hex);
+                (content, BodyType::Binary)
+            }
+        };
+        if let Some(request) = self.collection_store.borrow().get_selected_request() {
+            request.write().unwrap().body_type = Some(body_type);
+        }
+        self.body = TextObject::from(&content).with_write();
+        self.cursor = Cursor::default();
+        self.editor_mode = EditorMode::Normal;
+        self.snippet_session = None;
+        self.viewport.reset();
+    }
+
+    /// handles the keys of the hex editor, returning whether the key was
+    /// used. Keys it doesn't use go through the keymaps, so the hex editor
+    /// can be closed and changes undone the same way as on text
+    fn handle_hex_key(&mut self, key_event: KeyEvent) -> bool {
+        let Some(hex) = self.hex.as_mut() else {
+            return false;
+        };
+        let read_only = self.collection_store.borrow().is_read_only();
+        let row = BYTES_PER_ROW as isize;
+
+        match (&self.editor_mode, key_event.code) {
+            (_, KeyCode::Left) => hex.move_by(-1),
+            (_, KeyCode::Right) => hex.move_by(1),
+            (_, KeyCode::Up) => hex.move_by(-row),
+            (_, KeyCode::Down) => hex.move_by(row),
+            (_, KeyCode::Home) => hex.move_to_row_start(),
+            (_, KeyCode::End) => hex.move_to_row_end(),
+            (_, KeyCode::Tab) => self.hex_ascii = !self.hex_ascii,
+            (EditorMode::Normal, KeyCode::Char('h')) => hex.move_by(-1),
+            (EditorMode::Normal, KeyCode::Char('l')) => hex.move_by(1),
+            (EditorMode::Normal, KeyCode::Char('k')) => hex.move_by(-row),
+            (EditorMode::Normal, KeyCode::Char('j')) => hex.move_by(row),
+            (EditorMode::Normal, KeyCode::Char('0')) => hex.move_to_row_start(),
+            (EditorMode::Normal, KeyCode::Char('$')) => hex.move_to_row_end(),
+            (EditorMode::Normal, KeyCode::Char('g')) => hex.move_to(0),
+            (EditorMode::Normal, KeyCode::Char('G')) => hex.move_to(usize::MAX),
+            (EditorMode::Normal, KeyCode::Char('i' | 'a' | 'R' | 'x') | KeyCode::Delete)
+                if read_only => {}
+            (EditorMode::Normal, KeyCode::Char(c @ ('i' | 'a' | 'R'))) => {
+                if c.eq(&'a') {
+                    hex.move_by(1);
+                }
+                self.hex_write = match c {
+                    'R' => HexWrite::Overwrite,
+                    _ => HexWrite::Insert,
+                };
+                self.editor_mode = EditorMode::Insert;
+                self.snapshot();
+            }
+            (EditorMode::Normal, KeyCode::Char('x') | KeyCode::Delete) => {
+                self.snapshot();
+                self.edit_hex(HexBuffer::delete);
+            }
+            (EditorMode::Normal, _) => return false,
+            (EditorMode::Insert, KeyCode::Esc) => self.editor_mode = EditorMode::Normal,
+            (EditorMode::Insert, KeyCode::Backspace) => self.edit_hex(HexBuffer::delete_previous),
+            (EditorMode::Insert, KeyCode::Delete) => self.edit_hex(HexBuffer::delete),
+            (EditorMode::Insert, KeyCode::Char(c)) => {
+                let (write, ascii) = (self.hex_write, self.hex_ascii);
+                self.edit_hex(|hex| match ascii {
+                    true => hex.type_char(c, write),
+                    false => hex.type_nibble(c, write),
+                });
+            }
+            (EditorMode::Insert, _) => {}
+        }
+        true
+    }
+
+    /// changes the bytes, storing them on the body as base64
+    fn edit_hex<F>(&mut self, edit: F)
+    where
+        F: FnOnce(&mut HexBuffer),
+    {
+        if let Some(hex) = self.hex.as_mut() {
+            edit(hex);
+            self.body = TextObject::from(&hex.to_base64()).with_write();
+        }
+    }
+
+    /// the offset, bytes and ASCII gutter of every row of the hex editor
+    fn draw_hex(&mut self, frame: &mut Frame, size: Rect) {
+        let Some(hex) = self.hex.as_ref() else {
+            return;
+        };
+        let height = usize::from(size.height).max(1);
+        let cursor_row = hex.cursor().div(BYTES_PER_ROW);
+        let len_rows = hex.bytes().len().div(BYTES_PER_ROW).add(1);
+        self.viewport.resize(height, size.width.into());
+        self.viewport.follow(cursor_row, 0, len_rows);
+
+        let cursor_style = Style::default()
+            .fg(self.colors.normal.black)
+            .bg(self.colors.bright.blue);
+        let mut rows = hex.rows();
+        // the cursor is after the last byte on a row of its own
+        if hex.cursor().eq(&hex.bytes().len()) && hex.cursor() % BYTES_PER_ROW == 0 {
+            rows.push(hac_core::text_object::hex_buffer::HexRow {
+                offset: hex.cursor(),
+                bytes: &[],
+            });
+        }
+
+        let lines = rows
+            .iter()
+            .skip(self.viewport.top())
+            .take(height)
+            .map(|row| {
+                let mut spans = vec![format!("{:08x}  ", row.offset).fg(self.colors.bright.black)];
+                for idx in 0..BYTES_PER_ROW {
+                    let position = row.offset.add(idx);
+                    let text = row
+                        .bytes
+                        .get(idx)
+                        .map(|byte| format!("{byte:02x}"))
+                        .unwrap_or_else(|| "  ".into());
+                    let span = match position.eq(&hex.cursor()) {
+                        true => Span::styled(text, cursor_style),
+                        false => Span::from(text).fg(self.colors.normal.white),
+                    };
+                    spans.push(span);
+                    spans.push(Span::from(match idx.eq(&(BYTES_PER_ROW / 2 - 1)) {
+                        true => "  ",
+                        false => " ",
+                    }));
+                }
+                spans.push("│".fg(self.colors.bright.black));
+                for (idx, c) in row.ascii().chars().enumerate() {
+                    let span = match row.offset.add(idx).eq(&hex.cursor()) {
+                        true => Span::styled(c.to_string(), cursor_style),
+                        false => Span::from(c.to_string()).fg(self.colors.normal.yellow),
+                    };
+                    spans.push(span);
+                }
+                Line::from(spans)
+            })
+            .chain(std::iter::repeat(Line::from(
+                "~".fg(self.colors.bright.black),
+            )))
+            .take(height)
+            .collect::<Vec<_>>();
+
+        frame.render_widget(Paragraph::new(lines), size);
+    }
+
+    /// puts the cursor on the nibble or ASCII char being typed
+    fn draw_hex_cursor(&self, hex: &HexBuffer, frame: &mut Frame) {
+        let column = hex.cursor() % BYTES_PER_ROW;
+        let col = match self.hex_ascii {
+            // after the offset, the bytes and the separator
+            true => 10 + BYTES_PER_ROW * 3 + 2 + column,
+            false => {
+                let gap = usize::from(column.ge(&(BYTES_PER_ROW / 2)));
+                10 + column * 3 + gap + usize::from(hex.is_half_typed())
+            }
+        };
+        // the hex view never scrolls sideways
+        let Some((_, row)) = self
+            .viewport
+            .screen_position(hex.cursor().div(BYTES_PER_ROW), 0)
+        else {
+            return;
+        };
+        frame.set_cursor(
+            self.size.x.add(col as u16).min(self.size.right()),
+            self.size.y.add(row as u16),
+        );
+    }
+
+    fn paste_after(&mut self) {
+        let Some(register) = self.registers.get(None).cloned() else {
+            return;
+        };
+        let (col, row) = self.body.paste_after(&self.cursor, &register);
+        self.cursor.move_to_row(row);
+        self.cursor.move_to_col(col);
+        self.maybe_scroll_view();
+    }
+
     fn move_to_next_word(&mut self) {
         let (col, row) = self.body.find_char_after_separator(&self.cursor);
         self.cursor.move_to_row(row);
@@ -466,6 +1862,7 @@ impl<'be> BodyEditor<'be> {
             self.cursor.move_left(1);
         }
         self.editor_mode = EditorMode::Normal;
+        self.snippet_session = None;
     }
 
     fn enter_insert_mode(&mut self) {
@@ -473,6 +1870,10 @@ impl<'be> BodyEditor<'be> {
     }
 
     fn insert_tab(&mut self) {
+        if self.jump_to_next_tab_stop() || self.maybe_expand_snippet() {
+            return;
+        }
+
         self.body.insert_char(' ', &self.cursor);
         self.body.insert_char(' ', &self.cursor);
         self.cursor.move_right(2);
@@ -511,25 +1912,61 @@ impl Renderable for BodyEditor<'_> {
         let [request_pane, statusline_pane] = build_editor_layout(size);
 
         self.draw_statusline(frame, statusline_pane);
+        if self.hex.is_some() {
+            self.draw_hex(frame, request_pane);
+            return Ok(());
+        }
+
+        // the cursor is drawn past the line numbers
+        let gutter_width = self.gutter_width();
+        self.cursor.set_col_offset(gutter_width.into());
+        let [gutter_pane, text_pane] = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(gutter_width), Constraint::Fill(1)])
+            .areas(request_pane);
+        if gutter_width.gt(&0) {
+            self.draw_line_numbers(frame, gutter_pane);
+        }
 
+        let match_style = Style::default()
+            .fg(self.colors.normal.black)
+            .bg(self.colors.normal.yellow);
+        let whitespace_style = Style::default().fg(self.colors.bright.black);
         let lines_in_view = self
             .styled_display
             .clone()
             .into_iter()
-            .skip(self.row_scroll)
+            .enumerate()
+            .skip(self.viewport.top())
+            .map(|(row, line)| {
+                let columns = self
+                    .search_matches
+                    .iter()
+                    .filter(|m| m.row.eq(&row))
+                    .map(|m| (m.start, m.end))
+                    .collect::<Vec<_>>();
+                let line = highlight_columns(line, &columns, match_style);
+                match self.config.show_whitespace {
+                    true => show_whitespace(&line, self.body.line_ending(row), whitespace_style),
+                    false => line,
+                }
+            })
             .chain(std::iter::repeat(Line::from(
                 "~".fg(self.colors.bright.black),
             )))
             .take(size.height.into())
-            .map(|line| get_visible_spans(&line, self.col_scroll))
+            .map(|line| get_visible_spans(&line, self.viewport.left()))
             .collect::<Vec<Line>>();
 
-        frame.render_widget(Paragraph::new(lines_in_view), request_pane);
+        frame.render_widget(Paragraph::new(lines_in_view), text_pane);
+        self.draw_variable_preview(frame, request_pane);
+        self.draw_completion(frame, request_pane);
         Ok(())
     }
 
     fn resize(&mut self, new_size: Rect) {
         self.size = new_size;
+        self.maybe_scroll_view();
     }
 }
 
@@ -537,17 +1974,31 @@ impl Eventful for BodyEditor<'_> {
     type Result = BodyEditorEvent;
 
     fn handle_key_event(&mut self, key_event: KeyEvent) -> anyhow::Result<Option<Self::Result>> {
+        // the keys that start or stop the recording aren't part of the macro
+        let recording = self.macros.recording().is_some();
+        let result = self.handle_key(key_event);
+        if recording && self.macros.recording().is_some() {
+            self.macros.record(MacroInput::Key(key_event));
+        }
+        self.update_variable_preview();
+        result
+    }
+}
+
+impl BodyEditor<'_> {
+    fn handle_key(&mut self, key_event: KeyEvent) -> anyhow::Result<Option<BodyEditorEvent>> {
+        self.anchor_marks();
         let key_str = keycode_as_string(key_event);
 
         if let Some(buffered_keymap) = self.keymap_buffer.to_owned() {
             match buffered_keymap {
                 KeyAction::Complex(key_action) => match key_action.get(&key_str) {
                     Some(KeyAction::Simple(action)) => {
-                        self.handle_action(action);
+                        self.run_actions(std::slice::from_ref(action));
                         self.keymap_buffer = None;
                     }
                     Some(KeyAction::Multiple(actions)) => {
-                        actions.iter().for_each(|a| self.handle_action(a));
+                        self.run_actions(actions);
                         self.keymap_buffer = None;
                     }
                     Some(key_action) => self.keymap_buffer = Some(key_action.clone()),
@@ -562,6 +2013,25 @@ impl Eventful for BodyEditor<'_> {
                 self.tree.as_ref(),
                 self.colors,
             );
+            self.refresh_search_matches();
+            return Ok(None);
+        }
+
+        self.command_message = None;
+        if self.name_prompt.is_some() {
+            self.handle_name_key(key_event);
+            return Ok(None);
+        }
+        if self.search_prompt.is_some() {
+            self.handle_search_key(key_event);
+            return Ok(None);
+        }
+        if self.command_line.is_some() {
+            self.handle_command_key(key_event);
+            return Ok(None);
+        }
+        if self.substitute_confirm.is_some() {
+            self.handle_substitute_key(key_event);
             return Ok(None);
         }
 
@@ -575,24 +2045,29 @@ impl Eventful for BodyEditor<'_> {
             return Ok(Some(BodyEditorEvent::Quit));
         };
 
+        if self.handle_hex_key(key_event) {
+            return Ok(None);
+        }
+
+        if self.handle_completion_key(key_event) {
+            self.rehighlight();
+            return Ok(None);
+        }
+
         match self.editor_mode {
             EditorMode::Normal => match self.config.editor_keys.normal.get(&key_str) {
-                Some(KeyAction::Simple(action)) => self.handle_action(action),
-                Some(KeyAction::Multiple(actions)) => {
-                    actions.iter().for_each(|a| self.handle_action(a))
-                }
+                Some(KeyAction::Simple(action)) => self.run_actions(std::slice::from_ref(action)),
+                Some(KeyAction::Multiple(actions)) => self.run_actions(actions),
                 Some(key_action) => self.keymap_buffer = Some(key_action.clone()),
                 None => {}
             },
             EditorMode::Insert => match self.config.editor_keys.insert.get(&key_str) {
-                Some(KeyAction::Simple(action)) => self.handle_action(action),
-                Some(KeyAction::Multiple(actions)) => {
-                    actions.iter().for_each(|a| self.handle_action(a))
-                }
+                Some(KeyAction::Simple(action)) => self.run_actions(std::slice::from_ref(action)),
+                Some(KeyAction::Multiple(actions)) => self.run_actions(actions),
                 Some(key_action) => self.keymap_buffer = Some(key_action.clone()),
                 None => {
                     if let Some(char) = key_str.chars().last() {
-                        self.handle_action(&Action::InsertChar(char));
+                        self.run_actions(&[Action::InsertChar(char)]);
                     }
                 }
             },
@@ -601,11 +2076,29 @@ impl Eventful for BodyEditor<'_> {
         self.tree = HIGHLIGHTER.write().unwrap().parse(&self.body.to_string());
         self.styled_display =
             build_syntax_highlighted_lines(&self.body.to_string(), self.tree.as_ref(), self.colors);
+        if self.search_prompt.is_none() {
+            self.refresh_search_matches();
+        }
+        self.update_completion();
 
         Ok(None)
     }
 }
 
+/// motions that move far enough to be remembered on the jumplist
+fn is_jump(action: &Action) -> bool {
+    matches!(
+        action,
+        Action::MoveToTop
+            | Action::MoveToBottom
+            | Action::JumpToClosing
+            | Action::JumpToEmptyLineBelow
+            | Action::JumpToEmptyLineAbove
+            | Action::FindNext
+            | Action::FindPrevious
+    )
+}
+
 fn keycode_as_string(key_event: KeyEvent) -> String {
     match (key_event.code, key_event.modifiers) {
         (KeyCode::Char(c), KeyModifiers::NONE) => c.into(),
@@ -639,12 +2132,16 @@ fn get_visible_spans(line: &Line<'static>, scroll: usize) -> Line<'static> {
     let mut new_spans = vec![];
 
     for span in line.spans.iter() {
-        let span_len = span.content.len();
+        let span_len = span.content.chars().count();
         if scroll_remaining >= span_len {
             scroll_remaining -= span_len;
             continue;
         } else {
-            let visible_content = span.content[scroll_remaining..].to_string();
+            let visible_content = span
+                .content
+                .chars()
+                .skip(scroll_remaining)
+                .collect::<String>();
             new_spans.push(Span::styled(visible_content, span.style));
             scroll_remaining = 0;
         }
@@ -653,6 +2150,44 @@ fn get_visible_spans(line: &Line<'static>, scroll: usize) -> Line<'static> {
     Line::from(new_spans)
 }
 
+/// restyles the columns of the line, splitting the syntax highlighted spans
+/// where a range starts or ends. Ranges are of chars, not bytes
+fn highlight_columns(
+    line: Line<'static>,
+    columns: &[(usize, usize)],
+    style: Style,
+) -> Line<'static> {
+    if columns.is_empty() {
+        return line;
+    }
+
+    let mut spans = vec![];
+    let mut col = 0;
+    for span in line.spans.iter() {
+        let chars = span.content.chars().collect::<Vec<_>>();
+        let end = col.add(chars.len());
+        let mut cursor = col;
+        while cursor.lt(&end) {
+            let found = columns
+                .iter()
+                .find(|(start, stop)| stop.gt(&cursor) && start.lt(&end));
+            let (next, span_style) = match found {
+                Some((start, _)) if start.gt(&cursor) => (*start, span.style),
+                Some((_, stop)) => (*stop.min(&end), span.style.patch(style)),
+                None => (end, span.style),
+            };
+            let text = chars[cursor.sub(col)..next.sub(col)]
+                .iter()
+                .collect::<String>();
+            spans.push(Span::styled(text, span_style));
+            cursor = next;
+        }
+        col = end;
+    }
+
+    Line::from(spans).style(line.style)
+}
+
 fn make_body(collection_store: &Rc<RefCell<CollectionStore>>) -> (TextObject<Write>, Option<Tree>) {
     let (body, tree) = if let Some(request) = collection_store.borrow().get_selected_request() {
         if let Some(body) = request.read().unwrap().body.as_ref() {
@@ -669,3 +2204,37 @@ fn make_body(collection_store: &Rc<RefCell<CollectionStore>>) -> (TextObject<Wri
 
     (body, tree)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippet_placeholders_are_replaced() {
+        let colors = hac_colors::Colors::default();
+        let mut config = hac_config::load_config();
+        config
+            .snippets
+            .insert("pair".into(), "${1:key} = ${2:value}".into());
+        let store = Rc::new(RefCell::new(CollectionStore::default()));
+        let mut editor = BodyEditor::new(&colors, &config, store, Rect::new(0, 0, 80, 24));
+
+        editor.run_actions(&[Action::EnterMode(EditorMode::Insert)]);
+        "pair"
+            .chars()
+            .for_each(|c| editor.run_actions(&[Action::InsertChar(c)]));
+        editor.run_actions(&[Action::InsertTab]);
+        assert_eq!(editor.body(), "key = value");
+
+        editor.run_actions(&[Action::InsertChar('a'), Action::InsertTab]);
+        assert_eq!(editor.body(), "a = value");
+
+        // the last tab stop has its placeholder replaced as the others
+        editor.run_actions(&[Action::InsertChar('b')]);
+        assert_eq!(editor.body(), "a = b");
+        assert!(editor.snippet_session.is_none());
+
+        editor.run_actions(&[Action::InsertTab]);
+        assert_eq!(editor.body(), "a = b  ");
+    }
+}
@@ -0,0 +1,270 @@
+use crate::i18n::Message;
+use crate::pages::collection_viewer::collection_store::CollectionStore;
+use crate::pages::{Eventful, Renderable};
+use crate::utils::build_markdown_lines;
+
+use std::cell::RefCell;
+use std::ops::{Add, Sub};
+use std::rc::Rc;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use hac_core::collection::types::RequestKind;
+use ratatui::layout::Rect;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::widgets::{Paragraph, Wrap};
+use ratatui::Frame;
+
+pub enum DocsEditorEvent {
+    /// user pressed `Esc` while not editing, so we bubble a remove selection
+    /// event for the parent to handle
+    RemoveSelection,
+    Quit,
+}
+
+/// which documentation is being edited, the request itself or the directory
+/// it lives in
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DocsTarget {
+    Request,
+    Directory,
+}
+
+/// displays the markdown documentation of the selected request, followed by
+/// the documentation of its parent directory, and allows editing the raw
+/// markdown of either of them
+#[derive(Debug)]
+pub struct DocsEditor<'de> {
+    colors: &'de hac_colors::colors::Colors,
+    collection_store: Rc<RefCell<CollectionStore>>,
+    editing: Option<DocsTarget>,
+    scroll: u16,
+    size: Rect,
+}
+
+impl<'de> DocsEditor<'de> {
+    pub fn new(
+        colors: &'de hac_colors::colors::Colors,
+        collection_store: Rc<RefCell<CollectionStore>>,
+        size: Rect,
+    ) -> Self {
+        DocsEditor {
+            colors,
+            collection_store,
+            editing: None,
+            scroll: 0,
+            size,
+        }
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    /// appends pasted text to the docs being edited, line breaks included,
+    /// returning whether it was used
+    pub fn paste(&mut self, text: &str) -> bool {
+        let Some(target) = self.editing else {
+            return false;
+        };
+        let text = hac_core::paste::normalize_line_breaks(text);
+        self.update_docs(target, |docs| docs.push_str(&text));
+        true
+    }
+
+    pub fn resize(&mut self, new_size: Rect) {
+        self.size = new_size;
+    }
+
+    /// draws the cursor at the end of the raw markdown being edited
+    pub fn draw_cursor(&self, frame: &mut Frame) {
+        let Some(target) = self.editing else {
+            return;
+        };
+
+        let docs = self.get_docs(target).unwrap_or_default();
+        let row = docs.split('\n').count().sub(1) as u16;
+        let col = docs.rsplit('\n').next().unwrap_or_default().chars().count() as u16;
+        let row = row.saturating_sub(self.raw_scroll(&docs));
+
+        frame.set_cursor(
+            self.size.x.add(col).min(self.size.right().sub(1)),
+            self.size.y.add(row),
+        );
+    }
+
+    /// id and name of the directory the selected request lives in, if any
+    fn parent_dir(&self) -> Option<(String, String)> {
+        let store = self.collection_store.borrow();
+        let parent = store
+            .get_selected_request()?
+            .read()
+            .unwrap()
+            .parent
+            .clone()?;
+        let requests = store.get_requests()?;
+        let requests = requests.read().unwrap();
+        requests.iter().find_map(|item| match item {
+            RequestKind::Nested(dir) if dir.id.eq(&parent) => {
+                Some((dir.id.clone(), dir.name.clone()))
+            }
+            _ => None,
+        })
+    }
+
+    fn get_docs(&self, target: DocsTarget) -> Option<String> {
+        let store = self.collection_store.borrow();
+        match target {
+            DocsTarget::Request => store.get_selected_request()?.read().unwrap().docs.clone(),
+            DocsTarget::Directory => {
+                let (dir_id, _) = self.parent_dir()?;
+                let requests = store.get_requests()?;
+                let requests = requests.read().unwrap();
+                requests.iter().find_map(|item| match item {
+                    RequestKind::Nested(dir) if dir.id.eq(&dir_id) => dir.docs.clone(),
+                    _ => None,
+                })
+            }
+        }
+    }
+
+    fn update_docs(&self, target: DocsTarget, update: impl FnOnce(&mut String)) {
+        let store = self.collection_store.borrow();
+        let apply = |docs: &mut Option<String>| {
+            let mut content = docs.take().unwrap_or_default();
+            update(&mut content);
+            *docs = (!content.is_empty()).then_some(content);
+        };
+
+        match target {
+            DocsTarget::Request => {
+                if let Some(request) = store.get_selected_request() {
+                    apply(&mut request.write().unwrap().docs);
+                }
+            }
+            DocsTarget::Directory => {
+                let (Some((dir_id, _)), Some(requests)) = (self.parent_dir(), store.get_requests())
+                else {
+                    return;
+                };
+                let mut requests = requests.write().unwrap();
+                if let Some(RequestKind::Nested(dir)) =
+                    requests.iter_mut().find(|item| item.get_id().eq(&dir_id))
+                {
+                    apply(&mut dir.docs);
+                }
+            }
+        }
+    }
+
+    /// when editing, we keep the last line of the raw markdown on view
+    fn raw_scroll(&self, docs: &str) -> u16 {
+        let total_lines = docs.split('\n').count() as u16;
+        total_lines.saturating_sub(self.size.height.saturating_sub(1))
+    }
+
+    fn build_docs_lines(&self) -> Vec<Line<'static>> {
+        let mut lines = match self.get_docs(DocsTarget::Request) {
+            Some(docs) => build_markdown_lines(&docs, self.colors),
+            None => vec![Line::from(Message::NoRequestDocs.text()).fg(self.colors.bright.black)],
+        };
+
+        if let Some((_, dir_name)) = self.parent_dir() {
+            lines.push(Line::default());
+            lines.push(Line::from(format!("── {} ──", dir_name)).fg(self.colors.bright.black));
+            match self.get_docs(DocsTarget::Directory) {
+                Some(docs) => lines.extend(build_markdown_lines(&docs, self.colors)),
+                None => lines
+                    .push(Line::from(Message::NoDirectoryDocs.text()).fg(self.colors.bright.black)),
+            }
+        }
+
+        lines
+    }
+
+    fn get_hint_size(&self, frame: &mut Frame) -> Rect {
+        let size = frame.size();
+        Rect::new(0, size.height.sub(1), size.width, 1)
+    }
+
+    fn draw_hint(&self, frame: &mut Frame) {
+        let hint_size = self.get_hint_size(frame);
+        let hint = match (self.editing, self.parent_dir().is_some()) {
+            (Some(_), _) => Message::DocsEditingHint.text(),
+            (None, true) => Message::DocsDirectoryHint.text(),
+            (None, false) => Message::DocsHint.text(),
+        };
+        frame.render_widget(
+            Paragraph::new(hint).fg(self.colors.bright.black).centered(),
+            hint_size,
+        );
+    }
+}
+
+impl Renderable for DocsEditor<'_> {
+    fn draw(&mut self, frame: &mut Frame, size: Rect) -> anyhow::Result<()> {
+        if self
+            .collection_store
+            .borrow()
+            .get_selected_request()
+            .is_none()
+        {
+            return Ok(());
+        }
+
+        self.draw_hint(frame);
+
+        if let Some(target) = self.editing {
+            let docs = self.get_docs(target).unwrap_or_default();
+            let raw = Paragraph::new(docs.clone())
+                .fg(self.colors.normal.white)
+                .scroll((self.raw_scroll(&docs), 0));
+            frame.render_widget(raw, size);
+            return Ok(());
+        }
+
+        let docs = Paragraph::new(self.build_docs_lines())
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+        frame.render_widget(docs, size);
+
+        Ok(())
+    }
+}
+
+impl Eventful for DocsEditor<'_> {
+    type Result = DocsEditorEvent;
+
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> anyhow::Result<Option<Self::Result>> {
+        if let Some(target) = self.editing {
+            match key_event.code {
+                KeyCode::Esc => self.editing = None,
+                KeyCode::Char(c) => self.update_docs(target, |docs| docs.push(c)),
+                KeyCode::Enter => self.update_docs(target, |docs| docs.push('\n')),
+                KeyCode::Tab => self.update_docs(target, |docs| docs.push_str("  ")),
+                KeyCode::Backspace => self.update_docs(target, |docs| _ = docs.pop()),
+                _ => {}
+            }
+            return Ok(None);
+        }
+
+        if let (KeyCode::Char('c'), KeyModifiers::CONTROL) = (key_event.code, key_event.modifiers) {
+            return Ok(Some(DocsEditorEvent::Quit));
+        }
+
+        let is_read_only = self.collection_store.borrow().is_read_only();
+
+        match key_event.code {
+            KeyCode::Esc => return Ok(Some(DocsEditorEvent::RemoveSelection)),
+            KeyCode::Char('j') | KeyCode::Down => self.scroll = self.scroll.add(1),
+            KeyCode::Char('k') | KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Char('i') if !is_read_only => self.editing = Some(DocsTarget::Request),
+            KeyCode::Char('f') if !is_read_only && self.parent_dir().is_some() => {
+                self.editing = Some(DocsTarget::Directory)
+            }
+            _ => {}
+        }
+
+        Ok(None)
+    }
+}
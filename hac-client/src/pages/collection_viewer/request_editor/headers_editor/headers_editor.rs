@@ -1,7 +1,9 @@
 use crate::ascii::LOGO_ASCII;
+use crate::i18n::Message;
 use crate::pages::collection_viewer::collection_viewer::CollectionViewerOverlay;
 use crate::pages::overlay::make_overlay;
 use crate::pages::{collection_viewer::collection_store::CollectionStore, Eventful, Renderable};
+use crate::utils::build_variable_spans;
 
 use std::ops::{Div, Mul, Sub};
 use std::{cell::RefCell, ops::Add, rc::Rc};
@@ -55,6 +57,7 @@ pub struct HeadersEditor<'he> {
 impl<'he> HeadersEditor<'he> {
     pub fn new(
         colors: &'he hac_colors::colors::Colors,
+        config: &'he hac_config::Config,
         collection_store: Rc<RefCell<CollectionStore>>,
         size: Rect,
     ) -> Self {
@@ -64,7 +67,7 @@ impl<'he> HeadersEditor<'he> {
 
         HeadersEditor {
             delete_prompt: HeadersEditorDeletePrompt::new(colors),
-            header_form: HeadersEditorForm::new(colors, collection_store.clone()),
+            header_form: HeadersEditorForm::new(colors, config, collection_store.clone()),
 
             colors,
             collection_store,
@@ -89,7 +92,15 @@ impl<'he> HeadersEditor<'he> {
             (false, false) => disabled,
         };
 
-        let make_paragraph = |text: &str| Paragraph::new(text.to_string()).fg(text_color);
+        // disabled headers are not sent, so their variables are not highlighted
+        let make_paragraph = |text: &str| match header.enabled {
+            true => Paragraph::new(Line::from(build_variable_spans(
+                text,
+                Style::default().fg(text_color),
+                self.colors,
+            ))),
+            false => Paragraph::new(text.to_string()).fg(text_color),
+        };
 
         let name = make_paragraph(&header.pair.0);
         let value = make_paragraph(&header.pair.1);
@@ -112,8 +123,8 @@ impl<'he> HeadersEditor<'he> {
     fn draw_hint(&self, frame: &mut Frame) {
         let hint_size = self.get_hint_size(frame);
         let hint = match hint_size.width {
-            w if w.le(&100) => "[j/k -> move down/up] [enter -> select] [space -> enable/disable] [? -> help]",
-            _ => "[j/k -> move down/up] [enter -> select] [space -> enable/disable] [d -> delete] [? -> help]",
+            w if w.le(&100) => Message::HeadersHint.text(),
+            _ => Message::HeadersHintWide.text(),
         };
         frame.render_widget(
             Paragraph::new(hint).fg(self.colors.bright.black).centered(),
@@ -127,30 +138,27 @@ impl<'he> HeadersEditor<'he> {
         let lines = [
             [
                 format!("j{}", " ".repeat(11)).fg(self.colors.normal.red),
-                format!("- move down{}", " ".repeat(29)).fg(self.colors.normal.yellow),
+                format!("{:<40}", Message::HelpMoveDown.text()).fg(self.colors.normal.yellow),
             ],
             [
                 format!("k{}", " ".repeat(11)).fg(self.colors.normal.red),
-                format!("- move up{}", " ".repeat(31)).fg(self.colors.normal.yellow),
+                format!("{:<40}", Message::HelpMoveUp.text()).fg(self.colors.normal.yellow),
             ],
             [
                 format!("d{}", " ".repeat(11)).fg(self.colors.normal.red),
-                format!("- deletes header{}", " ".repeat(20)).fg(self.colors.normal.yellow),
+                format!("{:<40}", Message::HelpDeleteHeader.text()).fg(self.colors.normal.yellow),
             ],
             [
                 format!("space{}", " ".repeat(7)).fg(self.colors.normal.red),
-                format!("- enables or disabled header{}", " ".repeat(12))
-                    .fg(self.colors.normal.yellow),
+                format!("{:<40}", Message::HelpToggleHeader.text()).fg(self.colors.normal.yellow),
             ],
             [
                 format!("enter{}", " ".repeat(7)).fg(self.colors.normal.red),
-                format!("- select header for editing{}", " ".repeat(13))
-                    .fg(self.colors.normal.yellow),
+                format!("{:<40}", Message::HelpEditHeader.text()).fg(self.colors.normal.yellow),
             ],
             [
                 format!("?{}", " ".repeat(11)).fg(self.colors.normal.red),
-                format!("- shows this help message{}", " ".repeat(15))
-                    .fg(self.colors.normal.yellow),
+                format!("{:<40}", Message::HelpShowHelp.text()).fg(self.colors.normal.yellow),
             ],
         ];
 
@@ -194,7 +202,7 @@ impl<'he> HeadersEditor<'he> {
             1,
         );
 
-        let hint = Line::from("press any key to close this dialog")
+        let hint = Line::from(Message::CloseDialog.text())
             .fg(self.colors.bright.black)
             .centered();
 
@@ -204,7 +212,7 @@ impl<'he> HeadersEditor<'he> {
 
     pub fn draw_empty_message(&self, frame: &mut Frame) {
         let size = self.layout.content_size;
-        let no_headers = "No headers method".fg(self.colors.bright.black);
+        let no_headers = Message::NoHeaders.text().fg(self.colors.bright.black);
         let no_request = Paragraph::new(no_headers).centered().block(
             Block::default()
                 .fg(self.colors.normal.white)
@@ -252,9 +260,13 @@ impl Renderable for HeadersEditor<'_> {
         }
 
         let headers = headers.unwrap();
-        let title_name = Paragraph::new("Name").fg(self.colors.normal.yellow).bold();
-        let title_value = Paragraph::new("Value").fg(self.colors.normal.yellow).bold();
-        let title_enabled = Paragraph::new("Enabled")
+        let title_name = Paragraph::new(Message::Name.text())
+            .fg(self.colors.normal.yellow)
+            .bold();
+        let title_value = Paragraph::new(Message::Value.text())
+            .fg(self.colors.normal.yellow)
+            .bold();
+        let title_enabled = Paragraph::new(Message::Enabled.text())
             .fg(self.colors.normal.yellow)
             .bold();
 
@@ -386,6 +398,17 @@ impl Eventful for HeadersEditor<'_> {
             return Ok(Some(HeadersEditorEvent::Quit));
         }
 
+        // toggling, deleting, editing or creating headers is not allowed until
+        // a read only collection is unlocked
+        if self.collection_store.borrow().is_read_only()
+            && matches!(
+                key_event.code,
+                KeyCode::Char(' ' | 'd' | 'n') | KeyCode::Enter
+            )
+        {
+            return Ok(None);
+        }
+
         let Some(request) = self.collection_store.borrow_mut().get_selected_request() else {
             return Ok(None);
         };
@@ -1,6 +1,7 @@
 use std::ops::{Add, Div};
 
 use crate::ascii::LOGO_ASCII;
+use crate::i18n::Message;
 use crate::pages::{overlay::make_overlay, Eventful, Renderable};
 
 use crossterm::event::{KeyCode, KeyEvent};
@@ -39,15 +40,15 @@ impl Renderable for HeadersEditorDeletePrompt<'_> {
         make_overlay(self.colors, self.colors.normal.black, 0.1, frame);
 
         let lines: Vec<Line> = vec![
-            "are you sure you want to delete this header?"
+            Message::DeleteHeaderPrompt
+                .text()
                 .fg(self.colors.normal.yellow)
                 .into(),
             "".fg(self.colors.normal.white).into(),
             Line::from(vec![
-                "[Y]".fg(self.colors.normal.red),
-                "es        ".fg(self.colors.normal.white),
-                "[N]".fg(self.colors.normal.red),
-                "o".fg(self.colors.normal.white),
+                Message::Yes.text().fg(self.colors.normal.green),
+                " ".into(),
+                Message::No.text().fg(self.colors.normal.red),
             ])
             .centered(),
         ];
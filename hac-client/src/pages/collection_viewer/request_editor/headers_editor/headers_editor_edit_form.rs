@@ -1,4 +1,10 @@
+use hac_core::abbreviation::{expand_trailing, is_word_char};
+use hac_core::collection::validation;
+use hac_core::completion::CompletionContext;
+
 use crate::ascii::LOGO_ASCII;
+use crate::components::variable_preview::{draw_variable_preview, InputVariablePreview};
+use crate::i18n::Message;
 use crate::pages::collection_viewer::collection_store::CollectionStore;
 use crate::pages::collection_viewer::collection_viewer::CollectionViewerOverlay;
 use crate::pages::input::Input;
@@ -14,9 +20,12 @@ use rand::Rng;
 use ratatui::layout::Rect;
 use ratatui::style::Stylize;
 use ratatui::text::Line;
-use ratatui::widgets::Paragraph;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 use ratatui::Frame;
 
+/// most suggestions listed under the focused input
+const MAX_SUGGESTIONS: usize = 5;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HeadersEditorFormEvent {
     FinishEdit,
@@ -42,29 +51,43 @@ impl HeadersEditorFormInput {
 #[derive(Debug)]
 pub struct HeadersEditorForm<'hef> {
     colors: &'hef hac_colors::Colors,
+    config: &'hef hac_config::Config,
     collection_store: Rc<RefCell<CollectionStore>>,
     header_idx: usize,
     logo_idx: usize,
     focused_input: HeadersEditorFormInput,
     original_name: String,
     original_value: String,
+    /// the suggestion highlighted while typing the name or value of the
+    /// header
+    suggestion_idx: Option<usize>,
+    /// whether the suggestions were cancelled, they are shown again as soon
+    /// as something is typed
+    suggestions_hidden: bool,
+    /// the variable at the end of the focused input
+    variable_preview: InputVariablePreview,
 }
 
 impl<'hef> HeadersEditorForm<'hef> {
     pub fn new(
         colors: &'hef hac_colors::Colors,
+        config: &'hef hac_config::Config,
         collection_store: Rc<RefCell<CollectionStore>>,
     ) -> HeadersEditorForm<'hef> {
         let logo_idx = rand::rng().random_range(0..LOGO_ASCII.len());
 
         HeadersEditorForm {
             colors,
+            config,
             header_idx: 0,
             collection_store,
             logo_idx,
             focused_input: HeadersEditorFormInput::Name,
             original_name: String::default(),
             original_value: String::default(),
+            suggestion_idx: None,
+            suggestions_hidden: false,
+            variable_preview: InputVariablePreview::new(config.environment.as_deref()),
         }
     }
 
@@ -102,6 +125,65 @@ impl<'hef> HeadersEditorForm<'hef> {
     fn reset(&mut self) {
         self.original_name.clear();
         self.original_value.clear();
+        self.suggestion_idx = None;
+        self.suggestions_hidden = false;
+    }
+
+    /// completions for the focused input, values used before on the header
+    /// come first, followed by the names and values of the collection and
+    /// well known ones
+    fn suggestions(&self, store: &CollectionStore, name: &str, value: &str) -> Vec<String> {
+        if self.suggestions_hidden {
+            return vec![];
+        }
+
+        let (context, typed) = match self.focused_input {
+            HeadersEditorFormInput::Name => (CompletionContext::HeaderName, name),
+            HeadersEditorFormInput::Value => {
+                (CompletionContext::HeaderValue { header_name: name }, value)
+            }
+        };
+        let mut suggestions = match (self.focused_input, store.get_header_history()) {
+            (HeadersEditorFormInput::Value, Some(history)) => history.borrow().suggest(name, value),
+            _ => vec![],
+        };
+        if let Some(engine) = store.get_completions() {
+            let candidates = engine.borrow().complete(&context, typed);
+            suggestions.extend(candidates.into_iter().map(|candidate| candidate.label));
+        }
+
+        let mut seen = vec![];
+        suggestions.retain(|suggestion| {
+            let is_new = !seen.contains(suggestion) && suggestion.ne(typed);
+            seen.push(suggestion.clone());
+            is_new
+        });
+        suggestions.truncate(MAX_SUGGESTIONS);
+        suggestions
+    }
+
+    fn draw_suggestions(&self, suggestions: &[String], frame: &mut Frame, size: Rect) {
+        let lines = suggestions
+            .iter()
+            .enumerate()
+            .map(|(idx, suggestion)| match self.suggestion_idx {
+                Some(selected) if selected.eq(&idx) => Line::from(format!(" {suggestion}"))
+                    .fg(self.colors.normal.black)
+                    .bg(self.colors.normal.blue),
+                _ => Line::from(format!(" {suggestion}")).fg(self.colors.normal.white),
+            })
+            .collect::<Vec<_>>();
+
+        frame.render_widget(Clear, size);
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(Message::HeaderSuggestions.text())
+                    .fg(self.colors.bright.black),
+            ),
+            size,
+        );
     }
 }
 
@@ -151,11 +233,12 @@ impl Renderable for HeadersEditorForm<'_> {
             size.y = size.height.div(2).saturating_sub(5);
         }
 
-        let mut name_input = Input::new(self.colors, "Name".into());
-        let mut value_input = Input::new(self.colors, "Value".into());
-        let hint = Paragraph::new(
-            "Press enter to confirm, press esc to cancel".fg(self.colors.bright.black),
-        )
+        let mut name_input = Input::new(self.colors, Message::Name.text().into());
+        let mut value_input = Input::new(self.colors, Message::Value.text().into());
+        let hint = match validation::validate_header(&header.pair.0, &header.pair.1) {
+            Some(issue) => Paragraph::new(issue.fg(self.colors.normal.red)),
+            None => Paragraph::new(Message::ConfirmOrCancel.text().fg(self.colors.bright.black)),
+        }
         .centered();
 
         match self.focused_input {
@@ -184,20 +267,31 @@ impl Renderable for HeadersEditorForm<'_> {
         frame.render_stateful_widget(value_input, value_size, &mut header.pair.1.clone());
         frame.render_widget(hint, hint_size);
 
-        match self.focused_input {
-            HeadersEditorFormInput::Name => {
-                frame.set_cursor(
-                    name_size.x.add(header.pair.0.chars().count().add(1) as u16),
-                    name_size.y.add(1),
-                );
-            }
-            HeadersEditorFormInput::Value => {
-                frame.set_cursor(
-                    value_size
-                        .x
-                        .add(header.pair.1.chars().count().add(1) as u16),
-                    value_size.y.add(1),
-                );
+        let suggestions = self.suggestions(&store, &header.pair.0, &header.pair.1);
+        if !suggestions.is_empty() {
+            let height = (suggestions.len() as u16)
+                .add(2)
+                .min(frame.size().height.saturating_sub(hint_size.bottom()));
+            let suggestions_size = Rect::new(size.x, hint_size.bottom(), size.width, height);
+            self.draw_suggestions(&suggestions, frame, suggestions_size);
+        }
+
+        let (focused_value, focused_size) = match self.focused_input {
+            HeadersEditorFormInput::Name => (&header.pair.0, name_size),
+            HeadersEditorFormInput::Value => (&header.pair.1, value_size),
+        };
+        let cursor = (
+            focused_size
+                .x
+                .add(focused_value.chars().count().add(1) as u16),
+            focused_size.y.add(1),
+        );
+        frame.set_cursor(cursor.0, cursor.1);
+
+        // the suggestions are what is being typed, so they aren't covered
+        if suggestions.is_empty() {
+            if let Some(preview) = self.variable_preview.resolve(&store, focused_value) {
+                draw_variable_preview(frame, preview, cursor, frame.size(), self.colors);
             }
         }
 
@@ -235,17 +329,67 @@ impl Eventful for HeadersEditorForm<'_> {
             return Ok(Some(HeadersEditorFormEvent::Quit));
         }
 
+        let suggestions = self.suggestions(&store, &header.pair.0, &header.pair.1);
+        let selected = self
+            .suggestion_idx
+            .and_then(|idx| suggestions.get(idx))
+            .cloned();
+        match key_event.code {
+            KeyCode::Down if !suggestions.is_empty() => {
+                let last = suggestions.len().sub(1);
+                self.suggestion_idx =
+                    Some(self.suggestion_idx.map_or(0, |idx| idx.add(1).min(last)));
+                return Ok(None);
+            }
+            KeyCode::Up if !suggestions.is_empty() => {
+                self.suggestion_idx = self.suggestion_idx.and_then(|idx| idx.checked_sub(1));
+                return Ok(None);
+            }
+            KeyCode::Right | KeyCode::Enter if selected.is_some() => {
+                match self.focused_input {
+                    HeadersEditorFormInput::Name => header.pair.0 = selected.unwrap_or_default(),
+                    HeadersEditorFormInput::Value => header.pair.1 = selected.unwrap_or_default(),
+                }
+                self.suggestion_idx = None;
+                return Ok(None);
+            }
+            KeyCode::Esc if selected.is_some() => {
+                self.suggestion_idx = None;
+                self.suggestions_hidden = true;
+                return Ok(None);
+            }
+            _ => self.suggestion_idx = None,
+        }
+
+        let focused_value = match self.focused_input {
+            HeadersEditorFormInput::Name => &mut header.pair.0,
+            HeadersEditorFormInput::Value => &mut header.pair.1,
+        };
+
+        // leaving the field or typing a word boundary expands abbreviations
+        let is_boundary = match key_event.code {
+            KeyCode::Char(c) => !is_word_char(c),
+            KeyCode::Tab | KeyCode::BackTab | KeyCode::Enter => true,
+            _ => false,
+        };
+        if is_boundary {
+            if let Some(expanded) = expand_trailing(focused_value, &self.config.abbreviations) {
+                *focused_value = expanded;
+            }
+        }
+
+        if matches!(
+            key_event.code,
+            KeyCode::Char(_) | KeyCode::Backspace | KeyCode::Tab | KeyCode::BackTab
+        ) {
+            self.suggestions_hidden = false;
+        }
+
         match key_event.code {
             KeyCode::Tab => self.focused_input = self.focused_input.next(),
             KeyCode::BackTab => self.focused_input = self.focused_input.next(),
-            KeyCode::Backspace => match self.focused_input {
-                HeadersEditorFormInput::Name => _ = header.pair.0.pop(),
-                HeadersEditorFormInput::Value => _ = header.pair.1.pop(),
-            },
-            KeyCode::Char(c) => match self.focused_input {
-                HeadersEditorFormInput::Name => header.pair.0.push(c),
-                HeadersEditorFormInput::Value => header.pair.1.push(c),
-            },
+            KeyCode::Backspace => _ = focused_value.pop(),
+            KeyCode::Char(c) => focused_value.push(c),
             KeyCode::Esc => {
                 header.pair = (self.original_name.clone(), self.original_value.clone());
                 if is_new {
@@ -255,6 +399,10 @@ impl Eventful for HeadersEditorForm<'_> {
                 self.reset();
                 return Ok(Some(HeadersEditorFormEvent::CancelEdit));
             }
+            // headers that would make the request fail are kept on the form
+            // until they are fixed
+            KeyCode::Enter
+                if validation::validate_header(&header.pair.0, &header.pair.1).is_some() => {}
             KeyCode::Enter => {
                 drop(store);
                 self.reset();
@@ -1,14 +1,21 @@
+use hac_core::collection::validation;
+
+use crate::components::variable_preview::{draw_variable_preview, InputVariablePreview};
+use crate::i18n::Message;
 use crate::pages::collection_viewer::collection_store::CollectionStore;
 use crate::pages::collection_viewer::collection_store::CollectionStoreAction;
 use crate::pages::collection_viewer::collection_viewer::PaneFocus;
 use crate::pages::{Eventful, Renderable};
+use crate::utils::build_variable_spans;
 
 use std::cell::RefCell;
+use std::ops::Add;
 use std::rc::Rc;
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::layout::Rect;
 use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
 use ratatui::widgets::{Block, Borders, Paragraph};
 use ratatui::Frame;
 
@@ -34,11 +41,17 @@ pub struct RequestUri<'ru> {
     colors: &'ru hac_colors::Colors,
     collection_store: Rc<RefCell<CollectionStore>>,
     size: Rect,
+    /// the variable at the end of the uri, shown while it is being edited
+    variable_preview: InputVariablePreview,
+    /// whether the uri was changed since this pane was selected, every edit
+    /// until it is left is undone at once
+    is_editing: bool,
 }
 
 impl<'ru> RequestUri<'ru> {
     pub fn new(
         colors: &'ru hac_colors::Colors,
+        config: &hac_config::Config,
         collection_store: Rc<RefCell<CollectionStore>>,
         size: Rect,
     ) -> Self {
@@ -46,6 +59,19 @@ impl<'ru> RequestUri<'ru> {
             colors,
             collection_store,
             size,
+            variable_preview: InputVariablePreview::new(config.environment.as_deref()),
+            is_editing: false,
+        }
+    }
+}
+
+impl RequestUri<'_> {
+    fn record_uri_change(&mut self) {
+        let mut store = self.collection_store.borrow_mut();
+        if let Some(req) = store.get_selected_request() {
+            let name = req.read().unwrap().name.clone();
+            store.record_change(Message::ChangeUri.format(&[&name]));
+            self.is_editing = true;
         }
     }
 }
@@ -81,19 +107,55 @@ impl<'a> Renderable for RequestUri<'a> {
             .map(|req| req.read().unwrap().uri.to_string())
             .unwrap_or_default();
 
+        // while editing, long uris scroll so the end being typed stays visible
+        let offset = match is_selected {
+            true => (uri.chars().count() as u16)
+                .add(1)
+                .saturating_sub(size.width.saturating_sub(2)),
+            false => 0,
+        };
+
+        // an empty uri is the starting point of every new request, so it is
+        // only reported when sending
+        let issue = (!uri.trim().is_empty())
+            .then(|| validation::validate_uri(&uri))
+            .flatten();
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(block_border)
+            .title(vec![
+                "U".fg(self.colors.normal.red).bold(),
+                "ri".fg(self.colors.bright.black),
+            ]);
+        if let Some(issue) = issue {
+            block = block.title_bottom(Line::from(format!(" {issue} ").fg(self.colors.normal.red)));
+        }
+
         frame.render_widget(
-            Paragraph::new(uri).fg(self.colors.normal.white).block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_style(block_border)
-                    .title(vec![
-                        "U".fg(self.colors.normal.red).bold(),
-                        "ri".fg(self.colors.bright.black),
-                    ]),
-            ),
+            Paragraph::new(Line::from(build_variable_spans(
+                &uri,
+                Style::default().fg(self.colors.normal.white),
+                self.colors,
+            )))
+            .scroll((0, offset))
+            .block(block),
             size,
         );
 
+        if is_selected {
+            let store = self.collection_store.borrow();
+            if let Some(preview) = self.variable_preview.resolve(&store, &uri) {
+                let cursor_col = size
+                    .x
+                    .add(1)
+                    .add(uri.chars().count() as u16)
+                    .saturating_sub(offset)
+                    .min(size.right());
+                let cursor = (cursor_col, size.y.add(1));
+                draw_variable_preview(frame, preview, cursor, frame.size(), self.colors);
+            }
+        }
+
         Ok(())
     }
 }
@@ -117,11 +179,21 @@ impl Eventful for RequestUri<'_> {
             return Ok(Some(RequestUriEvent::Quit));
         }
 
+        let is_read_only = self.collection_store.borrow().is_read_only();
+
+        match key_event.code {
+            KeyCode::Char(_) | KeyCode::Backspace if !is_read_only && !self.is_editing => {
+                self.record_uri_change()
+            }
+            KeyCode::Char(_) | KeyCode::Backspace => {}
+            _ => self.is_editing = false,
+        }
+
         match key_event.code {
             KeyCode::Esc => return Ok(Some(RequestUriEvent::RemoveSelection)),
             KeyCode::Tab => return Ok(Some(RequestUriEvent::SelectNext)),
             KeyCode::BackTab => return Ok(Some(RequestUriEvent::SelectPrev)),
-            KeyCode::Char(c) => {
+            KeyCode::Char(c) if !is_read_only => {
                 if let Some(req) = self
                     .collection_store
                     .borrow()
@@ -131,7 +203,7 @@ impl Eventful for RequestUri<'_> {
                     req.write().unwrap().uri.push(c);
                 }
             }
-            KeyCode::Backspace => {
+            KeyCode::Backspace if !is_read_only => {
                 if let Some(req) = self
                     .collection_store
                     .borrow()
@@ -1,15 +1,33 @@
+use hac_colors::Indicator;
+use hac_core::collection::highlights::{HighlightColor, Highlights};
+use hac_core::json_path;
+use hac_core::json_table::{self, JsonTable};
+use hac_core::json_tree::JsonTree;
+use hac_core::line_diff::{self, DiffLine};
+use hac_core::ndjson::{self, NdjsonRecord};
+use hac_core::net::body_limit::{self, BodySource};
+use hac_core::net::download::human_bytes;
+use hac_core::net::mime::{self, MimeHandler, MimeRegistry, Preview};
+use hac_core::net::rate_limit::RateLimit;
 use hac_core::net::request_manager::Response;
+use hac_core::net::status_codes;
+use hac_core::net::tls_inspection::TlsInfo;
 use hac_core::syntax::highlighter::HIGHLIGHTER;
+use hac_core::timestamp;
+use hac_core::viewer_preferences::{ViewerPreferences, ViewerTab};
 
 use crate::ascii::{BIG_ERROR_ARTS, LOGO_ASCII, SMALL_ERROR_ARTS};
+use crate::clipboard;
+use crate::i18n::Message;
 use crate::pages::collection_viewer::collection_viewer::PaneFocus;
 use crate::pages::under_construction::UnderConstruction;
 use crate::pages::{spinner::Spinner, Eventful, Renderable};
 use crate::utils::build_syntax_highlighted_lines;
 
 use std::cell::RefCell;
+use std::collections::HashSet;
 use std::iter;
-use std::ops::{Add, Sub};
+use std::ops::{Add, Div, Sub};
 use std::rc::Rc;
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
@@ -18,42 +36,80 @@ use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Padding, Paragraph, Scrollbar};
-use ratatui::widgets::{ScrollbarOrientation, ScrollbarState, Tabs};
+use ratatui::widgets::{ScrollbarOrientation, ScrollbarState, Tabs, Wrap};
 use ratatui::Frame;
 use tree_sitter::Tree;
 
 use super::collection_store::CollectionStore;
 
+/// widest a column of the table view gets, longer values are cut
+const MAX_COLUMN_WIDTH: usize = 30;
+
 #[derive(Debug)]
 pub enum ResponseViewerEvent {
     RemoveSelection,
     Quit,
+    /// a message to show on the corner of the screen, with its title and
+    /// whether it is a failure
+    Notify(Message, String, bool),
+    /// asks for the filter of the selected request, starting from the
+    /// current one
+    EditFilter(Option<String>),
+    /// asks for the TLS session of the server of the selected request to be
+    /// inspected and shown along with the headers
+    InspectTls,
+    /// asks for the whole body of the response to be loaded back, as only
+    /// its start is in memory
+    LoadBody(BodyUse),
+}
+
+/// what the whole body of a response is loaded back for, it is used once it
+/// is loaded
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BodyUse {
+    /// shown in place of its start
+    Show,
+    /// narrowed down by the filter, pinned and shown as a tree or table
+    Refresh,
+    Save,
+    Copy,
+    Diff,
+    Pager,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ResViewerTabs {
     Preview,
+    Tree,
+    Table,
     Raw,
     Cookies,
     Headers,
+    Status,
 }
 
 impl ResViewerTabs {
     pub fn next(tab: &ResViewerTabs) -> Self {
         match tab {
-            Self::Preview => ResViewerTabs::Raw,
+            Self::Preview => ResViewerTabs::Tree,
+            Self::Tree => ResViewerTabs::Table,
+            Self::Table => ResViewerTabs::Raw,
             Self::Raw => ResViewerTabs::Headers,
             Self::Headers => ResViewerTabs::Cookies,
-            Self::Cookies => ResViewerTabs::Preview,
+            Self::Cookies => ResViewerTabs::Status,
+            Self::Status => ResViewerTabs::Preview,
         }
     }
 
     pub fn prev(tab: &ResViewerTabs) -> Self {
         match tab {
-            Self::Preview => ResViewerTabs::Cookies,
-            Self::Raw => ResViewerTabs::Preview,
+            Self::Preview => ResViewerTabs::Status,
+            Self::Tree => ResViewerTabs::Preview,
+            Self::Table => ResViewerTabs::Tree,
+            Self::Raw => ResViewerTabs::Table,
             Self::Headers => ResViewerTabs::Raw,
             Self::Cookies => ResViewerTabs::Headers,
+            Self::Status => ResViewerTabs::Cookies,
         }
     }
 }
@@ -62,9 +118,40 @@ impl From<ResViewerTabs> for usize {
     fn from(value: ResViewerTabs) -> Self {
         match value {
             ResViewerTabs::Preview => 0,
-            ResViewerTabs::Raw => 1,
-            ResViewerTabs::Headers => 2,
-            ResViewerTabs::Cookies => 3,
+            ResViewerTabs::Tree => 1,
+            ResViewerTabs::Table => 2,
+            ResViewerTabs::Raw => 3,
+            ResViewerTabs::Headers => 4,
+            ResViewerTabs::Cookies => 5,
+            ResViewerTabs::Status => 6,
+        }
+    }
+}
+
+impl From<ViewerTab> for ResViewerTabs {
+    fn from(value: ViewerTab) -> Self {
+        match value {
+            ViewerTab::Pretty => ResViewerTabs::Preview,
+            ViewerTab::Tree => ResViewerTabs::Tree,
+            ViewerTab::Table => ResViewerTabs::Table,
+            ViewerTab::Raw => ResViewerTabs::Raw,
+            ViewerTab::Headers => ResViewerTabs::Headers,
+            ViewerTab::Cookies => ResViewerTabs::Cookies,
+            ViewerTab::Status => ResViewerTabs::Status,
+        }
+    }
+}
+
+impl From<&ResViewerTabs> for ViewerTab {
+    fn from(value: &ResViewerTabs) -> Self {
+        match value {
+            ResViewerTabs::Preview => ViewerTab::Pretty,
+            ResViewerTabs::Tree => ViewerTab::Tree,
+            ResViewerTabs::Table => ViewerTab::Table,
+            ResViewerTabs::Raw => ViewerTab::Raw,
+            ResViewerTabs::Headers => ViewerTab::Headers,
+            ResViewerTabs::Cookies => ViewerTab::Cookies,
+            ResViewerTabs::Status => ViewerTab::Status,
         }
     }
 }
@@ -86,6 +173,61 @@ struct PreviewLayout {
 pub struct ResponseViewer<'a> {
     colors: &'a hac_colors::Colors,
     response: Option<Rc<RefCell<Response>>>,
+    /// the last real response, kept so we can go back to it after browsing
+    /// the example responses of the request
+    live_response: Option<Rc<RefCell<Response>>>,
+    /// index of the example response being displayed, if any
+    example_index: Option<usize>,
+    /// what was overridden when sending the live response, if anything
+    overrides: Option<String>,
+    /// whether epoch timestamps on the preview are annotated with a readable
+    /// date
+    show_timestamps: bool,
+    /// whether the view is kept at the end of the body, following streamed
+    /// bodies as they grow
+    follow: bool,
+    /// bodies larger than this many bytes only have their start shown
+    display_limit: u64,
+    /// whether the whole body is shown even when over the limit
+    full_body: bool,
+    /// whether a view needed the whole body of the response while only its
+    /// start was in memory
+    body_wanted: bool,
+    /// the response of the send before the current one, to compare with
+    previous: Option<Rc<RefCell<Response>>>,
+    /// whether the body shows what changed since the previous send
+    compare: bool,
+    /// whether long lines of the pretty body wrap instead of being cut
+    wrap: bool,
+    /// JSONPath the body is narrowed down to
+    filter: Option<String>,
+    /// the request whose preferences the viewer is showing its responses
+    /// with
+    preferences_request: Option<String>,
+    /// the pinned paths of the request with the values they point to on
+    /// the displayed response
+    pinned: Vec<(String, Option<String>)>,
+    /// the body as a foldable tree, built the first time the tab is shown
+    /// after the response changes
+    json_tree: Option<JsonTree>,
+    json_tree_stale: bool,
+    tree_cursor: usize,
+    tree_scroll: usize,
+    /// the body as a table when it is a list of objects, built the first
+    /// time the tab is shown after the response changes
+    json_table: Option<JsonTable>,
+    json_table_stale: bool,
+    table_scroll: usize,
+    /// the selected column, shown as the first one of the view
+    table_column: usize,
+    /// the column the rows are ordered by, and whether it is descending
+    table_sort: Option<(usize, bool)>,
+    /// how responses are shown, by their content type
+    mime: MimeRegistry,
+    /// records of ndjson bodies folded into a single line
+    folded_records: HashSet<usize>,
+    /// the line of the pretty view each record of ndjson bodies starts on
+    record_starts: Vec<usize>,
     tree: Option<Tree>,
     lines: Vec<Line<'static>>,
     error_lines: Option<Vec<Line<'static>>>,
@@ -98,6 +240,7 @@ pub struct ResponseViewer<'a> {
     headers_scroll_y: usize,
     headers_scroll_x: usize,
     pretty_scroll: usize,
+    status_scroll: usize,
 }
 
 impl<'a> ResponseViewer<'a> {
@@ -106,6 +249,7 @@ impl<'a> ResponseViewer<'a> {
         collection_store: Rc<RefCell<CollectionStore>>,
         response: Option<Rc<RefCell<Response>>>,
         size: Rect,
+        display_limit: u64,
     ) -> Self {
         let tree = response.as_ref().and_then(|response| {
             if let Some(ref pretty_body) = response.borrow().pretty_body {
@@ -124,6 +268,32 @@ impl<'a> ResponseViewer<'a> {
 
         ResponseViewer {
             colors,
+            live_response: response.clone(),
+            example_index: None,
+            overrides: None,
+            show_timestamps: false,
+            follow: false,
+            display_limit,
+            full_body: false,
+            body_wanted: false,
+            previous: None,
+            compare: false,
+            wrap: false,
+            filter: None,
+            preferences_request: None,
+            pinned: vec![],
+            json_tree: None,
+            json_tree_stale: true,
+            tree_cursor: 0,
+            tree_scroll: 0,
+            json_table: None,
+            json_table_stale: true,
+            mime: MimeRegistry::default(),
+            folded_records: HashSet::default(),
+            record_starts: vec![],
+            table_scroll: 0,
+            table_column: 0,
+            table_sort: None,
             response,
             tree,
             lines: vec![],
@@ -136,6 +306,7 @@ impl<'a> ResponseViewer<'a> {
             headers_scroll_y: 0,
             headers_scroll_x: 0,
             pretty_scroll: 0,
+            status_scroll: 0,
             collection_store,
         }
     }
@@ -145,7 +316,36 @@ impl<'a> ResponseViewer<'a> {
         self.preview_layout = build_preview_layout(self.layout.content_pane);
     }
 
+    /// flags the current response as sent with overrides, with a summary of
+    /// what was changed
+    pub fn set_overrides(&mut self, overrides: Option<String>) {
+        self.overrides = overrides;
+    }
+
+    /// the response of the previous send of the request, if there was one
+    pub fn set_previous(&mut self, previous: Option<Rc<RefCell<Response>>>) {
+        self.previous = previous;
+    }
+
     pub fn update(&mut self, response: Option<Rc<RefCell<Response>>>) {
+        let same_response = match (self.live_response.as_ref(), response.as_ref()) {
+            (Some(live), Some(response)) => Rc::ptr_eq(live, response),
+            _ => false,
+        };
+        if !same_response {
+            self.full_body = false;
+            self.folded_records.clear();
+        }
+        self.live_response = response.clone();
+        if !same_response && response.is_some() {
+            self.open_default_tab(response.as_ref());
+        }
+        self.example_index = None;
+        self.display(response);
+    }
+
+    fn display(&mut self, response: Option<Rc<RefCell<Response>>>) {
+        self.response = response.clone();
         let body_str = response
             .as_ref()
             .and_then(|res| {
@@ -156,9 +356,33 @@ impl<'a> ResponseViewer<'a> {
             })
             .unwrap_or_default();
 
-        if body_str.len().gt(&0) {
+        let filtered = self.apply_filter();
+        let body_str = match filtered.as_ref() {
+            Some(Ok(filtered)) => filtered.clone(),
+            _ => body_str,
+        };
+
+        // large bodies freeze the viewer while highlighted, so only their
+        // start is shown until the whole of it is asked for
+        let body_str = match self.truncation() {
+            Some(_) => body_limit::head(&body_str, self.display_limit as usize).to_string(),
+            None => body_str,
+        };
+
+        self.record_starts.clear();
+        if let Some(records) = self.ndjson_records(filtered.is_some()) {
+            self.tree = None;
+            self.lines = self.build_ndjson_lines(&records);
+        } else if body_str.len().gt(&0) {
             self.tree = HIGHLIGHTER.write().unwrap().parse(&body_str);
             self.lines = build_syntax_highlighted_lines(&body_str, self.tree.as_ref(), self.colors);
+            let highlights = self.highlights();
+            if !highlights.is_empty() {
+                self.lines = std::mem::take(&mut self.lines)
+                    .into_iter()
+                    .map(|line| apply_highlights(line, &highlights, self.colors))
+                    .collect();
+            }
         } else {
             self.tree = None;
             self.lines = vec![];
@@ -195,6 +419,465 @@ impl<'a> ResponseViewer<'a> {
 
         self.empty_lines = make_empty_ascii_art(self.colors);
         self.response = response;
+        self.json_tree_stale = true;
+        self.json_table_stale = true;
+        self.refresh_pinned();
+        if self.compare && self.example_index.is_none() {
+            self.compare_with_previous(&body_str);
+        }
+        if let Some(banner) = self.truncation_banner() {
+            self.lines.insert(0, banner);
+        }
+        if let (Some(filter), Some(filtered)) = (self.filter.as_ref(), filtered) {
+            let banner = match filtered {
+                Ok(_) => Line::from(Message::FilteredBy.format(&[filter]))
+                    .fg(self.colors.normal.blue)
+                    .bold(),
+                Err(e) => Line::from(e.to_string()).fg(self.colors.normal.red).bold(),
+            };
+            self.lines.insert(0, banner);
+        }
+    }
+
+    /// the records of ndjson bodies once every line arrived. Filtered,
+    /// compared and truncated bodies are shown as text
+    fn ndjson_records(&self, filtered: bool) -> Option<Vec<NdjsonRecord>> {
+        let response = self.response.as_ref()?.borrow();
+        let shown_as_text = filtered || self.compare || self.truncation().is_some();
+        if shown_as_text || response.streaming || self.mime_handler().name.ne("ndjson") {
+            return None;
+        }
+        ndjson::parse(response.body.as_deref()?)
+    }
+
+    /// every record under a line with its index, pretty printed on its own
+    /// or summarized on a single line when folded
+    fn build_ndjson_lines(&mut self, records: &[NdjsonRecord]) -> Vec<Line<'static>> {
+        let mut lines = vec![];
+        for record in records {
+            self.record_starts.push(lines.len());
+            let index = format!("#{}", record.index);
+            if self.folded_records.contains(&record.index) {
+                lines.push(Line::from(vec![
+                    format!("▸ {index} ").fg(self.colors.normal.blue).bold(),
+                    record.summary().fg(self.colors.bright.black),
+                ]));
+                continue;
+            }
+            lines.push(Line::from(
+                format!("▾ {index}").fg(self.colors.normal.blue).bold(),
+            ));
+            let tree = HIGHLIGHTER.write().unwrap().parse(record.text());
+            lines.extend(build_syntax_highlighted_lines(
+                record.text(),
+                tree.as_ref(),
+                self.colors,
+            ));
+        }
+        lines
+    }
+
+    /// handles the keys that fold and move between the records of ndjson
+    /// bodies, returning whether the key was used
+    fn handle_ndjson_key(&mut self, key_event: KeyEvent) -> bool {
+        let Some(current) = ndjson::record_at(&self.record_starts, self.pretty_scroll) else {
+            return false;
+        };
+        let last = self.record_starts.len().sub(1);
+        let target = match key_event.code {
+            KeyCode::Char(']') => current.add(1).min(last),
+            KeyCode::Char('[') if self.pretty_scroll.gt(&self.record_starts[current]) => current,
+            KeyCode::Char('[') => current.saturating_sub(1),
+            KeyCode::Char('z') => {
+                if !self.folded_records.remove(&current) {
+                    self.folded_records.insert(current);
+                }
+                self.display(self.response.clone());
+                current
+            }
+            // folds every record, or unfolds them all when they are folded
+            KeyCode::Char('Z') => {
+                match self.folded_records.len().gt(&last) {
+                    true => self.folded_records.clear(),
+                    false => self.folded_records = (0..=last).collect(),
+                }
+                self.display(self.response.clone());
+                current
+            }
+            _ => return false,
+        };
+        self.follow = false;
+        self.pretty_scroll = self.record_starts.get(target).copied().unwrap_or_default();
+        true
+    }
+
+    /// the body narrowed down to the filter, when there is one and the
+    /// response is not an example
+    fn apply_filter(&mut self) -> Option<anyhow::Result<String>> {
+        let filter = self.filter.clone()?;
+        if self.example_index.is_some() {
+            return None;
+        }
+        let body = self.whole_body()?;
+        Some(json_path::filter(&body, &filter))
+    }
+
+    /// the whole body of the displayed response, asking for it to be loaded
+    /// back when only its start is in memory
+    fn whole_body(&mut self) -> Option<String> {
+        let response = self.response.clone()?;
+        let response = response.borrow();
+        let body = body_limit::full_body(&response);
+        self.body_wanted |= body.is_none() && response.body_file.is_some();
+        body
+    }
+
+    /// whether a view needed the whole body of the response since the last
+    /// time this was asked
+    pub fn take_body_wanted(&mut self) -> bool {
+        std::mem::take(&mut self.body_wanted)
+    }
+
+    /// displays the response again, after more of its body was loaded
+    pub fn refresh(&mut self) {
+        self.display(self.response.clone());
+    }
+
+    /// narrows the body of the responses of the selected request down to the
+    /// path, or shows all of it again
+    pub fn set_filter(&mut self, filter: Option<String>) {
+        self.filter = filter;
+        self.store_preferences();
+        self.display(self.response.clone());
+    }
+
+    /// shows responses the way the user last looked at the ones of the
+    /// selected request, once it changes
+    fn restore_preferences(&mut self) {
+        let store = self.collection_store.borrow();
+        let request_id = store
+            .get_selected_request()
+            .map(|request| request.read().unwrap().id.clone());
+        if request_id.eq(&self.preferences_request) {
+            return;
+        }
+        let preferences = match (request_id.as_ref(), store.get_viewer_preferences()) {
+            (Some(id), Some(preferences)) => preferences.borrow().get(id),
+            _ => ViewerPreferences::default(),
+        };
+        drop(store);
+
+        self.preferences_request = request_id;
+        self.active_tab = preferences.tab.into();
+        self.wrap = preferences.wrap;
+        self.show_timestamps = preferences.timestamps;
+        if self.filter.ne(&preferences.filter) {
+            self.filter = preferences.filter;
+            self.display(self.response.clone());
+        }
+    }
+
+    /// the content type of the displayed response
+    fn content_type(&self) -> String {
+        self.response
+            .as_ref()
+            .and_then(|response| {
+                let response = response.borrow();
+                let content_type = response.headers.as_ref()?.get("Content-Type")?;
+                content_type.to_str().ok().map(String::from)
+            })
+            .unwrap_or_default()
+    }
+
+    /// how the displayed response is shown
+    fn mime_handler(&self) -> &MimeHandler {
+        self.mime.handler(&self.content_type())
+    }
+
+    /// responses open on the tab of their content type, like the table for
+    /// csv, unless the user picked a tab for the request
+    fn open_default_tab(&mut self, response: Option<&Rc<RefCell<Response>>>) {
+        let store = self.collection_store.borrow();
+        let picked_tab = match (store.get_selected_request(), store.get_viewer_preferences()) {
+            (Some(request), Some(preferences)) => {
+                preferences.borrow().contains(&request.read().unwrap().id)
+            }
+            _ => false,
+        };
+        drop(store);
+        let Some(headers) = response.and_then(|response| response.borrow().headers.clone()) else {
+            return;
+        };
+        if !picked_tab {
+            self.active_tab = self.mime.for_headers(&headers).tab.into();
+        }
+    }
+
+    fn store_preferences(&self) {
+        let store = self.collection_store.borrow();
+        let (Some(request), Some(preferences)) =
+            (store.get_selected_request(), store.get_viewer_preferences())
+        else {
+            return;
+        };
+        preferences.borrow_mut().set(
+            &request.read().unwrap().id,
+            ViewerPreferences {
+                tab: (&self.active_tab).into(),
+                wrap: self.wrap,
+                timestamps: self.show_timestamps,
+                filter: self.filter.clone(),
+            },
+        );
+    }
+
+    /// marks the lines of the body that changed since the previous send,
+    /// showing the ones that are gone where they used to be
+    fn compare_with_previous(&mut self, body_str: &str) {
+        let previous = self.previous.as_ref().map(|previous| {
+            let previous = previous.borrow();
+            let body = previous
+                .pretty_body
+                .as_ref()
+                .map(|body| body.to_string())
+                .unwrap_or_default();
+            body_limit::head(&body, self.display_limit as usize).to_string()
+        });
+        let Some(previous) = previous else {
+            self.lines.insert(
+                0,
+                Line::from(Message::NoPreviousResponse.text())
+                    .fg(self.colors.bright.black)
+                    .bold(),
+            );
+            return;
+        };
+
+        let diff = line_diff::diff_lines(&previous, body_str);
+        let lines = std::mem::take(&mut self.lines);
+        let (mut added, mut removed) = (0, 0);
+        self.lines = diff
+            .into_iter()
+            .map(|line| match line {
+                DiffLine::Same(idx) => {
+                    let mut line = lines.get(idx).cloned().unwrap_or_default();
+                    line.spans.insert(0, "  ".into());
+                    line
+                }
+                DiffLine::Added(idx) => {
+                    added += 1;
+                    let mut line = lines.get(idx).cloned().unwrap_or_default();
+                    line.spans
+                        .insert(0, "+ ".fg(self.colors.normal.green).bold());
+                    line.bg(self.colors.primary.hover)
+                }
+                DiffLine::Removed(text) => {
+                    removed += 1;
+                    Line::from(vec![
+                        "- ".fg(self.colors.normal.red).bold(),
+                        text.fg(self.colors.normal.red),
+                    ])
+                }
+            })
+            .collect();
+
+        let summary = match added + removed {
+            0 => Message::NothingChanged.text().to_string(),
+            _ => Message::ChangedSincePrevious.format(&[&added.to_string(), &removed.to_string()]),
+        };
+        self.lines
+            .insert(0, Line::from(summary).fg(self.colors.normal.yellow).bold());
+    }
+
+    /// takes the values of the pinned paths of the selected request out of
+    /// the displayed response again, after either of them changed
+    pub fn refresh_pinned(&mut self) {
+        let paths = self
+            .collection_store
+            .borrow()
+            .get_selected_request()
+            .map(|request| request.read().unwrap().pinned.clone())
+            .unwrap_or_default();
+        let body = match paths.is_empty() {
+            true => None,
+            false => self.whole_body(),
+        };
+        self.pinned = match body {
+            Some(body) => json_path::extract(&paths, &body),
+            None => paths.into_iter().map(|path| (path, None)).collect(),
+        };
+    }
+
+    /// the highlight rules of the collection, invalid patterns are logged
+    /// and ignored
+    fn highlights(&self) -> Highlights {
+        let Some(collection) = self.collection_store.borrow().get_collection() else {
+            return Highlights::default();
+        };
+        let (highlights, errors) = Highlights::compile(&collection.borrow().highlights);
+        errors.iter().for_each(|error| tracing::warn!("{error}"));
+        highlights
+    }
+
+    /// the size of the body of the displayed response, when only its start
+    /// is shown
+    fn truncation(&self) -> Option<u64> {
+        let response = self.response.as_ref()?.borrow();
+        let size = response
+            .body_size
+            .unwrap_or_else(|| response.body.as_ref().map(String::len).unwrap_or_default() as u64);
+        let truncated = size.gt(&self.display_limit) || response.body_file.is_some();
+        (truncated && !self.full_body).then_some(size)
+    }
+
+    fn truncation_banner(&self) -> Option<Line<'static>> {
+        let size = self.truncation()?;
+        let shown = self.display_limit.min(size);
+        Some(Line::from(
+            Message::BodyTruncated
+                .format(&[&human_bytes(shown), &human_bytes(size)])
+                .fg(self.colors.normal.yellow)
+                .bold(),
+        ))
+    }
+
+    /// shows the whole body of the response, asking for it to be loaded
+    /// back first when only its start is in memory
+    fn load_full_body(&mut self) -> Option<ResponseViewerEvent> {
+        let response = self.response.clone()?;
+        self.truncation()?;
+        if BodySource::of(&response.borrow()).is_some() {
+            return Some(ResponseViewerEvent::LoadBody(BodyUse::Show));
+        }
+        self.show_full_body();
+        None
+    }
+
+    /// shows the whole body of the response, once all of it is in memory
+    pub fn show_full_body(&mut self) {
+        self.full_body = true;
+        self.display(self.response.clone());
+    }
+
+    /// writes the whole body of the response to the working directory,
+    /// named after the request and when it was saved
+    pub fn save_body(&mut self) -> Option<ResponseViewerEvent> {
+        let response = self.response.clone()?;
+        let spilled = response.borrow().raw_body.is_none() && response.borrow().body_file.is_some();
+        if spilled {
+            return Some(ResponseViewerEvent::LoadBody(BodyUse::Save));
+        }
+        let name = self
+            .collection_store
+            .borrow()
+            .get_selected_request()
+            .map(|request| request.read().unwrap().name.clone())
+            .unwrap_or_else(|| "response".into());
+        let name = name
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '-' })
+            .collect::<String>();
+        let extension = self.mime_handler().extension(&self.content_type());
+        let file = format!(
+            "{name}-{}.{extension}",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        );
+
+        let response = response.borrow();
+        let saved = match response.raw_body.as_ref() {
+            Some(bytes) => std::fs::write(&file, bytes),
+            None => std::fs::write(&file, body_limit::full_body(&response).unwrap_or_default()),
+        };
+        Some(match saved {
+            Ok(()) => ResponseViewerEvent::Notify(
+                Message::Body,
+                Message::BodySaved.format(&[&file]),
+                false,
+            ),
+            Err(e) => ResponseViewerEvent::Notify(Message::Body, e.to_string(), true),
+        })
+    }
+
+    /// stores the current response as an example on the selected request,
+    /// naming it after its status, eg: `200 OK`
+    fn save_as_example(&mut self) {
+        let store = self.collection_store.borrow();
+        if store.is_read_only() || self.example_index.is_some() {
+            return;
+        }
+
+        let (Some(request), Some(response)) =
+            (store.get_selected_request(), self.live_response.as_ref())
+        else {
+            return;
+        };
+
+        let mut request = request.write().unwrap();
+        let Some(status) = response.borrow().status else {
+            return;
+        };
+
+        let base_name = match status.canonical_reason() {
+            Some(reason) => format!("{} {}", status.as_str(), reason),
+            None => status.as_str().to_string(),
+        };
+        let name = (1..)
+            .map(|n| match n {
+                1 => base_name.clone(),
+                n => format!("{} ({})", base_name, n),
+            })
+            .find(|name| !request.examples.iter().any(|ex| ex.name.eq(name)))
+            .expect("there is always an unused name");
+
+        if let Some(example) = response.borrow().to_example(name) {
+            request.examples.push(example);
+        }
+    }
+
+    /// cycles through the example responses of the selected request, going
+    /// back to the last real response after the last example
+    fn show_next_example(&mut self) {
+        let examples = self
+            .collection_store
+            .borrow()
+            .get_selected_request()
+            .map(|request| request.read().unwrap().examples.clone())
+            .unwrap_or_default();
+
+        let next = match self.example_index {
+            None => 0,
+            Some(idx) => idx.add(1),
+        };
+
+        match examples.get(next) {
+            Some(example) => {
+                self.example_index = Some(next);
+                let response = Response::from_example(example);
+                self.display(Some(Rc::new(RefCell::new(response))));
+            }
+            None => {
+                self.example_index = None;
+                self.display(self.live_response.clone());
+            }
+        }
+
+        self.pretty_scroll = 0;
+        self.raw_scroll = 0;
+    }
+
+    /// index of the example being displayed instead of the response, if any
+    pub fn example_index(&self) -> Option<usize> {
+        self.example_index
+    }
+
+    fn example_name(&self) -> Option<String> {
+        let idx = self.example_index?;
+        let request = self.collection_store.borrow().get_selected_request()?;
+        let request = request.read().unwrap();
+        request
+            .examples
+            .get(idx)
+            .map(|example| example.name.clone())
     }
 
     fn draw_container(&self, size: Rect, frame: &mut Frame) {
@@ -227,14 +910,16 @@ impl<'a> ResponseViewer<'a> {
     }
 
     fn draw_tabs(&self, frame: &mut Frame, size: Rect) {
-        let tabs = Tabs::new(["Pretty", "Raw", "Headers", "Cookies"])
-            .style(Style::default().fg(self.colors.bright.black))
-            .select(self.active_tab.clone().into())
-            .highlight_style(
-                Style::default()
-                    .fg(self.colors.normal.white)
-                    .bg(self.colors.normal.blue),
-            );
+        let tabs = Tabs::new([
+            "Pretty", "Tree", "Table", "Raw", "Headers", "Cookies", "Status",
+        ])
+        .style(Style::default().fg(self.colors.bright.black))
+        .select(self.active_tab.clone().into())
+        .highlight_style(
+            Style::default()
+                .fg(self.colors.normal.white)
+                .bg(self.colors.normal.blue),
+        );
         frame.render_widget(tabs, size);
     }
 
@@ -344,30 +1029,121 @@ impl<'a> ResponseViewer<'a> {
         {
             match self.active_tab {
                 ResViewerTabs::Preview => self.draw_pretty_response(frame, size),
+                ResViewerTabs::Tree => self.draw_json_tree(frame, size),
+                ResViewerTabs::Table => self.draw_json_table(frame, size),
                 ResViewerTabs::Raw => self.draw_raw_response(frame, size),
                 ResViewerTabs::Headers => self.draw_response_headers(frame),
                 ResViewerTabs::Cookies => UnderConstruction::new(self.colors).draw(frame, size)?,
+                ResViewerTabs::Status => self.draw_status_explanation(frame, size),
             }
         }
 
-        if self.collection_store.borrow().has_pending_request() {
+        let streaming = self
+            .response
+            .as_ref()
+            .is_some_and(|res| res.borrow().streaming);
+        if self.collection_store.borrow().has_pending_request() && !streaming {
             self.draw_spinner(frame);
         }
 
         Ok(())
     }
 
+    /// explains what the status of the response means, what usually causes it
+    /// and the values of the headers that are relevant to it
+    fn draw_status_explanation(&mut self, frame: &mut Frame, size: Rect) {
+        let Some(response) = self.response.as_ref() else {
+            return;
+        };
+        let response = response.borrow();
+        let Some(status) = response.status else {
+            return;
+        };
+
+        let code = status.as_u16();
+        let info = status_codes::lookup(code);
+        let title = match (info, status.canonical_reason()) {
+            (Some(info), _) => format!("{} {}", code, info.name),
+            (None, Some(reason)) => format!("{} {}", code, reason),
+            (None, None) => code.to_string(),
+        };
+
+        let mut lines: Vec<Line> = vec![
+            Line::from(title.fg(self.colors.normal.red).bold()),
+            Line::from(""),
+            Line::from(status_codes::describe_class(code).fg(self.colors.bright.black)),
+        ];
+
+        if let Some(info) = info {
+            lines.push(Line::from(info.meaning.fg(self.colors.normal.white)));
+
+            if !info.causes.is_empty() {
+                lines.push(Line::from(""));
+                lines.push(Line::from(
+                    Message::CommonCauses
+                        .text()
+                        .fg(self.colors.normal.red)
+                        .bold(),
+                ));
+                info.causes.iter().for_each(|cause| {
+                    lines.push(Line::from(vec![
+                        "• ".fg(self.colors.normal.yellow),
+                        cause.fg(self.colors.normal.white),
+                    ]))
+                });
+            }
+
+            if !info.headers.is_empty() {
+                lines.push(Line::from(""));
+                lines.push(Line::from(
+                    Message::HeadersToCheck
+                        .text()
+                        .fg(self.colors.normal.red)
+                        .bold(),
+                ));
+                info.headers.iter().for_each(|name| {
+                    let value = response
+                        .headers
+                        .as_ref()
+                        .and_then(|headers| headers.get(*name))
+                        .and_then(|value| value.to_str().ok());
+                    lines.push(Line::from(vec![
+                        format!("{}: ", name).fg(self.colors.normal.yellow),
+                        match value {
+                            Some(value) => value.to_string().fg(self.colors.normal.white),
+                            None => Message::NotOnResponse.text().fg(self.colors.bright.black),
+                        },
+                    ]));
+                });
+            }
+        }
+
+        frame.render_widget(
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: false })
+                .scroll((self.status_scroll as u16, 0)),
+            size,
+        );
+    }
+
     fn draw_response_headers(&mut self, frame: &mut Frame) {
         if let Some(response) = self.response.as_ref() {
-            if let Some(headers) = response.borrow().headers.as_ref() {
+            // failed requests have no headers, but the session negotiated
+            // with their server may tell why they failed
+            let (headers, tls) = {
+                let response = response.borrow();
+                (response.headers.clone(), response.tls.clone())
+            };
+            if headers.is_some() || tls.is_some() {
+                let headers = headers.unwrap_or_default();
                 let mut longest_line: usize = 0;
 
                 let mut lines: Vec<Line> = vec![
-                    Line::from("Headers".fg(self.colors.normal.red).bold()),
+                    Line::from(Message::Headers.text().fg(self.colors.normal.red).bold()),
                     Line::from(""),
                 ];
 
-                for (name, value) in headers {
+                for (name, value) in &headers {
                     if let Ok(value) = value.to_str() {
                         let name_string = name.to_string();
                         let aux = name_string.len().max(value.len());
@@ -390,6 +1166,43 @@ impl<'a> ResponseViewer<'a> {
                     }
                 }
 
+                // interim responses are easy to lose track of, as clients
+                // and proxies usually hide them
+                let interim = response.borrow().interim.clone();
+                if !interim.is_empty() {
+                    lines.push(Line::from(
+                        Message::InterimResponses
+                            .text()
+                            .fg(self.colors.normal.red)
+                            .bold(),
+                    ));
+                    lines.push(Line::from(""));
+                }
+                for interim in interim {
+                    lines.push(Line::from(
+                        format!("{} {}ms", interim.status, interim.elapsed.as_millis())
+                            .bold()
+                            .yellow(),
+                    ));
+                    for (name, value) in interim.headers.iter() {
+                        let line = format!("{name}: {}", value.to_str().unwrap_or_default());
+                        longest_line = line.len().max(longest_line);
+                        lines.push(Line::from(
+                            line.chars().skip(self.headers_scroll_x).collect::<String>(),
+                        ));
+                    }
+                    lines.push(Line::from(""));
+                }
+
+                if let Some(tls) = tls.as_ref() {
+                    for line in self.tls_lines(tls) {
+                        // the lines come already scrolled
+                        let width = line.width().add(self.headers_scroll_x);
+                        longest_line = width.max(longest_line);
+                        lines.push(line);
+                    }
+                }
+
                 if self
                     .headers_scroll_y
                     // we add a blank line after every entry, we account for that here
@@ -444,23 +1257,98 @@ impl<'a> ResponseViewer<'a> {
         }
     }
 
+    /// the session negotiated with the server, and the certificates it
+    /// presented along with when they expire
+    fn tls_lines(&self, tls: &TlsInfo) -> Vec<Line<'static>> {
+        let scroll = |text: String| text.chars().skip(self.headers_scroll_x).collect::<String>();
+        let mut lines = vec![
+            Line::from(Message::Tls.text().fg(self.colors.normal.red).bold()),
+            Line::from(""),
+            Line::from(
+                scroll(format!("{} {}", tls.version, tls.cipher_suite))
+                    .bold()
+                    .yellow(),
+            ),
+        ];
+        if let Some(alpn) = tls.alpn.as_ref() {
+            lines.push(Line::from(scroll(format!("ALPN: {alpn}"))));
+        }
+        if let Some(reason) = tls.untrusted.as_ref() {
+            lines.push(Line::from(
+                scroll(format!("{}{reason}", Message::Untrusted.text())).fg(self.colors.normal.red),
+            ));
+        }
+        lines.push(Line::from(""));
+
+        let now = chrono::Utc::now();
+        for certificate in tls.chain.iter() {
+            lines.push(Line::from(
+                scroll(certificate.subject.clone()).bold().yellow(),
+            ));
+            lines.push(Line::from(scroll(format!(
+                "{}{}",
+                Message::IssuedBy.text(),
+                certificate.issuer
+            ))));
+
+            let days_left = certificate.days_left(now);
+            let (expiry, color) = match days_left {
+                days if days < 0 => (
+                    Message::ExpiredAgo.format(&[&days.abs().to_string()]),
+                    self.colors.normal.red,
+                ),
+                days if days < 30 => (
+                    Message::ExpiresIn.format(&[&days.to_string()]),
+                    self.colors.normal.yellow,
+                ),
+                days => (
+                    Message::ExpiresIn.format(&[&days.to_string()]),
+                    self.colors.normal.green,
+                ),
+            };
+            lines.push(Line::from(
+                scroll(format!(
+                    "{} - {} ({expiry})",
+                    certificate.not_before.format("%Y-%m-%d"),
+                    certificate.not_after.format("%Y-%m-%d"),
+                ))
+                .fg(color),
+            ));
+            if !certificate.sans.is_empty() {
+                lines.push(Line::from(scroll(format!(
+                    "SAN: {}",
+                    certificate.sans.join(", ")
+                ))));
+            }
+            lines.push(Line::from(""));
+        }
+
+        lines
+    }
+
     fn draw_raw_response(&mut self, frame: &mut Frame, size: Rect) {
         if let Some(response) = self.response.as_ref() {
-            let lines = if response.borrow().body.is_some() {
-                response
-                    .borrow()
-                    .body
-                    .as_ref()
-                    .unwrap()
-                    .chars()
-                    .collect::<Vec<_>>()
-                    // accounting for the scrollbar width when splitting the lines
-                    .chunks(size.width.saturating_sub(2).into())
-                    .map(|row| Line::from(row.iter().collect::<String>()))
+            let lines = if let Some(body) = response.borrow().body.as_ref() {
+                let body = match self.truncation() {
+                    Some(_) => body_limit::head(body, self.display_limit as usize),
+                    None => body,
+                };
+                self.truncation_banner()
+                    .into_iter()
+                    .chain(
+                        body.chars()
+                            .collect::<Vec<_>>()
+                            // accounting for the scrollbar width when splitting the lines
+                            .chunks(size.width.saturating_sub(2).into())
+                            .map(|row| Line::from(row.iter().collect::<String>())),
+                    )
                     .collect::<Vec<_>>()
             } else {
-                vec![Line::from("No body").centered()]
+                vec![Line::from(Message::NoBody.text()).centered()]
             };
+            if self.follow {
+                self.raw_scroll = lines.len().saturating_sub(size.height.into());
+            }
             // allow for scrolling down until theres only one line left into view
             if self.raw_scroll.ge(&lines.len().saturating_sub(1)) {
                 self.raw_scroll = lines.len().saturating_sub(1);
@@ -519,8 +1407,74 @@ impl<'a> ResponseViewer<'a> {
         frame.render_stateful_widget(scrollbar, size, &mut scrollbar_state);
     }
 
+    /// draws the values of the pinned paths above the body, returning the
+    /// space left for the body
+    fn draw_pinned(&self, frame: &mut Frame, size: Rect) -> Rect {
+        if self.pinned.is_empty() {
+            return size;
+        }
+
+        let height = (self.pinned.len() as u16).add(2).min(size.height.div(2));
+        let [pinned_size, body_size] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(height), Constraint::Fill(1)])
+            .areas(size);
+
+        let width = self
+            .pinned
+            .iter()
+            .map(|(path, _)| path.chars().count())
+            .max()
+            .unwrap_or_default();
+        let lines = self
+            .pinned
+            .iter()
+            .map(|(path, value)| {
+                let value = match value {
+                    Some(value) => value.clone().fg(self.colors.normal.white),
+                    None => Message::NoValue.text().fg(self.colors.bright.black),
+                };
+                Line::from(vec![
+                    format!("{path:<width$}  ").fg(self.colors.normal.yellow),
+                    value,
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::TOP | Borders::BOTTOM)
+                    .title(Message::Pinned.text())
+                    .border_style(Style::default().fg(self.colors.bright.black)),
+            ),
+            pinned_size,
+        );
+        body_size
+    }
+
     fn draw_pretty_response(&mut self, frame: &mut Frame, size: Rect) {
+        let is_binary = self
+            .response
+            .as_ref()
+            .is_some_and(|response| response.borrow().raw_body.is_some());
+        if is_binary && self.mime_handler().preview.ne(&Preview::Text) {
+            self.draw_binary_preview(frame);
+            return;
+        }
+
         if self.response.as_ref().is_some() {
+            let content_pane = self.draw_pinned(frame, self.preview_layout.content_pane);
+            let size = Rect {
+                y: content_pane.y,
+                height: size
+                    .height
+                    .saturating_sub(content_pane.y.saturating_sub(size.y)),
+                ..size
+            };
+            if self.follow {
+                self.pretty_scroll = self.lines.len().saturating_sub(size.height.into());
+            }
             if self.pretty_scroll.ge(&self.lines.len().saturating_sub(1)) {
                 self.pretty_scroll = self.lines.len().saturating_sub(1);
             }
@@ -535,61 +1489,510 @@ impl<'a> ResponseViewer<'a> {
             let lines = if self.lines.len().gt(&0) {
                 self.lines.clone()
             } else {
-                vec![Line::from("No body").centered()]
+                vec![Line::from(Message::NoBody.text()).centered()]
             };
 
+            let width = usize::from(content_pane.width).max(1);
             let lines_in_view = lines
                 .into_iter()
                 .skip(self.pretty_scroll)
+                .map(|line| match self.show_timestamps {
+                    true => self.annotate_timestamps(line),
+                    false => line,
+                })
+                .flat_map(|line| match self.wrap {
+                    true => wrap_line(line, width),
+                    false => vec![line],
+                })
                 .chain(iter::repeat(Line::from("~".fg(self.colors.bright.black))))
                 .take(size.height.into())
                 .collect::<Vec<_>>();
 
             let pretty_response = Paragraph::new(lines_in_view);
-            frame.render_widget(pretty_response, self.preview_layout.content_pane);
+            frame.render_widget(pretty_response, content_pane);
+        }
+    }
+
+    /// describes bodies the terminal can't show, like images and documents
+    fn draw_binary_preview(&self, frame: &mut Frame) {
+        let Some(response) = self.response.as_ref() else {
+            return;
+        };
+        let response = response.borrow();
+        let bytes = response.raw_body.as_deref().unwrap_or_default();
+
+        let mut lines = vec![Line::from(self.content_type()).bold()];
+        if self.mime_handler().preview.eq(&Preview::Image) {
+            if let Some((width, height)) = mime::image_dimensions(bytes) {
+                let (width, height) = (width.to_string(), height.to_string());
+                lines.push(Line::from(Message::ImageSize.format(&[&width, &height])));
+            }
+        }
+        lines.push(Line::from(human_bytes(bytes.len() as u64)));
+        lines.push(Line::default());
+        lines.push(Line::from(Message::SaveBinaryBody.text()).fg(self.colors.bright.black));
+
+        let content_pane = self.preview_layout.content_pane;
+        let height = (lines.len() as u16).min(content_pane.height);
+        let area = Rect::new(
+            content_pane.x,
+            content_pane
+                .y
+                .add(content_pane.height.saturating_sub(height).div(2)),
+            content_pane.width,
+            height,
+        );
+        frame.render_widget(
+            Paragraph::new(lines)
+                .fg(self.colors.normal.white)
+                .centered(),
+            area,
+        );
+    }
+
+    /// builds the tree of the body again when the response changed, keeping
+    /// the folds of the previous one
+    fn refresh_json_tree(&mut self) {
+        if !self.json_tree_stale {
+            return;
         }
+        self.json_tree_stale = false;
+        let tree = self.whole_body().and_then(|body| JsonTree::parse(&body));
+        self.json_tree = match (tree, self.json_tree.as_ref()) {
+            (Some(mut tree), Some(previous)) => {
+                tree.keep_folds(previous);
+                Some(tree)
+            }
+            (tree, _) => tree,
+        };
+    }
+
+    fn draw_json_tree(&mut self, frame: &mut Frame, size: Rect) {
+        self.refresh_json_tree();
+        let Some(tree) = self.json_tree.as_ref() else {
+            frame.render_widget(
+                Line::from(Message::NotJson.text())
+                    .fg(self.colors.bright.black)
+                    .centered(),
+                self.preview_layout.content_pane,
+            );
+            return;
+        };
+
+        let rows = tree.rows();
+        self.tree_cursor = self.tree_cursor.min(rows.len().saturating_sub(1));
+        let height = usize::from(size.height).max(1);
+        if self.tree_cursor.lt(&self.tree_scroll) {
+            self.tree_scroll = self.tree_cursor;
+        }
+        if self.tree_cursor.ge(&self.tree_scroll.add(height)) {
+            self.tree_scroll = self.tree_cursor.sub(height).add(1);
+        }
+
+        self.draw_scrollbar(
+            rows.len(),
+            self.tree_scroll,
+            frame,
+            self.preview_layout.scrollbar,
+        );
+
+        let lines = rows
+            .iter()
+            .enumerate()
+            .skip(self.tree_scroll)
+            .take(height)
+            .map(|(idx, row)| {
+                let marker = match (row.expandable, row.expanded) {
+                    (true, true) => "▾ ",
+                    (true, false) => "▸ ",
+                    _ => "  ",
+                };
+                let mut spans = vec![
+                    "  ".repeat(row.depth).into(),
+                    marker.fg(self.colors.bright.black),
+                ];
+                if let Some(key) = row.key.as_ref() {
+                    spans.push(key.clone().fg(self.colors.normal.yellow));
+                    spans.push(": ".fg(self.colors.bright.black));
+                }
+                spans.push(match row.expandable {
+                    true => row.preview.clone().fg(self.colors.bright.black),
+                    false => row.preview.clone().fg(self.colors.normal.white),
+                });
+                let line = Line::from(spans);
+                match idx.eq(&self.tree_cursor) {
+                    true => line.bg(self.colors.primary.hover),
+                    false => line,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        frame.render_widget(Paragraph::new(lines), self.preview_layout.content_pane);
+    }
+
+    /// handles the keys of the tree tab, returning whether the key was used
+    fn handle_tree_key(&mut self, key_event: KeyEvent) -> (bool, Option<ResponseViewerEvent>) {
+        let Some(tree) = self.json_tree.as_mut() else {
+            return (false, None);
+        };
+        let rows = tree.rows();
+        let Some(row) = rows.get(self.tree_cursor) else {
+            return (false, None);
+        };
+
+        match key_event.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.tree_cursor = self.tree_cursor.add(1).min(rows.len().sub(1));
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.tree_cursor = self.tree_cursor.saturating_sub(1)
+            }
+            KeyCode::Char('g') => self.tree_cursor = 0,
+            KeyCode::Char('G') => self.tree_cursor = rows.len().sub(1),
+            KeyCode::Char('l') | KeyCode::Right if row.expandable => tree.expand(&row.path),
+            KeyCode::Enter if row.expanded => tree.collapse(&row.path),
+            KeyCode::Enter if row.expandable => tree.expand(&row.path),
+            // folds the value, or goes up to its parent when it is folded
+            KeyCode::Char('h') | KeyCode::Left if row.expanded => tree.collapse(&row.path),
+            KeyCode::Char('h') | KeyCode::Left => {
+                if let Some(parent) = rows[..self.tree_cursor]
+                    .iter()
+                    .rposition(|candidate| candidate.depth.lt(&row.depth))
+                {
+                    self.tree_cursor = parent;
+                }
+            }
+            KeyCode::Char('y') => return (true, Some(self.copy_tree(row.path.clone()))),
+            KeyCode::Char('Y') => {
+                let value = tree.value(&row.path).unwrap_or_default();
+                return (true, Some(self.copy_tree(value)));
+            }
+            _ => return (false, None),
+        }
+        (true, None)
+    }
+
+    /// builds the table of the body again when the response changed,
+    /// ordered the way the user last asked for
+    fn refresh_json_table(&mut self) {
+        if !self.json_table_stale {
+            return;
+        }
+        self.json_table_stale = false;
+        self.json_table = self
+            .whole_body()
+            .and_then(|body| match self.mime_handler().name {
+                "csv" => JsonTable::from_csv(&body),
+                _ => JsonTable::parse(&body),
+            });
+        match (self.json_table.as_mut(), self.table_sort) {
+            (Some(table), Some((column, descending))) if column.lt(&table.columns.len()) => {
+                table.sort(column, descending)
+            }
+            _ => self.table_sort = None,
+        }
+    }
+
+    fn draw_json_table(&mut self, frame: &mut Frame, size: Rect) {
+        self.refresh_json_table();
+        let Some(table) = self.json_table.as_ref() else {
+            frame.render_widget(
+                Line::from(Message::NotATable.text())
+                    .fg(self.colors.bright.black)
+                    .centered(),
+                self.preview_layout.content_pane,
+            );
+            return;
+        };
+
+        self.table_column = self.table_column.min(table.columns.len().sub(1));
+        let body_height = usize::from(size.height).saturating_sub(1).max(1);
+        self.table_scroll = self
+            .table_scroll
+            .min(table.rows.len().saturating_sub(body_height));
+
+        let widths = table
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(idx, column)| {
+                table
+                    .rows
+                    .iter()
+                    .map(|row| json_table::cell_text(&row[idx]).chars().count())
+                    .chain(iter::once(column.chars().count().add(2)))
+                    .max()
+                    .unwrap_or_default()
+                    .clamp(3, MAX_COLUMN_WIDTH)
+            })
+            .collect::<Vec<_>>();
+        // numbers are aligned to the right, so their digits line up
+        let numeric = (0..table.columns.len())
+            .map(|idx| {
+                table
+                    .rows
+                    .iter()
+                    .all(|row| row[idx].is_null() || json_table::as_number(&row[idx]).is_some())
+            })
+            .collect::<Vec<_>>();
+
+        // the selected column is always the first one, followed by as many
+        // as fit on the view
+        let available = usize::from(self.preview_layout.content_pane.width);
+        let mut used = 0;
+        let visible = (self.table_column..table.columns.len())
+            .take_while(|&idx| {
+                let fits = used.eq(&0) || used.add(widths[idx]).le(&available);
+                used = used.add(widths[idx]).add(3);
+                fits
+            })
+            .collect::<Vec<_>>();
+
+        let separator = || " │ ".fg(self.colors.bright.black);
+        let mut header = vec![];
+        for (position, &idx) in visible.iter().enumerate() {
+            if position.gt(&0) {
+                header.push(separator());
+            }
+            let arrow = match self.table_sort {
+                Some((column, false)) if column.eq(&idx) => " ▲",
+                Some((column, true)) if column.eq(&idx) => " ▼",
+                _ => "",
+            };
+            let title = fit_cell(&format!("{}{arrow}", table.columns[idx]), widths[idx]);
+            let title = title.fg(self.colors.normal.yellow).bold();
+            header.push(match position.eq(&0) {
+                true => title.bg(self.colors.primary.hover),
+                false => title,
+            });
+        }
+
+        let mut lines = vec![Line::from(header)];
+        lines.extend(
+            table
+                .rows
+                .iter()
+                .skip(self.table_scroll)
+                .take(body_height)
+                .map(|row| {
+                    let mut spans = vec![];
+                    for (position, &idx) in visible.iter().enumerate() {
+                        if position.gt(&0) {
+                            spans.push(separator());
+                        }
+                        let text = json_table::cell_text(&row[idx]);
+                        let color = match row[idx] {
+                            serde_json::Value::Object(_) | serde_json::Value::Array(_) => {
+                                self.colors.bright.black
+                            }
+                            _ => self.colors.normal.white,
+                        };
+                        let cell = match numeric[idx] {
+                            true => format!(
+                                "{:>1$}",
+                                fit_cell(&text, widths[idx]).trim_end(),
+                                widths[idx]
+                            ),
+                            false => fit_cell(&text, widths[idx]),
+                        };
+                        spans.push(cell.fg(color));
+                    }
+                    Line::from(spans)
+                }),
+        );
+
+        self.draw_scrollbar(
+            table.rows.len(),
+            self.table_scroll,
+            frame,
+            self.preview_layout.scrollbar,
+        );
+        frame.render_widget(Paragraph::new(lines), self.preview_layout.content_pane);
+    }
+
+    /// handles the keys of the table tab, returning whether the key was used
+    fn handle_table_key(&mut self, key_event: KeyEvent) -> bool {
+        let Some(table) = self.json_table.as_ref() else {
+            return false;
+        };
+        let last_column = table.columns.len().sub(1);
+
+        match key_event.code {
+            KeyCode::Char('j') | KeyCode::Down => self.table_scroll = self.table_scroll.add(1),
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.table_scroll = self.table_scroll.saturating_sub(1)
+            }
+            KeyCode::Char('l') | KeyCode::Right => {
+                self.table_column = self.table_column.add(1).min(last_column)
+            }
+            KeyCode::Char('h') | KeyCode::Left => {
+                self.table_column = self.table_column.saturating_sub(1)
+            }
+            KeyCode::Char('0') => self.table_column = 0,
+            KeyCode::Char('$') => self.table_column = last_column,
+            // cycles the selected column between ascending, descending and
+            // the order of the body
+            KeyCode::Char('S') => {
+                self.table_sort = match self.table_sort {
+                    Some((column, false)) if column.eq(&self.table_column) => Some((column, true)),
+                    Some((column, true)) if column.eq(&self.table_column) => None,
+                    _ => Some((self.table_column, false)),
+                };
+                self.json_table_stale = true;
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn copy_tree(&self, text: String) -> ResponseViewerEvent {
+        match clipboard::copy(&text) {
+            Ok(()) => ResponseViewerEvent::Notify(Message::Clipboard, text, false),
+            Err(e) => ResponseViewerEvent::Notify(Message::Clipboard, e.to_string(), true),
+        }
+    }
+
+    /// appends a readable date for every epoch timestamp on the line
+    fn annotate_timestamps(&self, mut line: Line<'static>) -> Line<'static> {
+        let text = line
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect::<String>();
+        let timestamps = timestamp::find_timestamps(&text);
+        if timestamps.is_empty() {
+            return line;
+        }
+
+        let dates = timestamps
+            .iter()
+            .map(|found| timestamp::describe(&found.datetime))
+            .collect::<Vec<_>>()
+            .join(", ");
+        line.spans
+            .push(format!("  // {dates}").fg(self.colors.bright.black));
+        line
+    }
+
+    /// sparkline of the last sends of the selected request followed by its
+    /// p50 and p95, eg: `▂▃▁▇ p50 120ms p95 340ms`
+    fn latency_summary(&self) -> Option<Vec<Span<'static>>> {
+        let store = self.collection_store.borrow();
+        let request = store.get_selected_request()?;
+        let history = store.get_latency_history()?;
+        let history = history.borrow();
+        let id = &request.read().unwrap().id;
+
+        let stats = history.stats(id).filter(|stats| stats.count.gt(&1))?;
+        let sparkline = build_sparkline(&history.last_runs(id, 12), stats.min, stats.max);
+
+        Some(vec![
+            sparkline.fg(self.colors.normal.blue),
+            " p50 ".fg(self.colors.bright.black),
+            format!("{}ms", stats.p50).fg(self.colors.normal.green),
+            " p95 ".fg(self.colors.bright.black),
+            format!("{}ms", stats.p95).fg(self.colors.normal.yellow),
+        ])
     }
 
     fn draw_summary(&self, frame: &mut Frame, size: Rect) {
         if let Some(ref response) = self.response {
-            let status_color = match response
+            let indicator = match response
                 .borrow()
                 .status
                 .map(|status| status.as_u16())
                 .unwrap_or_default()
             {
-                s if s < 400 => self.colors.normal.green,
-                _ => self.colors.normal.red,
+                s if s < 400 => Indicator::Success,
+                _ => Indicator::Failure,
             };
 
             let status = match response.borrow().status {
-                Some(status) if size.width.gt(&50) => format!(
-                    "{} ({})",
-                    status.as_str(),
-                    status
-                        .canonical_reason()
-                        .expect("tried to get a canonical_reason from a invalid status code")
-                )
-                .fg(status_color),
-                Some(status) => status.as_str().to_string().fg(status_color),
-                None => "Error".fg(self.colors.normal.red),
+                Some(status) if size.width.gt(&50) => self.colors.indicate(
+                    indicator,
+                    format!(
+                        "{} ({})",
+                        status.as_str(),
+                        status
+                            .canonical_reason()
+                            .expect("tried to get a canonical_reason from a invalid status code")
+                    ),
+                ),
+                Some(status) => self.colors.indicate(indicator, status.as_str()),
+                None => self
+                    .colors
+                    .indicate(Indicator::Failure, Message::Error.text()),
             };
 
-            let mut pieces: Vec<Span> = vec![
-                "Status: ".fg(self.colors.bright.black),
+            let mut pieces: Vec<Span> = vec![];
+            if let Some(name) = self.example_name() {
+                pieces.push(Message::Example.text().fg(self.colors.bright.black));
+                pieces.push(format!("{} ", name).fg(self.colors.normal.yellow));
+            }
+            if let (Some(name), None) = (response.borrow().offline.as_ref(), self.example_index) {
+                pieces.push(Message::OfflineExample.text().fg(self.colors.bright.black));
+                pieces.push(format!("{} ", name).fg(self.colors.normal.magenta));
+            }
+
+            if let (Some(overrides), None) = (self.overrides.as_ref(), self.example_index) {
+                pieces.push(Message::Modified.text().fg(self.colors.bright.black));
+                pieces.push(self.colors.indicate(Indicator::Changed, overrides.as_str()));
+                pieces.push(" ".into());
+            }
+
+            pieces.extend([
+                Message::Status.text().fg(self.colors.bright.black),
                 status,
                 " ".into(),
-                "Time: ".fg(self.colors.bright.black),
+                Message::Time.text().fg(self.colors.bright.black),
                 format!("{}ms", response.borrow().duration.as_millis())
                     .fg(self.colors.normal.green),
                 " ".into(),
-            ];
+            ]);
 
             if let Some(size) = response.borrow().size {
-                pieces.push("Size: ".fg(self.colors.bright.black));
+                pieces.push(Message::Size.text().fg(self.colors.bright.black));
                 pieces.push(format!("{} B", size).fg(self.colors.normal.green))
             };
 
+            if response.borrow().streaming {
+                pieces.push(" ".into());
+                pieces.push(Message::Streaming.text().fg(self.colors.normal.yellow));
+            }
+            if self.follow {
+                pieces.push(" ".into());
+                pieces.push(Message::Following.text().fg(self.colors.normal.blue));
+            }
+
+            if let Some(latency) = self.latency_summary() {
+                pieces.push(" ".into());
+                pieces.extend(latency);
+            }
+
+            let rate_limit = response
+                .borrow()
+                .headers
+                .as_ref()
+                .and_then(RateLimit::from_headers);
+            if let Some(rate_limit) = rate_limit {
+                let quota_color = match rate_limit.is_exhausted() {
+                    true => self.colors.normal.red,
+                    false => self.colors.normal.green,
+                };
+                if let Some(remaining) = rate_limit.remaining {
+                    let quota = match rate_limit.limit {
+                        Some(limit) => format!("{}/{}", remaining, limit),
+                        None => remaining.to_string(),
+                    };
+                    pieces.push(Message::Quota.text().fg(self.colors.bright.black));
+                    pieces.push(quota.fg(quota_color));
+                }
+                if let Some(wait) = rate_limit.retry_after.or(rate_limit.reset) {
+                    pieces.push(Message::Reset.text().fg(self.colors.bright.black));
+                    pieces.push(format!("{}s", wait.as_secs()).fg(quota_color));
+                }
+            }
+
             frame.render_widget(Line::from(pieces), size);
         }
     }
@@ -597,6 +2000,7 @@ impl<'a> ResponseViewer<'a> {
 
 impl<'a> Renderable for ResponseViewer<'a> {
     fn draw(&mut self, frame: &mut Frame, size: Rect) -> anyhow::Result<()> {
+        self.restore_preferences();
         self.draw_tabs(frame, self.layout.tabs_pane);
         self.draw_current_tab(frame, self.layout.content_pane)?;
         self.draw_summary(frame, self.layout.summary_pane);
@@ -622,10 +2026,31 @@ impl<'a> Eventful for ResponseViewer<'a> {
 
         if let KeyCode::Tab = key_event.code {
             self.active_tab = ResViewerTabs::next(&self.active_tab);
+            self.store_preferences();
         }
 
         if let KeyCode::BackTab = key_event.code {
             self.active_tab = ResViewerTabs::prev(&self.active_tab);
+            self.store_preferences();
+        }
+
+        if self.active_tab.eq(&ResViewerTabs::Tree) {
+            if let (true, event) = self.handle_tree_key(key_event) {
+                return Ok(event);
+            }
+        }
+
+        if self.active_tab.eq(&ResViewerTabs::Table) && self.handle_table_key(key_event) {
+            return Ok(None);
+        }
+
+        if self.active_tab.eq(&ResViewerTabs::Preview) && self.handle_ndjson_key(key_event) {
+            return Ok(None);
+        }
+
+        // scrolling stops following the end of the body
+        if let KeyCode::Char('j' | 'k') = key_event.code {
+            self.follow = false;
         }
 
         match key_event.code {
@@ -640,11 +2065,13 @@ impl<'a> Eventful for ResponseViewer<'a> {
                     self.headers_scroll_x = self.headers_scroll_x.saturating_sub(1)
                 }
             }
+            KeyCode::Char('f') => self.follow = !self.follow,
             KeyCode::Char('j') => match self.active_tab {
                 ResViewerTabs::Preview => self.pretty_scroll = self.pretty_scroll.add(1),
                 ResViewerTabs::Raw => self.raw_scroll = self.raw_scroll.add(1),
                 ResViewerTabs::Headers => self.headers_scroll_y = self.headers_scroll_y.add(1),
-                ResViewerTabs::Cookies => {}
+                ResViewerTabs::Status => self.status_scroll = self.status_scroll.add(1),
+                ResViewerTabs::Cookies | ResViewerTabs::Tree | ResViewerTabs::Table => {}
             },
             KeyCode::Char('k') => match self.active_tab {
                 ResViewerTabs::Preview => self.pretty_scroll = self.pretty_scroll.saturating_sub(1),
@@ -652,13 +2079,34 @@ impl<'a> Eventful for ResponseViewer<'a> {
                 ResViewerTabs::Headers => {
                     self.headers_scroll_y = self.headers_scroll_y.saturating_sub(1)
                 }
-                ResViewerTabs::Cookies => {}
+                ResViewerTabs::Status => self.status_scroll = self.status_scroll.saturating_sub(1),
+                ResViewerTabs::Cookies | ResViewerTabs::Tree | ResViewerTabs::Table => {}
             },
             KeyCode::Char('l') => {
                 if let ResViewerTabs::Headers = self.active_tab {
                     self.headers_scroll_x = self.headers_scroll_x.add(1)
                 }
             }
+            KeyCode::Char('t') => {
+                self.show_timestamps = !self.show_timestamps;
+                self.store_preferences();
+            }
+            KeyCode::Char('w') => {
+                self.wrap = !self.wrap;
+                self.store_preferences();
+            }
+            KeyCode::Char('|') => {
+                return Ok(Some(ResponseViewerEvent::EditFilter(self.filter.clone())))
+            }
+            KeyCode::Char('c') => {
+                self.compare = !self.compare;
+                self.display(self.response.clone());
+            }
+            KeyCode::Char('s') => self.save_as_example(),
+            KeyCode::Char('x') => self.show_next_example(),
+            KeyCode::Char('F') => return Ok(self.load_full_body()),
+            KeyCode::Char('W') => return Ok(self.save_body()),
+            KeyCode::Char('i') => return Ok(Some(ResponseViewerEvent::InspectTls)),
             _ => {}
         }
 
@@ -666,6 +2114,52 @@ impl<'a> Eventful for ResponseViewer<'a> {
     }
 }
 
+/// breaks the line into as many lines of at most `width` characters as it
+/// takes, keeping the style of every piece
+fn wrap_line(line: Line<'static>, width: usize) -> Vec<Line<'static>> {
+    if line.width().le(&width) {
+        return vec![line];
+    }
+
+    let mut lines = vec![];
+    let mut current: Vec<Span<'static>> = vec![];
+    let mut current_width = 0;
+    for span in line.spans.iter() {
+        let mut chars = span.content.chars().peekable();
+        while chars.peek().is_some() {
+            let piece = chars
+                .by_ref()
+                .take(width.sub(current_width))
+                .collect::<String>();
+            current_width = current_width.add(piece.chars().count());
+            current.push(Span::styled(piece, span.style));
+            if current_width.eq(&width) {
+                lines.push(Line::from(std::mem::take(&mut current)).style(line.style));
+                current_width = 0;
+            }
+        }
+    }
+    if !current.is_empty() {
+        lines.push(Line::from(current).style(line.style));
+    }
+    lines
+}
+
+/// pads the text to the width of the column, cutting it with an ellipsis
+/// when it doesn't fit
+fn fit_cell(text: &str, width: usize) -> String {
+    let text = text.replace('\n', " ");
+    match text.chars().count().gt(&width) {
+        true => format!(
+            "{}…",
+            text.chars()
+                .take(width.saturating_sub(1))
+                .collect::<String>()
+        ),
+        false => format!("{text:<width$}"),
+    }
+}
+
 fn build_layout(size: Rect) -> ResViewerLayout {
     let size = Rect::new(
         size.x.add(1),
@@ -756,6 +2250,78 @@ fn make_empty_ascii_art(colors: &hac_colors::Colors) -> Vec<Line<'static>> {
         .collect::<Vec<_>>()
 }
 
+/// draws each duration as a block whose height is relative to the fastest
+/// and slowest durations on the history
+fn build_sparkline(durations: &[u64], min: u64, max: u64) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let range = max.saturating_sub(min).max(1);
+    durations
+        .iter()
+        .map(|duration| {
+            let level = duration.saturating_sub(min) * (BLOCKS.len() as u64 - 1) / range;
+            BLOCKS[(level as usize).min(BLOCKS.len() - 1)]
+        })
+        .collect()
+}
+
+/// restyles the parts of the line matching the highlight rules, splitting
+/// the syntax highlighted spans where a match starts or ends
+fn apply_highlights(
+    line: Line<'static>,
+    highlights: &Highlights,
+    colors: &hac_colors::Colors,
+) -> Line<'static> {
+    let text = line
+        .spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect::<String>();
+    let matches = highlights.matches(&text);
+    if matches.is_empty() {
+        return line;
+    }
+
+    let mut spans = vec![];
+    let mut offset = 0;
+    for span in line.spans.iter() {
+        let end = offset + span.content.len();
+        let mut cursor = offset;
+        while cursor < end {
+            let found = matches
+                .iter()
+                .find(|(range, _)| range.end > cursor && range.start < end);
+            let (next, style) = match found {
+                Some((range, _)) if range.start > cursor => (range.start, span.style),
+                Some((range, rule)) => {
+                    let color = match rule.color {
+                        HighlightColor::Red => colors.normal.red,
+                        HighlightColor::Green => colors.normal.green,
+                        HighlightColor::Yellow => colors.normal.yellow,
+                        HighlightColor::Blue => colors.normal.blue,
+                        HighlightColor::Magenta => colors.normal.magenta,
+                        HighlightColor::Orange => colors.normal.orange,
+                    };
+                    let style = match rule.background {
+                        true => span.style.bg(color).fg(colors.normal.black),
+                        false => span.style.fg(color),
+                    };
+                    let style = match rule.bold {
+                        true => style.bold(),
+                        false => style,
+                    };
+                    (range.end.min(end), style)
+                }
+                None => (end, span.style),
+            };
+            spans.push(Span::styled(text[cursor..next].to_string(), style));
+            cursor = next;
+        }
+        offset = end;
+    }
+
+    Line::from(spans).style(line.style)
+}
+
 #[cfg(test)]
 mod tests {
     use rand::{rngs::StdRng, SeedableRng};
@@ -802,4 +2368,80 @@ mod tests {
 
         assert_eq!(art, expected);
     }
+
+    #[test]
+    fn test_build_sparkline() {
+        assert_eq!(build_sparkline(&[10, 20, 30, 80], 10, 80), "▁▂▃█");
+        assert_eq!(build_sparkline(&[50, 50], 50, 50), "▁▁");
+    }
+
+    #[test]
+    fn test_wrap_line() {
+        let style = Style::default().fg(ratatui::style::Color::Red);
+        let line = Line::from(vec![Span::raw("abcd"), Span::styled("efg", style)]);
+        let wrapped = wrap_line(line, 3)
+            .into_iter()
+            .map(|line| {
+                line.spans
+                    .into_iter()
+                    .map(|span| (span.content.to_string(), span.style))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            wrapped,
+            vec![
+                vec![("abc".to_string(), Style::default())],
+                vec![
+                    ("d".to_string(), Style::default()),
+                    ("ef".to_string(), style)
+                ],
+                vec![("g".to_string(), style)],
+            ]
+        );
+        assert_eq!(wrap_line(Line::from("short"), 10).len(), 1);
+    }
+
+    #[test]
+    fn test_fit_cell() {
+        assert_eq!(fit_cell("id", 5), "id   ");
+        assert_eq!(fit_cell("a long name", 6), "a lon…");
+        assert_eq!(fit_cell("two\nlines", 9), "two lines");
+    }
+
+    #[test]
+    fn test_apply_highlights() {
+        use hac_core::collection::highlights::HighlightRule;
+
+        let colors = hac_colors::Colors::default();
+        let (highlights, _) = Highlights::compile(&[HighlightRule {
+            pattern: r"user-\d+".into(),
+            color: HighlightColor::Green,
+            bold: true,
+            background: false,
+        }]);
+        let line = Line::from(vec![
+            Span::styled("\"id\": ", Style::default().fg(colors.normal.yellow)),
+            Span::styled("\"user-", Style::default().fg(colors.normal.blue)),
+            Span::styled("42\"", Style::default().fg(colors.normal.red)),
+        ]);
+
+        let line = apply_highlights(line, &highlights, &colors);
+        let spans = line
+            .spans
+            .iter()
+            .map(|span| (span.content.as_ref(), span.style))
+            .collect::<Vec<_>>();
+        let highlighted = Style::default().fg(colors.normal.green).bold();
+        assert_eq!(
+            spans,
+            vec![
+                ("\"id\": ", Style::default().fg(colors.normal.yellow)),
+                ("\"", Style::default().fg(colors.normal.blue)),
+                ("user-", highlighted),
+                ("42", highlighted),
+                ("\"", Style::default().fg(colors.normal.red)),
+            ]
+        );
+    }
 }
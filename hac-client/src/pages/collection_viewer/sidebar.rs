@@ -1,3 +1,4 @@
+mod bulk_rename_form;
 mod create_directory_form;
 mod create_request_form;
 mod delete_item_prompt;
@@ -7,13 +8,18 @@ mod edit_request_form;
 mod request_form;
 mod select_request_parent;
 
-use hac_core::collection::types::{Request, RequestKind, RequestMethod};
+use hac_colors::Indicator;
+use hac_core::collection::template::RequestTemplate;
+use hac_core::collection::types::{Monitor, Request, RequestKind, RequestMethod};
+use hac_core::monitor::MonitorStats;
 
+use super::sidebar::bulk_rename_form::{BulkRenameForm, BulkRenameFormEvent};
 use super::sidebar::delete_item_prompt::{DeleteItemPrompt, DeleteItemPromptEvent};
 use super::sidebar::directory_form::{DirectoryForm, DirectoryFormEvent};
 use super::sidebar::directory_form::{DirectoryFormCreate, DirectoryFormEdit};
 use super::sidebar::request_form::{RequestForm, RequestFormEvent};
 use super::sidebar::request_form::{RequestFormCreate, RequestFormEdit};
+use crate::i18n::Message;
 use crate::pages::collection_viewer::collection_store::{CollectionStore, CollectionStoreAction};
 use crate::pages::collection_viewer::collection_viewer::{CollectionViewerOverlay, PaneFocus};
 use crate::pages::{Eventful, Renderable};
@@ -60,6 +66,15 @@ pub enum SidebarEvent {
     /// user pressed `DeleteItem (D)` hotkey, which should notify the caller to open the
     /// delete_item_prompt to ask the user for confirmation
     DeleteItem(String),
+    /// user pressed `BulkRename (R)` hotkey, which should notify the caller to open the
+    /// bulk_rename_form over the hovered directory, or the whole collection
+    BulkRename,
+    /// user pressed `SaveTemplate (T)` hotkey on a request, which became the template
+    /// new requests of the collection start with, and should be synced
+    TemplateSaved(String),
+    /// user pressed `Undo (u)` hotkey, which restored the collection as it was
+    /// before the described change, or there was nothing to undo
+    Undone(Option<String>),
     /// user pressed a hotkey to quit the application, so we bubble up so the caller
     /// can do a few things before bubbling the quit request further up
     Quit,
@@ -122,6 +137,10 @@ pub struct Sidebar<'sbar> {
     request_form: RequestFormVariant<'sbar>,
     directory_form: DirectoryFormVariant<'sbar>,
     delete_item_prompt: DeleteItemPrompt<'sbar>,
+    bulk_rename_form: BulkRenameForm<'sbar>,
+    /// the requests as they were when a form was opened, recorded as a change
+    /// only when the form is confirmed
+    pending_change: Option<(String, Vec<RequestKind>)>,
 }
 
 impl<'sbar> Sidebar<'sbar> {
@@ -139,6 +158,8 @@ impl<'sbar> Sidebar<'sbar> {
                 DirectoryForm::<DirectoryFormCreate>::new(colors, collection_store.clone()),
             ),
             delete_item_prompt: DeleteItemPrompt::new(colors, collection_store.clone()),
+            bulk_rename_form: BulkRenameForm::new(colors),
+            pending_change: None,
             lines: vec![],
             collection_store,
         };
@@ -156,10 +177,33 @@ impl<'sbar> Sidebar<'sbar> {
             collection_store.get_selected_request(),
             collection_store.get_hovered_request(),
             collection_store.get_dirs_expanded().unwrap().clone(),
+            &collection_store
+                .get_monitor_stats()
+                .unwrap_or_default()
+                .borrow(),
             self.colors,
         );
     }
 
+    /// closes the request form and selects a request that already existed,
+    /// unfolding the directory it lives in so it is visible on the sidebar
+    fn open_existing(&mut self, request: Arc<RwLock<Request>>) -> SidebarEvent {
+        let mut store = self.collection_store.borrow_mut();
+        store.pop_overlay();
+        let (id, parent) = {
+            let request = request.read().unwrap();
+            (request.id.clone(), request.parent.clone())
+        };
+        if let (Some(parent), Some(dirs_expanded)) = (parent, store.get_dirs_expanded()) {
+            dirs_expanded.borrow_mut().insert(parent, true);
+        }
+        store.dispatch(CollectionStoreAction::SetHoveredRequest(Some(id)));
+        store.dispatch(CollectionStoreAction::SetSelectedRequest(Some(request)));
+        drop(store);
+        self.rebuild_tree_view();
+        SidebarEvent::RebuildView
+    }
+
     pub fn draw_overlay(
         &mut self,
         frame: &mut Frame,
@@ -184,6 +228,9 @@ impl<'sbar> Sidebar<'sbar> {
             CollectionViewerOverlay::DeleteSidebarItem(_) => {
                 self.delete_item_prompt.draw(frame, frame.size())?;
             }
+            CollectionViewerOverlay::BulkRename => {
+                self.bulk_rename_form.draw(frame, frame.size())?;
+            }
             _ => {}
         };
 
@@ -212,12 +259,17 @@ impl<'sbar> Renderable for Sidebar<'sbar> {
             (false, _) => Style::default().fg(self.colors.bright.black),
         };
 
+        let mut title = vec![
+            "R".fg(self.colors.normal.red).bold(),
+            "equests".fg(self.colors.bright.black),
+        ];
+        if self.collection_store.borrow().is_read_only() {
+            title.push(Message::ReadOnly.text().fg(self.colors.normal.yellow));
+        }
+
         let block = Block::default()
             .borders(Borders::ALL)
-            .title(vec![
-                "R".fg(self.colors.normal.red).bold(),
-                "equests".fg(self.colors.bright.black),
-            ])
+            .title(title)
             .border_style(block_border);
 
         frame.render_widget(block, size);
@@ -255,6 +307,9 @@ impl<'a> Eventful for Sidebar<'a> {
                     Some(RequestFormEvent::Confirm) => {
                         let mut store = self.collection_store.borrow_mut();
                         store.pop_overlay();
+                        if let Some((label, snapshot)) = self.pending_change.take() {
+                            store.record_snapshot(label, snapshot);
+                        }
                         drop(store);
                         self.rebuild_tree_view();
                         return Ok(Some(SidebarEvent::SyncCollection));
@@ -263,9 +318,13 @@ impl<'a> Eventful for Sidebar<'a> {
                         let mut store = self.collection_store.borrow_mut();
                         store.pop_overlay();
                         drop(store);
+                        self.pending_change = None;
                         self.rebuild_tree_view();
                         return Ok(None);
                     }
+                    Some(RequestFormEvent::OpenExisting(request)) => {
+                        return Ok(Some(self.open_existing(request)));
+                    }
                     None => return Ok(None),
                 }
             }
@@ -282,6 +341,9 @@ impl<'a> Eventful for Sidebar<'a> {
                     Some(DirectoryFormEvent::Confirm) => {
                         let mut store = self.collection_store.borrow_mut();
                         store.pop_overlay();
+                        if let Some((label, snapshot)) = self.pending_change.take() {
+                            store.record_snapshot(label, snapshot);
+                        }
                         drop(store);
                         self.rebuild_tree_view();
                         return Ok(Some(SidebarEvent::SyncCollection));
@@ -290,6 +352,7 @@ impl<'a> Eventful for Sidebar<'a> {
                         let mut store = self.collection_store.borrow_mut();
                         store.pop_overlay();
                         drop(store);
+                        self.pending_change = None;
                         self.rebuild_tree_view();
                         return Ok(None);
                     }
@@ -301,6 +364,9 @@ impl<'a> Eventful for Sidebar<'a> {
                     Some(DirectoryFormEvent::Confirm) => {
                         let mut store = self.collection_store.borrow_mut();
                         store.pop_overlay();
+                        if let Some((label, snapshot)) = self.pending_change.take() {
+                            store.record_snapshot(label, snapshot);
+                        }
                         drop(store);
                         self.rebuild_tree_view();
                         return Ok(Some(SidebarEvent::SyncCollection));
@@ -309,6 +375,7 @@ impl<'a> Eventful for Sidebar<'a> {
                         let mut store = self.collection_store.borrow_mut();
                         store.pop_overlay();
                         drop(store);
+                        self.pending_change = None;
                         self.rebuild_tree_view();
                         return Ok(None);
                     }
@@ -321,6 +388,9 @@ impl<'a> Eventful for Sidebar<'a> {
                     Some(RequestFormEvent::Confirm) => {
                         let mut store = self.collection_store.borrow_mut();
                         store.pop_overlay();
+                        if let Some((label, snapshot)) = self.pending_change.take() {
+                            store.record_snapshot(label, snapshot);
+                        }
                         drop(store);
                         self.rebuild_tree_view();
                         return Ok(Some(SidebarEvent::SyncCollection));
@@ -329,6 +399,33 @@ impl<'a> Eventful for Sidebar<'a> {
                         let mut store = self.collection_store.borrow_mut();
                         store.pop_overlay();
                         drop(store);
+                        self.pending_change = None;
+                        self.rebuild_tree_view();
+                        return Ok(None);
+                    }
+                    Some(RequestFormEvent::OpenExisting(request)) => {
+                        return Ok(Some(self.open_existing(request)));
+                    }
+                    None => return Ok(None),
+                }
+            }
+            CollectionViewerOverlay::BulkRename => {
+                match self.bulk_rename_form.handle_key_event(key_event)? {
+                    Some(BulkRenameFormEvent::Confirm) => {
+                        let mut store = self.collection_store.borrow_mut();
+                        store.pop_overlay();
+                        if let Some((label, snapshot)) = self.pending_change.take() {
+                            store.record_snapshot(label, snapshot);
+                        }
+                        drop(store);
+                        self.rebuild_tree_view();
+                        return Ok(Some(SidebarEvent::SyncCollection));
+                    }
+                    Some(BulkRenameFormEvent::Cancel) => {
+                        let mut store = self.collection_store.borrow_mut();
+                        store.pop_overlay();
+                        drop(store);
+                        self.pending_change = None;
                         self.rebuild_tree_view();
                         return Ok(None);
                     }
@@ -342,6 +439,14 @@ impl<'a> Eventful for Sidebar<'a> {
                         let changed_selection = store
                             .get_selected_request()
                             .is_some_and(|req| req.read().unwrap().id.eq(&item_id));
+                        let name = match store
+                            .get_hovered_request()
+                            .is_some_and(|id| id.eq(&item_id))
+                        {
+                            true => store.find_hovered_request().get_name(),
+                            false => item_id.clone(),
+                        };
+                        store.record_change(Message::ChangeDelete.format(&[&name]));
                         store.remove_item(item_id);
                         store.pop_overlay();
                         drop(store);
@@ -357,6 +462,7 @@ impl<'a> Eventful for Sidebar<'a> {
                         let mut store = self.collection_store.borrow_mut();
                         store.pop_overlay();
                         drop(store);
+                        self.pending_change = None;
                         self.rebuild_tree_view();
                         return Ok(None);
                     }
@@ -372,6 +478,17 @@ impl<'a> Eventful for Sidebar<'a> {
 
         let mut store = self.collection_store.borrow_mut();
 
+        // creating, editing or deleting items is not allowed until a read
+        // only collection is unlocked
+        if store.is_read_only()
+            && matches!(
+                key_event.code,
+                KeyCode::Char('n' | 'e' | 'D' | 'd' | 'p' | 'M' | 'R' | 'T' | 'u')
+            )
+        {
+            return Ok(None);
+        }
+
         match key_event.code {
             KeyCode::Enter => {
                 if store.get_requests().is_none() || store.get_hovered_request().is_none() {
@@ -392,6 +509,10 @@ impl<'a> Eventful for Sidebar<'a> {
             KeyCode::Char('j') | KeyCode::Down => store.dispatch(CollectionStoreAction::HoverNext),
             KeyCode::Char('k') | KeyCode::Up => store.dispatch(CollectionStoreAction::HoverPrev),
             KeyCode::Char('n') => {
+                self.pending_change = Some((
+                    Message::ChangeCreateRequest.text().into(),
+                    store.snapshot_requests(),
+                ));
                 self.request_form =
                     RequestFormVariant::Create(RequestForm::<RequestFormCreate>::new(
                         self.colors,
@@ -401,6 +522,10 @@ impl<'a> Eventful for Sidebar<'a> {
             }
             KeyCode::Char('e') => {
                 let hovered_request = store.find_hovered_request();
+                self.pending_change = Some((
+                    Message::ChangeEdit.format(&[&hovered_request.get_name()]),
+                    store.snapshot_requests(),
+                ));
                 drop(store);
                 match hovered_request {
                     RequestKind::Single(req) => {
@@ -430,7 +555,99 @@ impl<'a> Eventful for Sidebar<'a> {
                     return Ok(Some(SidebarEvent::DeleteItem(item_id)));
                 }
             }
-            KeyCode::Char('d') => return Ok(Some(SidebarEvent::CreateDirectory)),
+            KeyCode::Char('d') => {
+                self.pending_change = Some((
+                    Message::ChangeCreateDirectory.text().into(),
+                    store.snapshot_requests(),
+                ));
+                return Ok(Some(SidebarEvent::CreateDirectory));
+            }
+            KeyCode::Char('R') => {
+                // renames what is inside the hovered directory, or every
+                // request of the collection when hovering a request
+                let hovered_dir = match store.get_hovered_request().is_some() {
+                    true => match store.find_hovered_request() {
+                        RequestKind::Nested(dir) => Some(dir),
+                        RequestKind::Single(_) => None,
+                    },
+                    false => None,
+                };
+                let (requests, scope) = match hovered_dir {
+                    Some(dir) => (dir.requests.clone(), dir.name.clone()),
+                    None => {
+                        let Some(collection) = store.get_collection() else {
+                            return Ok(None);
+                        };
+                        let collection = collection.borrow();
+                        let Some(requests) = collection.requests.clone() else {
+                            return Ok(None);
+                        };
+                        (requests, collection.info.name.clone())
+                    }
+                };
+                self.pending_change = Some((
+                    Message::ChangeRename.format(&[&scope]),
+                    store.snapshot_requests(),
+                ));
+                self.bulk_rename_form.set_scope(requests, scope);
+                return Ok(Some(SidebarEvent::BulkRename));
+            }
+            KeyCode::Char('p') => {
+                // marks the hovered request as a prerequisite of the selected
+                // one, or unmarks it when it already is
+                let (Some(selected), Some(hovered_id)) =
+                    (store.get_selected_request(), store.get_hovered_request())
+                else {
+                    return Ok(None);
+                };
+                if let RequestKind::Single(hovered) = store.find_hovered_request() {
+                    if hovered.read().unwrap().id.ne(&selected.read().unwrap().id) {
+                        let name = selected.read().unwrap().name.clone();
+                        store.record_change(Message::ChangeEdit.format(&[&name]));
+                        let depends_on = &mut selected.write().unwrap().depends_on;
+                        match depends_on.iter().position(|id| id.eq(&hovered_id)) {
+                            Some(idx) => _ = depends_on.remove(idx),
+                            None => depends_on.push(hovered_id),
+                        }
+                    }
+                }
+            }
+            KeyCode::Char('M') => {
+                // starts monitoring the hovered request every minute, or stops
+                // monitoring it when it already is
+                if store.get_hovered_request().is_none() {
+                    return Ok(None);
+                }
+                if let RequestKind::Single(hovered) = store.find_hovered_request() {
+                    let name = hovered.read().unwrap().name.clone();
+                    store.record_change(Message::ChangeEdit.format(&[&name]));
+                    let mut hovered = hovered.write().unwrap();
+                    hovered.monitor = match hovered.monitor.take() {
+                        Some(_) => None,
+                        None => Some(Monitor {
+                            schedule: "1m".into(),
+                            expect_status: None,
+                            max_latency_ms: None,
+                        }),
+                    };
+                }
+            }
+            KeyCode::Char('T') => {
+                // new requests of the collection start like the hovered one
+                if store.get_hovered_request().is_none() {
+                    return Ok(None);
+                }
+                let RequestKind::Single(hovered) = store.find_hovered_request() else {
+                    return Ok(None);
+                };
+                let Some(collection) = store.get_collection() else {
+                    return Ok(None);
+                };
+                let hovered = hovered.read().unwrap();
+                collection.borrow_mut().template = Some(RequestTemplate::from_request(&hovered));
+                return Ok(Some(SidebarEvent::TemplateSaved(hovered.name.clone())));
+            }
+            KeyCode::Char('u') => return Ok(Some(SidebarEvent::Undone(store.undo()))),
             KeyCode::Esc => return Ok(Some(SidebarEvent::RemoveSelection)),
             _ => {}
         }
@@ -448,6 +665,7 @@ pub fn build_lines(
     selected_request: Option<Arc<RwLock<Request>>>,
     hovered_request: Option<String>,
     dirs_expanded: Rc<RefCell<HashMap<String, bool>>>,
+    monitor_stats: &HashMap<String, MonitorStats>,
     colors: &hac_colors::Colors,
 ) -> Vec<Paragraph<'static>> {
     requests
@@ -488,6 +706,7 @@ pub fn build_lines(
                         selected_request.clone(),
                         hovered_request.clone(),
                         dirs_expanded.clone(),
+                        monitor_stats,
                         colors,
                     )
                 } else {
@@ -517,12 +736,29 @@ pub fn build_lines(
                     (false, false) => Style::default().fg(colors.normal.white),
                 };
 
-                let line: Line<'_> = vec![
+                let is_prerequisite = selected_request.as_ref().is_some_and(|selected| {
+                    selected
+                        .read()
+                        .unwrap()
+                        .depends_on
+                        .contains(&req.read().unwrap().id)
+                });
+
+                let mut line: Line<'_> = vec![
                     Span::from(gap.clone()),
                     colored_method(req.read().unwrap().method.clone(), colors),
                     Span::from(format!(" {}", req.read().unwrap().name.clone())),
                 ]
                 .into();
+                if is_prerequisite {
+                    line.push_span(Message::Prerequisite.text().fg(colors.bright.black));
+                }
+                if req.read().unwrap().monitor.is_some() {
+                    line.push_span(monitor_summary(
+                        monitor_stats.get(&req.read().unwrap().id),
+                        colors,
+                    ));
+                }
 
                 vec![Paragraph::new(line).set_style(req_style)]
             }
@@ -530,6 +766,27 @@ pub fn build_lines(
         .collect()
 }
 
+/// a short summary of how a monitored request is doing, eg: ` ● 98% 120ms`
+fn monitor_summary(stats: Option<&MonitorStats>, colors: &hac_colors::Colors) -> Span<'static> {
+    let Some((uptime, latency)) =
+        stats.and_then(|stats| stats.uptime().zip(stats.average_latency()))
+    else {
+        return " ●".fg(colors.bright.black);
+    };
+
+    let indicator = match stats.is_some_and(|stats| stats.is_failing()) {
+        true => Indicator::Failure,
+        false => Indicator::Success,
+    };
+    let summary = format!("{:.0}% {}ms", uptime, latency.as_millis());
+    // with symbols enabled the indicator already has a marker of its own
+    let summary = match colors.indicators.symbols {
+        true => colors.indicate(indicator, summary),
+        false => colors.indicate(indicator, format!("● {summary}")),
+    };
+    Span::styled(format!(" {}", summary.content), summary.style)
+}
+
 fn colored_method(method: RequestMethod, colors: &hac_colors::Colors) -> Span<'static> {
     match method {
         RequestMethod::Get => "GET   ".fg(colors.normal.green).bold(),
@@ -0,0 +1,282 @@
+use hac_core::collection::rename::{self, RenameChange, RenamePattern};
+use hac_core::collection::types::RequestKind;
+
+use crate::i18n::Message;
+use crate::pages::input::Input;
+use crate::pages::overlay::make_overlay;
+use crate::pages::{Eventful, Renderable};
+
+use std::ops::{Add, Div, Mul, Sub};
+use std::sync::{Arc, RwLock};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::Rect;
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BulkRenameFormEvent {
+    /// the changes were applied to the requests and should be saved
+    Confirm,
+    Cancel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RenameField {
+    Find,
+    Replace,
+    Prefix,
+    Suffix,
+}
+
+impl RenameField {
+    const ALL: [RenameField; 4] = [
+        RenameField::Find,
+        RenameField::Replace,
+        RenameField::Prefix,
+        RenameField::Suffix,
+    ];
+
+    fn next(&self) -> Self {
+        let idx = RenameField::ALL
+            .iter()
+            .position(|f| f.eq(self))
+            .unwrap_or(0);
+        RenameField::ALL[(idx + 1) % RenameField::ALL.len()]
+    }
+
+    fn prev(&self) -> Self {
+        let idx = RenameField::ALL
+            .iter()
+            .position(|f| f.eq(self))
+            .unwrap_or(0);
+        RenameField::ALL[(idx + RenameField::ALL.len() - 1) % RenameField::ALL.len()]
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            RenameField::Find => Message::RenameFind.text(),
+            RenameField::Replace => Message::RenameReplace.text(),
+            RenameField::Prefix => Message::RenamePrefix.text(),
+            RenameField::Suffix => Message::RenameSuffix.text(),
+        }
+    }
+
+    fn placeholder(&self) -> &'static str {
+        match self {
+            RenameField::Find => r"^get (\w+)",
+            RenameField::Replace => "fetch $1",
+            RenameField::Prefix => "[v2] ",
+            RenameField::Suffix => " (legacy)",
+        }
+    }
+}
+
+/// renames every request of a directory, or of the whole collection, at
+/// once, listing what changes before anything is applied
+#[derive(Debug)]
+pub struct BulkRenameForm<'br> {
+    colors: &'br hac_colors::Colors,
+    /// the requests being renamed, nested ones included
+    requests: Arc<RwLock<Vec<RequestKind>>>,
+    /// the name of the directory or collection being renamed
+    scope: String,
+    values: [String; 4],
+    focused_field: RenameField,
+    include_uris: bool,
+}
+
+impl<'br> BulkRenameForm<'br> {
+    pub fn new(colors: &'br hac_colors::Colors) -> Self {
+        BulkRenameForm {
+            colors,
+            requests: Arc::default(),
+            scope: String::default(),
+            values: Default::default(),
+            focused_field: RenameField::Find,
+            include_uris: false,
+        }
+    }
+
+    /// starts renaming the given requests from scratch
+    pub fn set_scope(&mut self, requests: Arc<RwLock<Vec<RequestKind>>>, scope: String) {
+        self.requests = requests;
+        self.scope = scope;
+        self.values = Default::default();
+        self.focused_field = RenameField::Find;
+        self.include_uris = false;
+    }
+
+    fn value_mut(&mut self, field: RenameField) -> &mut String {
+        let idx = RenameField::ALL.iter().position(|f| f.eq(&field)).unwrap();
+        &mut self.values[idx]
+    }
+
+    fn pattern(&self) -> anyhow::Result<RenamePattern> {
+        let [find, replace, prefix, suffix] = &self.values;
+        RenamePattern::new(find, replace, prefix, suffix, self.include_uris)
+    }
+
+    fn changes(&self) -> anyhow::Result<Vec<RenameChange>> {
+        let pattern = self.pattern()?;
+        Ok(rename::preview(&self.requests.read().unwrap(), &pattern))
+    }
+
+    fn preview_lines(&self) -> Vec<Line<'static>> {
+        let changes = match self.changes() {
+            Ok(changes) => changes,
+            Err(e) => return vec![Line::from(e.to_string()).fg(self.colors.normal.red)],
+        };
+        if changes.is_empty() {
+            return vec![Line::from(Message::NothingToRename.text()).fg(self.colors.bright.black)];
+        }
+
+        let mut lines = vec![];
+        for change in changes.iter() {
+            let name = match change.renames() {
+                true => format!("{} → {}", change.name.0, change.name.1),
+                false => change.name.0.clone(),
+            };
+            lines.push(Line::from(name).fg(self.colors.normal.white));
+            if change.moves() {
+                lines.push(
+                    Line::from(format!("  {} → {}", change.uri.0, change.uri.1))
+                        .fg(self.colors.bright.black),
+                );
+            }
+        }
+        lines
+    }
+}
+
+impl Renderable for BulkRenameForm<'_> {
+    fn draw(&mut self, frame: &mut Frame, _: Rect) -> anyhow::Result<()> {
+        make_overlay(self.colors, self.colors.normal.black, 0.1, frame);
+
+        let mut preview = self.preview_lines();
+        let size = frame.size();
+        let width = size.width.div(2).max(60).min(size.width);
+        // two rows of two inputs each, and the hint below the preview
+        let inputs_height = 6;
+        let max_preview = size.height.saturating_sub(inputs_height.add(3)).max(1) as usize;
+        if preview.len().gt(&max_preview) {
+            let hidden = preview.len().sub(max_preview).add(1);
+            preview.truncate(max_preview.sub(1));
+            preview.push(
+                Line::from(Message::MoreLines.format(&[&hidden.to_string()]))
+                    .fg(self.colors.bright.black),
+            );
+        }
+        let preview_height = (preview.len() as u16).add(2);
+        let height = inputs_height.add(preview_height).add(1).min(size.height);
+        let size = Rect::new(
+            size.width.sub(width).div(2),
+            size.height.sub(height).div(2),
+            width,
+            height,
+        );
+        frame.render_widget(Clear, size);
+
+        let half = size.width.div(2);
+        let mut cursor = None;
+        for (idx, field) in RenameField::ALL.into_iter().enumerate() {
+            let x = size.x.add(half.mul(idx as u16 % 2));
+            let y = size.y.add(3.mul(idx as u16 / 2));
+            let width = match idx % 2 {
+                0 => half,
+                _ => size.width.sub(half),
+            };
+            let field_size = Rect::new(x, y, width, 3);
+            let mut input = Input::new(self.colors, field.name().into())
+                .placeholder(field.placeholder().into());
+            let is_focused = field.eq(&self.focused_field);
+            let value = self.value_mut(field);
+            if is_focused {
+                input.focus();
+                cursor = Some((
+                    field_size.x.add(value.chars().count() as u16).add(1),
+                    field_size.y.add(1),
+                ));
+            }
+            frame.render_stateful_widget(input, field_size, value);
+        }
+
+        let title = match self.include_uris {
+            true => Message::RenamingNamesAndUrls.format(&[&self.scope]),
+            false => Message::RenamingNames.format(&[&self.scope]),
+        };
+        let preview_size = Rect::new(
+            size.x,
+            size.y.add(inputs_height),
+            size.width,
+            preview_height.min(size.height.saturating_sub(inputs_height)),
+        );
+        frame.render_widget(
+            Paragraph::new(preview).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(title)
+                    .border_style(Style::default().fg(self.colors.bright.black)),
+            ),
+            preview_size,
+        );
+
+        let hint_size = Rect::new(size.x, preview_size.bottom(), size.width, 1);
+        if hint_size.bottom().le(&frame.size().height) {
+            frame.render_widget(
+                Line::from(Message::RenameHint.text())
+                    .fg(self.colors.bright.black)
+                    .centered(),
+                hint_size,
+            );
+        }
+
+        if let Some((x, y)) = cursor {
+            frame.set_cursor(x, y);
+        }
+
+        Ok(())
+    }
+}
+
+impl Eventful for BulkRenameForm<'_> {
+    type Result = BulkRenameFormEvent;
+
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> anyhow::Result<Option<Self::Result>> {
+        match (key_event.code, key_event.modifiers) {
+            (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
+                return Ok(Some(BulkRenameFormEvent::Cancel));
+            }
+            (KeyCode::Char('u'), KeyModifiers::CONTROL) => {
+                self.include_uris = !self.include_uris;
+                return Ok(None);
+            }
+            _ => {}
+        }
+
+        match key_event.code {
+            KeyCode::Esc => return Ok(Some(BulkRenameFormEvent::Cancel)),
+            KeyCode::Enter => {
+                // an invalid regex or a pattern that changes nothing keeps
+                // the form open, the preview already tells why
+                let Ok(changes) = self.changes() else {
+                    return Ok(None);
+                };
+                if changes.is_empty() {
+                    return Ok(None);
+                }
+                rename::apply(&changes);
+                return Ok(Some(BulkRenameFormEvent::Confirm));
+            }
+            KeyCode::Tab => self.focused_field = self.focused_field.next(),
+            KeyCode::BackTab => self.focused_field = self.focused_field.prev(),
+            KeyCode::Char(c) => self.value_mut(self.focused_field).push(c),
+            KeyCode::Backspace => _ = self.value_mut(self.focused_field).pop(),
+            _ => {}
+        }
+
+        Ok(None)
+    }
+}
@@ -67,6 +67,7 @@ impl Eventful for DirectoryForm<'_, DirectoryFormCreate> {
                     id: uuid::Uuid::new_v4().to_string(),
                     name: self.dir_name.clone(),
                     requests: Arc::new(RwLock::new(vec![])),
+                    docs: None,
                 }));
 
                 drop(store);
@@ -1,4 +1,7 @@
+use hac_core::collection::duplicates;
+use hac_core::collection::template;
 use hac_core::collection::types::*;
+use hac_core::collection::validation;
 
 use super::request_form::FormField;
 use super::request_form::RequestForm;
@@ -47,7 +50,11 @@ impl<'rf> RequestForm<'rf, RequestFormCreate> {
             collection_store,
             logo_idx,
             request_name: String::default(),
+            request_uri: String::default(),
             request_method: RequestMethod::Get,
+            request_timeout: String::default(),
+            timeout_error: None,
+            duplicate: None,
             parent_dir: None,
             focused_field: FormField::Name,
             marker: std::marker::PhantomData,
@@ -111,13 +118,55 @@ impl Eventful for RequestForm<'_, RequestFormCreate> {
             return Ok(None);
         }
 
+        if let (KeyCode::Char('o'), KeyModifiers::CONTROL) = (key_event.code, key_event.modifiers) {
+            if let Some(duplicate) = self.duplicate.take() {
+                self.reset();
+                return Ok(Some(RequestFormEvent::OpenExisting(duplicate)));
+            }
+            return Ok(None);
+        }
+
+        // the request has to be checked for duplicates again once what
+        // identifies it changes
+        if matches!(self.focused_field, FormField::Uri | FormField::Method)
+            && key_event.code.ne(&KeyCode::Enter)
+        {
+            self.duplicate = None;
+        }
+
         if let KeyCode::Enter = key_event.code {
+            let timeout_ms = match validation::parse_timeout(&self.request_timeout) {
+                Ok(timeout_ms) => timeout_ms,
+                Err(e) => {
+                    self.timeout_error = Some(e);
+                    return Ok(None);
+                }
+            };
+            if self.duplicate.is_none() {
+                let duplicate =
+                    self.collection_store
+                        .borrow()
+                        .get_requests()
+                        .and_then(|requests| {
+                            duplicates::find_duplicate(
+                                &requests.read().unwrap(),
+                                &self.request_method,
+                                &self.request_uri,
+                            )
+                        });
+                if duplicate.is_some() {
+                    self.duplicate = duplicate;
+                    return Ok(None);
+                }
+            }
+
             let store = self.collection_store.borrow_mut();
             let collection = store
                 .get_collection()
                 .expect("tried to create a request without a collection");
 
             let mut collection = collection.borrow_mut();
+            let template = template::for_collection(&collection);
             let requests = collection
                 .requests
                 .get_or_insert(Arc::new(RwLock::new(vec![])));
@@ -127,17 +176,21 @@ impl Eventful for RequestForm<'_, RequestFormCreate> {
                 self.request_name = String::from("unnamed request");
             }
 
-            let request = RequestKind::Single(Arc::new(RwLock::new(Request {
+            let mut request = Request {
                 id: uuid::Uuid::new_v4().to_string(),
-                auth_method: None,
-                body: None,
-                body_type: None,
+                timeout_ms,
                 parent: self.parent_dir.as_ref().map(|(id, _)| id.clone()),
-                headers: None,
                 method: self.request_method.clone(),
                 name: self.request_name.clone(),
-                uri: String::default(),
-            })));
+                uri: self.request_uri.clone(),
+                ..Default::default()
+            };
+            // new requests start from the template of the collection, or of
+            // the workspace, rather than completely blank
+            if let Some(template) = template {
+                template.apply(&mut request);
+            }
+            let request = RequestKind::Single(Arc::new(RwLock::new(request)));
 
             if let Some((dir_id, _)) = self.parent_dir.as_ref() {
                 if let RequestKind::Nested(dir) = requests
@@ -176,6 +229,15 @@ impl Eventful for RequestForm<'_, RequestFormCreate> {
                 }
                 _ => {}
             },
+            FormField::Uri => match key_event.code {
+                KeyCode::Char(c) => {
+                    self.request_uri.push(c);
+                }
+                KeyCode::Backspace => {
+                    self.request_uri.pop();
+                }
+                _ => {}
+            },
             FormField::Method => match key_event.code {
                 KeyCode::Char(c @ '1'..='5') => {
                     self.request_method = (c.to_digit(10).unwrap() as usize).sub(1).try_into()?;
@@ -190,6 +252,17 @@ impl Eventful for RequestForm<'_, RequestFormCreate> {
                 KeyCode::Char('l') => self.request_method = self.request_method.next(),
                 _ => {}
             },
+            FormField::Timeout => match key_event.code {
+                KeyCode::Char(c) if c.is_ascii_digit() => {
+                    self.request_timeout.push(c);
+                    self.timeout_error = None;
+                }
+                KeyCode::Backspace => {
+                    self.request_timeout.pop();
+                    self.timeout_error = None;
+                }
+                _ => {}
+            },
             FormField::Parent => {
                 if let KeyCode::Char(' ') = key_event.code {
                     let mut store = self.collection_store.borrow_mut();
@@ -1,4 +1,5 @@
 use crate::ascii::LOGO_ASCII;
+use crate::i18n::Message;
 use crate::pages::collection_viewer::collection_store::CollectionStore;
 use crate::pages::overlay::make_overlay;
 use crate::pages::{Eventful, Renderable};
@@ -66,27 +67,23 @@ impl Renderable for DeleteItemPrompt<'_> {
 
         let mut lines = if is_dir {
             vec![
+                Line::from(Message::DeleteDirectory.text().fg(self.colors.normal.red)).centered(),
                 Line::from(
-                    "Are you sure you want to delete the directory?".fg(self.colors.normal.red),
+                    Message::DeleteDirectoryRequests
+                        .text()
+                        .fg(self.colors.normal.red),
                 )
                 .centered(),
-                Line::from("This will delete all the requests inside".fg(self.colors.normal.red))
-                    .centered(),
                 Line::from(""),
             ]
         } else {
             vec![
-                Line::from(
-                    "Are you sure you want to delete the request?".fg(self.colors.normal.red),
-                )
-                .centered(),
+                Line::from(Message::DeleteRequest.text().fg(self.colors.normal.red)).centered(),
                 Line::from(""),
             ]
         };
 
-        lines.push(
-            Line::from("[Confirm: Enter] [Cancel: Esc]".fg(self.colors.bright.black)).centered(),
-        );
+        lines.push(Line::from(Message::ConfirmHint.text().fg(self.colors.bright.black)).centered());
 
         let logo = LOGO_ASCII[self.logo_idx];
         let logo_size = logo.len() as u16;
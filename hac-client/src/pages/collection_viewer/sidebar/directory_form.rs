@@ -1,4 +1,5 @@
 use crate::ascii::LOGO_ASCII;
+use crate::i18n::Message;
 use crate::pages::collection_viewer::collection_store::CollectionStore;
 use crate::pages::input::Input;
 use crate::pages::overlay::make_overlay;
@@ -77,10 +78,10 @@ impl<State> Renderable for DirectoryForm<'_, State> {
             .map(|line| Line::from(line.to_string().fg(self.colors.normal.red)).centered())
             .collect::<Vec<_>>();
 
-        let mut input = Input::new(self.colors, "Name".into());
+        let mut input = Input::new(self.colors, Message::Name.text().into());
         input.focus();
 
-        let hint = Line::from("[Confirm: Enter] [Cancel: Esc]")
+        let hint = Line::from(Message::ConfirmHint.text())
             .fg(self.colors.bright.black)
             .centered();
 
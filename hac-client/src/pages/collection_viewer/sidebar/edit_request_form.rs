@@ -1,4 +1,5 @@
 use hac_core::collection::types::*;
+use hac_core::collection::validation;
 
 use super::request_form::FormField;
 use super::request_form::RequestForm;
@@ -43,6 +44,13 @@ impl<'rf> RequestForm<'rf, RequestFormEdit> {
         let logo_idx = rand::rng().random_range(0..LOGO_ASCII.len());
         let request_method = request.read().unwrap().method.clone();
         let request_name = request.read().unwrap().name.clone();
+        let request_uri = request.read().unwrap().uri.clone();
+        let request_timeout = request
+            .read()
+            .unwrap()
+            .timeout_ms
+            .map(|timeout| timeout.to_string())
+            .unwrap_or_default();
 
         let parent_dir = if request.read().unwrap().parent.is_some() {
             let store = collection_store.borrow();
@@ -74,7 +82,11 @@ impl<'rf> RequestForm<'rf, RequestFormEdit> {
             collection_store,
             logo_idx,
             request_name,
+            request_uri,
             request_method,
+            request_timeout,
+            timeout_error: None,
+            duplicate: None,
             parent_dir,
             focused_field: FormField::Name,
             marker: std::marker::PhantomData,
@@ -135,11 +147,20 @@ impl Eventful for RequestForm<'_, RequestFormEdit> {
         }
 
         if let KeyCode::Enter = key_event.code {
+            let timeout_ms = match validation::parse_timeout(&self.request_timeout) {
+                Ok(timeout_ms) => timeout_ms,
+                Err(e) => {
+                    self.timeout_error = Some(e);
+                    return Ok(None);
+                }
+            };
             let request = self.request.as_mut().unwrap();
             let mut request = request.write().unwrap();
 
             request.name.clone_from(&self.request_name);
             request.method.clone_from(&self.request_method);
+            request.uri.clone_from(&self.request_uri);
+            request.timeout_ms = timeout_ms;
             request.parent = self.parent_dir.as_ref().map(|(id, _)| id.clone());
             let request_id = request.id.clone();
 
@@ -208,6 +229,15 @@ impl Eventful for RequestForm<'_, RequestFormEdit> {
                 }
                 _ => {}
             },
+            FormField::Uri => match key_event.code {
+                KeyCode::Char(c) => {
+                    self.request_uri.push(c);
+                }
+                KeyCode::Backspace => {
+                    self.request_uri.pop();
+                }
+                _ => {}
+            },
             FormField::Method => match key_event.code {
                 KeyCode::Char(c @ '1'..='5') => {
                     self.request_method = (c.to_digit(10).unwrap() as usize).sub(1).try_into()?;
@@ -222,6 +252,17 @@ impl Eventful for RequestForm<'_, RequestFormEdit> {
                 KeyCode::Char('l') => self.request_method = self.request_method.next(),
                 _ => {}
             },
+            FormField::Timeout => match key_event.code {
+                KeyCode::Char(c) if c.is_ascii_digit() => {
+                    self.request_timeout.push(c);
+                    self.timeout_error = None;
+                }
+                KeyCode::Backspace => {
+                    self.request_timeout.pop();
+                    self.timeout_error = None;
+                }
+                _ => {}
+            },
             FormField::Parent => {
                 if let KeyCode::Char(' ') = key_event.code {
                     let mut store = self.collection_store.borrow_mut();
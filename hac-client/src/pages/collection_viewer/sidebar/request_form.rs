@@ -1,6 +1,7 @@
 use hac_core::collection::types::{Request, RequestMethod};
 
 use crate::ascii::LOGO_ASCII;
+use crate::i18n::Message;
 use crate::pages::collection_viewer::collection_store::CollectionStore;
 use crate::pages::collection_viewer::sidebar::select_request_parent::SelectRequestParent;
 use crate::pages::input::Input;
@@ -22,20 +23,27 @@ use ratatui::Frame;
 pub enum RequestFormEvent {
     Confirm,
     Cancel,
+    /// the user chose to open the request that already sends the same method
+    /// to the same uri instead of creating another one
+    OpenExisting(Arc<RwLock<Request>>),
 }
 
 #[derive(Debug, PartialEq)]
 pub enum FormField {
     Name,
+    Uri,
     Method,
+    Timeout,
     Parent,
 }
 
 impl FormField {
     pub fn next(&self) -> Self {
         match self {
-            FormField::Name => FormField::Method,
-            FormField::Method => FormField::Parent,
+            FormField::Name => FormField::Uri,
+            FormField::Uri => FormField::Method,
+            FormField::Method => FormField::Timeout,
+            FormField::Timeout => FormField::Parent,
             FormField::Parent => FormField::Name,
         }
     }
@@ -43,8 +51,10 @@ impl FormField {
     pub fn prev(&self) -> Self {
         match self {
             FormField::Name => FormField::Parent,
-            FormField::Method => FormField::Name,
-            FormField::Parent => FormField::Method,
+            FormField::Uri => FormField::Name,
+            FormField::Method => FormField::Uri,
+            FormField::Timeout => FormField::Method,
+            FormField::Parent => FormField::Timeout,
         }
     }
 }
@@ -65,8 +75,18 @@ pub struct RequestForm<'rf, State = RequestFormCreate> {
     pub logo_idx: usize,
     /// the name of the current request being edited or created
     pub request_name: String,
+    pub request_uri: String,
     /// which method the request should have when finishing edition or creation
     pub request_method: RequestMethod,
+    /// timeout of the request in milliseconds, as typed, empty for none
+    pub request_timeout: String,
+    /// why the typed timeout can't be used, shown until the form is confirmed
+    /// with a valid one
+    pub timeout_error: Option<String>,
+    /// a request already sending the same method to the same uri, found when
+    /// confirming the creation of a new one. Confirming again creates it
+    /// anyway
+    pub duplicate: Option<Arc<RwLock<Request>>>,
     /// we store the parent dir uuid so its easier to find it and we dont need
     /// lifetimes or to Rc our way to hell, along with it we also store the name
     /// for displaying purposes
@@ -90,7 +110,11 @@ pub struct RequestForm<'rf, State = RequestFormCreate> {
 impl<'rf, State> RequestForm<'rf, State> {
     pub fn reset(&mut self) {
         self.request_name = String::default();
+        self.request_uri = String::default();
         self.request_method = RequestMethod::Get;
+        self.request_timeout = String::default();
+        self.timeout_error = None;
+        self.duplicate = None;
         self.focused_field = FormField::Name;
         self.parent_dir = None;
     }
@@ -107,7 +131,7 @@ impl<'rf, State> Renderable for RequestForm<'rf, State> {
         let mut logo = LOGO_ASCII[self.logo_idx];
         let mut logo_size = logo.len() as u16;
         // adding size of the form + spacing + hint
-        let total_size = logo_size.add(11).add(2);
+        let total_size = logo_size.add(17).add(2);
 
         let size = frame.size();
         let mut size = Rect::new(
@@ -117,14 +141,14 @@ impl<'rf, State> Renderable for RequestForm<'rf, State> {
                 .saturating_sub(logo_size.div(2))
                 .saturating_sub(6),
             65,
-            logo_size.add(12),
+            logo_size.add(18),
         );
 
         if total_size.ge(&frame.size().height) {
             logo = &[];
             logo_size = 0;
-            size.height = 12;
-            size.y = frame.size().height.div(2).saturating_sub(5);
+            size.height = 18;
+            size.y = frame.size().height.div(2).saturating_sub(9);
         }
 
         if !logo.is_empty() {
@@ -137,7 +161,11 @@ impl<'rf, State> Renderable for RequestForm<'rf, State> {
             frame.render_widget(Paragraph::new(logo), logo_size);
         }
 
-        let mut name_input = Input::new(self.colors, "Name".into());
+        let mut name_input = Input::new(self.colors, Message::Name.text().into());
+        let mut uri_input = Input::new(self.colors, Message::UriTitle.text().into())
+            .placeholder("{{base_url}}/users".into());
+        let mut timeout_input = Input::new(self.colors, Message::TimeoutMs.text().into())
+            .placeholder(Message::NoTimeout.text().into());
         let method_title = Paragraph::new("Method".fg(self.colors.normal.white));
         let hint =
             "[Confirm: Enter] [Cancel: Esc] [Switch: Tab] [Select: Space] [Remove Parent: <C-p>]";
@@ -147,11 +175,19 @@ impl<'rf, State> Renderable for RequestForm<'rf, State> {
         if self.focused_field.eq(&FormField::Name) {
             name_input.focus();
         }
+        if self.focused_field.eq(&FormField::Uri) {
+            uri_input.focus();
+        }
+        if self.focused_field.eq(&FormField::Timeout) {
+            timeout_input.focus();
+        }
 
         let name_size = Rect::new(size.x, size.y.add(logo_size).add(1), size.width, 3);
-        let method_title_size = Rect::new(size.x, name_size.y.add(3), size.width, 1);
+        let uri_size = Rect::new(size.x, name_size.y.add(3), size.width, 3);
+        let method_title_size = Rect::new(size.x, uri_size.y.add(3), size.width, 1);
         let methods_size = Rect::new(size.x, method_title_size.y.add(1), size.width, 3);
-        let parent_size = Rect::new(size.x, methods_size.y.add(3), size.width, 3);
+        let timeout_size = Rect::new(size.x, methods_size.y.add(3), size.width, 3);
+        let parent_size = Rect::new(size.x, timeout_size.y.add(3), size.width, 3);
         let hint_size = Rect::new(
             frame.size().width.div(2).saturating_sub(hint_size.div(2)),
             parent_size.y.add(4),
@@ -164,25 +200,25 @@ impl<'rf, State> Renderable for RequestForm<'rf, State> {
             .constraints((0..5).map(|_| Constraint::Length(13)))
             .split(methods_size);
 
-        let parent_name = if self.parent_dir.is_none() {
+        let parent_name = if let Some((_, parent_name)) = self.parent_dir.as_ref() {
             format!(
-                "{}None{}",
+                "{}{}{}",
                 " ".repeat(parent_size.width.div(2).sub(2).into()),
+                parent_name,
                 " ".repeat(parent_size.width.div(2).sub(2).into())
             )
-            .fg(self.colors.bright.black)
+            .fg(self.colors.normal.white)
         } else {
             format!(
-                "{}{}{}",
+                "{}None{}",
                 " ".repeat(parent_size.width.div(2).sub(2).into()),
-                self.parent_dir.as_ref().unwrap().1.clone(),
                 " ".repeat(parent_size.width.div(2).sub(2).into())
             )
-            .fg(self.colors.normal.white)
+            .fg(self.colors.bright.black)
         };
         let parent = Paragraph::new(parent_name).centered().block(
             Block::default()
-                .title("Parent".fg(self.colors.normal.white))
+                .title(Message::Parent.text().fg(self.colors.normal.white))
                 .borders(Borders::ALL)
                 .fg(if self.focused_field.eq(&FormField::Parent) {
                     self.colors.normal.red
@@ -211,10 +247,30 @@ impl<'rf, State> Renderable for RequestForm<'rf, State> {
         }
 
         frame.render_stateful_widget(name_input, name_size, &mut self.request_name);
+        frame.render_stateful_widget(uri_input, uri_size, &mut self.request_uri);
         frame.render_widget(method_title, method_title_size);
+        frame.render_stateful_widget(timeout_input, timeout_size, &mut self.request_timeout);
         frame.render_widget(parent, parent_size);
 
-        if self
+        if let Some(error) = self.timeout_error.as_ref() {
+            let error_size = Rect::new(size.x, hint_size.y, size.width, 1);
+            let error = Paragraph::new(error.as_str().fg(self.colors.normal.red)).centered();
+            frame.render_widget(error, error_size);
+        } else if let Some(duplicate) = self.duplicate.as_ref() {
+            let duplicate = duplicate.read().unwrap();
+            let warning_size = Rect::new(size.x, hint_size.y, size.width, 1);
+            let warning = Paragraph::new(
+                Message::DuplicateRequest
+                    .format(&[
+                        &duplicate.method.to_string(),
+                        &duplicate.uri,
+                        &duplicate.name,
+                    ])
+                    .fg(self.colors.normal.yellow),
+            )
+            .centered();
+            frame.render_widget(warning, warning_size);
+        } else if self
             .no_available_parent_timer
             .is_some_and(|timer| timer.elapsed().as_secs().le(&3))
         {
@@ -243,6 +299,24 @@ impl<'rf, State> Renderable for RequestForm<'rf, State> {
                 name_size.y.add(1),
             );
         }
+        if self.focused_field.eq(&FormField::Uri) {
+            frame.set_cursor(
+                uri_size
+                    .x
+                    .add(self.request_uri.chars().count() as u16)
+                    .add(1),
+                uri_size.y.add(1),
+            );
+        }
+        if self.focused_field.eq(&FormField::Timeout) {
+            frame.set_cursor(
+                timeout_size
+                    .x
+                    .add(self.request_timeout.chars().count() as u16)
+                    .add(1),
+                timeout_size.y.add(1),
+            );
+        }
 
         Ok(())
     }
@@ -0,0 +1,234 @@
+use hac_core::url_builder::{self, UrlParts};
+
+use crate::i18n::Message;
+use crate::pages::input::Input;
+use crate::pages::overlay::make_overlay;
+use crate::pages::{Eventful, Renderable};
+
+use std::collections::BTreeMap;
+use std::ops::{Add, Div, Mul, Sub};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::Rect;
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+use ratatui::Frame;
+
+/// most query parameters listed under the preview
+const MAX_LISTED_PARAMS: usize = 5;
+
+pub enum UrlBuilderFormEvent {
+    /// replaces the uri of the selected request
+    Save(String),
+    Cancel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum UrlField {
+    Scheme,
+    Host,
+    Path,
+    Query,
+    Fragment,
+}
+
+impl UrlField {
+    const ALL: [UrlField; 5] = [
+        UrlField::Scheme,
+        UrlField::Host,
+        UrlField::Path,
+        UrlField::Query,
+        UrlField::Fragment,
+    ];
+
+    fn next(&self) -> Self {
+        let idx = UrlField::ALL.iter().position(|f| f.eq(self)).unwrap_or(0);
+        UrlField::ALL[(idx + 1) % UrlField::ALL.len()]
+    }
+
+    fn prev(&self) -> Self {
+        let idx = UrlField::ALL.iter().position(|f| f.eq(self)).unwrap_or(0);
+        UrlField::ALL[(idx + UrlField::ALL.len() - 1) % UrlField::ALL.len()]
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            UrlField::Scheme => Message::Scheme.text(),
+            UrlField::Host => Message::Host.text(),
+            UrlField::Path => Message::Path.text(),
+            UrlField::Query => Message::Query.text(),
+            UrlField::Fragment => Message::Fragment.text(),
+        }
+    }
+
+    fn placeholder(&self) -> &'static str {
+        match self {
+            UrlField::Scheme => "https",
+            UrlField::Host => Message::UrlHostPlaceholder.text(),
+            UrlField::Path => "/users/{{user_id}}",
+            UrlField::Query => "page=1&sort=name",
+            UrlField::Fragment => "section",
+        }
+    }
+}
+
+/// edits the uri of the selected request one component at a time, showing
+/// the url that is actually sent as it changes
+#[derive(Debug)]
+pub struct UrlBuilderForm<'ub> {
+    colors: &'ub hac_colors::Colors,
+    parts: UrlParts,
+    focused_field: UrlField,
+    /// the variables of the active environment and session, used to resolve
+    /// the preview
+    variables: BTreeMap<String, String>,
+}
+
+impl<'ub> UrlBuilderForm<'ub> {
+    pub fn new(colors: &'ub hac_colors::Colors) -> Self {
+        UrlBuilderForm {
+            colors,
+            parts: UrlParts::default(),
+            focused_field: UrlField::Host,
+            variables: BTreeMap::default(),
+        }
+    }
+
+    /// starts editing the uri the request already has
+    pub fn set_uri(&mut self, uri: &str, variables: BTreeMap<String, String>) {
+        self.parts = UrlParts::split(uri);
+        self.focused_field = UrlField::Host;
+        self.variables = variables;
+    }
+
+    fn field_mut(&mut self, field: UrlField) -> &mut String {
+        match field {
+            UrlField::Scheme => &mut self.parts.scheme,
+            UrlField::Host => &mut self.parts.host,
+            UrlField::Path => &mut self.parts.path,
+            UrlField::Query => &mut self.parts.query,
+            UrlField::Fragment => &mut self.parts.fragment,
+        }
+    }
+
+    fn preview_lines(&self) -> Vec<Line<'static>> {
+        let uri = self.parts.join();
+        let mut lines = match url_builder::preview(&uri, &self.variables) {
+            Ok(url) => {
+                let mut lines = vec![Line::from(url.to_string()).fg(self.colors.normal.green)];
+                let params = url.query_pairs().collect::<Vec<_>>();
+                lines.extend(params.iter().take(MAX_LISTED_PARAMS).map(|(key, value)| {
+                    Line::from(format!("  {key} = {value}")).fg(self.colors.bright.black)
+                }));
+                if params.len().gt(&MAX_LISTED_PARAMS) {
+                    let more = params.len().sub(MAX_LISTED_PARAMS);
+                    lines.push(Line::from(format!("  +{more}")).fg(self.colors.bright.black));
+                }
+                lines
+            }
+            Err(e) => vec![Line::from(e).fg(self.colors.normal.red)],
+        };
+        lines.extend(
+            url_builder::warnings(&uri, &self.variables)
+                .into_iter()
+                .map(|warning| Line::from(warning).fg(self.colors.normal.yellow)),
+        );
+        lines
+    }
+}
+
+impl Renderable for UrlBuilderForm<'_> {
+    fn draw(&mut self, frame: &mut Frame, _: Rect) -> anyhow::Result<()> {
+        make_overlay(self.colors, self.colors.normal.black, 0.1, frame);
+
+        let preview = self.preview_lines();
+        let inputs_height = (UrlField::ALL.len() as u16).mul(3);
+        let preview_height = (preview.len() as u16).add(2);
+
+        let size = frame.size();
+        let width = size.width.div(2).max(60).min(size.width);
+        let height = inputs_height.add(preview_height).add(1).min(size.height);
+        let size = Rect::new(
+            size.width.sub(width).div(2),
+            size.height.sub(height).div(2),
+            width,
+            height,
+        );
+        frame.render_widget(Clear, size);
+
+        let mut cursor = None;
+        for (idx, field) in UrlField::ALL.into_iter().enumerate() {
+            let field_size = Rect::new(size.x, size.y.add(idx as u16 * 3), size.width, 3);
+            if field_size.bottom().gt(&size.bottom()) {
+                break;
+            }
+            let mut input = Input::new(self.colors, field.name().into())
+                .placeholder(field.placeholder().into());
+            let is_focused = field.eq(&self.focused_field);
+            let value = self.field_mut(field);
+            if is_focused {
+                input.focus();
+                cursor = Some((
+                    field_size.x.add(value.chars().count() as u16).add(1),
+                    field_size.y.add(1),
+                ));
+            }
+            frame.render_stateful_widget(input, field_size, value);
+        }
+
+        let preview_size = Rect::new(
+            size.x,
+            size.y.add(inputs_height),
+            size.width,
+            preview_height.min(size.height.saturating_sub(inputs_height)),
+        );
+        frame.render_widget(
+            Paragraph::new(preview).wrap(Wrap { trim: false }).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(Message::Preview.text())
+                    .border_style(Style::default().fg(self.colors.bright.black)),
+            ),
+            preview_size,
+        );
+
+        let hint_size = Rect::new(size.x, preview_size.bottom(), size.width, 1);
+        if hint_size.bottom().le(&frame.size().height) {
+            frame.render_widget(
+                Line::from(Message::UrlBuilderHint.text())
+                    .fg(self.colors.bright.black)
+                    .centered(),
+                hint_size,
+            );
+        }
+
+        if let Some((x, y)) = cursor {
+            frame.set_cursor(x, y);
+        }
+
+        Ok(())
+    }
+}
+
+impl Eventful for UrlBuilderForm<'_> {
+    type Result = UrlBuilderFormEvent;
+
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> anyhow::Result<Option<Self::Result>> {
+        if let (KeyCode::Char('c'), KeyModifiers::CONTROL) = (key_event.code, key_event.modifiers) {
+            return Ok(Some(UrlBuilderFormEvent::Cancel));
+        }
+
+        match key_event.code {
+            KeyCode::Esc => return Ok(Some(UrlBuilderFormEvent::Cancel)),
+            KeyCode::Enter => return Ok(Some(UrlBuilderFormEvent::Save(self.parts.join()))),
+            KeyCode::Tab => self.focused_field = self.focused_field.next(),
+            KeyCode::BackTab => self.focused_field = self.focused_field.prev(),
+            KeyCode::Char(c) => self.field_mut(self.focused_field).push(c),
+            KeyCode::Backspace => _ = self.field_mut(self.focused_field).pop(),
+            _ => {}
+        }
+
+        Ok(None)
+    }
+}
@@ -1,3 +1,5 @@
+use crate::i18n::Message;
+
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::{Style, Stylize};
@@ -19,9 +21,9 @@ impl<'a> ConfirmPopup<'a> {
             self.message.clone().fg(self.colors.normal.yellow).into(),
             "".into(),
             Line::from(vec![
-                "(y)es".fg(self.colors.normal.green),
+                Message::Yes.text().fg(self.colors.normal.green),
                 " ".into(),
-                "(n)o".fg(self.colors.normal.red),
+                Message::No.text().fg(self.colors.normal.red),
             ])
             .centered(),
         ];
@@ -1,3 +1,5 @@
+use crate::i18n::Message;
+
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Direction, Flex, Layout, Rect};
 use ratatui::style::{Style, Stylize};
@@ -23,8 +25,13 @@ impl<'a> ErrorPopup<'a> {
         let message = Paragraph::new(self.message.clone().fg(self.colors.normal.red))
             .wrap(Wrap { trim: true });
 
-        let confirmation = Paragraph::new("(O)k".fg(self.colors.normal.green).into_centered_line())
-            .wrap(Wrap { trim: true });
+        let confirmation = Paragraph::new(
+            Message::Ok
+                .text()
+                .fg(self.colors.normal.green)
+                .into_centered_line(),
+        )
+        .wrap(Wrap { trim: true });
 
         (message, confirmation)
     }
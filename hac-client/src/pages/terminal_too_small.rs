@@ -1,3 +1,4 @@
+use crate::i18n::Message;
 use crate::pages::Renderable;
 
 use ratatui::layout::{Alignment, Constraint, Direction, Flex, Layout, Rect};
@@ -24,16 +25,30 @@ impl Renderable for TerminalTooSmall<'_> {
     fn draw(&mut self, frame: &mut Frame, size: Rect) -> anyhow::Result<()> {
         let layout = build_layout(size);
 
-        let lines = Line::from("Terminal is too small:".bold().fg(self.colors.bright.black));
+        let lines = Line::from(
+            Message::TerminalTooSmall
+                .text()
+                .bold()
+                .fg(self.colors.bright.black),
+        );
         let curr_size = Line::from(vec![
-            "Width = ".bold().fg(self.colors.bright.black),
+            Message::Width.text().bold().fg(self.colors.bright.black),
             format!("{} ", size.width).bold().fg(self.colors.normal.red),
-            "Height = ".bold().fg(self.colors.bright.black),
+            Message::Height.text().bold().fg(self.colors.bright.black),
             format!("{}", size.height).bold().fg(self.colors.normal.red),
         ]);
         let empty = Line::from(" ");
-        let hint = Line::from("Minimum size needed:".bold().fg(self.colors.bright.black));
-        let min_size = Line::from("Width = 80 Height = 22".bold().fg(self.colors.bright.black));
+        let hint = Line::from(
+            Message::MinimumSize
+                .text()
+                .bold()
+                .fg(self.colors.bright.black),
+        );
+        let min_size = Line::from(
+            format!("{}80 {}22", Message::Width.text(), Message::Height.text())
+                .bold()
+                .fg(self.colors.bright.black),
+        );
 
         let text = Paragraph::new(vec![lines, curr_size, empty, hint, min_size])
             .wrap(Wrap { trim: true })
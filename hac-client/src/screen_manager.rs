@@ -1,11 +1,13 @@
 use hac_core::{collection::Collection, command::Command};
 
 use crate::event_pool::Event;
+use crate::i18n::Message;
 use crate::pages::collection_dashboard::CollectionDashboard;
 use crate::pages::collection_viewer::collection_store::CollectionStore;
 use crate::pages::collection_viewer::CollectionViewer;
 use crate::pages::terminal_too_small::TerminalTooSmall;
 use crate::pages::{Eventful, Renderable};
+use crate::utils::strip_decorations;
 
 use std::{cell::RefCell, rc::Rc};
 
@@ -68,6 +70,38 @@ impl<'sm> ScreenManager<'sm> {
         })
     }
 
+    /// swaps the colors and config every screen is drawn with, keeping
+    /// whatever they were showing
+    pub fn reload_config(
+        &mut self,
+        colors: &'sm hac_colors::Colors,
+        config: &'sm hac_config::Config,
+        files: usize,
+    ) {
+        self.colors = colors;
+        self.config = config;
+        self.terminal_too_small = TerminalTooSmall::new(colors);
+        self.collection_list.set_colors(colors);
+        if let Some(viewer) = self.collection_viewer.as_mut() {
+            viewer.reload_config(colors, config, self.size);
+            viewer.notify(
+                Message::Config,
+                Message::ConfigReloaded.format(&[&files.to_string()]),
+                false,
+            );
+        }
+    }
+
+    /// reports a configuration that failed to load on the current screen
+    pub fn config_failed(&mut self, error: String) {
+        match (self.curr_screen.clone(), self.collection_viewer.as_mut()) {
+            (Screens::CollectionViewer, Some(viewer)) => {
+                viewer.notify(Message::Config, error, true)
+            }
+            _ => self.collection_list.display_error(error),
+        }
+    }
+
     fn restore_screen(&mut self) {
         std::mem::swap(&mut self.curr_screen, &mut self.prev_screen);
     }
@@ -108,6 +142,25 @@ impl<'sm> ScreenManager<'sm> {
             Command::Error(msg) => {
                 self.collection_list.display_error(msg);
             }
+            Command::ExternalClosed(result) => {
+                if let Some(viewer) = self.collection_viewer.as_mut() {
+                    viewer.external_closed(result);
+                }
+            }
+            Command::CollectionConflict => {
+                if let Some(viewer) = self.collection_viewer.as_mut() {
+                    viewer.sync_conflict();
+                }
+            }
+            Command::UpdateAvailable(latest) => {
+                let message = Message::UpdateVersion.format(&[&latest, env!("CARGO_PKG_VERSION")]);
+                if let (Screens::CollectionViewer, Some(viewer)) =
+                    (self.curr_screen.clone(), self.collection_viewer.as_mut())
+                {
+                    viewer.notify(Message::UpdateAvailable, message.clone(), false);
+                }
+                self.collection_list.set_update_notice(message);
+            }
             _ => {}
         }
     }
@@ -136,6 +189,10 @@ impl Renderable for ScreenManager<'_> {
             Screens::TerminalTooSmall => self.terminal_too_small.draw(frame, frame.size())?,
         };
 
+        if self.config.accessibility.plain {
+            strip_decorations(frame.buffer_mut());
+        }
+
         Ok(())
     }
 
@@ -285,8 +342,16 @@ mod tests {
             info: Info {
                 name: String::from("any_name"),
                 description: None,
+                read_only: false,
             },
             path: "any_path".into(),
+            unlocked: false,
+            protos: vec![],
+            highlights: vec![],
+            template: None,
+            format_on_save: false,
+            synced: Default::default(),
+            environments: vec![],
             requests: None,
         };
         let command = Command::SelectCollection(collection.clone());
@@ -302,6 +367,29 @@ mod tests {
         assert_eq!(sm.curr_screen, Screens::CollectionViewer);
     }
 
+    #[test]
+    fn test_reload_config_keeps_screen() {
+        let initial = Rect::new(0, 0, 80, 22);
+        let colors = hac_colors::Colors::default();
+        let color_blind = hac_colors::Colors::color_blind();
+        let (_guard, path) = setup_temp_collections(1);
+        let collections = collection::collection::get_collections(path).unwrap();
+        let command = Command::SelectCollection(collections[0].clone());
+        let config = hac_config::load_config();
+        let (tx, _) = tokio::sync::mpsc::unbounded_channel::<Command>();
+        let mut sm = ScreenManager::new(initial, &colors, collections, &config, true).unwrap();
+        _ = sm.register_command_handler(tx.clone());
+        sm.handle_command(command);
+
+        sm.reload_config(&color_blind, &config, 1);
+        assert_eq!(sm.curr_screen, Screens::CollectionViewer);
+        assert!(sm.collection_viewer.is_some());
+        assert_eq!(sm.colors.normal.red, color_blind.normal.red);
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 22)).unwrap();
+        sm.draw(&mut terminal.get_frame(), initial).unwrap();
+    }
+
     #[test]
     fn test_register_command_sender_for_dashboard() {
         let initial = Rect::new(0, 0, 80, 22);
@@ -1,8 +1,11 @@
+use hac_core::collection::environment::variable_spans;
 use hac_core::syntax::highlighter::HIGHLIGHTER;
+use hac_core::syntax::xml;
 
-use std::ops::Add;
+use std::ops::{Add, Sub};
 
-use ratatui::style::{Color, Stylize};
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier, Style, Stylize};
 use ratatui::text::{Line, Span};
 use tree_sitter::Tree;
 
@@ -20,11 +23,15 @@ pub fn build_syntax_highlighted_lines(
     let mut styled_lines: Vec<Line> = vec![];
 
     // `HIGHLIGHTER` returns a vector of `ColorInfo`, which contains information about
-    // which kind of token that is, and the style to apply to it
-    let mut highlights = HIGHLIGHTER
-        .read()
-        .unwrap()
-        .apply(content, tree, &colors.tokens);
+    // which kind of token that is, and the style to apply to it. XML bodies, like the
+    // ones of SOAP services, are scanned by the xml module instead
+    let mut highlights = match xml::looks_like_xml(content) {
+        true => xml::highlight(content, &colors.tokens),
+        false => HIGHLIGHTER
+            .read()
+            .unwrap()
+            .apply(content, tree, &colors.tokens),
+    };
 
     // these are helper variables to collect each line into styled spans based on the
     // token it contains
@@ -145,6 +152,257 @@ pub fn build_syntax_highlighted_lines(
     styled_lines
 }
 
+/// Builds the spans of a single line text, like an uri or a header, where every
+/// `{{variable}}` is styled with the variable token and the rest with `style`
+pub fn build_variable_spans(
+    text: &str,
+    style: Style,
+    colors: &hac_colors::Colors,
+) -> Vec<Span<'static>> {
+    let variable_style = colors.tokens.get("variable").copied().unwrap_or(style);
+    let mut spans = vec![];
+    let mut offset = 0;
+
+    for variable in variable_spans(text) {
+        if variable.start.gt(&offset) {
+            spans.push(Span::styled(
+                text[offset..variable.start].to_string(),
+                style,
+            ));
+        }
+        spans.push(Span::styled(
+            text[variable.start..variable.end].to_string(),
+            variable_style,
+        ));
+        offset = variable.end;
+    }
+
+    if offset.lt(&text.len()) {
+        spans.push(Span::styled(text[offset..].to_string(), style));
+    }
+
+    spans
+}
+
+/// Draws the whitespace of a syntax highlighted line as symbols styled with
+/// `style`: tabs as `→`, trailing spaces as `·` and the line break as `↵`,
+/// or `␍↵` when it is CRLF. Symbols take the column of the char they stand
+/// for, so the cursor stays where it was
+pub fn show_whitespace(line: &Line<'static>, ending: &str, style: Style) -> Line<'static> {
+    let text = line
+        .spans
+        .iter()
+        .flat_map(|span| span.content.chars())
+        .filter(|c| !is_endline(*c))
+        .collect::<String>();
+    let trailing_from = text.trim_end_matches([' ', '\t']).chars().count();
+
+    let mut spans = vec![];
+    let mut col = 0;
+    for span in line.spans.iter() {
+        let mut content = String::default();
+        for c in span.content.chars().filter(|c| !is_endline(*c)) {
+            let symbol = match c {
+                '\t' => Some('→'),
+                ' ' if col.ge(&trailing_from) => Some('·'),
+                _ => None,
+            };
+            col += 1;
+            let Some(symbol) = symbol else {
+                content.push(c);
+                continue;
+            };
+            if !content.is_empty() {
+                spans.push(Span::styled(std::mem::take(&mut content), span.style));
+            }
+            spans.push(Span::styled(symbol.to_string(), span.style.patch(style)));
+        }
+        if !content.is_empty() {
+            spans.push(Span::styled(content, span.style));
+        }
+    }
+
+    let ending = match ending {
+        "\r\n" => "␍↵",
+        "\n" => "↵",
+        _ => "",
+    };
+    if !ending.is_empty() {
+        spans.push(Span::styled(ending, style));
+    }
+
+    Line::from(spans).style(line.style)
+}
+
+/// Builds a vector of `Lines` from a markdown document, supporting the subset of
+/// markdown that is useful for documenting requests: headings, lists, quotes, rules,
+/// fenced code blocks, and inline code, bold, italic and links
+pub fn build_markdown_lines(content: &str, colors: &hac_colors::Colors) -> Vec<Line<'static>> {
+    let mut lines = vec![];
+    let mut in_code_block = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+
+        if in_code_block {
+            lines.push(Line::from(format!("  {line}")).fg(colors.normal.green));
+            continue;
+        }
+
+        let heading_level = trimmed.chars().take_while(|c| c.eq(&'#')).count();
+        if (1..=6).contains(&heading_level) && trimmed[heading_level..].starts_with(' ') {
+            let color = match heading_level {
+                1 => colors.normal.red,
+                2 => colors.normal.blue,
+                _ => colors.normal.magenta,
+            };
+            let style = Style::default().fg(color).add_modifier(Modifier::BOLD);
+            lines.push(Line::from(build_markdown_spans(
+                trimmed[heading_level..].trim(),
+                style,
+                colors,
+            )));
+            continue;
+        }
+
+        if matches!(trimmed, "---" | "***" | "___") {
+            lines.push(Line::from("─".repeat(40)).fg(colors.bright.black));
+            continue;
+        }
+
+        let indent = " ".repeat(line.len().sub(trimmed.len()));
+        let text_style = Style::default().fg(colors.normal.white);
+
+        if let Some(quote) = trimmed.strip_prefix('>') {
+            let mut spans = vec![Span::from("│ ").fg(colors.bright.black)];
+            spans.extend(build_markdown_spans(
+                quote.trim_start(),
+                text_style.add_modifier(Modifier::ITALIC),
+                colors,
+            ));
+            lines.push(Line::from(spans));
+            continue;
+        }
+
+        let list_item = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|marker| trimmed.strip_prefix(marker));
+        if let Some(item) = list_item {
+            let mut spans = vec![Span::from(format!("{indent}• ")).fg(colors.normal.yellow)];
+            spans.extend(build_markdown_spans(item, text_style, colors));
+            lines.push(Line::from(spans));
+            continue;
+        }
+
+        let mut spans = build_markdown_spans(trimmed, text_style, colors);
+        if !indent.is_empty() {
+            spans.insert(0, Span::from(indent));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    lines
+}
+
+/// styles the inline elements of a single markdown line
+fn build_markdown_spans(
+    text: &str,
+    base: Style,
+    colors: &hac_colors::Colors,
+) -> Vec<Span<'static>> {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut spans = vec![];
+    let mut current = String::new();
+    let mut bold = false;
+    let mut italic = false;
+    let mut i = 0;
+
+    let style_for = |bold: bool, italic: bool| {
+        let mut style = base;
+        if bold {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+        if italic {
+            style = style.add_modifier(Modifier::ITALIC);
+        }
+        style
+    };
+
+    let find_from = |from: usize, needle: char| {
+        chars[from..]
+            .iter()
+            .position(|c| c.eq(&needle))
+            .map(|pos| pos.add(from))
+    };
+
+    while i < chars.len() {
+        match (chars[i], chars.get(i + 1)) {
+            ('\\', Some(escaped)) => {
+                current.push(*escaped);
+                i += 2;
+            }
+            ('`', _) => match find_from(i + 1, '`') {
+                Some(close) => {
+                    spans.push(Span::styled(current.clone(), style_for(bold, italic)));
+                    current.clear();
+                    let code = chars[i + 1..close].iter().collect::<String>();
+                    spans.push(Span::from(code).fg(colors.normal.green));
+                    i = close + 1;
+                }
+                None => {
+                    current.push('`');
+                    i += 1;
+                }
+            },
+            ('*', Some('*')) | ('_', Some('_')) => {
+                spans.push(Span::styled(current.clone(), style_for(bold, italic)));
+                current.clear();
+                bold = !bold;
+                i += 2;
+            }
+            ('*', _) | ('_', _) => {
+                spans.push(Span::styled(current.clone(), style_for(bold, italic)));
+                current.clear();
+                italic = !italic;
+                i += 1;
+            }
+            ('[', _) => {
+                let link = find_from(i + 1, ']')
+                    .filter(|close| chars.get(close + 1).is_some_and(|c| c.eq(&'(')))
+                    .and_then(|close| find_from(close + 2, ')').map(|end| (close, end)));
+                match link {
+                    Some((close, end)) => {
+                        spans.push(Span::styled(current.clone(), style_for(bold, italic)));
+                        current.clear();
+                        let label = chars[i + 1..close].iter().collect::<String>();
+                        let url = chars[close + 2..end].iter().collect::<String>();
+                        spans.push(Span::from(label).fg(colors.normal.blue).underlined());
+                        spans.push(Span::from(format!(" ({url})")).fg(colors.bright.black));
+                        i = end + 1;
+                    }
+                    None => {
+                        current.push('[');
+                        i += 1;
+                    }
+                }
+            }
+            (c, _) => {
+                current.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    spans.push(Span::styled(current, style_for(bold, italic)));
+    spans.retain(|span| !span.content.is_empty());
+    spans
+}
+
 /// will try to apply a blending using multiply to two colors, based on a given alpha.
 ///
 /// It will apply the background over the foreground so we get a middleground color. This
@@ -221,3 +479,106 @@ fn ansi_to_rgb(val: u8) -> Option<(u8, u8, u8)> {
         None
     }
 }
+
+/// blanks every box drawing and block character on the buffer, so borders,
+/// scrollbars and other decorations are not read out by screen readers
+pub fn strip_decorations(buffer: &mut Buffer) {
+    buffer
+        .content
+        .iter_mut()
+        .filter(|cell| {
+            cell.symbol()
+                .chars()
+                .all(|c| matches!(c, '\u{2500}'..='\u{259f}'))
+        })
+        .for_each(|cell| {
+            cell.set_symbol(" ");
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_inline_styles() {
+        let colors = hac_colors::Colors::default();
+        let lines = build_markdown_lines("use **bold**, `code` and [docs](http://a.b)", &colors);
+        let contents = lines[0]
+            .spans
+            .iter()
+            .map(|span| span.content.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            contents,
+            vec![
+                "use ",
+                "bold",
+                ", ",
+                "code",
+                " and ",
+                "docs",
+                " (http://a.b)"
+            ]
+        );
+        assert!(lines[0].spans[1]
+            .style
+            .add_modifier
+            .contains(Modifier::BOLD));
+    }
+
+    #[test]
+    fn test_markdown_code_blocks_are_not_parsed() {
+        let colors = hac_colors::Colors::default();
+        let lines = build_markdown_lines("# Title\n```\n**not bold**\n```\n- item", &colors);
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1].spans[0].content, "  **not bold**");
+        assert_eq!(lines[2].spans[0].content, "• ");
+    }
+
+    #[test]
+    fn test_show_whitespace() {
+        let style = Style::default().fg(Color::DarkGray);
+        let line = Line::from(vec![
+            Span::from("\t\"a\": "),
+            Span::from("1, ").fg(Color::Red),
+            Span::from(" \r\n"),
+        ]);
+
+        let shown = show_whitespace(&line, "\r\n", style);
+        let contents = shown
+            .spans
+            .iter()
+            .map(|span| span.content.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(contents, vec!["→", "\"a\": ", "1,", "·", "·", "␍↵"]);
+        assert_eq!(shown.spans[3].style.fg, Some(Color::DarkGray));
+
+        let shown = show_whitespace(&Line::from("a b"), "", style);
+        assert_eq!(shown.spans[0].content, "a b");
+        assert_eq!(shown.spans.len(), 1);
+    }
+
+    #[test]
+    fn test_strip_decorations() {
+        use crate::i18n::Message;
+        use ratatui::layout::Rect;
+        use ratatui::widgets::{Block, Borders, Widget};
+
+        let area = Rect::new(0, 0, 8, 3);
+        let mut buffer = Buffer::empty(area);
+        Block::default()
+            .borders(Borders::ALL)
+            .title(Message::UriTitle.text())
+            .render(area, &mut buffer);
+
+        strip_decorations(&mut buffer);
+
+        assert_eq!(
+            buffer,
+            Buffer::with_lines(vec![" Uri    ", "        ", "        "])
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
-use ratatui::style::{Color, Style};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::Span;
 
 #[derive(Debug, PartialEq)]
 pub struct Colors {
@@ -8,6 +9,7 @@ pub struct Colors {
     pub normal: NormalColors,
     pub bright: BrightColors,
     pub tokens: HashMap<String, Style>,
+    pub indicators: Indicators,
 }
 
 impl Default for Colors {
@@ -17,10 +19,63 @@ impl Default for Colors {
             normal: Default::default(),
             bright: Default::default(),
             tokens: token_highlight(),
+            indicators: Default::default(),
         }
     }
 }
 
+impl Colors {
+    /// the default colors, but with indicators that can be told apart with
+    /// any kind of color blindness, and that always show their symbols
+    pub fn color_blind() -> Self {
+        Colors {
+            indicators: Indicators {
+                success: Color::Rgb(0x56, 0xb4, 0xe9),
+                failure: Color::Rgb(0xe6, 0x9f, 0x00),
+                changed: Color::Rgb(0xf0, 0xe4, 0x42),
+                symbols: true,
+            },
+            ..Default::default()
+        }
+    }
+
+    /// styles the text with the color of the indicator, prefixing it with its
+    /// symbol when symbols are enabled
+    pub fn indicate<S>(&self, indicator: Indicator, text: S) -> Span<'static>
+    where
+        S: Into<String>,
+    {
+        let (color, symbol) = match indicator {
+            Indicator::Success => (self.indicators.success, "✓"),
+            Indicator::Failure => (self.indicators.failure, "✗"),
+            Indicator::Changed => (self.indicators.changed, "Δ"),
+        };
+
+        let text = text.into();
+        match self.indicators.symbols {
+            true => format!("{symbol} {text}").fg(color),
+            false => text.fg(color),
+        }
+    }
+}
+
+/// states that shouldn't be conveyed by color alone
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Indicator {
+    Success,
+    Failure,
+    Changed,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Indicators {
+    pub success: Color,
+    pub failure: Color,
+    pub changed: Color,
+    /// whether indicators are prefixed with a symbol
+    pub symbols: bool,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct PrimaryColors {
     pub foreground: Color,
@@ -70,10 +125,31 @@ fn token_highlight() -> HashMap<String, Style> {
         Style::new().fg(colors.magenta),
     );
     tokens.insert("string".into(), Style::new().fg(colors.green));
+    tokens.insert("tag".into(), Style::new().fg(colors.blue));
+    tokens.insert(
+        "variable".into(),
+        Style::new().fg(colors.orange).add_modifier(Modifier::BOLD),
+    );
+    tokens.insert(
+        "comment".into(),
+        Style::new().fg(colors.black).add_modifier(Modifier::ITALIC),
+    );
 
     tokens
 }
 
+impl Default for Indicators {
+    fn default() -> Self {
+        let colors = NormalColors::default();
+        Indicators {
+            success: colors.green,
+            failure: colors.red,
+            changed: colors.yellow,
+            symbols: false,
+        }
+    }
+}
+
 impl Default for PrimaryColors {
     fn default() -> Self {
         PrimaryColors {
@@ -1,3 +1,3 @@
 pub mod colors;
 
-pub use colors::Colors;
+pub use colors::{Colors, Indicator};
@@ -2,6 +2,7 @@ use crate::default_config::DEFAULT_CONFIG;
 use crate::{EditorMode, APP_NAME, CONFIG_ENV_VAR, CONFIG_FILE, XDG_DEFAULTS, XDG_ENV_VARS};
 
 use std::collections::HashMap;
+use std::net::IpAddr;
 use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
@@ -47,11 +48,333 @@ pub enum Action {
     JumpToClosing,
     JumpToEmptyLineBelow,
     JumpToEmptyLineAbove,
+    NextVariable,
+    PreviousVariable,
+    Base64Encode,
+    Base64Decode,
+    UrlEncode,
+    UrlDecode,
+    JsonEscape,
+    JsonUnescape,
+    ConvertTimestamp,
+    GenerateFromSchema,
+    ToggleHexMode,
+    EditString,
+    SearchForward,
+    SearchBackward,
+    CommandLine,
+    RepeatEdit,
+    RecordMacro,
+    PlayMacro,
+    SetMark,
+    JumpToMark,
+    JumpBack,
+    JumpForward,
+    ScrollCursorCenter,
+    ScrollCursorTop,
+    ScrollCursorBottom,
+}
+
+impl Action {
+    /// whether performing this action changes the contents of the buffer, or
+    /// puts the editor in a mode where it can be changed
+    pub fn modifies_buffer(&self) -> bool {
+        !matches!(
+            self,
+            Action::FindNext
+                | Action::FindPrevious
+                | Action::SearchForward
+                | Action::SearchBackward
+                | Action::NextWord
+                | Action::PreviousWord
+                | Action::MoveLeft
+                | Action::MoveDown
+                | Action::MoveUp
+                | Action::MoveRight
+                | Action::MoveToBottom
+                | Action::MoveToTop
+                | Action::MoveToLineEnd
+                | Action::MoveToLineStart
+                | Action::PageDown
+                | Action::PageUp
+                | Action::EnterMode(EditorMode::Normal)
+                | Action::MoveAfterWhitespaceReverse
+                | Action::MoveAfterWhitespace
+                | Action::JumpToClosing
+                | Action::JumpToEmptyLineBelow
+                | Action::JumpToEmptyLineAbove
+                | Action::NextVariable
+                | Action::PreviousVariable
+                | Action::RecordMacro
+                | Action::SetMark
+                | Action::JumpToMark
+                | Action::JumpBack
+                | Action::JumpForward
+                | Action::ScrollCursorCenter
+                | Action::ScrollCursorTop
+                | Action::ScrollCursorBottom
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub editor_keys: Keys,
+    /// bodies are allowed to have comments and trailing commas (JSONC) while
+    /// editing, when this is set we strip them before actually sending the
+    /// request, so servers only accepting strict JSON won't reject it
+    #[serde(default = "default_true")]
+    pub strip_body_comments: bool,
+    /// the body editor draws tabs, trailing spaces and line breaks, for
+    /// bodies where whitespace matters, like signed ones
+    #[serde(default)]
+    pub show_whitespace: bool,
+    /// numbers drawn next to the lines of the body editor
+    #[serde(default)]
+    pub line_numbers: LineNumbers,
+    /// lines of the body editor kept visible above and below the cursor
+    #[serde(default)]
+    pub scrolloff: usize,
+    /// user defined snippets, keyed by the word that triggers them when
+    /// pressing tab on insert mode, these take precedence over builtin ones
+    #[serde(default)]
+    pub snippets: HashMap<String, String>,
+    /// insert mode abbreviations, when a word matching one of these keys is
+    /// followed by a word boundary it is replaced by its expansion
+    #[serde(default)]
+    pub abbreviations: HashMap<String, String>,
+    /// how rate limited responses are handled
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    /// name of the environment requests are being sent against, eg: staging,
+    /// requests can be restricted to only run on some environments
+    #[serde(default)]
+    pub environment: Option<String>,
+    /// how the interface is colored, and whether states are also shown with
+    /// symbols
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    #[serde(default)]
+    pub accessibility: AccessibilityConfig,
+    /// language of the interface
+    #[serde(default)]
+    pub locale: Locale,
+    /// makes urls on the screen clickable with OSC 8 escape sequences, only
+    /// enable it when the terminal supports them
+    #[serde(default)]
+    pub hyperlinks: bool,
+    /// command used to compare responses, like `vimdiff` or
+    /// `code --diff --wait`, both files are passed as its last arguments
+    #[serde(default = "default_diff_tool")]
+    pub diff_tool: String,
+    /// which of curl's own configuration files are read when sending
+    /// requests
+    #[serde(default)]
+    pub curl: CurlConfig,
+    /// bodies of at least this many bytes are sent with
+    /// `Expect: 100-continue`, waiting for the server to accept the request
+    /// before uploading them
+    #[serde(default)]
+    pub expect_continue_bytes: Option<u64>,
+    /// simulates a slow link for every request that doesn't set its own
+    /// conditions
+    #[serde(default)]
+    pub network: NetworkConfig,
+    /// how outgoing connections are made, taking precedence over the
+    /// `.curlrc`
+    #[serde(default)]
+    pub connection: ConnectionConfig,
+    /// no request reaches the network, their stored examples are shown
+    /// instead
+    #[serde(default)]
+    pub offline: bool,
+    /// requests to these hosts fail unless their certificate chain has one
+    /// of the listed public keys, as `sha256/<base64>` hashes of their SPKI.
+    /// `*.example.com` pins every subdomain of example.com
+    #[serde(default)]
+    pub pins: HashMap<String, Vec<String>>,
+    /// how much of large bodies is shown and kept in memory
+    #[serde(default)]
+    pub limits: LimitsConfig,
+    /// once a day, asks github whether a newer release exists and tells
+    /// when one does
+    #[serde(default)]
+    pub check_updates: bool,
+}
+
+fn default_diff_tool() -> String {
+    "vimdiff".into()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// when set, rate limited requests (`429` or `503` with `Retry-After`)
+    /// are sent again after waiting for as long as the server asked
+    #[serde(default)]
+    pub auto_retry: bool,
+    /// how many times a single request can be retried
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// requests asking us to wait longer than this, in seconds, are not
+    /// retried, as waiting that long is rarely desired
+    #[serde(default = "default_max_wait_secs")]
+    pub max_wait_secs: u64,
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_max_wait_secs() -> u64 {
+    60
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        RateLimitConfig {
+            auto_retry: false,
+            max_retries: default_max_retries(),
+            max_wait_secs: default_max_wait_secs(),
+        }
+    }
+}
+
+/// lets users coming from curl reuse the credentials and settings they
+/// already have instead of entering them again
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CurlConfig {
+    /// requests without an `Authorization` header use the login and password
+    /// of their host on `~/.netrc`, or the file on `$NETRC`
+    #[serde(default)]
+    pub netrc: bool,
+    /// reads the proxy, client certificate and credentials from `.curlrc`,
+    /// on `$CURL_HOME` or the home directory
+    #[serde(default)]
+    pub curlrc: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LimitsConfig {
+    /// bodies larger than this many bytes only have their start shown,
+    /// until the whole of it is asked for
+    #[serde(default = "default_display_bytes")]
+    pub display_bytes: u64,
+    /// bodies of past responses larger than this many bytes are written to
+    /// the data directory, keeping only their start in memory
+    #[serde(default = "default_history_bytes")]
+    pub history_bytes: u64,
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        LimitsConfig {
+            display_bytes: default_display_bytes(),
+            history_bytes: default_history_bytes(),
+        }
+    }
+}
+
+fn default_display_bytes() -> u64 {
+    1 << 20
+}
+
+fn default_history_bytes() -> u64 {
+    8 << 20
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConnectionConfig {
+    /// which addresses hosts are resolved to, useful to test each side of
+    /// dual-stack services
+    #[serde(default)]
+    pub ip_version: IpVersion,
+    /// local address outgoing connections are bound to
+    #[serde(default)]
+    pub local_address: Option<IpAddr>,
+    /// network interface outgoing connections go through, like `eth0`
+    #[serde(default)]
+    pub interface: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IpVersion {
+    #[default]
+    Any,
+    V4,
+    V6,
+}
+
+/// rates are in bytes per second, and unset values leave that part of the
+/// link as it is
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct NetworkConfig {
+    #[serde(default)]
+    pub download_rate: Option<u64>,
+    #[serde(default)]
+    pub upload_rate: Option<u64>,
+    #[serde(default)]
+    pub latency_ms: Option<u64>,
+    #[serde(default)]
+    pub jitter_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Palette {
+    #[default]
+    Default,
+    /// success and failure are shown in blue and orange instead of green and
+    /// red, which are hard to tell apart with the most common color blindness
+    ColorBlind,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LineNumbers {
+    #[default]
+    Off,
+    Absolute,
+    /// how many lines each one is away from the cursor, like the
+    /// `relativenumber` of vim
+    Relative,
+    /// relative numbers, but the line of the cursor shows its own number
+    Hybrid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ThemeConfig {
+    #[serde(default)]
+    pub palette: Palette,
+    /// prefixes statuses and changes with a symbol, like `✓`, `✗` or `Δ`, so
+    /// they are not conveyed by color alone. Always on for the color blind
+    /// palette
+    #[serde(default)]
+    pub symbols: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "pt-BR")]
+    BrazilianPortuguese,
+}
+
+/// settings to make the interface usable with screen readers and braille
+/// terminals
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AccessibilityConfig {
+    /// draws the interface without box drawing characters, lays every pane
+    /// on a single column and announces what is going on with a status line
+    /// at the bottom instead of popups
+    #[serde(default)]
+    pub plain: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -77,14 +400,6 @@ impl std::fmt::Display for EditorMode {
     }
 }
 
-fn load_config_from_file<P>(path: P) -> anyhow::Result<Config>
-where
-    P: AsRef<Path>,
-{
-    let config_file = std::fs::read_to_string(path.as_ref())?;
-    Ok(toml::from_str::<Config>(&config_file)?)
-}
-
 /// try to get the configuration path from `XDG_CONFIG_HOME` on unix or `LOCALAPPDATA` on windows
 /// if that fails, fallback to the default path specified on the specification, or `AppData\\Local`
 /// on windows
@@ -124,22 +439,38 @@ pub fn get_config_dir_path() -> Option<PathBuf> {
     None
 }
 
-fn load_default_config() -> Config {
-    toml::from_str::<Config>(DEFAULT_CONFIG).expect("failed to parse default config string")
-}
-
 pub fn default_as_str() -> &'static str {
     DEFAULT_CONFIG
 }
 
+/// loads the configuration of every layer, see `resolve_config`. Problems
+/// are only logged, layers that fail to load are skipped, falling back to
+/// the ones below them
 pub fn load_config() -> Config {
-    let config = get_config_dir_path().and_then(|path| load_config_from_file(path).ok());
+    let resolved = crate::layers::resolve_config();
+    for issue in resolved.issues.iter() {
+        tracing::error!("invalid config: {issue}");
+    }
+    resolved.config
+}
 
-    if let Some(config) = config {
-        config
-    } else {
-        load_default_config()
+/// loads the configuration of every layer, failing with every problem found
+/// on them instead of falling back to the defaults
+pub fn load_config_strict() -> anyhow::Result<Config> {
+    let resolved = crate::layers::resolve_config();
+    if !resolved.issues.is_empty() {
+        let issues = resolved
+            .issues
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        anyhow::bail!(
+            "found {} problems on the config\n{}",
+            issues.len(),
+            issues.join("\n")
+        );
     }
+    Ok(resolved.config)
 }
 
 pub fn get_usual_path() -> PathBuf {
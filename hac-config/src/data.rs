@@ -1,35 +1,87 @@
-use crate::{APP_NAME, COLLECTIONS_DIR, XDG_DEFAULTS, XDG_ENV_VARS};
+use crate::{
+    APP_NAME, BODIES_DIR, CACHE_DIR_ENV_VAR, COLLECTIONS_DIR, DATA_DIR_ENV_VAR,
+    HEADER_HISTORY_FILE, LATENCY_HISTORY_FILE, STATE_DIR_ENV_VAR, UPDATE_CHECK_FILE,
+    VIEWER_PREFERENCES_FILE, XDG_DEFAULTS, XDG_ENV_VARS,
+};
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-pub fn get_data_dir() -> PathBuf {
-    let data_dir = std::env::var(XDG_ENV_VARS[1])
-        .map(PathBuf::from)
-        .unwrap_or_else(|_| PathBuf::from(XDG_DEFAULTS[1]));
-
-    dirs::home_dir()
-        .expect("failed to get the home directory")
-        .join(data_dir)
-        .join(APP_NAME)
+/// where the files hac keeps, besides its configuration, are stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataDir {
+    /// files the user creates and would back up, like collections
+    Data,
+    /// files that are fine to lose, like the large bodies of past responses
+    Cache,
+    /// files kept between sessions that aren't worth backing up, like
+    /// histories, preferences and logs
+    State,
 }
 
-pub fn get_or_create_data_dir() -> PathBuf {
-    let data_dir = get_data_dir();
+impl DataDir {
+    /// the directory of this kind, the environment variable for it takes
+    /// precedence over the directory the platform uses
+    pub fn path(self) -> PathBuf {
+        let env_var = match self {
+            DataDir::Data => DATA_DIR_ENV_VAR,
+            DataDir::Cache => CACHE_DIR_ENV_VAR,
+            DataDir::State => STATE_DIR_ENV_VAR,
+        };
+        match std::env::var(env_var) {
+            Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => self.platform_path(),
+        }
+    }
 
-    if !data_dir.exists() && !data_dir.is_dir() {
-        match std::fs::create_dir(&data_dir) {
-            // if we create the data dir, theres nothing to do
-            Ok(_) => {}
-            // if we fail to do so, panicking is adequate as we won't be able to properly run the
-            // application
-            Err(_) => {
-                tracing::error!("failed to create data_dir at: {data_dir:?}");
-                panic!("failed to create data_dir at: {data_dir:?}");
+    /// `XDG_DATA_HOME`, `XDG_CACHE_HOME` and `XDG_STATE_HOME` on linux, the
+    /// `Library` folders on macos and `LOCALAPPDATA` on windows
+    fn platform_path(self) -> PathBuf {
+        let data = dirs::data_local_dir()
+            .expect("failed to get the home directory")
+            .join(APP_NAME);
+        // windows keeps caches on the same directory as data, and only linux
+        // has a directory for state, so they get a folder inside data instead
+        match self {
+            DataDir::Data => data,
+            DataDir::Cache => dirs::cache_dir()
+                .map(|dir| dir.join(APP_NAME))
+                .filter(|dir| dir.ne(&data))
+                .unwrap_or_else(|| data.join("cache")),
+            DataDir::State => dirs::state_dir()
+                .map(|dir| dir.join(APP_NAME))
+                .unwrap_or_else(|| data.join("state")),
+        }
+    }
+
+    pub fn get_or_create(self) -> PathBuf {
+        let dir = self.path();
+
+        if !dir.is_dir() {
+            // if we fail to create it, panicking is adequate as we won't be
+            // able to properly run the application
+            if let Err(e) = std::fs::create_dir_all(&dir) {
+                tracing::error!("failed to create {dir:?}: {e}");
+                panic!("failed to create {dir:?}: {e}");
             }
         }
+
+        dir
     }
+}
+
+pub fn get_data_dir() -> PathBuf {
+    DataDir::Data.path()
+}
+
+pub fn get_or_create_data_dir() -> PathBuf {
+    DataDir::Data.get_or_create()
+}
 
-    data_dir
+/// creates the data, cache and state directories
+pub fn get_or_create_data_dirs() {
+    for dir in [DataDir::Data, DataDir::Cache, DataDir::State] {
+        dir.get_or_create();
+    }
 }
 
 pub fn get_collections_dir() -> PathBuf {
@@ -41,7 +93,7 @@ pub fn get_or_create_collections_dir() -> PathBuf {
     let collections_dir = get_collections_dir();
 
     if !collections_dir.exists() && !collections_dir.is_dir() {
-        match std::fs::create_dir(&collections_dir) {
+        match std::fs::create_dir_all(&collections_dir) {
             // if we create the collections dir, theres nothing to do
             Ok(_) => {}
             // if we fail to do so, panicking is adequate as we won't be able to properly run the
@@ -56,6 +108,159 @@ pub fn get_or_create_collections_dir() -> PathBuf {
     collections_dir
 }
 
+/// where bodies too large to keep in memory are written
+pub fn bodies_dir() -> PathBuf {
+    DataDir::Cache.path().join(BODIES_DIR)
+}
+
+/// file where the duration of past requests is stored
+pub fn latency_history_file() -> PathBuf {
+    DataDir::State.path().join(LATENCY_HISTORY_FILE)
+}
+
+/// file where the values sent on headers are stored
+pub fn header_history_file() -> PathBuf {
+    DataDir::State.path().join(HEADER_HISTORY_FILE)
+}
+
+/// file where the last time we looked for a newer release is stored
+pub fn update_check_file() -> PathBuf {
+    DataDir::State.path().join(UPDATE_CHECK_FILE)
+}
+
+/// file where how each request was last looked at is stored
+pub fn viewer_preferences_file() -> PathBuf {
+    DataDir::State.path().join(VIEWER_PREFERENCES_FILE)
+}
+
 pub fn log_file() -> (PathBuf, String) {
-    (get_data_dir(), format!("{}.log", APP_NAME))
+    (DataDir::State.path(), format!("{}.log", APP_NAME))
+}
+
+/// where every file used to be stored, before they were split by kind
+fn legacy_data_dir() -> PathBuf {
+    let data_dir = std::env::var(XDG_ENV_VARS[1])
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(XDG_DEFAULTS[1]));
+
+    dirs::home_dir()
+        .expect("failed to get the home directory")
+        .join(data_dir)
+        .join(APP_NAME)
+}
+
+/// moves the files of the old layout, where everything lived on the data
+/// directory, to the directories they belong to. Returns where each moved
+/// file was and where it is now
+pub fn migrate_legacy_layout() -> anyhow::Result<Vec<(PathBuf, PathBuf)>> {
+    migrate_layout(&legacy_data_dir(), |dir| dir.path())
+}
+
+fn migrate_layout(
+    legacy: &Path,
+    target: impl Fn(DataDir) -> PathBuf,
+) -> anyhow::Result<Vec<(PathBuf, PathBuf)>> {
+    let log_file = format!("{}.log", APP_NAME);
+    let entries = [
+        (COLLECTIONS_DIR, DataDir::Data),
+        (BODIES_DIR, DataDir::Cache),
+        (LATENCY_HISTORY_FILE, DataDir::State),
+        (HEADER_HISTORY_FILE, DataDir::State),
+        (VIEWER_PREFERENCES_FILE, DataDir::State),
+        (log_file.as_str(), DataDir::State),
+    ];
+
+    let mut moved = vec![];
+    for (name, dir) in entries {
+        let from = legacy.join(name);
+        let to = target(dir).join(name);
+        // files already on the new layout are never overwritten
+        if from.eq(&to) || !from.exists() || to.exists() {
+            continue;
+        }
+        if let Some(parent) = to.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        move_path(&from, &to)?;
+        moved.push((from, to));
+    }
+
+    Ok(moved)
+}
+
+/// renames the file or directory, copying it when the destination is on
+/// another filesystem
+fn move_path(from: &Path, to: &Path) -> anyhow::Result<()> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    copy_path(from, to)?;
+    match from.is_dir() {
+        true => std::fs::remove_dir_all(from)?,
+        false => std::fs::remove_file(from)?,
+    }
+    Ok(())
+}
+
+fn copy_path(from: &Path, to: &Path) -> anyhow::Result<()> {
+    if !from.is_dir() {
+        std::fs::copy(from, to)?;
+        return Ok(());
+    }
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        copy_path(&entry.path(), &to.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_layout() {
+        let root = std::env::temp_dir().join(format!("hac-migrate-{}", std::process::id()));
+        let legacy = root.join("share");
+        std::fs::create_dir_all(legacy.join(COLLECTIONS_DIR)).unwrap();
+        std::fs::write(legacy.join(COLLECTIONS_DIR).join("api.json"), "{}").unwrap();
+        std::fs::write(legacy.join(LATENCY_HISTORY_FILE), "{}").unwrap();
+        std::fs::create_dir_all(root.join("state")).unwrap();
+        std::fs::write(root.join("state").join(HEADER_HISTORY_FILE), "new").unwrap();
+        std::fs::write(legacy.join(HEADER_HISTORY_FILE), "old").unwrap();
+
+        let target = |dir: DataDir| match dir {
+            DataDir::Data => root.join("data"),
+            DataDir::Cache => root.join("cache"),
+            DataDir::State => root.join("state"),
+        };
+        let moved = migrate_layout(&legacy, target).unwrap();
+
+        assert_eq!(
+            moved,
+            vec![
+                (
+                    legacy.join(COLLECTIONS_DIR),
+                    root.join("data").join(COLLECTIONS_DIR)
+                ),
+                (
+                    legacy.join(LATENCY_HISTORY_FILE),
+                    root.join("state").join(LATENCY_HISTORY_FILE)
+                ),
+            ]
+        );
+        assert!(root
+            .join("data")
+            .join(COLLECTIONS_DIR)
+            .join("api.json")
+            .is_file());
+        assert!(!legacy.join(COLLECTIONS_DIR).exists());
+        let header_history =
+            std::fs::read_to_string(root.join("state").join(HEADER_HISTORY_FILE)).unwrap();
+        assert_eq!(header_history, "new");
+        assert!(migrate_layout(&legacy, target).unwrap().is_empty());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
 }
@@ -1,8 +1,79 @@
 pub static DEFAULT_CONFIG: &str = r##"
+strip_body_comments = true
+# draws tabs, trailing spaces and line breaks on the body editor
+show_whitespace = false
+# either "off", "absolute", "relative" or "hybrid"
+line_numbers = "off"
+# lines kept visible above and below the cursor
+scrolloff = 0
+# either "en" or "pt-BR"
+locale = "en"
+hyperlinks = false
+diff_tool = "vimdiff"
+# uploads of at least this many bytes wait for `100 Continue` before sending
+# their body
+# expect_continue_bytes = 1048576
+# serves the examples of requests instead of sending them
+offline = false
+# environment = "staging"
+# tells when a newer release exists, checking at most once a day
+check_updates = false
+
+[rate_limit]
+auto_retry = false
+max_retries = 3
+max_wait_secs = 60
+
+[curl]
+netrc = false
+curlrc = false
+
+[limits]
+# bodies larger than this are shown truncated
+display_bytes = 1048576
+# bodies of past responses larger than this are kept on disk
+history_bytes = 8388608
+
+[connection]
+# either "any", "v4" or "v6"
+ip_version = "any"
+# local_address = "192.168.0.10"
+# interface = "eth0"
+
+# simulates a slow link, rates are in bytes per second
+[network]
+# download_rate = 65536
+# upload_rate = 16384
+# latency_ms = 200
+# jitter_ms = 50
+
+# hosts whose certificate chain must carry one of these public keys
+# [pins]
+# "api.example.com" = ["sha256/47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU="]
+
+[theme]
+# either "default" or "color_blind"
+palette = "default"
+symbols = false
+
+[accessibility]
+plain = false
+
 [editor_keys.normal]
 "u" = "Undo"
 "n" = "FindNext"
 "S-N" = "FindPrevious"
+"/" = "SearchForward"
+"?" = "SearchBackward"
+":" = "CommandLine"
+"." = "RepeatEdit"
+"q" = "RecordMacro"
+"@" = "PlayMacro"
+"m" = "SetMark"
+"`" = "JumpToMark"
+"C-o" = "JumpBack"
+"C-i" = "JumpForward"
+"Tab" = "JumpForward"
 "w" = "NextWord"
 "b" = "PreviousWord"
 "h" = "MoveLeft"
@@ -36,6 +107,9 @@ pub static DEFAULT_CONFIG: &str = r##"
 "%" = "JumpToClosing"
 "{" = "JumpToEmptyLineAbove"
 "}" = "JumpToEmptyLineBelow"
+"]" = { "v" = "NextVariable" }
+"[" = { "v" = "PreviousVariable" }
+"z" = { "z" = "ScrollCursorCenter", "t" = "ScrollCursorTop", "b" = "ScrollCursorBottom" }
 
 [editor_keys.normal.d]
 "w" = "DeleteWord"
@@ -46,6 +120,18 @@ pub static DEFAULT_CONFIG: &str = r##"
 "l" = "DeleteCurrentChar"
 "h" = "DeletePreviousChar"
 
+[editor_keys.normal.t]
+"b" = "Base64Encode"
+"S-B" = "Base64Decode"
+"u" = "UrlEncode"
+"S-U" = "UrlDecode"
+"j" = "JsonEscape"
+"S-J" = "JsonUnescape"
+"t" = "ConvertTimestamp"
+"s" = "GenerateFromSchema"
+"x" = "ToggleHexMode"
+"e" = "EditString"
+
 [editor_keys.insert]
 "Tab" = "InsertTab"
 "Enter" = "InsertLine"
@@ -53,4 +139,14 @@ pub static DEFAULT_CONFIG: &str = r##"
 "Esc" = { EnterMode = "Normal" }
 "C-c" = { EnterMode = "Normal" }
 "C-w" = "DeleteBack"
+
+[abbreviations]
+"appjson" = "application/json"
+"appxml" = "application/xml"
+"appform" = "application/x-www-form-urlencoded"
+"textplain" = "text/plain"
+"ctype" = "Content-Type"
+
+[snippets]
+"err" = "{\n  \"error\": {\n    \"code\": ${1:400},\n    \"message\": \"${2:message}\"\n  }\n}$0"
 "##;
@@ -0,0 +1,333 @@
+use crate::config::{get_config_dir_path, Config};
+use crate::default_config::DEFAULT_CONFIG;
+use crate::validation::{self, ConfigIssue};
+use crate::{CONFIG_FILE, SYSTEM_CONFIG_DIR, WORKSPACE_DIR};
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// where a configuration value comes from, later layers take precedence over
+/// earlier ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConfigLayer {
+    Default,
+    /// shared by every user of the machine, like `/etc/hac/hac.toml`
+    System,
+    /// the configuration file of the user, see `get_config_dir_path`
+    User,
+    /// `.hac/hac.toml` on the current directory or any of its parents, so a
+    /// project can override keybindings or themes for itself
+    Workspace,
+}
+
+impl std::fmt::Display for ConfigLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigLayer::Default => f.write_str("default"),
+            ConfigLayer::System => f.write_str("system"),
+            ConfigLayer::User => f.write_str("user"),
+            ConfigLayer::Workspace => f.write_str("workspace"),
+        }
+    }
+}
+
+/// the configuration every layer adds up to, along with where each of its
+/// values came from
+#[derive(Debug, Clone)]
+pub struct ResolvedConfig {
+    pub config: Config,
+    /// the files that were read, lowest precedence first
+    pub files: Vec<(ConfigLayer, PathBuf)>,
+    /// problems found on the files, layers that couldn't be read or parsed
+    /// are skipped
+    pub issues: Vec<ConfigIssue>,
+    /// which layer set each value, keyed by its dotted path like
+    /// `rate_limit.auto_retry`
+    origins: BTreeMap<String, ConfigLayer>,
+    merged: toml::Table,
+}
+
+impl ResolvedConfig {
+    /// which layer the value on the dotted path came from
+    pub fn origin(&self, key: &str) -> Option<ConfigLayer> {
+        self.origins.get(key).copied()
+    }
+
+    /// the merged configuration as toml, with the files it came from and
+    /// every value that doesn't hold its default listed as comments
+    pub fn render(&self) -> String {
+        let mut out = String::from("# default < system < user < workspace\n");
+        for (layer, path) in self.files.iter() {
+            out.push_str(&format!("# {layer}: {}\n", path.to_string_lossy()));
+        }
+        for issue in self.issues.iter() {
+            out.push_str(&format!("# invalid: {issue}\n"));
+        }
+        out.push('\n');
+        out.push_str(&toml::to_string_pretty(&self.merged).unwrap_or_default());
+
+        let overridden = self
+            .origins
+            .iter()
+            .filter(|(_, layer)| layer.ne(&&ConfigLayer::Default))
+            .collect::<Vec<_>>();
+        if !overridden.is_empty() {
+            out.push_str("\n# overridden\n");
+            for (key, layer) in overridden {
+                out.push_str(&format!("# {key} ({layer})\n"));
+            }
+        }
+        out
+    }
+}
+
+/// the configuration file shared by every user of the machine
+pub fn system_config_path() -> PathBuf {
+    PathBuf::from(SYSTEM_CONFIG_DIR).join(CONFIG_FILE)
+}
+
+/// the closest `.hac` directory, starting at `from` and going up through its
+/// parents
+pub fn find_workspace_dir(from: &Path) -> Option<PathBuf> {
+    from.ancestors()
+        .map(|dir| dir.join(WORKSPACE_DIR))
+        .find(|dir| dir.is_dir())
+}
+
+/// the files of every layer above the defaults, whether they exist or not
+pub fn layer_paths() -> Vec<(ConfigLayer, PathBuf)> {
+    let mut paths = vec![(ConfigLayer::System, system_config_path())];
+    if let Some(path) = get_config_dir_path() {
+        paths.push((ConfigLayer::User, path));
+    }
+    let workspace = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| find_workspace_dir(&cwd));
+    if let Some(dir) = workspace {
+        paths.push((ConfigLayer::Workspace, dir.join(CONFIG_FILE)));
+    }
+    paths
+}
+
+/// notices when the configuration file of any layer is created, changed or
+/// removed, so it can be applied without restarting
+#[derive(Debug, Default)]
+pub struct ConfigWatcher {
+    modified: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl ConfigWatcher {
+    pub fn new() -> Self {
+        let mut watcher = ConfigWatcher::default();
+        watcher.changed();
+        watcher
+    }
+
+    /// whether any file changed since the last time this was asked. Layers
+    /// are looked up again, so a workspace created meanwhile is noticed
+    pub fn changed(&mut self) -> bool {
+        let modified = layer_paths()
+            .into_iter()
+            .map(|(_, path)| {
+                let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+                (path, modified)
+            })
+            .collect::<Vec<_>>();
+        let changed = modified.ne(&self.modified);
+        self.modified = modified;
+        changed
+    }
+}
+
+/// the configuration of every layer, from the system one up to the one of
+/// the current workspace
+pub fn resolve_config() -> ResolvedConfig {
+    resolve(&layer_paths())
+}
+
+/// merges the files over the default configuration, files that don't exist
+/// are ignored. Every file is validated against the layers below it, and the
+/// ones that fail to parse are skipped, see `issues`
+pub fn resolve(paths: &[(ConfigLayer, PathBuf)]) -> ResolvedConfig {
+    let mut merged =
+        toml::from_str::<toml::Table>(DEFAULT_CONFIG).expect("failed to parse default config");
+    let mut origins = BTreeMap::new();
+    record_origins(&merged, "", ConfigLayer::Default, &mut origins);
+
+    let mut files = vec![];
+    let mut issues = vec![];
+    for (layer, path) in paths {
+        if !path.is_file() {
+            continue;
+        }
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                issues.push(ConfigIssue {
+                    location: None,
+                    message: format!("failed to read {}: {e}", path.to_string_lossy()),
+                });
+                continue;
+            }
+        };
+        issues.extend(validation::validate_layer(path, &contents, &merged));
+        if let Ok(table) = toml::from_str::<toml::Table>(&contents) {
+            merge(&mut merged, table, "", *layer, &mut origins);
+            files.push((*layer, path.clone()));
+        }
+    }
+
+    // a value of the wrong type on any layer makes the whole configuration
+    // invalid, so we fall back to the defaults the same way a broken single
+    // file always did
+    let config = match toml::Value::Table(merged.clone()).try_into::<Config>() {
+        Ok(config) => config,
+        Err(e) => {
+            // validation already points at the value that broke it
+            if issues.is_empty() {
+                issues.push(ConfigIssue {
+                    location: None,
+                    message: e.to_string(),
+                });
+            }
+            toml::from_str::<Config>(DEFAULT_CONFIG).expect("failed to parse default config")
+        }
+    };
+
+    ResolvedConfig {
+        config,
+        files,
+        issues,
+        origins,
+        merged,
+    }
+}
+
+fn join_key(prefix: &str, key: &str) -> String {
+    match prefix.is_empty() {
+        true => key.to_string(),
+        false => format!("{prefix}.{key}"),
+    }
+}
+
+fn record_origins(
+    table: &toml::Table,
+    prefix: &str,
+    layer: ConfigLayer,
+    origins: &mut BTreeMap<String, ConfigLayer>,
+) {
+    for (key, value) in table {
+        let path = join_key(prefix, key);
+        match value {
+            toml::Value::Table(inner) => record_origins(inner, &path, layer, origins),
+            _ => _ = origins.insert(path, layer),
+        }
+    }
+}
+
+/// tables are merged key by key, so a layer can change a single keybinding,
+/// any other value replaces the one below it
+fn merge(
+    base: &mut toml::Table,
+    layer_table: toml::Table,
+    prefix: &str,
+    layer: ConfigLayer,
+    origins: &mut BTreeMap<String, ConfigLayer>,
+) {
+    for (key, value) in layer_table {
+        let path = join_key(prefix, &key);
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(inner)) => {
+                merge(existing, inner, &path, layer, origins);
+            }
+            (_, value) => {
+                let nested = format!("{path}.");
+                origins.retain(|key, _| key.ne(&path) && !key.starts_with(&nested));
+                match &value {
+                    toml::Value::Table(inner) => record_origins(inner, &path, layer, origins),
+                    _ => _ = origins.insert(path, layer),
+                }
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_layer(dir: &Path, name: &str, contents: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_resolve_layers() {
+        let dir = std::env::temp_dir().join(format!("hac-layers-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let system = write_layer(&dir, "system.toml", "diff_tool = \"meld\"\n");
+        let user = write_layer(
+            &dir,
+            "user.toml",
+            "offline = true\n[rate_limit]\nmax_retries = 5\n",
+        );
+        let workspace = write_layer(
+            &dir,
+            "workspace.toml",
+            "diff_tool = \"code --diff --wait\"\n[rate_limit]\nauto_retry = true\n",
+        );
+
+        let resolved = resolve(&[
+            (ConfigLayer::System, system),
+            (ConfigLayer::User, user),
+            (ConfigLayer::Workspace, workspace),
+            (ConfigLayer::Workspace, dir.join("missing.toml")),
+        ]);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(resolved.issues.is_empty());
+        assert_eq!(resolved.files.len(), 3);
+        assert_eq!(resolved.config.diff_tool, "code --diff --wait");
+        assert!(resolved.config.offline);
+        assert!(resolved.config.rate_limit.auto_retry);
+        assert_eq!(resolved.config.rate_limit.max_retries, 5);
+        assert_eq!(resolved.config.rate_limit.max_wait_secs, 60);
+        assert!(!resolved.config.editor_keys.normal.is_empty());
+
+        assert_eq!(resolved.origin("diff_tool"), Some(ConfigLayer::Workspace));
+        assert_eq!(
+            resolved.origin("rate_limit.max_retries"),
+            Some(ConfigLayer::User)
+        );
+        assert_eq!(
+            resolved.origin("rate_limit.max_wait_secs"),
+            Some(ConfigLayer::Default)
+        );
+        assert!(resolved
+            .render()
+            .contains("# rate_limit.auto_retry (workspace)\n"));
+    }
+
+    #[test]
+    fn test_invalid_layer_falls_back() {
+        let dir = std::env::temp_dir().join(format!("hac-bad-layers-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let broken = write_layer(&dir, "broken.toml", "offline = \n");
+        let mistyped = write_layer(&dir, "mistyped.toml", "offline = \"yes\"\n");
+
+        let resolved = resolve(&[(ConfigLayer::User, broken)]);
+        assert_eq!(resolved.issues.len(), 1);
+        assert!(resolved.files.is_empty());
+
+        let resolved = resolve(&[(ConfigLayer::Workspace, mistyped)]);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(resolved.issues.len(), 1);
+        assert!(resolved.issues[0]
+            .to_string()
+            .ends_with("mistyped.toml:1:11: `offline` should be a boolean, found \"yes\""));
+        assert!(!resolved.config.offline);
+    }
+}
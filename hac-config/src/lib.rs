@@ -1,14 +1,22 @@
 pub mod config;
 pub mod data;
 mod default_config;
+pub mod layers;
+pub mod validation;
 
 pub use config::{
-    default_as_str, get_config_dir_path, get_usual_path, load_config, Action, Config, KeyAction,
+    default_as_str, get_config_dir_path, get_usual_path, load_config, load_config_strict,
+    AccessibilityConfig, Action, Config, ConnectionConfig, CurlConfig, IpVersion, KeyAction,
+    LimitsConfig, LineNumbers, Locale, NetworkConfig, Palette, RateLimitConfig, ThemeConfig,
 };
 pub use data::{
-    get_collections_dir, get_or_create_collections_dir, get_or_create_data_dir, log_file,
+    bodies_dir, get_collections_dir, get_or_create_collections_dir, get_or_create_data_dir,
+    get_or_create_data_dirs, header_history_file, latency_history_file, log_file,
+    migrate_legacy_layout, update_check_file, viewer_preferences_file, DataDir,
 };
+pub use layers::{find_workspace_dir, resolve_config, ConfigLayer, ConfigWatcher, ResolvedConfig};
 use serde::{Deserialize, Serialize};
+pub use validation::ConfigIssue;
 
 #[derive(PartialEq, Deserialize, Serialize, Debug, Clone)]
 pub enum EditorMode {
@@ -19,8 +27,30 @@ pub enum EditorMode {
 pub static APP_NAME: &str = "hac";
 pub static COLLECTIONS_DIR: &str = "collections";
 pub static CONFIG_FILE: &str = "hac.toml";
+pub static LATENCY_HISTORY_FILE: &str = "latency_history.json";
+pub static HEADER_HISTORY_FILE: &str = "header_history.json";
+pub static VIEWER_PREFERENCES_FILE: &str = "viewer_preferences.json";
+pub static UPDATE_CHECK_FILE: &str = "update_check.json";
 pub static THEMES_DIR: &str = "themes";
+pub static BODIES_DIR: &str = "bodies";
+/// template new requests start with, on the workspace directory
+pub static TEMPLATE_FILE: &str = "template.json";
+/// name the files of variables kept out of version control start with, on
+/// the workspace directory
+pub static LOCAL_VARIABLES: &str = "local";
 pub static CONFIG_ENV_VAR: &str = "HAC_CONFIG";
+pub static DATA_DIR_ENV_VAR: &str = "HAC_DATA_DIR";
+pub static CACHE_DIR_ENV_VAR: &str = "HAC_CACHE_DIR";
+pub static STATE_DIR_ENV_VAR: &str = "HAC_STATE_DIR";
+/// directory holding the configuration of a project, looked up from the
+/// current directory
+pub static WORKSPACE_DIR: &str = ".hac";
+
+#[cfg(unix)]
+static SYSTEM_CONFIG_DIR: &str = "/etc/hac";
+
+#[cfg(windows)]
+static SYSTEM_CONFIG_DIR: &str = "C:\\ProgramData\\hac";
 
 #[cfg(unix)]
 static XDG_ENV_VARS: [&str; 2] = ["XDG_CONFIG_HOME", "XDG_DATA_HOME"];
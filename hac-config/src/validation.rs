@@ -0,0 +1,500 @@
+use crate::config::Action;
+
+use std::path::{Path, PathBuf};
+
+use toml::de::{DeTable, DeValue};
+use toml::Spanned;
+
+/// what a value of the configuration is expected to be
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Boolean,
+    Integer,
+    String,
+    /// one of the given strings
+    OneOf(&'static [&'static str]),
+    /// a table whose keys are chosen by the user
+    Map(&'static Kind),
+    Array(&'static Kind),
+    /// keys of the editor bound to actions, or to tables of the keys that
+    /// follow them
+    Bindings,
+}
+
+/// every value the configuration accepts, tables are implied by the paths
+/// nested in them
+const SCHEMA: &[(&str, Kind)] = &[
+    ("strip_body_comments", Kind::Boolean),
+    ("snippets", Kind::Map(&Kind::String)),
+    ("abbreviations", Kind::Map(&Kind::String)),
+    ("rate_limit.auto_retry", Kind::Boolean),
+    ("rate_limit.max_retries", Kind::Integer),
+    ("rate_limit.max_wait_secs", Kind::Integer),
+    ("environment", Kind::String),
+    ("theme.palette", Kind::OneOf(&["default", "color_blind"])),
+    ("theme.symbols", Kind::Boolean),
+    ("accessibility.plain", Kind::Boolean),
+    ("locale", Kind::OneOf(&["en", "pt-BR"])),
+    ("hyperlinks", Kind::Boolean),
+    ("show_whitespace", Kind::Boolean),
+    ("scrolloff", Kind::Integer),
+    (
+        "line_numbers",
+        Kind::OneOf(&["off", "absolute", "relative", "hybrid"]),
+    ),
+    ("diff_tool", Kind::String),
+    ("curl.netrc", Kind::Boolean),
+    ("curl.curlrc", Kind::Boolean),
+    ("expect_continue_bytes", Kind::Integer),
+    ("network.download_rate", Kind::Integer),
+    ("network.upload_rate", Kind::Integer),
+    ("network.latency_ms", Kind::Integer),
+    ("network.jitter_ms", Kind::Integer),
+    ("connection.ip_version", Kind::OneOf(&["any", "v4", "v6"])),
+    ("connection.local_address", Kind::String),
+    ("connection.interface", Kind::String),
+    ("offline", Kind::Boolean),
+    ("check_updates", Kind::Boolean),
+    ("pins", Kind::Map(&Kind::Array(&Kind::String))),
+    ("limits.display_bytes", Kind::Integer),
+    ("limits.history_bytes", Kind::Integer),
+    ("editor_keys.normal", Kind::Bindings),
+    ("editor_keys.insert", Kind::Bindings),
+];
+
+/// keys of the editor that aren't a character, see `keycode_as_string`
+const NAMED_KEYS: &[&str] = &[
+    "Backspace",
+    "Left",
+    "Down",
+    "Up",
+    "Right",
+    "Home",
+    "End",
+    "Enter",
+    "Tab",
+    "Esc",
+];
+
+/// keys handled before normal mode bindings are looked up
+const RESERVED_NORMAL_KEYS: &[&str] = &["Esc", "C-c"];
+
+/// a problem on a configuration file, pointing at where it is
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigIssue {
+    /// the file, and the line and column starting at 1. Problems of the
+    /// configuration as a whole have no location
+    pub location: Option<(PathBuf, usize, usize)>,
+    pub message: String,
+}
+
+impl std::fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.location.as_ref() {
+            Some((path, line, column)) => write!(
+                f,
+                "{}:{line}:{column}: {}",
+                path.to_string_lossy(),
+                self.message
+            ),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+struct Validator<'v> {
+    path: &'v Path,
+    contents: &'v str,
+    /// the configuration of the layers below the one being validated
+    below: &'v toml::Table,
+    issues: Vec<ConfigIssue>,
+}
+
+/// checks a configuration file for syntax errors, unknown keys, values of
+/// the wrong type and keybindings that conflict with each other, or with the
+/// layers below it
+pub fn validate_layer(path: &Path, contents: &str, below: &toml::Table) -> Vec<ConfigIssue> {
+    let mut validator = Validator {
+        path,
+        contents,
+        below,
+        issues: vec![],
+    };
+
+    let (document, values) = match (
+        DeTable::parse(contents),
+        toml::from_str::<toml::Table>(contents),
+    ) {
+        (Ok(document), Ok(values)) => (document, values),
+        (Err(e), _) => {
+            validator.push(e.span().unwrap_or_default(), e.message().trim().into());
+            return validator.issues;
+        }
+        (_, Err(e)) => {
+            validator.push(e.span().unwrap_or_default(), e.message().trim().into());
+            return validator.issues;
+        }
+    };
+
+    validator.table(document.get_ref(), &values, &mut vec![]);
+    validator.issues.sort_by_key(|issue| {
+        issue
+            .location
+            .as_ref()
+            .map(|(_, line, column)| (*line, *column))
+    });
+    validator.issues
+}
+
+impl Validator<'_> {
+    fn push(&mut self, span: std::ops::Range<usize>, message: String) {
+        let before = &self.contents[..span.start.min(self.contents.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before.len() - before.rfind('\n').map(|idx| idx + 1).unwrap_or(0) + 1;
+        self.issues.push(ConfigIssue {
+            location: Some((self.path.to_path_buf(), line, column)),
+            message,
+        });
+    }
+
+    fn table(&mut self, table: &DeTable, values: &toml::Table, segments: &mut Vec<String>) {
+        for (key, value) in table.iter() {
+            let name = key.get_ref().as_ref();
+            let Some(toml_value) = values.get(name) else {
+                continue;
+            };
+            segments.push(name.to_string());
+            let path = segments.join(".");
+
+            match SCHEMA.iter().find(|(known, _)| known.eq(&path)) {
+                Some((_, kind)) => self.value(*kind, &path, value, toml_value),
+                None if is_table(&path) => match (value.get_ref(), toml_value) {
+                    (DeValue::Table(inner), toml::Value::Table(inner_values)) => {
+                        self.table(inner, inner_values, segments)
+                    }
+                    _ => self.push(
+                        value.span(),
+                        format!("`{path}` should be a table, found {}", found(toml_value)),
+                    ),
+                },
+                None => {
+                    let parent = &segments[..segments.len() - 1];
+                    let message = match closest(name, &siblings(parent)) {
+                        Some(suggestion) => {
+                            format!("unknown key `{path}`, did you mean `{suggestion}`?")
+                        }
+                        None => format!("unknown key `{path}`"),
+                    };
+                    self.push(key.span(), message);
+                }
+            }
+
+            segments.pop();
+        }
+    }
+
+    fn value(
+        &mut self,
+        kind: Kind,
+        path: &str,
+        value: &Spanned<DeValue>,
+        toml_value: &toml::Value,
+    ) {
+        let expected = match (kind, toml_value) {
+            (Kind::Boolean, toml::Value::Boolean(_)) => return,
+            (Kind::Integer, toml::Value::Integer(n)) if n.is_negative() => {
+                let message = format!("`{path}` can't be negative");
+                return self.push(value.span(), message);
+            }
+            (Kind::Integer, toml::Value::Integer(_)) => return,
+            (Kind::String, toml::Value::String(_)) => return,
+            (Kind::OneOf(options), toml::Value::String(s)) if options.contains(&s.as_str()) => {
+                return
+            }
+            (Kind::Map(inner), toml::Value::Table(values)) => {
+                if let DeValue::Table(table) = value.get_ref() {
+                    for (key, entry) in table.iter() {
+                        let name = key.get_ref().as_ref();
+                        if let Some(entry_value) = values.get(name) {
+                            self.value(*inner, &format!("{path}.{name}"), entry, entry_value);
+                        }
+                    }
+                }
+                return;
+            }
+            (Kind::Array(inner), toml::Value::Array(values)) => {
+                if let DeValue::Array(array) = value.get_ref() {
+                    for (idx, (item, item_value)) in array.iter().zip(values).enumerate() {
+                        self.value(*inner, &format!("{path}[{idx}]"), item, item_value);
+                    }
+                }
+                return;
+            }
+            (Kind::Bindings, toml::Value::Table(values)) => {
+                if let DeValue::Table(table) = value.get_ref() {
+                    let mode = path.rsplit('.').next().unwrap_or_default().to_string();
+                    self.bindings(&mode, &mut vec![], table, values);
+                }
+                return;
+            }
+            (Kind::Boolean, _) => "a boolean".to_string(),
+            (Kind::Integer, _) => "an integer".to_string(),
+            (Kind::String, _) => "a string".to_string(),
+            (Kind::OneOf(options), _) => format!(
+                "one of {}",
+                options
+                    .iter()
+                    .map(|option| format!("\"{option}\""))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            (Kind::Map(_) | Kind::Bindings, _) => "a table".to_string(),
+            (Kind::Array(_), _) => "a list".to_string(),
+        };
+
+        let message = format!("`{path}` should be {expected}, found {}", found(toml_value));
+        self.push(value.span(), message);
+    }
+
+    /// checks the keys of a mode of the editor, `sequence` holds the keys
+    /// pressed before the ones of `table`
+    fn bindings(
+        &mut self,
+        mode: &str,
+        sequence: &mut Vec<String>,
+        table: &DeTable,
+        values: &toml::Table,
+    ) {
+        for (key, value) in table.iter() {
+            let name = key.get_ref().as_ref();
+            let Some(toml_value) = values.get(name) else {
+                continue;
+            };
+            sequence.push(name.to_string());
+            let keys = sequence.join("");
+
+            if !is_key(name) {
+                let message = format!(
+                    "`{name}` on editor_keys.{mode} is not a key, use a character, \
+                     `S-<char>`, `C-<char>` or one of {}",
+                    NAMED_KEYS.join(", ")
+                );
+                self.push(key.span(), message);
+            } else if sequence.len() == 1 && mode.eq("insert") && name.chars().count() == 1 {
+                let message = format!(
+                    "binding `{name}` on editor_keys.insert stops `{name}` from being typed"
+                );
+                self.push(key.span(), message);
+            } else if sequence.len() == 1
+                && mode.eq("normal")
+                && RESERVED_NORMAL_KEYS.contains(&name)
+            {
+                let message = format!(
+                    "`{name}` on editor_keys.normal never runs, it always leaves the editor"
+                );
+                self.push(key.span(), message);
+            }
+
+            match (value.get_ref(), toml_value) {
+                (DeValue::Table(inner), toml::Value::Table(inner_values))
+                    if Action::deserialize_from(toml_value).is_err() =>
+                {
+                    if let Some(variant) = ["EnterMode", "InsertChar"]
+                        .into_iter()
+                        .find(|variant| inner_values.contains_key(*variant))
+                    {
+                        let message = format!(
+                            "`{keys}` on editor_keys.{mode} has an invalid {variant}: {}",
+                            Action::deserialize_from(toml_value).unwrap_err()
+                        );
+                        self.push(value.span(), message);
+                    } else {
+                        self.bindings(mode, sequence, inner, inner_values);
+                    }
+                }
+                (_, toml::Value::Array(actions)) => {
+                    if let Some(e) = actions
+                        .iter()
+                        .find_map(|action| Action::deserialize_from(action).err())
+                    {
+                        let message = format!("`{keys}` on editor_keys.{mode}: {e}");
+                        self.push(value.span(), message);
+                    }
+                }
+                (_, action) => match Action::deserialize_from(action) {
+                    Ok(_) => self.replaced_sequences(mode, sequence, value.span()),
+                    Err(e) => {
+                        let message = format!("`{keys}` on editor_keys.{mode}: {e}");
+                        self.push(value.span(), message);
+                    }
+                },
+            }
+
+            sequence.pop();
+        }
+    }
+
+    /// binding a key that the layers below use to start sequences replaces
+    /// all of those sequences, which is rarely what was meant
+    fn replaced_sequences(
+        &mut self,
+        mode: &str,
+        sequence: &[String],
+        span: std::ops::Range<usize>,
+    ) {
+        let mut below = self
+            .below
+            .get("editor_keys")
+            .and_then(|keys| keys.get(mode));
+        for key in sequence {
+            below = below.and_then(|value| value.get(key));
+        }
+        let Some(toml::Value::Table(replaced)) = below else {
+            return;
+        };
+        // tables like `{ EnterMode = "Insert" }` are actions, not sequences
+        if Action::deserialize_from(&toml::Value::Table(replaced.clone())).is_ok() {
+            return;
+        }
+
+        let prefix = sequence.join("");
+        let replaced = replaced
+            .keys()
+            .map(|key| format!("{prefix}{key}"))
+            .collect::<Vec<_>>();
+        let message = format!(
+            "`{prefix}` on editor_keys.{mode} replaces the sequences starting with it on the \
+             layers below: {}",
+            replaced.join(", ")
+        );
+        self.push(span, message);
+    }
+}
+
+impl Action {
+    fn deserialize_from(value: &toml::Value) -> Result<Action, String> {
+        value
+            .clone()
+            .try_into::<Action>()
+            .map_err(|e| e.message().trim().to_string())
+    }
+}
+
+/// whether any value of the schema lives inside the table on `path`
+fn is_table(path: &str) -> bool {
+    let nested = format!("{path}.");
+    SCHEMA.iter().any(|(known, _)| known.starts_with(&nested))
+}
+
+/// the keys the schema knows inside the table with the given path
+fn siblings(parent: &[String]) -> Vec<&'static str> {
+    let prefix = match parent.is_empty() {
+        true => String::default(),
+        false => format!("{}.", parent.join(".")),
+    };
+    let mut siblings = SCHEMA
+        .iter()
+        .filter_map(|(known, _)| known.strip_prefix(&prefix))
+        .map(|rest| rest.split('.').next().unwrap_or(rest))
+        .collect::<Vec<_>>();
+    siblings.dedup();
+    siblings
+}
+
+/// the candidate that is at most two edits away from `key`
+fn closest<'a>(key: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(key, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char.ne(b_char));
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+fn is_key(name: &str) -> bool {
+    let character = name
+        .strip_prefix("S-")
+        .or_else(|| name.strip_prefix("C-"))
+        .unwrap_or(name);
+    character.chars().count() == 1 || NAMED_KEYS.contains(&name)
+}
+
+fn found(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => format!("\"{s}\""),
+        toml::Value::Integer(n) => n.to_string(),
+        toml::Value::Float(n) => n.to_string(),
+        toml::Value::Boolean(b) => b.to_string(),
+        toml::Value::Datetime(d) => d.to_string(),
+        toml::Value::Array(_) => "a list".into(),
+        toml::Value::Table(_) => "a table".into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::default_config::DEFAULT_CONFIG;
+
+    fn issues(contents: &str) -> Vec<String> {
+        let below = toml::from_str::<toml::Table>(DEFAULT_CONFIG).unwrap();
+        validate_layer(Path::new("hac.toml"), contents, &below)
+            .into_iter()
+            .map(|issue| issue.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_default_config_is_valid() {
+        assert_eq!(issues(DEFAULT_CONFIG), Vec::<String>::new());
+        assert!(validate_layer(Path::new("x"), DEFAULT_CONFIG, &toml::Table::new()).is_empty());
+    }
+
+    #[test]
+    fn test_unknown_keys_and_types() {
+        let contents = "offline = \"yes\"\ndiff_tol = \"meld\"\n\n[rate_limit]\nmax_retries = -1\nretry = true\n\n[theme]\npalette = \"dark\"\n";
+        assert_eq!(
+            issues(contents),
+            vec![
+                "hac.toml:1:11: `offline` should be a boolean, found \"yes\"",
+                "hac.toml:2:1: unknown key `diff_tol`, did you mean `diff_tool`?",
+                "hac.toml:5:15: `rate_limit.max_retries` can't be negative",
+                "hac.toml:6:1: unknown key `rate_limit.retry`",
+                "hac.toml:9:11: `theme.palette` should be one of \"default\", \"color_blind\", found \"dark\"",
+            ]
+        );
+        assert_eq!(
+            issues("offline = \n"),
+            vec!["hac.toml:1:11: string values must be quoted, expected literal string"]
+        );
+    }
+
+    #[test]
+    fn test_conflicting_keybindings() {
+        let contents = "[editor_keys.normal]\nd = \"DeleteLine\"\nEsc = \"Undo\"\n\"ctrl-x\" = \"Undo\"\nq = \"Quit\"\n\n[editor_keys.insert]\nj = { k = { EnterMode = \"Normal\" } }\n";
+        assert_eq!(
+            issues(contents),
+            vec![
+                "hac.toml:2:5: `d` on editor_keys.normal replaces the sequences starting with it on the layers below: db, dd, dh, dj, dk, dl, dw",
+                "hac.toml:3:1: `Esc` on editor_keys.normal never runs, it always leaves the editor",
+                "hac.toml:4:1: `ctrl-x` on editor_keys.normal is not a key, use a character, `S-<char>`, `C-<char>` or one of Backspace, Left, Down, Up, Right, Home, End, Enter, Tab, Esc",
+                "hac.toml:5:5: `q` on editor_keys.normal: unknown variant `Quit`, expected one of `Undo`, `FindNext`, `FindPrevious`, `NextWord`, `PreviousWord`, `MoveLeft`, `MoveDown`, `MoveUp`, `MoveRight`, `MoveToBottom`, `MoveToTop`, `MoveToLineEnd`, `MoveToLineStart`, `PageDown`, `PageUp`, `DeleteWord`, `DeleteLine`, `DeleteBack`, `DeleteUntilEOL`, `DeleteCurrentChar`, `InsertLineBelow`, `InsertLineAbove`, `PasteBelow`, `InsertAhead`, `EnterMode`, `InsertAtEOL`, `MoveAfterWhitespaceReverse`, `MoveAfterWhitespace`, `DeletePreviousNonWrapping`, `DeleteCurrAndBelow`, `DeleteCurrAndAbove`, `InsertChar`, `InsertTab`, `InsertLine`, `DeletePreviousChar`, `JumpToClosing`, `JumpToEmptyLineBelow`, `JumpToEmptyLineAbove`, `NextVariable`, `PreviousVariable`, `Base64Encode`, `Base64Decode`, `UrlEncode`, `UrlDecode`, `JsonEscape`, `JsonUnescape`, `ConvertTimestamp`, `GenerateFromSchema`, `ToggleHexMode`, `EditString`, `SearchForward`, `SearchBackward`, `CommandLine`, `RepeatEdit`, `RecordMacro`, `PlayMacro`, `SetMark`, `JumpToMark`, `JumpBack`, `JumpForward`, `ScrollCursorCenter`, `ScrollCursorTop`, `ScrollCursorBottom`",
+                "hac.toml:8:1: binding `j` on editor_keys.insert stops `j` from being typed",
+            ]
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use std::path::Path;
+use std::process::Command;
+
+/// grammars whose versions `hac --version --verbose` reports
+const GRAMMARS: &[&str] = &["tree-sitter", "tree-sitter-json"];
+
+fn main() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    println!("cargo:rerun-if-changed=../.git/HEAD");
+    println!("cargo:rerun-if-changed=../.git/refs");
+    println!("cargo:rerun-if-changed=../Cargo.lock");
+
+    // builds outside of a git checkout, like the ones of crates.io, have no
+    // commit to report
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .current_dir(&root)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+    if let Some(commit) = commit {
+        println!("cargo:rustc-env=HAC_COMMIT_HASH={commit}");
+    }
+
+    let lockfile = std::fs::read_to_string(root.join("Cargo.lock")).unwrap_or_default();
+    let versions = GRAMMARS
+        .iter()
+        .filter_map(|grammar| {
+            let name = format!("name = \"{grammar}\"");
+            let mut lines = lockfile.lines().skip_while(|line| line.ne(&name));
+            let version = lines.nth(1)?.strip_prefix("version = ")?.trim_matches('"');
+            Some(format!("{grammar}={version}"))
+        })
+        .collect::<Vec<_>>();
+    println!(
+        "cargo:rustc-env=HAC_GRAMMAR_VERSIONS={}",
+        versions.join(",")
+    );
+}
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+/// chars that can be part of an abbreviation, any other char is considered a
+/// word boundary and triggers the expansion of the word typed before it
+pub fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-')
+}
+
+/// the word at the very end of `text`
+pub fn trailing_word(text: &str) -> &str {
+    let start = text
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_word_char(*c))
+        .last()
+        .map(|(idx, _)| idx)
+        .unwrap_or(text.len());
+
+    &text[start..]
+}
+
+/// if `text` ends with an abbreviation, returns `text` with the abbreviation
+/// replaced by its expansion
+pub fn expand_trailing(text: &str, abbreviations: &HashMap<String, String>) -> Option<String> {
+    let word = trailing_word(text);
+    let expansion = abbreviations.get(word)?;
+    let mut expanded = text[..text.len() - word.len()].to_string();
+    expanded.push_str(expansion);
+    Some(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn abbreviations() -> HashMap<String, String> {
+        HashMap::from([("appjson".to_string(), "application/json".to_string())])
+    }
+
+    #[test]
+    fn test_expand_trailing_word() {
+        let expanded = expand_trailing("text/plain, appjson", &abbreviations());
+        assert_eq!(expanded.as_deref(), Some("text/plain, application/json"));
+    }
+
+    #[test]
+    fn test_only_whole_words_are_expanded() {
+        assert!(expand_trailing("myappjson", &abbreviations()).is_none());
+        assert!(expand_trailing("appjson ", &abbreviations()).is_none());
+        assert_eq!(trailing_word("\"x-appjson"), "x-appjson");
+    }
+}
@@ -1,5 +1,23 @@
 #[allow(clippy::module_inception)]
 pub mod collection;
+pub mod conditions;
+pub mod dependencies;
+pub mod diagnostics;
+pub mod duplicates;
+pub mod environment;
+#[cfg(test)]
+pub(crate) mod fixtures;
+pub mod highlights;
+pub mod history;
+pub mod local;
+pub mod merge;
+pub mod overrides;
+pub mod rename;
+pub mod report;
+pub mod session;
+pub mod template;
 pub mod types;
+pub mod validation;
+pub mod variables;
 pub use types::Collection;
-mod errors;
+pub mod errors;
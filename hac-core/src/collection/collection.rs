@@ -1,6 +1,7 @@
 use crate::collection::types::{Collection, Info};
 
 use std::path::Path;
+use std::sync::{Arc, Mutex};
 use std::time::{self, UNIX_EPOCH};
 
 #[tracing::instrument(err)]
@@ -20,11 +21,13 @@ where
 
     for item in items.into_iter().flatten() {
         let file_name = item.file_name();
+        // hidden files, like the locks held while a collection is written,
+        // are never collections
+        if file_name.to_string_lossy().starts_with('.') {
+            continue;
+        }
         let collection_name = collections_dir.as_ref().join(file_name);
-        let file = std::fs::read_to_string(&collection_name)?;
-        let mut collection: Collection = serde_json::from_str(&file)?;
-        collection.path = collection_name;
-        collections.push(collection);
+        collections.push(load(collection_name)?);
     }
 
     collections.sort_by(|a, b| a.info.name.cmp(&b.info.name));
@@ -32,6 +35,20 @@ where
     Ok(collections)
 }
 
+/// reads the collection stored on `path`, remembering what the file held
+/// to notice when it is changed by someone else
+pub fn load<P>(path: P) -> anyhow::Result<Collection>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let file = std::fs::read_to_string(path)?;
+    let mut collection: Collection = serde_json::from_str(&file)?;
+    collection.path = path.to_path_buf();
+    collection.synced = Arc::new(Mutex::new(Some(file)));
+    Ok(collection)
+}
+
 pub fn create_from_form(name: String, description: String) -> Collection {
     let name = if name.is_empty() {
         let now = time::SystemTime::now()
@@ -51,9 +68,17 @@ pub fn create_from_form(name: String, description: String) -> Collection {
         info: Info {
             name,
             description: Some(description),
+            read_only: false,
         },
         requests: None,
         path: format!("{}.json", collection_name.to_string_lossy()).into(),
+        unlocked: false,
+        protos: vec![],
+        highlights: vec![],
+        template: None,
+        format_on_save: false,
+        synced: Default::default(),
+        environments: vec![],
     }
 }
 
@@ -68,4 +93,27 @@ mod tests {
         assert!(collection.info.name.eq("any valid name"));
         assert!(collection.info.description.is_some())
     }
+
+    #[test]
+    fn test_read_only_collection_requires_unlock() {
+        let json =
+            r#"{"info":{"name":"shared","description":null,"readOnly":true},"requests":null}"#;
+        let mut collection: Collection = serde_json::from_str(json).unwrap();
+
+        assert!(collection.is_read_only());
+        assert!(collection.ensure_writable().is_err());
+
+        collection.unlocked = true;
+        assert!(!collection.is_read_only());
+        assert!(collection.ensure_writable().is_ok());
+
+        // unlocking is never persisted, and writable collections don't store the flag
+        let serialized = serde_json::to_string(&collection).unwrap();
+        assert!(serialized.contains("\"readOnly\":true"));
+        assert!(!serialized.contains("unlocked"));
+        let writable = create_from_form("name".into(), "".into());
+        assert!(!serde_json::to_string(&writable)
+            .unwrap()
+            .contains("readOnly"));
+    }
 }
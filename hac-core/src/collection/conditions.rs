@@ -0,0 +1,111 @@
+use std::collections::BTreeMap;
+
+use crate::collection::types::Request;
+
+/// what is known about the current run when deciding whether a request
+/// should run or not
+#[derive(Debug, Default, Clone)]
+pub struct RunContext {
+    /// the environment requests are being sent against, if any
+    pub environment: Option<String>,
+    /// variables set for the current run
+    pub variables: BTreeMap<String, String>,
+}
+
+impl RunContext {
+    /// the variables are the ones requests are resolved with, so variables
+    /// that are set are never seen as unset
+    pub fn new(environment: Option<String>, variables: BTreeMap<String, String>) -> Self {
+        RunContext {
+            environment,
+            variables,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RunDecision {
+    Run,
+    /// the request should not run, holding a human readable reason
+    Skip(String),
+}
+
+/// decides whether a request should run given the current context, requests
+/// without conditions always run
+pub fn evaluate(request: &Request, context: &RunContext) -> RunDecision {
+    let Some(conditions) = request.conditions.as_ref() else {
+        return RunDecision::Run;
+    };
+
+    if !conditions.only_in.is_empty() {
+        let environment = context.environment.as_deref().unwrap_or_default();
+        if !conditions.only_in.iter().any(|env| env.eq(environment)) {
+            return RunDecision::Skip(format!("only runs on {}", conditions.only_in.join(", ")));
+        }
+    }
+
+    let unset = conditions
+        .skip_if_unset
+        .iter()
+        .filter(|name| context.variables.get(*name).is_none_or(String::is_empty))
+        .cloned()
+        .collect::<Vec<_>>();
+    if !unset.is_empty() {
+        return RunDecision::Skip(format!("{} is not set", unset.join(", ")));
+    }
+
+    RunDecision::Run
+}
+
+/// whether a sequence should keep going after the given request failed
+pub fn continues_on_failure(request: &Request) -> bool {
+    request
+        .conditions
+        .as_ref()
+        .is_some_and(|conditions| conditions.continue_on_failure)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::fixtures;
+    use crate::collection::types::RunConditions;
+
+    #[test]
+    fn test_evaluate_conditions() {
+        let mut context = RunContext::new(Some("staging".into()), BTreeMap::default());
+        let seed = fixtures::request("seed", "http://localhost/seed");
+        assert_eq!(evaluate(&seed, &context), RunDecision::Run);
+
+        let staging_only = Request {
+            conditions: Some(RunConditions {
+                only_in: vec!["staging".into()],
+                ..Default::default()
+            }),
+            ..seed.clone()
+        };
+        assert_eq!(evaluate(&staging_only, &context), RunDecision::Run);
+        context.environment = Some("production".into());
+        assert!(matches!(
+            evaluate(&staging_only, &context),
+            RunDecision::Skip(_)
+        ));
+
+        let needs_token = Request {
+            conditions: Some(RunConditions {
+                skip_if_unset: vec!["token".into()],
+                continue_on_failure: true,
+                ..Default::default()
+            }),
+            ..seed
+        };
+        assert_eq!(
+            evaluate(&needs_token, &context),
+            RunDecision::Skip("token is not set".into())
+        );
+        context.variables.insert("token".into(), "abc".into());
+        assert_eq!(evaluate(&needs_token, &context), RunDecision::Run);
+        assert!(continues_on_failure(&needs_token));
+        assert!(!continues_on_failure(&staging_only));
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, RwLock};
+
+use crate::collection::errors::CollectionError;
+use crate::collection::types::{collect_requests, find_request_by, Request, RequestKind};
+
+/// finds a request by its id, looking inside of directories as well
+pub fn find_request(requests: &[RequestKind], id: &str) -> Option<Arc<RwLock<Request>>> {
+    find_request_by(requests, |req| req.id.eq(id))
+}
+
+/// resolves every request that has to be sent before `request`, in the order
+/// they have to be sent. Shared dependencies are only included once, and the
+/// request itself is never part of the result.
+///
+/// fails when a dependency doesn't exist or when the dependencies form a cycle
+pub fn resolve_dependencies(
+    requests: &[RequestKind],
+    request: &Request,
+) -> Result<Vec<Request>, CollectionError> {
+    let mut resolved = vec![];
+    let mut visiting = vec![request.id.clone()];
+
+    for id in request.depends_on.iter() {
+        visit(requests, id, &mut visiting, &mut resolved)?;
+    }
+
+    Ok(resolved)
+}
+
+fn visit(
+    requests: &[RequestKind],
+    id: &str,
+    visiting: &mut Vec<String>,
+    resolved: &mut Vec<Request>,
+) -> Result<(), CollectionError> {
+    if resolved.iter().any(|req| req.id.eq(id)) {
+        return Ok(());
+    }
+
+    let request = find_request(requests, id)
+        .ok_or_else(|| CollectionError::MissingDependency(id.to_string()))?;
+    let request = request.read().unwrap().clone();
+
+    if visiting.iter().any(|visited| visited.eq(id)) {
+        return Err(CollectionError::DependencyCycle(request.name));
+    }
+
+    visiting.push(request.id.clone());
+    for dependency in request.depends_on.iter() {
+        visit(requests, dependency, visiting, resolved)?;
+    }
+    visiting.pop();
+
+    resolved.push(request);
+    Ok(())
+}
+
+/// how the requests of a collection feed into each other, an edge from
+/// `a` to `b` means `a` has to be sent before `b`
+#[derive(Debug, Default, PartialEq)]
+pub struct FlowGraph {
+    /// names of every request that is part of a chain, keyed by id
+    pub nodes: BTreeMap<String, String>,
+    pub edges: BTreeSet<(String, String)>,
+    /// dependencies that point to requests that don't exist, as pairs of the
+    /// dependent request id and the missing id
+    pub missing: Vec<(String, String)>,
+}
+
+impl FlowGraph {
+    /// the ids of the requests `id` depends on directly
+    pub fn dependencies_of<'a>(&'a self, id: &'a str) -> impl Iterator<Item = &'a String> {
+        self.edges
+            .iter()
+            .filter(move |(_, to)| to.eq(id))
+            .map(|(from, _)| from)
+    }
+
+    /// orders every request so each one comes after its dependencies. Fails
+    /// with the name of a request that is part of a cycle when there is one
+    pub fn topological_order(&self) -> Result<Vec<String>, CollectionError> {
+        let mut pending = self
+            .nodes
+            .keys()
+            .map(|id| (id, self.dependencies_of(id).count()))
+            .collect::<BTreeMap<_, _>>();
+        let mut order = vec![];
+
+        while !pending.is_empty() {
+            let ready = pending
+                .iter()
+                .filter(|(_, remaining)| remaining.eq(&&0))
+                .map(|(id, _)| (*id).clone())
+                .collect::<Vec<_>>();
+
+            if ready.is_empty() {
+                let (id, _) = pending.first_key_value().expect("pending is not empty");
+                return Err(CollectionError::DependencyCycle(self.nodes[*id].clone()));
+            }
+
+            for id in ready {
+                pending.remove(&id);
+                self.edges
+                    .iter()
+                    .filter(|(from, _)| from.eq(&id))
+                    .for_each(|(_, to)| {
+                        if let Some(remaining) = pending.get_mut(to) {
+                            *remaining -= 1;
+                        }
+                    });
+                order.push(id);
+            }
+        }
+
+        Ok(order)
+    }
+
+    /// checks that every dependency exists and that there are no cycles, so
+    /// every request on the flow can be sent
+    pub fn validate(&self) -> Result<(), CollectionError> {
+        if let Some((_, missing)) = self.missing.first() {
+            return Err(CollectionError::MissingDependency(missing.clone()));
+        }
+        self.topological_order().map(|_| ())
+    }
+}
+
+/// builds the flow graph of every request that depends on or is a
+/// dependency of another request, looking inside of directories as well
+pub fn flow_graph(requests: &[RequestKind]) -> FlowGraph {
+    let all = collect_requests(requests);
+
+    let mut graph = FlowGraph::default();
+    for request in all.iter().filter(|req| !req.depends_on.is_empty()) {
+        graph.nodes.insert(request.id.clone(), request.name.clone());
+
+        for dependency in request.depends_on.iter() {
+            match all.iter().find(|req| req.id.eq(dependency)) {
+                Some(found) => {
+                    graph.nodes.insert(found.id.clone(), found.name.clone());
+                    graph.edges.insert((found.id.clone(), request.id.clone()));
+                }
+                None => graph.missing.push((request.id.clone(), dependency.clone())),
+            }
+        }
+    }
+
+    graph
+}
+
+/// renders the flow as plain text, every request is listed after the ones
+/// it depends on, to be printed on a terminal
+pub fn render_flow(graph: &FlowGraph) -> Result<String, CollectionError> {
+    let mut out = String::new();
+
+    for id in graph.topological_order()? {
+        let name = &graph.nodes[&id];
+        let dependencies = graph
+            .dependencies_of(&id)
+            .map(|dependency| graph.nodes[dependency].as_str())
+            .collect::<Vec<_>>();
+
+        match dependencies.is_empty() {
+            true => out.push_str(&format!("{name}\n")),
+            false => out.push_str(&format!("{name} <- {}\n", dependencies.join(", "))),
+        }
+    }
+
+    for (id, missing) in graph.missing.iter() {
+        out.push_str(&format!("{} <- {missing} (missing)\n", graph.nodes[id]));
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::fixtures::{self, single};
+    use crate::collection::types::Directory;
+
+    fn request(id: &str, depends_on: &[&str]) -> Request {
+        Request {
+            depends_on: depends_on.iter().map(|id| id.to_string()).collect(),
+            ..fixtures::request(id, &format!("http://localhost/{id}"))
+        }
+    }
+
+    #[test]
+    fn test_resolve_dependencies() {
+        let requests = vec![
+            single(request("login", &[])),
+            single(request("profile", &["login"])),
+            RequestKind::Nested(Directory {
+                id: "dir".into(),
+                name: "orders".into(),
+                requests: Arc::new(RwLock::new(vec![single(request(
+                    "order",
+                    &["profile", "login"],
+                ))])),
+                docs: None,
+            }),
+        ];
+
+        let order = find_request(&requests, "order").unwrap();
+        let resolved = resolve_dependencies(&requests, &order.read().unwrap()).unwrap();
+        let ids = resolved
+            .iter()
+            .map(|req| req.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["login", "profile"]);
+
+        let missing = request("missing", &["nope"]);
+        assert!(matches!(
+            resolve_dependencies(&requests, &missing),
+            Err(CollectionError::MissingDependency(_))
+        ));
+    }
+
+    #[test]
+    fn test_dependency_cycle() {
+        let requests = vec![
+            single(request("a", &["b"])),
+            single(request("b", &["c"])),
+            single(request("c", &["a"])),
+        ];
+
+        let a = request("a", &["b"]);
+        assert!(matches!(
+            resolve_dependencies(&requests, &a),
+            Err(CollectionError::DependencyCycle(_))
+        ));
+    }
+
+    #[test]
+    fn test_flow_graph() {
+        let requests = vec![
+            single(request("order", &["profile", "login"])),
+            single(request("profile", &["login"])),
+            single(request("login", &[])),
+            single(request("health", &[])),
+            single(request("refund", &["order", "gone"])),
+        ];
+
+        let graph = flow_graph(&requests);
+        assert!(!graph.nodes.contains_key("health"));
+        assert_eq!(
+            graph.missing,
+            vec![("refund".to_string(), "gone".to_string())]
+        );
+        assert_eq!(
+            graph.topological_order().unwrap(),
+            vec!["login", "profile", "order", "refund"]
+        );
+        assert!(matches!(
+            graph.validate(),
+            Err(CollectionError::MissingDependency(_))
+        ));
+        assert_eq!(
+            render_flow(&graph).unwrap(),
+            "login\nprofile <- login\norder <- login, profile\nrefund <- order\nrefund <- gone (missing)\n"
+        );
+
+        let cyclic = flow_graph(&[single(request("a", &["b"])), single(request("b", &["a"]))]);
+        assert!(matches!(
+            cyclic.validate(),
+            Err(CollectionError::DependencyCycle(_))
+        ));
+    }
+}
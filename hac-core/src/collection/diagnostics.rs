@@ -0,0 +1,257 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::collection::environment::{find_environment, variable_spans};
+use crate::collection::types::{collect_requests, Collection, Request};
+use crate::faker;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiagnosticKind {
+    /// a request references a variable that isn't defined on the scope
+    UndefinedVariable,
+    /// an environment defines a variable that no request references
+    UnusedVariable,
+}
+
+/// which part of a request a diagnostic points to
+#[derive(Debug, Clone, PartialEq)]
+pub enum RequestField {
+    Uri,
+    /// the name or value of the header at the given index
+    Header(usize),
+    Body,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Location {
+    /// a position on a request, `line` and `column` start at 1
+    Request {
+        id: String,
+        name: String,
+        field: RequestField,
+        line: usize,
+        column: usize,
+    },
+    Environment(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub variable: String,
+    pub location: Location,
+    /// a defined variable with a similar name, likely what was meant
+    pub suggestion: Option<String>,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.location {
+            Location::Request {
+                name,
+                field,
+                line,
+                column,
+                ..
+            } => {
+                let field = match field {
+                    RequestField::Uri => "uri".to_string(),
+                    RequestField::Header(idx) => format!("header {}", idx + 1),
+                    RequestField::Body => "body".to_string(),
+                };
+                write!(f, "{name} > {field} {line}:{column}: ")?;
+            }
+            Location::Environment(name) => write!(f, "environment {name}: ")?,
+        }
+
+        match self.kind {
+            DiagnosticKind::UndefinedVariable => {
+                write!(f, "undefined variable {{{{{}}}}}", self.variable)?
+            }
+            DiagnosticKind::UnusedVariable => write!(f, "unused variable {}", self.variable)?,
+        }
+
+        match self.suggestion.as_ref() {
+            Some(suggestion) => write!(f, " (did you mean {suggestion}?)"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// scans every request of the collection for variables that are not defined
+/// and every environment for variables that are never used.
+///
+/// when an environment is given, only its variables are considered defined,
+/// otherwise a variable defined on any environment is. The local variables of
+/// the workspace are always defined
+pub fn analyze(
+    collection: &Collection,
+    environment: Option<&str>,
+    local: &BTreeMap<String, String>,
+) -> Vec<Diagnostic> {
+    let mut defined = match environment.and_then(|name| find_environment(collection, name)) {
+        Some(env) => env.variables.keys().cloned().collect::<BTreeSet<_>>(),
+        None => collection
+            .environments
+            .iter()
+            .flat_map(|env| env.variables.keys().cloned())
+            .collect(),
+    };
+    defined.extend(local.keys().cloned());
+
+    let requests = collection
+        .requests
+        .as_ref()
+        .map(|items| collect_requests(&items.read().unwrap()))
+        .unwrap_or_default();
+
+    let mut diagnostics = vec![];
+    let mut used = BTreeSet::new();
+    for request in requests.iter() {
+        for (field, text) in request_texts(request) {
+            for span in variable_spans(text) {
+                used.insert(span.name.clone());
+                if defined.contains(&span.name) || faker::is_function(&span.name) {
+                    continue;
+                }
+
+                let before = &text[..span.start];
+                let line = before.matches('\n').count() + 1;
+                let column = before
+                    .rsplit('\n')
+                    .next()
+                    .unwrap_or_default()
+                    .chars()
+                    .count()
+                    + 1;
+                diagnostics.push(Diagnostic {
+                    kind: DiagnosticKind::UndefinedVariable,
+                    suggestion: closest_name(&span.name, &defined),
+                    variable: span.name,
+                    location: Location::Request {
+                        id: request.id.clone(),
+                        name: request.name.clone(),
+                        field: field.clone(),
+                        line,
+                        column,
+                    },
+                });
+            }
+        }
+    }
+
+    for env in collection.environments.iter() {
+        env.variables
+            .keys()
+            .filter(|name| !used.contains(*name))
+            .for_each(|name| {
+                diagnostics.push(Diagnostic {
+                    kind: DiagnosticKind::UnusedVariable,
+                    variable: name.clone(),
+                    location: Location::Environment(env.name.clone()),
+                    suggestion: None,
+                })
+            });
+    }
+
+    diagnostics
+}
+
+fn request_texts(request: &Request) -> Vec<(RequestField, &str)> {
+    let mut texts = vec![(RequestField::Uri, request.uri.as_str())];
+    request
+        .headers
+        .iter()
+        .flatten()
+        .enumerate()
+        .filter(|(_, header)| header.enabled)
+        .for_each(|(idx, header)| {
+            texts.push((RequestField::Header(idx), header.pair.0.as_str()));
+            texts.push((RequestField::Header(idx), header.pair.1.as_str()));
+        });
+    if let Some(body) = request.body.as_ref() {
+        texts.push((RequestField::Body, body));
+    }
+    texts
+}
+
+/// the defined name closest to `name`, as long as it is close enough to
+/// likely be a typo
+fn closest_name(name: &str, defined: &BTreeSet<String>) -> Option<String> {
+    let max_distance = (name.chars().count() / 3).clamp(1, 3);
+    defined
+        .iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| distance.le(&max_distance))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.clone())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut curr = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca.ne(cb));
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        prev = curr;
+    }
+
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::types::{Environment, Info, RequestKind, RequestMethod};
+    use std::sync::{Arc, RwLock};
+
+    #[test]
+    fn test_analyze() {
+        let request = Request {
+            id: "id".into(),
+            method: RequestMethod::Post,
+            name: "create user".into(),
+            uri: "{{basUrl}}/users".into(),
+            body: Some("{\n  \"token\": \"{{token}}\"\n}".into()),
+            ..Default::default()
+        };
+        let collection = Collection {
+            info: Info {
+                name: "api".into(),
+                description: None,
+                read_only: false,
+            },
+            requests: Some(Arc::new(RwLock::new(vec![RequestKind::Single(Arc::new(
+                RwLock::new(request),
+            ))]))),
+            environments: vec![Environment {
+                name: "dev".into(),
+                variables: [("baseUrl".to_string(), "http://localhost".to_string())].into(),
+            }],
+            path: "api.json".into(),
+            unlocked: false,
+            protos: vec![],
+            highlights: vec![],
+            template: None,
+            format_on_save: false,
+            synced: Default::default(),
+        };
+
+        let diagnostics = analyze(&collection, Some("dev"), &BTreeMap::default());
+        let rendered = diagnostics
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rendered,
+            vec![
+                "create user > uri 1:1: undefined variable {{basUrl}} (did you mean baseUrl?)",
+                "create user > body 2:13: undefined variable {{token}}",
+                "environment dev: unused variable baseUrl",
+            ]
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use crate::collection::environment::variable_spans;
+use crate::collection::types::{find_request_by, Request, RequestKind, RequestMethod};
+use crate::url_builder::UrlParts;
+
+use std::sync::{Arc, RwLock};
+
+/// the uri reduced to what tells endpoints apart. Which variable is used,
+/// the case of the scheme and host, trailing slashes, the order of the query
+/// parameters and the fragment make no difference
+pub fn normalize_uri(uri: &str) -> String {
+    let uri = uri.trim();
+    let mut normalized = String::with_capacity(uri.len());
+    let mut last = 0;
+    for span in variable_spans(uri) {
+        normalized.push_str(&uri[last..span.start]);
+        normalized.push_str("{{}}");
+        last = span.end;
+    }
+    normalized.push_str(&uri[last..]);
+
+    let parts = UrlParts::split(&normalized);
+    let mut query = parts
+        .query
+        .split('&')
+        .filter(|param| !param.is_empty())
+        .collect::<Vec<_>>();
+    query.sort_unstable();
+
+    UrlParts {
+        scheme: parts.scheme.to_ascii_lowercase(),
+        host: parts.host.to_ascii_lowercase(),
+        path: parts.path.trim_end_matches('/').to_string(),
+        query: query.join("&"),
+        fragment: String::default(),
+    }
+    .join()
+}
+
+/// finds a request, anywhere on the collection, sending the same method to
+/// the same uri once both are normalized
+pub fn find_duplicate(
+    requests: &[RequestKind],
+    method: &RequestMethod,
+    uri: &str,
+) -> Option<Arc<RwLock<Request>>> {
+    let normalized = normalize_uri(uri);
+    if normalized.is_empty() {
+        return None;
+    }
+
+    find_request_by(requests, |candidate| {
+        candidate.method.eq(method) && normalize_uri(&candidate.uri).eq(&normalized)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::fixtures::{self, single};
+    use crate::collection::types::Directory;
+
+    #[test]
+    fn test_normalize_uri() {
+        assert_eq!(
+            normalize_uri("HTTPS://API.example.com/Users/{{ user_id }}/?b=2&a={{a}}#top"),
+            "https://api.example.com/Users/{{}}?a={{}}&b=2"
+        );
+        assert_eq!(
+            normalize_uri("{{base_url}}/users/{{id}}"),
+            normalize_uri("{{host}}/users/{{user}}/")
+        );
+        assert_eq!(normalize_uri("  "), "");
+    }
+
+    #[test]
+    fn test_find_duplicate() {
+        let requests = vec![
+            single(fixtures::request("list", "{{base_url}}/users")),
+            RequestKind::Nested(Directory {
+                id: "dir".into(),
+                name: "dir".into(),
+                requests: Arc::new(RwLock::new(vec![single(Request {
+                    method: RequestMethod::Post,
+                    ..fixtures::request("create", "{{base_url}}/users")
+                })])),
+                ..Default::default()
+            }),
+        ];
+
+        let found = find_duplicate(&requests, &RequestMethod::Post, "{{ base_url }}/users/");
+        assert_eq!(found.unwrap().read().unwrap().name, "create");
+        let found = find_duplicate(&requests, &RequestMethod::Get, "{{base_url}}/users");
+        assert_eq!(found.unwrap().read().unwrap().name, "list");
+        assert!(find_duplicate(&requests, &RequestMethod::Delete, "{{base_url}}/users").is_none());
+        assert!(find_duplicate(&requests, &RequestMethod::Get, "").is_none());
+    }
+}
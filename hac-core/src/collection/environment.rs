@@ -0,0 +1,490 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::collection::local;
+use crate::collection::types::{Collection, Environment, Request};
+use crate::completion::find_variables;
+use crate::faker;
+
+/// finds an environment of the collection by its name
+pub fn find_environment<'a>(collection: &'a Collection, name: &str) -> Option<&'a Environment> {
+    collection.environments.iter().find(|env| env.name.eq(name))
+}
+
+/// the variables of the environment with the local ones of the workspace on
+/// top of them, see `local`. Session variables still take precedence
+pub fn environment_variables(
+    collection: &Collection,
+    environment: Option<&str>,
+) -> BTreeMap<String, String> {
+    let mut variables = environment
+        .and_then(|name| find_environment(collection, name))
+        .map(|env| env.variables.clone())
+        .unwrap_or_default();
+    variables.extend(local::workspace_variables(environment));
+    variables
+}
+
+/// replaces every `{{variable}}` on the text by its value, template functions
+/// like `{{$randomEmail}}` get a new value on every use, unless a variable
+/// with the same name is defined. Variables that are not defined are left
+/// untouched so they are easy to spot
+pub fn substitute(text: &str, variables: &BTreeMap<String, String>) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(open) = rest.find("{{") {
+        let after_open = &rest[open + 2..];
+        let Some(close) = after_open.find("}}") else {
+            break;
+        };
+
+        result.push_str(&rest[..open]);
+        let name = after_open[..close].trim();
+        match variables
+            .get(name)
+            .cloned()
+            .or_else(|| faker::generate(name))
+        {
+            Some(value) => result.push_str(&value),
+            None => result.push_str(&rest[open..open + close + 4]),
+        }
+        rest = &after_open[close + 2..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// where a `{{variable}}` is on a text, `start` and `end` are byte offsets
+/// that include the braces
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableSpan {
+    pub name: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// finds every `{{variable}}` on a text along with where it is
+pub fn variable_spans(text: &str) -> Vec<VariableSpan> {
+    let mut spans = vec![];
+    let mut offset = 0;
+
+    while let Some(open) = text[offset..].find("{{") {
+        let start = offset + open;
+        let Some(close) = text[start + 2..].find("}}") else {
+            break;
+        };
+        let end = start + 2 + close + 2;
+        let name = text[start + 2..end - 2].trim();
+        if !name.is_empty() {
+            spans.push(VariableSpan {
+                name: name.to_string(),
+                start,
+                end,
+            });
+        }
+        offset = end;
+    }
+
+    spans
+}
+
+/// the variable whose `{{...}}` token contains the given char index, the
+/// braces are considered part of the token
+pub fn variable_at(text: &str, char_idx: usize) -> Option<VariableSpan> {
+    let byte_idx = text
+        .char_indices()
+        .nth(char_idx)
+        .map(|(idx, _)| idx)
+        .unwrap_or(text.len());
+
+    variable_spans(text)
+        .into_iter()
+        .find(|span| span.start.le(&byte_idx) && span.end.gt(&byte_idx))
+}
+
+/// where the value of a variable comes from
+#[derive(Debug, Clone, PartialEq)]
+pub enum VariableScope {
+    Environment(String),
+    /// the local variable files of the workspace, kept out of version control
+    Local,
+    /// set while hac runs, like values captured from responses
+    Session,
+    /// a template function like `{{$randomEmail}}`, the value is a sample as
+    /// a new one is generated every time the request is sent
+    Function,
+}
+
+/// what a variable under the cursor resolves to, `value` and `scope` are
+/// `None` when the variable isn't defined on the active environment
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariablePreview {
+    pub name: String,
+    pub value: Option<String>,
+    pub scope: Option<VariableScope>,
+    /// every environment that defines the variable, including the active one
+    pub defined_in: Vec<String>,
+}
+
+/// resolves the `{{variable}}` at the given char index of a buffer against
+/// the session variables, the local variables of the workspace and the
+/// active environment, to be shown as a preview while editing
+pub fn preview_variable(
+    collection: &Collection,
+    active_environment: Option<&str>,
+    session: &BTreeMap<String, String>,
+    local: &BTreeMap<String, String>,
+    text: &str,
+    char_idx: usize,
+) -> Option<VariablePreview> {
+    let span = variable_at(text, char_idx)?;
+
+    let value = match (session.get(&span.name), local.get(&span.name)) {
+        (Some(value), _) => Some((value.clone(), VariableScope::Session)),
+        (None, Some(value)) => Some((value.clone(), VariableScope::Local)),
+        (None, None) => active_environment
+            .and_then(|name| find_environment(collection, name))
+            .and_then(|env| {
+                env.variables
+                    .get(&span.name)
+                    .map(|value| (value.clone(), VariableScope::Environment(env.name.clone())))
+            })
+            .or_else(|| faker::generate(&span.name).map(|value| (value, VariableScope::Function))),
+    };
+
+    Some(VariablePreview {
+        value: value.as_ref().map(|(value, _)| value.clone()),
+        scope: value.map(|(_, scope)| scope),
+        defined_in: collection
+            .environments
+            .iter()
+            .filter(|env| env.variables.contains_key(&span.name))
+            .map(|env| env.name.clone())
+            .collect(),
+        name: span.name,
+    })
+}
+
+/// a copy of the request with every variable on its uri, headers, body,
+/// auth config and scenario replaced by the values of the given variables
+pub fn resolve_request(request: &Request, variables: &BTreeMap<String, String>) -> Request {
+    let mut resolved = request.clone();
+    resolved.uri = substitute(&request.uri, variables);
+    resolved.body = request
+        .body
+        .as_ref()
+        .map(|body| substitute(body, variables));
+    resolved.auth = request
+        .auth
+        .as_ref()
+        .map(|auth| auth.map_values(|value| substitute(value, variables)));
+    resolved.scenario = request
+        .scenario
+        .iter()
+        .map(|step| step.map_values(|value| substitute(value, variables)))
+        .collect();
+    if let Some(headers) = resolved.headers.as_mut() {
+        headers.iter_mut().for_each(|header| {
+            header.pair = (
+                substitute(&header.pair.0, variables),
+                substitute(&header.pair.1, variables),
+            );
+        });
+    }
+    resolved
+}
+
+/// variables the request uses that are not defined on the given variables
+pub fn missing_variables(request: &Request, variables: &BTreeMap<String, String>) -> Vec<String> {
+    let mut texts = vec![request.uri.as_str()];
+    if let Some(body) = request.body.as_ref() {
+        texts.push(body);
+    }
+    if let Some(headers) = request.headers.as_ref() {
+        headers
+            .iter()
+            .filter(|header| header.enabled)
+            .for_each(|header| texts.extend([header.pair.0.as_str(), header.pair.1.as_str()]));
+    }
+
+    texts
+        .into_iter()
+        .flat_map(find_variables)
+        .filter(|name| !variables.contains_key(name) && !faker::is_function(name))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// a part of a request that resolves differently on two environments, a
+/// `None` side means the part doesn't exist on that environment
+#[derive(Debug, PartialEq)]
+pub struct FieldDiff {
+    pub field: String,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct EnvironmentComparison {
+    pub left: String,
+    pub right: String,
+    pub diffs: Vec<FieldDiff>,
+    pub missing_left: Vec<String>,
+    pub missing_right: Vec<String>,
+}
+
+impl EnvironmentComparison {
+    /// whether both environments resolve the request in the same way, and
+    /// neither of them is missing variables
+    pub fn is_identical(&self) -> bool {
+        self.diffs.is_empty() && self.missing_left.is_empty() && self.missing_right.is_empty()
+    }
+}
+
+/// resolves the request against both environments and compares the uri,
+/// enabled headers and body of the results
+pub fn compare(
+    request: &Request,
+    left: &Environment,
+    right: &Environment,
+) -> EnvironmentComparison {
+    let resolved_left = resolve_request(request, &left.variables);
+    let resolved_right = resolve_request(request, &right.variables);
+    let mut diffs = vec![];
+
+    if resolved_left.uri.ne(&resolved_right.uri) {
+        diffs.push(FieldDiff {
+            field: "uri".into(),
+            left: Some(resolved_left.uri.clone()),
+            right: Some(resolved_right.uri.clone()),
+        });
+    }
+
+    let left_headers = enabled_headers(&resolved_left);
+    let right_headers = enabled_headers(&resolved_right);
+    left_headers
+        .keys()
+        .chain(right_headers.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .for_each(|name| {
+            let (left, right) = (left_headers.get(name), right_headers.get(name));
+            if left.ne(&right) {
+                diffs.push(FieldDiff {
+                    field: format!("header {name}"),
+                    left: left.cloned(),
+                    right: right.cloned(),
+                });
+            }
+        });
+
+    let left_body = resolved_left.body.unwrap_or_default();
+    let right_body = resolved_right.body.unwrap_or_default();
+    if left_body.ne(&right_body) {
+        let left_lines = left_body.lines().collect::<Vec<_>>();
+        let right_lines = right_body.lines().collect::<Vec<_>>();
+        (0..left_lines.len().max(right_lines.len())).for_each(|idx| {
+            let (left, right) = (left_lines.get(idx), right_lines.get(idx));
+            if left.ne(&right) {
+                diffs.push(FieldDiff {
+                    field: format!("body line {}", idx + 1),
+                    left: left.map(|line| line.to_string()),
+                    right: right.map(|line| line.to_string()),
+                });
+            }
+        });
+    }
+
+    EnvironmentComparison {
+        left: left.name.clone(),
+        right: right.name.clone(),
+        diffs,
+        missing_left: missing_variables(request, &left.variables),
+        missing_right: missing_variables(request, &right.variables),
+    }
+}
+
+fn enabled_headers(request: &Request) -> BTreeMap<String, String> {
+    request
+        .headers
+        .iter()
+        .flatten()
+        .filter(|header| header.enabled)
+        .map(|header| (header.pair.0.to_lowercase(), header.pair.1.clone()))
+        .collect()
+}
+
+/// renders a comparison as plain text, to be printed on a terminal
+pub fn render_comparison(comparison: &EnvironmentComparison) -> String {
+    let mut out = String::new();
+    let (left, right) = (&comparison.left, &comparison.right);
+
+    if comparison.is_identical() {
+        out.push_str(&format!(
+            "{left} and {right} resolve the request the same way\n"
+        ));
+        return out;
+    }
+
+    for (env, missing) in [
+        (left, &comparison.missing_left),
+        (right, &comparison.missing_right),
+    ] {
+        if !missing.is_empty() {
+            out.push_str(&format!("missing on {env}: {}\n", missing.join(", ")));
+        }
+    }
+
+    for diff in comparison.diffs.iter() {
+        out.push_str(&format!("{}\n", diff.field));
+        let none = "<none>".to_string();
+        out.push_str(&format!(
+            "  - {left}: {}\n",
+            diff.left.as_ref().unwrap_or(&none)
+        ));
+        out.push_str(&format!(
+            "  + {right}: {}\n",
+            diff.right.as_ref().unwrap_or(&none)
+        ));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::types::{HeaderMap, Info, RequestMethod};
+
+    fn environment(name: &str, variables: &[(&str, &str)]) -> Environment {
+        Environment {
+            name: name.into(),
+            variables: variables
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_substitute() {
+        let env = environment("dev", &[("host", "localhost:3000"), ("id", "1")]);
+
+        assert_eq!(
+            substitute("http://{{host}}/users/{{ id }}", &env.variables),
+            "http://localhost:3000/users/1"
+        );
+        assert_eq!(
+            substitute("{{token}} and {{host", &env.variables),
+            "{{token}} and {{host"
+        );
+
+        // functions generate a new value on every use
+        let ids = substitute("{{$randomUUID}} {{$randomUUID}}", &env.variables);
+        let (first, second) = ids.split_once(' ').unwrap();
+        assert_eq!(first.len(), 36);
+        assert_ne!(first, second);
+
+        let env = environment("dev", &[("$randomEmail", "fixed@example.com")]);
+        assert_eq!(
+            substitute("{{$randomEmail}}", &env.variables),
+            "fixed@example.com"
+        );
+    }
+
+    #[test]
+    fn test_preview_variable() {
+        let collection = Collection {
+            info: Info {
+                name: "api".into(),
+                description: None,
+                read_only: false,
+            },
+            requests: None,
+            environments: vec![
+                environment("dev", &[("host", "localhost:3000")]),
+                environment("prod", &[("host", "api.com"), ("token", "abc")]),
+            ],
+            path: "api.json".into(),
+            unlocked: false,
+            protos: vec![],
+            highlights: vec![],
+            template: None,
+            format_on_save: false,
+            synced: Default::default(),
+        };
+        let text = "http://{{ host }}/é/{{token}}";
+
+        let (session, local) = (BTreeMap::default(), BTreeMap::default());
+        let preview =
+            preview_variable(&collection, Some("dev"), &session, &local, text, 7).unwrap();
+        assert_eq!(preview.name, "host");
+        assert_eq!(preview.value.as_deref(), Some("localhost:3000"));
+        assert_eq!(
+            preview.scope,
+            Some(VariableScope::Environment("dev".into()))
+        );
+        assert_eq!(preview.defined_in, vec!["dev", "prod"]);
+
+        let preview =
+            preview_variable(&collection, Some("dev"), &session, &local, text, 22).unwrap();
+        assert_eq!(preview.name, "token");
+        assert!(preview.value.is_none() && preview.scope.is_none());
+        assert_eq!(preview.defined_in, vec!["prod"]);
+
+        let local = BTreeMap::from([("token".to_string(), "mine".to_string())]);
+        let preview =
+            preview_variable(&collection, Some("dev"), &session, &local, text, 22).unwrap();
+        assert_eq!(preview.value.as_deref(), Some("mine"));
+        assert_eq!(preview.scope, Some(VariableScope::Local));
+
+        let session = BTreeMap::from([("token".to_string(), "captured".to_string())]);
+        let preview =
+            preview_variable(&collection, Some("dev"), &session, &local, text, 22).unwrap();
+        assert_eq!(preview.value.as_deref(), Some("captured"));
+        assert_eq!(preview.scope, Some(VariableScope::Session));
+
+        assert!(preview_variable(&collection, Some("dev"), &session, &local, text, 17).is_none());
+        assert!(preview_variable(&collection, Some("dev"), &session, &local, text, 6).is_none());
+    }
+
+    #[test]
+    fn test_compare_environments() {
+        let request = Request {
+            id: "id".into(),
+            method: RequestMethod::Post,
+            name: "create user".into(),
+            uri: "{{host}}/users".into(),
+            headers: Some(vec![HeaderMap {
+                pair: ("Authorization".into(), "Bearer {{token}}".into()),
+                enabled: true,
+            }]),
+            body: Some("{\n  \"role\": \"{{role}}\",\n  \"name\": \"john\"\n}".into()),
+            ..Default::default()
+        };
+        let staging = environment(
+            "staging",
+            &[
+                ("host", "https://staging.api"),
+                ("token", "abc"),
+                ("role", "admin"),
+            ],
+        );
+        let production = environment("production", &[("host", "https://api"), ("role", "admin")]);
+
+        let comparison = compare(&request, &staging, &production);
+        assert!(!comparison.is_identical());
+        assert_eq!(comparison.missing_right, vec!["token"]);
+        assert!(comparison.missing_left.is_empty());
+
+        let fields = comparison
+            .diffs
+            .iter()
+            .map(|diff| diff.field.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(fields, vec!["uri", "header authorization"]);
+        assert!(compare(&request, &staging, &staging).is_identical());
+    }
+}
@@ -1,12 +1,35 @@
-// TODO use this somewhere, seems important
-#[allow(dead_code)]
+#[derive(Debug)]
 pub enum CollectionError {
+    /// tried to modify a collection that is read only and wasn't unlocked
+    ReadOnly(String),
+    /// a request depends on a request that doesn't exist on the collection
+    MissingDependency(String),
+    /// following the dependencies of a request leads back to itself
+    DependencyCycle(String),
+    /// a variable was renamed to one that is already defined
+    VariableExists(String),
     Unknown(String),
 }
 
 impl std::fmt::Display for CollectionError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            CollectionError::ReadOnly(name) => {
+                write!(
+                    f,
+                    "collection {:?} is read only, unlock it to make changes",
+                    name
+                )
+            }
+            CollectionError::MissingDependency(id) => {
+                write!(f, "request depends on {:?}, which doesn't exist", id)
+            }
+            CollectionError::DependencyCycle(name) => {
+                write!(f, "request {:?} depends on itself", name)
+            }
+            CollectionError::VariableExists(name) => {
+                write!(f, "variable {:?} already exists", name)
+            }
             CollectionError::Unknown(msg) => write!(f, "{}", msg),
         }
     }
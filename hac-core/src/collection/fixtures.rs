@@ -0,0 +1,20 @@
+//! requests shared by the tests of the collection modules, anything they
+//! need besides a name and an uri is set with `..fixtures::request(..)`
+use std::sync::{Arc, RwLock};
+
+use crate::collection::types::{Request, RequestKind};
+
+/// a GET request named after its id, sent against the uri
+pub fn request(name: &str, uri: &str) -> Request {
+    Request {
+        id: name.into(),
+        name: name.into(),
+        uri: uri.into(),
+        ..Default::default()
+    }
+}
+
+/// the request as a node of the request tree of a collection
+pub fn single(request: Request) -> RequestKind {
+    RequestKind::Single(Arc::new(RwLock::new(request)))
+}
@@ -0,0 +1,129 @@
+use std::ops::Range;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// a value of responses that should stand out, like an user id or an error
+/// code, every match of the pattern is shown with the given style
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HighlightRule {
+    pub pattern: String,
+    pub color: HighlightColor,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub bold: bool,
+    /// colors the background instead of the text
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub background: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HighlightColor {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Orange,
+}
+
+/// the rules of a collection with their patterns compiled
+#[derive(Debug, Default)]
+pub struct Highlights {
+    rules: Vec<(Regex, HighlightRule)>,
+}
+
+impl Highlights {
+    /// compiles every rule, rules with invalid patterns are reported and
+    /// left out
+    pub fn compile(rules: &[HighlightRule]) -> (Highlights, Vec<String>) {
+        let mut errors = vec![];
+        let rules = rules
+            .iter()
+            .filter_map(|rule| match Regex::new(&rule.pattern) {
+                Ok(regex) => Some((regex, rule.clone())),
+                Err(e) => {
+                    errors.push(format!("invalid highlight pattern {:?}: {e}", rule.pattern));
+                    None
+                }
+            })
+            .collect();
+        (Highlights { rules }, errors)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// every match of the rules on the text, in order and without
+    /// overlapping, rules that come first win over the ones after them
+    pub fn matches(&self, text: &str) -> Vec<(Range<usize>, &HighlightRule)> {
+        let mut matches: Vec<(Range<usize>, &HighlightRule)> = vec![];
+        for (regex, rule) in self.rules.iter() {
+            for found in regex.find_iter(text).filter(|found| !found.is_empty()) {
+                let range = found.range();
+                let overlaps = matches
+                    .iter()
+                    .any(|(taken, _)| range.start < taken.end && taken.start < range.end);
+                if !overlaps {
+                    matches.push((range, rule));
+                }
+            }
+        }
+        matches.sort_by_key(|(range, _)| range.start);
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, color: HighlightColor) -> HighlightRule {
+        HighlightRule {
+            pattern: pattern.into(),
+            color,
+            bold: false,
+            background: false,
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        let (highlights, errors) = Highlights::compile(&[
+            rule(r#""userId": \d+"#, HighlightColor::Green),
+            rule(r"\d+", HighlightColor::Red),
+            rule(r"(unclosed", HighlightColor::Blue),
+        ]);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("invalid highlight pattern \"(unclosed\""));
+
+        let text = r#"{"code": 42, "userId": 7}"#;
+        let matches = highlights
+            .matches(text)
+            .into_iter()
+            .map(|(range, rule)| (&text[range], rule.color))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            matches,
+            vec![
+                ("42", HighlightColor::Red),
+                (r#""userId": 7"#, HighlightColor::Green),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rule_serde() {
+        let rules = serde_json::from_str::<Vec<HighlightRule>>(
+            r#"[{"pattern": "ERR-\\d+", "color": "red", "bold": true}]"#,
+        )
+        .unwrap();
+        assert_eq!(rules[0].color, HighlightColor::Red);
+        assert!(rules[0].bold && !rules[0].background);
+        assert_eq!(
+            serde_json::to_string(&rules[0]).unwrap(),
+            r#"{"pattern":"ERR-\\d+","color":"red","bold":true}"#
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use crate::collection::types::{Directory, RequestKind};
+
+use std::sync::{Arc, RwLock};
+
+/// how many changes to the collection are kept to be undone
+const MAX_HISTORY: usize = 100;
+
+/// the requests of a collection as they were before each structural change,
+/// like deleting a request or moving it to another directory, so a change
+/// done by mistake can be undone
+#[derive(Debug, Default)]
+pub struct CollectionHistory {
+    /// what was changed, and the requests before the change
+    changes: Vec<(String, Vec<RequestKind>)>,
+}
+
+impl CollectionHistory {
+    /// keeps a copy of `requests`, described by `label`, to be restored later
+    pub fn record<L>(&mut self, label: L, requests: &[RequestKind])
+    where
+        L: Into<String>,
+    {
+        self.changes.push((label.into(), deep_clone(requests)));
+        if self.changes.len().gt(&MAX_HISTORY) {
+            self.changes.remove(0);
+        }
+    }
+
+    /// the last change recorded, and the requests as they were before it
+    pub fn undo(&mut self) -> Option<(String, Vec<RequestKind>)> {
+        self.changes.pop()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// copies every request and directory, requests are shared behind locks so
+/// cloning the tree alone would still point to the ones being changed
+pub fn deep_clone(requests: &[RequestKind]) -> Vec<RequestKind> {
+    requests
+        .iter()
+        .map(|item| match item {
+            RequestKind::Single(req) => {
+                RequestKind::Single(Arc::new(RwLock::new(req.read().unwrap().clone())))
+            }
+            RequestKind::Nested(dir) => RequestKind::Nested(Directory {
+                id: dir.id.clone(),
+                name: dir.name.clone(),
+                requests: Arc::new(RwLock::new(deep_clone(&dir.requests.read().unwrap()))),
+                docs: dir.docs.clone(),
+            }),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::dependencies::find_request;
+
+    #[test]
+    fn test_undo_changes() {
+        let requests = serde_json::from_str::<Vec<RequestKind>>(
+            r#"[
+                { "id": "users", "name": "users", "requests": [
+                    { "id": "list", "method": "GET", "name": "list", "uri": "/users" }
+                ] },
+                { "id": "health", "method": "GET", "name": "health", "uri": "/health" }
+            ]"#,
+        )
+        .unwrap();
+        let mut history = CollectionHistory::default();
+
+        history.record("uri of list", &requests);
+        let list = find_request(&requests, "list").unwrap();
+        list.write().unwrap().uri = "/people".into();
+
+        history.record("delete users", &requests);
+        let mut requests = requests;
+        requests.remove(0);
+
+        let (label, restored) = history.undo().unwrap();
+        assert_eq!(label, "delete users");
+        assert_eq!(restored.len(), 2);
+        let restored_list = find_request(&restored, "list").unwrap();
+        assert_eq!(restored_list.read().unwrap().uri, "/people");
+
+        let (_, restored) = history.undo().unwrap();
+        let restored_list = find_request(&restored, "list").unwrap();
+        assert_eq!(restored_list.read().unwrap().uri, "/users");
+        assert!(!Arc::ptr_eq(&restored_list, &list));
+        assert!(history.is_empty());
+        assert!(history.undo().is_none());
+    }
+}
@@ -0,0 +1,107 @@
+use crate::collection::variables::{parse_dotenv, parse_json_variables};
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// what the `.gitignore` written on workspaces holds, so local variables
+/// are never committed by accident
+const GITIGNORE: &str = "# machine specific and secret variables\nlocal.*\n";
+
+/// the local variable files of a workspace that apply to the environment,
+/// lowest precedence first. `local.env` and `local.json` apply to every
+/// environment, and `local.<environment>.env` or `.json` only to that one
+pub fn files(workspace: &Path, environment: Option<&str>) -> Vec<PathBuf> {
+    let mut names = vec![hac_config::LOCAL_VARIABLES.to_string()];
+    if let Some(environment) = environment {
+        names.push(format!("{}.{environment}", hac_config::LOCAL_VARIABLES));
+    }
+    names
+        .iter()
+        .flat_map(|name| ["json", "env"].map(|ext| workspace.join(format!("{name}.{ext}"))))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// reads the local variables of the workspace for the environment, the
+/// files for the environment take precedence over the ones for every
+/// environment
+pub fn load(
+    workspace: &Path,
+    environment: Option<&str>,
+) -> anyhow::Result<BTreeMap<String, String>> {
+    let mut variables = BTreeMap::new();
+    for path in files(workspace, environment) {
+        let contents = std::fs::read_to_string(&path)?;
+        let parsed = match path.extension().is_some_and(|ext| ext.eq("json")) {
+            true => parse_json_variables(&contents),
+            false => parse_dotenv(&contents),
+        };
+        let parsed = parsed.map_err(|e| anyhow::anyhow!("{}: {e}", path.to_string_lossy()))?;
+        variables.extend(parsed);
+    }
+    Ok(variables)
+}
+
+/// the local variables of the workspace hac runs on, if any. They take
+/// precedence over the variables of the environment, and only the session
+/// ones take precedence over them
+pub fn workspace_variables(environment: Option<&str>) -> BTreeMap<String, String> {
+    let Some(workspace) = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| hac_config::find_workspace_dir(&cwd))
+    else {
+        return BTreeMap::default();
+    };
+
+    if let Err(e) = ignore_local_files(&workspace) {
+        tracing::warn!("failed to write the .gitignore of {workspace:?}: {e}");
+    }
+    match load(&workspace, environment) {
+        Ok(variables) => variables,
+        Err(e) => {
+            tracing::error!("failed to load local variables: {e}");
+            BTreeMap::default()
+        }
+    }
+}
+
+/// writes a `.gitignore` ignoring the local variable files on a workspace
+/// that has them, unless the workspace already has one
+pub fn ignore_local_files(workspace: &Path) -> anyhow::Result<()> {
+    let gitignore = workspace.join(".gitignore");
+    if gitignore.exists() || files(workspace, None).is_empty() {
+        return Ok(());
+    }
+    std::fs::write(gitignore, GITIGNORE)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_local_variables() {
+        let dir = std::env::temp_dir().join(format!("hac-local-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("local.env"),
+            "base_url=http://localhost:3000\ntoken=mine\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("local.staging.json"), r#"{ "token": "staging" }"#).unwrap();
+
+        let variables = load(&dir, Some("staging")).unwrap();
+        assert_eq!(variables.get("base_url").unwrap(), "http://localhost:3000");
+        assert_eq!(variables.get("token").unwrap(), "staging");
+        assert_eq!(
+            load(&dir, Some("prod")).unwrap().get("token").unwrap(),
+            "mine"
+        );
+
+        ignore_local_files(&dir).unwrap();
+        let gitignore = std::fs::read_to_string(dir.join(".gitignore")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(gitignore.contains("local.*\n"));
+    }
+}
@@ -0,0 +1,313 @@
+use crate::collection::history::deep_clone;
+use crate::collection::types::{Collection, Directory, Request, RequestKind};
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+/// every request of a tree by id, with the directory it lives in and how it
+/// serializes, to compare versions of it
+type Flattened = BTreeMap<String, (Option<String>, serde_json::Value)>;
+
+/// combines the changes made on this session, `ours`, with the ones another
+/// session wrote to the file, `theirs`, both made since `base` was synced.
+///
+/// requests and directories are merged one by one: what only one side
+/// changed is kept, and when both sides changed the same request, ours is
+/// kept. Everything else on the collection, like its environments, is taken
+/// from ours when we changed it and from theirs otherwise
+pub fn merge(
+    base: &Collection,
+    ours: &Collection,
+    theirs: &Collection,
+) -> anyhow::Result<Collection> {
+    let requests_of = |collection: &Collection| {
+        collection
+            .requests
+            .as_ref()
+            .map(|requests| requests.read().unwrap().clone())
+            .unwrap_or_default()
+    };
+    let (base_requests, our_requests) = (requests_of(base), requests_of(ours));
+    let mut merged_requests = deep_clone(&requests_of(theirs));
+
+    let base_dirs = directories(&base_requests);
+    let our_dirs = directories(&our_requests);
+    for dir in our_dirs.values() {
+        let base_dir = base_dirs.get(&dir.id);
+        match find_directory(&merged_requests, &dir.id) {
+            // directories created on this session
+            None if base_dir.is_none() => merged_requests.push(RequestKind::Nested(Directory {
+                id: dir.id.clone(),
+                name: dir.name.clone(),
+                requests: Arc::new(RwLock::new(vec![])),
+                docs: dir.docs.clone(),
+            })),
+            Some(mut merged) => {
+                if base_dir.is_some_and(|base| base.name.ne(&dir.name)) {
+                    merged.name.clone_from(&dir.name);
+                }
+                if base_dir.is_some_and(|base| base.docs.ne(&dir.docs)) {
+                    merged.docs.clone_from(&dir.docs);
+                }
+                set_directory(&mut merged_requests, merged);
+            }
+            // deleted by the other session
+            None => {}
+        }
+    }
+
+    let base_flat = flatten(&base_requests)?;
+    let their_flat = flatten(&merged_requests)?;
+    let our_flat = flatten(&our_requests)?;
+
+    for (id, (parent, value)) in our_flat.iter() {
+        if base_flat
+            .get(id)
+            .is_some_and(|base| base.0.eq(parent) && base.1.eq(value))
+        {
+            continue;
+        }
+        let request: Request = serde_json::from_value(value.clone())?;
+        match their_flat.get(id).is_some_and(|theirs| theirs.0.eq(parent)) {
+            true => replace(&merged_requests, &request),
+            false => {
+                remove(&mut merged_requests, id);
+                let request = RequestKind::Single(Arc::new(RwLock::new(request)));
+                insert(&mut merged_requests, parent.as_deref(), request);
+            }
+        }
+    }
+
+    // requests deleted on this session are only deleted when the other
+    // session didn't change them
+    for (id, base) in base_flat.iter() {
+        if !our_flat.contains_key(id) && their_flat.get(id).is_some_and(|theirs| theirs.eq(base)) {
+            remove(&mut merged_requests, id);
+        }
+    }
+    // and so are directories, when nothing is left inside of them
+    merged_requests.retain(|item| match item {
+        RequestKind::Nested(dir) => {
+            our_dirs.contains_key(&dir.id)
+                || !base_dirs.contains_key(&dir.id)
+                || !dir.requests.read().unwrap().is_empty()
+        }
+        RequestKind::Single(_) => true,
+    });
+
+    let mut merged = merge_fields(base, ours, theirs)?;
+    merged.requests = Some(Arc::new(RwLock::new(merged_requests)));
+    merged.path = ours.path.clone();
+    merged.unlocked = ours.unlocked;
+    merged.synced = ours.synced.clone();
+    Ok(merged)
+}
+
+/// the fields of the collection besides its requests, from ours when they
+/// changed since base and from theirs otherwise
+fn merge_fields(
+    base: &Collection,
+    ours: &Collection,
+    theirs: &Collection,
+) -> anyhow::Result<Collection> {
+    let fields = |collection: &Collection| {
+        serde_json::to_value(Collection {
+            requests: None,
+            ..collection.clone()
+        })
+    };
+    let merged = merge_value(&fields(base)?, &fields(ours)?, fields(theirs)?);
+    Ok(serde_json::from_value(merged)?)
+}
+
+/// objects are merged key by key, so both sides can change different fields
+/// of the same object, any other value is taken from ours when it changed
+fn merge_value(
+    base: &serde_json::Value,
+    ours: &serde_json::Value,
+    theirs: serde_json::Value,
+) -> serde_json::Value {
+    use serde_json::Value;
+
+    let (Value::Object(base), Value::Object(ours), Value::Object(mut merged)) =
+        (base, ours, theirs.clone())
+    else {
+        return match ours.eq(base) {
+            true => theirs,
+            false => ours.clone(),
+        };
+    };
+
+    for key in ours.keys().chain(base.keys()) {
+        match (base.get(key), ours.get(key)) {
+            (Some(base), Some(ours)) => match merged.remove(key) {
+                Some(theirs) => _ = merged.insert(key.clone(), merge_value(base, ours, theirs)),
+                // removed by them, unless we changed it
+                None if ours.ne(base) => _ = merged.insert(key.clone(), ours.clone()),
+                None => {}
+            },
+            (None, Some(ours)) => _ = merged.insert(key.clone(), ours.clone()),
+            (Some(_), None) => _ = merged.remove(key),
+            (None, None) => {}
+        }
+    }
+    Value::Object(merged)
+}
+
+fn flatten(requests: &[RequestKind]) -> anyhow::Result<Flattened> {
+    let mut flattened = Flattened::new();
+    flatten_into(requests, None, &mut flattened)?;
+    Ok(flattened)
+}
+
+fn flatten_into(
+    requests: &[RequestKind],
+    parent: Option<&str>,
+    flattened: &mut Flattened,
+) -> anyhow::Result<()> {
+    for item in requests {
+        match item {
+            RequestKind::Single(req) => {
+                let req = req.read().unwrap();
+                let value = serde_json::to_value(&*req)?;
+                flattened.insert(req.id.clone(), (parent.map(String::from), value));
+            }
+            RequestKind::Nested(dir) => {
+                flatten_into(&dir.requests.read().unwrap(), Some(&dir.id), flattened)?
+            }
+        }
+    }
+    Ok(())
+}
+
+fn directories(requests: &[RequestKind]) -> BTreeMap<String, Directory> {
+    requests
+        .iter()
+        .filter_map(|item| match item {
+            RequestKind::Nested(dir) => Some((dir.id.clone(), dir.clone())),
+            RequestKind::Single(_) => None,
+        })
+        .collect()
+}
+
+fn find_directory(requests: &[RequestKind], id: &str) -> Option<Directory> {
+    directories(requests).remove(id)
+}
+
+fn set_directory(requests: &mut [RequestKind], directory: Directory) {
+    for item in requests.iter_mut() {
+        if let RequestKind::Nested(dir) = item {
+            if dir.id.eq(&directory.id) {
+                *dir = directory;
+                return;
+            }
+        }
+    }
+}
+
+fn replace(requests: &[RequestKind], request: &Request) {
+    for item in requests {
+        match item {
+            RequestKind::Single(req) if req.read().unwrap().id.eq(&request.id) => {
+                *req.write().unwrap() = request.clone();
+            }
+            RequestKind::Single(_) => {}
+            RequestKind::Nested(dir) => replace(&dir.requests.read().unwrap(), request),
+        }
+    }
+}
+
+fn remove(requests: &mut Vec<RequestKind>, id: &str) {
+    requests.retain(|item| item.get_id().ne(id) || item.is_dir());
+    for item in requests.iter() {
+        if let RequestKind::Nested(dir) = item {
+            remove(&mut dir.requests.write().unwrap(), id);
+        }
+    }
+}
+
+/// adds the request inside of the directory, or at the root of the
+/// collection when there is no such directory
+fn insert(requests: &mut Vec<RequestKind>, parent: Option<&str>, request: RequestKind) {
+    let dir = parent.and_then(|parent| {
+        requests.iter().find_map(|item| match item {
+            RequestKind::Nested(dir) if dir.id.eq(parent) => Some(dir.requests.clone()),
+            _ => None,
+        })
+    });
+    match dir {
+        Some(dir) => dir.write().unwrap().push(request),
+        None => requests.push(request),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::dependencies::find_request;
+
+    fn collection(requests: &str, description: &str) -> Collection {
+        serde_json::from_str(&format!(
+            r#"{{ "info": {{ "name": "api", "description": "{description}" }}, "requests": {requests} }}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_merge_collections() {
+        let base = collection(
+            r#"[
+                { "id": "users", "name": "users", "requests": [
+                    { "id": "list", "method": "GET", "name": "list", "uri": "/users" }
+                ] },
+                { "id": "health", "method": "GET", "name": "health", "uri": "/health" },
+                { "id": "old", "method": "GET", "name": "old", "uri": "/old" }
+            ]"#,
+            "base",
+        );
+        // we changed the uri of list, deleted old and created a directory
+        let ours = collection(
+            r#"[
+                { "id": "users", "name": "users", "requests": [
+                    { "id": "list", "method": "GET", "name": "list", "uri": "/people" }
+                ] },
+                { "id": "health", "method": "GET", "name": "health", "uri": "/health" },
+                { "id": "orders", "name": "orders", "requests": [
+                    { "id": "create", "method": "POST", "name": "create", "uri": "/orders" }
+                ] }
+            ]"#,
+            "base",
+        );
+        // they moved health into users, created a request and changed the
+        // description
+        let theirs = collection(
+            r#"[
+                { "id": "users", "name": "users", "requests": [
+                    { "id": "list", "method": "GET", "name": "list", "uri": "/users" },
+                    { "id": "health", "method": "GET", "name": "health", "uri": "/health" }
+                ] },
+                { "id": "old", "method": "GET", "name": "old", "uri": "/old" },
+                { "id": "me", "method": "GET", "name": "me", "uri": "/me" }
+            ]"#,
+            "theirs",
+        );
+
+        let merged = merge(&base, &ours, &theirs).unwrap();
+        let requests = merged.requests.as_ref().unwrap().read().unwrap();
+        let ids = requests.iter().map(RequestKind::get_id).collect::<Vec<_>>();
+        assert_eq!(ids, vec!["users", "me", "orders"]);
+
+        let RequestKind::Nested(users) = &requests[0] else {
+            panic!("users should still be a directory");
+        };
+        let users = users.requests.read().unwrap();
+        assert_eq!(
+            users.iter().map(RequestKind::get_id).collect::<Vec<_>>(),
+            vec!["list", "health"]
+        );
+        let list = find_request(&requests, "list").unwrap();
+        assert_eq!(list.read().unwrap().uri, "/people");
+        assert!(find_request(&requests, "create").is_some());
+        assert_eq!(merged.info.description.as_deref(), Some("theirs"));
+    }
+}
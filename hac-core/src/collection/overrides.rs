@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+
+use crate::collection::types::Request;
+use crate::digest::set_header;
+
+/// temporary changes to a request that only apply to a single send, they are
+/// never written back to the collection
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RequestOverrides {
+    /// headers replacing the ones with the same name, or added when the
+    /// request doesn't have them
+    pub headers: Vec<(String, String)>,
+    /// variables replacing the ones of the active environment
+    pub variables: BTreeMap<String, String>,
+    pub body: Option<String>,
+}
+
+impl RequestOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty() && self.variables.is_empty() && self.body.is_none()
+    }
+
+    /// adds a single override written as `header Name: value`,
+    /// `var name=value` or `body contents`, where `\n` on a body is a line
+    /// break
+    pub fn push(&mut self, line: &str) -> anyhow::Result<()> {
+        let line = line.trim();
+        let (kind, rest) = line.split_once(' ').unwrap_or((line, ""));
+
+        match kind {
+            "header" => {
+                let Some((name, value)) = rest.split_once(':') else {
+                    anyhow::bail!("headers are written as `header Name: value`");
+                };
+                let name = name.trim();
+                if name.is_empty() {
+                    anyhow::bail!("header name cannot be empty");
+                }
+                self.headers
+                    .retain(|(existing, _)| !existing.eq_ignore_ascii_case(name));
+                self.headers
+                    .push((name.to_string(), value.trim().to_string()));
+            }
+            "var" => {
+                let Some((name, value)) = rest.split_once('=') else {
+                    anyhow::bail!("variables are written as `var name=value`");
+                };
+                let name = name.trim();
+                if name.is_empty() {
+                    anyhow::bail!("variable name cannot be empty");
+                }
+                self.variables
+                    .insert(name.to_string(), value.trim().to_string());
+            }
+            "body" => self.body = Some(rest.replace("\\n", "\n")),
+            _ => anyhow::bail!("unknown override {:?}, use header, var or body", kind),
+        }
+
+        Ok(())
+    }
+
+    /// a copy of the request with the overridden headers and body, the
+    /// variables are not substituted here as they are merged into the
+    /// environment before resolving the request
+    pub fn apply(&self, request: &Request) -> Request {
+        let mut overridden = request.clone();
+
+        for (name, value) in self.headers.iter() {
+            set_header(&mut overridden, name, value.clone());
+        }
+
+        if let Some(body) = self.body.as_ref() {
+            overridden.body = Some(body.clone());
+        }
+
+        overridden
+    }
+
+    /// the variables of the environment with the overridden ones on top
+    pub fn merge_variables(&self, variables: &mut BTreeMap<String, String>) {
+        variables.extend(self.variables.clone());
+    }
+
+    /// a short description of what was overridden, like `1 header, body`
+    pub fn summary(&self) -> String {
+        let plural = |count: usize, word: &str| match count {
+            1 => format!("1 {word}"),
+            _ => format!("{count} {word}s"),
+        };
+
+        let mut parts = vec![];
+        if !self.headers.is_empty() {
+            parts.push(plural(self.headers.len(), "header"));
+        }
+        if !self.variables.is_empty() {
+            parts.push(plural(self.variables.len(), "variable"));
+        }
+        if self.body.is_some() {
+            parts.push("body".to_string());
+        }
+        parts.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::types::{HeaderMap, RequestMethod};
+
+    #[test]
+    fn test_request_overrides() {
+        let request = Request {
+            id: "id".into(),
+            method: RequestMethod::Post,
+            name: "create user".into(),
+            uri: "{{host}}/users".into(),
+            headers: Some(vec![HeaderMap {
+                pair: ("Authorization".into(), "Bearer {{token}}".into()),
+                enabled: false,
+            }]),
+            body: Some("{}".into()),
+            ..Default::default()
+        };
+
+        let mut overrides = RequestOverrides::default();
+        assert!(overrides.is_empty());
+        overrides
+            .push("header authorization: Bearer other")
+            .unwrap();
+        overrides.push("header X-Debug: 1").unwrap();
+        overrides.push("var host = http://localhost").unwrap();
+        overrides.push("body {\\n  \"name\": \"john\"\\n}").unwrap();
+        assert!(overrides.push("header no colon").is_err());
+        assert!(overrides.push("cookie a=b").is_err());
+        assert_eq!(overrides.summary(), "2 headers, 1 variable, body");
+
+        let overridden = overrides.apply(&request);
+        let headers = overridden.headers.unwrap();
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers[0].pair.1, "Bearer other");
+        assert!(headers[0].enabled);
+        assert_eq!(
+            overridden.body.as_deref(),
+            Some("{\n  \"name\": \"john\"\n}")
+        );
+
+        let mut variables = BTreeMap::from([("host".to_string(), "https://api".to_string())]);
+        overrides.merge_variables(&mut variables);
+        assert_eq!(variables["host"], "http://localhost");
+
+        // the original request is never touched
+        assert_eq!(request.body.as_deref(), Some("{}"));
+    }
+}
@@ -0,0 +1,171 @@
+use crate::collection::types::{for_each_request, Request, RequestKind};
+
+use std::sync::{Arc, RwLock};
+
+use regex::Regex;
+
+/// how the names, and optionally the uris, of many requests are rewritten
+/// at once. The regex replacement runs first, then the prefix and suffix
+/// are added to the names
+#[derive(Debug, Clone, Default)]
+pub struct RenamePattern {
+    pub find: Option<Regex>,
+    /// replaces every match of `find`, supporting `$1` and `${name}` groups
+    pub replace: String,
+    pub prefix: String,
+    pub suffix: String,
+    /// whether `find` also rewrites the uris, prefix and suffix never touch
+    /// uris as they would hardly ever make sense there
+    pub include_uris: bool,
+}
+
+/// a request whose name or uri changes, along with the values it has before
+/// and after the rename
+#[derive(Debug, Clone)]
+pub struct RenameChange {
+    pub request: Arc<RwLock<Request>>,
+    pub name: (String, String),
+    pub uri: (String, String),
+}
+
+impl RenameChange {
+    pub fn renames(&self) -> bool {
+        self.name.0.ne(&self.name.1)
+    }
+
+    pub fn moves(&self) -> bool {
+        self.uri.0.ne(&self.uri.1)
+    }
+}
+
+impl RenamePattern {
+    /// builds the pattern, failing when `find` is not a valid regex
+    pub fn new(
+        find: &str,
+        replace: &str,
+        prefix: &str,
+        suffix: &str,
+        include_uris: bool,
+    ) -> anyhow::Result<RenamePattern> {
+        let find = match find.is_empty() {
+            true => None,
+            false => Some(Regex::new(find)?),
+        };
+        Ok(RenamePattern {
+            find,
+            replace: replace.into(),
+            prefix: prefix.into(),
+            suffix: suffix.into(),
+            include_uris,
+        })
+    }
+
+    pub fn rename(&self, name: &str) -> String {
+        let replaced = match self.find.as_ref() {
+            Some(find) => find.replace_all(name, self.replace.as_str()).to_string(),
+            None => name.to_string(),
+        };
+        format!("{}{}{}", self.prefix, replaced, self.suffix)
+    }
+
+    pub fn rewrite_uri(&self, uri: &str) -> String {
+        match (self.include_uris, self.find.as_ref()) {
+            (true, Some(find)) => find.replace_all(uri, self.replace.as_str()).to_string(),
+            _ => uri.to_string(),
+        }
+    }
+}
+
+/// every request under `requests`, nested ones included, that the pattern
+/// would change, in the order they appear on the sidebar
+pub fn preview(requests: &[RequestKind], pattern: &RenamePattern) -> Vec<RenameChange> {
+    let mut changes = vec![];
+    for_each_request(requests, &mut |request| {
+        let (name, uri) = {
+            let request = request.read().unwrap();
+            (request.name.clone(), request.uri.clone())
+        };
+        let change = RenameChange {
+            request: request.clone(),
+            name: (name.clone(), pattern.rename(&name)),
+            uri: (uri.clone(), pattern.rewrite_uri(&uri)),
+        };
+        if change.renames() || change.moves() {
+            changes.push(change);
+        }
+    });
+    changes
+}
+
+/// writes the new names and uris to the requests
+pub fn apply(changes: &[RenameChange]) {
+    for change in changes {
+        let mut request = change.request.write().unwrap();
+        request.name.clone_from(&change.name.1);
+        request.uri.clone_from(&change.uri.1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::fixtures::{request, single};
+    use crate::collection::types::Directory;
+
+    fn make_requests() -> Vec<RequestKind> {
+        vec![
+            single(request("get user", "{{base_url}}/v1/users/1")),
+            RequestKind::Nested(Directory {
+                id: "dir".into(),
+                name: "orders".into(),
+                requests: Arc::new(RwLock::new(vec![
+                    single(request("get order", "{{base_url}}/v1/orders/1")),
+                    single(request("health", "{{base_url}}/health")),
+                ])),
+                ..Default::default()
+            }),
+        ]
+    }
+
+    #[test]
+    fn test_preview_and_apply() {
+        let requests = make_requests();
+        let pattern = RenamePattern::new(r"^get (\w+)", "fetch $1", "", "", false).unwrap();
+
+        let changes = preview(&requests, &pattern);
+        let renamed = changes
+            .iter()
+            .map(|change| change.name.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            renamed,
+            vec![
+                ("get user".into(), "fetch user".into()),
+                ("get order".into(), "fetch order".into()),
+            ]
+        );
+        assert!(changes.iter().all(|change| !change.moves()));
+
+        apply(&changes);
+        let RequestKind::Single(first) = &requests[0] else {
+            unreachable!();
+        };
+        assert_eq!(first.read().unwrap().name, "fetch user");
+        assert!(preview(&requests, &pattern).is_empty());
+    }
+
+    #[test]
+    fn test_affixes_and_uris() {
+        let requests = make_requests();
+        let pattern = RenamePattern::new("/v1/", "/v2/", "[v2] ", "", true).unwrap();
+
+        let changes = preview(&requests, &pattern);
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[0].name.1, "[v2] get user");
+        assert_eq!(changes[0].uri.1, "{{base_url}}/v2/users/1");
+        assert_eq!(changes[2].name.1, "[v2] health");
+        assert!(!changes[2].moves());
+
+        assert!(RenamePattern::new("(unclosed", "", "", "", false).is_err());
+    }
+}
@@ -0,0 +1,285 @@
+use crate::collection::diagnostics::{self, DiagnosticKind, Location};
+use crate::collection::types::{Request, RequestKind, ScenarioStep};
+use crate::collection::Collection;
+use crate::net::latency_history::LatencyHistory;
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// how the requests at the top of the collection are listed
+const ROOT_FOLDER: &str = "(root)";
+
+/// the last time a request was sent, as far as the latency history knows
+#[derive(Debug, Clone, PartialEq)]
+pub struct LastRun {
+    pub status: Option<u16>,
+    pub duration_ms: u64,
+}
+
+/// a summary of what needs attention on a collection
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollectionReport {
+    pub name: String,
+    pub total: usize,
+    /// how many requests each folder holds, by the path of the folder
+    pub folders: Vec<(String, usize)>,
+    /// requests without documentation, by their path
+    pub undocumented: Vec<String>,
+    /// requests whose responses nothing checks, neither a monitor nor a
+    /// message awaited by their scenario
+    pub unchecked: Vec<String>,
+    /// variables defined on an environment that no request uses, as
+    /// `environment: variable`
+    pub unused_variables: Vec<String>,
+    /// names used by more than one request, with how many use it
+    pub duplicate_names: Vec<(String, usize)>,
+    pub last_runs: Vec<(String, Option<LastRun>)>,
+}
+
+impl CollectionReport {
+    /// whether anything on the collection needs attention
+    pub fn is_healthy(&self) -> bool {
+        self.undocumented.is_empty()
+            && self.unchecked.is_empty()
+            && self.unused_variables.is_empty()
+            && self.duplicate_names.is_empty()
+            && self
+                .last_runs
+                .iter()
+                .all(|(_, run)| !run.as_ref().is_some_and(LastRun::failed))
+    }
+}
+
+impl LastRun {
+    fn failed(&self) -> bool {
+        self.status.is_some_and(|status| status >= 400)
+    }
+}
+
+/// reports on every request of the collection, variables are considered
+/// unused the same way `lint` does
+pub fn build(
+    collection: &Collection,
+    environment: Option<&str>,
+    history: &LatencyHistory,
+) -> CollectionReport {
+    let mut requests = vec![];
+    if let Some(items) = collection.requests.as_ref() {
+        collect(&items.read().unwrap(), ROOT_FOLDER, &mut requests);
+    }
+
+    let mut folders: BTreeMap<String, usize> = BTreeMap::new();
+    let mut names: BTreeMap<String, usize> = BTreeMap::new();
+    let mut report = CollectionReport {
+        name: collection.info.name.clone(),
+        total: requests.len(),
+        folders: vec![],
+        undocumented: vec![],
+        unchecked: vec![],
+        unused_variables: vec![],
+        duplicate_names: vec![],
+        last_runs: vec![],
+    };
+
+    for (folder, request) in requests.iter() {
+        *folders.entry(folder.clone()).or_default() += 1;
+        *names.entry(request.name.clone()).or_default() += 1;
+
+        let path = match folder.eq(ROOT_FOLDER) {
+            true => request.name.clone(),
+            false => format!("{folder} > {}", request.name),
+        };
+        if request
+            .docs
+            .as_deref()
+            .is_none_or(|docs| docs.trim().is_empty())
+        {
+            report.undocumented.push(path.clone());
+        }
+        let awaits_messages = request
+            .scenario
+            .iter()
+            .any(|step| matches!(step, ScenarioStep::Expect(_)));
+        if request.monitor.is_none() && !awaits_messages {
+            report.unchecked.push(path.clone());
+        }
+
+        let last_run = history
+            .last_runs(&request.id, 1)
+            .first()
+            .map(|duration_ms| LastRun {
+                status: history.last_status(&request.id),
+                duration_ms: *duration_ms,
+            });
+        report.last_runs.push((path, last_run));
+    }
+
+    report.folders = folders.into_iter().collect();
+    report.duplicate_names = names.into_iter().filter(|(_, count)| *count > 1).collect();
+    // local variables only make undefined ones defined, which the report
+    // doesn't look at
+    report.unused_variables = diagnostics::analyze(collection, environment, &BTreeMap::default())
+        .into_iter()
+        .filter(|diagnostic| diagnostic.kind.eq(&DiagnosticKind::UnusedVariable))
+        .map(|diagnostic| match diagnostic.location {
+            Location::Environment(env) => format!("{env}: {}", diagnostic.variable),
+            _ => diagnostic.variable,
+        })
+        .collect();
+
+    report
+}
+
+fn collect(items: &[RequestKind], folder: &str, requests: &mut Vec<(String, Request)>) {
+    for item in items {
+        match item {
+            RequestKind::Single(request) => {
+                requests.push((folder.to_string(), request.read().unwrap().clone()))
+            }
+            RequestKind::Nested(dir) => {
+                let path = match folder.eq(ROOT_FOLDER) {
+                    true => dir.name.clone(),
+                    false => format!("{folder}/{}", dir.name),
+                };
+                collect(&dir.requests.read().unwrap(), &path, requests);
+            }
+        }
+    }
+}
+
+/// the report as plain text, with a section for each kind of problem
+pub fn render(report: &CollectionReport) -> String {
+    let mut out = String::default();
+    _ = writeln!(out, "collection: {}", report.name);
+    _ = writeln!(out, "requests: {}", report.total);
+
+    let width = report
+        .folders
+        .iter()
+        .map(|(folder, _)| folder.chars().count())
+        .max()
+        .unwrap_or_default();
+    _ = writeln!(out, "\nrequests per folder");
+    for (folder, count) in report.folders.iter() {
+        _ = writeln!(out, "  {folder:<width$}  {count}");
+    }
+
+    let sections = [
+        ("without docs", &report.undocumented),
+        ("without checks", &report.unchecked),
+        ("unused variables", &report.unused_variables),
+    ];
+    for (title, items) in sections {
+        _ = writeln!(out, "\n{title} ({})", items.len());
+        items.iter().for_each(|item| _ = writeln!(out, "  {item}"));
+    }
+
+    _ = writeln!(out, "\nduplicate names ({})", report.duplicate_names.len());
+    for (name, count) in report.duplicate_names.iter() {
+        _ = writeln!(out, "  {name:?} used by {count} requests");
+    }
+
+    _ = writeln!(out, "\nlast runs");
+    for (path, run) in report.last_runs.iter() {
+        let run = match run {
+            Some(LastRun {
+                status: Some(status),
+                duration_ms,
+            }) => format!("{status} {duration_ms:>6}ms"),
+            Some(LastRun { duration_ms, .. }) => format!("--- {duration_ms:>6}ms"),
+            None => "never sent  ".into(),
+        };
+        _ = writeln!(out, "  {run}  {path}");
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::fixtures::{self, single};
+    use crate::collection::types::{Directory, Environment, Info, Monitor};
+    use std::sync::{Arc, RwLock};
+    use std::time::Duration;
+
+    fn make_collection() -> Collection {
+        Collection {
+            info: Info {
+                name: "Users API".into(),
+                description: None,
+                read_only: false,
+            },
+            requests: Some(Arc::new(RwLock::new(vec![
+                single(Request {
+                    docs: Some("pings".into()),
+                    monitor: Some(Monitor {
+                        schedule: "30s".into(),
+                        expect_status: Some(200),
+                        max_latency_ms: None,
+                    }),
+                    ..fixtures::request("health", "{{base_url}}/health")
+                }),
+                RequestKind::Nested(Directory {
+                    id: "dir".into(),
+                    name: "users".into(),
+                    requests: Arc::new(RwLock::new(vec![
+                        single(fixtures::request("list", "{{base_url}}/users")),
+                        single(Request {
+                            id: "other".into(),
+                            docs: Some(" ".into()),
+                            ..fixtures::request("list", "{{base_url}}/users?page=2")
+                        }),
+                    ])),
+                    docs: None,
+                }),
+            ]))),
+            path: "users.json".into(),
+            unlocked: false,
+            protos: vec![],
+            highlights: vec![],
+            template: None,
+            format_on_save: false,
+            synced: Default::default(),
+            environments: vec![Environment {
+                name: "local".into(),
+                variables: BTreeMap::from([
+                    ("base_url".into(), "http://localhost".into()),
+                    ("old_token".into(), "abc".into()),
+                ]),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_build() {
+        let mut history = LatencyHistory::default();
+        history.record("list", Duration::from_millis(120));
+        history.record_status("list", 500);
+
+        let report = build(&make_collection(), None, &history);
+        assert_eq!(report.total, 3);
+        assert_eq!(
+            report.folders,
+            vec![("(root)".into(), 1), ("users".into(), 2)]
+        );
+        assert_eq!(report.undocumented, vec!["users > list", "users > list"]);
+        assert_eq!(report.unchecked, vec!["users > list", "users > list"]);
+        assert_eq!(report.unused_variables, vec!["local: old_token"]);
+        assert_eq!(report.duplicate_names, vec![("list".into(), 2)]);
+        assert_eq!(report.last_runs[0], ("health".into(), None));
+        assert_eq!(
+            report.last_runs[1].1,
+            Some(LastRun {
+                status: Some(500),
+                duration_ms: 120,
+            })
+        );
+        assert!(!report.is_healthy());
+
+        let rendered = render(&report);
+        assert!(rendered.contains("  (root)  1\n  users   2\n"));
+        assert!(rendered.contains("  500    120ms  users > list\n"));
+        assert!(rendered.contains("  never sent    health\n"));
+    }
+}
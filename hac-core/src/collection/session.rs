@@ -0,0 +1,129 @@
+use std::collections::BTreeMap;
+
+use crate::collection::types::Capture;
+use crate::net::request_manager::Response;
+
+/// variables set while hac runs, like values captured from responses. They
+/// take precedence over the environment and are never written to disk, so
+/// transient tokens and ids don't end up on files tracked by git
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Session {
+    variables: BTreeMap<String, String>,
+}
+
+impl Session {
+    pub fn get(&self, name: &str) -> Option<&String> {
+        self.variables.get(name)
+    }
+
+    pub fn extend(&mut self, variables: BTreeMap<String, String>) {
+        self.variables.extend(variables);
+    }
+
+    pub fn clear(&mut self) {
+        self.variables.clear();
+    }
+
+    pub fn variables(&self) -> &BTreeMap<String, String> {
+        &self.variables
+    }
+
+    /// the variables of the environment with the session ones on top
+    pub fn layered(&self, environment: BTreeMap<String, String>) -> BTreeMap<String, String> {
+        let mut variables = environment;
+        variables.extend(self.variables.clone());
+        variables
+    }
+}
+
+/// takes the values the captures point to out of the response, captures
+/// that point to nothing are left out
+pub fn capture(captures: &[Capture], response: &Response) -> BTreeMap<String, String> {
+    if captures.is_empty() {
+        return BTreeMap::default();
+    }
+
+    let body = response
+        .body
+        .as_deref()
+        .and_then(|body| serde_json::from_str::<serde_json::Value>(body).ok());
+
+    captures
+        .iter()
+        .filter_map(|capture| {
+            let value = match (capture.header.as_deref(), capture.pointer.as_deref()) {
+                (Some(header), _) => response
+                    .headers
+                    .as_ref()
+                    .and_then(|headers| headers.get(header))
+                    .map(|value| String::from_utf8_lossy(value.as_bytes()).to_string()),
+                (None, Some(pointer)) => {
+                    body.as_ref()
+                        .and_then(|body| body.pointer(pointer))
+                        .map(|value| match value {
+                            serde_json::Value::String(value) => value.clone(),
+                            value => value.to_string(),
+                        })
+                }
+                (None, None) => None,
+            };
+            value.map(|value| (capture.variable.clone(), value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::types::ExampleResponse;
+
+    #[test]
+    fn test_capture() {
+        let response = Response::from_example(&ExampleResponse {
+            name: "login".into(),
+            status: 200,
+            headers: vec![("x-request-id".into(), "abc".into())],
+            body: Some(r#"{"data":{"token":"t0k3n","user":{"id":42}}}"#.into()),
+        });
+        let captures = [
+            Capture {
+                variable: "token".into(),
+                pointer: Some("/data/token".into()),
+                header: None,
+            },
+            Capture {
+                variable: "user_id".into(),
+                pointer: Some("/data/user/id".into()),
+                header: None,
+            },
+            Capture {
+                variable: "request_id".into(),
+                pointer: None,
+                header: Some("X-Request-Id".into()),
+            },
+            Capture {
+                variable: "missing".into(),
+                pointer: Some("/data/missing".into()),
+                header: None,
+            },
+        ];
+
+        let mut session = Session::default();
+        session.extend(capture(&captures, &response));
+        assert_eq!(session.get("token").map(String::as_str), Some("t0k3n"));
+        assert_eq!(session.get("user_id").map(String::as_str), Some("42"));
+        assert_eq!(session.get("request_id").map(String::as_str), Some("abc"));
+        assert!(session.get("missing").is_none());
+
+        let environment = BTreeMap::from([
+            ("token".to_string(), "stale".to_string()),
+            ("host".to_string(), "localhost".to_string()),
+        ]);
+        let variables = session.layered(environment);
+        assert_eq!(variables["token"], "t0k3n");
+        assert_eq!(variables["host"], "localhost");
+
+        session.clear();
+        assert!(session.variables().is_empty());
+    }
+}
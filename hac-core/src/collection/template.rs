@@ -0,0 +1,144 @@
+use crate::collection::types::{
+    AuthConfig, AuthMethod, BodyType, Collection, HeaderMap, Request, RequestMethod,
+};
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// what every new request starts with instead of being completely blank,
+/// set on a collection or on `.hac/template.json` for every collection of a
+/// workspace
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RequestTemplate {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<HeaderMap>,
+    /// the auth new requests inherit, along with its parameters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_method: Option<AuthMethod>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<AuthConfig>,
+    /// the content type of new requests with a body, json when not set
+    #[serde(default, rename = "bodyType", skip_serializing_if = "Option::is_none")]
+    pub body_type: Option<BodyType>,
+    /// the body new requests start with, for each content type
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub bodies: BTreeMap<BodyType, String>,
+    #[serde(default, rename = "timeoutMs", skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+impl RequestTemplate {
+    /// a template that creates requests like the given one, without its name
+    /// or uri
+    pub fn from_request(request: &Request) -> RequestTemplate {
+        let mut bodies = BTreeMap::new();
+        if let Some(body) = request.body.as_ref().filter(|body| !body.trim().is_empty()) {
+            let body_type = request.body_type.clone().unwrap_or(BodyType::Json);
+            bodies.insert(body_type, body.clone());
+        }
+        RequestTemplate {
+            headers: request.headers.clone().unwrap_or_default(),
+            auth_method: request.auth_method.clone(),
+            auth: request.auth.clone(),
+            body_type: request.body_type.clone(),
+            bodies,
+            timeout_ms: request.timeout_ms,
+        }
+    }
+
+    /// fills what the request doesn't have yet, bodies are only given to
+    /// methods that send one
+    pub fn apply(&self, request: &mut Request) {
+        if request.headers.is_none() && !self.headers.is_empty() {
+            request.headers = Some(self.headers.clone());
+        }
+        if request.auth_method.is_none() {
+            request.auth_method.clone_from(&self.auth_method);
+            request.auth.clone_from(&self.auth);
+        }
+        request.timeout_ms = request.timeout_ms.or(self.timeout_ms);
+
+        let sends_body = matches!(
+            request.method,
+            RequestMethod::Post | RequestMethod::Put | RequestMethod::Patch
+        );
+        if sends_body && request.body.is_none() {
+            let body_type = self.body_type.clone().unwrap_or(BodyType::Json);
+            if let Some(body) = self.bodies.get(&body_type) {
+                request.body = Some(body.clone());
+                request.body_type = Some(body_type);
+            }
+        }
+    }
+}
+
+/// where the template shared by every collection of the current workspace
+/// lives, if there is a workspace
+pub fn workspace_template_path() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok()?;
+    hac_config::find_workspace_dir(&cwd).map(|dir| dir.join(hac_config::TEMPLATE_FILE))
+}
+
+pub fn load(path: &Path) -> anyhow::Result<RequestTemplate> {
+    let contents = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+/// the template new requests of the collection start with, the one of the
+/// collection takes precedence over the one of the workspace
+pub fn for_collection(collection: &Collection) -> Option<RequestTemplate> {
+    if let Some(template) = collection.template.as_ref() {
+        return Some(template.clone());
+    }
+    let path = workspace_template_path().filter(|path| path.is_file())?;
+    match load(&path) {
+        Ok(template) => Some(template),
+        Err(e) => {
+            tracing::warn!("failed to load request template {path:?}: {e}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::fixtures;
+
+    #[test]
+    fn test_apply_template() {
+        let template = serde_json::from_str::<RequestTemplate>(
+            r#"{
+                "headers": [{ "pair": ["Accept", "application/json"], "enabled": true }],
+                "auth_method": "Bearer",
+                "bodyType": "xml",
+                "bodies": { "json": "{}", "xml": "<request/>" },
+                "timeoutMs": 5000
+            }"#,
+        )
+        .unwrap();
+
+        let mut post = Request {
+            method: RequestMethod::Post,
+            timeout_ms: Some(100),
+            ..fixtures::request("new", "{{base_url}}/users")
+        };
+        template.apply(&mut post);
+        assert_eq!(post.headers.as_ref().unwrap()[0].pair.0, "Accept");
+        assert_eq!(post.auth_method, Some(AuthMethod::Bearer));
+        assert_eq!(post.body.as_deref(), Some("<request/>"));
+        assert_eq!(post.body_type, Some(BodyType::Xml));
+        assert_eq!(post.timeout_ms, Some(100));
+
+        let mut get = fixtures::request("new", "{{base_url}}/users");
+        template.apply(&mut get);
+        assert!(get.body.is_none());
+        assert_eq!(get.timeout_ms, Some(5000));
+
+        let saved = RequestTemplate::from_request(&post);
+        assert_eq!(saved.bodies.get(&BodyType::Xml).unwrap(), "<request/>");
+        assert_eq!(saved.bodies.len(), 1);
+    }
+}
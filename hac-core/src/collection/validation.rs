@@ -0,0 +1,150 @@
+use crate::collection::diagnostics::RequestField;
+use crate::collection::types::Request;
+
+/// schemes a request can be sent to
+const SUPPORTED_SCHEMES: [&str; 4] = ["http", "https", "ws", "wss"];
+
+/// a problem that would make the request fail before reaching the server
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationIssue {
+    pub field: RequestField,
+    pub message: String,
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.field {
+            RequestField::Uri => write!(f, "uri: {}", self.message),
+            RequestField::Header(idx) => write!(f, "header {}: {}", idx + 1, self.message),
+            RequestField::Body => write!(f, "body: {}", self.message),
+        }
+    }
+}
+
+/// text still holding variables can only be checked once they are resolved
+fn has_variables(text: &str) -> bool {
+    text.contains("{{")
+}
+
+/// why the uri cannot be sent, if anything
+pub fn validate_uri(uri: &str) -> Option<String> {
+    let uri = uri.trim();
+    if uri.is_empty() {
+        return Some("the uri is empty".into());
+    }
+    if has_variables(uri) {
+        return None;
+    }
+    if !uri.contains("://") {
+        return Some("the uri has no scheme, like https://".into());
+    }
+
+    match reqwest::Url::parse(uri) {
+        Ok(url) if !SUPPORTED_SCHEMES.contains(&url.scheme()) => Some(format!(
+            "unsupported scheme {}, use one of {}",
+            url.scheme(),
+            SUPPORTED_SCHEMES.join(", ")
+        )),
+        Ok(url) if !url.has_host() => Some("the uri has no host".into()),
+        Ok(_) => None,
+        Err(e) => Some(e.to_string()),
+    }
+}
+
+/// why the header cannot be sent, if anything
+pub fn validate_header(name: &str, value: &str) -> Option<String> {
+    if name.is_empty() {
+        return Some("the header has no name".into());
+    }
+    if !has_variables(name) && http::HeaderName::from_bytes(name.as_bytes()).is_err() {
+        let invalid = name
+            .chars()
+            .find(|c| http::HeaderName::from_bytes(c.to_string().as_bytes()).is_err())
+            .unwrap_or(' ');
+        return Some(format!("invalid character {invalid:?} on the name"));
+    }
+    if !has_variables(value) && http::HeaderValue::from_bytes(value.as_bytes()).is_err() {
+        return Some("the value cannot hold line breaks or control characters".into());
+    }
+    None
+}
+
+/// parses the timeout of a request as typed on a form, where nothing means
+/// no timeout
+pub fn parse_timeout(timeout: &str) -> Result<Option<u64>, String> {
+    let timeout = timeout.trim();
+    if timeout.is_empty() {
+        return Ok(None);
+    }
+    match timeout.parse::<u64>() {
+        Ok(0) => Err("the timeout must be greater than 0".into()),
+        Ok(timeout) => Ok(Some(timeout)),
+        Err(_) => Err(format!("{timeout} is not a number of milliseconds")),
+    }
+}
+
+/// every issue of the request that would make sending it fail, the request
+/// should have its variables resolved to be fully checked
+pub fn validate_request(request: &Request) -> Vec<ValidationIssue> {
+    let mut issues = vec![];
+    if let Some(message) = validate_uri(&request.uri) {
+        issues.push(ValidationIssue {
+            field: RequestField::Uri,
+            message,
+        });
+    }
+    for (idx, header) in request.headers.iter().flatten().enumerate() {
+        if !header.enabled {
+            continue;
+        }
+        if let Some(message) = validate_header(&header.pair.0, &header.pair.1) {
+            issues.push(ValidationIssue {
+                field: RequestField::Header(idx),
+                message,
+            });
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_uri() {
+        assert_eq!(validate_uri("https://example.com/users?page=1"), None);
+        assert_eq!(validate_uri("wss://example.com/socket"), None);
+        assert_eq!(validate_uri("{{base_url}}/users"), None);
+        assert_eq!(validate_uri(" "), Some("the uri is empty".into()));
+        assert_eq!(
+            validate_uri("example.com"),
+            Some("the uri has no scheme, like https://".into())
+        );
+        assert_eq!(
+            validate_uri("ftp://example.com"),
+            Some("unsupported scheme ftp, use one of http, https, ws, wss".into())
+        );
+        assert!(validate_uri("http://exa mple.com").is_some());
+    }
+
+    #[test]
+    fn test_validate_header() {
+        assert_eq!(validate_header("Content-Type", "application/json"), None);
+        assert_eq!(validate_header("X-{{name}}", "{{value}}"), None);
+        assert_eq!(
+            validate_header("Content Type", "text/plain"),
+            Some("invalid character ' ' on the name".into())
+        );
+        assert!(validate_header("X-Token", "line\nbreak").is_some());
+        assert!(validate_header("", "value").is_some());
+    }
+
+    #[test]
+    fn test_parse_timeout() {
+        assert_eq!(parse_timeout(""), Ok(None));
+        assert_eq!(parse_timeout("1500"), Ok(Some(1500)));
+        assert!(parse_timeout("0").is_err());
+        assert!(parse_timeout("99999999999999999999999").is_err());
+    }
+}
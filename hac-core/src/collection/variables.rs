@@ -0,0 +1,323 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::collection::environment::variable_spans;
+use crate::collection::errors::CollectionError;
+use crate::collection::types::{for_each_request, Collection, Environment};
+use crate::completion::find_variables;
+
+/// parses the contents of a `.env` file. Blank lines and comments are
+/// ignored, an optional `export` prefix is allowed and values can be wrapped
+/// in single or double quotes
+pub fn parse_dotenv(contents: &str) -> anyhow::Result<BTreeMap<String, String>> {
+    let mut variables = BTreeMap::new();
+
+    for (idx, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((name, value)) = line.split_once('=') else {
+            anyhow::bail!("line {} is not a `NAME=value` pair", idx + 1);
+        };
+        let name = name.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            anyhow::bail!("line {} has an invalid variable name", idx + 1);
+        }
+
+        let value = value.trim();
+        let value = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) if value.len() > 1 && value.ends_with(quote) => {
+                let inner = &value[1..value.len() - 1];
+                match quote {
+                    '"' => inner.replace("\\n", "\n").replace("\\\"", "\""),
+                    _ => inner.to_string(),
+                }
+            }
+            // unquoted values can have trailing comments
+            _ => value
+                .split_once(" #")
+                .map(|(value, _)| value.trim_end())
+                .unwrap_or(value)
+                .to_string(),
+        };
+
+        variables.insert(name.to_string(), value);
+    }
+
+    Ok(variables)
+}
+
+/// renders the variables of an environment as a `.env` file, values are
+/// quoted when needed so parsing them back gives the same values
+pub fn to_dotenv(environment: &Environment) -> String {
+    environment
+        .variables
+        .iter()
+        .map(|(name, value)| {
+            let needs_quotes = value.is_empty()
+                || value.contains(|c: char| c.is_whitespace() || matches!(c, '#' | '"' | '\''));
+            match needs_quotes {
+                true => format!(
+                    "{name}=\"{}\"\n",
+                    value.replace('"', "\\\"").replace('\n', "\\n")
+                ),
+                false => format!("{name}={value}\n"),
+            }
+        })
+        .collect()
+}
+
+/// parses a JSON object of variables, numbers and booleans are accepted and
+/// stored as text, but nested objects and arrays are not
+pub fn parse_json_variables(contents: &str) -> anyhow::Result<BTreeMap<String, String>> {
+    let value: serde_json::Value = serde_json::from_str(contents)?;
+    let serde_json::Value::Object(map) = value else {
+        anyhow::bail!("expected a JSON object of variables");
+    };
+
+    map.into_iter()
+        .map(|(name, value)| match value {
+            serde_json::Value::String(value) => Ok((name, value)),
+            serde_json::Value::Number(_) | serde_json::Value::Bool(_) => {
+                Ok((name, value.to_string()))
+            }
+            serde_json::Value::Null => Ok((name, String::default())),
+            _ => anyhow::bail!("variable {:?} must be a string, number or boolean", name),
+        })
+        .collect()
+}
+
+pub fn to_json_variables(environment: &Environment) -> anyhow::Result<String> {
+    Ok(serde_json::to_string_pretty(&environment.variables)?)
+}
+
+/// adds the variables to the environment with the given name, creating it if
+/// it doesn't exist. Existing variables are only replaced when `overwrite`
+/// is set, returns how many variables were written
+pub fn import_variables(
+    collection: &mut Collection,
+    environment: &str,
+    variables: BTreeMap<String, String>,
+    overwrite: bool,
+) -> Result<usize, CollectionError> {
+    collection.ensure_writable()?;
+
+    let idx = match collection
+        .environments
+        .iter()
+        .position(|env| env.name.eq(environment))
+    {
+        Some(idx) => idx,
+        None => {
+            collection.environments.push(Environment {
+                name: environment.to_string(),
+                variables: BTreeMap::default(),
+            });
+            collection.environments.len() - 1
+        }
+    };
+
+    let target = &mut collection.environments[idx].variables;
+    let mut written = 0;
+    for (name, value) in variables {
+        if overwrite || !target.contains_key(&name) {
+            target.insert(name, value);
+            written += 1;
+        }
+    }
+
+    Ok(written)
+}
+
+/// renames a variable on every environment and every `{{variable}}` that
+/// references it on the requests, returns how many places were changed.
+///
+/// fails when `to` is already defined on any environment, as its value would
+/// be overwritten
+pub fn rename_variable(
+    collection: &mut Collection,
+    from: &str,
+    to: &str,
+) -> Result<usize, CollectionError> {
+    collection.ensure_writable()?;
+    let exists = collection
+        .environments
+        .iter()
+        .any(|env| env.variables.contains_key(to));
+    if exists && from.ne(to) {
+        return Err(CollectionError::VariableExists(to.to_string()));
+    }
+
+    let mut changed = 0;
+    for env in collection.environments.iter_mut() {
+        if let Some(value) = env.variables.remove(from) {
+            env.variables.insert(to.to_string(), value);
+            changed += 1;
+        }
+    }
+
+    if let Some(requests) = collection.requests.as_ref() {
+        for_each_request(&requests.read().unwrap(), &mut |request| {
+            let mut request = request.write().unwrap();
+            let mut rename = |text: &mut String| {
+                let renamed = rename_references(text, from, to);
+                if renamed.ne(text) {
+                    *text = renamed;
+                    changed += 1;
+                }
+            };
+
+            rename(&mut request.uri);
+            if let Some(body) = request.body.as_mut() {
+                rename(body);
+            }
+            request.headers.iter_mut().flatten().for_each(|header| {
+                rename(&mut header.pair.0);
+                rename(&mut header.pair.1);
+            });
+        });
+    }
+
+    Ok(changed)
+}
+
+/// variables defined on any environment that no request references
+pub fn unused_variables(collection: &Collection) -> Vec<String> {
+    let mut used = BTreeSet::new();
+    if let Some(requests) = collection.requests.as_ref() {
+        for_each_request(&requests.read().unwrap(), &mut |request| {
+            let request = request.read().unwrap();
+            used.extend(find_variables(&request.uri));
+            if let Some(body) = request.body.as_ref() {
+                used.extend(find_variables(body));
+            }
+            request.headers.iter().flatten().for_each(|header| {
+                used.extend(find_variables(&header.pair.0));
+                used.extend(find_variables(&header.pair.1));
+            });
+        });
+    }
+
+    collection
+        .environments
+        .iter()
+        .flat_map(|env| env.variables.keys())
+        .filter(|name| !used.contains(*name))
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// replaces every `{{from}}` on the text by `{{to}}`, keeping any other
+/// variable untouched
+fn rename_references(text: &str, from: &str, to: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+
+    for span in variable_spans(text)
+        .iter()
+        .filter(|span| span.name.eq(from))
+    {
+        result.push_str(&text[last..span.start]);
+        result.push_str(&format!("{{{{{to}}}}}"));
+        last = span.end;
+    }
+
+    result.push_str(&text[last..]);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::types::{Info, Request, RequestKind};
+    use std::sync::{Arc, RwLock};
+
+    #[test]
+    fn test_dotenv_round_trip() {
+        let contents = r#"
+# staging credentials
+export HOST=https://staging.api # trailing comment
+TOKEN="abc \"123\""
+EMPTY=
+NAME='john doe'
+"#;
+        let variables = parse_dotenv(contents).unwrap();
+        assert_eq!(variables["HOST"], "https://staging.api");
+        assert_eq!(variables["TOKEN"], "abc \"123\"");
+        assert_eq!(variables["EMPTY"], "");
+        assert_eq!(variables["NAME"], "john doe");
+        assert!(parse_dotenv("NOT A PAIR").is_err());
+
+        let env = Environment {
+            name: "staging".into(),
+            variables,
+        };
+        assert_eq!(parse_dotenv(&to_dotenv(&env)).unwrap(), env.variables);
+
+        let json = parse_json_variables(r#"{"port": 8080, "debug": true}"#).unwrap();
+        assert_eq!(json["port"], "8080");
+        assert!(parse_json_variables(r#"{"nested": {}}"#).is_err());
+    }
+
+    #[test]
+    fn test_bulk_operations() {
+        let request = Request {
+            id: "id".into(),
+            name: "me".into(),
+            uri: "{{host}}/users/{{ user_id }}".into(),
+            ..Default::default()
+        };
+        let mut collection = Collection {
+            info: Info {
+                name: "api".into(),
+                description: None,
+                read_only: false,
+            },
+            requests: Some(Arc::new(RwLock::new(vec![RequestKind::Single(Arc::new(
+                RwLock::new(request),
+            ))]))),
+            environments: vec![],
+            path: "api.json".into(),
+            unlocked: false,
+            protos: vec![],
+            highlights: vec![],
+            template: None,
+            format_on_save: false,
+            synced: Default::default(),
+        };
+
+        let variables = parse_dotenv("host=http://localhost\nuser_id=1\nstale=x").unwrap();
+        assert_eq!(
+            import_variables(&mut collection, "dev", variables.clone(), false).unwrap(),
+            3
+        );
+        assert_eq!(
+            import_variables(&mut collection, "dev", variables, false).unwrap(),
+            0
+        );
+        assert_eq!(unused_variables(&collection), vec!["stale"]);
+
+        assert_eq!(
+            rename_variable(&mut collection, "user_id", "id").unwrap(),
+            2
+        );
+        assert!(matches!(
+            rename_variable(&mut collection, "id", "host"),
+            Err(CollectionError::VariableExists(_))
+        ));
+        assert_eq!(
+            collection.environments[0].variables["host"],
+            "http://localhost"
+        );
+        let requests = collection.requests.as_ref().unwrap().read().unwrap();
+        let RequestKind::Single(request) = &requests[0] else {
+            unreachable!();
+        };
+        assert_eq!(request.read().unwrap().uri, "{{host}}/users/{{id}}");
+        assert!(collection.environments[0].variables.contains_key("id"));
+    }
+}
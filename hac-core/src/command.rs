@@ -6,4 +6,24 @@ pub enum Command {
     SelectCollection(Collection),
     Error(String),
     CreateCollection(Collection),
+    /// suspends the interface to open the contents on an external program,
+    /// on the editor when `edit` is set and on the pager otherwise
+    OpenExternal {
+        contents: String,
+        extension: String,
+        edit: bool,
+    },
+    /// suspends the interface to compare both texts on the diff tool
+    DiffExternal {
+        left: String,
+        right: String,
+        extension: String,
+    },
+    /// the external program exited, with what was written when editing
+    ExternalClosed(Result<Option<String>, String>),
+    /// a release newer than the running one exists, with its version
+    UpdateAvailable(String),
+    /// the collection being edited was changed on disk by another session, so
+    /// the changes of this one were not written
+    CollectionConflict,
 }
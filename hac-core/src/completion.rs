@@ -0,0 +1,475 @@
+use crate::collection::types::{for_each_request, Request};
+use crate::collection::Collection;
+use crate::syntax::jsonc::strip_jsonc;
+
+use std::collections::{BTreeSet, HashMap};
+
+/// header names most commonly used on requests, those are always suggested
+/// alongside any header name that is already used on the collection
+pub static KNOWN_HEADER_NAMES: &[&str] = &[
+    "Accept",
+    "Accept-Charset",
+    "Accept-Encoding",
+    "Accept-Language",
+    "Authorization",
+    "Cache-Control",
+    "Connection",
+    "Content-Encoding",
+    "Content-Length",
+    "Content-Type",
+    "Cookie",
+    "Host",
+    "If-Match",
+    "If-Modified-Since",
+    "If-None-Match",
+    "Origin",
+    "Referer",
+    "User-Agent",
+    "X-Request-Id",
+];
+
+static KNOWN_MEDIA_TYPES: &[&str] = &[
+    "application/json",
+    "application/xml",
+    "application/x-www-form-urlencoded",
+    "application/octet-stream",
+    "multipart/form-data",
+    "text/plain",
+    "text/html",
+    "text/csv",
+    "*/*",
+];
+
+static KNOWN_ENCODINGS: &[&str] = &["gzip", "deflate", "br", "identity", "*"];
+
+static KNOWN_CACHE_DIRECTIVES: &[&str] = &["no-cache", "no-store", "max-age=0", "must-revalidate"];
+
+/// known values for a given header name, header names are case insensitive
+pub fn known_header_values(header_name: &str) -> &'static [&'static str] {
+    match header_name.to_ascii_lowercase().as_str() {
+        "accept" | "content-type" => KNOWN_MEDIA_TYPES,
+        "accept-encoding" | "content-encoding" => KNOWN_ENCODINGS,
+        "cache-control" => KNOWN_CACHE_DIRECTIVES,
+        "connection" => &["keep-alive", "close"],
+        "authorization" => &["Bearer ", "Basic "],
+        _ => &[],
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompletionKind {
+    HeaderName,
+    HeaderValue,
+    Variable,
+    JsonKey,
+}
+
+/// where the completion was requested from, each context draws candidates from
+/// a different source
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompletionContext<'a> {
+    HeaderName,
+    HeaderValue { header_name: &'a str },
+    Body,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub label: String,
+    pub kind: CompletionKind,
+    /// higher is better, candidates are returned sorted by this score
+    pub score: i64,
+}
+
+/// result of a completion request, `start` is the column on which the prefix
+/// starts, so the UI knows what to replace when a candidate is accepted
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion {
+    pub start: usize,
+    pub prefix: String,
+    pub candidates: Vec<Candidate>,
+}
+
+/// the completion engine holds every word that can be suggested to the user,
+/// it is built from a collection and should be rebuilt whenever the collection
+/// changes enough for the suggestions to be outdated
+#[derive(Debug, Default, Clone)]
+pub struct CompletionEngine {
+    header_names: BTreeSet<String>,
+    header_values: HashMap<String, BTreeSet<String>>,
+    variables: BTreeSet<String>,
+    json_keys: BTreeSet<String>,
+}
+
+impl CompletionEngine {
+    pub fn from_collection(collection: &Collection) -> Self {
+        let mut engine = CompletionEngine::default();
+
+        if let Some(requests) = collection.requests.as_ref() {
+            for_each_request(&requests.read().unwrap(), &mut |request| {
+                engine.index_request(&request.read().unwrap())
+            });
+        }
+
+        engine
+    }
+
+    /// adds variable names that are not used anywhere on the collection but
+    /// are known to exist, like the ones defined on an environment
+    pub fn add_variables<I, S>(&mut self, variables: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.variables.extend(variables.into_iter().map(Into::into));
+    }
+
+    fn index_request(&mut self, request: &Request) {
+        self.variables.extend(find_variables(&request.uri));
+
+        if let Some(headers) = request.headers.as_ref() {
+            for header in headers {
+                let (name, value) = &header.pair;
+                self.variables.extend(find_variables(name));
+                self.variables.extend(find_variables(value));
+                if name.is_empty() {
+                    continue;
+                }
+                self.header_names.insert(name.to_string());
+                if !value.is_empty() {
+                    self.header_values
+                        .entry(name.to_ascii_lowercase())
+                        .or_default()
+                        .insert(value.to_string());
+                }
+            }
+        }
+
+        if let Some(body) = request.body.as_ref() {
+            self.variables.extend(find_variables(body));
+            if let Ok(value) = serde_json::from_str::<serde_json::Value>(&strip_jsonc(body)) {
+                collect_json_keys(&value, &mut self.json_keys);
+            }
+        }
+    }
+
+    /// returns every candidate matching `prefix` on the given context, sorted
+    /// from the best to the worst match
+    pub fn complete(&self, context: &CompletionContext, prefix: &str) -> Vec<Candidate> {
+        let sources: Vec<(CompletionKind, Vec<&str>)> = match context {
+            CompletionContext::HeaderName => vec![(
+                CompletionKind::HeaderName,
+                self.header_names
+                    .iter()
+                    .map(String::as_str)
+                    .chain(KNOWN_HEADER_NAMES.iter().copied())
+                    .collect(),
+            )],
+            CompletionContext::HeaderValue { header_name } => vec![(
+                CompletionKind::HeaderValue,
+                self.header_values
+                    .get(&header_name.to_ascii_lowercase())
+                    .into_iter()
+                    .flatten()
+                    .map(String::as_str)
+                    .chain(known_header_values(header_name).iter().copied())
+                    .collect(),
+            )],
+            CompletionContext::Body => vec![
+                (
+                    CompletionKind::Variable,
+                    self.variables.iter().map(String::as_str).collect(),
+                ),
+                (
+                    CompletionKind::JsonKey,
+                    self.json_keys.iter().map(String::as_str).collect(),
+                ),
+            ],
+        };
+
+        let mut seen = BTreeSet::new();
+        let mut candidates = sources
+            .into_iter()
+            .flat_map(|(kind, words)| words.into_iter().map(move |word| (kind, word)))
+            .filter(|(_, word)| seen.insert(word.to_ascii_lowercase()))
+            .filter_map(|(kind, word)| {
+                score_candidate(word, prefix).map(|score| Candidate {
+                    label: word.to_string(),
+                    kind,
+                    score,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        candidates.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then(a.label.len().cmp(&b.label.len()))
+                .then(a.label.cmp(&b.label))
+        });
+
+        candidates
+    }
+
+    /// completes a body given the text on the current line up to the cursor.
+    ///
+    /// when the cursor is after an unclosed `{{` only variables are suggested,
+    /// when it is inside of a string where a key goes only json keys are
+    /// suggested
+    pub fn complete_body(&self, line_before_cursor: &str) -> Option<Completion> {
+        if let Some(open) = line_before_cursor.rfind("{{") {
+            let after_open = &line_before_cursor[open + 2..];
+            if !after_open.contains("}}") {
+                let prefix = after_open.trim_start().to_string();
+                let candidates = self
+                    .complete(&CompletionContext::Body, &prefix)
+                    .into_iter()
+                    .filter(|c| c.kind.eq(&CompletionKind::Variable))
+                    .collect();
+                let start = line_before_cursor.chars().count() - prefix.chars().count();
+                return Some(Completion {
+                    start,
+                    prefix,
+                    candidates,
+                });
+            }
+        }
+
+        // an odd amount of unescaped quotes means we are inside of a string,
+        // which holds a key unless it comes after a `:` or opens an array
+        let quotes = unescaped_quotes(line_before_cursor);
+        let is_key = |quote_idx: usize| {
+            !line_before_cursor[..quote_idx]
+                .trim_end()
+                .ends_with([':', '['])
+        };
+        if let Some(quote_idx) = quotes.last().copied().filter(|_| quotes.len() % 2 == 1) {
+            if !is_key(quote_idx) {
+                return None;
+            }
+            let prefix = line_before_cursor[quote_idx + 1..].to_string();
+            let candidates = self
+                .complete(&CompletionContext::Body, &prefix)
+                .into_iter()
+                .filter(|c| c.kind.eq(&CompletionKind::JsonKey))
+                .collect();
+            let start = line_before_cursor[..quote_idx + 1].chars().count();
+            return Some(Completion {
+                start,
+                prefix,
+                candidates,
+            });
+        }
+
+        None
+    }
+}
+
+/// byte indexes of the quotes that open or close strings, leaving out the
+/// escaped ones
+fn unescaped_quotes(text: &str) -> Vec<usize> {
+    let mut escaped = false;
+    text.char_indices()
+        .filter_map(|(idx, c)| {
+            let is_quote = c.eq(&'"') && !escaped;
+            escaped = c.eq(&'\\') && !escaped;
+            is_quote.then_some(idx)
+        })
+        .collect()
+}
+
+/// scores how well a word matches a prefix, returning `None` when it doesn't
+/// match at all. Exact matches are better than prefix matches, which are
+/// better than substring matches, which are better than fuzzy matches
+fn score_candidate(word: &str, prefix: &str) -> Option<i64> {
+    if prefix.is_empty() {
+        return Some(0);
+    }
+
+    let lower_word = word.to_ascii_lowercase();
+    let lower_prefix = prefix.to_ascii_lowercase();
+
+    if lower_word.eq(&lower_prefix) {
+        return Some(500);
+    }
+
+    if word.starts_with(prefix) {
+        return Some(400);
+    }
+
+    if lower_word.starts_with(&lower_prefix) {
+        return Some(300);
+    }
+
+    if let Some(idx) = lower_word.find(&lower_prefix) {
+        return Some(200 - idx as i64);
+    }
+
+    // fuzzy subsequence match, penalized by how spread the characters are
+    let mut gaps = 0;
+    let mut last_match: Option<usize> = None;
+    let mut chars = lower_word.char_indices();
+    for needle in lower_prefix.chars() {
+        let (idx, _) = chars.find(|(_, c)| c.eq(&needle))?;
+        if let Some(last) = last_match {
+            gaps += idx - last - 1;
+        }
+        last_match = Some(idx);
+    }
+
+    Some(100 - gaps as i64)
+}
+
+/// finds every `{{variable}}` used in a text
+pub fn find_variables(text: &str) -> Vec<String> {
+    let mut variables = vec![];
+    let mut rest = text;
+
+    while let Some(open) = rest.find("{{") {
+        let after_open = &rest[open + 2..];
+        let Some(close) = after_open.find("}}") else {
+            break;
+        };
+        let name = after_open[..close].trim();
+        if !name.is_empty() {
+            variables.push(name.to_string());
+        }
+        rest = &after_open[close + 2..];
+    }
+
+    variables
+}
+
+fn collect_json_keys(value: &serde_json::Value, keys: &mut BTreeSet<String>) {
+    match value {
+        serde_json::Value::Object(map) => map.iter().for_each(|(key, value)| {
+            keys.insert(key.to_string());
+            collect_json_keys(value, keys);
+        }),
+        serde_json::Value::Array(values) => values.iter().for_each(|v| collect_json_keys(v, keys)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::types::{HeaderMap, Info, RequestKind, RequestMethod};
+
+    use std::sync::{Arc, RwLock};
+
+    fn make_collection() -> Collection {
+        let request = Request {
+            id: "id".into(),
+            method: RequestMethod::Post,
+            name: "create user".into(),
+            uri: "{{base_url}}/users".into(),
+            headers: Some(vec![HeaderMap {
+                pair: ("X-Tenant".into(), "{{tenant}}".into()),
+                enabled: true,
+            }]),
+            body: Some(
+                "{ // user\n \"name\": \"{{user_name}}\", \"address\": { \"street\": 1 }, }".into(),
+            ),
+            ..Default::default()
+        };
+
+        Collection {
+            info: Info {
+                name: "test".into(),
+                description: None,
+                read_only: false,
+            },
+            requests: Some(Arc::new(RwLock::new(vec![RequestKind::Single(Arc::new(
+                RwLock::new(request),
+            ))]))),
+            path: "test.json".into(),
+            unlocked: false,
+            protos: vec![],
+            highlights: vec![],
+            template: None,
+            format_on_save: false,
+            synced: Default::default(),
+            environments: vec![],
+        }
+    }
+
+    #[test]
+    fn test_header_names_are_ranked() {
+        let engine = CompletionEngine::from_collection(&make_collection());
+
+        let candidates = engine.complete(&CompletionContext::HeaderName, "Con");
+        let labels = candidates
+            .iter()
+            .map(|c| c.label.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(labels[0], "Connection");
+        assert!(labels.contains(&"Content-Type"));
+        assert!(!labels.contains(&"Accept"));
+
+        let candidates = engine.complete(&CompletionContext::HeaderName, "x-t");
+        assert_eq!(candidates[0].label, "X-Tenant");
+    }
+
+    #[test]
+    fn test_header_values() {
+        let engine = CompletionEngine::from_collection(&make_collection());
+
+        let candidates = engine.complete(
+            &CompletionContext::HeaderValue {
+                header_name: "content-type",
+            },
+            "json",
+        );
+
+        assert_eq!(candidates[0].label, "application/json");
+        assert_eq!(candidates[0].kind, CompletionKind::HeaderValue);
+    }
+
+    #[test]
+    fn test_complete_body_variables() {
+        let engine = CompletionEngine::from_collection(&make_collection());
+
+        let completion = engine.complete_body("  \"id\": \"{{te").unwrap();
+
+        assert_eq!(completion.prefix, "te");
+        assert_eq!(completion.start, 11);
+        assert_eq!(completion.candidates.len(), 1);
+        assert_eq!(completion.candidates[0].label, "tenant");
+    }
+
+    #[test]
+    fn test_complete_body_json_keys() {
+        let engine = CompletionEngine::from_collection(&make_collection());
+
+        let completion = engine.complete_body("  \"str").unwrap();
+
+        assert_eq!(completion.start, 3);
+        assert_eq!(completion.candidates[0].label, "street");
+        assert!(engine.complete_body("  \"name\": 1").is_none());
+        // values are not keys
+        assert!(engine.complete_body("  \"name\": \"jo").is_none());
+        assert!(engine.complete_body("  \"tags\": [\"str").is_none());
+        let completion = engine.complete_body("{\"name\": \"jo\", \"str").unwrap();
+        assert_eq!(completion.prefix, "str");
+    }
+
+    #[test]
+    fn test_complete_body_escaped_quotes() {
+        let engine = CompletionEngine::from_collection(&make_collection());
+
+        // an escaped quote doesn't close the string it is on
+        assert!(engine.complete_body("  \"name\": \"say \\\"hi").is_none());
+        let completion = engine.complete_body("  \"say \\\"hi\": 1, \"str").unwrap();
+        assert_eq!(completion.prefix, "str");
+        assert!(engine.complete_body("  \"say \\\"hi\": \"str").is_none());
+        assert_eq!(unescaped_quotes(r#""a\"b\\""#), [0, 7]);
+    }
+
+    #[test]
+    fn test_find_variables() {
+        let variables = find_variables("{{ host }}/{{path}}/{{}}/{{unclosed");
+        assert_eq!(variables, vec!["host", "path"]);
+    }
+}
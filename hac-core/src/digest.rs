@@ -0,0 +1,144 @@
+use crate::collection::types::{HeaderMap, Request};
+
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use md5::Md5;
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DigestAlgorithm {
+    Md5,
+    Sha256,
+    /// keyed with the given secret
+    HmacSha256(String),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum DigestEncoding {
+    #[default]
+    Hex,
+    Base64,
+}
+
+/// where the computed digest is written to
+#[derive(Debug, Clone, PartialEq)]
+pub enum DigestTarget {
+    Header(String),
+    Variable(String),
+}
+
+/// a digest to compute over the body of a request, and where to put it
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigestCommand {
+    pub algorithm: DigestAlgorithm,
+    pub encoding: DigestEncoding,
+    pub target: DigestTarget,
+}
+
+impl DigestCommand {
+    /// parses commands like `sha256 header X-Checksum`, `md5 var body_md5`
+    /// or `hmac-sha256 <secret> header X-Signature`. Appending `base64`
+    /// encodes the digest as base64 instead of hex
+    pub fn parse(command: &str) -> anyhow::Result<DigestCommand> {
+        let mut words = command.split_whitespace().collect::<Vec<_>>();
+
+        let encoding = match words.last() {
+            Some(&"base64") => {
+                words.pop();
+                DigestEncoding::Base64
+            }
+            Some(&"hex") => {
+                words.pop();
+                DigestEncoding::Hex
+            }
+            _ => DigestEncoding::Hex,
+        };
+
+        let (algorithm, rest) = match words.as_slice() {
+            ["md5", rest @ ..] => (DigestAlgorithm::Md5, rest),
+            ["sha256", rest @ ..] => (DigestAlgorithm::Sha256, rest),
+            ["hmac-sha256", key, rest @ ..] => (DigestAlgorithm::HmacSha256(key.to_string()), rest),
+            ["hmac-sha256"] => anyhow::bail!("hmac-sha256 needs a secret key"),
+            _ => anyhow::bail!("expected md5, sha256 or hmac-sha256"),
+        };
+
+        let target = match rest {
+            ["header", name] => DigestTarget::Header(name.to_string()),
+            ["var", name] => DigestTarget::Variable(name.to_string()),
+            _ => anyhow::bail!("expected `header <name>` or `var <name>` after the algorithm"),
+        };
+
+        Ok(DigestCommand {
+            algorithm,
+            encoding,
+            target,
+        })
+    }
+
+    /// computes the digest of the given, already resolved, body
+    pub fn compute(&self, body: &str) -> String {
+        let bytes = match &self.algorithm {
+            DigestAlgorithm::Md5 => Md5::digest(body).to_vec(),
+            DigestAlgorithm::Sha256 => Sha256::digest(body).to_vec(),
+            DigestAlgorithm::HmacSha256(key) => {
+                let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes())
+                    .expect("hmac accepts keys of any size");
+                mac.update(body.as_bytes());
+                mac.finalize().into_bytes().to_vec()
+            }
+        };
+
+        match self.encoding {
+            DigestEncoding::Hex => bytes.iter().map(|byte| format!("{byte:02x}")).collect(),
+            DigestEncoding::Base64 => base64::engine::general_purpose::STANDARD.encode(bytes),
+        }
+    }
+}
+
+/// sets the header with the given name on the request, the name is matched
+/// ignoring case and the header is added when it doesn't exist yet
+pub fn set_header(request: &mut Request, name: &str, value: String) {
+    let headers = request.headers.get_or_insert_with(Vec::new);
+    match headers
+        .iter_mut()
+        .find(|header| header.pair.0.eq_ignore_ascii_case(name))
+    {
+        Some(header) => {
+            header.pair.1 = value;
+            header.enabled = true;
+        }
+        None => headers.push(HeaderMap {
+            pair: (name.to_string(), value),
+            enabled: true,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest_command() {
+        let md5 = DigestCommand::parse("md5 var body_md5").unwrap();
+        assert_eq!(md5.target, DigestTarget::Variable("body_md5".into()));
+        assert_eq!(md5.compute(""), "d41d8cd98f00b204e9800998ecf8427e");
+
+        let sha256 = DigestCommand::parse("sha256 header X-Checksum").unwrap();
+        assert_eq!(
+            sha256.compute("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+
+        let hmac = DigestCommand::parse("hmac-sha256 key header X-Signature base64").unwrap();
+        assert_eq!(hmac.encoding, DigestEncoding::Base64);
+        assert_eq!(
+            hmac.compute("The quick brown fox jumps over the lazy dog"),
+            "97yD9DBThCSxMpjmqm+xQ+9NWaFJRhdZl0edvC0aPNg="
+        );
+
+        assert!(DigestCommand::parse("sha1 header X").is_err());
+        assert!(DigestCommand::parse("hmac-sha256").is_err());
+        assert!(DigestCommand::parse("sha256 cookie X").is_err());
+    }
+}
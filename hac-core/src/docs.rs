@@ -0,0 +1,535 @@
+use crate::collection::types::{Request, RequestKind};
+use crate::collection::Collection;
+
+use std::fmt::Write;
+
+/// formats a collection can be exported to as documentation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DocsFormat {
+    Markdown,
+    Html,
+}
+
+/// renders a collection as documentation in the given format, the markdown
+/// output is the source for the html one, so both always have the same content
+pub fn export(collection: &Collection, format: DocsFormat) -> String {
+    let markdown = render_markdown(collection);
+    match format {
+        DocsFormat::Markdown => markdown,
+        DocsFormat::Html => render_html(&collection.info.name, &markdown),
+    }
+}
+
+/// renders every request of a collection, along with its query parameters,
+/// headers, authentication, example body, example responses and documentation
+/// into markdown
+pub fn render_markdown(collection: &Collection) -> String {
+    let mut out = String::new();
+    let requests = collection
+        .requests
+        .as_ref()
+        .map(|requests| requests.read().unwrap().clone())
+        .unwrap_or_default();
+
+    _ = writeln!(out, "# {}\n", collection.info.name);
+    if let Some(description) = collection
+        .info
+        .description
+        .as_ref()
+        .filter(|d| !d.is_empty())
+    {
+        _ = writeln!(out, "{}\n", description);
+    }
+
+    if requests.is_empty() {
+        out.push_str("_This collection has no requests._\n");
+        return out;
+    }
+
+    out.push_str("## Contents\n\n");
+    for item in requests.iter() {
+        match item {
+            RequestKind::Single(req) => {
+                let title = request_title(&req.read().unwrap());
+                _ = writeln!(out, "- [{}](#{})", title, slugify(&title));
+            }
+            RequestKind::Nested(dir) => {
+                _ = writeln!(out, "- [{}](#{})", dir.name, slugify(&dir.name));
+                for child in dir.requests.read().unwrap().iter() {
+                    if let RequestKind::Single(req) = child {
+                        let title = request_title(&req.read().unwrap());
+                        _ = writeln!(out, "  - [{}](#{})", title, slugify(&title));
+                    }
+                }
+            }
+        }
+    }
+    out.push('\n');
+
+    for item in requests.iter() {
+        match item {
+            RequestKind::Single(req) => render_request(&mut out, &req.read().unwrap(), 2),
+            RequestKind::Nested(dir) => {
+                _ = writeln!(out, "## {}\n", dir.name);
+                if let Some(docs) = dir.docs.as_ref() {
+                    _ = writeln!(out, "{}\n", docs.trim_end());
+                }
+                for child in dir.requests.read().unwrap().iter() {
+                    if let RequestKind::Single(req) = child {
+                        render_request(&mut out, &req.read().unwrap(), 3);
+                    }
+                }
+            }
+        }
+    }
+
+    out
+}
+
+fn request_title(request: &Request) -> String {
+    format!("{} {}", request.method, request.name)
+}
+
+fn render_request(out: &mut String, request: &Request, level: usize) {
+    _ = writeln!(out, "{} {}\n", "#".repeat(level), request_title(request));
+    _ = writeln!(out, "`{} {}`\n", request.method, request.uri);
+
+    if let Some(docs) = request.docs.as_ref() {
+        _ = writeln!(out, "{}\n", docs.trim_end());
+    }
+
+    let params = query_params(&request.uri);
+    if !params.is_empty() {
+        out.push_str("**Query parameters**\n\n| Name | Value |\n| --- | --- |\n");
+        for (name, value) in params {
+            _ = writeln!(out, "| {} | {} |", escape_cell(&name), escape_cell(&value));
+        }
+        out.push('\n');
+    }
+
+    let headers = request
+        .headers
+        .as_ref()
+        .map(|headers| {
+            headers
+                .iter()
+                .filter(|header| header.enabled)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    if !headers.is_empty() {
+        out.push_str("**Headers**\n\n| Name | Value |\n| --- | --- |\n");
+        for header in headers {
+            _ = writeln!(
+                out,
+                "| {} | {} |",
+                escape_cell(&header.pair.0),
+                escape_cell(&header.pair.1)
+            );
+        }
+        out.push('\n');
+    }
+
+    if let Some(auth) = request.auth_method.as_ref() {
+        _ = writeln!(out, "**Authentication**: {}\n", auth);
+    }
+
+    if let Some(body) = request.body.as_ref().filter(|body| !body.trim().is_empty()) {
+        _ = writeln!(
+            out,
+            "**Example body**\n\n```json\n{}\n```\n",
+            body.trim_end()
+        );
+    }
+
+    if !request.examples.is_empty() {
+        out.push_str("**Example responses**\n\n");
+        for example in request.examples.iter() {
+            _ = writeln!(out, "- {} ({})", example.name, example.status);
+            if let Some(body) = example.body.as_ref().filter(|body| !body.trim().is_empty()) {
+                let body = jsonxf::pretty_print(body).unwrap_or_else(|_| body.to_string());
+                _ = writeln!(out, "\n```json\n{}\n```\n", body.trim_end());
+            }
+        }
+        out.push('\n');
+    }
+}
+
+/// splits the query string of an uri into its name/value pairs
+fn query_params(uri: &str) -> Vec<(String, String)> {
+    let Some((_, query)) = uri.split_once('?') else {
+        return vec![];
+    };
+    let query = query.split('#').next().unwrap_or_default();
+
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((name, value)) => (name.to_string(), value.to_string()),
+            None => (pair.to_string(), String::default()),
+        })
+        .collect()
+}
+
+fn escape_cell(value: &str) -> String {
+    value.replace('|', "\\|")
+}
+
+/// the anchor a heading will have, following the same rules as GitHub so the
+/// markdown links also work when the file is published there
+pub fn slugify(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            c if c.is_alphanumeric() || c.eq(&'-') || c.eq(&'_') => Some(c),
+            ' ' => Some('-'),
+            _ => None,
+        })
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// wraps the html version of a markdown document into a standalone page
+fn render_html(title: &str, markdown: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{}</title>
+<style>
+body {{ font-family: sans-serif; max-width: 960px; margin: 2rem auto; padding: 0 1rem; line-height: 1.5; }}
+pre, code {{ background: #f4f4f4; border-radius: 4px; }}
+pre {{ padding: 1rem; overflow-x: auto; }}
+code {{ padding: 0 .25rem; }}
+table {{ border-collapse: collapse; }}
+th, td {{ border: 1px solid #ddd; padding: .25rem .75rem; text-align: left; }}
+</style>
+</head>
+<body>
+{}</body>
+</html>
+"#,
+        escape_html(title),
+        markdown_to_html(markdown)
+    )
+}
+
+/// converts the subset of markdown we generate (and that is commonly used on
+/// request docs) into html
+pub fn markdown_to_html(markdown: &str) -> String {
+    let mut out = String::new();
+    let mut paragraph: Vec<String> = vec![];
+    let mut list_depth = 0;
+    let mut in_code_block = false;
+    let mut in_table = false;
+
+    let flush_paragraph = |out: &mut String, paragraph: &mut Vec<String>| {
+        if !paragraph.is_empty() {
+            _ = writeln!(out, "<p>{}</p>", paragraph.join(" "));
+            paragraph.clear();
+        }
+    };
+
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+
+        if in_code_block {
+            if trimmed.starts_with("```") {
+                in_code_block = false;
+                out.push_str("</code></pre>\n");
+            } else {
+                _ = writeln!(out, "{}", escape_html(line));
+            }
+            continue;
+        }
+
+        let is_list_item = trimmed.starts_with("- ") || trimmed.starts_with("* ");
+        let is_table_row = trimmed.starts_with('|');
+
+        if !is_list_item {
+            for _ in 0..list_depth {
+                out.push_str("</ul>\n");
+            }
+            list_depth = 0;
+        }
+        if !is_table_row && in_table {
+            out.push_str("</table>\n");
+            in_table = false;
+        }
+
+        if let Some(lang) = trimmed.strip_prefix("```") {
+            flush_paragraph(&mut out, &mut paragraph);
+            in_code_block = true;
+            match lang.trim() {
+                "" => out.push_str("<pre><code>"),
+                lang => _ = write!(out, "<pre><code class=\"language-{}\">", escape_html(lang)),
+            }
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            flush_paragraph(&mut out, &mut paragraph);
+            continue;
+        }
+
+        let level = trimmed.chars().take_while(|c| c.eq(&'#')).count();
+        if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+            flush_paragraph(&mut out, &mut paragraph);
+            let text = trimmed[level..].trim();
+            _ = writeln!(
+                out,
+                "<h{level} id=\"{}\">{}</h{level}>",
+                slugify(text),
+                inline_to_html(text)
+            );
+            continue;
+        }
+
+        if is_list_item {
+            flush_paragraph(&mut out, &mut paragraph);
+            let depth = line.len().saturating_sub(trimmed.len()) / 2 + 1;
+            while list_depth < depth {
+                out.push_str("<ul>\n");
+                list_depth += 1;
+            }
+            while list_depth > depth {
+                out.push_str("</ul>\n");
+                list_depth -= 1;
+            }
+            _ = writeln!(out, "<li>{}</li>", inline_to_html(&trimmed[2..]));
+            continue;
+        }
+
+        if is_table_row {
+            flush_paragraph(&mut out, &mut paragraph);
+            let cells = split_table_row(trimmed);
+            if cells.iter().all(|cell| cell.chars().all(|c| c.eq(&'-'))) {
+                continue;
+            }
+            let tag = if in_table { "td" } else { "th" };
+            if !in_table {
+                out.push_str("<table>\n");
+                in_table = true;
+            }
+            out.push_str("<tr>");
+            for cell in cells {
+                _ = write!(out, "<{tag}>{}</{tag}>", inline_to_html(&cell));
+            }
+            out.push_str("</tr>\n");
+            continue;
+        }
+
+        paragraph.push(inline_to_html(trimmed));
+    }
+
+    flush_paragraph(&mut out, &mut paragraph);
+    for _ in 0..list_depth {
+        out.push_str("</ul>\n");
+    }
+    if in_table {
+        out.push_str("</table>\n");
+    }
+    if in_code_block {
+        out.push_str("</code></pre>\n");
+    }
+
+    out
+}
+
+fn split_table_row(row: &str) -> Vec<String> {
+    let row = row.trim().trim_start_matches('|').trim_end_matches('|');
+    let mut cells = vec![];
+    let mut current = String::new();
+    let mut chars = row.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some('|')) => {
+                current.push('|');
+                chars.next();
+            }
+            ('|', _) => cells.push(std::mem::take(&mut current).trim().to_string()),
+            (c, _) => current.push(c),
+        }
+    }
+    cells.push(current.trim().to_string());
+
+    cells
+}
+
+/// converts inline code, bold, italic and links into html, escaping everything
+/// else
+fn inline_to_html(text: &str) -> String {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut out = String::new();
+    let mut bold = false;
+    let mut italic = false;
+    let mut i = 0;
+
+    let find_from = |from: usize, needle: char| {
+        chars
+            .get(from..)?
+            .iter()
+            .position(|c| c.eq(&needle))
+            .map(|pos| pos + from)
+    };
+
+    while i < chars.len() {
+        match (chars[i], chars.get(i + 1)) {
+            ('\\', Some(escaped)) if escaped.is_ascii_punctuation() => {
+                out.push_str(&escape_html(&escaped.to_string()));
+                i += 2;
+            }
+            ('`', _) if find_from(i + 1, '`').is_some() => {
+                let close = find_from(i + 1, '`').unwrap();
+                let code = chars[i + 1..close].iter().collect::<String>();
+                _ = write!(out, "<code>{}</code>", escape_html(&code));
+                i = close + 1;
+            }
+            ('*', Some('*')) | ('_', Some('_')) => {
+                out.push_str(if bold { "</strong>" } else { "<strong>" });
+                bold = !bold;
+                i += 2;
+            }
+            ('*', _) | ('_', _) => {
+                out.push_str(if italic { "</em>" } else { "<em>" });
+                italic = !italic;
+                i += 1;
+            }
+            ('[', _) => {
+                let link = find_from(i + 1, ']')
+                    .filter(|close| chars.get(close + 1).is_some_and(|c| c.eq(&'(')))
+                    .and_then(|close| find_from(close + 2, ')').map(|end| (close, end)));
+                match link {
+                    Some((close, end)) => {
+                        let label = chars[i + 1..close].iter().collect::<String>();
+                        let url = chars[close + 2..end].iter().collect::<String>();
+                        _ = write!(
+                            out,
+                            "<a href=\"{}\">{}</a>",
+                            escape_html(&url),
+                            inline_to_html(&label)
+                        );
+                        i = end + 1;
+                    }
+                    None => {
+                        out.push('[');
+                        i += 1;
+                    }
+                }
+            }
+            (c, _) => {
+                out.push_str(&escape_html(&c.to_string()));
+                i += 1;
+            }
+        }
+    }
+
+    if italic {
+        out.push_str("</em>");
+    }
+    if bold {
+        out.push_str("</strong>");
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::types::{Directory, ExampleResponse, HeaderMap, Info, RequestMethod};
+    use std::sync::{Arc, RwLock};
+
+    fn make_collection() -> Collection {
+        let request = Request {
+            id: "1".into(),
+            method: RequestMethod::Post,
+            name: "Create user".into(),
+            uri: "https://api.dev/users?notify=true&dry".into(),
+            headers: Some(vec![
+                HeaderMap {
+                    pair: ("Content-Type".into(), "application/json".into()),
+                    enabled: true,
+                },
+                HeaderMap {
+                    pair: ("X-Debug".into(), "1".into()),
+                    enabled: false,
+                },
+            ]),
+            parent: Some("dir".into()),
+            body: Some("{ \"name\": \"John\" }".into()),
+            docs: Some("Creates a **new** user".into()),
+            examples: vec![ExampleResponse {
+                name: "created".into(),
+                status: 201,
+                headers: vec![],
+                body: Some("{\"id\":1}".into()),
+            }],
+            ..Default::default()
+        };
+
+        Collection {
+            info: Info {
+                name: "Users API".into(),
+                description: Some("Manage users".into()),
+                read_only: false,
+            },
+            requests: Some(Arc::new(RwLock::new(vec![RequestKind::Nested(
+                Directory {
+                    id: "dir".into(),
+                    name: "Users".into(),
+                    requests: Arc::new(RwLock::new(vec![RequestKind::Single(Arc::new(
+                        RwLock::new(request),
+                    ))])),
+                    docs: Some("Everything about users".into()),
+                },
+            )]))),
+            path: "users.json".into(),
+            unlocked: false,
+            protos: vec![],
+            highlights: vec![],
+            template: None,
+            format_on_save: false,
+            synced: Default::default(),
+            environments: vec![],
+        }
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let markdown = render_markdown(&make_collection());
+
+        assert!(markdown.starts_with("# Users API\n\nManage users\n"));
+        assert!(markdown.contains("- [Users](#users)\n  - [POST Create user](#post-create-user)"));
+        assert!(markdown.contains("## Users\n\nEverything about users\n"));
+        assert!(markdown
+            .contains("### POST Create user\n\n`POST https://api.dev/users?notify=true&dry`"));
+        assert!(markdown.contains("| notify | true |\n| dry |  |"));
+        assert!(markdown.contains("| Content-Type | application/json |"));
+        assert!(!markdown.contains("X-Debug"));
+        assert!(markdown.contains("```json\n{ \"name\": \"John\" }\n```"));
+        assert!(markdown.contains("- created (201)\n\n```json\n{\n  \"id\": 1\n}\n```"));
+    }
+
+    #[test]
+    fn test_markdown_to_html() {
+        let html = markdown_to_html(
+            "## Get <users>\n\nuse `id` and **bold**\n\n- [a](#a)\n\n| N | V |\n| --- | --- |\n| x \\| y | 1 |\n\n```json\n{}\n```",
+        );
+
+        assert!(html.contains("<h2 id=\"get-users\">Get &lt;users&gt;</h2>"));
+        assert!(html.contains("<p>use <code>id</code> and <strong>bold</strong></p>"));
+        assert!(html.contains("<ul>\n<li><a href=\"#a\">a</a></li>\n</ul>"));
+        assert!(html.contains("<tr><th>N</th><th>V</th></tr>\n<tr><td>x | y</td><td>1</td></tr>"));
+        assert!(html.contains("<pre><code class=\"language-json\">{}\n</code></pre>"));
+    }
+}
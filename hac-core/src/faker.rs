@@ -0,0 +1,131 @@
+use hac_config::Locale;
+
+use std::sync::OnceLock;
+
+use fake::faker::address::raw::{CityName, CountryName, StreetName, ZipCode};
+use fake::faker::boolean::raw::Boolean;
+use fake::faker::company::raw::CompanyName;
+use fake::faker::internet::raw::{IPv4, IPv6, MACAddress, Password, SafeEmail, Username};
+use fake::faker::job::raw::Title;
+use fake::faker::lorem::raw::{Sentence, Word};
+use fake::faker::name::raw::{FirstName, LastName, Name};
+use fake::faker::phone_number::raw::PhoneNumber;
+use fake::locales::{EN, PT_BR};
+use fake::Fake;
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+/// every function that can be used on a template, like `{{$randomEmail}}`
+pub const FUNCTIONS: &[&str] = &[
+    "$randomFirstName",
+    "$randomLastName",
+    "$randomName",
+    "$randomUserName",
+    "$randomEmail",
+    "$randomPassword",
+    "$randomPhoneNumber",
+    "$randomCity",
+    "$randomCountry",
+    "$randomStreetName",
+    "$randomZipCode",
+    "$randomCompanyName",
+    "$randomJobTitle",
+    "$randomWord",
+    "$randomLoremSentence",
+    "$randomIPv4",
+    "$randomIPv6",
+    "$randomMACAddress",
+    "$randomUUID",
+    "$randomInt",
+    "$randomBoolean",
+    "$timestamp",
+    "$isoTimestamp",
+];
+
+/// sets the language names, addresses and words are generated in, this is
+/// meant to be called once, while starting, and later calls have no effect
+pub fn set_locale(locale: Locale) {
+    if LOCALE.set(locale).is_err() {
+        tracing::warn!("tried to change the faker locale after it was set");
+    }
+}
+
+/// whether the name is one of the template functions
+pub fn is_function(name: &str) -> bool {
+    FUNCTIONS.contains(&name)
+}
+
+/// a macro as some fakers, like the one for city names, are implemented for
+/// each locale instead of any of them
+macro_rules! generate_with {
+    ($name:expr, $locale:expr) => {{
+        let value: String = match $name {
+            "$randomFirstName" => FirstName($locale).fake(),
+            "$randomLastName" => LastName($locale).fake(),
+            "$randomName" => Name($locale).fake(),
+            "$randomUserName" => Username($locale).fake(),
+            "$randomEmail" => SafeEmail($locale).fake(),
+            "$randomPassword" => Password($locale, 12..20).fake(),
+            "$randomPhoneNumber" => PhoneNumber($locale).fake(),
+            "$randomCity" => CityName($locale).fake(),
+            "$randomCountry" => CountryName($locale).fake(),
+            "$randomStreetName" => StreetName($locale).fake(),
+            "$randomZipCode" => ZipCode($locale).fake(),
+            "$randomCompanyName" => CompanyName($locale).fake(),
+            "$randomJobTitle" => Title($locale).fake(),
+            "$randomWord" => Word($locale).fake(),
+            "$randomLoremSentence" => Sentence($locale, 4..10).fake(),
+            "$randomIPv4" => IPv4($locale).fake(),
+            "$randomIPv6" => IPv6($locale).fake(),
+            "$randomMACAddress" => MACAddress($locale).fake(),
+            "$randomUUID" => uuid::Uuid::new_v4().to_string(),
+            "$randomInt" => (0..1000).fake::<u32>().to_string(),
+            "$randomBoolean" => Boolean($locale, 50).fake::<bool>().to_string(),
+            "$timestamp" => chrono::Utc::now().timestamp().to_string(),
+            "$isoTimestamp" => {
+                chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+            }
+            _ => return None,
+        };
+        Some(value)
+    }};
+}
+
+/// a new random value for the template function, every call generates a
+/// different one so each request sent gets its own data. `None` when the
+/// name isn't a function
+pub fn generate(name: &str) -> Option<String> {
+    generate_in(name, LOCALE.get().copied().unwrap_or_default())
+}
+
+fn generate_in(name: &str, locale: Locale) -> Option<String> {
+    match locale {
+        Locale::English => generate_with!(name, EN),
+        Locale::BrazilianPortuguese => generate_with!(name, PT_BR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() {
+        for function in FUNCTIONS {
+            let value = generate(function).unwrap();
+            assert!(!value.is_empty(), "{function} generated nothing");
+        }
+        assert!(generate("$notAFunction").is_none());
+        assert!(generate("randomEmail").is_none());
+
+        let email = generate_in("$randomEmail", Locale::BrazilianPortuguese).unwrap();
+        assert!(email.contains('@'));
+        let ip = generate("$randomIPv4").unwrap();
+        assert!(ip.parse::<std::net::Ipv4Addr>().is_ok());
+
+        let uuids = (0..10)
+            .map(|_| generate("$randomUUID").unwrap())
+            .collect::<std::collections::BTreeSet<_>>();
+        assert_eq!(uuids.len(), 10);
+    }
+}
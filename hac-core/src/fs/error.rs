@@ -3,6 +3,12 @@ pub enum FsError {
     SerializationError(String),
     IOError(String),
     CollectionAlreadyExists(String),
+    ReadOnlyCollection(String),
+    /// the file was changed by someone else since this session last synced
+    /// it, so writing it would discard their changes
+    Conflict(String),
+    /// another session held the lock of the file for too long
+    Locked(String),
     Unknown,
 }
 
@@ -12,6 +18,13 @@ impl std::fmt::Display for FsError {
             FsError::CollectionAlreadyExists(path) => {
                 write!(f, "collection {:?} already exists", path)
             }
+            FsError::ReadOnlyCollection(msg) => write!(f, "{}", msg),
+            FsError::Conflict(path) => {
+                write!(f, "collection {:?} was changed by another session", path)
+            }
+            FsError::Locked(path) => {
+                write!(f, "collection {:?} is locked by another session", path)
+            }
             FsError::Unknown => write!(f, "unknown error"),
             FsError::SerializationError(msg) => write!(f, "{}", msg),
             FsError::IOError(msg) => write!(f, "{}", msg),
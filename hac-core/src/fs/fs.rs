@@ -1,7 +1,14 @@
-use crate::collection::{collection::create_from_form, Collection};
+use crate::collection::collection::{self, create_from_form};
+use crate::collection::{merge, Collection};
 use crate::fs::error::FsError;
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// how long to wait for another session to release the lock of a collection
+const LOCK_TIMEOUT: Duration = Duration::from_secs(2);
+/// locks older than this were left by a session that died while writing
+const STALE_LOCK: Duration = Duration::from_secs(10);
 
 #[tracing::instrument(err, skip_all)]
 pub async fn delete_collection<P>(path: P) -> anyhow::Result<(), FsError>
@@ -36,20 +43,49 @@ pub async fn create_collection(
 
     // if we are on a dry_run, we skip syncing
     if !dry_run {
-        tokio::fs::write(&collection.path, serialized_collection)
+        tokio::fs::write(&collection.path, &serialized_collection)
             .await
             .map_err(|e| FsError::IOError(format!("failed to write collection: {:?}", e)))?;
     }
 
+    *collection.synced.lock().unwrap() = Some(serialized_collection);
     tracing::debug!("successfully created new collection: {:?}", collection.path);
     Ok(collection)
 }
 
+/// writes the collection to its file, unless another session changed the
+/// file since this one last synced it, which fails with `FsError::Conflict`
 pub async fn sync_collection(collection: Collection) -> anyhow::Result<(), FsError> {
+    collection
+        .ensure_writable()
+        .map_err(|e| FsError::ReadOnlyCollection(e.to_string()))?;
+
     let collection_str = serde_json::to_string(&collection)
         .map_err(|e| FsError::SerializationError(e.to_string()))?;
 
-    tokio::fs::write(&collection.path, collection_str)
+    let synced = collection.synced.lock().unwrap().clone();
+    // nothing changed since the last sync, so there is nothing to write. The
+    // file might not be formatted like we write it, so it is parsed first
+    let unchanged = synced
+        .as_deref()
+        .and_then(|synced| serde_json::from_str::<Collection>(synced).ok())
+        .and_then(|synced| serde_json::to_string(&synced).ok())
+        .is_some_and(|synced| synced.eq(&collection_str));
+    if unchanged {
+        return Ok(());
+    }
+
+    let _lock = CollectionLock::acquire(&collection.path).await?;
+    let on_disk = tokio::fs::read_to_string(&collection.path).await.ok();
+    if let (Some(synced), Some(on_disk)) = (synced, on_disk) {
+        if synced.ne(&on_disk) && on_disk.ne(&collection_str) {
+            return Err(FsError::Conflict(
+                collection.path.to_string_lossy().to_string(),
+            ));
+        }
+    }
+
+    tokio::fs::write(&collection.path, &collection_str)
         .await
         .map_err(|_| {
             FsError::IOError(format!(
@@ -57,8 +93,128 @@ pub async fn sync_collection(collection: Collection) -> anyhow::Result<(), FsErr
                 collection.path
             ))
         })?;
+    *collection.synced.lock().unwrap() = Some(collection_str);
 
     tracing::debug!("synchronization of collection: {:?}", collection.path);
 
     Ok(())
 }
+
+/// the collection as another session left it on its file, replacing the
+/// changes of this session
+pub fn reload_collection(collection: &Collection) -> anyhow::Result<Collection> {
+    let mut reloaded = collection::load(&collection.path)?;
+    reloaded.unlocked = collection.unlocked;
+    Ok(reloaded)
+}
+
+/// the changes of this session combined with the ones another session wrote
+/// to the file, see `merge::merge`. Syncing the result overwrites the file
+pub fn merge_collection(collection: &Collection) -> anyhow::Result<Collection> {
+    let theirs = collection::load(&collection.path)?;
+    let synced = collection.synced.lock().unwrap().clone();
+    let base = match synced {
+        Some(synced) => serde_json::from_str(&synced)?,
+        None => theirs.clone(),
+    };
+    let merged = merge::merge(&base, collection, &theirs)?;
+    *merged.synced.lock().unwrap() = theirs.synced.lock().unwrap().take();
+    Ok(merged)
+}
+
+/// the changes of this session will replace the ones another session wrote
+/// to the file on the next sync
+pub fn overwrite_collection(collection: &Collection) -> anyhow::Result<()> {
+    let on_disk = std::fs::read_to_string(&collection.path)?;
+    *collection.synced.lock().unwrap() = Some(on_disk);
+    Ok(())
+}
+
+/// a hidden file next to a collection that only one session can create at a
+/// time, held while the collection is compared and written
+struct CollectionLock {
+    path: PathBuf,
+}
+
+impl CollectionLock {
+    async fn acquire(collection: &Path) -> Result<CollectionLock, FsError> {
+        let file_name = collection.file_name().unwrap_or_default().to_string_lossy();
+        let path = collection.with_file_name(format!(".{file_name}.lock"));
+        let started_at = std::time::Instant::now();
+
+        loop {
+            let created = std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path);
+            match created {
+                Ok(_) => return Ok(CollectionLock { path }),
+                Err(e) if e.kind().ne(&std::io::ErrorKind::AlreadyExists) => {
+                    return Err(FsError::IOError(format!(
+                        "failed to lock collection {collection:?}: {e}"
+                    )));
+                }
+                Err(_) => {}
+            }
+
+            let is_stale = std::fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|elapsed| elapsed.gt(&STALE_LOCK));
+            if is_stale {
+                tracing::warn!("removing stale lock {path:?}");
+                _ = std::fs::remove_file(&path);
+                continue;
+            }
+            if started_at.elapsed().gt(&LOCK_TIMEOUT) {
+                return Err(FsError::Locked(collection.to_string_lossy().to_string()));
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+}
+
+impl Drop for CollectionLock {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            tracing::warn!("failed to release lock {:?}: {e}", self.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sync_detects_conflicts() {
+        let dir = std::env::temp_dir().join(format!("hac-sync-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("api.json");
+        std::fs::write(&path, r#"{"info":{"name":"api"},"requests":[]}"#).unwrap();
+
+        let ours = collection::load(&path).unwrap();
+        let theirs = collection::load(&path).unwrap();
+
+        let mut changed = theirs.clone();
+        changed.info.description = Some("theirs".into());
+        sync_collection(changed).await.unwrap();
+
+        // nothing changed on ours, so there is nothing to write
+        sync_collection(ours.clone()).await.unwrap();
+        let mut changed = ours.clone();
+        changed.info.name = "ours".into();
+        let result = sync_collection(changed.clone()).await;
+        assert!(matches!(result, Err(FsError::Conflict(_))));
+
+        let merged = merge_collection(&changed).unwrap();
+        sync_collection(merged).await.unwrap();
+        let on_disk = collection::load(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(on_disk.info.name, "ours");
+        assert_eq!(on_disk.info.description.as_deref(), Some("theirs"));
+        assert!(!dir.join(".api.json.lock").exists());
+    }
+}
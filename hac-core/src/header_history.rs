@@ -0,0 +1,128 @@
+use crate::collection::types::Request;
+use crate::completion::known_header_values;
+
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// how many values are kept for each header, the least recently used ones
+/// are dropped
+const MAX_VALUES: usize = 20;
+
+/// values sent on the headers of every collection, most recent first, keyed
+/// by the lowercase header name. This is persisted on the data directory so
+/// values typed once can be suggested anywhere else
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HeaderHistory {
+    values: HashMap<String, VecDeque<String>>,
+}
+
+impl HeaderHistory {
+    /// loads the history from disk, a missing or corrupted file means we
+    /// start over with an empty history
+    pub fn load<P>(path: P) -> HeaderHistory
+    where
+        P: AsRef<Path>,
+    {
+        std::fs::read_to_string(path.as_ref())
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save<P>(&self, path: P) -> anyhow::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        std::fs::write(path.as_ref(), serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// moves the value to the front of the ones used on the header
+    pub fn record(&mut self, header_name: &str, value: &str) {
+        if header_name.trim().is_empty() || value.trim().is_empty() {
+            return;
+        }
+        let values = self
+            .values
+            .entry(header_name.to_ascii_lowercase())
+            .or_default();
+        values.retain(|used| used.ne(value));
+        values.push_front(value.to_string());
+        values.truncate(MAX_VALUES);
+    }
+
+    /// records every enabled header of the request
+    pub fn record_request(&mut self, request: &Request) {
+        for header in request.headers.iter().flatten() {
+            if header.enabled {
+                self.record(&header.pair.0, &header.pair.1);
+            }
+        }
+    }
+
+    /// values to suggest for the header while `typed` is its value, the ones
+    /// used before come first, most recent first, followed by well known
+    /// values of the header
+    pub fn suggest(&self, header_name: &str, typed: &str) -> Vec<String> {
+        let typed_lowercase = typed.to_lowercase();
+        let mut suggestions: Vec<String> = vec![];
+        let used = self
+            .values
+            .get(&header_name.to_ascii_lowercase())
+            .into_iter()
+            .flatten()
+            .map(String::as_str);
+        for value in used.chain(known_header_values(header_name).iter().copied()) {
+            let matches = value.to_lowercase().starts_with(&typed_lowercase) && value.ne(typed);
+            if matches && !suggestions.iter().any(|s| s.eq(value)) {
+                suggestions.push(value.to_string());
+            }
+        }
+        suggestions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggest() {
+        let mut history = HeaderHistory::default();
+        history.record("Content-Type", "application/vnd.api+json");
+        history.record("X-Api-Key", "{{api_key}}");
+        history.record("content-type", "text/plain");
+        history.record("Content-Type", "application/vnd.api+json");
+        history.record("X-Api-Key", "");
+
+        assert_eq!(
+            history.suggest("CONTENT-TYPE", "app")[..3],
+            [
+                "application/vnd.api+json",
+                "application/json",
+                "application/xml"
+            ]
+        );
+        assert_eq!(history.suggest("content-type", "")[1], "text/plain");
+        assert_eq!(history.suggest("X-Api-Key", "{{"), vec!["{{api_key}}"]);
+        assert!(history.suggest("X-Api-Key", "{{api_key}}").is_empty());
+        assert!(history.suggest("X-Other", "").is_empty());
+    }
+
+    #[test]
+    fn test_keeps_most_recent_values() {
+        let mut history = HeaderHistory::default();
+        (0..MAX_VALUES + 5).for_each(|idx| history.record("X-Id", &idx.to_string()));
+
+        let suggestions = history.suggest("x-id", "");
+        assert_eq!(suggestions.len(), MAX_VALUES);
+        assert_eq!(suggestions[0], (MAX_VALUES + 4).to_string());
+
+        let path = std::env::temp_dir().join(format!("hac-headers-{}.json", std::process::id()));
+        history.save(&path).unwrap();
+        assert_eq!(HeaderHistory::load(&path), history);
+        std::fs::remove_file(path).unwrap();
+    }
+}
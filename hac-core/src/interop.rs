@@ -0,0 +1,307 @@
+mod bruno;
+mod hurl;
+mod openapi;
+mod rest_client;
+mod wsdl;
+
+use crate::collection::types::{
+    BodyType, Environment, ExampleResponse, HeaderMap, Info, Request, RequestKind, RequestMethod,
+};
+use crate::collection::Collection;
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+/// name of the example that holds the status a request file expects
+const EXPECTED_EXAMPLE: &str = "expected";
+
+/// plain text request files that other tools keep on repositories, which
+/// collections can be imported from and exported to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RequestFileFormat {
+    /// `.http` or `.rest` files of the VS Code REST Client extension
+    RestClient,
+    Hurl,
+}
+
+impl RequestFileFormat {
+    /// guesses the format of a file by its extension
+    pub fn from_path(path: &Path) -> Option<RequestFileFormat> {
+        match path.extension()?.to_str()? {
+            "http" | "rest" => Some(RequestFileFormat::RestClient),
+            "hurl" => Some(RequestFileFormat::Hurl),
+            _ => None,
+        }
+    }
+}
+
+/// what is read from a request file before becoming a collection
+#[derive(Debug, Default)]
+struct RequestFile {
+    requests: Vec<Request>,
+    variables: BTreeMap<String, String>,
+}
+
+/// builds a collection with every request of the file, variables declared
+/// on it become an environment named `default`
+pub fn import(contents: &str, format: RequestFileFormat, name: &str) -> anyhow::Result<Collection> {
+    let file = match format {
+        RequestFileFormat::RestClient => rest_client::parse(contents)?,
+        RequestFileFormat::Hurl => hurl::parse(contents)?,
+    };
+
+    let environments = match file.variables.is_empty() {
+        true => vec![],
+        false => vec![Environment {
+            name: "default".into(),
+            variables: file.variables,
+        }],
+    };
+    let requests = file
+        .requests
+        .into_iter()
+        .map(|request| RequestKind::Single(Arc::new(RwLock::new(request))))
+        .collect();
+
+    Ok(Collection {
+        info: Info {
+            name: name.into(),
+            description: None,
+            read_only: false,
+        },
+        requests: Some(Arc::new(RwLock::new(requests))),
+        environments,
+        path: Default::default(),
+        unlocked: false,
+        protos: vec![],
+        highlights: vec![],
+        template: None,
+        format_on_save: false,
+        synced: Default::default(),
+    })
+}
+
+/// builds a collection out of the directory of a bruno collection, its
+/// folders become directories and its environments are imported as well
+pub fn import_bruno(dir: &Path) -> anyhow::Result<Collection> {
+    let (name, requests, environments) = bruno::import(dir)?;
+
+    Ok(Collection {
+        info: Info {
+            name,
+            description: None,
+            read_only: false,
+        },
+        requests: Some(Arc::new(RwLock::new(requests))),
+        environments,
+        path: Default::default(),
+        unlocked: false,
+        protos: vec![],
+        highlights: vec![],
+        template: None,
+        format_on_save: false,
+        synced: Default::default(),
+    })
+}
+
+/// builds a collection with a request for every operation of the SOAP
+/// bindings of a WSDL, named after its service when it has one
+pub fn import_wsdl(contents: &str, name: &str) -> anyhow::Result<Collection> {
+    let service = wsdl::parse(contents)?;
+
+    Ok(Collection {
+        info: Info {
+            name: service.name.unwrap_or_else(|| name.into()),
+            description: None,
+            read_only: false,
+        },
+        requests: Some(Arc::new(RwLock::new(service.requests))),
+        environments: vec![],
+        path: Default::default(),
+        unlocked: false,
+        protos: vec![],
+        highlights: vec![],
+        template: None,
+        format_on_save: false,
+        synced: Default::default(),
+    })
+}
+
+/// builds a collection with a request for every operation of an OpenAPI
+/// document, the url of its first server becomes the `baseUrl` variable of
+/// an environment named `default`
+pub fn import_openapi(contents: &str, name: &str) -> anyhow::Result<Collection> {
+    let api = openapi::parse(contents)?;
+
+    let environments = match api.variables.is_empty() {
+        true => vec![],
+        false => vec![Environment {
+            name: "default".into(),
+            variables: api.variables,
+        }],
+    };
+
+    Ok(Collection {
+        info: Info {
+            name: api.name.unwrap_or_else(|| name.into()),
+            description: None,
+            read_only: false,
+        },
+        requests: Some(Arc::new(RwLock::new(api.requests))),
+        environments,
+        path: Default::default(),
+        unlocked: false,
+        protos: vec![],
+        highlights: vec![],
+        template: None,
+        format_on_save: false,
+        synced: Default::default(),
+    })
+}
+
+/// writes the collection as a bruno collection on the directory, creating it
+/// when needed. Files of requests with the same name are overwritten
+pub fn export_bruno(collection: &Collection, dir: &Path) -> anyhow::Result<()> {
+    let requests = collection
+        .requests
+        .as_ref()
+        .map(|requests| requests.read().unwrap().clone())
+        .unwrap_or_default();
+
+    for (path, contents) in
+        bruno::export_files(&collection.info.name, &requests, &collection.environments)
+    {
+        let path = dir.join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, contents)?;
+    }
+
+    Ok(())
+}
+
+/// renders every request of the collection in the given format, directories
+/// are flattened with their name prepended to the names of their requests.
+/// The variables of the environment are declared on the file, when given
+pub fn export(
+    collection: &Collection,
+    environment: Option<&Environment>,
+    format: RequestFileFormat,
+) -> String {
+    let mut requests = vec![];
+    if let Some(items) = collection.requests.as_ref() {
+        flatten(&items.read().unwrap(), None, &mut requests);
+    }
+    let variables = environment
+        .map(|env| env.variables.clone())
+        .unwrap_or_default();
+
+    match format {
+        RequestFileFormat::RestClient => rest_client::render(&requests, &variables),
+        RequestFileFormat::Hurl => hurl::render(&requests, &variables),
+    }
+}
+
+fn flatten(items: &[RequestKind], dir: Option<&str>, requests: &mut Vec<Request>) {
+    items.iter().for_each(|item| match item {
+        RequestKind::Single(req) => {
+            let mut request = req.read().unwrap().clone();
+            if let Some(dir) = dir {
+                request.name = format!("{dir} / {}", request.name);
+            }
+            requests.push(request);
+        }
+        RequestKind::Nested(dir) => {
+            flatten(&dir.requests.read().unwrap(), Some(&dir.name), requests)
+        }
+    });
+}
+
+fn parse_method(method: &str) -> Option<RequestMethod> {
+    RequestMethod::iter()
+        .find(|candidate| candidate.to_string().eq_ignore_ascii_case(method))
+        .cloned()
+}
+
+fn new_request(
+    name: String,
+    method: RequestMethod,
+    uri: String,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+) -> Request {
+    Request {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        method,
+        uri,
+        headers: (!headers.is_empty()).then(|| {
+            headers
+                .into_iter()
+                .map(|pair| HeaderMap {
+                    pair,
+                    enabled: true,
+                })
+                .collect()
+        }),
+        body_type: body.as_ref().map(|_| BodyType::Json),
+        body,
+        ..Default::default()
+    }
+}
+
+/// a name for requests the file didn't name, like `GET /users`
+fn default_name(method: &RequestMethod, uri: &str) -> String {
+    let path = uri
+        .split_once("://")
+        .map(|(_, rest)| rest.find('/').map(|idx| &rest[idx..]).unwrap_or("/"))
+        .unwrap_or(uri);
+    format!("{method} {path}")
+}
+
+/// the body without the blank lines around it, or none when it is empty
+fn trim_body(lines: &[&str]) -> Option<String> {
+    let body = lines.join("\n");
+    let body = body.trim_matches('\n').trim_end();
+    (!body.is_empty()).then(|| body.to_string())
+}
+
+/// assertions have no equivalent on hac, so they are kept on the
+/// documentation of the request inside a code block tagged with the format
+/// they came from, and written back when exporting to it
+fn append_asserts(docs: &mut String, tag: &str, asserts: &[String]) {
+    if asserts.is_empty() {
+        return;
+    }
+    if !docs.is_empty() {
+        docs.push_str("\n\n");
+    }
+    docs.push_str(&format!("```{tag}\n{}\n```", asserts.join("\n")));
+}
+
+/// splits the documentation of a request into its prose and the assertions
+/// kept by `append_asserts`
+fn split_docs<'a>(docs: &'a str, tag: &str) -> (&'a str, Option<&'a str>) {
+    let fence = format!("```{tag}\n");
+    let Some(start) = docs.find(&fence) else {
+        return (docs.trim_end(), None);
+    };
+    let asserts = &docs[start + fence.len()..];
+    let asserts = asserts
+        .find("\n```")
+        .map(|end| &asserts[..end])
+        .unwrap_or(asserts);
+    (docs[..start].trim_end(), Some(asserts.trim()))
+}
+
+/// the example holding the expected status, or the first one when the
+/// request wasn't imported
+fn expected_example(request: &Request) -> Option<&ExampleResponse> {
+    request
+        .examples
+        .iter()
+        .find(|example| example.name.eq(EXPECTED_EXAMPLE))
+        .or(request.examples.first())
+}
@@ -0,0 +1,510 @@
+use crate::collection::types::{
+    Directory, Environment, ExampleResponse, HeaderMap, Request, RequestKind,
+};
+use crate::interop::{
+    append_asserts, default_name, expected_example, new_request, parse_method, split_docs,
+    trim_body, EXPECTED_EXAMPLE,
+};
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+/// tag of the block assertions are kept in
+const ASSERTS_TAG: &str = "bru";
+/// file bruno uses to tell a directory is a collection
+const COLLECTION_FILE: &str = "bruno.json";
+const ENVIRONMENTS_DIR: &str = "environments";
+
+/// a `name {` or `name [` block of a `.bru` file, with its lines dedented
+#[derive(Debug, PartialEq)]
+struct Block {
+    name: String,
+    lines: Vec<String>,
+}
+
+fn parse_blocks(contents: &str) -> Vec<Block> {
+    let mut blocks = vec![];
+    let mut current: Option<Block> = None;
+
+    for line in contents.lines() {
+        match current.as_mut() {
+            Some(_) if line.eq("}") || line.eq("]") => blocks.extend(current.take()),
+            Some(block) => block
+                .lines
+                .push(line.strip_prefix("  ").unwrap_or(line).to_string()),
+            None => {
+                let name = line
+                    .strip_suffix(" {")
+                    .or_else(|| line.strip_suffix(" ["))
+                    .filter(|name| !name.is_empty() && !name.starts_with(' '));
+                current = name.map(|name| Block {
+                    name: name.to_string(),
+                    lines: vec![],
+                });
+            }
+        }
+    }
+
+    blocks
+}
+
+/// `key: value` lines of a block, disabled ones are prefixed by `~`
+fn dictionary(block: &Block) -> Vec<(String, String, bool)> {
+    block
+        .lines
+        .iter()
+        .filter_map(|line| {
+            let (key, value) = line.trim().split_once(':')?;
+            let (key, enabled) = match key.strip_prefix('~') {
+                Some(key) => (key, false),
+                None => (key, true),
+            };
+            Some((key.trim().to_string(), value.trim().to_string(), enabled))
+        })
+        .collect()
+}
+
+/// contents of a block holding text, like a body or the documentation
+fn text(block: &Block) -> Option<String> {
+    trim_body(&block.lines.iter().map(String::as_str).collect::<Vec<_>>())
+}
+
+fn find<'a>(blocks: &'a [Block], name: &str) -> Option<&'a Block> {
+    blocks.iter().find(|block| block.name.eq(name))
+}
+
+/// parses a request file, returning its position on the collection along
+/// with it. Requests that are not http, like graphql ones, are skipped
+fn parse_request(contents: &str) -> anyhow::Result<Option<(usize, Request)>> {
+    let blocks = parse_blocks(contents);
+    let meta = find(&blocks, "meta").map(dictionary).unwrap_or_default();
+    let meta = |key: &str| {
+        meta.iter()
+            .find(|(name, _, _)| name.eq(key))
+            .map(|(_, value, _)| value.clone())
+    };
+    if meta("type").is_some_and(|kind| kind.ne("http")) {
+        return Ok(None);
+    }
+
+    let Some((method, method_block)) = blocks
+        .iter()
+        .find_map(|block| parse_method(&block.name).map(|method| (method, block)))
+    else {
+        anyhow::bail!("no supported request method was found");
+    };
+    let uri = dictionary(method_block)
+        .into_iter()
+        .find(|(key, _, _)| key.eq("url"))
+        .map(|(_, value, _)| value)
+        .unwrap_or_default();
+
+    let headers = find(&blocks, "headers").map(dictionary).unwrap_or_default();
+    let mut body = ["body:json", "body:text", "body:xml"]
+        .iter()
+        .find_map(|name| find(&blocks, name))
+        .and_then(text);
+    let mut form_content_type = None;
+    if let Some(form) = find(&blocks, "body:form-urlencoded") {
+        let pairs = dictionary(form)
+            .into_iter()
+            .filter(|(_, _, enabled)| *enabled)
+            .map(|(key, value, _)| format!("{key}={value}"))
+            .collect::<Vec<_>>();
+        body = Some(pairs.join("&"));
+        form_content_type = Some((
+            "Content-Type".to_string(),
+            "application/x-www-form-urlencoded".to_string(),
+            true,
+        ));
+    }
+
+    let name = meta("name").unwrap_or_else(|| default_name(&method, &uri));
+    let mut request = new_request(name, method, uri, vec![], body);
+    let headers = headers
+        .into_iter()
+        .chain(form_content_type)
+        .map(|(key, value, enabled)| HeaderMap {
+            pair: (key, value),
+            enabled,
+        })
+        .collect::<Vec<_>>();
+    request.headers = (!headers.is_empty()).then_some(headers);
+
+    let mut docs = find(&blocks, "docs").and_then(text).unwrap_or_default();
+    let mut asserts = vec![];
+    for (key, value, enabled) in find(&blocks, "assert").map(dictionary).unwrap_or_default() {
+        let status = value
+            .strip_prefix("eq ")
+            .and_then(|status| status.parse().ok());
+        match (key.as_str(), status) {
+            ("res.status", Some(status)) if enabled => request.examples.push(ExampleResponse {
+                name: EXPECTED_EXAMPLE.into(),
+                status,
+                headers: vec![],
+                body: None,
+            }),
+            _ => asserts.push(format!("{}{key}: {value}", if enabled { "" } else { "~" })),
+        }
+    }
+    append_asserts(&mut docs, ASSERTS_TAG, &asserts);
+    request.docs = (!docs.is_empty()).then_some(docs);
+
+    let seq = meta("seq")
+        .and_then(|seq| seq.parse().ok())
+        .unwrap_or(usize::MAX);
+    Ok(Some((seq, request)))
+}
+
+fn render_request(request: &Request, seq: usize) -> String {
+    let mut out = format!(
+        "meta {{\n  name: {}\n  type: http\n  seq: {seq}\n}}\n",
+        request.name
+    );
+
+    let body = request.body.as_ref().filter(|body| !body.is_empty());
+    let body_kind = body.map(
+        |body| match serde_json::from_str::<serde_json::Value>(body) {
+            Ok(_) => "json",
+            Err(_) => "text",
+        },
+    );
+    out.push_str(&format!(
+        "\n{} {{\n  url: {}\n  body: {}\n  auth: none\n}}\n",
+        request.method.to_string().to_lowercase(),
+        request.uri,
+        body_kind.unwrap_or("none"),
+    ));
+
+    if let Some(headers) = request
+        .headers
+        .as_ref()
+        .filter(|headers| !headers.is_empty())
+    {
+        out.push_str("\nheaders {\n");
+        for header in headers {
+            let disabled = if header.enabled { "" } else { "~" };
+            out.push_str(&format!(
+                "  {disabled}{}: {}\n",
+                header.pair.0, header.pair.1
+            ));
+        }
+        out.push_str("}\n");
+    }
+
+    if let (Some(body), Some(kind)) = (body, body_kind) {
+        out.push_str(&format!("\nbody:{kind} {{\n{}\n}}\n", indent(body)));
+    }
+
+    let (docs, asserts) = split_docs(request.docs.as_deref().unwrap_or_default(), ASSERTS_TAG);
+    let status =
+        expected_example(request).map(|example| format!("res.status: eq {}", example.status));
+    let asserts = status
+        .into_iter()
+        .chain(
+            asserts
+                .into_iter()
+                .flat_map(|asserts| asserts.lines().map(String::from)),
+        )
+        .collect::<Vec<_>>();
+    if !asserts.is_empty() {
+        out.push_str(&format!(
+            "\nassert {{\n{}\n}}\n",
+            indent(&asserts.join("\n"))
+        ));
+    }
+
+    if !docs.is_empty() {
+        out.push_str(&format!("\ndocs {{\n{}\n}}\n", indent(docs)));
+    }
+
+    out
+}
+
+fn indent(text: &str) -> String {
+    text.lines()
+        .map(|line| match line.is_empty() {
+            true => String::default(),
+            false => format!("  {line}"),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// an environment file, secret variables have their values stored outside
+/// of the collection, so they are imported empty
+fn parse_environment(contents: &str, name: &str) -> Environment {
+    let blocks = parse_blocks(contents);
+    let mut variables = find(&blocks, "vars")
+        .map(dictionary)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(key, value, _)| (key, value))
+        .collect::<BTreeMap<_, _>>();
+    if let Some(secrets) = find(&blocks, "vars:secret") {
+        secrets
+            .lines
+            .iter()
+            .map(|line| line.trim().trim_end_matches(',').trim_start_matches('~'))
+            .filter(|line| !line.is_empty())
+            .for_each(|secret| {
+                variables.entry(secret.to_string()).or_default();
+            });
+    }
+
+    Environment {
+        name: name.to_string(),
+        variables,
+    }
+}
+
+fn render_environment(environment: &Environment) -> String {
+    let vars = environment
+        .variables
+        .iter()
+        .map(|(name, value)| format!("  {name}: {value}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("vars {{\n{vars}\n}}\n")
+}
+
+/// reads every request on the directory and the ones below it, sorted by
+/// their position. Requests of nested directories are flattened into the
+/// outermost one, with the name of their directory prepended
+fn read_requests(dir: &Path, prefix: Option<&str>) -> anyhow::Result<Vec<(usize, RequestKind)>> {
+    let mut items = vec![];
+
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+
+        if path.is_dir() {
+            if file_name.starts_with('.') || (prefix.is_none() && file_name.eq(ENVIRONMENTS_DIR)) {
+                continue;
+            }
+            let name = match prefix {
+                Some(prefix) => format!("{prefix} / {file_name}"),
+                None => file_name.clone(),
+            };
+            let children = read_requests(&path, Some(&name))?;
+            match prefix {
+                Some(_) => items.extend(children),
+                None => {
+                    let requests = children.into_iter().map(|(_, item)| item).collect();
+                    items.push((
+                        usize::MAX,
+                        RequestKind::Nested(Directory {
+                            id: uuid::Uuid::new_v4().to_string(),
+                            name: file_name,
+                            requests: Arc::new(RwLock::new(requests)),
+                            docs: None,
+                        }),
+                    ));
+                }
+            }
+            continue;
+        }
+
+        // folder.bru and collection.bru hold settings, not requests
+        if path.extension().is_none_or(|ext| ext.ne("bru"))
+            || matches!(file_name.as_str(), "folder.bru" | "collection.bru")
+        {
+            continue;
+        }
+
+        let contents = std::fs::read_to_string(&path)?;
+        let Some((seq, mut request)) = parse_request(&contents)
+            .map_err(|e| anyhow::anyhow!("{}: {e}", path.to_string_lossy()))?
+        else {
+            continue;
+        };
+        if let Some(prefix) = prefix.filter(|prefix| prefix.contains(" / ")) {
+            let (_, nested) = prefix.split_once(" / ").unwrap_or_default();
+            request.name = format!("{nested} / {}", request.name);
+        }
+        items.push((seq, RequestKind::Single(Arc::new(RwLock::new(request)))));
+    }
+
+    items.sort_by(|(a_seq, a), (b_seq, b)| a_seq.cmp(b_seq).then(a.get_name().cmp(&b.get_name())));
+    Ok(items)
+}
+
+/// the name and contents of every request and environment file of a bruno
+/// collection, relative to its directory
+pub(super) fn export_files(
+    name: &str,
+    requests: &[RequestKind],
+    environments: &[Environment],
+) -> Vec<(String, String)> {
+    let manifest = serde_json::json!({
+        "version": "1",
+        "name": name,
+        "type": "collection",
+        "ignore": ["node_modules", ".git"],
+    });
+    let mut files = vec![(
+        COLLECTION_FILE.to_string(),
+        serde_json::to_string_pretty(&manifest).unwrap_or_default(),
+    )];
+
+    let mut push_requests = |dir: Option<&str>, requests: &[RequestKind]| {
+        let mut seq = 0;
+        for item in requests {
+            let RequestKind::Single(request) = item else {
+                continue;
+            };
+            seq += 1;
+            let request = request.read().unwrap();
+            let file_name = format!("{}.bru", file_name(&request.name));
+            let path = match dir {
+                Some(dir) => format!("{}/{file_name}", self::file_name(dir)),
+                None => file_name,
+            };
+            files.push((path, render_request(&request, seq)));
+        }
+    };
+
+    push_requests(None, requests);
+    for item in requests {
+        if let RequestKind::Nested(dir) = item {
+            push_requests(Some(&dir.name), &dir.requests.read().unwrap());
+        }
+    }
+
+    for environment in environments {
+        files.push((
+            format!("{ENVIRONMENTS_DIR}/{}.bru", file_name(&environment.name)),
+            render_environment(environment),
+        ));
+    }
+
+    files
+}
+
+/// removes the characters file systems don't allow on file names
+fn file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c => c,
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+pub(super) fn import(dir: &Path) -> anyhow::Result<(String, Vec<RequestKind>, Vec<Environment>)> {
+    let manifest = std::fs::read_to_string(dir.join(COLLECTION_FILE)).map_err(|_| {
+        anyhow::anyhow!(
+            "{:?} has no {COLLECTION_FILE}, it is not a bruno collection",
+            dir
+        )
+    })?;
+    let manifest: serde_json::Value = serde_json::from_str(&manifest)?;
+    let name = manifest
+        .get("name")
+        .and_then(|name| name.as_str())
+        .map(String::from)
+        .or_else(|| {
+            dir.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .unwrap_or_default();
+
+    let requests = read_requests(dir, None)?
+        .into_iter()
+        .map(|(_, item)| item)
+        .collect();
+
+    let mut environments = vec![];
+    if let Ok(entries) = std::fs::read_dir(dir.join(ENVIRONMENTS_DIR)) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext.ne("bru")) {
+                continue;
+            }
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            environments.push(parse_environment(&std::fs::read_to_string(&path)?, &name));
+        }
+    }
+    environments.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok((name, requests, environments))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::types::RequestMethod;
+
+    #[test]
+    fn test_request_round_trip() {
+        let contents = r#"meta {
+  name: Create user
+  type: http
+  seq: 2
+}
+
+post {
+  url: {{host}}/users
+  body: json
+  auth: none
+}
+
+headers {
+  Content-Type: application/json
+  ~X-Debug: 1
+}
+
+body:json {
+  {
+    "name": "john"
+  }
+}
+
+assert {
+  res.status: eq 201
+  res.body.id: isNumber
+}
+
+docs {
+  creates a user
+
+  the id is generated
+}
+"#;
+        let (seq, request) = parse_request(contents).unwrap().unwrap();
+        assert_eq!(seq, 2);
+        assert_eq!(request.name, "Create user");
+        assert_eq!(request.method, RequestMethod::Post);
+        assert_eq!(request.uri, "{{host}}/users");
+        assert_eq!(request.body.as_deref(), Some("{\n  \"name\": \"john\"\n}"));
+        let headers = request.headers.as_ref().unwrap();
+        assert!(headers[0].enabled && !headers[1].enabled);
+        assert_eq!(request.examples[0].status, 201);
+        assert_eq!(
+            request.docs.as_deref(),
+            Some("creates a user\n\nthe id is generated\n\n```bru\nres.body.id: isNumber\n```")
+        );
+
+        assert_eq!(render_request(&request, 2), contents);
+        assert!(parse_request("meta {\n  type: graphql\n}\n")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_environment_round_trip() {
+        let contents = "vars {\n  host: http://localhost\n}\nvars:secret [\n  token\n]\n";
+        let environment = parse_environment(contents, "dev");
+        assert_eq!(environment.variables["host"], "http://localhost");
+        assert_eq!(environment.variables["token"], "");
+
+        let rendered = render_environment(&environment);
+        assert_eq!(parse_environment(&rendered, "dev"), environment);
+    }
+}
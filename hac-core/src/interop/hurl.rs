@@ -0,0 +1,382 @@
+use crate::collection::types::{ExampleResponse, Request};
+use crate::interop::{
+    append_asserts, default_name, expected_example, new_request, parse_method, split_docs,
+    trim_body, RequestFile, EXPECTED_EXAMPLE,
+};
+
+use std::collections::BTreeMap;
+
+use base64::Engine;
+
+/// tag of the block assertions and captures are kept in
+const ASSERTS_TAG: &str = "hurl";
+
+#[derive(Debug, Default)]
+struct Entry {
+    comments: Vec<String>,
+    method_line: (usize, String),
+    headers: Vec<(String, String)>,
+    query: Vec<String>,
+    form: Vec<String>,
+    body: Vec<String>,
+    status: Option<u16>,
+    response_headers: Vec<(String, String)>,
+    response_body: Vec<String>,
+    asserts: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Part {
+    Request,
+    Response,
+}
+
+/// parses a hurl file. Comments before an entry name and document it, the
+/// status it expects is stored as an example, and `variable` options become
+/// variables
+pub(super) fn parse(contents: &str) -> anyhow::Result<RequestFile> {
+    let mut file = RequestFile::default();
+    let mut entries: Vec<Entry> = vec![];
+    let mut comments = vec![];
+    let mut part = Part::Request;
+    let mut section: Option<String> = None;
+    let mut fenced = false;
+
+    for (idx, raw) in contents.lines().enumerate() {
+        let line = raw.trim();
+
+        if fenced {
+            fenced = !line.starts_with("```");
+            if let (true, Some(entry)) = (fenced, entries.last_mut()) {
+                match part {
+                    Part::Request => entry.body.push(raw.into()),
+                    Part::Response => entry.response_body.push(raw.into()),
+                }
+            }
+            continue;
+        }
+
+        if let Some(comment) = line.strip_prefix('#') {
+            comments.push(comment.trim().to_string());
+            continue;
+        }
+        if is_method_line(line) {
+            entries.push(Entry {
+                comments: std::mem::take(&mut comments),
+                method_line: (idx, line.into()),
+                ..Default::default()
+            });
+            part = Part::Request;
+            section = None;
+            continue;
+        }
+
+        let Some(entry) = entries.last_mut() else {
+            if line.is_empty() {
+                continue;
+            }
+            anyhow::bail!(
+                "line {}: expected a request, like `GET https://...`",
+                idx + 1
+            );
+        };
+
+        // comments followed by more lines of an entry are not about the next
+        // one, so only the ones right before a request are kept
+        if !line.is_empty() {
+            comments.clear();
+        }
+
+        if let Some(status) = line
+            .strip_prefix("HTTP")
+            .filter(|rest| rest.starts_with([' ', '/']))
+        {
+            part = Part::Response;
+            section = None;
+            entry.status = status
+                .split_whitespace()
+                .last()
+                .and_then(|s| s.parse().ok());
+            continue;
+        }
+
+        let body_started = match part {
+            Part::Request => !entry.body.is_empty(),
+            Part::Response => !entry.response_body.is_empty(),
+        };
+
+        if !body_started {
+            if line.is_empty() {
+                continue;
+            }
+            if line.starts_with('[') && line.ends_with(']') {
+                section = Some(line[1..line.len() - 1].to_string());
+                if part.eq(&Part::Response) {
+                    entry.asserts.push(line.into());
+                }
+                continue;
+            }
+            if line.starts_with("```") {
+                fenced = true;
+                continue;
+            }
+
+            if let Some((key, value)) = key_value(line) {
+                match (part, section.as_deref()) {
+                    (Part::Request, None) => entry.headers.push((key, value)),
+                    (Part::Request, Some("QueryStringParams" | "Query")) => {
+                        entry.query.push(format!("{key}={value}"))
+                    }
+                    (Part::Request, Some("FormParams" | "Form")) => {
+                        entry.form.push(format!("{key}={value}"))
+                    }
+                    (Part::Request, Some("BasicAuth")) => {
+                        let token = base64::engine::general_purpose::STANDARD
+                            .encode(format!("{key}:{value}"));
+                        entry
+                            .headers
+                            .push(("Authorization".into(), format!("Basic {token}")));
+                    }
+                    (Part::Request, Some("Options")) if key.eq("variable") => {
+                        if let Some((name, value)) = value.split_once('=') {
+                            file.variables
+                                .insert(name.trim().into(), value.trim().into());
+                        }
+                    }
+                    (Part::Request, Some(_)) => {}
+                    (Part::Response, None) => entry.response_headers.push((key, value)),
+                    (Part::Response, Some(_)) => entry.asserts.push(line.into()),
+                }
+                continue;
+            }
+            if part.eq(&Part::Response) && section.is_some() {
+                entry.asserts.push(line.into());
+                continue;
+            }
+        }
+
+        match part {
+            Part::Request => entry.body.push(raw.into()),
+            Part::Response => entry.response_body.push(raw.into()),
+        }
+    }
+
+    for entry in entries {
+        file.requests.push(into_request(entry)?);
+    }
+
+    Ok(file)
+}
+
+/// whether the line starts a new entry, like `GET https://example.com`
+fn is_method_line(line: &str) -> bool {
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(uri)) = (parts.next(), parts.next()) else {
+        return false;
+    };
+    method.chars().all(|c| c.is_ascii_uppercase())
+        && method.ne("HTTP")
+        && (uri.contains("://") || uri.starts_with("{{"))
+}
+
+/// a `key: value` line, where the key has no whitespace or is quoted
+fn key_value(line: &str) -> Option<(String, String)> {
+    let (key, value) = line.split_once(':')?;
+    let key = key.trim();
+    let key = match key.strip_prefix('"').and_then(|key| key.strip_suffix('"')) {
+        Some(quoted) => quoted,
+        None if key.is_empty() || key.contains(char::is_whitespace) => return None,
+        None => key,
+    };
+    Some((key.to_string(), value.trim().to_string()))
+}
+
+fn into_request(entry: Entry) -> anyhow::Result<Request> {
+    let (idx, method_line) = entry.method_line;
+    let mut parts = method_line.split_whitespace();
+    let method = parts
+        .next()
+        .and_then(parse_method)
+        .ok_or_else(|| anyhow::anyhow!("line {}: unsupported method", idx + 1))?;
+    let mut uri = parts.next().unwrap_or_default().to_string();
+
+    if !entry.query.is_empty() {
+        let separator = if uri.contains('?') { '&' } else { '?' };
+        uri = format!("{uri}{separator}{}", entry.query.join("&"));
+    }
+
+    let mut headers = entry.headers;
+    let body_lines = entry.body.iter().map(String::as_str).collect::<Vec<_>>();
+    let body = match entry.form.is_empty() {
+        true => trim_body(&body_lines),
+        false => {
+            headers.push((
+                "Content-Type".into(),
+                "application/x-www-form-urlencoded".into(),
+            ));
+            Some(entry.form.join("&"))
+        }
+    };
+
+    let mut comments = entry.comments.into_iter();
+    let name = comments
+        .next()
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| default_name(&method, &uri));
+    let mut docs = comments.collect::<Vec<_>>().join("\n");
+    append_asserts(&mut docs, ASSERTS_TAG, &entry.asserts);
+
+    let mut request = new_request(name, method, uri, headers, body);
+    request.docs = (!docs.is_empty()).then_some(docs);
+    if let Some(status) = entry.status {
+        let response_body = entry
+            .response_body
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        request.examples.push(ExampleResponse {
+            name: EXPECTED_EXAMPLE.into(),
+            status,
+            headers: entry.response_headers,
+            body: trim_body(&response_body),
+        });
+    }
+
+    Ok(request)
+}
+
+pub(super) fn render(requests: &[Request], variables: &BTreeMap<String, String>) -> String {
+    let mut out = String::new();
+
+    for (idx, request) in requests.iter().enumerate() {
+        if idx > 0 {
+            out.push('\n');
+        }
+
+        let (docs, asserts) = split_docs(request.docs.as_deref().unwrap_or_default(), ASSERTS_TAG);
+        out.push_str(&format!("# {}\n", request.name));
+        for line in docs.lines() {
+            match line.is_empty() {
+                true => out.push_str("#\n"),
+                false => out.push_str(&format!("# {line}\n")),
+            }
+        }
+
+        out.push_str(&format!("{} {}\n", request.method, request.uri));
+        for header in request.headers.iter().flatten().filter(|h| h.enabled) {
+            out.push_str(&format!("{}: {}\n", header.pair.0, header.pair.1));
+        }
+
+        // variables only have to be declared once, they are kept for the
+        // entries that follow
+        if idx == 0 && !variables.is_empty() {
+            out.push_str("[Options]\n");
+            for (name, value) in variables {
+                out.push_str(&format!("variable: {name}={value}\n"));
+            }
+        }
+
+        if let Some(body) = request.body.as_ref().filter(|body| !body.is_empty()) {
+            match serde_json::from_str::<serde_json::Value>(body) {
+                Ok(_) => out.push_str(&format!("{body}\n")),
+                Err(_) => out.push_str(&format!("```\n{body}\n```\n")),
+            }
+        }
+
+        let expected = expected_example(request);
+        match (expected, asserts) {
+            (None, None) => {}
+            (expected, asserts) => {
+                match expected {
+                    Some(expected) => out.push_str(&format!("\nHTTP {}\n", expected.status)),
+                    None => out.push_str("\nHTTP *\n"),
+                }
+                if let Some(asserts) = asserts {
+                    out.push_str(&format!("{asserts}\n"));
+                }
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::types::RequestMethod;
+
+    #[test]
+    fn test_round_trip() {
+        let contents = r#"# login
+# exchanges credentials for a token
+POST https://{{host}}/login
+Accept: application/json
+[Options]
+variable: host=localhost:3000
+{
+  "user": "john"
+}
+HTTP 200
+[Captures]
+token: jsonpath "$.token"
+[Asserts]
+jsonpath "$.token" exists
+
+GET https://{{host}}/users
+[QueryStringParams]
+page: 1
+[BasicAuth]
+john: secret
+HTTP/1.1 200
+Content-Type: application/json
+```
+[]
+```
+
+PUT https://{{host}}/users/1
+[FormParams]
+name: john
+"#;
+        let file = parse(contents).unwrap();
+        assert_eq!(file.variables["host"], "localhost:3000");
+        assert_eq!(file.requests.len(), 3);
+
+        let login = &file.requests[0];
+        assert_eq!(login.name, "login");
+        assert_eq!(login.body.as_deref(), Some("{\n  \"user\": \"john\"\n}"));
+        assert_eq!(login.examples[0].status, 200);
+        assert_eq!(
+            login.docs.as_deref(),
+            Some("exchanges credentials for a token\n\n```hurl\n[Captures]\ntoken: jsonpath \"$.token\"\n[Asserts]\njsonpath \"$.token\" exists\n```")
+        );
+
+        let users = &file.requests[1];
+        assert_eq!(users.name, "GET /users?page=1");
+        assert_eq!(users.uri, "https://{{host}}/users?page=1");
+        assert_eq!(
+            users.headers.as_ref().unwrap()[0].pair.1,
+            "Basic am9objpzZWNyZXQ="
+        );
+        assert_eq!(users.examples[0].body.as_deref(), Some("[]"));
+        assert_eq!(users.examples[0].headers.len(), 1);
+
+        let update = &file.requests[2];
+        assert_eq!(update.method, RequestMethod::Put);
+        assert_eq!(update.body.as_deref(), Some("name=john"));
+        assert!(update.examples.is_empty());
+
+        let rendered = render(&file.requests, &file.variables);
+        let reparsed = parse(&rendered).unwrap();
+        assert_eq!(reparsed.variables, file.variables);
+        for (left, right) in file.requests.iter().zip(reparsed.requests.iter()) {
+            assert_eq!(
+                (&left.name, &left.uri, &left.body, &left.docs),
+                (&right.name, &right.uri, &right.body, &right.docs)
+            );
+        }
+
+        assert!(parse("HEAD https://example.com").is_err());
+        assert!(parse("not a request").is_err());
+    }
+}
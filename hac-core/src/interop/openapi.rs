@@ -0,0 +1,179 @@
+use crate::collection::types::{Request, RequestKind, RequestMethod};
+use crate::interop::{new_request, parse_method};
+use crate::json_schema;
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+use serde_json::Value;
+
+/// variable holding the url of the first server of the document
+const BASE_URL: &str = "baseUrl";
+
+/// what is read from an OpenAPI document before becoming a collection
+pub(super) struct Api {
+    pub name: Option<String>,
+    pub requests: Vec<RequestKind>,
+    pub variables: BTreeMap<String, String>,
+}
+
+/// parses an OpenAPI 3 document written as JSON, every operation becomes a
+/// request whose body is the example of its JSON request body, either the
+/// one the document declares or one built from its schema. Path parameters
+/// become variables, like `/users/{{id}}`
+pub(super) fn parse(contents: &str) -> anyhow::Result<Api> {
+    let document = serde_json::from_str::<Value>(contents)
+        .map_err(|e| anyhow::anyhow!("expected an OpenAPI document written as JSON: {e}"))?;
+    if document.get("openapi").is_none() {
+        anyhow::bail!("expected an OpenAPI 3 document, it has no `openapi` version");
+    }
+
+    let mut requests = vec![];
+    let paths = document.get("paths").and_then(Value::as_object);
+    for (path, item) in paths.into_iter().flatten() {
+        let Some(item) = item.as_object() else {
+            continue;
+        };
+        for (method, operation) in item {
+            let Some(method) = parse_method(method) else {
+                continue;
+            };
+            requests.push(request(&document, path, method, operation));
+        }
+    }
+
+    let mut variables = BTreeMap::new();
+    if let Some(url) = document.pointer("/servers/0/url").and_then(Value::as_str) {
+        variables.insert(BASE_URL.into(), url.trim_end_matches('/').into());
+    }
+
+    Ok(Api {
+        name: document
+            .pointer("/info/title")
+            .and_then(Value::as_str)
+            .map(String::from),
+        requests: requests
+            .into_iter()
+            .map(|request| RequestKind::Single(Arc::new(RwLock::new(request))))
+            .collect(),
+        variables,
+    })
+}
+
+fn request(document: &Value, path: &str, method: RequestMethod, operation: &Value) -> Request {
+    let name = operation
+        .get("summary")
+        .or_else(|| operation.get("operationId"))
+        .and_then(Value::as_str)
+        .map(String::from)
+        .unwrap_or_else(|| format!("{method} {path}"));
+    let uri = format!(
+        "{{{{{BASE_URL}}}}}{}",
+        path.replace('{', "{{").replace('}', "}}")
+    );
+
+    let body = json_body(document, operation)
+        .map(|body| serde_json::to_string_pretty(&body).expect("json values always serialize"));
+    let headers = match body.is_some() {
+        true => vec![("Content-Type".into(), "application/json".into())],
+        false => vec![],
+    };
+
+    let mut request = new_request(name, method, uri, headers, body);
+    request.docs = operation
+        .get("description")
+        .and_then(Value::as_str)
+        .map(String::from);
+    request
+}
+
+/// the example of the JSON request body of the operation, as declared by
+/// the document or built from its schema
+fn json_body(document: &Value, operation: &Value) -> Option<Value> {
+    let mut body = operation.get("requestBody")?;
+    if let Some(reference) = body.get("$ref").and_then(Value::as_str) {
+        body = document.pointer(reference.strip_prefix('#')?)?;
+    }
+    let content = body.get("content")?.as_object()?;
+    let (_, media) = content
+        .iter()
+        .find(|(media_type, _)| media_type.contains("json"))?;
+
+    let declared = media.get("example").cloned().or_else(|| {
+        media
+            .get("examples")?
+            .as_object()?
+            .values()
+            .find_map(|example| example.get("value").cloned())
+    });
+    declared.or_else(|| Some(json_schema::example(media.get("schema")?, document)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let contents = r##"{
+            "openapi": "3.0.3",
+            "info": { "title": "Users API" },
+            "servers": [{ "url": "https://api.example.com/v1/" }],
+            "paths": {
+                "/users": {
+                    "post": {
+                        "summary": "create user",
+                        "requestBody": {
+                            "content": {
+                                "application/json": {
+                                    "schema": { "$ref": "#/components/schemas/NewUser" }
+                                }
+                            }
+                        }
+                    }
+                },
+                "/users/{id}": {
+                    "parameters": [],
+                    "get": { "operationId": "getUser" }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "NewUser": {
+                        "type": "object",
+                        "required": ["email"],
+                        "properties": {
+                            "email": { "type": "string", "format": "email" },
+                            "name": { "type": "string" }
+                        }
+                    }
+                }
+            }
+        }"##;
+
+        let api = parse(contents).unwrap();
+        assert_eq!(api.name.as_deref(), Some("Users API"));
+        assert_eq!(api.variables[BASE_URL], "https://api.example.com/v1");
+
+        let RequestKind::Single(request) = &api.requests[0] else {
+            panic!("expected a request");
+        };
+        let request = request.read().unwrap();
+        assert_eq!(request.name, "create user");
+        assert_eq!(request.uri, "{{baseUrl}}/users");
+        assert_eq!(
+            request.body.as_deref(),
+            Some("{\n  \"email\": \"user@example.com\"\n}")
+        );
+
+        let RequestKind::Single(request) = &api.requests[1] else {
+            panic!("expected a request");
+        };
+        let request = request.read().unwrap();
+        assert_eq!(request.name, "getUser");
+        assert_eq!(request.uri, "{{baseUrl}}/users/{{id}}");
+        assert!(request.body.is_none());
+
+        assert!(parse(r#"{"swagger": "2.0"}"#).is_err());
+    }
+}
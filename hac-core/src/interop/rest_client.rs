@@ -0,0 +1,197 @@
+use crate::collection::types::{Request, RequestMethod};
+use crate::interop::{default_name, new_request, parse_method, trim_body, RequestFile};
+
+use std::collections::BTreeMap;
+
+/// parses a REST Client file. Requests are separated by `###` lines, which
+/// can also name them, as well as `# @name` comments. Other comments before
+/// a request become its documentation, and `@name = value` lines declare
+/// variables
+pub(super) fn parse(contents: &str) -> anyhow::Result<RequestFile> {
+    let mut file = RequestFile::default();
+    let mut lines = contents.lines().enumerate().peekable();
+
+    while lines.peek().is_some() {
+        let mut name = None;
+        let mut docs = vec![];
+        let mut request_line = None;
+
+        // everything up to the request line, or the end of the block when it
+        // has no request at all
+        for (idx, line) in lines.by_ref() {
+            let line = line.trim();
+            if let Some(separator) = line.strip_prefix("###") {
+                let separator = separator.trim();
+                name = (!separator.is_empty()).then(|| separator.to_string());
+                docs.clear();
+                continue;
+            }
+            if line.is_empty() {
+                continue;
+            }
+            if let Some((variable, value)) = line
+                .strip_prefix('@')
+                .and_then(|declaration| declaration.split_once('='))
+            {
+                file.variables
+                    .insert(variable.trim().into(), value.trim().into());
+                continue;
+            }
+            if let Some(comment) = line
+                .strip_prefix('#')
+                .or_else(|| line.strip_prefix("//"))
+                .map(str::trim)
+            {
+                match comment.strip_prefix("@name") {
+                    Some(annotation) => name = Some(annotation.trim().to_string()),
+                    None => docs.push(comment.to_string()),
+                }
+                continue;
+            }
+            request_line = Some((idx, line));
+            break;
+        }
+
+        let Some((idx, request_line)) = request_line else {
+            break;
+        };
+        let (method, mut uri) = parse_request_line(request_line)
+            .ok_or_else(|| anyhow::anyhow!("line {}: unsupported method", idx + 1))?;
+
+        // long query strings can be broken into lines starting with ? or &
+        while let Some((_, line)) = lines.next_if(|(_, line)| {
+            let line = line.trim();
+            line.starts_with('?') || line.starts_with('&')
+        }) {
+            uri.push_str(line.trim());
+        }
+
+        let mut headers = vec![];
+        while let Some((_, line)) =
+            lines.next_if(|(_, line)| !line.trim().is_empty() && !line.starts_with("###"))
+        {
+            let line = line.trim();
+            if line.starts_with('#') || line.starts_with("//") {
+                continue;
+            }
+            if let Some((header, value)) = line.split_once(':') {
+                headers.push((header.trim().to_string(), value.trim().to_string()));
+            }
+        }
+
+        let mut body = vec![];
+        while let Some((_, line)) = lines.next_if(|(_, line)| !line.starts_with("###")) {
+            body.push(line);
+        }
+
+        let name = name.unwrap_or_else(|| default_name(&method, &uri));
+        let mut request = new_request(name, method, uri, headers, trim_body(&body));
+        request.docs = (!docs.is_empty()).then(|| docs.join("\n"));
+        file.requests.push(request);
+    }
+
+    Ok(file)
+}
+
+/// `METHOD uri HTTP/1.1`, where both the method and the version are
+/// optional, requests without a method are GET requests
+fn parse_request_line(line: &str) -> Option<(RequestMethod, String)> {
+    let mut parts = line.split_whitespace().collect::<Vec<_>>();
+    if parts.last().is_some_and(|part| part.starts_with("HTTP/")) {
+        parts.pop();
+    }
+
+    match parts.as_slice() {
+        [uri] => Some((RequestMethod::Get, uri.to_string())),
+        [method, uri] => Some((parse_method(method)?, uri.to_string())),
+        _ => None,
+    }
+}
+
+pub(super) fn render(requests: &[Request], variables: &BTreeMap<String, String>) -> String {
+    let mut out = String::new();
+
+    for (name, value) in variables {
+        out.push_str(&format!("@{name} = {value}\n"));
+    }
+
+    for request in requests {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&format!("### {}\n", request.name));
+        for line in request.docs.iter().flat_map(|docs| docs.lines()) {
+            match line.is_empty() {
+                true => out.push_str("#\n"),
+                false => out.push_str(&format!("# {line}\n")),
+            }
+        }
+        out.push_str(&format!("{} {}\n", request.method, request.uri));
+        for header in request.headers.iter().flatten().filter(|h| h.enabled) {
+            out.push_str(&format!("{}: {}\n", header.pair.0, header.pair.1));
+        }
+        if let Some(body) = request.body.as_ref().filter(|body| !body.is_empty()) {
+            out.push_str(&format!("\n{body}\n"));
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let contents = r#"@host = http://localhost:3000
+@token = abc
+
+### list users
+# every user, paginated
+GET {{host}}/users
+    ?page=1
+    &limit=10
+Authorization: Bearer {{token}}
+
+###
+# @name createUser
+POST {{host}}/users HTTP/1.1
+Content-Type: application/json
+
+{
+  "name": "john"
+}
+
+###
+{{host}}/health
+"#;
+        let file = parse(contents).unwrap();
+        assert_eq!(file.variables["host"], "http://localhost:3000");
+        assert_eq!(file.requests.len(), 3);
+
+        let list = &file.requests[0];
+        assert_eq!(list.name, "list users");
+        assert_eq!(list.uri, "{{host}}/users?page=1&limit=10");
+        assert_eq!(list.docs.as_deref(), Some("every user, paginated"));
+        assert_eq!(list.headers.as_ref().unwrap()[0].pair.1, "Bearer {{token}}");
+
+        let create = &file.requests[1];
+        assert_eq!(create.name, "createUser");
+        assert_eq!(create.method, RequestMethod::Post);
+        assert_eq!(create.body.as_deref(), Some("{\n  \"name\": \"john\"\n}"));
+        assert_eq!(file.requests[2].name, "GET {{host}}/health");
+
+        let rendered = render(&file.requests, &file.variables);
+        let reparsed = parse(&rendered).unwrap();
+        assert_eq!(reparsed.variables, file.variables);
+        for (left, right) in file.requests.iter().zip(reparsed.requests.iter()) {
+            assert_eq!(
+                (&left.name, &left.uri, &left.body, &left.docs),
+                (&right.name, &right.uri, &right.body, &right.docs)
+            );
+        }
+
+        assert!(parse("OPTIONS {{host}}/users").is_err());
+    }
+}
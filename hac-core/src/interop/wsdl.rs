@@ -0,0 +1,606 @@
+use crate::collection::types::{
+    BodyType, Directory, Request, RequestKind, RequestMethod, SoapOperation, SoapVersion,
+};
+use crate::interop::new_request;
+use crate::syntax::xml::{self, Element, Node};
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+const SOAP_11_BINDING: &str = "http://schemas.xmlsoap.org/wsdl/soap/";
+const SOAP_12_BINDING: &str = "http://schemas.xmlsoap.org/wsdl/soap12/";
+const SOAP_11_ENVELOPE: &str = "http://schemas.xmlsoap.org/soap/envelope/";
+const SOAP_12_ENVELOPE: &str = "http://www.w3.org/2003/05/soap-envelope";
+const XML_SCHEMA: &str = "http://www.w3.org/2001/XMLSchema";
+
+/// how deep types are expanded on the envelope templates, so recursive types
+/// don't expand forever
+const MAX_DEPTH: usize = 12;
+
+/// what is read from a WSDL before becoming a collection
+pub(super) struct Service {
+    pub name: Option<String>,
+    pub requests: Vec<RequestKind>,
+}
+
+struct Wsdl<'a> {
+    definitions: &'a Element,
+    namespaces: HashMap<String, String>,
+    target_namespace: String,
+    schemas: Vec<&'a Element>,
+}
+
+/// parses a WSDL 1.1 document, every operation of its SOAP bindings becomes
+/// a request with an envelope template, where values to fill are marked with
+/// `?`. Services with bindings for both SOAP versions get a directory for
+/// each binding
+pub(super) fn parse(contents: &str) -> anyhow::Result<Service> {
+    let document = xml::parse(contents)?;
+    let definitions = &document.root;
+    if definitions.local_name().ne("definitions") {
+        anyhow::bail!("expected a WSDL 1.1 document, found <{}>", definitions.name);
+    }
+
+    let wsdl = Wsdl {
+        definitions,
+        namespaces: definitions.namespaces(),
+        target_namespace: definitions
+            .attribute("targetNamespace")
+            .unwrap_or_default()
+            .to_string(),
+        schemas: definitions
+            .children_named("types")
+            .flat_map(|types| types.children_named("schema"))
+            .collect(),
+    };
+
+    let mut groups = vec![];
+    for binding in definitions.children_named("binding") {
+        let Some(version) = wsdl.soap_version(binding) else {
+            continue;
+        };
+        let requests = binding
+            .children_named("operation")
+            .map(|operation| wsdl.request(binding, operation, version))
+            .collect::<Vec<_>>();
+        let name = binding.attribute("name").unwrap_or_default().to_string();
+        groups.push((name, requests));
+    }
+
+    if groups.is_empty() {
+        anyhow::bail!("the WSDL has no SOAP bindings");
+    }
+
+    let requests = match groups.len() {
+        1 => groups
+            .pop()
+            .unwrap()
+            .1
+            .into_iter()
+            .map(|request| RequestKind::Single(Arc::new(RwLock::new(request))))
+            .collect(),
+        _ => groups
+            .into_iter()
+            .map(|(name, requests)| {
+                let id = uuid::Uuid::new_v4().to_string();
+                let requests = requests
+                    .into_iter()
+                    .map(|mut request| {
+                        request.parent = Some(id.clone());
+                        RequestKind::Single(Arc::new(RwLock::new(request)))
+                    })
+                    .collect();
+                RequestKind::Nested(Directory {
+                    id,
+                    name,
+                    requests: Arc::new(RwLock::new(requests)),
+                    docs: None,
+                })
+            })
+            .collect(),
+    };
+
+    let name = definitions
+        .child("service")
+        .or(Some(definitions))
+        .and_then(|element| element.attribute("name"))
+        .map(String::from);
+
+    Ok(Service { name, requests })
+}
+
+impl<'a> Wsdl<'a> {
+    /// the namespace and local name a qualified name refers to
+    fn resolve(&self, qname: &str) -> (String, String) {
+        let namespace = match xml::prefix(qname) {
+            "" => self.target_namespace.clone(),
+            prefix => self.namespaces.get(prefix).cloned().unwrap_or_default(),
+        };
+        (namespace, xml::local_name(qname).to_string())
+    }
+
+    /// top level WSDL elements, like messages and port types, by name
+    fn find(&self, kind: &str, qname: &str) -> Option<&'a Element> {
+        let name = xml::local_name(qname);
+        self.definitions.elements().find(|element| {
+            element.local_name().eq(kind) && element.attribute("name").eq(&Some(name))
+        })
+    }
+
+    /// declarations of the schemas of the WSDL, the ones on the namespace of
+    /// the name are preferred, as prefixes can be declared anywhere
+    fn find_schema_item(&self, kind: &str, qname: &str) -> Option<(&'a Element, &'a Element)> {
+        let (namespace, name) = self.resolve(qname);
+        let name = name.as_str();
+        let mut candidates = self.schemas.iter().flat_map(|schema| {
+            schema
+                .elements()
+                .filter(move |item| {
+                    item.local_name().eq(kind) && item.attribute("name").eq(&Some(name))
+                })
+                .map(move |item| (*schema, item))
+        });
+        let first = candidates.next()?;
+        Some(
+            std::iter::once(first)
+                .chain(candidates)
+                .find(|(schema, _)| schema.attribute("targetNamespace").eq(&Some(&namespace)))
+                .unwrap_or(first),
+        )
+    }
+
+    fn soap_version(&self, binding: &Element) -> Option<SoapVersion> {
+        let soap_binding = binding.child("binding")?;
+        match self.resolve(&soap_binding.name).0.as_str() {
+            SOAP_11_BINDING => Some(SoapVersion::V11),
+            SOAP_12_BINDING => Some(SoapVersion::V12),
+            _ => None,
+        }
+    }
+
+    fn request(&self, binding: &Element, operation: &Element, version: SoapVersion) -> Request {
+        let name = operation.attribute("name").unwrap_or_default();
+        let soap_operation = operation.child("operation");
+        let action = soap_operation
+            .and_then(|op| op.attribute("soapAction"))
+            .unwrap_or_default()
+            .to_string();
+        let style = soap_operation
+            .and_then(|op| op.attribute("style"))
+            .or(binding
+                .child("binding")
+                .and_then(|soap_binding| soap_binding.attribute("style")))
+            .unwrap_or("document");
+        let rpc_namespace = operation
+            .child("input")
+            .and_then(|input| input.child("body"))
+            .and_then(|body| body.attribute("namespace"))
+            .unwrap_or(&self.target_namespace);
+
+        let port_operation = binding
+            .attribute("type")
+            .and_then(|port_type| self.find("portType", port_type))
+            .and_then(|port_type| {
+                port_type
+                    .children_named("operation")
+                    .find(|op| op.attribute("name").eq(&Some(name)))
+            });
+        let parts = port_operation
+            .and_then(|op| op.child("input"))
+            .and_then(|input| input.attribute("message"))
+            .and_then(|message| self.find("message", message))
+            .map(|message| message.children_named("part").collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        let mut envelope = Envelope::default();
+        let mut body = vec![];
+        match style {
+            "rpc" => {
+                let prefix = envelope.prefix_for(rpc_namespace);
+                let mut wrapper = Element {
+                    name: format!("{prefix}:{name}"),
+                    ..Default::default()
+                };
+                for part in parts {
+                    wrapper
+                        .children
+                        .extend(self.part(part, &mut envelope, false));
+                }
+                body.push(Node::Element(wrapper));
+            }
+            _ => {
+                for part in parts {
+                    body.extend(self.part(part, &mut envelope, true));
+                }
+            }
+        }
+
+        let mut request = new_request(
+            name.to_string(),
+            RequestMethod::Post,
+            self.address(binding).unwrap_or_default(),
+            vec![],
+            Some(envelope.render(version, body)),
+        );
+        request.body_type = Some(BodyType::Xml);
+        request.soap = Some(SoapOperation { action, version });
+        request.docs = port_operation
+            .and_then(|op| op.child("documentation"))
+            .map(|docs| docs.text().trim().to_string())
+            .filter(|docs| !docs.is_empty());
+        request
+    }
+
+    /// where the service is served for the binding
+    fn address(&self, binding: &Element) -> Option<String> {
+        let name = binding.attribute("name")?;
+        self.definitions
+            .children_named("service")
+            .flat_map(|service| service.children_named("port"))
+            .find(|port| {
+                port.attribute("binding")
+                    .is_some_and(|port_binding| xml::local_name(port_binding).eq(name))
+            })
+            .and_then(|port| port.child("address"))
+            .and_then(|address| address.attribute("location"))
+            .map(String::from)
+    }
+
+    /// the elements a message part becomes on the body. Parts pointing to an
+    /// element are written as that element, parts with a type are named
+    /// after the part
+    fn part(&self, part: &Element, envelope: &mut Envelope, qualified: bool) -> Vec<Node> {
+        let name = part.attribute("name").unwrap_or_default();
+        if let Some(element) = part.attribute("element") {
+            return match self.find_schema_item("element", element) {
+                Some((schema, declaration)) => {
+                    self.element(schema, declaration, true, envelope, &mut vec![])
+                }
+                None => {
+                    let (namespace, name) = self.resolve(element);
+                    let prefix = envelope.prefix_for(&namespace);
+                    vec![placeholder(format!("{prefix}:{name}"))]
+                }
+            };
+        }
+
+        let name = match qualified {
+            true => format!("{}:{name}", envelope.prefix_for(&self.target_namespace)),
+            false => name.to_string(),
+        };
+        let mut element = Element {
+            name,
+            ..Default::default()
+        };
+        element.children = match part.attribute("type") {
+            Some(kind) => self.type_content(kind, envelope, &mut vec![]),
+            None => vec![Node::Text("?".into())],
+        };
+        vec![Node::Element(element)]
+    }
+
+    /// an element declaration of a schema written as it would appear on the
+    /// body, preceded by a comment when it is optional
+    fn element(
+        &self,
+        schema: &Element,
+        declaration: &Element,
+        global: bool,
+        envelope: &mut Envelope,
+        stack: &mut Vec<String>,
+    ) -> Vec<Node> {
+        if let Some(reference) = declaration.attribute("ref") {
+            let mut nodes = optional_comment(declaration);
+            if let Some((schema, referenced)) = self.find_schema_item("element", reference) {
+                nodes.extend(self.element(schema, referenced, true, envelope, stack));
+            }
+            return nodes;
+        }
+
+        let name = declaration.attribute("name").unwrap_or_default();
+        let qualified = global
+            || schema
+                .attribute("elementFormDefault")
+                .eq(&Some("qualified"));
+        let name = match qualified {
+            true => {
+                let namespace = schema.attribute("targetNamespace").unwrap_or_default();
+                format!("{}:{name}", envelope.prefix_for(namespace))
+            }
+            false => name.to_string(),
+        };
+
+        let children = match (
+            declaration.child("complexType"),
+            declaration.attribute("type"),
+        ) {
+            _ if stack.len() >= MAX_DEPTH => vec![],
+            (Some(complex_type), _) => self.complex_content(schema, complex_type, envelope, stack),
+            (None, Some(kind)) => self.type_content(kind, envelope, stack),
+            (None, None) => vec![Node::Text("?".into())],
+        };
+
+        let mut nodes = match global {
+            true => vec![],
+            false => optional_comment(declaration),
+        };
+        nodes.push(Node::Element(Element {
+            name,
+            attributes: vec![],
+            children,
+        }));
+        nodes
+    }
+
+    /// the contents of an element of the given type, simple types are
+    /// placeholders and complex types list their elements
+    fn type_content(
+        &self,
+        kind: &str,
+        envelope: &mut Envelope,
+        stack: &mut Vec<String>,
+    ) -> Vec<Node> {
+        let (namespace, name) = self.resolve(kind);
+        if namespace.eq(XML_SCHEMA) || stack.contains(&name) {
+            return vec![Node::Text("?".into())];
+        }
+
+        match self.find_schema_item("complexType", kind) {
+            Some((schema, complex_type)) => {
+                stack.push(name);
+                let content = self.complex_content(schema, complex_type, envelope, stack);
+                stack.pop();
+                content
+            }
+            None => vec![Node::Text("?".into())],
+        }
+    }
+
+    fn complex_content(
+        &self,
+        schema: &Element,
+        complex_type: &Element,
+        envelope: &mut Envelope,
+        stack: &mut Vec<String>,
+    ) -> Vec<Node> {
+        if complex_type.child("simpleContent").is_some() {
+            return vec![Node::Text("?".into())];
+        }
+
+        let mut nodes = vec![];
+        if let Some(extension) = complex_type
+            .child("complexContent")
+            .and_then(|content| content.child("extension").or(content.child("restriction")))
+        {
+            if let Some(base) = extension.attribute("base") {
+                nodes.extend(
+                    self.type_content(base, envelope, stack)
+                        .into_iter()
+                        .filter(|node| !matches!(node, Node::Text(_))),
+                );
+            }
+            nodes.extend(self.particles(schema, extension, envelope, stack));
+            return nodes;
+        }
+
+        nodes.extend(self.particles(schema, complex_type, envelope, stack));
+        nodes
+    }
+
+    /// elements of the sequences, alls and choices of a type. Only the first
+    /// alternative of a choice is written
+    fn particles(
+        &self,
+        schema: &Element,
+        parent: &Element,
+        envelope: &mut Envelope,
+        stack: &mut Vec<String>,
+    ) -> Vec<Node> {
+        let mut nodes = vec![];
+        for child in parent.elements() {
+            match child.local_name() {
+                "sequence" | "all" => nodes.extend(self.particles(schema, child, envelope, stack)),
+                "choice" => {
+                    if let Some(first) = child.elements().next() {
+                        let choice = Element {
+                            children: vec![Node::Element(first.clone())],
+                            ..Default::default()
+                        };
+                        nodes.push(Node::Comment("You have a choice of the next item".into()));
+                        nodes.extend(self.particles(schema, &choice, envelope, stack));
+                    }
+                }
+                "element" => nodes.extend(self.element(schema, child, false, envelope, stack)),
+                _ => {}
+            }
+        }
+        nodes
+    }
+}
+
+/// namespaces used by an envelope, each one is declared once on the envelope
+/// with a short prefix
+#[derive(Default)]
+struct Envelope {
+    prefixes: Vec<(String, String)>,
+}
+
+impl Envelope {
+    fn prefix_for(&mut self, namespace: &str) -> String {
+        if let Some((_, prefix)) = self.prefixes.iter().find(|(ns, _)| ns.eq(namespace)) {
+            return prefix.clone();
+        }
+        let prefix = match self.prefixes.len() {
+            0 => "tns".to_string(),
+            n => format!("ns{}", n + 1),
+        };
+        self.prefixes.push((namespace.to_string(), prefix.clone()));
+        prefix
+    }
+
+    fn render(&self, version: SoapVersion, body: Vec<Node>) -> String {
+        let envelope_namespace = match version {
+            SoapVersion::V11 => SOAP_11_ENVELOPE,
+            SoapVersion::V12 => SOAP_12_ENVELOPE,
+        };
+        let mut attributes = vec![("xmlns:soapenv".to_string(), envelope_namespace.to_string())];
+        attributes.extend(
+            self.prefixes
+                .iter()
+                .map(|(namespace, prefix)| (format!("xmlns:{prefix}"), namespace.clone())),
+        );
+
+        xml::render(&Element {
+            name: "soapenv:Envelope".into(),
+            attributes,
+            children: vec![
+                Node::Element(Element {
+                    name: "soapenv:Header".into(),
+                    ..Default::default()
+                }),
+                Node::Element(Element {
+                    name: "soapenv:Body".into(),
+                    attributes: vec![],
+                    children: body,
+                }),
+            ],
+        })
+    }
+}
+
+fn placeholder(name: String) -> Node {
+    Node::Element(Element {
+        name,
+        attributes: vec![],
+        children: vec![Node::Text("?".into())],
+    })
+}
+
+fn optional_comment(declaration: &Element) -> Vec<Node> {
+    match declaration.attribute("minOccurs") {
+        Some("0") => vec![Node::Comment("Optional:".into())],
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WSDL: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<wsdl:definitions name="Shop" targetNamespace="urn:shop"
+    xmlns:wsdl="http://schemas.xmlsoap.org/wsdl/"
+    xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/"
+    xmlns:soap12="http://schemas.xmlsoap.org/wsdl/soap12/"
+    xmlns:xs="http://www.w3.org/2001/XMLSchema"
+    xmlns:tns="urn:shop">
+  <wsdl:types>
+    <xs:schema targetNamespace="urn:shop" elementFormDefault="qualified">
+      <xs:complexType name="Item">
+        <xs:sequence>
+          <xs:element name="sku" type="xs:string"/>
+          <xs:element name="quantity" type="xs:int" minOccurs="0"/>
+        </xs:sequence>
+      </xs:complexType>
+      <xs:element name="GetPrice">
+        <xs:complexType>
+          <xs:sequence>
+            <xs:element name="item" type="tns:Item"/>
+          </xs:sequence>
+        </xs:complexType>
+      </xs:element>
+    </xs:schema>
+  </wsdl:types>
+  <wsdl:message name="GetPriceRequest">
+    <wsdl:part name="parameters" element="tns:GetPrice"/>
+  </wsdl:message>
+  <wsdl:portType name="ShopPort">
+    <wsdl:operation name="GetPrice">
+      <wsdl:documentation>price of an item</wsdl:documentation>
+      <wsdl:input message="tns:GetPriceRequest"/>
+    </wsdl:operation>
+  </wsdl:portType>
+  <wsdl:binding name="ShopSoap" type="tns:ShopPort">
+    <soap:binding transport="http://schemas.xmlsoap.org/soap/http"/>
+    <wsdl:operation name="GetPrice">
+      <soap:operation soapAction="urn:shop/GetPrice"/>
+      <wsdl:input><soap:body use="literal"/></wsdl:input>
+    </wsdl:operation>
+  </wsdl:binding>
+  <wsdl:binding name="ShopSoap12" type="tns:ShopPort">
+    <soap12:binding transport="http://schemas.xmlsoap.org/soap/http"/>
+    <wsdl:operation name="GetPrice">
+      <soap12:operation soapAction="urn:shop/GetPrice"/>
+      <wsdl:input><soap12:body use="literal"/></wsdl:input>
+    </wsdl:operation>
+  </wsdl:binding>
+  <wsdl:service name="ShopService">
+    <wsdl:port name="ShopSoap" binding="tns:ShopSoap">
+      <soap:address location="http://localhost:8080/shop"/>
+    </wsdl:port>
+  </wsdl:service>
+</wsdl:definitions>"#;
+
+    #[test]
+    fn test_parse() {
+        let service = parse(WSDL).unwrap();
+        assert_eq!(service.name.as_deref(), Some("ShopService"));
+        assert_eq!(service.requests.len(), 2);
+
+        let RequestKind::Nested(dir) = &service.requests[0] else {
+            panic!("expected a directory for each binding");
+        };
+        assert_eq!(dir.name, "ShopSoap");
+        let requests = dir.requests.read().unwrap();
+        let RequestKind::Single(request) = &requests[0] else {
+            panic!("expected a request");
+        };
+        let request = request.read().unwrap();
+
+        assert_eq!(request.name, "GetPrice");
+        assert_eq!(request.uri, "http://localhost:8080/shop");
+        assert_eq!(request.docs.as_deref(), Some("price of an item"));
+        assert_eq!(request.parent, Some(dir.id.clone()));
+        assert_eq!(
+            request.soap,
+            Some(SoapOperation {
+                action: "urn:shop/GetPrice".into(),
+                version: SoapVersion::V11,
+            })
+        );
+        assert_eq!(
+            request.body.as_deref(),
+            Some(
+                r#"<soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/" xmlns:tns="urn:shop">
+  <soapenv:Header/>
+  <soapenv:Body>
+    <tns:GetPrice>
+      <tns:item>
+        <tns:sku>?</tns:sku>
+        <!--Optional:-->
+        <tns:quantity>?</tns:quantity>
+      </tns:item>
+    </tns:GetPrice>
+  </soapenv:Body>
+</soapenv:Envelope>"#
+            )
+        );
+
+        let RequestKind::Nested(dir) = &service.requests[1] else {
+            panic!("expected a directory for each binding");
+        };
+        let requests = dir.requests.read().unwrap();
+        let RequestKind::Single(request) = &requests[0] else {
+            panic!("expected a request");
+        };
+        let request = request.read().unwrap();
+        assert_eq!(request.uri, "");
+        assert!(request
+            .body
+            .as_ref()
+            .unwrap()
+            .contains("http://www.w3.org/2003/05/soap-envelope"));
+
+        assert!(parse("<html></html>").is_err());
+    }
+}
@@ -0,0 +1,499 @@
+use crate::collection::environment::resolve_request;
+use crate::collection::session;
+use crate::collection::types::Request;
+use crate::monitor;
+use crate::net::curl_config::CurlDefaults;
+use crate::net::request_manager::{prepare_request, send_request, Response};
+use crate::net::request_strategies::http_strategy::HttpResponse;
+use crate::protobuf::Schema;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::mpsc::UnboundedSender;
+
+/// how much of a response body is shown on the table of results
+const BODY_PREVIEW_LEN: usize = 40;
+
+/// a row of the data file, the name of each column is the variable its
+/// value is bound to
+pub type Row = BTreeMap<String, String>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DataFormat {
+    /// the first line names the columns, every other line is a row
+    Csv,
+    /// an array of objects, each object is a row
+    Json,
+}
+
+impl DataFormat {
+    pub fn from_path(path: &Path) -> Option<DataFormat> {
+        let extension = path.extension()?.to_string_lossy().to_ascii_lowercase();
+        match extension.as_str() {
+            "csv" => Some(DataFormat::Csv),
+            "json" => Some(DataFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// reads every row of a data file
+pub fn parse_rows(contents: &str, format: DataFormat) -> anyhow::Result<Vec<Row>> {
+    match format {
+        DataFormat::Csv => parse_csv(contents),
+        DataFormat::Json => parse_json(contents),
+    }
+}
+
+fn parse_json(contents: &str) -> anyhow::Result<Vec<Row>> {
+    let value = serde_json::from_str::<serde_json::Value>(contents)?;
+    let Some(items) = value.as_array() else {
+        anyhow::bail!("expected an array of objects, one for each iteration");
+    };
+
+    items
+        .iter()
+        .enumerate()
+        .map(|(idx, item)| {
+            let Some(item) = item.as_object() else {
+                anyhow::bail!("item {} is not an object", idx + 1);
+            };
+            Ok(item
+                .iter()
+                .map(|(name, value)| {
+                    let value = match value {
+                        serde_json::Value::String(value) => value.clone(),
+                        value => value.to_string(),
+                    };
+                    (name.clone(), value)
+                })
+                .collect())
+        })
+        .collect()
+}
+
+fn parse_csv(contents: &str) -> anyhow::Result<Vec<Row>> {
+    let mut records = csv_records(contents)?.into_iter();
+    let Some(columns) = records.next() else {
+        return Ok(vec![]);
+    };
+
+    records
+        .enumerate()
+        .filter(|(_, record)| !record.iter().all(String::is_empty))
+        .map(|(idx, record)| {
+            if record.len().ne(&columns.len()) {
+                anyhow::bail!(
+                    "line {} has {} values but there are {} columns",
+                    idx + 2,
+                    record.len(),
+                    columns.len()
+                );
+            }
+            Ok(columns.iter().cloned().zip(record).collect())
+        })
+        .collect()
+}
+
+/// splits a csv file into records, fields can be quoted to hold commas,
+/// line breaks and quotes, which are escaped by doubling them
+pub fn csv_records(contents: &str) -> anyhow::Result<Vec<Vec<String>>> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = contents.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek().eq(&Some(&'"')) => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            c => field.push(c),
+        }
+    }
+
+    if quoted {
+        anyhow::bail!("a quoted value is never closed");
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+/// what happened when the request was sent with the variables of a row
+#[derive(Debug, PartialEq)]
+pub struct IterationResult {
+    /// position of the row on the data file, starting at 1
+    pub row: usize,
+    /// name of the request sent
+    pub request: String,
+    pub variables: Row,
+    pub status: Option<reqwest::StatusCode>,
+    pub duration: Duration,
+    pub body: Option<String>,
+    /// values the captures of the request took out of the response
+    pub captured: BTreeMap<String, String>,
+    /// why the response isn't what the request expects, either because it
+    /// failed or because it doesn't pass the checks of its monitor
+    pub failure: Option<String>,
+}
+
+impl IterationResult {
+    fn new(row: usize, variables: Row, request: &Request, response: Response) -> Self {
+        let failure = match request.monitor.as_ref() {
+            Some(monitor) => monitor::check(monitor, &response).err(),
+            None => match (response.cause.as_ref(), response.status) {
+                (Some(cause), _) => Some(cause.clone()),
+                (None, Some(status)) if !monitor::is_successful(status) => {
+                    Some(format!("got status {status}"))
+                }
+                (None, Some(_)) => None,
+                (None, None) => Some("no status code on response".into()),
+            },
+        };
+
+        IterationResult {
+            row,
+            request: request.name.clone(),
+            variables,
+            captured: session::capture(&request.captures, &response),
+            status: response.status,
+            duration: response.duration,
+            body: response.body,
+            failure,
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+/// sends the request once for every row, one after the other, with the
+/// values of the row layered on top of the given variables. Results are
+/// sent back as each request finishes and the channel closes after the last
+pub fn run_iteration(
+    request: Request,
+    rows: Vec<Row>,
+    variables: BTreeMap<String, String>,
+    config: &hac_config::Config,
+    protobuf: Option<Arc<Schema>>,
+    result_tx: UnboundedSender<IterationResult>,
+) {
+    let curl = CurlDefaults::load(&config.curl);
+    let mut request = prepare_request(request, config);
+    curl.authorize(&mut request);
+    let rate_limit = config.rate_limit.clone();
+    let offline = config.offline;
+    let strategy = curl
+        .client(&config.connection, &config.pins)
+        .map(|client| HttpResponse {
+            client,
+            protobuf,
+            progress_tx: None,
+            pins: config.pins.clone(),
+            body_limit: None,
+        });
+
+    tokio::spawn(async move {
+        for (idx, row) in rows.into_iter().enumerate() {
+            let mut row_variables = variables.clone();
+            row_variables.extend(row.clone());
+            let request = resolve_request(&request, &row_variables);
+            let response = match strategy.as_ref() {
+                Ok(strategy) => send_request(request.clone(), strategy, &rate_limit, offline).await,
+                Err(e) => {
+                    Response::from_error(format!("failed to apply the settings of .curlrc: {e}"))
+                }
+            };
+            if result_tx
+                .send(IterationResult::new(idx + 1, row, &request, response))
+                .is_err()
+            {
+                tracing::warn!("stopped iterating as nobody is waiting for the results");
+                return;
+            }
+        }
+    });
+}
+
+/// renders the results as a table, with a column for each variable of the
+/// data file followed by the status, time and the start of the body
+pub fn render_results(results: &[IterationResult]) -> String {
+    let mut columns = vec!["#".to_string()];
+    let variables = results
+        .iter()
+        .flat_map(|result| result.variables.keys().cloned())
+        .collect::<BTreeSet<_>>();
+    columns.extend(variables.iter().cloned());
+    columns.extend(["status", "time", "body"].map(String::from));
+
+    let rows = results
+        .iter()
+        .map(|result| {
+            let mut row = vec![result.row.to_string()];
+            row.extend(
+                variables
+                    .iter()
+                    .map(|name| result.variables.get(name).cloned().unwrap_or_default()),
+            );
+            row.push(match result.status {
+                Some(status) => status.as_u16().to_string(),
+                None => "error".into(),
+            });
+            row.push(format!("{}ms", result.duration.as_millis()));
+            row.push(preview(
+                result
+                    .failure
+                    .as_deref()
+                    .or(result.body.as_deref())
+                    .unwrap_or_default(),
+            ));
+            row
+        })
+        .collect::<Vec<_>>();
+
+    let widths = columns
+        .iter()
+        .enumerate()
+        .map(|(idx, column)| {
+            rows.iter()
+                .map(|row| row[idx].chars().count())
+                .chain([column.chars().count()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    let mut table = String::new();
+    for row in [columns].iter().chain(rows.iter()) {
+        let line = row
+            .iter()
+            .zip(widths.iter())
+            .map(|(value, width)| format!("{value:width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        table.push_str(line.trim_end());
+        table.push('\n');
+    }
+
+    let passed = results.iter().filter(|result| result.is_success()).count();
+    table.push_str(&format!("\n{passed} of {} succeeded\n", results.len()));
+    table
+}
+
+/// the results as a csv or json file, so they can be analyzed elsewhere.
+/// Every result has its row, request, status, duration in milliseconds,
+/// whether it passed and why it failed, along with the variables of the row
+/// and the values captured from the response
+pub fn export_results(results: &[IterationResult], format: DataFormat) -> String {
+    match format {
+        DataFormat::Csv => export_csv(results),
+        DataFormat::Json => export_json(results),
+    }
+}
+
+fn export_json(results: &[IterationResult]) -> String {
+    let results = results
+        .iter()
+        .map(|result| {
+            serde_json::json!({
+                "row": result.row,
+                "request": result.request,
+                "status": result.status.map(|status| status.as_u16()),
+                "durationMs": result.duration.as_millis() as u64,
+                "passed": result.is_success(),
+                "failure": result.failure,
+                "variables": result.variables,
+                "captured": result.captured,
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_string_pretty(&results).expect("json values always serialize")
+}
+
+fn export_csv(results: &[IterationResult]) -> String {
+    let variables = results
+        .iter()
+        .flat_map(|result| result.variables.keys())
+        .collect::<BTreeSet<_>>();
+    let captured = results
+        .iter()
+        .flat_map(|result| result.captured.keys())
+        .collect::<BTreeSet<_>>();
+
+    let mut columns = [
+        "row",
+        "request",
+        "status",
+        "durationMs",
+        "passed",
+        "failure",
+    ]
+    .map(String::from)
+    .to_vec();
+    columns.extend(variables.iter().map(|name| name.to_string()));
+    columns.extend(captured.iter().map(|name| format!("captured.{name}")));
+
+    let mut csv = csv_line(&columns);
+    for result in results {
+        let mut values = vec![
+            result.row.to_string(),
+            result.request.clone(),
+            result
+                .status
+                .map(|status| status.as_u16().to_string())
+                .unwrap_or_default(),
+            result.duration.as_millis().to_string(),
+            result.is_success().to_string(),
+            result.failure.clone().unwrap_or_default(),
+        ];
+        values.extend(
+            variables
+                .iter()
+                .map(|name| result.variables.get(*name).cloned().unwrap_or_default()),
+        );
+        values.extend(
+            captured
+                .iter()
+                .map(|name| result.captured.get(*name).cloned().unwrap_or_default()),
+        );
+        csv.push_str(&csv_line(&values));
+    }
+    csv
+}
+
+/// a line of a csv file, quoting the values that need it
+fn csv_line(values: &[String]) -> String {
+    let values = values
+        .iter()
+        .map(|value| match value.contains([',', '"', '\n', '\r']) {
+            true => format!("\"{}\"", value.replace('"', "\"\"")),
+            false => value.clone(),
+        })
+        .collect::<Vec<_>>();
+    format!("{}\n", values.join(","))
+}
+
+/// the start of a body on a single line
+fn preview(body: &str) -> String {
+    let body = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    match body.chars().count() > BODY_PREVIEW_LEN {
+        true => format!(
+            "{}...",
+            body.chars().take(BODY_PREVIEW_LEN).collect::<String>()
+        ),
+        false => body,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rows() {
+        let csv = "id,name\n1,\"Doe, John\"\r\n2,\"say \"\"hi\"\"\"\n\n";
+        let rows = parse_rows(csv, DataFormat::Csv).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["id"], "1");
+        assert_eq!(rows[0]["name"], "Doe, John");
+        assert_eq!(rows[1]["name"], "say \"hi\"");
+        assert!(parse_rows("id,name\n1", DataFormat::Csv).is_err());
+        assert!(parse_rows("id\n\"1", DataFormat::Csv).is_err());
+
+        let json = r#"[{"id": 1, "name": "john", "admin": true}, {"id": 2}]"#;
+        let rows = parse_rows(json, DataFormat::Json).unwrap();
+        assert_eq!(rows[0]["id"], "1");
+        assert_eq!(rows[0]["name"], "john");
+        assert_eq!(rows[0]["admin"], "true");
+        assert_eq!(rows[1].len(), 1);
+        assert!(parse_rows(r#"{"id": 1}"#, DataFormat::Json).is_err());
+        assert!(parse_rows(r#"[1]"#, DataFormat::Json).is_err());
+    }
+
+    fn results() -> Vec<IterationResult> {
+        vec![
+            IterationResult {
+                row: 1,
+                request: "get user".into(),
+                variables: Row::from([("id".to_string(), "1".to_string())]),
+                status: Some(reqwest::StatusCode::OK),
+                duration: Duration::from_millis(12),
+                body: Some("{\n  \"id\": 1\n}".into()),
+                captured: BTreeMap::from([("user_id".to_string(), "1".to_string())]),
+                failure: None,
+            },
+            IterationResult {
+                row: 2,
+                request: "get user".into(),
+                variables: Row::from([("id".to_string(), "20".to_string())]),
+                status: None,
+                duration: Duration::default(),
+                body: None,
+                captured: BTreeMap::default(),
+                failure: Some("connection refused, \"retry\"".into()),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_render_results() {
+        let results = results();
+        assert_eq!(
+            render_results(&results),
+            [
+                "#  id  status  time  body",
+                "1  1   200     12ms  { \"id\": 1 }",
+                "2  20  error   0ms   connection refused, \"retry\"",
+                "",
+                "1 of 2 succeeded",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_export_results() {
+        let results = results();
+        let csv = export_results(&results, DataFormat::Csv);
+        assert_eq!(
+            csv,
+            [
+                "row,request,status,durationMs,passed,failure,id,captured.user_id",
+                "1,get user,200,12,true,,1,1",
+                "2,get user,,0,false,\"connection refused, \"\"retry\"\"\",20,",
+                "",
+            ]
+            .join("\n")
+        );
+        // exported csv files can be used as data files again
+        let rows = parse_rows(&csv, DataFormat::Csv).unwrap();
+        assert_eq!(rows[1]["failure"], "connection refused, \"retry\"");
+
+        let json = export_results(&results, DataFormat::Json);
+        let json = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+        assert_eq!(json[0]["status"], 200);
+        assert_eq!(json[0]["passed"], true);
+        assert_eq!(json[0]["captured"]["user_id"], "1");
+        assert_eq!(json[1]["status"], serde_json::Value::Null);
+        assert_eq!(json[1]["variables"]["id"], "20");
+    }
+}
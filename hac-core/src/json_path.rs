@@ -0,0 +1,276 @@
+use serde_json::Value;
+
+/// a step of a JSONPath expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    /// `.name` or `['name']`
+    Key(String),
+    /// `[2]`, negative indexes count from the end of the array
+    Index(i64),
+    /// `.*` or `[*]`, every child of the value
+    Wildcard,
+    /// `..name` or `..*`, the step applied to the value and every value
+    /// nested in it
+    Descendant(Box<Segment>),
+}
+
+/// parses the subset of JSONPath that points to values, like
+/// `$.data.items[0].id`, `$..id` or `$.meta['next cursor']`. Filters and
+/// slices are not supported
+pub fn parse(path: &str) -> anyhow::Result<Vec<Segment>> {
+    let path = path.trim();
+    let rest = path.strip_prefix('$').unwrap_or(path);
+    let chars = rest.chars().collect::<Vec<_>>();
+    let mut segments = vec![];
+    let mut idx = 0;
+
+    // paths like `data.id` are read as if they started with `$.`
+    if chars.first().is_some_and(|c| !matches!(c, '.' | '[')) {
+        let (segment, next) = parse_name(&chars, 0, path)?;
+        segments.push(segment);
+        idx = next;
+    }
+
+    while idx < chars.len() {
+        match chars[idx] {
+            '.' if chars.get(idx + 1).eq(&Some(&'.')) => {
+                let (segment, next) = match chars.get(idx + 2) {
+                    Some('[') => parse_bracket(&chars, idx + 2, path)?,
+                    _ => parse_name(&chars, idx + 2, path)?,
+                };
+                segments.push(Segment::Descendant(Box::new(segment)));
+                idx = next;
+            }
+            '.' => {
+                let (segment, next) = parse_name(&chars, idx + 1, path)?;
+                segments.push(segment);
+                idx = next;
+            }
+            '[' => {
+                let (segment, next) = parse_bracket(&chars, idx, path)?;
+                segments.push(segment);
+                idx = next;
+            }
+            c => anyhow::bail!("unexpected {c:?} on {path}"),
+        }
+    }
+
+    Ok(segments)
+}
+
+fn parse_name(chars: &[char], start: usize, path: &str) -> anyhow::Result<(Segment, usize)> {
+    let end = chars[start..]
+        .iter()
+        .position(|c| matches!(c, '.' | '['))
+        .map(|len| start + len)
+        .unwrap_or(chars.len());
+    let name = chars[start..end].iter().collect::<String>();
+    match name.as_str() {
+        "" => anyhow::bail!("missing a name on {path}"),
+        "*" => Ok((Segment::Wildcard, end)),
+        _ => Ok((Segment::Key(name), end)),
+    }
+}
+
+fn parse_bracket(chars: &[char], start: usize, path: &str) -> anyhow::Result<(Segment, usize)> {
+    let inner_start = start + 1;
+    let segment_end = match chars.get(inner_start) {
+        Some(quote @ ('\'' | '"')) => {
+            let close = chars[inner_start + 1..]
+                .iter()
+                .position(|c| c.eq(quote))
+                .map(|len| inner_start + 1 + len)
+                .ok_or_else(|| anyhow::anyhow!("unclosed quote on {path}"))?;
+            if chars.get(close + 1).ne(&Some(&']')) {
+                anyhow::bail!("unclosed bracket on {path}");
+            }
+            let name = chars[inner_start + 1..close].iter().collect::<String>();
+            return Ok((Segment::Key(name), close + 2));
+        }
+        _ => chars[inner_start..]
+            .iter()
+            .position(|c| c.eq(&']'))
+            .map(|len| inner_start + len)
+            .ok_or_else(|| anyhow::anyhow!("unclosed bracket on {path}"))?,
+    };
+
+    let inner = chars[inner_start..segment_end].iter().collect::<String>();
+    let segment = match inner.trim() {
+        "*" => Segment::Wildcard,
+        index => Segment::Index(
+            index
+                .parse()
+                .map_err(|_| anyhow::anyhow!("invalid index {index:?} on {path}"))?,
+        ),
+    };
+    Ok((segment, segment_end + 1))
+}
+
+/// every value the path points to
+pub fn select<'a>(value: &'a Value, path: &str) -> anyhow::Result<Vec<&'a Value>> {
+    let segments = parse(path)?;
+    let mut current = vec![value];
+    for segment in segments.iter() {
+        current = current
+            .into_iter()
+            .flat_map(|value| apply(value, segment))
+            .collect();
+    }
+    Ok(current)
+}
+
+fn apply<'a>(value: &'a Value, segment: &Segment) -> Vec<&'a Value> {
+    match (segment, value) {
+        (Segment::Key(key), Value::Object(map)) => map.get(key).into_iter().collect(),
+        (Segment::Index(index), Value::Array(items)) => {
+            let index = match index.is_negative() {
+                true => items.len() as i64 + index,
+                false => *index,
+            };
+            usize::try_from(index)
+                .ok()
+                .and_then(|index| items.get(index))
+                .into_iter()
+                .collect()
+        }
+        (Segment::Wildcard, Value::Object(map)) => map.values().collect(),
+        (Segment::Wildcard, Value::Array(items)) => items.iter().collect(),
+        (Segment::Descendant(inner), value) => {
+            let mut found = apply(value, inner);
+            let children: Box<dyn Iterator<Item = &Value>> = match value {
+                Value::Object(map) => Box::new(map.values()),
+                Value::Array(items) => Box::new(items.iter()),
+                _ => Box::new(std::iter::empty()),
+            };
+            for child in children {
+                found.extend(apply(child, segment));
+            }
+            found
+        }
+        _ => vec![],
+    }
+}
+
+/// the values of each path on the JSON body, as they are shown to the user.
+/// Strings are shown without quotes, and paths matching many values show
+/// them as an array. Paths that match nothing have no value
+pub fn extract(paths: &[String], body: &str) -> Vec<(String, Option<String>)> {
+    let json = serde_json::from_str::<Value>(body).ok();
+    paths
+        .iter()
+        .map(|path| {
+            let value = json
+                .as_ref()
+                .and_then(|json| select(json, path).ok())
+                .and_then(|values| match values.as_slice() {
+                    [] => None,
+                    [Value::String(value)] => Some(value.clone()),
+                    [value] => Some(value.to_string()),
+                    values => Some(Value::from_iter(values.iter().cloned().cloned()).to_string()),
+                });
+            (path.clone(), value)
+        })
+        .collect()
+}
+
+/// narrows the JSON body down to what the path points to, pretty printed.
+/// A path matching many values shows them as an array
+pub fn filter(body: &str, path: &str) -> anyhow::Result<String> {
+    let json = serde_json::from_str::<Value>(body)?;
+    let values = select(&json, path)?;
+    let filtered = match values.as_slice() {
+        [] => anyhow::bail!("{path} matches nothing"),
+        [value] => serde_json::to_string_pretty(value)?,
+        values => serde_json::to_string_pretty(values)?,
+    };
+    Ok(filtered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("$.data.items[0]['next cursor']").unwrap(),
+            vec![
+                Segment::Key("data".into()),
+                Segment::Key("items".into()),
+                Segment::Index(0),
+                Segment::Key("next cursor".into()),
+            ]
+        );
+        assert_eq!(
+            parse("$..id").unwrap(),
+            vec![Segment::Descendant(Box::new(Segment::Key("id".into())))]
+        );
+        assert_eq!(
+            parse("meta.*").unwrap(),
+            vec![Segment::Key("meta".into()), Segment::Wildcard]
+        );
+        assert_eq!(parse("$").unwrap(), vec![]);
+        assert!(parse("$.data[").is_err());
+        assert!(parse("$.items[first]").is_err());
+        assert!(parse("$.data.").is_err());
+    }
+
+    #[test]
+    fn test_select() {
+        let json = serde_json::json!({
+            "data": {"id": 1, "items": [{"id": 2}, {"id": 3}]},
+            "meta": {"next_cursor": "abc"}
+        });
+
+        let ids = |path: &str| {
+            select(&json, path)
+                .unwrap()
+                .into_iter()
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids("$.data.id"), vec![1]);
+        assert_eq!(ids("$.data.items[-1].id"), vec![3]);
+        assert_eq!(ids("$.data.items[*].id"), vec![2, 3]);
+        assert_eq!(ids("$..id"), vec![1, 2, 3]);
+        assert_eq!(ids("$.data.items[5]"), Vec::<Value>::new());
+    }
+
+    #[test]
+    fn test_extract() {
+        let body = r#"{"data": {"id": 7, "tags": ["a", "b"]}, "meta": {"next_cursor": "abc"}}"#;
+        let paths = [
+            "$.data.id",
+            "$.meta.next_cursor",
+            "$.data.tags[*]",
+            "$.missing",
+        ]
+        .map(String::from);
+
+        assert_eq!(
+            extract(&paths, body),
+            vec![
+                ("$.data.id".to_string(), Some("7".to_string())),
+                ("$.meta.next_cursor".to_string(), Some("abc".to_string())),
+                (
+                    "$.data.tags[*]".to_string(),
+                    Some(r#"["a","b"]"#.to_string())
+                ),
+                ("$.missing".to_string(), None),
+            ]
+        );
+        assert_eq!(extract(&paths[..1], "not json")[0].1, None);
+    }
+
+    #[test]
+    fn test_filter() {
+        let body = r#"{"data": [{"id": 1}, {"id": 2}]}"#;
+        assert_eq!(filter(body, "$.data[0]").unwrap(), "{\n  \"id\": 1\n}");
+        assert_eq!(filter(body, "$..id").unwrap(), "[\n  1,\n  2\n]");
+        assert_eq!(
+            filter(body, "$.missing").unwrap_err().to_string(),
+            "$.missing matches nothing"
+        );
+        assert!(filter("not json", "$").is_err());
+    }
+}
@@ -0,0 +1,251 @@
+use serde_json::{Map, Value};
+
+/// how deep references are followed, so recursive schemas still end
+const MAX_DEPTH: usize = 8;
+
+/// builds an example value out of a JSON Schema, preferring the examples,
+/// defaults and enum values the schema declares. Objects only get their
+/// required properties, or every property when none is required, and
+/// strings with a known format get a value of that format. `$ref`s are
+/// resolved against `root`, the document the schema lives in
+pub fn example(schema: &Value, root: &Value) -> Value {
+    generate(schema, root, None, 0)
+}
+
+/// the example of a schema given as text, like one pasted on the body of a
+/// request, formatted to be used as a body
+pub fn example_body(schema: &str) -> anyhow::Result<String> {
+    let schema = serde_json::from_str::<Value>(schema)
+        .map_err(|e| anyhow::anyhow!("the schema is not valid JSON: {e}"))?;
+    if !schema.is_object() {
+        anyhow::bail!("a JSON Schema is an object");
+    }
+    Ok(serde_json::to_string_pretty(&example(&schema, &schema))?)
+}
+
+fn generate(schema: &Value, root: &Value, name: Option<&str>, depth: usize) -> Value {
+    let Value::Object(schema) = schema else {
+        return Value::Null;
+    };
+    if depth > MAX_DEPTH {
+        return Value::Null;
+    }
+
+    if let Some(value) = declared_value(schema) {
+        return value;
+    }
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return match resolve(reference, root) {
+            Some(resolved) => generate(resolved, root, name, depth + 1),
+            None => Value::Null,
+        };
+    }
+
+    if let Some(Value::Array(schemas)) = schema.get("allOf") {
+        let mut merged = Map::new();
+        for schema in schemas {
+            match generate(schema, root, name, depth + 1) {
+                Value::Object(properties) => merged.extend(properties),
+                value if merged.is_empty() => return value,
+                _ => {}
+            }
+        }
+        return Value::Object(merged);
+    }
+
+    let alternatives = schema.get("oneOf").or_else(|| schema.get("anyOf"));
+    if let Some(first) = alternatives
+        .and_then(Value::as_array)
+        .and_then(|s| s.first())
+    {
+        return generate(first, root, name, depth + 1);
+    }
+
+    match schema_type(schema) {
+        Some("object") => object(schema, root, depth),
+        Some("array") => {
+            let item = schema
+                .get("items")
+                .map(|items| generate(items, root, name, depth + 1))
+                .unwrap_or(Value::Null);
+            let count = schema
+                .get("minItems")
+                .and_then(Value::as_u64)
+                .unwrap_or(1)
+                .max(1);
+            Value::Array(vec![item; count as usize])
+        }
+        Some("string") => Value::String(string(schema, name)),
+        Some("integer") => Value::from(
+            minimum(schema)
+                .map(|minimum| minimum.ceil() as i64)
+                .unwrap_or_default(),
+        ),
+        Some("number") => Value::from(minimum(schema).unwrap_or_default()),
+        Some("boolean") => Value::Bool(true),
+        Some("null") => Value::Null,
+        _ => Value::Null,
+    }
+}
+
+/// a value the schema spells out, with `const` taking precedence
+fn declared_value(schema: &Map<String, Value>) -> Option<Value> {
+    schema
+        .get("const")
+        .or_else(|| schema.get("example"))
+        .or_else(|| {
+            schema
+                .get("examples")
+                .and_then(Value::as_array)
+                .and_then(|examples| examples.first())
+        })
+        .or_else(|| schema.get("default"))
+        .or_else(|| {
+            schema
+                .get("enum")
+                .and_then(Value::as_array)
+                .and_then(|values| values.first())
+        })
+        .cloned()
+}
+
+/// the type of the schema, the first that isn't `null` when it has many,
+/// or guessed from the keywords it uses when it has none
+fn schema_type(schema: &Map<String, Value>) -> Option<&str> {
+    match schema.get("type") {
+        Some(Value::String(kind)) => Some(kind),
+        Some(Value::Array(kinds)) => kinds
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|kind| kind.ne(&"null")),
+        _ if schema.contains_key("properties") => Some("object"),
+        _ if schema.contains_key("items") => Some("array"),
+        _ => None,
+    }
+}
+
+fn object(schema: &Map<String, Value>, root: &Value, depth: usize) -> Value {
+    let Some(Value::Object(properties)) = schema.get("properties") else {
+        return Value::Object(Map::new());
+    };
+    let required = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|required| {
+            required
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+        });
+
+    properties
+        .iter()
+        .filter(|(name, _)| {
+            required
+                .as_ref()
+                .is_none_or(|required| required.contains(&name.as_str()))
+        })
+        .map(|(name, property)| {
+            (
+                name.clone(),
+                generate(property, root, Some(name), depth + 1),
+            )
+        })
+        .collect::<Map<_, _>>()
+        .into()
+}
+
+/// a string of the format of the schema, or the name of the property it
+/// is the value of
+fn string(schema: &Map<String, Value>, name: Option<&str>) -> String {
+    let format = schema.get("format").and_then(Value::as_str);
+    let value = match format {
+        Some("email") | Some("idn-email") => "user@example.com",
+        Some("uuid") => "3fa85f64-5717-4562-b3fc-2c963f66afa6",
+        Some("date-time") => "2024-01-01T00:00:00Z",
+        Some("date") => "2024-01-01",
+        Some("time") => "12:00:00",
+        Some("uri") | Some("url") | Some("iri") => "https://example.com",
+        Some("hostname") | Some("idn-hostname") => "example.com",
+        Some("ipv4") => "192.168.0.1",
+        Some("ipv6") => "::1",
+        Some("byte") => "ZXhhbXBsZQ==",
+        Some("password") => "password",
+        _ => name.unwrap_or("string"),
+    };
+
+    let min_length = schema
+        .get("minLength")
+        .and_then(Value::as_u64)
+        .unwrap_or_default() as usize;
+    match value.chars().count() < min_length && format.is_none() {
+        true => format!("{value:x<min_length$}"),
+        false => value.to_string(),
+    }
+}
+
+fn minimum(schema: &Map<String, Value>) -> Option<f64> {
+    match (schema.get("minimum"), schema.get("exclusiveMinimum")) {
+        (Some(minimum), _) => minimum.as_f64(),
+        (None, Some(minimum)) => minimum.as_f64().map(|minimum| minimum + 1.0),
+        (None, None) => None,
+    }
+}
+
+/// follows a local reference like `#/components/schemas/User`
+fn resolve<'a>(reference: &str, root: &'a Value) -> Option<&'a Value> {
+    let pointer = reference.strip_prefix('#')?;
+    root.pointer(pointer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let document = serde_json::json!({
+            "components": {
+                "schemas": {
+                    "Role": { "type": "string", "enum": ["admin", "member"] },
+                    "User": {
+                        "type": "object",
+                        "required": ["id", "email", "role", "tags", "age", "manager"],
+                        "properties": {
+                            "id": { "type": "string", "format": "uuid" },
+                            "email": { "type": "string", "format": "email" },
+                            "name": { "type": "string" },
+                            "role": { "$ref": "#/components/schemas/Role" },
+                            "tags": { "type": "array", "items": { "type": "string" } },
+                            "age": { "type": ["integer", "null"], "minimum": 18 },
+                            "manager": { "$ref": "#/components/schemas/User" }
+                        }
+                    }
+                }
+            }
+        });
+        let schema = serde_json::json!({ "$ref": "#/components/schemas/User" });
+
+        let example = example(&schema, &document);
+        assert_eq!(example["id"], "3fa85f64-5717-4562-b3fc-2c963f66afa6");
+        assert_eq!(example["email"], "user@example.com");
+        assert_eq!(example["role"], "admin");
+        assert_eq!(example["tags"], serde_json::json!(["tags"]));
+        assert_eq!(example["age"], 18);
+        assert!(example.get("name").is_none());
+        // recursive schemas stop instead of going on forever
+        assert!(example["manager"]["manager"]["manager"]["manager"]["manager"].is_null());
+
+        let schema = serde_json::json!({
+            "properties": {
+                "code": { "type": "string", "minLength": 6 },
+                "price": { "type": "number", "example": 9.99 }
+            }
+        });
+        assert_eq!(
+            super::example(&schema, &schema),
+            serde_json::json!({ "code": "codexx", "price": 9.99 })
+        );
+    }
+}
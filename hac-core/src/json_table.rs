@@ -0,0 +1,188 @@
+use std::cmp::Ordering;
+
+use serde_json::Value;
+
+/// an array of JSON objects laid out as rows, with a column for every key
+/// found on them
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonTable {
+    /// JSONPath of the array the table was made of, `$` when it is the body
+    pub path: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl JsonTable {
+    /// the table of the body when it is an array of objects, or when it is
+    /// an object holding one, like the `{"data": [...]}` of list endpoints
+    pub fn parse(body: &str) -> Option<JsonTable> {
+        let value = serde_json::from_str::<Value>(body).ok()?;
+        if let Some(table) = JsonTable::from_array("$".into(), &value) {
+            return Some(table);
+        }
+        value
+            .as_object()?
+            .iter()
+            .find_map(|(key, value)| JsonTable::from_array(format!("$.{key}"), value))
+    }
+
+    /// the table of a csv body, the first record names the columns and
+    /// every value is kept as text, empty ones being empty cells
+    pub fn from_csv(body: &str) -> Option<JsonTable> {
+        let mut records = crate::iteration::csv_records(body).ok()?.into_iter();
+        let columns = records.next().filter(|columns| !columns.is_empty())?;
+        let rows = records
+            .filter(|record| !record.iter().all(String::is_empty))
+            .map(|mut record| {
+                record.resize(columns.len(), String::default());
+                record
+                    .into_iter()
+                    .map(|field| match field.is_empty() {
+                        true => Value::Null,
+                        false => Value::String(field),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Some(JsonTable {
+            path: "$".into(),
+            columns,
+            rows,
+        })
+    }
+
+    fn from_array(path: String, value: &Value) -> Option<JsonTable> {
+        let items = value.as_array().filter(|items| !items.is_empty())?;
+        let objects = items
+            .iter()
+            .map(Value::as_object)
+            .collect::<Option<Vec<_>>>()?;
+
+        let mut columns: Vec<String> = vec![];
+        for key in objects.iter().flat_map(|object| object.keys()) {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+        let rows = objects
+            .iter()
+            .map(|object| {
+                columns
+                    .iter()
+                    .map(|column| object.get(column).cloned().unwrap_or(Value::Null))
+                    .collect()
+            })
+            .collect();
+
+        Some(JsonTable {
+            path,
+            columns,
+            rows,
+        })
+    }
+
+    /// orders the rows by the values of the column, numbers by their value
+    /// and everything else by its text, with empty cells always at the end
+    pub fn sort(&mut self, column: usize, descending: bool) {
+        self.rows.sort_by(|a, b| {
+            let (a, b) = (&a[column], &b[column]);
+            match (a.is_null(), b.is_null()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => {
+                    let ordering = compare(a, b);
+                    match descending {
+                        true => ordering.reverse(),
+                        false => ordering,
+                    }
+                }
+            }
+        });
+    }
+}
+
+fn compare(a: &Value, b: &Value) -> Ordering {
+    match (as_number(a), as_number(b)) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        _ => cell_text(a).cmp(&cell_text(b)),
+    }
+}
+
+/// numbers, and text holding one like the values of csv bodies
+pub fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::String(text) => text.trim().parse().ok(),
+        value => value.as_f64(),
+    }
+}
+
+/// how a value is shown on a cell, nested objects and arrays are only
+/// described by their size
+pub fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::default(),
+        Value::String(text) => text.clone(),
+        Value::Object(map) if map.len().eq(&1) => "{1 key}".into(),
+        Value::Object(map) => format!("{{{} keys}}", map.len()),
+        Value::Array(items) if items.len().eq(&1) => "[1 item]".into(),
+        Value::Array(items) => format!("[{} items]", items.len()),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let table = JsonTable::parse(
+            r#"[{"id": 2, "name": "b"}, {"id": 1, "tags": ["x"]}, {"id": 10, "name": "a"}]"#,
+        )
+        .unwrap();
+        assert_eq!(table.path, "$");
+        assert_eq!(table.columns, vec!["id", "name", "tags"]);
+        assert_eq!(
+            table.rows[1].iter().map(cell_text).collect::<Vec<_>>(),
+            vec!["1", "", "[1 item]"]
+        );
+
+        let table = JsonTable::parse(r#"{"total": 1, "data": [{"id": 1}]}"#).unwrap();
+        assert_eq!(table.path, "$.data");
+
+        assert!(JsonTable::parse(r#"[{"id": 1}, 2]"#).is_none());
+        assert!(JsonTable::parse("[]").is_none());
+        assert!(JsonTable::parse(r#"{"id": 1}"#).is_none());
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut table =
+            JsonTable::parse(r#"[{"id": 2, "name": "b"}, {"id": 1}, {"id": 10, "name": "a"}]"#)
+                .unwrap();
+        let column = |table: &JsonTable, idx: usize| {
+            table
+                .rows
+                .iter()
+                .map(|row| cell_text(&row[idx]))
+                .collect::<Vec<_>>()
+        };
+
+        table.sort(0, false);
+        assert_eq!(column(&table, 0), vec!["1", "2", "10"]);
+        table.sort(0, true);
+        assert_eq!(column(&table, 0), vec!["10", "2", "1"]);
+        table.sort(1, true);
+        assert_eq!(column(&table, 1), vec!["b", "a", ""]);
+
+        let mut table = JsonTable::from_csv("id,name\n2,b\n10,\"a, z\"\n\n1\n").unwrap();
+        assert_eq!(table.columns, vec!["id", "name"]);
+        assert_eq!(column(&table, 1), vec!["b", "a, z", ""]);
+        table.sort(0, false);
+        assert_eq!(column(&table, 0), vec!["1", "2", "10"]);
+        assert!(JsonTable::from_csv("").is_none());
+        assert!(JsonTable::from_csv("id\n\"1").is_none());
+    }
+}
@@ -0,0 +1,197 @@
+use crate::json_path;
+
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+/// longest preview of a string value shown on a row of the tree
+const PREVIEW_LEN: usize = 40;
+
+/// a line of the tree, a value with the key or index it lives under
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeRow {
+    pub depth: usize,
+    /// JSONPath to the value, like `$.data.items[0]`
+    pub path: String,
+    /// the key or index of the value on its parent, the root has none
+    pub key: Option<String>,
+    /// a short description of the value, the number of keys or items of
+    /// objects and arrays, or the value itself for the rest
+    pub preview: String,
+    pub expandable: bool,
+    pub expanded: bool,
+}
+
+/// a JSON document shown as a tree where objects and arrays can be folded
+#[derive(Debug, Clone)]
+pub struct JsonTree {
+    root: Value,
+    /// paths of the objects and arrays that show their children
+    expanded: HashSet<String>,
+}
+
+impl JsonTree {
+    /// the tree of the body with only the root unfolded, or nothing when the
+    /// body is not JSON
+    pub fn parse(body: &str) -> Option<JsonTree> {
+        let root = serde_json::from_str::<Value>(body).ok()?;
+        Some(JsonTree {
+            root,
+            expanded: HashSet::from(["$".to_string()]),
+        })
+    }
+
+    /// keeps the folds of a previous tree, so a body that was received again
+    /// is shown the way the user left it
+    pub fn keep_folds(&mut self, previous: &JsonTree) {
+        self.expanded = previous.expanded.clone();
+    }
+
+    /// the rows of every value that is visible, in order
+    pub fn rows(&self) -> Vec<TreeRow> {
+        let mut rows = vec![];
+        self.push_rows(&self.root, "$".into(), None, 0, &mut rows);
+        rows
+    }
+
+    fn push_rows(
+        &self,
+        value: &Value,
+        path: String,
+        key: Option<String>,
+        depth: usize,
+        rows: &mut Vec<TreeRow>,
+    ) {
+        let expandable = match value {
+            Value::Object(map) => !map.is_empty(),
+            Value::Array(items) => !items.is_empty(),
+            _ => false,
+        };
+        let expanded = expandable && self.expanded.contains(&path);
+        rows.push(TreeRow {
+            depth,
+            path: path.clone(),
+            key,
+            preview: preview(value),
+            expandable,
+            expanded,
+        });
+
+        if !expanded {
+            return;
+        }
+        match value {
+            Value::Object(map) => map.iter().for_each(|(key, child)| {
+                let child_path = child_path(&path, key);
+                self.push_rows(child, child_path, Some(key.clone()), depth + 1, rows);
+            }),
+            Value::Array(items) => items.iter().enumerate().for_each(|(idx, child)| {
+                let child_path = format!("{path}[{idx}]");
+                self.push_rows(child, child_path, Some(idx.to_string()), depth + 1, rows);
+            }),
+            _ => {}
+        }
+    }
+
+    pub fn expand(&mut self, path: &str) {
+        self.expanded.insert(path.to_string());
+    }
+
+    pub fn collapse(&mut self, path: &str) {
+        self.expanded.remove(path);
+    }
+
+    /// the value at the path, as compact JSON, or unquoted when it is a
+    /// string
+    pub fn value(&self, path: &str) -> Option<String> {
+        let values = json_path::select(&self.root, path).ok()?;
+        match values.first()? {
+            Value::String(value) => Some(value.clone()),
+            value => Some(value.to_string()),
+        }
+    }
+}
+
+/// the path of a key under the parent, in bracket notation when the key is
+/// not a plain name
+fn child_path(parent: &str, key: &str) -> String {
+    let plain = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c.eq(&'_'))
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c.eq(&'_'));
+    match (plain, key.contains('\'')) {
+        (true, _) => format!("{parent}.{key}"),
+        (false, false) => format!("{parent}['{key}']"),
+        (false, true) => format!("{parent}[\"{key}\"]"),
+    }
+}
+
+fn preview(value: &Value) -> String {
+    match value {
+        Value::Object(map) if map.len().eq(&1) => "{1 key}".into(),
+        Value::Object(map) => format!("{{{} keys}}", map.len()),
+        Value::Array(items) if items.len().eq(&1) => "[1 item]".into(),
+        Value::Array(items) => format!("[{} items]", items.len()),
+        Value::String(text) if text.chars().count().gt(&PREVIEW_LEN) => {
+            let start = text.chars().take(PREVIEW_LEN).collect::<String>();
+            format!("\"{start}…\"")
+        }
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows() {
+        let mut tree =
+            JsonTree::parse(r#"{"data": {"id": 7, "tags": ["a"]}, "next cursor": null}"#).unwrap();
+
+        let rows = tree.rows();
+        let summary = |rows: &[TreeRow]| {
+            rows.iter()
+                .map(|row| (row.depth, row.path.clone(), row.preview.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            summary(&rows),
+            vec![
+                (0, "$".into(), "{2 keys}".into()),
+                (1, "$.data".into(), "{2 keys}".into()),
+                (1, "$['next cursor']".into(), "null".into()),
+            ]
+        );
+        assert!(rows[1].expandable && !rows[1].expanded);
+        assert!(!rows[2].expandable);
+
+        tree.expand("$.data");
+        tree.expand("$.data.tags");
+        assert_eq!(
+            summary(&tree.rows()[2..5]),
+            vec![
+                (2, "$.data.id".into(), "7".into()),
+                (2, "$.data.tags".into(), "[1 item]".into()),
+                (3, "$.data.tags[0]".into(), "\"a\"".into()),
+            ]
+        );
+
+        tree.collapse("$");
+        assert_eq!(tree.rows().len(), 1);
+    }
+
+    #[test]
+    fn test_value() {
+        let tree = JsonTree::parse(r#"{"data": {"id": 7, "name": "hac"}}"#).unwrap();
+        assert_eq!(tree.value("$.data.id"), Some("7".into()));
+        assert_eq!(tree.value("$.data.name"), Some("hac".into()));
+        assert_eq!(
+            tree.value("$.data"),
+            Some(r#"{"id":7,"name":"hac"}"#.into())
+        );
+        assert_eq!(tree.value("$.missing"), None);
+        assert!(JsonTree::parse("not json").is_none());
+    }
+}
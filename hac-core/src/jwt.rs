@@ -0,0 +1,151 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// a json web token split into its readable parts, the signature is kept
+/// encoded as it is not meant to be read
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedJwt {
+    pub header: serde_json::Value,
+    pub claims: serde_json::Value,
+    pub signature: String,
+}
+
+impl DecodedJwt {
+    /// seconds until the token expires, negative when it already expired and
+    /// `None` when the token has no `exp` claim
+    pub fn expires_in(&self, now: u64) -> Option<i64> {
+        let exp = self.claims.get("exp")?.as_i64()?;
+        Some(exp - now as i64)
+    }
+
+    /// renders the header and claims as indented json, followed by when the
+    /// token expires, to be shown on a popup or printed on a terminal
+    pub fn render(&self, now: u64) -> String {
+        let pretty = |value: &serde_json::Value| {
+            serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+        };
+
+        let mut out = format!(
+            "header\n{}\n\nclaims\n{}\n",
+            pretty(&self.header),
+            pretty(&self.claims)
+        );
+        match self.expires_in(now) {
+            Some(secs) if secs.ge(&0) => {
+                out.push_str(&format!("\nexpires in {}\n", format_countdown(secs as u64)))
+            }
+            Some(secs) => out.push_str(&format!(
+                "\nexpired {} ago\n",
+                format_countdown(secs.unsigned_abs())
+            )),
+            None => out.push_str("\nnever expires\n"),
+        }
+        out
+    }
+}
+
+/// formats an amount of seconds like `1d 2h 3m 4s`, omitting leading units
+/// that are zero
+pub fn format_countdown(secs: u64) -> String {
+    let units = [(86400, "d"), (3600, "h"), (60, "m"), (1, "s")];
+    let mut remaining = secs;
+    let parts = units
+        .iter()
+        .map(|(size, unit)| {
+            let amount = remaining / size;
+            remaining %= size;
+            (amount, unit)
+        })
+        .skip_while(|(amount, unit)| amount.eq(&0) && unit.ne(&&"s"))
+        .map(|(amount, unit)| format!("{amount}{unit}"))
+        .collect::<Vec<_>>();
+    parts.join(" ")
+}
+
+/// decodes the header and claims of a token, the signature is not verified
+pub fn decode(token: &str) -> anyhow::Result<DecodedJwt> {
+    let token = token.trim();
+    let token = token.strip_prefix("Bearer ").unwrap_or(token).trim();
+
+    let [header, claims, signature] = token.split('.').collect::<Vec<_>>()[..] else {
+        anyhow::bail!("a jwt has three parts separated by dots");
+    };
+
+    let decode_part = |part: &str, name: &str| -> anyhow::Result<serde_json::Value> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(part.trim_end_matches('='))
+            .map_err(|e| anyhow::anyhow!("{name} is not valid base64: {e}"))?;
+        serde_json::from_slice(&bytes).map_err(|e| anyhow::anyhow!("{name} is not json: {e}"))
+    };
+
+    Ok(DecodedJwt {
+        header: decode_part(header, "header")?,
+        claims: decode_part(claims, "claims")?,
+        signature: signature.to_string(),
+    })
+}
+
+/// finds the first thing that looks like a jwt on the text, like the value
+/// of an authorization header or a token on a response body
+pub fn find_token(text: &str) -> Option<&str> {
+    text.split(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .find(|word| word.starts_with("eyJ") && word.split('.').count().eq(&3))
+}
+
+/// signs the claims with HS256, meant to generate tokens for local
+/// development and testing
+pub fn sign_hs256(claims: &serde_json::Value, secret: &str) -> String {
+    let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#);
+    let claims = URL_SAFE_NO_PAD.encode(claims.to_string());
+    let payload = format!("{header}.{claims}");
+
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac accepts keys of any size");
+    mac.update(payload.as_bytes());
+    let signature = URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes());
+
+    format!("{payload}.{signature}")
+}
+
+/// seconds since the unix epoch
+pub fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jwt() {
+        let claims =
+            serde_json::json!({"sub": "1234567890", "name": "John Doe", "iat": 1516239022});
+        let token = sign_hs256(&claims, "your-256-bit-secret");
+        assert_eq!(
+            token,
+            "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.eyJpYXQiOjE1MTYyMzkwMjIsIm5hbWUiOiJKb2huIERvZSIsInN1YiI6IjEyMzQ1Njc4OTAifQ.\
+             fdOPQ05ZfRhkST2-rIWgUpbqUsVhkkNVNcuG7Ki0s-8"
+        );
+
+        let body = format!(r#"{{"access_token":"{token}","expires_in":3600}}"#);
+        assert_eq!(find_token(&body), Some(token.as_str()));
+
+        let decoded = decode(&format!("Bearer {token}")).unwrap();
+        assert_eq!(decoded.header["alg"], "HS256");
+        assert_eq!(decoded.claims, claims);
+        assert!(decoded.expires_in(0).is_none());
+        assert!(decode("not.a-token").is_err());
+
+        let expiring = decode(&sign_hs256(&serde_json::json!({"exp": 10000}), "s")).unwrap();
+        assert_eq!(expiring.expires_in(10000 - 3723), Some(3723));
+        assert!(expiring
+            .render(10000 - 3723)
+            .ends_with("expires in 1h 2m 3s\n"));
+        assert!(expiring.render(10030).ends_with("expired 30s ago\n"));
+    }
+}
@@ -1,6 +1,31 @@
+pub mod abbreviation;
 pub mod collection;
 pub mod command;
+pub mod completion;
+pub mod digest;
+pub mod docs;
+pub mod faker;
 pub mod fs;
+pub mod header_history;
+pub mod interop;
+pub mod iteration;
+pub mod json_path;
+pub mod json_schema;
+pub mod json_table;
+pub mod json_tree;
+pub mod jwt;
+pub mod line_diff;
+pub mod monitor;
+pub mod ndjson;
 pub mod net;
+pub mod paste;
+pub mod protobuf;
+pub mod snippet;
 pub mod syntax;
 pub mod text_object;
+pub mod timestamp;
+pub mod transform;
+pub mod update;
+pub mod url_builder;
+pub mod version;
+pub mod viewer_preferences;
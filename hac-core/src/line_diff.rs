@@ -0,0 +1,130 @@
+/// past this many cells the changed middle of the texts is not matched line
+/// by line, and is shown as entirely replaced instead
+const MAX_TABLE_CELLS: usize = 4_000_000;
+
+/// a line of the comparison between an old and a new text
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine {
+    /// the line at this index of the new text is on the old one as well
+    Same(usize),
+    /// the line at this index of the new text is not on the old one
+    Added(usize),
+    /// a line of the old text that is gone from the new one
+    Removed(String),
+}
+
+/// compares the texts line by line, listing every line of the new text in
+/// order with the removed lines of the old one where they used to be
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+
+    let prefix = old
+        .iter()
+        .zip(new.iter())
+        .take_while(|(a, b)| a.eq(b))
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a.eq(b))
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut diff = (0..prefix).map(DiffLine::Same).collect::<Vec<_>>();
+    diff.extend(
+        diff_middle(old_middle, new_middle)
+            .into_iter()
+            .map(|line| match line {
+                DiffLine::Same(idx) => DiffLine::Same(idx + prefix),
+                DiffLine::Added(idx) => DiffLine::Added(idx + prefix),
+                removed => removed,
+            }),
+    );
+    diff.extend((new.len() - suffix..new.len()).map(DiffLine::Same));
+    diff
+}
+
+/// matches the lines through their longest common subsequence
+fn diff_middle(old: &[&str], new: &[&str]) -> Vec<DiffLine> {
+    if (old.len() + 1)
+        .saturating_mul(new.len() + 1)
+        .gt(&MAX_TABLE_CELLS)
+    {
+        return old
+            .iter()
+            .map(|line| DiffLine::Removed(line.to_string()))
+            .chain((0..new.len()).map(DiffLine::Added))
+            .collect();
+    }
+
+    // lengths[i][j] is the length of the common subsequence of old[i..] and
+    // new[j..]
+    let width = new.len() + 1;
+    let mut lengths = vec![0u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i * width + j] = match old[i].eq(new[j]) {
+                true => lengths[(i + 1) * width + j + 1] + 1,
+                false => lengths[(i + 1) * width + j].max(lengths[i * width + j + 1]),
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut diff = vec![];
+    while i < old.len() && j < new.len() {
+        if old[i].eq(new[j]) {
+            diff.push(DiffLine::Same(j));
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j].ge(&lengths[i * width + j + 1]) {
+            diff.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(j));
+            j += 1;
+        }
+    }
+    diff.extend(
+        old[i..]
+            .iter()
+            .map(|line| DiffLine::Removed(line.to_string())),
+    );
+    diff.extend((j..new.len()).map(DiffLine::Added));
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines() {
+        let old = "{\n  \"id\": 1,\n  \"name\": \"old\",\n  \"tags\": []\n}";
+        let new = "{\n  \"id\": 1,\n  \"name\": \"new\",\n  \"extra\": true,\n  \"tags\": []\n}";
+
+        assert_eq!(
+            diff_lines(old, new),
+            vec![
+                DiffLine::Same(0),
+                DiffLine::Same(1),
+                DiffLine::Removed("  \"name\": \"old\",".into()),
+                DiffLine::Added(2),
+                DiffLine::Added(3),
+                DiffLine::Same(4),
+                DiffLine::Same(5),
+            ]
+        );
+        assert_eq!(
+            diff_lines(new, new),
+            (0..6).map(DiffLine::Same).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            diff_lines("a\nb", ""),
+            vec![DiffLine::Removed("a".into()), DiffLine::Removed("b".into())]
+        );
+    }
+}
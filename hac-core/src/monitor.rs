@@ -0,0 +1,337 @@
+use crate::collection::types::{for_each_request, Monitor, Request, RequestKind};
+use crate::net::curl_config::CurlDefaults;
+use crate::net::request_manager::{prepare_request, send_request, Response};
+use crate::net::request_strategies::http_strategy::HttpResponse;
+use crate::protobuf::Schema;
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::mpsc::UnboundedSender;
+
+/// how many results are kept for each monitor, older ones are dropped
+const MAX_SAMPLES: usize = 100;
+
+/// parses how often a monitor runs. Supported formats are plain durations,
+/// like `30s`, `5m` or `1h30m`, the `@every <duration>`, `@hourly` and
+/// `@daily` shorthands, and cron expressions that run every `n` minutes,
+/// like `*/10 * * * *`
+pub fn parse_schedule(schedule: &str) -> Option<Duration> {
+    let schedule = schedule.trim();
+    match schedule {
+        "@hourly" => return Some(Duration::from_secs(60 * 60)),
+        "@daily" => return Some(Duration::from_secs(24 * 60 * 60)),
+        _ => {}
+    }
+
+    if let Some(duration) = schedule.strip_prefix("@every ") {
+        return parse_duration(duration);
+    }
+
+    let fields = schedule.split_whitespace().collect::<Vec<_>>();
+    if let [minutes, "*", "*", "*", "*"] = fields.as_slice() {
+        let step: u64 = match *minutes {
+            "*" => 1,
+            minutes => minutes.strip_prefix("*/")?.parse().ok()?,
+        };
+        return step
+            .checked_mul(60)
+            .filter(|seconds| seconds.gt(&0))
+            .map(Duration::from_secs);
+    }
+
+    parse_duration(schedule)
+}
+
+/// parses durations like `45s`, `10m`, `2h` or a combination of them
+fn parse_duration(value: &str) -> Option<Duration> {
+    let mut total: u64 = 0;
+    let mut number = String::new();
+
+    for c in value.trim().chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            's' | 'm' | 'h' | 'd' => {
+                let amount: u64 = number.parse().ok()?;
+                let unit = match c {
+                    's' => 1,
+                    'm' => 60,
+                    'h' => 60 * 60,
+                    _ => 24 * 60 * 60,
+                };
+                total = amount
+                    .checked_mul(unit)
+                    .and_then(|seconds| total.checked_add(seconds))?;
+                number.clear();
+            }
+            _ => return None,
+        }
+    }
+
+    (number.is_empty() && total > 0).then(|| Duration::from_secs(total))
+}
+
+/// checks a response against what the monitor expects, returning why it
+/// failed when it does
+pub fn check(monitor: &Monitor, response: &Response) -> Result<(), String> {
+    if response.is_error {
+        return Err(response
+            .cause
+            .clone()
+            .unwrap_or_else(|| "request failed".into()));
+    }
+
+    let status = response.status.ok_or("no status code on response")?;
+    match monitor.expect_status {
+        Some(expected) if status.as_u16().ne(&expected) => {
+            return Err(format!("expected status {expected}, got {status}"));
+        }
+        None if !is_successful(status) => return Err(format!("got status {status}")),
+        _ => {}
+    }
+
+    if let Some(max_latency) = monitor.max_latency_ms {
+        let latency = response.duration.as_millis();
+        if latency.gt(&(max_latency as u128)) {
+            return Err(format!("took {latency}ms, limit is {max_latency}ms"));
+        }
+    }
+
+    Ok(())
+}
+
+/// whether the status is a success, including the switch to the WebSocket
+/// protocol that opens connections
+pub fn is_successful(status: reqwest::StatusCode) -> bool {
+    status.is_success() || status.eq(&reqwest::StatusCode::SWITCHING_PROTOCOLS)
+}
+
+/// result of a single run of a monitor
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub latency: Duration,
+    /// why the run failed, `None` means it passed
+    pub failure: Option<String>,
+}
+
+/// reported when a monitor changes from healthy to failing or back
+#[derive(Debug, PartialEq)]
+pub enum MonitorTransition {
+    Failing(String),
+    Recovered,
+}
+
+/// the history of a monitor while the collection is open
+#[derive(Debug, Clone, Default)]
+pub struct MonitorStats {
+    samples: VecDeque<Sample>,
+    last_run: Option<Instant>,
+    /// the schedule the interval was parsed from, it is only parsed again
+    /// when the schedule changes
+    schedule: Option<String>,
+    interval: Option<Duration>,
+    /// when the monitor runs next, `None` when it has no valid schedule
+    next_run: Option<Instant>,
+}
+
+impl MonitorStats {
+    /// parses the schedule of the monitor when it changed, or stops running
+    /// it when the request is no longer monitored
+    pub fn set_schedule(&mut self, schedule: Option<&str>, now: Instant) {
+        if self.schedule.as_deref().eq(&schedule) {
+            return;
+        }
+
+        self.schedule = schedule.map(ToString::to_string);
+        self.interval = schedule.and_then(parse_schedule);
+        if let (Some(schedule), None) = (schedule, self.interval) {
+            tracing::warn!("invalid monitor schedule {schedule:?}");
+        }
+        self.next_run = self.interval.map(|interval| {
+            self.last_run
+                .and_then(|last_run| last_run.checked_add(interval))
+                .unwrap_or(now)
+        });
+    }
+
+    /// whether the monitor has a schedule and it is time to run it
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.next_run.is_some_and(|next_run| now.ge(&next_run))
+    }
+
+    pub fn mark_run(&mut self, now: Instant) {
+        self.last_run = Some(now);
+        self.next_run = self.interval.and_then(|interval| now.checked_add(interval));
+    }
+
+    /// stores the result of a run, returning a transition when the monitor
+    /// started failing or recovered from failures
+    pub fn record(&mut self, sample: Sample) -> Option<MonitorTransition> {
+        let was_failing = self.is_failing();
+
+        let transition = match (&sample.failure, was_failing) {
+            (Some(failure), false) => Some(MonitorTransition::Failing(failure.clone())),
+            (None, true) => Some(MonitorTransition::Recovered),
+            _ => None,
+        };
+
+        self.samples.push_back(sample);
+        if self.samples.len().gt(&MAX_SAMPLES) {
+            self.samples.pop_front();
+        }
+
+        transition
+    }
+
+    pub fn is_failing(&self) -> bool {
+        self.samples
+            .back()
+            .is_some_and(|sample| sample.failure.is_some())
+    }
+
+    /// percentage of runs that passed
+    pub fn uptime(&self) -> Option<f64> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let passed = self
+            .samples
+            .iter()
+            .filter(|sample| sample.failure.is_none())
+            .count();
+        Some(passed as f64 * 100.0 / self.samples.len() as f64)
+    }
+
+    pub fn average_latency(&self) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let total = self
+            .samples
+            .iter()
+            .map(|sample| sample.latency)
+            .sum::<Duration>();
+        Some(total / self.samples.len() as u32)
+    }
+
+    pub fn samples(&self) -> impl Iterator<Item = &Sample> {
+        self.samples.iter()
+    }
+}
+
+/// the id and schedule of every request that has a monitor, including the
+/// ones inside directories
+pub fn monitor_schedules(requests: &[RequestKind]) -> Vec<(String, String)> {
+    let mut schedules = vec![];
+    for_each_request(requests, &mut |req| {
+        let req = req.read().unwrap();
+        if let Some(monitor) = req.monitor.as_ref() {
+            schedules.push((req.id.clone(), monitor.schedule.clone()));
+        }
+    });
+    schedules
+}
+
+/// sends a monitored request in the background, the response is sent back
+/// along with the id of the request so it can be recorded
+pub fn run_monitor(
+    request: Request,
+    config: &hac_config::Config,
+    protobuf: Option<Arc<Schema>>,
+    response_tx: UnboundedSender<(String, Response)>,
+) {
+    let curl = CurlDefaults::load(&config.curl);
+    let mut request = prepare_request(request, config);
+    curl.authorize(&mut request);
+    let rate_limit = config.rate_limit.clone();
+    let offline = config.offline;
+    let strategy = curl
+        .client(&config.connection, &config.pins)
+        .map(|client| HttpResponse {
+            client,
+            protobuf,
+            progress_tx: None,
+            pins: config.pins.clone(),
+            body_limit: None,
+        });
+
+    tokio::spawn(async move {
+        let id = request.id.clone();
+        let response = match strategy {
+            Ok(strategy) => send_request(request, &strategy, &rate_limit, offline).await,
+            Err(e) => Response::from_error(format!("failed to apply the settings of .curlrc: {e}")),
+        };
+        if response_tx.send((id, response)).is_err() {
+            tracing::warn!("monitor finished after the collection was closed");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_schedule() {
+        assert_eq!(parse_schedule("30s"), Some(Duration::from_secs(30)));
+        assert_eq!(
+            parse_schedule("@every 1h30m"),
+            Some(Duration::from_secs(5400))
+        );
+        assert_eq!(parse_schedule("@hourly"), Some(Duration::from_secs(3600)));
+        assert_eq!(
+            parse_schedule("*/10 * * * *"),
+            Some(Duration::from_secs(600))
+        );
+        assert_eq!(parse_schedule("* * * * *"), Some(Duration::from_secs(60)));
+        assert_eq!(parse_schedule("0 9 * * 1"), None);
+        assert_eq!(parse_schedule("10"), None);
+        assert_eq!(parse_schedule("0s"), None);
+        assert_eq!(parse_schedule("213503982334602d"), None);
+        assert_eq!(parse_schedule("18446744073709551615s1s"), None);
+        assert_eq!(parse_schedule("*/307445734561825861 * * * *"), None);
+    }
+
+    #[test]
+    fn test_monitor_stats() {
+        let mut stats = MonitorStats::default();
+        let now = Instant::now();
+        assert!(!stats.is_due(now));
+        stats.set_schedule(Some("1m"), now);
+        assert!(stats.is_due(now));
+        stats.mark_run(now);
+        assert!(!stats.is_due(now));
+        assert!(stats.is_due(now + Duration::from_secs(60)));
+
+        // a shorter schedule counts from the last run
+        stats.set_schedule(Some("10s"), now);
+        assert!(stats.is_due(now + Duration::from_secs(10)));
+        stats.set_schedule(Some("every now and then"), now);
+        assert!(!stats.is_due(now + Duration::from_secs(3600)));
+        stats.set_schedule(Some("10s"), now);
+        stats.set_schedule(None, now);
+        assert!(!stats.is_due(now + Duration::from_secs(3600)));
+
+        let passed = |ms| Sample {
+            latency: Duration::from_millis(ms),
+            failure: None,
+        };
+        assert_eq!(stats.record(passed(100)), None);
+        assert_eq!(
+            stats.record(Sample {
+                latency: Duration::from_millis(300),
+                failure: Some("got status 500".into()),
+            }),
+            Some(MonitorTransition::Failing("got status 500".into()))
+        );
+        assert!(stats.is_failing());
+        assert_eq!(
+            stats.record(passed(200)),
+            Some(MonitorTransition::Recovered)
+        );
+
+        assert_eq!(stats.average_latency(), Some(Duration::from_millis(200)));
+        assert!((stats.uptime().unwrap() - 66.66).abs() < 0.1);
+    }
+}
@@ -0,0 +1,82 @@
+/// longest summary of a folded record, longer records are cut
+const SUMMARY_LEN: usize = 120;
+
+/// a line of a body made of one JSON document per line, like the ones of
+/// `application/x-ndjson` responses
+#[derive(Debug, Clone, PartialEq)]
+pub struct NdjsonRecord {
+    /// position of the record on the body, blank lines are not counted
+    pub index: usize,
+    pub line: String,
+    /// the record pretty printed, or nothing when the line is not JSON
+    pub pretty: Option<String>,
+}
+
+impl NdjsonRecord {
+    /// how the record is shown when unfolded
+    pub fn text(&self) -> &str {
+        self.pretty.as_deref().unwrap_or(&self.line)
+    }
+
+    /// how the record is shown when folded, on a single line
+    pub fn summary(&self) -> String {
+        let line = self.line.trim();
+        match line.chars().count().gt(&SUMMARY_LEN) {
+            true => format!("{}…", line.chars().take(SUMMARY_LEN).collect::<String>()),
+            false => line.to_string(),
+        }
+    }
+}
+
+/// every record of the body, or nothing when none of its lines is JSON
+pub fn parse(body: &str) -> Option<Vec<NdjsonRecord>> {
+    let records = body
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(index, line)| NdjsonRecord {
+            index,
+            line: line.to_string(),
+            pretty: serde_json::from_str::<serde_json::Value>(line)
+                .ok()
+                .and_then(|_| jsonxf::pretty_print(line).ok()),
+        })
+        .collect::<Vec<_>>();
+
+    records
+        .iter()
+        .any(|record| record.pretty.is_some())
+        .then_some(records)
+}
+
+/// the record shown on the line of the view, `starts` being the line each
+/// record starts on
+pub fn record_at(starts: &[usize], line: usize) -> Option<usize> {
+    starts.iter().rposition(|start| start.le(&line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_records() {
+        let body = "{\"id\":1,\"name\":\"a\"}\r\n\n{\"id\":2}\nnot json\n";
+        let records = parse(body).unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].text(), "{\n  \"id\": 1,\n  \"name\": \"a\"\n}");
+        assert_eq!(records[1].index, 1);
+        assert_eq!(records[1].summary(), "{\"id\":2}");
+        assert_eq!(records[2].text(), "not json");
+        assert!(parse("plain\ntext").is_none());
+
+        let long = format!("{{\"data\":\"{}\"}}", "x".repeat(200));
+        let record = &parse(&long).unwrap()[0];
+        assert_eq!(record.summary().chars().count(), SUMMARY_LEN + 1);
+
+        assert_eq!(record_at(&[0, 4, 9], 0), Some(0));
+        assert_eq!(record_at(&[0, 4, 9], 6), Some(1));
+        assert_eq!(record_at(&[2, 4], 1), None);
+    }
+}
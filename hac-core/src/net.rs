@@ -1,6 +1,24 @@
+pub mod auth;
+pub mod body_limit;
+pub mod charset;
+pub mod connection;
+pub mod curl;
+pub mod curl_config;
+pub mod download;
+pub mod expect_continue;
+pub mod graphql_ws;
+pub mod latency_history;
+pub mod mime;
+pub mod network_conditions;
+pub mod offline;
+pub mod pinning;
+pub mod rate_limit;
 pub mod request_client;
 pub mod request_manager;
 pub mod request_strategies;
 pub mod response_decoders;
+pub mod soap;
+pub mod status_codes;
+pub mod tls_inspection;
 
 pub use request_manager::handle_request;
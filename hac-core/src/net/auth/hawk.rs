@@ -0,0 +1,84 @@
+use crate::collection::types::{HawkAlgorithm, HawkConfig};
+use crate::net::auth::Nonce;
+
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::Sha256;
+
+/// value of the `Authorization` header signing the request with hawk. The
+/// payload is not signed, which servers accept unless they require it
+pub fn authorization(
+    method: &str,
+    url: &reqwest::Url,
+    config: &HawkConfig,
+    nonce: &Nonce,
+) -> String {
+    let mut resource = url.path().to_string();
+    if let Some(query) = url.query() {
+        resource.push('?');
+        resource.push_str(query);
+    }
+    let ext = config.ext.as_deref().unwrap_or_default();
+    let normalized = format!(
+        "hawk.1.header\n{}\n{}\n{}\n{resource}\n{}\n{}\n\n{}\n",
+        nonce.timestamp,
+        nonce.nonce,
+        method.to_uppercase(),
+        url.host_str().unwrap_or_default().to_lowercase(),
+        url.port_or_known_default().unwrap_or_default(),
+        ext.replace('\\', "\\\\").replace('\n', "\\n"),
+    );
+
+    let mac = match config.algorithm {
+        HawkAlgorithm::Sha256 => {
+            let mut mac = Hmac::<Sha256>::new_from_slice(config.key.as_bytes())
+                .expect("hmac accepts any key");
+            mac.update(normalized.as_bytes());
+            mac.finalize().into_bytes().to_vec()
+        }
+        HawkAlgorithm::Sha1 => {
+            let mut mac =
+                Hmac::<Sha1>::new_from_slice(config.key.as_bytes()).expect("hmac accepts any key");
+            mac.update(normalized.as_bytes());
+            mac.finalize().into_bytes().to_vec()
+        }
+    };
+    let mac = base64::engine::general_purpose::STANDARD.encode(mac);
+
+    let mut header = format!(
+        "Hawk id=\"{}\", ts=\"{}\", nonce=\"{}\", mac=\"{mac}\"",
+        config.id, nonce.timestamp, nonce.nonce
+    );
+    if !ext.is_empty() {
+        header.push_str(&format!(", ext=\"{}\"", ext.replace('"', "\\\"")));
+    }
+    header
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authorization() {
+        // example of the hawk documentation
+        let config = HawkConfig {
+            id: "dh37fgj492je".into(),
+            key: "werxhqb98rpaxn39848xrunpaw3489ruxnpa98w4rxn".into(),
+            algorithm: HawkAlgorithm::Sha256,
+            ext: Some("some-app-ext-data".into()),
+        };
+        let url = reqwest::Url::parse("http://example.com:8000/resource/1?b=1&a=2").unwrap();
+        let nonce = Nonce {
+            nonce: "j4h3g2".into(),
+            timestamp: 1353832234,
+        };
+
+        assert_eq!(
+            authorization("GET", &url, &config, &nonce),
+            "Hawk id=\"dh37fgj492je\", ts=\"1353832234\", nonce=\"j4h3g2\", \
+             mac=\"6R4rV5iE+NPoym+WwjeHzjAGXUtLNIxmo1vpMofpLAE=\", ext=\"some-app-ext-data\""
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! kerberos authentication through SPNEGO, using the GSSAPI library of the
+//! system, which is loaded when a request needs it so hac still runs on
+//! machines without kerberos installed
+
+use std::ffi::{c_void, CStr};
+
+/// names of the GSSAPI library of MIT kerberos and heimdal
+const LIBRARIES: [&CStr; 3] = [
+    c"libgssapi_krb5.so.2",
+    c"libgssapi_krb5.so",
+    c"libgssapi.so.3",
+];
+
+/// 1.3.6.1.5.5.2, the SPNEGO mechanism
+const SPNEGO_OID: [u8; 6] = [0x2b, 0x06, 0x01, 0x05, 0x05, 0x02];
+/// 1.2.840.113554.1.2.1.4, names of services like `HTTP@host`
+const HOSTBASED_SERVICE_OID: [u8; 10] =
+    [0x2a, 0x86, 0x48, 0x86, 0xf7, 0x12, 0x01, 0x02, 0x01, 0x04];
+
+const GSS_C_MUTUAL_FLAG: u32 = 2;
+const GSS_S_COMPLETE: u32 = 0;
+const GSS_S_CONTINUE_NEEDED: u32 = 1;
+
+#[repr(C)]
+struct BufferDesc {
+    length: usize,
+    value: *mut c_void,
+}
+
+#[repr(C)]
+struct OidDesc {
+    length: u32,
+    elements: *mut c_void,
+}
+
+type ImportName =
+    unsafe extern "C" fn(*mut u32, *mut BufferDesc, *const OidDesc, *mut *mut c_void) -> u32;
+type InitSecContext = unsafe extern "C" fn(
+    *mut u32,
+    *mut c_void,
+    *mut *mut c_void,
+    *mut c_void,
+    *const OidDesc,
+    u32,
+    u32,
+    *mut c_void,
+    *mut BufferDesc,
+    *mut *mut OidDesc,
+    *mut BufferDesc,
+    *mut u32,
+    *mut u32,
+) -> u32;
+type ReleaseBuffer = unsafe extern "C" fn(*mut u32, *mut BufferDesc) -> u32;
+type ReleaseName = unsafe extern "C" fn(*mut u32, *mut *mut c_void) -> u32;
+type DeleteSecContext = unsafe extern "C" fn(*mut u32, *mut *mut c_void, *mut BufferDesc) -> u32;
+
+/// a security context being established with a server
+pub struct Context {
+    library: *mut c_void,
+    init_sec_context: InitSecContext,
+    release_buffer: ReleaseBuffer,
+    release_name: ReleaseName,
+    delete_sec_context: DeleteSecContext,
+    name: *mut c_void,
+    context: *mut c_void,
+}
+
+// the context is only ever used by the request that created it, one step at
+// a time, which GSSAPI allows from any thread
+unsafe impl Send for Context {}
+
+impl Context {
+    /// starts a context for the HTTP service of the host
+    pub fn new(host: &str) -> anyhow::Result<Context> {
+        let library = LIBRARIES
+            .iter()
+            .map(|name| unsafe { libc::dlopen(name.as_ptr(), libc::RTLD_NOW) })
+            .find(|library| !library.is_null())
+            .ok_or_else(|| {
+                anyhow::anyhow!("failed to load the GSSAPI library, is kerberos installed?")
+            })?;
+
+        let symbol = |name: &CStr| {
+            let symbol = unsafe { libc::dlsym(library, name.as_ptr()) };
+            match symbol.is_null() {
+                true => Err(anyhow::anyhow!("GSSAPI has no symbol {name:?}")),
+                false => Ok(symbol),
+            }
+        };
+        let loaded = (|| -> anyhow::Result<_> {
+            unsafe {
+                Ok((
+                    std::mem::transmute::<*mut c_void, ImportName>(symbol(c"gss_import_name")?),
+                    std::mem::transmute::<*mut c_void, InitSecContext>(symbol(
+                        c"gss_init_sec_context",
+                    )?),
+                    std::mem::transmute::<*mut c_void, ReleaseBuffer>(symbol(
+                        c"gss_release_buffer",
+                    )?),
+                    std::mem::transmute::<*mut c_void, ReleaseName>(symbol(c"gss_release_name")?),
+                    std::mem::transmute::<*mut c_void, DeleteSecContext>(symbol(
+                        c"gss_delete_sec_context",
+                    )?),
+                ))
+            }
+        })();
+        let (import_name, init_sec_context, release_buffer, release_name, delete_sec_context) =
+            match loaded {
+                Ok(loaded) => loaded,
+                Err(e) => {
+                    unsafe { libc::dlclose(library) };
+                    return Err(e);
+                }
+            };
+
+        let mut context = Context {
+            library,
+            init_sec_context,
+            release_buffer,
+            release_name,
+            delete_sec_context,
+            name: std::ptr::null_mut(),
+            context: std::ptr::null_mut(),
+        };
+
+        let mut service = format!("HTTP@{host}").into_bytes();
+        let mut buffer = BufferDesc {
+            length: service.len(),
+            value: service.as_mut_ptr().cast(),
+        };
+        let name_type = OidDesc {
+            length: HOSTBASED_SERVICE_OID.len() as u32,
+            elements: HOSTBASED_SERVICE_OID.as_ptr() as *mut c_void,
+        };
+        let mut minor = 0;
+        let major = unsafe { import_name(&mut minor, &mut buffer, &name_type, &mut context.name) };
+        if major != GSS_S_COMPLETE {
+            anyhow::bail!("GSSAPI failed to import the name of {host} ({major}, {minor})");
+        }
+
+        Ok(context)
+    }
+
+    /// produces the next token to send, given the token the server answered
+    /// with, if any. Yields whether the context is established
+    pub fn step(&mut self, token: Option<&[u8]>) -> anyhow::Result<(Vec<u8>, bool)> {
+        let mut input = token.map(|token| BufferDesc {
+            length: token.len(),
+            value: token.as_ptr() as *mut c_void,
+        });
+        let input = input
+            .as_mut()
+            .map(|input| input as *mut BufferDesc)
+            .unwrap_or(std::ptr::null_mut());
+        let mech = OidDesc {
+            length: SPNEGO_OID.len() as u32,
+            elements: SPNEGO_OID.as_ptr() as *mut c_void,
+        };
+        let mut output = BufferDesc {
+            length: 0,
+            value: std::ptr::null_mut(),
+        };
+        let mut minor = 0;
+
+        let major = unsafe {
+            (self.init_sec_context)(
+                &mut minor,
+                std::ptr::null_mut(),
+                &mut self.context,
+                self.name,
+                &mech,
+                GSS_C_MUTUAL_FLAG,
+                0,
+                std::ptr::null_mut(),
+                input,
+                std::ptr::null_mut(),
+                &mut output,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+
+        let token = match output.value.is_null() {
+            true => vec![],
+            false => unsafe {
+                std::slice::from_raw_parts(output.value as *const u8, output.length).to_vec()
+            },
+        };
+        unsafe { (self.release_buffer)(&mut minor, &mut output) };
+
+        match major {
+            GSS_S_COMPLETE => Ok((token, true)),
+            GSS_S_CONTINUE_NEEDED => Ok((token, false)),
+            major => anyhow::bail!(
+                "kerberos authentication failed ({major}, {minor}), is there a ticket for the user? \
+                 it can be requested with `kinit`"
+            ),
+        }
+    }
+}
+
+impl Drop for Context {
+    fn drop(&mut self) {
+        let mut minor = 0;
+        unsafe {
+            if !self.context.is_null() {
+                (self.delete_sec_context)(&mut minor, &mut self.context, std::ptr::null_mut());
+            }
+            if !self.name.is_null() {
+                (self.release_name)(&mut minor, &mut self.name);
+            }
+            libc::dlclose(self.library);
+        }
+    }
+}
@@ -0,0 +1,311 @@
+use hmac::{Hmac, Mac};
+use md4::{Digest, Md4};
+use md5::Md5;
+
+const SIGNATURE: &[u8; 8] = b"NTLMSSP\0";
+
+const NEGOTIATE_UNICODE: u32 = 0x0000_0001;
+const NEGOTIATE_OEM: u32 = 0x0000_0002;
+const REQUEST_TARGET: u32 = 0x0000_0004;
+const NEGOTIATE_NTLM: u32 = 0x0000_0200;
+const NEGOTIATE_ALWAYS_SIGN: u32 = 0x0000_8000;
+const NEGOTIATE_EXTENDED_SESSION_SECURITY: u32 = 0x0008_0000;
+const NEGOTIATE_128: u32 = 0x2000_0000;
+const NEGOTIATE_56: u32 = 0x8000_0000;
+
+const FLAGS: u32 = NEGOTIATE_UNICODE
+    | NEGOTIATE_OEM
+    | REQUEST_TARGET
+    | NEGOTIATE_NTLM
+    | NEGOTIATE_ALWAYS_SIGN
+    | NEGOTIATE_EXTENDED_SESSION_SECURITY
+    | NEGOTIATE_128
+    | NEGOTIATE_56;
+
+/// id of the timestamp on the target info the server sends
+const AV_TIMESTAMP: u16 = 7;
+
+/// seconds between 1601, where windows timestamps start, and 1970
+const WINDOWS_EPOCH_OFFSET: u64 = 11_644_473_600;
+
+/// credentials for NTLMv2, the domain can be given on the user name as
+/// `DOMAIN\user`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Credentials {
+    pub domain: String,
+    pub user: String,
+    pub password: String,
+}
+
+impl Credentials {
+    pub fn new(user: &str, password: &str) -> Credentials {
+        let (domain, user) = user.split_once('\\').unwrap_or(("", user));
+        Credentials {
+            domain: domain.into(),
+            user: user.into(),
+            password: password.into(),
+        }
+    }
+}
+
+/// the first message of the handshake, announcing what we support
+pub fn negotiate_message() -> Vec<u8> {
+    let mut message = Vec::with_capacity(32);
+    message.extend_from_slice(SIGNATURE);
+    message.extend_from_slice(&1u32.to_le_bytes());
+    message.extend_from_slice(&FLAGS.to_le_bytes());
+    // no domain nor workstation are sent, both are empty security buffers
+    message.extend_from_slice(&[0; 16]);
+    message
+}
+
+/// what matters from the challenge the server answers with
+#[derive(Debug, PartialEq)]
+struct Challenge {
+    flags: u32,
+    server_challenge: [u8; 8],
+    target_info: Vec<u8>,
+}
+
+fn parse_challenge(message: &[u8]) -> anyhow::Result<Challenge> {
+    if message.len() < 32 || !message.starts_with(SIGNATURE) || read_u32(message, 8) != Some(2) {
+        anyhow::bail!("the server didn't answer with a NTLM challenge");
+    }
+    let flags = read_u32(message, 20).unwrap_or_default();
+    let server_challenge = message[24..32].try_into().expect("checked the length");
+    let target_info = match (read_u16(message, 40), read_u32(message, 44)) {
+        (Some(len), Some(offset)) => message
+            .get(offset as usize..offset as usize + len as usize)
+            .ok_or_else(|| anyhow::anyhow!("the NTLM challenge is truncated"))?
+            .to_vec(),
+        _ => vec![],
+    };
+
+    Ok(Challenge {
+        flags,
+        server_challenge,
+        target_info,
+    })
+}
+
+/// the last message of the handshake, proving we know the password by
+/// answering the challenge with NTLMv2. The client challenge is random and
+/// the timestamp is the current time when the server didn't send one, when
+/// it did the LMv2 response is left zeroed as MS-NLMP asks
+pub fn authenticate_message(
+    challenge: &[u8],
+    credentials: &Credentials,
+    client_challenge: [u8; 8],
+) -> anyhow::Result<Vec<u8>> {
+    let challenge = parse_challenge(challenge)?;
+    let server_timestamp = server_timestamp(&challenge.target_info);
+    let timestamp = server_timestamp.unwrap_or_else(now_as_filetime);
+    let hash = ntowf_v2(credentials);
+
+    let mut blob = vec![1, 1, 0, 0, 0, 0, 0, 0];
+    blob.extend_from_slice(&timestamp.to_le_bytes());
+    blob.extend_from_slice(&client_challenge);
+    blob.extend_from_slice(&[0; 4]);
+    blob.extend_from_slice(&challenge.target_info);
+    blob.extend_from_slice(&[0; 4]);
+
+    let nt_proof = hmac_md5(&hash, &[&challenge.server_challenge, blob.as_slice()]);
+    let mut nt_response = nt_proof.to_vec();
+    nt_response.extend_from_slice(&blob);
+    let lm_response = match server_timestamp {
+        Some(_) => vec![0; 24],
+        None => {
+            let mut lm_response =
+                hmac_md5(&hash, &[&challenge.server_challenge, &client_challenge]).to_vec();
+            lm_response.extend_from_slice(&client_challenge);
+            lm_response
+        }
+    };
+
+    let fields = [
+        lm_response,
+        nt_response,
+        utf16(&credentials.domain),
+        utf16(&credentials.user),
+        vec![],
+        vec![],
+    ];
+    let mut message = Vec::new();
+    message.extend_from_slice(SIGNATURE);
+    message.extend_from_slice(&3u32.to_le_bytes());
+
+    // security buffers point at the fields, written after the header
+    let mut offset = 64u32;
+    for field in fields.iter() {
+        message.extend_from_slice(&(field.len() as u16).to_le_bytes());
+        message.extend_from_slice(&(field.len() as u16).to_le_bytes());
+        message.extend_from_slice(&offset.to_le_bytes());
+        offset += field.len() as u32;
+    }
+    message.extend_from_slice(&(challenge.flags & FLAGS).to_le_bytes());
+    for field in fields.iter() {
+        message.extend_from_slice(field);
+    }
+
+    Ok(message)
+}
+
+fn server_timestamp(target_info: &[u8]) -> Option<u64> {
+    let mut pos = 0;
+    while let (Some(id), Some(len)) = (read_u16(target_info, pos), read_u16(target_info, pos + 2)) {
+        let value = target_info.get(pos + 4..pos + 4 + len as usize)?;
+        match id {
+            0 => return None,
+            AV_TIMESTAMP => return Some(u64::from_le_bytes(value.try_into().ok()?)),
+            _ => pos += 4 + len as usize,
+        }
+    }
+    None
+}
+
+fn now_as_filetime() -> u64 {
+    let since_epoch = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    (since_epoch.as_secs() + WINDOWS_EPOCH_OFFSET) * 10_000_000
+        + since_epoch.subsec_nanos() as u64 / 100
+}
+
+fn ntowf_v2(credentials: &Credentials) -> [u8; 16] {
+    let nt_hash = md4(&utf16(&credentials.password));
+    let identity = format!("{}{}", credentials.user.to_uppercase(), credentials.domain);
+    hmac_md5(&nt_hash, &[&utf16(&identity)])
+}
+
+fn hmac_md5(key: &[u8], parts: &[&[u8]]) -> [u8; 16] {
+    let mut mac = Hmac::<Md5>::new_from_slice(key).expect("hmac accepts keys of any size");
+    parts.iter().for_each(|part| mac.update(part));
+    mac.finalize().into_bytes().into()
+}
+
+fn utf16(text: &str) -> Vec<u8> {
+    text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+}
+
+fn read_u16(bytes: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        bytes.get(pos..pos + 2)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32(bytes: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        bytes.get(pos..pos + 4)?.try_into().ok()?,
+    ))
+}
+
+fn md4(input: &[u8]) -> [u8; 16] {
+    Md4::digest(input).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    #[test]
+    fn test_hashes() {
+        assert_eq!(hex(&md4(b"")), "31d6cfe0d16ae931b73c59d7e0c089c0");
+        assert_eq!(hex(&md4(b"abc")), "a448017aaf21d8525fc10ae87aa6729d");
+        // values from the examples of the NTLM specification
+        assert_eq!(
+            hex(&md4(&utf16("Password"))),
+            "a4f49c406510bdcab6824ee7c30fd852"
+        );
+        let credentials = Credentials::new("Domain\\User", "Password");
+        assert_eq!(credentials.domain, "Domain");
+        assert_eq!(
+            hex(&ntowf_v2(&credentials)),
+            "0c868a403bfd7a93a3001ef22ef02e3f"
+        );
+    }
+
+    fn challenge_message(target_info: &[u8]) -> Vec<u8> {
+        let mut challenge = SIGNATURE.to_vec();
+        challenge.extend_from_slice(&2u32.to_le_bytes());
+        challenge.extend_from_slice(&[0; 8]);
+        challenge.extend_from_slice(&FLAGS.to_le_bytes());
+        challenge.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        challenge.extend_from_slice(&[0; 8]);
+        challenge.extend_from_slice(&(target_info.len() as u16).to_le_bytes());
+        challenge.extend_from_slice(&(target_info.len() as u16).to_le_bytes());
+        challenge.extend_from_slice(&48u32.to_le_bytes());
+        challenge.extend_from_slice(target_info);
+        challenge
+    }
+
+    /// the LMv2 response, the first field of the authenticate message
+    fn lm_response(message: &[u8]) -> &[u8] {
+        let (len, offset) = (
+            read_u16(message, 12).unwrap() as usize,
+            read_u32(message, 16).unwrap() as usize,
+        );
+        &message[offset..offset + len]
+    }
+
+    #[test]
+    fn test_handshake_messages() {
+        let negotiate = negotiate_message();
+        assert_eq!(negotiate.len(), 32);
+        assert!(negotiate.starts_with(SIGNATURE));
+
+        let target_info = [
+            2, 0, 4, 0, b'D', 0, b'C', 0, 7, 0, 8, 0, 1, 2, 3, 4, 5, 6, 7, 8, 0, 0, 0, 0,
+        ];
+        let challenge = challenge_message(&target_info);
+
+        let parsed = parse_challenge(&challenge).unwrap();
+        assert_eq!(parsed.server_challenge, [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(
+            server_timestamp(&parsed.target_info),
+            Some(u64::from_le_bytes([1, 2, 3, 4, 5, 6, 7, 8]))
+        );
+
+        let credentials = Credentials::new("User", "Password");
+        let message = authenticate_message(&challenge, &credentials, [9; 8]).unwrap();
+        assert_eq!(read_u32(&message, 8), Some(3));
+        // the user is the fourth field, after the responses and the domain
+        let (len, offset) = (
+            read_u16(&message, 36).unwrap(),
+            read_u32(&message, 40).unwrap(),
+        );
+        assert_eq!(
+            &message[offset as usize..offset as usize + len as usize],
+            utf16("User").as_slice()
+        );
+        // the blob carries the timestamp of the server and our challenge
+        let nt_offset = read_u32(&message, 24).unwrap() as usize;
+        assert_eq!(
+            &message[nt_offset + 24..nt_offset + 32],
+            &[1, 2, 3, 4, 5, 6, 7, 8]
+        );
+        assert_eq!(&message[nt_offset + 32..nt_offset + 40], &[9; 8]);
+
+        assert!(parse_challenge(&negotiate).is_err());
+    }
+
+    #[test]
+    fn test_lm_response() {
+        let credentials = Credentials::new("User", "Password");
+
+        // the server sent a timestamp, so the LMv2 response is zeroed
+        let with_timestamp = challenge_message(&[7, 0, 8, 0, 1, 2, 3, 4, 5, 6, 7, 8, 0, 0, 0, 0]);
+        let message = authenticate_message(&with_timestamp, &credentials, [9; 8]).unwrap();
+        assert_eq!(lm_response(&message), &[0; 24]);
+
+        let without_timestamp = challenge_message(&[2, 0, 4, 0, b'D', 0, b'C', 0, 0, 0, 0, 0]);
+        let message = authenticate_message(&without_timestamp, &credentials, [9; 8]).unwrap();
+        let lm = lm_response(&message);
+        assert_eq!(lm.len(), 24);
+        assert_eq!(&lm[16..], &[9; 8]);
+        assert_ne!(&lm[..16], &[0; 16]);
+    }
+}
@@ -0,0 +1,184 @@
+use crate::collection::types::{OAuth1Config, OAuth1SignatureMethod};
+use crate::net::auth::Nonce;
+
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::signature::{SignatureEncoding, Signer};
+use sha1::Sha1;
+
+/// value of the `Authorization` header signing the request, as described
+/// by RFC 5849. Only the query is signed, as bodies are never form encoded
+pub fn authorization(
+    method: &str,
+    url: &reqwest::Url,
+    config: &OAuth1Config,
+    nonce: &Nonce,
+) -> anyhow::Result<String> {
+    let mut params = vec![
+        ("oauth_consumer_key", config.consumer_key.clone()),
+        ("oauth_nonce", nonce.nonce.clone()),
+        ("oauth_signature_method", signature_method(config).into()),
+        ("oauth_timestamp", nonce.timestamp.to_string()),
+        ("oauth_version", "1.0".into()),
+    ];
+    if !config.token.is_empty() {
+        params.push(("oauth_token", config.token.clone()));
+    }
+
+    let signature = sign(&base_string(method, url, &params), config)?;
+    params.push(("oauth_signature", signature));
+    params.sort();
+
+    let mut fields = config
+        .realm
+        .iter()
+        .map(|realm| format!("realm=\"{realm}\""))
+        .collect::<Vec<_>>();
+    fields.extend(
+        params
+            .iter()
+            .map(|(name, value)| format!("{name}=\"{}\"", encode(value))),
+    );
+    Ok(format!("OAuth {}", fields.join(", ")))
+}
+
+fn signature_method(config: &OAuth1Config) -> &'static str {
+    match config.signature_method {
+        OAuth1SignatureMethod::HmacSha1 => "HMAC-SHA1",
+        OAuth1SignatureMethod::RsaSha1 => "RSA-SHA1",
+        OAuth1SignatureMethod::Plaintext => "PLAINTEXT",
+    }
+}
+
+/// the method, the uri without its query and every parameter sorted and
+/// encoded, joined by `&`
+fn base_string(method: &str, url: &reqwest::Url, oauth_params: &[(&str, String)]) -> String {
+    let mut base_url = format!(
+        "{}://{}",
+        url.scheme(),
+        url.host_str().unwrap_or_default().to_lowercase()
+    );
+    if let Some(port) = url.port() {
+        base_url.push_str(&format!(":{port}"));
+    }
+    base_url.push_str(url.path());
+
+    let mut params = url
+        .query_pairs()
+        .map(|(name, value)| (encode(&name), encode(&value)))
+        .chain(
+            oauth_params
+                .iter()
+                .map(|(name, value)| (encode(name), encode(value))),
+        )
+        .collect::<Vec<_>>();
+    params.sort();
+    let params = params
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    format!(
+        "{}&{}&{}",
+        method.to_uppercase(),
+        encode(&base_url),
+        encode(&params)
+    )
+}
+
+fn sign(base_string: &str, config: &OAuth1Config) -> anyhow::Result<String> {
+    let key = format!(
+        "{}&{}",
+        encode(&config.consumer_secret),
+        encode(&config.token_secret)
+    );
+
+    match config.signature_method {
+        OAuth1SignatureMethod::Plaintext => Ok(key),
+        OAuth1SignatureMethod::HmacSha1 => {
+            let mut mac =
+                Hmac::<Sha1>::new_from_slice(key.as_bytes()).expect("hmac accepts any key");
+            mac.update(base_string.as_bytes());
+            Ok(base64::engine::general_purpose::STANDARD.encode(mac.finalize().into_bytes()))
+        }
+        OAuth1SignatureMethod::RsaSha1 => {
+            let Some(private_key) = config.private_key.as_deref() else {
+                anyhow::bail!("RSA-SHA1 needs the private key on the oauth1 config");
+            };
+            let pem = match private_key.trim_start().starts_with("-----BEGIN") {
+                true => private_key.to_string(),
+                false => std::fs::read_to_string(private_key).map_err(|e| {
+                    anyhow::anyhow!("failed to read the private key at {private_key}: {e}")
+                })?,
+            };
+            let key = rsa::RsaPrivateKey::from_pkcs8_pem(&pem)
+                .or_else(|_| rsa::RsaPrivateKey::from_pkcs1_pem(&pem))
+                .map_err(|e| anyhow::anyhow!("the private key is not a valid RSA key: {e}"))?;
+            let signer = rsa::pkcs1v15::SigningKey::<Sha1>::new(key);
+            let signature = signer.sign(base_string.as_bytes());
+            Ok(base64::engine::general_purpose::STANDARD.encode(signature.to_bytes()))
+        }
+    }
+}
+
+/// percent encodes every byte that is not an unreserved character, which is
+/// the only encoding OAuth accepts
+pub(super) fn encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authorization() {
+        // example of the OAuth Core 1.0 specification
+        let config = OAuth1Config {
+            consumer_key: "dpf43f3p2l4k3l03".into(),
+            consumer_secret: "kd94hf93k423kf44".into(),
+            token: "nnch734d00sl2jdk".into(),
+            token_secret: "pfkkdhi9sl3r4s00".into(),
+            signature_method: OAuth1SignatureMethod::HmacSha1,
+            private_key: None,
+            realm: Some("http://photos.example.net/".into()),
+        };
+        let url =
+            reqwest::Url::parse("http://photos.example.net/photos?file=vacation.jpg&size=original")
+                .unwrap();
+        let nonce = Nonce {
+            nonce: "kllo9940pd9333jh".into(),
+            timestamp: 1191242096,
+        };
+
+        let header = authorization("GET", &url, &config, &nonce).unwrap();
+        assert_eq!(
+            header,
+            "OAuth realm=\"http://photos.example.net/\", \
+             oauth_consumer_key=\"dpf43f3p2l4k3l03\", \
+             oauth_nonce=\"kllo9940pd9333jh\", \
+             oauth_signature=\"tR3%2BTy81lMeYAr%2FFid0kMTYa%2FWM%3D\", \
+             oauth_signature_method=\"HMAC-SHA1\", \
+             oauth_timestamp=\"1191242096\", \
+             oauth_token=\"nnch734d00sl2jdk\", \
+             oauth_version=\"1.0\""
+        );
+
+        let config = OAuth1Config {
+            signature_method: OAuth1SignatureMethod::Plaintext,
+            ..config
+        };
+        let header = authorization("GET", &url, &config, &nonce).unwrap();
+        assert!(header.contains("oauth_signature=\"kd94hf93k423kf44%26pfkkdhi9sl3r4s00\""));
+    }
+}
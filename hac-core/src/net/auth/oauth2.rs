@@ -0,0 +1,409 @@
+//! the OAuth device authorization grant, described by RFC 8628, where the
+//! user signs in on another device while we poll for the token
+use crate::collection::types::{DeviceCodeConfig, HeaderMap, Request};
+use crate::net::auth::oauth1::encode;
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tokio::sync::mpsc::UnboundedSender;
+
+const DEVICE_CODE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+const REFRESH_TOKEN_GRANT: &str = "refresh_token";
+/// seconds between polls when the server doesn't say otherwise
+const DEFAULT_INTERVAL: u64 = 5;
+/// tokens expiring within this many seconds are refreshed before sending, so
+/// they don't expire while the request is in flight
+const REFRESH_MARGIN: i64 = 60;
+
+/// what the user needs to sign in, as answered by the device authorization
+/// endpoint
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct DeviceAuthorization {
+    pub device_code: String,
+    pub user_code: String,
+    /// azure AD v1 endpoints call it `verification_url`
+    #[serde(alias = "verification_url")]
+    pub verification_uri: String,
+    /// the verification uri with the code already filled in
+    #[serde(default)]
+    pub verification_uri_complete: Option<String>,
+    pub expires_in: u64,
+    #[serde(default)]
+    pub interval: Option<u64>,
+    /// instructions some providers send, ready to be shown to the user
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// an access token issued by the token endpoint
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub refresh_token: Option<String>,
+}
+
+impl Token {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// seconds until the token expires, negative when it already did, or
+    /// `None` when the server didn't say when it would
+    pub fn expires_in(&self, now: DateTime<Utc>) -> Option<i64> {
+        self.expires_at
+            .map(|expires_at| expires_at.signed_duration_since(now).num_seconds())
+    }
+
+    /// value of the `Authorization` header carrying the token
+    pub fn authorization(&self) -> String {
+        match self.token_type.eq_ignore_ascii_case("bearer") || self.token_type.is_empty() {
+            true => format!("Bearer {}", self.access_token),
+            false => format!("{} {}", self.token_type, self.access_token),
+        }
+    }
+}
+
+/// adds the token to the request, replacing any `Authorization` header it
+/// already had
+pub fn authorize(request: &mut Request, token: &Token) {
+    let headers = request.headers.get_or_insert_with(Vec::new);
+    headers.retain(|header| !header.pair.0.eq_ignore_ascii_case("authorization"));
+    headers.push(HeaderMap {
+        pair: ("Authorization".into(), token.authorization()),
+        enabled: true,
+    });
+}
+
+/// what has to happen before a request signing in with a config can be sent
+#[derive(Debug, PartialEq)]
+pub enum TokenState<'a> {
+    Valid(&'a Token),
+    /// the token is about to expire, or already did, but can be refreshed
+    /// without the user signing in again
+    Refresh(&'a str),
+    SignIn,
+}
+
+/// tokens acquired through the device code flow, by the key of their config,
+/// only kept while hac runs
+#[derive(Debug, Default)]
+pub struct TokenStore {
+    tokens: HashMap<String, Token>,
+}
+
+impl TokenStore {
+    pub fn get(&self, key: &str) -> Option<&Token> {
+        self.tokens.get(key)
+    }
+
+    pub fn insert(&mut self, key: String, token: Token) {
+        self.tokens.insert(key, token);
+    }
+
+    /// drops the token of the config, so the next request signs in again
+    pub fn forget(&mut self, key: &str) -> bool {
+        self.tokens.remove(key).is_some()
+    }
+
+    pub fn state(&self, key: &str, now: DateTime<Utc>) -> TokenState<'_> {
+        let Some(token) = self.tokens.get(key) else {
+            return TokenState::SignIn;
+        };
+        let expiring = token
+            .expires_in(now)
+            .is_some_and(|secs| secs <= REFRESH_MARGIN);
+        match (expiring, token.refresh_token.as_deref()) {
+            (false, _) => TokenState::Valid(token),
+            (true, Some(refresh_token)) => TokenState::Refresh(refresh_token),
+            (true, None) if !token.is_expired(now) => TokenState::Valid(token),
+            (true, None) => TokenState::SignIn,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum DeviceFlowEvent {
+    /// the user has to sign in with the code on the verification uri
+    Started(DeviceAuthorization),
+    /// the user signed in, the token is issued for the config with the key
+    Authorized(String, Token),
+    Failed(String),
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    #[serde(default)]
+    token_type: Option<String>,
+    #[serde(default)]
+    expires_in: Option<u64>,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    error_description: Option<String>,
+}
+
+/// what a poll of the token endpoint yielded
+#[derive(Debug, PartialEq)]
+enum Poll {
+    Issued(Token),
+    Pending,
+    SlowDown,
+}
+
+/// signs in through the device authorization grant on the background,
+/// reporting every step through the channel. When a refresh token is given
+/// it is tried first, and the user only signs in when it is refused
+pub fn run_device_flow(
+    config: DeviceCodeConfig,
+    client: reqwest::Client,
+    refresh_token: Option<String>,
+    events_tx: UnboundedSender<DeviceFlowEvent>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        if let Some(refresh_token) = refresh_token {
+            match refresh(&client, &config, &refresh_token).await {
+                Ok(token) => {
+                    _ = events_tx.send(DeviceFlowEvent::Authorized(config.key(), token));
+                    return;
+                }
+                Err(e) => tracing::warn!("failed to refresh the token, signing in again: {e}"),
+            }
+        }
+
+        let authorization = match request_device_code(&client, &config).await {
+            Ok(authorization) => authorization,
+            Err(e) => {
+                _ = events_tx.send(DeviceFlowEvent::Failed(e.to_string()));
+                return;
+            }
+        };
+        _ = events_tx.send(DeviceFlowEvent::Started(authorization.clone()));
+
+        let event = match poll_token(&client, &config, &authorization).await {
+            Ok(token) => DeviceFlowEvent::Authorized(config.key(), token),
+            Err(e) => DeviceFlowEvent::Failed(e.to_string()),
+        };
+        _ = events_tx.send(event);
+    })
+}
+
+pub async fn request_device_code(
+    client: &reqwest::Client,
+    config: &DeviceCodeConfig,
+) -> anyhow::Result<DeviceAuthorization> {
+    let mut params = vec![("client_id", config.client_id.as_str())];
+    if let Some(scope) = config.scope.as_deref() {
+        params.push(("scope", scope));
+    }
+
+    let response = post_form(client, &config.device_authorization_url, &params).await?;
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        anyhow::bail!("the device authorization endpoint responded with {status}: {body}");
+    }
+    serde_json::from_str(&body).map_err(|e| {
+        anyhow::anyhow!("unexpected answer from the device authorization endpoint: {e}")
+    })
+}
+
+/// polls the token endpoint until the user signs in, refuses to, or the code
+/// expires
+pub async fn poll_token(
+    client: &reqwest::Client,
+    config: &DeviceCodeConfig,
+    authorization: &DeviceAuthorization,
+) -> anyhow::Result<Token> {
+    let deadline = std::time::Instant::now() + Duration::from_secs(authorization.expires_in);
+    let mut interval = authorization.interval.unwrap_or(DEFAULT_INTERVAL);
+
+    let mut params = vec![
+        ("grant_type", DEVICE_CODE_GRANT),
+        ("device_code", authorization.device_code.as_str()),
+        ("client_id", config.client_id.as_str()),
+    ];
+    if let Some(secret) = config.client_secret.as_deref() {
+        params.push(("client_secret", secret));
+    }
+
+    while std::time::Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+        let response = post_form(client, &config.token_url, &params).await?;
+        let body = response.text().await?;
+        match parse_token_response(&body, Utc::now())? {
+            Poll::Issued(token) => return Ok(token),
+            Poll::Pending => {}
+            Poll::SlowDown => interval += 5,
+        }
+    }
+
+    anyhow::bail!("the code expired before the sign in was completed")
+}
+
+/// exchanges the refresh token for a new token, keeping the refresh token
+/// when the server doesn't rotate it
+pub async fn refresh(
+    client: &reqwest::Client,
+    config: &DeviceCodeConfig,
+    refresh_token: &str,
+) -> anyhow::Result<Token> {
+    let mut params = vec![
+        ("grant_type", REFRESH_TOKEN_GRANT),
+        ("refresh_token", refresh_token),
+        ("client_id", config.client_id.as_str()),
+    ];
+    if let Some(secret) = config.client_secret.as_deref() {
+        params.push(("client_secret", secret));
+    }
+    if let Some(scope) = config.scope.as_deref() {
+        params.push(("scope", scope));
+    }
+
+    let response = post_form(client, &config.token_url, &params).await?;
+    let body = response.text().await?;
+    match parse_token_response(&body, Utc::now())? {
+        Poll::Issued(mut token) => {
+            token.refresh_token = token.refresh_token.or(Some(refresh_token.into()));
+            Ok(token)
+        }
+        _ => anyhow::bail!("the token endpoint answered without a token"),
+    }
+}
+
+fn parse_token_response(body: &str, now: DateTime<Utc>) -> anyhow::Result<Poll> {
+    let response = serde_json::from_str::<TokenResponse>(body)
+        .map_err(|e| anyhow::anyhow!("unexpected answer from the token endpoint: {e}"))?;
+
+    match (response.access_token, response.error.as_deref()) {
+        (Some(access_token), _) => Ok(Poll::Issued(Token {
+            access_token,
+            token_type: response.token_type.unwrap_or_default(),
+            expires_at: response
+                .expires_in
+                .map(|secs| now + chrono::Duration::seconds(secs as i64)),
+            refresh_token: response.refresh_token,
+        })),
+        (None, Some("authorization_pending")) => Ok(Poll::Pending),
+        (None, Some("slow_down")) => Ok(Poll::SlowDown),
+        (None, Some("access_denied")) => anyhow::bail!("the sign in was declined"),
+        (None, Some("expired_token")) => {
+            anyhow::bail!("the code expired before the sign in was completed")
+        }
+        (None, Some(error)) => match response.error_description {
+            Some(description) => anyhow::bail!("{error}: {description}"),
+            None => anyhow::bail!("the token endpoint failed with {error}"),
+        },
+        (None, None) => anyhow::bail!("the token endpoint answered without a token"),
+    }
+}
+
+async fn post_form(
+    client: &reqwest::Client,
+    url: &str,
+    params: &[(&str, &str)],
+) -> anyhow::Result<reqwest::Response> {
+    let body = params
+        .iter()
+        .map(|(name, value)| format!("{}={}", encode(name), encode(value)))
+        .collect::<Vec<_>>()
+        .join("&");
+    let response = client
+        .post(url)
+        .header(
+            reqwest::header::CONTENT_TYPE,
+            "application/x-www-form-urlencoded",
+        )
+        .header(reqwest::header::ACCEPT, "application/json")
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to reach {url}: {e}"))?;
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_token_response() {
+        let now = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(
+            parse_token_response(r#"{"error":"authorization_pending"}"#, now).unwrap(),
+            Poll::Pending
+        );
+        assert_eq!(
+            parse_token_response(r#"{"error":"slow_down"}"#, now).unwrap(),
+            Poll::SlowDown
+        );
+        assert!(parse_token_response(r#"{"error":"access_denied"}"#, now).is_err());
+
+        let Poll::Issued(token) = parse_token_response(
+            r#"{"access_token":"abc","token_type":"Bearer","expires_in":3600,"refresh_token":"def"}"#,
+            now,
+        )
+        .unwrap() else {
+            panic!("expected a token");
+        };
+        assert_eq!(token.authorization(), "Bearer abc");
+        assert_eq!(token.refresh_token.as_deref(), Some("def"));
+        assert!(!token.is_expired(now));
+        assert!(token.is_expired(now + chrono::Duration::hours(1)));
+
+        let authorization: DeviceAuthorization = serde_json::from_str(
+            r#"{"device_code":"d","user_code":"ABCD-EFGH","verification_url":"https://microsoft.com/devicelogin","expires_in":900}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            authorization.verification_uri,
+            "https://microsoft.com/devicelogin"
+        );
+    }
+
+    #[test]
+    fn test_token_state() {
+        let now = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let token = |expires_in: i64, refresh_token: Option<&str>| Token {
+            access_token: "abc".into(),
+            token_type: "Bearer".into(),
+            expires_at: Some(now + chrono::Duration::seconds(expires_in)),
+            refresh_token: refresh_token.map(Into::into),
+        };
+
+        let mut store = TokenStore::default();
+        assert_eq!(store.state("key", now), TokenState::SignIn);
+
+        store.insert("key".into(), token(3600, Some("def")));
+        assert_eq!(
+            store.state("key", now),
+            TokenState::Valid(&token(3600, Some("def")))
+        );
+        assert_eq!(store.get("key").unwrap().expires_in(now), Some(3600));
+
+        store.insert("key".into(), token(30, Some("def")));
+        assert_eq!(store.state("key", now), TokenState::Refresh("def"));
+
+        store.insert("key".into(), token(30, None));
+        assert_eq!(store.state("key", now), TokenState::Valid(&token(30, None)));
+
+        store.insert("key".into(), token(-30, None));
+        assert_eq!(store.state("key", now), TokenState::SignIn);
+
+        assert!(store.forget("key"));
+        assert!(!store.forget("key"));
+        assert_eq!(store.state("key", now), TokenState::SignIn);
+    }
+}
@@ -0,0 +1,198 @@
+use crate::net::request_manager::{ContentType, Response};
+use crate::syntax::xml;
+use crate::text_object::TextObject;
+
+use std::path::{Path, PathBuf};
+
+use reqwest::header::HeaderMap;
+
+/// the start of the text, at most `limit` bytes long. It ends on the last
+/// line break before the limit when there is one, so no line is cut
+pub fn head(text: &str, limit: usize) -> &str {
+    if text.len().le(&limit) {
+        return text;
+    }
+    let mut end = limit;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    match text[..end].rfind('\n') {
+        Some(idx) if idx.gt(&0) => &text[..idx],
+        _ => &text[..end],
+    }
+}
+
+/// how much of the body of a response is kept in memory and pretty printed
+/// as it is decoded, so large bodies don't hold up the interface
+#[derive(Debug, Clone)]
+pub struct BodyLimit {
+    /// bodies larger than this many bytes are written whole to `path`, and
+    /// only their start is kept in memory
+    pub history_bytes: u64,
+    /// only this many bytes of the body are pretty printed, until the whole
+    /// of it is asked for
+    pub display_bytes: u64,
+    pub path: PathBuf,
+}
+
+impl BodyLimit {
+    pub fn new(limits: &hac_config::LimitsConfig, path: PathBuf) -> Self {
+        BodyLimit {
+            history_bytes: limits.history_bytes,
+            display_bytes: limits.display_bytes,
+            path,
+        }
+    }
+}
+
+/// keeps only the start of bodies larger than the limit in memory, writing
+/// the whole of it to the path of the limit. Returns the body that is kept,
+/// along with where the whole of it was written when it was
+pub async fn spill(limit: Option<&BodyLimit>, body: String) -> (String, Option<PathBuf>) {
+    let Some(limit) = limit.filter(|limit| (body.len() as u64).gt(&limit.history_bytes)) else {
+        return (body, None);
+    };
+
+    if let Err(e) = write(&limit.path, &body).await {
+        tracing::warn!("failed to write the body of the response to disk: {e}");
+        return (body, None);
+    }
+    let kept = head(&body, limit.history_bytes as usize).to_string();
+    (kept, Some(limit.path.clone()))
+}
+
+async fn write(path: &Path, body: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    tokio::fs::write(path, body).await
+}
+
+/// the start of the body that is pretty printed as it is decoded
+pub fn shown<'a>(limit: Option<&BodyLimit>, body: &'a str) -> &'a str {
+    match limit {
+        Some(limit) => head(body, limit.display_bytes as usize),
+        None => body,
+    }
+}
+
+/// where the whole body of a response is, when the response only holds its
+/// start or only has its start pretty printed
+#[derive(Debug, Clone, PartialEq)]
+pub enum BodySource {
+    Memory(String),
+    File(PathBuf),
+}
+
+impl BodySource {
+    /// `None` when the response already holds the whole of its body, pretty
+    /// printed
+    pub fn of(response: &Response) -> Option<BodySource> {
+        match (response.body_file.as_ref(), response.pretty_truncated) {
+            (Some(path), _) => Some(BodySource::File(path.clone())),
+            (None, true) => response.body.clone().map(BodySource::Memory),
+            (None, false) => None,
+        }
+    }
+
+    /// reads the whole body back and pretty prints it. This blocks for as
+    /// long as it takes, so it is meant to run away from the interface
+    pub fn load(self, headers: Option<&HeaderMap>) -> std::io::Result<FullBody> {
+        let body = match self {
+            BodySource::Memory(body) => body,
+            BodySource::File(path) => std::fs::read_to_string(path)?,
+        };
+        let pretty_body = pretty_print(&body, headers);
+        Ok(FullBody { body, pretty_body })
+    }
+}
+
+/// the whole body of a response, loaded back by [`BodySource::load`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FullBody {
+    pub body: String,
+    pub pretty_body: String,
+}
+
+impl FullBody {
+    /// replaces the start of the body the response holds by the whole of it
+    pub fn apply(self, response: &mut Response) {
+        response.pretty_body = Some(TextObject::from(&self.pretty_body));
+        response.body = Some(self.body);
+        response.body_file = None;
+        response.pretty_truncated = false;
+    }
+}
+
+/// pretty prints the body as the decoder of its content type does, keeping
+/// it as is when it can't be
+fn pretty_print(body: &str, headers: Option<&HeaderMap>) -> String {
+    let content_type = headers
+        .and_then(|headers| headers.get("Content-Type"))
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    match ContentType::from(content_type) {
+        ContentType::ApplicationXml => xml::pretty_print(body).unwrap_or_else(|| body.to_string()),
+        _ => jsonxf::pretty_print(body).unwrap_or_else(|_| body.to_string()),
+    }
+}
+
+/// the whole body of the response, `None` when only its start is kept in
+/// memory and it has to be loaded back first
+pub fn full_body(response: &Response) -> Option<String> {
+    match response.body_file {
+        Some(_) => None,
+        None => response.body.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_head() {
+        assert_eq!(head("short", 10), "short");
+        assert_eq!(
+            head("line one\nline two\nline three", 20),
+            "line one\nline two"
+        );
+        assert_eq!(head("a single long line", 8), "a single");
+        // never cuts a character in half
+        assert_eq!(head("ação", 2), "a");
+    }
+
+    #[tokio::test]
+    async fn test_spill() {
+        let dir = std::env::temp_dir().join(format!("hac-spill-{}", std::process::id()));
+        let limit = BodyLimit {
+            history_bytes: 64,
+            display_bytes: 32,
+            path: dir.join("request.body"),
+        };
+        let body = (0..100)
+            .map(|n| format!("{{\"n\":{n}}}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let (kept, body_file) = spill(Some(&limit), body.clone()).await;
+        assert!(kept.len() <= 64);
+        assert!(body.starts_with(&kept));
+        assert_eq!(body_file.as_ref(), Some(&limit.path));
+        assert!(shown(Some(&limit), &kept).len() <= 32);
+        assert_eq!(spill(None, body.clone()).await, (body.clone(), None));
+
+        let mut response = Response::from_error("");
+        response.body = Some(kept);
+        response.body_file = body_file;
+        assert_eq!(full_body(&response), None);
+
+        let source = BodySource::of(&response).unwrap();
+        source.load(None).unwrap().apply(&mut response);
+        assert_eq!(response.body, Some(body.clone()));
+        assert_eq!(full_body(&response), Some(body));
+        assert!(response.body_file.is_none());
+        assert_eq!(BodySource::of(&response), None);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
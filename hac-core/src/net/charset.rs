@@ -0,0 +1,86 @@
+use encoding_rs::Encoding;
+use reqwest::header::{HeaderMap, CONTENT_TYPE};
+
+/// finds the encoding specified by the `charset` parameter of a content type,
+/// eg: `text/plain; charset=ISO-8859-1`
+pub fn charset_from_content_type(content_type: &str) -> Option<&'static Encoding> {
+    content_type
+        .split(';')
+        .skip(1)
+        .filter_map(|param| param.split_once('='))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("charset"))
+        .and_then(|(_, value)| Encoding::for_label(value.trim().trim_matches('"').as_bytes()))
+}
+
+/// finds the encoding of a charset label, like `latin1` or `shift_jis`,
+/// warning when it isn't a known one
+pub fn encoding_for_label(label: &str) -> Option<&'static Encoding> {
+    let encoding = Encoding::for_label(label.trim().as_bytes());
+    if encoding.is_none() {
+        tracing::warn!("{label:?} is not a known charset, sending the body as UTF-8");
+    }
+    encoding
+}
+
+/// encodes a body with the given encoding, characters that cannot be
+/// represented on the encoding are replaced by numeric character references
+pub fn encode_body(body: &str, encoding: &'static Encoding) -> Vec<u8> {
+    let (bytes, _, had_errors) = encoding.encode(body);
+    if had_errors {
+        tracing::warn!(
+            "body has characters that can't be represented in {}",
+            encoding.name()
+        );
+    }
+    bytes.into_owned()
+}
+
+/// decodes a response body using the charset of its `Content-Type`, falling
+/// back to UTF-8 when there is none, a byte order mark always takes precedence
+pub fn decode_body(bytes: &[u8], headers: &HeaderMap) -> String {
+    let encoding = headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(charset_from_content_type)
+        .unwrap_or(encoding_rs::UTF_8);
+
+    let (body, _, _) = encoding.decode(bytes);
+    body.into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_charset_from_content_type() {
+        let latin1 = charset_from_content_type("text/plain; charset=\"ISO-8859-1\"");
+        let sjis = charset_from_content_type("application/json;Charset=shift_jis");
+
+        assert_eq!(latin1, Some(encoding_rs::WINDOWS_1252));
+        assert_eq!(sjis, Some(encoding_rs::SHIFT_JIS));
+        assert_eq!(charset_from_content_type("application/json"), None);
+    }
+
+    #[test]
+    fn test_encode_and_decode_body() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("text/plain; charset=shift_jis"),
+        );
+
+        let encoded = encode_body("こんにちは", encoding_rs::SHIFT_JIS);
+        assert_ne!(encoded, "こんにちは".as_bytes());
+        assert_eq!(decode_body(&encoded, &headers), "こんにちは");
+
+        let latin1 = [0x63, 0x61, 0x66, 0xe9];
+        headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("text/plain; charset=latin1"),
+        );
+        assert_eq!(decode_body(&latin1, &headers), "café");
+        assert_eq!(decode_body("café".as_bytes(), &HeaderMap::new()), "café");
+    }
+}
@@ -0,0 +1,164 @@
+use hac_config::IpVersion;
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+
+/// what outgoing connections are bound to, curl's `--interface` takes
+/// either an interface name or an address
+#[derive(Debug, Clone, PartialEq)]
+pub enum Binding {
+    Address(IpAddr),
+    Interface(String),
+}
+
+impl Binding {
+    /// parses the value of curl's `--interface`, which can be prefixed with
+    /// `if!` or `host!` to say what it is
+    pub fn parse(value: &str) -> Binding {
+        if let Some(name) = value.strip_prefix("if!") {
+            return Binding::Interface(name.into());
+        }
+        let value = value.strip_prefix("host!").unwrap_or(value);
+        match value.parse() {
+            Ok(address) => Binding::Address(address),
+            Err(_) => Binding::Interface(value.into()),
+        }
+    }
+}
+
+/// resolves hosts to addresses of a single ip version only
+#[derive(Debug)]
+struct FamilyResolver(IpVersion);
+
+impl Resolve for FamilyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let version = self.0;
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs = tokio::net::lookup_host((host.as_str(), 0)).await?;
+            let addrs = filter_addrs(addrs, version);
+            if addrs.is_empty() {
+                let family = match version {
+                    IpVersion::V6 => "IPv6",
+                    _ => "IPv4",
+                };
+                return Err(format!("{host} has no {family} address").into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// keeps only the addresses of the given ip version
+pub fn filter_addrs(
+    addrs: impl IntoIterator<Item = SocketAddr>,
+    version: IpVersion,
+) -> Vec<SocketAddr> {
+    addrs
+        .into_iter()
+        .filter(|addr| match version {
+            IpVersion::Any => true,
+            IpVersion::V4 => addr.is_ipv4(),
+            IpVersion::V6 => addr.is_ipv6(),
+        })
+        .collect()
+}
+
+/// makes the connections of the client resolve hosts to the given ip
+/// version only, and go out of the given address or interface
+pub fn configure(
+    mut builder: reqwest::ClientBuilder,
+    version: IpVersion,
+    binding: Option<Binding>,
+) -> anyhow::Result<reqwest::ClientBuilder> {
+    if version != IpVersion::Any {
+        builder = builder.dns_resolver(Arc::new(FamilyResolver(version)));
+    }
+    match binding {
+        Some(Binding::Address(address)) => builder = builder.local_address(address),
+        Some(Binding::Interface(interface)) => builder = bind_interface(builder, &interface)?,
+        None => {}
+    }
+    Ok(builder)
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "fuchsia",
+    target_os = "illumos",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "solaris",
+    target_os = "tvos",
+    target_os = "visionos",
+    target_os = "watchos",
+))]
+fn bind_interface(
+    builder: reqwest::ClientBuilder,
+    interface: &str,
+) -> anyhow::Result<reqwest::ClientBuilder> {
+    Ok(builder.interface(interface))
+}
+
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "fuchsia",
+    target_os = "illumos",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "solaris",
+    target_os = "tvos",
+    target_os = "visionos",
+    target_os = "watchos",
+)))]
+fn bind_interface(
+    _: reqwest::ClientBuilder,
+    interface: &str,
+) -> anyhow::Result<reqwest::ClientBuilder> {
+    anyhow::bail!("binding to the interface {interface} is not supported on this platform")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binding_parse() {
+        assert_eq!(
+            Binding::parse("192.168.0.10"),
+            Binding::Address("192.168.0.10".parse().unwrap())
+        );
+        assert_eq!(
+            Binding::parse("host!::1"),
+            Binding::Address("::1".parse().unwrap())
+        );
+        assert_eq!(Binding::parse("eth0"), Binding::Interface("eth0".into()));
+        assert_eq!(
+            Binding::parse("if!10.0.0.1"),
+            Binding::Interface("10.0.0.1".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_family() {
+        let addrs = ["127.0.0.1:0", "[::1]:0"]
+            .map(|addr| addr.parse::<SocketAddr>().unwrap())
+            .to_vec();
+        assert_eq!(filter_addrs(addrs.clone(), IpVersion::Any), addrs);
+        assert_eq!(filter_addrs(addrs.clone(), IpVersion::V4), addrs[..1]);
+        assert_eq!(filter_addrs(addrs.clone(), IpVersion::V6), addrs[1..]);
+
+        let resolver = FamilyResolver(IpVersion::V4);
+        let resolved = resolver
+            .resolve("localhost".parse().unwrap())
+            .await
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert!(!resolved.is_empty());
+        assert!(resolved.iter().all(SocketAddr::is_ipv4));
+    }
+}
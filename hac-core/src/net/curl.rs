@@ -0,0 +1,175 @@
+use crate::collection::types::{AuthMethod, AutomaticHeader, BodyType, Request};
+use crate::net::request_manager::prepare_request;
+use crate::net::soap;
+
+/// renders the request as a `curl` command that sends the same request,
+/// variables are expected to already be resolved
+pub fn to_curl(request: &Request, config: &hac_config::Config) -> String {
+    let request = prepare_request(request.clone(), config);
+    let mut command = format!("curl -X {} {}", request.method, quote(&request.uri));
+
+    // curl takes NTLM credentials from the uri, and kerberos needs an empty
+    // user to use the ticket of the current one
+    match request.auth_method {
+        Some(AuthMethod::Ntlm) => command.push_str(" \\\n  --ntlm"),
+        Some(AuthMethod::Negotiate) => command.push_str(" \\\n  --negotiate -u :"),
+        _ => {}
+    }
+
+    let headers = request
+        .headers
+        .iter()
+        .flatten()
+        .filter(|header| header.enabled)
+        .map(|header| header.pair.clone())
+        .chain(
+            soap::missing_headers(&request)
+                .into_iter()
+                .filter(|(name, _)| {
+                    !(name.eq_ignore_ascii_case("content-type")
+                        && request.omits(AutomaticHeader::ContentType))
+                }),
+        )
+        .collect::<Vec<_>>();
+    for (name, value) in headers.iter() {
+        let header = format!("{name}: {value}");
+        command.push_str(&format!(" \\\n  -H {}", quote(&header)));
+    }
+
+    // curl removes its own headers when they are given without a value
+    let curl_headers = [
+        (AutomaticHeader::UserAgent, "User-Agent"),
+        (AutomaticHeader::Accept, "Accept"),
+    ];
+    for (automatic, name) in curl_headers {
+        let is_set = headers
+            .iter()
+            .any(|(header, _)| header.eq_ignore_ascii_case(name));
+        if !is_set && request.omits(automatic) {
+            command.push_str(&format!(" \\\n  -H '{name}:'"));
+        }
+    }
+
+    if let Some(body) = request.body.as_ref().filter(|body| !body.is_empty()) {
+        let has_content_type = headers
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case("content-type"));
+        if request.omits(AutomaticHeader::ContentLength) {
+            command.push_str(" \\\n  -H 'Transfer-Encoding: chunked'");
+        }
+        let binary = request.body_type.eq(&Some(BodyType::Binary));
+        let default_content_type = match binary {
+            true => "application/octet-stream",
+            false => "application/json",
+        };
+        match (
+            has_content_type,
+            request.omits(AutomaticHeader::ContentType),
+        ) {
+            (true, _) => {}
+            (false, true) => command.push_str(" \\\n  -H 'Content-Type:'"),
+            (false, false) => {
+                command.push_str(&format!(" \\\n  -H 'Content-Type: {default_content_type}'"))
+            }
+        }
+        // shells can't hold every byte on an argument, so binary bodies are
+        // decoded from their base64 and piped into curl
+        match binary {
+            true => {
+                command.push_str(" \\\n  --data-binary @-");
+                command = format!("printf %s {} | base64 -d | {command}", quote(body));
+            }
+            false => command.push_str(&format!(" \\\n  --data-raw {}", quote(body))),
+        }
+    }
+
+    command
+}
+
+/// wraps the text in single quotes for POSIX shells, single quotes inside
+/// of it are closed, escaped and opened again
+fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::types::{HeaderMap, RequestMethod};
+
+    #[test]
+    fn test_to_curl() {
+        let request = Request {
+            id: "id".into(),
+            method: RequestMethod::Post,
+            name: "create user".into(),
+            uri: "http://localhost/users".into(),
+            headers: Some(vec![
+                HeaderMap {
+                    pair: ("Authorization".into(), "Bearer abc".into()),
+                    enabled: true,
+                },
+                HeaderMap {
+                    pair: ("X-Debug".into(), "1".into()),
+                    enabled: false,
+                },
+            ]),
+            body: Some("{\n  // the name\n  \"name\": \"o'neil\",\n}".into()),
+            ..Default::default()
+        };
+        let config = hac_config::load_config();
+
+        assert_eq!(
+            to_curl(&request, &config),
+            [
+                "curl -X POST 'http://localhost/users' \\",
+                "  -H 'Authorization: Bearer abc' \\",
+                "  -H 'Content-Type: application/json' \\",
+                "  --data-raw '{",
+                "  ",
+                "  \"name\": \"o'\\''neil\"",
+                "}'",
+            ]
+            .join("\n")
+        );
+
+        let request = Request {
+            omit_headers: vec![
+                AutomaticHeader::UserAgent,
+                AutomaticHeader::Accept,
+                AutomaticHeader::ContentType,
+            ],
+            body: Some("{}".into()),
+            ..request
+        };
+        assert_eq!(
+            to_curl(&request, &config),
+            [
+                "curl -X POST 'http://localhost/users' \\",
+                "  -H 'Authorization: Bearer abc' \\",
+                "  -H 'User-Agent:' \\",
+                "  -H 'Accept:' \\",
+                "  -H 'Content-Type:' \\",
+                "  --data-raw '{}'",
+            ]
+            .join("\n")
+        );
+
+        let request = Request {
+            omit_headers: vec![],
+            body: Some("/wE=".into()),
+            body_type: Some(BodyType::Binary),
+            ..request
+        };
+        assert_eq!(
+            to_curl(&request, &config),
+            [
+                "printf %s '/wE=' | base64 -d | curl -X POST 'http://localhost/users' \\",
+                "  -H 'Authorization: Bearer abc' \\",
+                "  -H 'Content-Type: application/octet-stream' \\",
+                "  --data-binary @-",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -0,0 +1,406 @@
+use crate::collection::types::{HeaderMap, Request};
+use crate::net::connection::{self, Binding};
+use crate::net::pinning::{self, TlsSettings};
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use base64::Engine;
+use hac_config::IpVersion;
+
+/// a `machine` entry of a netrc file, the `default` entry has no machine
+/// and matches any host
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetrcEntry {
+    pub machine: Option<String>,
+    pub login: String,
+    pub password: String,
+}
+
+/// the options of a `.curlrc` that make sense for hac, everything else on
+/// the file is ignored
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CurlSettings {
+    pub proxy: Option<String>,
+    pub noproxy: Option<String>,
+    /// `user:password` sent as basic auth when the host has no netrc entry
+    pub user: Option<(String, String)>,
+    pub cert: Option<PathBuf>,
+    pub key: Option<PathBuf>,
+    pub cacert: Option<PathBuf>,
+    pub insecure: bool,
+    /// whether the curlrc asks for the netrc file to be used
+    pub netrc: bool,
+    /// set by `--ipv4` and `--ipv6`
+    pub ip_version: IpVersion,
+    pub interface: Option<Binding>,
+}
+
+/// what was imported from curl's configuration files, applied to every
+/// request that is sent
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CurlDefaults {
+    pub credentials: Vec<NetrcEntry>,
+    pub settings: CurlSettings,
+}
+
+/// parses a netrc file. `account` values are ignored and `macdef` macros
+/// are skipped until the blank line that ends them
+pub fn parse_netrc(contents: &str) -> Vec<NetrcEntry> {
+    let mut entries = vec![];
+    let mut current: Option<NetrcEntry> = None;
+    let mut in_macro = false;
+
+    for line in contents.lines() {
+        if in_macro {
+            in_macro = !line.trim().is_empty();
+            continue;
+        }
+
+        let mut tokens = line
+            .split('#')
+            .next()
+            .unwrap_or_default()
+            .split_whitespace();
+        while let Some(token) = tokens.next() {
+            match token {
+                "machine" | "default" => {
+                    entries.extend(current.take());
+                    current = Some(NetrcEntry {
+                        machine: match token {
+                            "machine" => tokens.next().map(String::from),
+                            _ => None,
+                        },
+                        login: String::default(),
+                        password: String::default(),
+                    });
+                }
+                "login" => {
+                    let login = tokens.next().unwrap_or_default();
+                    current
+                        .iter_mut()
+                        .for_each(|entry| entry.login = login.into());
+                }
+                "password" => {
+                    let password = tokens.next().unwrap_or_default();
+                    current
+                        .iter_mut()
+                        .for_each(|entry| entry.password = password.into());
+                }
+                "account" => _ = tokens.next(),
+                "macdef" => {
+                    in_macro = true;
+                    break;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    entries.extend(current);
+    entries
+}
+
+/// parses a curlrc file. Options can be written with or without their
+/// leading dashes, separated from their value by whitespace, `=` or `:`,
+/// and values can be wrapped in double quotes
+pub fn parse_curlrc(contents: &str) -> CurlSettings {
+    let mut settings = CurlSettings::default();
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (option, value) = match line.find(|c: char| c.is_whitespace() || c == '=' || c == ':') {
+            Some(idx) => (
+                &line[..idx],
+                line[idx + 1..]
+                    .trim_start_matches(|c: char| c.is_whitespace() || c == '=' || c == ':'),
+            ),
+            None => (line, ""),
+        };
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .map(|value| value.replace("\\\"", "\"").replace("\\\\", "\\"))
+            .unwrap_or_else(|| value.to_string());
+
+        match option.trim_start_matches('-') {
+            "x" | "proxy" => settings.proxy = Some(value),
+            "noproxy" => settings.noproxy = Some(value),
+            "u" | "user" => {
+                let (user, password) = value.split_once(':').unwrap_or((value.as_str(), ""));
+                settings.user = Some((user.into(), password.into()));
+            }
+            "E" | "cert" => settings.cert = Some(value.into()),
+            "key" => settings.key = Some(value.into()),
+            "cacert" => settings.cacert = Some(value.into()),
+            "k" | "insecure" => settings.insecure = true,
+            "n" | "netrc" => settings.netrc = true,
+            "4" | "ipv4" => settings.ip_version = IpVersion::V4,
+            "6" | "ipv6" => settings.ip_version = IpVersion::V6,
+            "interface" => settings.interface = Some(Binding::parse(&value)),
+            _ => {}
+        }
+    }
+
+    settings
+}
+
+/// the entry for the host, falling back to the `default` entry
+pub fn find_credentials<'a>(entries: &'a [NetrcEntry], host: &str) -> Option<&'a NetrcEntry> {
+    entries
+        .iter()
+        .find(|entry| {
+            entry
+                .machine
+                .as_deref()
+                .is_some_and(|m| m.eq_ignore_ascii_case(host))
+        })
+        .or_else(|| entries.iter().find(|entry| entry.machine.is_none()))
+}
+
+impl CurlDefaults {
+    /// reads the files enabled on the configuration, missing files are the
+    /// same as empty ones
+    pub fn load(config: &hac_config::CurlConfig) -> CurlDefaults {
+        let home = std::env::var_os("HOME")
+            .or_else(|| std::env::var_os("USERPROFILE"))
+            .map(PathBuf::from);
+
+        let settings = config
+            .curlrc
+            .then(|| {
+                std::env::var_os("CURL_HOME")
+                    .map(PathBuf::from)
+                    .or(home.clone())
+            })
+            .flatten()
+            .and_then(|dir| std::fs::read_to_string(dir.join(".curlrc")).ok())
+            .map(|contents| parse_curlrc(&contents))
+            .unwrap_or_default();
+
+        let credentials = (config.netrc || settings.netrc)
+            .then(|| {
+                std::env::var_os("NETRC")
+                    .map(PathBuf::from)
+                    .or_else(|| home.map(|home| home.join(".netrc")))
+            })
+            .flatten()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|contents| parse_netrc(&contents))
+            .unwrap_or_default();
+
+        CurlDefaults {
+            credentials,
+            settings,
+        }
+    }
+
+    /// adds basic auth with the credentials for the host of the request,
+    /// requests that already have an `Authorization` header, or use an auth
+    /// method that sets it, are untouched
+    pub fn authorize(&self, request: &mut Request) {
+        let has_authorization =
+            request.headers.iter().flatten().any(|header| {
+                header.enabled && header.pair.0.eq_ignore_ascii_case("authorization")
+            });
+        let has_auth_method = request
+            .auth_method
+            .as_ref()
+            .is_some_and(|method| method.sets_authorization());
+        if has_authorization || has_auth_method {
+            return;
+        }
+
+        let host = reqwest::Url::parse(&request.uri)
+            .ok()
+            .and_then(|url| url.host_str().map(String::from));
+        let credentials = host
+            .and_then(|host| find_credentials(&self.credentials, &host))
+            .map(|entry| (entry.login.as_str(), entry.password.as_str()))
+            .or_else(|| {
+                self.settings
+                    .user
+                    .as_ref()
+                    .map(|(user, password)| (user.as_str(), password.as_str()))
+            });
+
+        if let Some((login, password)) = credentials {
+            let token =
+                base64::engine::general_purpose::STANDARD.encode(format!("{login}:{password}"));
+            request
+                .headers
+                .get_or_insert_with(Vec::new)
+                .push(HeaderMap {
+                    pair: ("Authorization".into(), format!("Basic {token}")),
+                    enabled: true,
+                });
+        }
+    }
+
+    /// a client that goes through the proxy and presents the certificates
+    /// of the curlrc, connecting as the config says or else as the curlrc
+    /// does. When hosts are pinned, their pins are checked on the handshake
+    /// of every connection the client makes
+    pub fn client(
+        &self,
+        connection: &hac_config::ConnectionConfig,
+        pins: &HashMap<String, Vec<String>>,
+    ) -> anyhow::Result<reqwest::Client> {
+        let settings = &self.settings;
+        let ip_version = match connection.ip_version {
+            IpVersion::Any => settings.ip_version,
+            version => version,
+        };
+        let binding = match (connection.local_address, connection.interface.as_ref()) {
+            (Some(address), _) => Some(Binding::Address(address)),
+            (None, Some(interface)) => Some(Binding::Interface(interface.clone())),
+            (None, None) => settings.interface.clone(),
+        };
+        let mut builder = connection::configure(reqwest::Client::builder(), ip_version, binding)?;
+
+        if let Some(proxy) = settings.proxy.as_ref() {
+            let proxy = reqwest::Proxy::all(proxy)?.no_proxy(
+                settings
+                    .noproxy
+                    .as_deref()
+                    .and_then(reqwest::NoProxy::from_string),
+            );
+            builder = builder.proxy(proxy);
+        }
+
+        let identity = match settings.cert.as_ref() {
+            Some(cert) => {
+                // curl accepts the key on the same file as the certificate
+                let mut pem = std::fs::read(cert)?;
+                if let Some(key) = settings.key.as_ref() {
+                    pem.extend(std::fs::read(key)?);
+                }
+                Some(pem)
+            }
+            None => None,
+        };
+        let roots = settings.cacert.as_ref().map(std::fs::read).transpose()?;
+
+        // reqwest has no way to check pins, so the TLS of clients of pinned
+        // hosts is set up by hac
+        if !pins.is_empty() {
+            let tls = pinning::tls_config(
+                pins,
+                TlsSettings {
+                    roots,
+                    identity,
+                    insecure: settings.insecure,
+                },
+            )?;
+            return Ok(builder.use_preconfigured_tls(tls).build()?);
+        }
+
+        if let Some(pem) = identity.as_ref() {
+            builder = builder.identity(reqwest::Identity::from_pem(pem)?);
+        }
+        if let Some(pem) = roots.as_ref() {
+            for cert in reqwest::Certificate::from_pem_bundle(pem)? {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+
+        Ok(builder
+            .danger_accept_invalid_certs(settings.insecure)
+            .build()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_netrc() {
+        let contents = r#"
+machine api.example.com login john password s3cret
+machine other.example.com
+    login jane
+    account ignored
+    password hunter2
+macdef init
+cd /pub
+machine inside.macro login nope password nope
+
+default login anonymous password guest # fallback
+"#;
+        let entries = parse_netrc(contents);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(
+            find_credentials(&entries, "other.example.com")
+                .unwrap()
+                .password,
+            "hunter2"
+        );
+        assert_eq!(
+            find_credentials(&entries, "unknown.host").unwrap().login,
+            "anonymous"
+        );
+        assert!(find_credentials(&entries, "inside.macro")
+            .unwrap()
+            .machine
+            .is_none());
+    }
+
+    #[test]
+    fn test_parse_curlrc() {
+        let contents = r#"
+# proxy everything but local hosts
+--proxy http://proxy.local:3128
+noproxy = "localhost,127.0.0.1"
+user: "john:pa:ss"
+-k
+cert=/home/john/client.pem
+silent
+-6
+--interface eth1
+"#;
+        let settings = parse_curlrc(contents);
+        assert_eq!(settings.proxy.as_deref(), Some("http://proxy.local:3128"));
+        assert_eq!(settings.noproxy.as_deref(), Some("localhost,127.0.0.1"));
+        assert_eq!(settings.user, Some(("john".into(), "pa:ss".into())));
+        assert_eq!(settings.cert, Some("/home/john/client.pem".into()));
+        assert!(settings.insecure);
+        assert!(!settings.netrc);
+        assert_eq!(settings.ip_version, IpVersion::V6);
+        assert_eq!(settings.interface, Some(Binding::Interface("eth1".into())));
+    }
+
+    #[test]
+    fn test_authorize() {
+        let defaults = CurlDefaults {
+            credentials: parse_netrc("machine api.example.com login john password s3cret"),
+            settings: CurlSettings::default(),
+        };
+        let request = |uri: &str| Request {
+            id: "id".into(),
+            name: "me".into(),
+            uri: uri.into(),
+            ..Default::default()
+        };
+
+        let mut me = request("https://api.example.com/me");
+        defaults.authorize(&mut me);
+        assert_eq!(
+            me.headers.as_ref().unwrap()[0].pair.1,
+            "Basic am9objpzM2NyZXQ="
+        );
+
+        // an existing authorization header takes precedence
+        defaults.authorize(&mut me);
+        assert_eq!(me.headers.unwrap().len(), 1);
+
+        let mut other = request("https://other.example.com");
+        defaults.authorize(&mut other);
+        assert!(other.headers.is_none());
+    }
+}
@@ -0,0 +1,367 @@
+use crate::collection::types::Request;
+use crate::net::auth;
+use crate::net::curl_config::CurlDefaults;
+use crate::net::request_client::RequestClient;
+use crate::net::request_manager::prepare_request;
+use crate::net::request_strategies::http_strategy::builder;
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// how often progress is reported while downloading
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// what is reported while a body is downloaded
+#[derive(Debug, Clone, PartialEq)]
+pub enum DownloadEvent {
+    /// the download continues from where an interrupted one stopped
+    Resumed(u64),
+    Progress(DownloadProgress),
+    /// the body was written to the file, with its size
+    Finished(PathBuf, u64),
+    Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DownloadProgress {
+    /// bytes on disk so far, including the ones of an earlier attempt
+    pub downloaded: u64,
+    /// size of the whole body, when the server tells it
+    pub total: Option<u64>,
+    /// bytes per second received by this attempt
+    pub rate: u64,
+    pub eta: Option<Duration>,
+}
+
+impl DownloadProgress {
+    /// the rate only counts what this attempt received, as the bytes of an
+    /// earlier attempt would make it look faster than it is
+    pub fn new(downloaded: u64, total: Option<u64>, resumed_from: u64, elapsed: Duration) -> Self {
+        let received = downloaded.saturating_sub(resumed_from);
+        let rate = match elapsed.as_millis() {
+            0 => 0,
+            millis => (received as u128 * 1000 / millis) as u64,
+        };
+        let eta = total
+            .filter(|_| rate.gt(&0))
+            .map(|total| Duration::from_secs(total.saturating_sub(downloaded).div_ceil(rate)));
+        DownloadProgress {
+            downloaded,
+            total,
+            rate,
+            eta,
+        }
+    }
+}
+
+impl std::fmt::Display for DownloadProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.total {
+            Some(total) => write!(f, "{}/{}", human_bytes(self.downloaded), human_bytes(total))?,
+            None => write!(f, "{}", human_bytes(self.downloaded))?,
+        }
+        write!(f, " at {}/s", human_bytes(self.rate))?;
+        if let Some(eta) = self.eta {
+            write!(f, ", {}s left", eta.as_secs())?;
+        }
+        Ok(())
+    }
+}
+
+pub fn human_bytes(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1 << 30 => format!("{:.1} GiB", b as f64 / (1u64 << 30) as f64),
+        b if b >= 1 << 20 => format!("{:.1} MiB", b as f64 / (1u64 << 20) as f64),
+        b if b >= 1 << 10 => format!("{:.1} KiB", b as f64 / (1u64 << 10) as f64),
+        b => format!("{b} B"),
+    }
+}
+
+/// kept next to an interrupted download, so the next attempt only asks for
+/// the rest of the body when it didn't change on the server
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct Validator {
+    uri: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified: Option<String>,
+}
+
+impl Validator {
+    /// the value for `If-Range`, weak etags can't be used to resume
+    fn if_range(&self) -> Option<&str> {
+        self.etag
+            .as_deref()
+            .filter(|etag| !etag.starts_with("W/"))
+            .or(self.last_modified.as_deref())
+    }
+}
+
+fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    path.with_file_name(name)
+}
+
+fn validator_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part.json");
+    path.with_file_name(name)
+}
+
+/// sends the request and writes its body to `path`, reporting progress
+/// through `events_tx`. The body is written to a `.part` file first, which
+/// is resumed with a `Range` request when a previous attempt was
+/// interrupted and the server says the body didn't change since
+pub fn run_download(
+    request: Request,
+    path: PathBuf,
+    config: &hac_config::Config,
+    events_tx: UnboundedSender<DownloadEvent>,
+) -> tokio::task::JoinHandle<()> {
+    let curl = CurlDefaults::load(&config.curl);
+    let mut request = prepare_request(request, config);
+    curl.authorize(&mut request);
+    let client = curl.client(&config.connection, &config.pins);
+    let offline = config.offline;
+
+    tokio::spawn(async move {
+        let result = match client {
+            _ if offline => Err(anyhow::anyhow!("nothing is downloaded while offline")),
+            Ok(client) => download(request, &path, client, &events_tx).await,
+            Err(e) => Err(anyhow::anyhow!(
+                "failed to apply the settings of .curlrc: {e}"
+            )),
+        };
+        let event = match result {
+            Ok(size) => DownloadEvent::Finished(path, size),
+            Err(e) => DownloadEvent::Failed(e.to_string()),
+        };
+        _ = events_tx.send(event);
+    })
+}
+
+async fn download(
+    mut request: Request,
+    path: &Path,
+    client: reqwest::Client,
+    events_tx: &UnboundedSender<DownloadEvent>,
+) -> anyhow::Result<u64> {
+    auth::sign(&mut request)?;
+    let partial = partial_path(path);
+    let validator_file = validator_path(path);
+
+    let existing = tokio::fs::metadata(&partial)
+        .await
+        .map(|metadata| metadata.len())
+        .unwrap_or_default();
+    let validator = tokio::fs::read_to_string(&validator_file)
+        .await
+        .ok()
+        .and_then(|contents| serde_json::from_str::<Validator>(&contents).ok())
+        .filter(|validator| validator.uri.eq(&request.uri));
+
+    let client = RequestClient::with_client(client);
+    let mut builder = builder(&client, &request);
+    let resume = validator
+        .as_ref()
+        .and_then(Validator::if_range)
+        .filter(|_| existing.gt(&0));
+    if let Some(if_range) = resume {
+        builder = builder
+            .header("Range", format!("bytes={existing}-"))
+            .header("If-Range", if_range);
+    }
+
+    let mut response = builder.send().await?;
+    let status = response.status();
+    let (mut file, resumed_from) = match status {
+        reqwest::StatusCode::PARTIAL_CONTENT if resume.is_some() => {
+            let file = tokio::fs::OpenOptions::new()
+                .append(true)
+                .open(&partial)
+                .await?;
+            _ = events_tx.send(DownloadEvent::Resumed(existing));
+            (file, existing)
+        }
+        // the partial file already holds the whole body
+        reqwest::StatusCode::RANGE_NOT_SATISFIABLE if resume.is_some() => {
+            return finish(&partial, &validator_file, path).await;
+        }
+        status if status.is_success() => {
+            // the body changed or the server can't resume, so it starts over
+            let validator = Validator {
+                uri: request.uri.clone(),
+                etag: header(&response, "ETag"),
+                last_modified: header(&response, "Last-Modified"),
+            };
+            tokio::fs::write(&validator_file, serde_json::to_string(&validator)?).await?;
+            (tokio::fs::File::create(&partial).await?, 0)
+        }
+        status => anyhow::bail!("the server responded with {status}"),
+    };
+
+    let total = response
+        .content_length()
+        .map(|length| length + resumed_from);
+    let started = Instant::now();
+    let mut reported = Instant::now();
+    let mut downloaded = resumed_from;
+    let received = loop {
+        let chunk = match response.chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => break Ok(()),
+            Err(e) => break Err(e),
+        };
+        file.write_all(&chunk).await?;
+        downloaded += chunk.len() as u64;
+        if reported.elapsed().ge(&PROGRESS_INTERVAL) {
+            reported = Instant::now();
+            let progress =
+                DownloadProgress::new(downloaded, total, resumed_from, started.elapsed());
+            _ = events_tx.send(DownloadEvent::Progress(progress));
+        }
+    };
+    // what arrived before an interruption is kept to be resumed later
+    file.flush().await?;
+    received?;
+    let progress = DownloadProgress::new(downloaded, total, resumed_from, started.elapsed());
+    _ = events_tx.send(DownloadEvent::Progress(progress));
+
+    finish(&partial, &validator_file, path).await
+}
+
+/// moves the finished body to where it was asked to be saved
+async fn finish(partial: &Path, validator_file: &Path, path: &Path) -> anyhow::Result<u64> {
+    tokio::fs::rename(partial, path).await?;
+    _ = tokio::fs::remove_file(validator_file).await;
+    Ok(tokio::fs::metadata(path).await?.len())
+}
+
+fn header(response: &reqwest::Response, name: &str) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::types::RequestMethod;
+
+    use tokio::io::{AsyncBufReadExt, BufReader};
+    use tokio::net::TcpListener;
+
+    const BODY: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+    /// answers the first request with half of the body before closing the
+    /// connection, and the ones after with the rest when asked for a range
+    async fn serve() -> (
+        std::net::SocketAddr,
+        tokio::sync::mpsc::UnboundedReceiver<String>,
+    ) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let (heads_tx, heads_rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            for attempt in 0.. {
+                let (socket, _) = listener.accept().await.unwrap();
+                let mut socket = BufReader::new(socket);
+                let mut head = String::new();
+                while !head.ends_with("\r\n\r\n") {
+                    socket.read_line(&mut head).await.unwrap();
+                }
+                let range = head
+                    .lines()
+                    .find_map(|line| {
+                        line.to_ascii_lowercase()
+                            .strip_prefix("range: bytes=")
+                            .map(String::from)
+                    })
+                    .and_then(|range| range.trim_end_matches('-').parse::<usize>().ok());
+                heads_tx.send(head).unwrap();
+
+                let response = match (attempt, range) {
+                    (0, _) => [
+                        format!(
+                            "HTTP/1.1 200 OK\r\netag: \"v1\"\r\ncontent-length: {}\r\n\r\n",
+                            BODY.len()
+                        )
+                        .into_bytes(),
+                        BODY[..10].to_vec(),
+                    ]
+                    .concat(),
+                    (_, Some(start)) => [
+                        format!(
+                            "HTTP/1.1 206 Partial Content\r\netag: \"v1\"\r\ncontent-length: {}\r\n\r\n",
+                            BODY.len() - start
+                        )
+                        .into_bytes(),
+                        BODY[start..].to_vec(),
+                    ]
+                    .concat(),
+                    (_, None) => unreachable!("the download should resume"),
+                };
+                socket.get_mut().write_all(&response).await.unwrap();
+            }
+        });
+        (address, heads_rx)
+    }
+
+    #[tokio::test]
+    async fn test_resume_download() {
+        let (address, mut heads_rx) = serve().await;
+        let dir = std::env::temp_dir().join(format!("hac-download-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("body.bin");
+        let request: Request = serde_json::from_value(serde_json::json!({
+            "id": "download",
+            "method": RequestMethod::Get,
+            "name": "download",
+            "uri": format!("http://{address}/body.bin"),
+        }))
+        .unwrap();
+
+        let (events_tx, _events_rx) = tokio::sync::mpsc::unbounded_channel();
+        let client = reqwest::Client::new();
+        let interrupted = download(request.clone(), &path, client.clone(), &events_tx).await;
+        assert!(interrupted.is_err());
+        assert_eq!(std::fs::read(partial_path(&path)).unwrap(), &BODY[..10]);
+        assert!(!heads_rx.recv().await.unwrap().contains("range"));
+
+        let size = download(request, &path, client, &events_tx).await.unwrap();
+        assert_eq!(size, BODY.len() as u64);
+        assert_eq!(std::fs::read(&path).unwrap(), BODY);
+        assert!(!partial_path(&path).exists());
+        assert!(!validator_path(&path).exists());
+        let head = heads_rx.recv().await.unwrap().to_ascii_lowercase();
+        assert!(head.contains("range: bytes=10-"));
+        assert!(head.contains("if-range: \"v1\""));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_download_progress() {
+        let progress =
+            DownloadProgress::new(3 << 20, Some(5 << 20), 1 << 20, Duration::from_secs(2));
+        assert_eq!(progress.rate, 1 << 20);
+        assert_eq!(progress.eta, Some(Duration::from_secs(2)));
+        assert_eq!(
+            progress.to_string(),
+            "3.0 MiB/5.0 MiB at 1.0 MiB/s, 2s left"
+        );
+
+        let progress = DownloadProgress::new(512, None, 0, Duration::ZERO);
+        assert_eq!(progress.eta, None);
+        assert_eq!(progress.to_string(), "512 B at 0 B/s");
+    }
+}
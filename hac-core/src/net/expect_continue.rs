@@ -0,0 +1,413 @@
+use crate::collection::types::{HeaderMap as RequestHeaders, Request};
+use crate::net::pinning::{self, TlsSettings};
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::StatusCode;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+
+/// how long the server has to answer `100 Continue` before the body is sent
+/// anyway, the same curl waits
+pub const CONTINUE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// a `1xx` response received before the final one, like `100 Continue`
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterimResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    /// how long after sending the request it arrived
+    pub elapsed: Duration,
+}
+
+/// what happened while exchanging a request that expected to continue
+#[derive(Debug, Default)]
+pub struct Exchange {
+    pub interim: Vec<InterimResponse>,
+    /// whether the body was sent, servers can reject a request by its
+    /// headers alone
+    pub body_sent: bool,
+}
+
+/// whether the request asks to wait for the server before sending its body
+pub fn expects_continue(request: &Request) -> bool {
+    request.headers.iter().flatten().any(|header| {
+        header.enabled
+            && header.pair.0.eq_ignore_ascii_case("expect")
+            && header.pair.1.trim().eq_ignore_ascii_case("100-continue")
+    })
+}
+
+/// asks large uploads to wait for the server to accept them before sending
+/// the body, unless the request already says what to expect
+pub fn expect_large_upload(request: &mut Request, threshold: Option<u64>) {
+    let Some(threshold) = threshold else {
+        return;
+    };
+    let body_size = request.body.as_ref().map(String::len).unwrap_or_default() as u64;
+    let has_expect = request
+        .headers
+        .iter()
+        .flatten()
+        .any(|header| header.enabled && header.pair.0.eq_ignore_ascii_case("expect"));
+    if body_size.ge(&threshold) && body_size.gt(&0) && !has_expect {
+        request
+            .headers
+            .get_or_insert_with(Vec::new)
+            .push(RequestHeaders {
+                pair: ("Expect".into(), "100-continue".into()),
+                enabled: true,
+            });
+    }
+}
+
+trait Connection: AsyncRead + AsyncWrite + Unpin + Send {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Connection for T {}
+
+/// sends the request over its own HTTP/1.1 connection, holding the body back
+/// until the server answers `100 Continue`, or for `timeout` when it
+/// doesn't. Every interim response is kept, as the client reqwest is built
+/// on hides them. Pinned hosts have their pins checked on the handshake
+pub async fn send(
+    request: reqwest::Request,
+    timeout: Duration,
+    pins: &HashMap<String, Vec<String>>,
+) -> anyhow::Result<(reqwest::Response, Exchange)> {
+    let now = Instant::now();
+    let url = request.url().clone();
+    let host = url
+        .host_str()
+        .ok_or_else(|| anyhow::anyhow!("the url has no host"))?
+        .to_string();
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| anyhow::anyhow!("the url has no port"))?;
+    let body = match request.body() {
+        Some(body) => body
+            .as_bytes()
+            .ok_or_else(|| anyhow::anyhow!("streamed bodies can't wait for 100 Continue"))?
+            .to_vec(),
+        None => vec![],
+    };
+
+    let tcp = TcpStream::connect((host.as_str(), port)).await?;
+    let connection: Box<dyn Connection> = match url.scheme() {
+        "https" => {
+            let config = match pinning::pins_for(pins, url.as_str()) {
+                Some(_) => pinning::tls_config(pins, TlsSettings::default())?,
+                None => {
+                    let mut roots = RootCertStore::empty();
+                    roots.add_parsable_certificates(rustls_native_certs::load_native_certs().certs);
+                    ClientConfig::builder()
+                        .with_root_certificates(roots)
+                        .with_no_client_auth()
+                }
+            };
+            let server_name = ServerName::try_from(host.clone())?;
+            let tls = TlsConnector::from(Arc::new(config))
+                .connect(server_name, tcp)
+                .await?;
+            Box::new(tls)
+        }
+        "http" => Box::new(tcp),
+        scheme => anyhow::bail!("{scheme} urls can't wait for 100 Continue"),
+    };
+    let mut connection = BufReader::new(connection);
+
+    let head = request_head(&request, &host, url.port(), body.len());
+    connection.get_mut().write_all(head.as_bytes()).await?;
+    connection.get_mut().flush().await?;
+
+    let mut exchange = Exchange::default();
+    let (status, headers) = loop {
+        let head = match exchange.body_sent {
+            true => read_head(&mut connection).await?,
+            false => match tokio::time::timeout(timeout, read_head(&mut connection)).await {
+                Ok(head) => head?,
+                // servers that don't know about `Expect` never answer it
+                Err(_) => {
+                    send_body(&mut connection, &body, &mut exchange).await?;
+                    continue;
+                }
+            },
+        };
+        let (status, headers) = head;
+        if !status.is_informational() || status == StatusCode::SWITCHING_PROTOCOLS {
+            break (status, headers);
+        }
+
+        exchange.interim.push(InterimResponse {
+            status,
+            headers,
+            elapsed: now.elapsed(),
+        });
+        if status == StatusCode::CONTINUE && !exchange.body_sent {
+            send_body(&mut connection, &body, &mut exchange).await?;
+        }
+    };
+
+    let body = match request.method() == reqwest::Method::HEAD
+        || status == StatusCode::NO_CONTENT
+        || status == StatusCode::NOT_MODIFIED
+    {
+        true => vec![],
+        false => read_body(&mut connection, &headers).await?,
+    };
+
+    let mut response = http::Response::builder().status(status);
+    if let Some(response_headers) = response.headers_mut() {
+        *response_headers = headers;
+    }
+    let response = response.body(body)?;
+    Ok((reqwest::Response::from(response), exchange))
+}
+
+fn request_head(
+    request: &reqwest::Request,
+    host: &str,
+    port: Option<u16>,
+    body_len: usize,
+) -> String {
+    let url = request.url();
+    let target = match url.query() {
+        Some(query) => format!("{}?{query}", url.path()),
+        None => url.path().to_string(),
+    };
+    let host = match port {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_string(),
+    };
+
+    let mut head = format!("{} {target} HTTP/1.1\r\nHost: {host}\r\n", request.method());
+    for (name, value) in request.headers() {
+        if name == reqwest::header::HOST || name == reqwest::header::CONNECTION {
+            continue;
+        }
+        head.push_str(&format!(
+            "{name}: {}\r\n",
+            String::from_utf8_lossy(value.as_bytes())
+        ));
+    }
+    if !request
+        .headers()
+        .contains_key(reqwest::header::CONTENT_LENGTH)
+    {
+        head.push_str(&format!("Content-Length: {body_len}\r\n"));
+    }
+    head.push_str("Connection: close\r\n\r\n");
+    head
+}
+
+async fn send_body<C>(
+    connection: &mut BufReader<C>,
+    body: &[u8],
+    exchange: &mut Exchange,
+) -> anyhow::Result<()>
+where
+    C: AsyncWrite + AsyncRead + Unpin,
+{
+    connection.get_mut().write_all(body).await?;
+    connection.get_mut().flush().await?;
+    exchange.body_sent = true;
+    Ok(())
+}
+
+/// reads the status line and headers of a response
+async fn read_head<C>(connection: &mut BufReader<C>) -> anyhow::Result<(StatusCode, HeaderMap)>
+where
+    C: AsyncRead + Unpin,
+{
+    let mut head = vec![];
+    loop {
+        let read = connection.read_until(b'\n', &mut head).await?;
+        if read == 0 {
+            anyhow::bail!("the connection closed before a response");
+        }
+        if head.ends_with(b"\r\n\r\n") || head.ends_with(b"\n\n") {
+            break;
+        }
+    }
+
+    let mut headers = [httparse::EMPTY_HEADER; 128];
+    let mut response = httparse::Response::new(&mut headers);
+    response.parse(&head)?;
+    let status = StatusCode::from_u16(response.code.unwrap_or_default())?;
+    let headers = response
+        .headers
+        .iter()
+        .filter_map(|header| {
+            let name = HeaderName::from_bytes(header.name.as_bytes()).ok()?;
+            let value = HeaderValue::from_bytes(header.value).ok()?;
+            Some((name, value))
+        })
+        .collect();
+    Ok((status, headers))
+}
+
+/// reads the body the way the headers say it is sent, chunked, with a
+/// length, or until the connection closes
+async fn read_body<C>(connection: &mut BufReader<C>, headers: &HeaderMap) -> anyhow::Result<Vec<u8>>
+where
+    C: AsyncRead + Unpin,
+{
+    let chunked = headers
+        .get(reqwest::header::TRANSFER_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.to_ascii_lowercase().contains("chunked"));
+    let length = headers
+        .get(reqwest::header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<usize>().ok());
+
+    let mut body = vec![];
+    match (chunked, length) {
+        (true, _) => loop {
+            let mut line = String::new();
+            connection.read_line(&mut line).await?;
+            let size = line.split(';').next().unwrap_or_default().trim();
+            let size = usize::from_str_radix(size, 16)
+                .map_err(|_| anyhow::anyhow!("invalid chunk size {size:?}"))?;
+            if size == 0 {
+                // trailers, if any, end with an empty line
+                while connection.read_line(&mut line).await? > 2 {
+                    line.clear();
+                }
+                break;
+            }
+            let start = body.len();
+            body.resize(start + size, 0);
+            connection.read_exact(&mut body[start..]).await?;
+            connection.read_line(&mut line).await?;
+        },
+        (false, Some(length)) => {
+            body.resize(length, 0);
+            connection.read_exact(&mut body).await?;
+        }
+        (false, None) => {
+            connection.read_to_end(&mut body).await?;
+        }
+    }
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    /// a server that answers the head of the request with `interim`, then
+    /// reads the body and answers with how many bytes it got
+    async fn serve(interim: &'static str) -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut socket = BufReader::new(socket);
+            let (_, headers) = read_request_head(&mut socket).await;
+            socket
+                .get_mut()
+                .write_all(interim.as_bytes())
+                .await
+                .unwrap();
+            if interim.starts_with("HTTP/1.1 417") {
+                return;
+            }
+            let length = headers["content-length"].parse::<usize>().unwrap();
+            let mut body = vec![0; length];
+            socket.read_exact(&mut body).await.unwrap();
+            let reply = format!("{{\"received\":{length}}}");
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ntransfer-encoding: chunked\r\n\r\n{:x}\r\n{reply}\r\n0\r\n\r\n",
+                reply.len()
+            );
+            socket
+                .get_mut()
+                .write_all(response.as_bytes())
+                .await
+                .unwrap();
+        });
+        address
+    }
+
+    async fn read_request_head(
+        socket: &mut BufReader<TcpStream>,
+    ) -> (String, std::collections::HashMap<String, String>) {
+        let mut request_line = String::new();
+        socket.read_line(&mut request_line).await.unwrap();
+        let mut headers = std::collections::HashMap::new();
+        loop {
+            let mut line = String::new();
+            socket.read_line(&mut line).await.unwrap();
+            let Some((name, value)) = line.trim_end().split_once(':') else {
+                break;
+            };
+            headers.insert(name.to_ascii_lowercase(), value.trim().to_string());
+        }
+        (request_line, headers)
+    }
+
+    fn upload(address: std::net::SocketAddr) -> reqwest::Request {
+        reqwest::Client::new()
+            .post(format!("http://{address}/upload?size=large"))
+            .header("Expect", "100-continue")
+            .body("a".repeat(64))
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_send() {
+        let address = serve("HTTP/1.1 100 Continue\r\nx-proxy: edge\r\n\r\n").await;
+        let (response, exchange) = send(upload(address), CONTINUE_TIMEOUT, &HashMap::default())
+            .await
+            .unwrap();
+        assert!(exchange.body_sent);
+        assert_eq!(exchange.interim.len(), 1);
+        assert_eq!(exchange.interim[0].status, StatusCode::CONTINUE);
+        assert_eq!(exchange.interim[0].headers["x-proxy"], "edge");
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.text().await.unwrap(), "{\"received\":64}");
+
+        // the body is sent anyway when the server doesn't answer in time
+        let address = serve("").await;
+        let (response, exchange) = send(
+            upload(address),
+            Duration::from_millis(50),
+            &HashMap::default(),
+        )
+        .await
+        .unwrap();
+        assert!(exchange.body_sent);
+        assert!(exchange.interim.is_empty());
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let address = serve("HTTP/1.1 417 Expectation Failed\r\ncontent-length: 0\r\n\r\n").await;
+        let (response, exchange) = send(upload(address), CONTINUE_TIMEOUT, &HashMap::default())
+            .await
+            .unwrap();
+        assert!(!exchange.body_sent);
+        assert_eq!(response.status(), StatusCode::EXPECTATION_FAILED);
+    }
+
+    #[test]
+    fn test_expect_large_upload() {
+        let mut request: Request = serde_json::from_str(
+            r#"{"id":"1","method":"POST","name":"upload","uri":"http://localhost","body":"0123456789"}"#,
+        )
+        .unwrap();
+        expect_large_upload(&mut request, Some(100));
+        assert!(!expects_continue(&request));
+        expect_large_upload(&mut request, None);
+        assert!(!expects_continue(&request));
+        expect_large_upload(&mut request, Some(10));
+        assert!(expects_continue(&request));
+        expect_large_upload(&mut request, Some(10));
+        assert_eq!(request.headers.as_ref().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,322 @@
+use crate::collection::types::Request;
+use crate::net::request_manager::Response;
+use crate::text_object::TextObject;
+
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+use futures_util::{SinkExt, StreamExt};
+use reqwest::header::{HeaderName, HeaderValue};
+use serde_json::{json, Value};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::oneshot;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::Message;
+
+/// subprotocol of the graphql-ws protocol
+const PROTOCOL: &str = "graphql-transport-ws";
+/// how long the server has to acknowledge the connection
+const ACK_TIMEOUT: Duration = Duration::from_secs(10);
+/// id of the only operation sent on the connection
+const OPERATION_ID: &str = "1";
+
+/// a subscription running on the background, events are sent through the
+/// channel it was started with until the server completes it or it is
+/// stopped
+#[derive(Debug)]
+pub struct Subscription {
+    /// id of the request that subscribed
+    pub request_id: String,
+    pub task: tokio::task::JoinHandle<()>,
+    stop_tx: Option<oneshot::Sender<()>>,
+}
+
+impl Subscription {
+    /// completes the subscription, telling the server it is no longer
+    /// wanted, the last response notes it was stopped
+    pub fn stop(&mut self) {
+        if let Some(stop_tx) = self.stop_tx.take() {
+            _ = stop_tx.send(());
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+}
+
+/// an event received from the subscription
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub received_at: DateTime<Local>,
+    pub payload: Value,
+}
+
+/// opens a graphql-ws connection to the uri of the request and subscribes
+/// with its body, which holds the `query` and `variables` of the operation.
+/// Every event received sends a new response with all of them so far, each
+/// one on its own line prefixed by when it arrived
+pub fn subscribe(request: Request, response_tx: UnboundedSender<Response>) -> Subscription {
+    let (stop_tx, stop_rx) = oneshot::channel();
+    let request_id = request.id.clone();
+    let task = tokio::spawn(async move {
+        let now = Instant::now();
+        let mut events = vec![];
+        let response = match run(&request, stop_rx, &mut events, &response_tx, now).await {
+            Ok(None) => events_response(&events, now, None),
+            Ok(Some(note)) => {
+                let mut response = events_response(&events, now, None);
+                append_line(&mut response, &note);
+                response
+            }
+            Err(e) if events.is_empty() => Response::from_error(e.to_string()),
+            Err(e) => events_response(&events, now, Some(e.to_string())),
+        };
+        _ = response_tx.send(response);
+    });
+
+    Subscription {
+        request_id,
+        task,
+        stop_tx: Some(stop_tx),
+    }
+}
+
+/// runs the protocol until the subscription ends, returning a note on how
+/// it ended when it wasn't the server that completed it
+async fn run(
+    request: &Request,
+    mut stop_rx: oneshot::Receiver<()>,
+    events: &mut Vec<Event>,
+    response_tx: &UnboundedSender<Response>,
+    now: Instant,
+) -> anyhow::Result<Option<String>> {
+    let payload = match request.body.as_deref().map(serde_json::from_str::<Value>) {
+        Some(Ok(payload)) if payload.is_object() => payload,
+        Some(Ok(_)) | None => anyhow::bail!("the body must be an object with the `query`"),
+        Some(Err(e)) => anyhow::bail!("the body is not valid JSON: {e}"),
+    };
+
+    let mut handshake = request.uri.trim().into_client_request()?;
+    for header in request.headers.iter().flatten().filter(|h| h.enabled) {
+        let name = HeaderName::from_bytes(header.pair.0.as_bytes());
+        let value = HeaderValue::from_str(&header.pair.1);
+        if let (Ok(name), Ok(value)) = (name, value) {
+            handshake.headers_mut().insert(name, value);
+        }
+    }
+    handshake
+        .headers_mut()
+        .insert("Sec-WebSocket-Protocol", HeaderValue::from_static(PROTOCOL));
+    let (mut stream, _) = tokio_tungstenite::connect_async(handshake).await?;
+
+    stream
+        .send(Message::text(
+            json!({ "type": "connection_init" }).to_string(),
+        ))
+        .await?;
+    let deadline = tokio::time::Instant::now() + ACK_TIMEOUT;
+    loop {
+        let message = tokio::time::timeout_at(deadline, stream.next())
+            .await
+            .map_err(|_| anyhow::anyhow!("the server didn't acknowledge the connection"))?;
+        match message.map(|message| message.map(parse)) {
+            Some(Ok(Some((kind, _)))) if kind.eq("connection_ack") => break,
+            Some(Ok(_)) => continue,
+            Some(Err(e)) => return Err(e.into()),
+            None => anyhow::bail!("the connection closed before being acknowledged"),
+        }
+    }
+
+    stream
+        .send(Message::text(
+            json!({ "id": OPERATION_ID, "type": "subscribe", "payload": payload }).to_string(),
+        ))
+        .await?;
+
+    loop {
+        let message = tokio::select! {
+            _ = &mut stop_rx => {
+                let complete = json!({ "id": OPERATION_ID, "type": "complete" });
+                _ = stream.send(Message::text(complete.to_string())).await;
+                _ = stream.close(None).await;
+                return Ok(Some("stopped".into()));
+            }
+            message = stream.next() => message,
+        };
+
+        let (kind, payload) = match message {
+            Some(Ok(Message::Close(_))) | None => {
+                return Ok(Some("the server closed the connection".into()))
+            }
+            Some(Ok(message)) => match parse(message) {
+                Some(parsed) => parsed,
+                None => continue,
+            },
+            Some(Err(e)) => return Err(e.into()),
+        };
+
+        match kind.as_str() {
+            "next" => {
+                events.push(Event {
+                    received_at: Local::now(),
+                    payload: payload.unwrap_or_default(),
+                });
+                let mut response = events_response(events, now, None);
+                response.streaming = true;
+                _ = response_tx.send(response);
+            }
+            "error" => anyhow::bail!("the subscription failed: {}", payload.unwrap_or_default()),
+            "complete" => return Ok(None),
+            "ping" => {
+                let pong = json!({ "type": "pong" }).to_string();
+                stream.send(Message::text(pong)).await?;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// the type and payload of a message of the protocol
+fn parse(message: Message) -> Option<(String, Option<Value>)> {
+    let Message::Text(text) = message else {
+        return None;
+    };
+    let mut message = serde_json::from_str::<Value>(text.as_str()).ok()?;
+    let kind = message.get("type")?.as_str()?.to_string();
+    Some((kind, message.get_mut("payload").map(Value::take)))
+}
+
+/// every event on its own line, prefixed by the time it arrived
+pub fn render_events(events: &[Event]) -> String {
+    events
+        .iter()
+        .map(|event| {
+            format!(
+                "[{}] {}",
+                event.received_at.format("%H:%M:%S%.3f"),
+                event.payload
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn events_response(events: &[Event], started: Instant, cause: Option<String>) -> Response {
+    Response {
+        duration: started.elapsed(),
+        status: Some(reqwest::StatusCode::SWITCHING_PROTOCOLS),
+        is_error: cause.is_some(),
+        cause,
+        ..Response::from_text(render_events(events))
+    }
+}
+
+fn append_line(response: &mut Response, line: &str) {
+    let body = match response.body.take().filter(|body| !body.is_empty()) {
+        Some(body) => format!("{body}\n{line}"),
+        None => line.to_string(),
+    };
+    response.pretty_body = Some(TextObject::from(&body));
+    response.body = Some(body);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::types::BodyType;
+
+    use tokio::sync::mpsc::unbounded_channel;
+    use tokio_tungstenite::tungstenite::handshake::server::{
+        Request as HandshakeRequest, Response as HandshakeResponse,
+    };
+
+    #[test]
+    fn test_render_events() {
+        let at = |time: &str| {
+            chrono::NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M:%S%.3f")
+                .unwrap()
+                .and_local_timezone(Local)
+                .unwrap()
+        };
+        let events = vec![
+            Event {
+                received_at: at("2024-01-01 12:00:01.250"),
+                payload: json!({ "data": { "count": 1 } }),
+            },
+            Event {
+                received_at: at("2024-01-01 12:00:02.000"),
+                payload: json!({ "data": { "count": 2 } }),
+            },
+        ];
+
+        assert_eq!(
+            render_events(&events),
+            "[12:00:01.250] {\"data\":{\"count\":1}}\n[12:00:02.000] {\"data\":{\"count\":2}}"
+        );
+    }
+
+    // the handshake callback has to return the error response of tungstenite
+    #[allow(clippy::result_large_err)]
+    #[tokio::test]
+    async fn test_subscribe() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let accept_protocol = |request: &HandshakeRequest, mut response: HandshakeResponse| {
+                let protocol = request.headers().get("Sec-WebSocket-Protocol").cloned();
+                response
+                    .headers_mut()
+                    .insert("Sec-WebSocket-Protocol", protocol.unwrap());
+                Ok(response)
+            };
+            let mut stream = tokio_tungstenite::accept_hdr_async(socket, accept_protocol)
+                .await
+                .unwrap();
+            while let Some(Ok(Message::Text(text))) = stream.next().await {
+                let message = serde_json::from_str::<Value>(text.as_str()).unwrap();
+                let replies = match message["type"].as_str().unwrap() {
+                    "connection_init" => vec![json!({ "type": "connection_ack" })],
+                    "subscribe" => {
+                        let query = message["payload"]["query"].clone();
+                        vec![
+                            json!({ "type": "ping" }),
+                            json!({ "id": "1", "type": "next", "payload": { "data": query } }),
+                            json!({ "id": "1", "type": "next", "payload": { "data": 2 } }),
+                            json!({ "id": "1", "type": "complete" }),
+                        ]
+                    }
+                    _ => vec![],
+                };
+                for reply in replies {
+                    stream.send(Message::text(reply.to_string())).await.unwrap();
+                }
+            }
+        });
+
+        let request = Request {
+            id: "subscription".into(),
+            name: "count".into(),
+            uri: format!("ws://{address}"),
+            body: Some(r#"{"query": "subscription { count }"}"#.into()),
+            body_type: Some(BodyType::GraphQl),
+            ..Default::default()
+        };
+        assert!(request.is_subscription());
+        let (response_tx, mut response_rx) = unbounded_channel();
+        let subscription = subscribe(request, response_tx);
+
+        let mut bodies = vec![];
+        while let Some(response) = response_rx.recv().await {
+            assert!(!response.is_error, "{:?}", response.cause);
+            bodies.push(response.body.unwrap());
+        }
+        assert!(subscription.is_finished());
+        assert_eq!(bodies.len(), 3);
+        assert!(bodies[0].ends_with(r#"{"data":"subscription { count }"}"#));
+        assert_eq!(bodies[1].lines().count(), 2);
+        assert!(bodies[1].ends_with(r#"{"data":2}"#));
+        assert_eq!(bodies[1], bodies[2]);
+    }
+}
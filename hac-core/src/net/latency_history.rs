@@ -0,0 +1,136 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// how many durations are kept for each request, older ones are dropped
+const MAX_RUNS: usize = 50;
+
+/// durations of the last sends of every request, in milliseconds, keyed by
+/// request id. This is persisted on the data directory so the history
+/// survives between sessions
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LatencyHistory {
+    runs: HashMap<String, VecDeque<u64>>,
+    /// status of the last response of every request
+    #[serde(default)]
+    statuses: HashMap<String, u16>,
+}
+
+/// statistics over the durations of a request, in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatencyStats {
+    pub p50: u64,
+    pub p95: u64,
+    pub min: u64,
+    pub max: u64,
+    pub count: usize,
+}
+
+impl LatencyHistory {
+    /// loads the history from disk, a missing or corrupted file means we
+    /// start over with an empty history
+    pub fn load<P>(path: P) -> LatencyHistory
+    where
+        P: AsRef<Path>,
+    {
+        std::fs::read_to_string(path.as_ref())
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save<P>(&self, path: P) -> anyhow::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        std::fs::write(path.as_ref(), serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn record(&mut self, request_id: &str, duration: Duration) {
+        let runs = self.runs.entry(request_id.to_string()).or_default();
+        runs.push_back(duration.as_millis() as u64);
+        if runs.len().gt(&MAX_RUNS) {
+            runs.pop_front();
+        }
+    }
+
+    pub fn record_status(&mut self, request_id: &str, status: u16) {
+        self.statuses.insert(request_id.to_string(), status);
+    }
+
+    /// status of the last response the request got, if it was ever sent
+    pub fn last_status(&self, request_id: &str) -> Option<u16> {
+        self.statuses.get(request_id).copied()
+    }
+
+    /// durations of the last `n` sends of a request, oldest first
+    pub fn last_runs(&self, request_id: &str, n: usize) -> Vec<u64> {
+        self.runs
+            .get(request_id)
+            .map(|runs| {
+                runs.iter()
+                    .skip(runs.len().saturating_sub(n))
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn stats(&self, request_id: &str) -> Option<LatencyStats> {
+        let mut runs = self
+            .runs
+            .get(request_id)?
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+        if runs.is_empty() {
+            return None;
+        }
+        runs.sort_unstable();
+
+        Some(LatencyStats {
+            p50: percentile(&runs, 50),
+            p95: percentile(&runs, 95),
+            min: runs[0],
+            max: runs[runs.len() - 1],
+            count: runs.len(),
+        })
+    }
+}
+
+/// nearest rank percentile over an already sorted slice
+fn percentile(sorted: &[u64], percentile: usize) -> u64 {
+    let rank = (percentile * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_history() {
+        let mut history = LatencyHistory::default();
+        assert!(history.stats("req").is_none());
+
+        (1..=100).for_each(|ms| history.record("req", Duration::from_millis(ms)));
+
+        let stats = history.stats("req").unwrap();
+        assert_eq!(stats.count, MAX_RUNS);
+        assert_eq!((stats.min, stats.max), (51, 100));
+        assert_eq!((stats.p50, stats.p95), (75, 98));
+        assert_eq!(history.last_runs("req", 3), vec![98, 99, 100]);
+
+        history.record_status("req", 503);
+        history.record_status("req", 200);
+        assert_eq!(history.last_status("req"), Some(200));
+        assert_eq!(history.last_status("other"), None);
+
+        let serialized = serde_json::to_string(&history).unwrap();
+        let deserialized: LatencyHistory = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, history);
+    }
+}
@@ -0,0 +1,278 @@
+use crate::viewer_preferences::ViewerTab;
+
+use reqwest::header::{HeaderMap, CONTENT_TYPE};
+
+/// how the preview tab shows a body
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Preview {
+    /// pretty printed and highlighted
+    Text,
+    /// its format, dimensions and size, as the terminal can't draw it
+    Image,
+    /// only what it is and how to save it, like documents and archives
+    Download,
+}
+
+/// how responses of some content types are decoded and shown
+#[derive(Debug, Clone)]
+pub struct MimeHandler {
+    pub name: &'static str,
+    /// the content types handled, either exact like `text/csv`, every subtype
+    /// of a type like `image/*` or every type with a suffix like `*+json`
+    pub content_types: &'static [&'static str],
+    /// the tab responses open on, unless the user picked one for the request
+    pub tab: ViewerTab,
+    pub preview: Preview,
+    /// extension of the files the body is saved to, the subtype of the
+    /// content type when not set, eg: `png` for `image/png`
+    pub extension: Option<&'static str>,
+    /// whether the body is kept as received instead of decoded as text
+    pub binary: bool,
+}
+
+impl MimeHandler {
+    fn matches(&self, essence: &str) -> bool {
+        self.content_types.iter().any(|pattern| {
+            match (pattern.strip_suffix("/*"), pattern.strip_prefix('*')) {
+                (Some(kind), _) => essence
+                    .split_once('/')
+                    .is_some_and(|(other, _)| other.eq(kind)),
+                (_, Some(suffix)) => essence.ends_with(suffix),
+                _ => essence.eq(*pattern),
+            }
+        })
+    }
+
+    /// the extension of files holding a body of the content type
+    pub fn extension(&self, content_type: &str) -> String {
+        match self.extension {
+            Some(extension) => extension.to_string(),
+            None => essence(content_type)
+                .split_once('/')
+                .map(|(_, subtype)| subtype.split('+').next().unwrap_or(subtype).to_string())
+                .filter(|subtype| !subtype.is_empty())
+                .unwrap_or_else(|| "bin".into()),
+        }
+    }
+}
+
+/// bodies of unknown content types are shown as text
+const FALLBACK: MimeHandler = MimeHandler {
+    name: "text",
+    content_types: &["text/*"],
+    tab: ViewerTab::Pretty,
+    preview: Preview::Text,
+    extension: Some("txt"),
+    binary: false,
+};
+
+/// the handlers of every content type hac knows how to show. Supporting a
+/// new kind of response is a matter of registering a handler for it
+#[derive(Debug, Clone)]
+pub struct MimeRegistry {
+    handlers: Vec<MimeHandler>,
+}
+
+impl Default for MimeRegistry {
+    fn default() -> Self {
+        let handlers = vec![
+            MimeHandler {
+                name: "json",
+                content_types: &["application/json", "*+json"],
+                tab: ViewerTab::Pretty,
+                preview: Preview::Text,
+                extension: Some("json"),
+                binary: false,
+            },
+            MimeHandler {
+                name: "ndjson",
+                content_types: &[
+                    "application/x-ndjson",
+                    "application/ndjson",
+                    "application/jsonl",
+                    "application/x-jsonlines",
+                ],
+                tab: ViewerTab::Pretty,
+                preview: Preview::Text,
+                extension: Some("ndjson"),
+                binary: false,
+            },
+            MimeHandler {
+                name: "xml",
+                content_types: &["application/xml", "text/xml", "*+xml"],
+                tab: ViewerTab::Pretty,
+                preview: Preview::Text,
+                extension: Some("xml"),
+                binary: false,
+            },
+            MimeHandler {
+                name: "html",
+                content_types: &["text/html"],
+                tab: ViewerTab::Pretty,
+                preview: Preview::Text,
+                extension: Some("html"),
+                binary: false,
+            },
+            MimeHandler {
+                name: "csv",
+                content_types: &["text/csv"],
+                tab: ViewerTab::Table,
+                preview: Preview::Text,
+                extension: Some("csv"),
+                binary: false,
+            },
+            MimeHandler {
+                name: "image",
+                content_types: &["image/*"],
+                tab: ViewerTab::Pretty,
+                preview: Preview::Image,
+                extension: None,
+                binary: true,
+            },
+            MimeHandler {
+                name: "pdf",
+                content_types: &["application/pdf"],
+                tab: ViewerTab::Pretty,
+                preview: Preview::Download,
+                extension: Some("pdf"),
+                binary: true,
+            },
+            MimeHandler {
+                name: "binary",
+                content_types: &[
+                    "application/octet-stream",
+                    "application/zip",
+                    "application/gzip",
+                    "audio/*",
+                    "video/*",
+                ],
+                tab: ViewerTab::Pretty,
+                preview: Preview::Download,
+                extension: None,
+                binary: true,
+            },
+            FALLBACK,
+        ];
+        MimeRegistry { handlers }
+    }
+}
+
+impl MimeRegistry {
+    /// adds a handler, taking precedence over the ones already registered
+    /// for the same content types
+    pub fn register(&mut self, handler: MimeHandler) {
+        self.handlers.insert(0, handler);
+    }
+
+    /// the handler of the content type, parameters like the charset are
+    /// ignored
+    pub fn handler(&self, content_type: &str) -> &MimeHandler {
+        let essence = essence(content_type);
+        self.handlers
+            .iter()
+            .find(|handler| handler.matches(&essence))
+            .unwrap_or(&FALLBACK)
+    }
+
+    /// the handler of the content type on the headers, responses without
+    /// one are handled as text
+    pub fn for_headers(&self, headers: &HeaderMap) -> &MimeHandler {
+        let content_type = headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        self.handler(content_type)
+    }
+}
+
+/// the content type without its parameters, eg: `text/csv; charset=utf-8`
+/// is `text/csv`
+pub fn essence(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// the width and height of png, gif and jpeg images, read from their header
+pub fn image_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let be16 = |at: usize| Some(u16::from_be_bytes(bytes.get(at..at + 2)?.try_into().ok()?));
+    let le16 = |at: usize| Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?));
+    let be32 = |at: usize| Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?));
+
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some((be32(16)?, be32(20)?));
+    }
+    if bytes.starts_with(b"GIF8") {
+        return Some((le16(6)?.into(), le16(8)?.into()));
+    }
+    if !bytes.starts_with(&[0xff, 0xd8]) {
+        return None;
+    }
+    // jpegs are a sequence of segments, the size is on the start of frame
+    let mut at = 2;
+    while bytes.get(at).is_some_and(|byte| *byte == 0xff) {
+        let marker = *bytes.get(at + 1)?;
+        let is_start_of_frame =
+            (0xc0..=0xcf).contains(&marker) && ![0xc4, 0xc8, 0xcc].contains(&marker);
+        if is_start_of_frame {
+            return Some((be16(at + 7)?.into(), be16(at + 5)?.into()));
+        }
+        at += 2 + usize::from(be16(at + 2)?);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handlers_by_content_type() {
+        let mut registry = MimeRegistry::default();
+        assert_eq!(
+            registry.handler("application/json; charset=utf-8").name,
+            "json"
+        );
+        assert_eq!(registry.handler("application/problem+json").name, "json");
+        assert_eq!(registry.handler("application/x-ndjson").name, "ndjson");
+        assert_eq!(registry.handler("Text/CSV").tab, ViewerTab::Table);
+        assert_eq!(registry.handler("image/png").preview, Preview::Image);
+        assert_eq!(registry.handler("image/svg+xml").name, "xml");
+        assert_eq!(
+            registry
+                .handler("application/pdf")
+                .extension("application/pdf"),
+            "pdf"
+        );
+        assert_eq!(
+            registry.handler("image/jpeg").extension("image/jpeg"),
+            "jpeg"
+        );
+        assert_eq!(registry.handler("").name, "text");
+        assert_eq!(registry.handler("application/x-unknown").name, "text");
+
+        registry.register(MimeHandler {
+            name: "geojson",
+            content_types: &["application/geo+json"],
+            tab: ViewerTab::Tree,
+            preview: Preview::Text,
+            extension: Some("geojson"),
+            binary: false,
+        });
+        assert_eq!(registry.handler("application/geo+json").name, "geojson");
+        assert_eq!(registry.handler("application/json").name, "json");
+
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend([0, 0, 2, 128, 0, 0, 1, 224]);
+        assert_eq!(image_dimensions(&png), Some((640, 480)));
+        assert_eq!(image_dimensions(b"GIF89a\x10\0\x20\0"), Some((16, 32)));
+        let jpeg = [
+            0xff, 0xd8, 0xff, 0xe0, 0, 4, 0, 0, 0xff, 0xc0, 0, 11, 8, 0, 100, 0, 200,
+        ];
+        assert_eq!(image_dimensions(&jpeg), Some((200, 100)));
+        assert_eq!(image_dimensions(b"not an image"), None);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::collection::types::NetworkConditions;
+
+use std::time::Duration;
+
+use fake::Fake;
+use futures_util::{Stream, StreamExt};
+use tokio::time::Instant;
+
+/// how many times per second a throttled body is let through, so it arrives
+/// steadily instead of in one burst every second
+const TICKS_PER_SECOND: u64 = 10;
+
+/// how long to wait before sending the request, the latency shifted by a
+/// random amount of up to the jitter in either direction
+pub fn delay(conditions: &NetworkConditions) -> Duration {
+    let latency = conditions.latency_ms.unwrap_or_default();
+    let jitter = conditions.jitter_ms.unwrap_or_default();
+    let millis = match jitter {
+        0 => latency,
+        jitter => (latency.saturating_sub(jitter)..=latency.saturating_add(jitter)).fake(),
+    };
+    Duration::from_millis(millis)
+}
+
+/// how long it takes to transfer `bytes` at `rate` bytes per second
+pub fn transfer_time(bytes: u64, rate: u64) -> Duration {
+    match rate {
+        0 => Duration::ZERO,
+        rate => Duration::from_secs_f64(bytes as f64 / rate as f64),
+    }
+}
+
+/// lets the bytes of the stream through no faster than `rate` bytes per
+/// second, splitting its chunks into smaller pieces so they arrive steadily
+pub fn throttle<S, T, E>(stream: S, rate: u64) -> impl Stream<Item = Result<Vec<u8>, E>>
+where
+    S: Stream<Item = Result<T, E>>,
+    T: AsRef<[u8]>,
+{
+    let piece = (rate / TICKS_PER_SECOND).max(1) as usize;
+    let mut started = None;
+    let mut sent = 0;
+    stream
+        .flat_map(move |chunk| {
+            let pieces = match chunk {
+                Ok(chunk) => chunk
+                    .as_ref()
+                    .chunks(piece)
+                    .map(|piece| Ok(piece.to_vec()))
+                    .collect::<Vec<_>>(),
+                Err(e) => vec![Err(e)],
+            };
+            futures_util::stream::iter(pieces)
+        })
+        .then(move |piece| {
+            let started = *started.get_or_insert_with(Instant::now);
+            if let Ok(bytes) = piece.as_ref() {
+                sent += bytes.len() as u64;
+            }
+            let until = started + transfer_time(sent, rate);
+            async move {
+                tokio::time::sleep_until(until).await;
+                piece
+            }
+        })
+}
+
+/// has the body of the response arrive no faster than `rate` bytes per
+/// second. Streamed bodies keep arriving piece by piece, every other body is
+/// read whole so its length is still known to the decoders
+pub async fn throttle_response(
+    response: reqwest::Response,
+    rate: u64,
+    streamed: bool,
+) -> reqwest::Response {
+    let mut builder = http::Response::builder()
+        .status(response.status())
+        .version(response.version());
+    if let Some(headers) = builder.headers_mut() {
+        *headers = response.headers().clone();
+    }
+
+    let body = throttle(response.bytes_stream(), rate);
+    let body = match streamed {
+        true => reqwest::Body::wrap_stream(body),
+        false => {
+            let mut bytes = vec![];
+            let mut body = std::pin::pin!(body);
+            while let Some(Ok(piece)) = body.next().await {
+                bytes.extend(piece);
+            }
+            reqwest::Body::from(bytes)
+        }
+    };
+    let response = builder
+        .body(body)
+        .expect("status and headers come from a valid response");
+    reqwest::Response::from(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay() {
+        let conditions = NetworkConditions {
+            latency_ms: Some(200),
+            jitter_ms: Some(50),
+            ..Default::default()
+        };
+        for _ in 0..50 {
+            let delay = delay(&conditions);
+            assert!(delay >= Duration::from_millis(150) && delay <= Duration::from_millis(250));
+        }
+
+        let conditions = NetworkConditions {
+            latency_ms: Some(20),
+            jitter_ms: Some(50),
+            ..Default::default()
+        };
+        assert!(delay(&conditions) <= Duration::from_millis(70));
+        assert_eq!(delay(&NetworkConditions::default()), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_transfer_time() {
+        assert_eq!(transfer_time(1024, 512), Duration::from_secs(2));
+        assert_eq!(transfer_time(100, 1000), Duration::from_millis(100));
+        assert_eq!(transfer_time(100, 0), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_throttle() {
+        let chunks = vec![Ok::<_, std::io::Error>(vec![b'a'; 30]), Ok(vec![b'b'; 10])];
+        let started = Instant::now();
+        let pieces = throttle(futures_util::stream::iter(chunks), 100)
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(pieces.len(), 4);
+        assert!(pieces
+            .iter()
+            .all(|piece| piece.as_ref().unwrap().len() == 10));
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(400) && elapsed < Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,62 @@
+use crate::collection::types::{ExampleResponse, Request};
+use crate::net::request_manager::Response;
+
+/// the example served for the request while offline, the first one that
+/// succeeded, or simply the first one when none did
+pub fn pick_example(request: &Request) -> Option<&ExampleResponse> {
+    request
+        .examples
+        .iter()
+        .find(|example| (200..300).contains(&example.status))
+        .or_else(|| request.examples.first())
+}
+
+/// answers the request with one of its examples instead of sending it,
+/// requests without examples fail saying so
+pub fn respond(request: &Request) -> Response {
+    match pick_example(request) {
+        Some(example) => {
+            let mut response = Response::from_example(example);
+            response.offline = Some(example.name.clone());
+            response
+        }
+        None => Response::from_error(format!(
+            "offline, and {:?} has no example response to serve",
+            request.name
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_respond() {
+        let mut request = serde_json::from_value::<Request>(serde_json::json!({
+            "id": "1",
+            "method": "GET",
+            "name": "users",
+            "uri": "https://example.com/users",
+        }))
+        .unwrap();
+        let response = respond(&request);
+        assert!(response.is_error);
+        assert!(response.offline.is_none());
+
+        let example = |name: &str, status: u16| ExampleResponse {
+            name: name.into(),
+            status,
+            headers: vec![],
+            body: Some(format!(r#"{{"example":"{name}"}}"#)),
+        };
+        request.examples = vec![example("missing", 404), example("listed", 200)];
+        let response = respond(&request);
+        assert_eq!(response.offline.as_deref(), Some("listed"));
+        assert_eq!(response.status, Some(reqwest::StatusCode::OK));
+        assert_eq!(response.body.as_deref(), Some(r#"{"example":"listed"}"#));
+
+        request.examples.pop();
+        assert_eq!(respond(&request).offline.as_deref(), Some("missing"));
+    }
+}
@@ -0,0 +1,276 @@
+use crate::net::tls_inspection::CertificateInfo;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use tokio_rustls::rustls::client::WebPkiServerVerifier;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use tokio_rustls::rustls::{
+    ClientConfig, DigitallySignedStruct, Error as TlsError, RootCertStore, SignatureScheme,
+};
+
+/// the pins of the host of the uri, hosts can be pinned by name or by a
+/// `*.` wildcard covering their subdomains
+pub fn pins_for<'a>(pins: &'a HashMap<String, Vec<String>>, uri: &str) -> Option<&'a [String]> {
+    let url = reqwest::Url::parse(uri.trim()).ok()?;
+    pins_for_host(pins, url.host_str()?)
+}
+
+fn pins_for_host<'a>(pins: &'a HashMap<String, Vec<String>>, host: &str) -> Option<&'a [String]> {
+    let host = host.to_ascii_lowercase();
+    pins.iter()
+        .find(|(pinned, _)| pinned.to_ascii_lowercase().eq(&host))
+        .or_else(|| {
+            pins.iter().find(|(pinned, _)| {
+                pinned
+                    .to_ascii_lowercase()
+                    .strip_prefix("*.")
+                    .is_some_and(|domain| host.ends_with(&format!(".{domain}")))
+            })
+        })
+        .map(|(_, pins)| pins.as_slice())
+}
+
+/// makes sure a certificate of the chain the server presented has one of
+/// the pinned public keys. Pins are base64 SHA-256 hashes of the public key
+/// info, optionally prefixed by `sha256/` as curl writes them
+pub fn check(host: &str, pins: &[String], chain: &[CertificateInfo]) -> anyhow::Result<()> {
+    let pinned = pins
+        .iter()
+        .map(|pin| pin.trim().trim_start_matches("sha256/"))
+        .collect::<Vec<_>>();
+    let matches = chain
+        .iter()
+        .any(|certificate| pinned.contains(&certificate.spki_sha256.as_str()));
+    if matches {
+        return Ok(());
+    }
+
+    let presented = chain
+        .iter()
+        .map(|certificate| format!("sha256/{}", certificate.spki_sha256))
+        .collect::<Vec<_>>()
+        .join(", ");
+    anyhow::bail!(
+        "the certificate of {host} doesn't match its pins, it presented the keys {presented}"
+    )
+}
+
+/// what the TLS of a client needs from the curlrc when it is set up by hac
+/// rather than by reqwest, as PEM files
+#[derive(Debug, Default)]
+pub struct TlsSettings {
+    /// certificates trusted besides the ones of the system
+    pub roots: Option<Vec<u8>>,
+    /// the certificate presented to servers, along with its key
+    pub identity: Option<Vec<u8>>,
+    /// whether untrusted certificates are accepted, pins are still checked
+    pub insecure: bool,
+}
+
+/// the TLS of a client that refuses, during the handshake, servers whose
+/// chain doesn't match the pins of their host. As the check happens on the
+/// connection requests are sent through, nothing reaches a server that
+/// doesn't match
+pub fn tls_config(
+    pins: &HashMap<String, Vec<String>>,
+    settings: TlsSettings,
+) -> anyhow::Result<ClientConfig> {
+    let mut roots = RootCertStore::empty();
+    roots.add_parsable_certificates(rustls_native_certs::load_native_certs().certs);
+    if let Some(pem) = settings.roots.as_ref() {
+        for cert in CertificateDer::pem_slice_iter(pem) {
+            roots.add(cert?)?;
+        }
+    }
+    let verifier = Arc::new(PinningVerifier {
+        inner: WebPkiServerVerifier::builder(Arc::new(roots)).build()?,
+        pins: pins.clone(),
+        insecure: settings.insecure,
+    });
+    let builder = ClientConfig::builder()
+        .dangerous()
+        .with_custom_certificate_verifier(verifier);
+
+    let mut config = match settings.identity.as_ref() {
+        Some(pem) => {
+            let certs = CertificateDer::pem_slice_iter(pem).collect::<Result<Vec<_>, _>>()?;
+            builder.with_client_auth_cert(certs, PrivateKeyDer::from_pem_slice(pem)?)?
+        }
+        None => builder.with_no_client_auth(),
+    };
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+/// checks the chain a server presented on the handshake against the pins
+/// of its host, hosts without pins are accepted
+fn check_handshake(
+    pins: &HashMap<String, Vec<String>>,
+    server_name: &ServerName<'_>,
+    end_entity: &CertificateDer<'_>,
+    intermediates: &[CertificateDer<'_>],
+) -> anyhow::Result<()> {
+    let host = server_name.to_str();
+    let Some(pins) = pins_for_host(pins, &host) else {
+        return Ok(());
+    };
+    let chain = std::iter::once(end_entity)
+        .chain(intermediates)
+        .map(|cert| CertificateInfo::parse(cert))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    check(&host, pins, &chain)
+}
+
+/// verifies certificates like the system does, unless told to accept
+/// untrusted ones, then checks the chain against the pins of the host
+#[derive(Debug)]
+struct PinningVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+    pins: HashMap<String, Vec<String>>,
+    insecure: bool,
+}
+
+impl ServerCertVerifier for PinningVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, TlsError> {
+        if !self.insecure {
+            self.inner.verify_server_cert(
+                end_entity,
+                intermediates,
+                server_name,
+                ocsp_response,
+                now,
+            )?;
+        }
+
+        check_handshake(&self.pins, server_name, end_entity, intermediates)
+            .map_err(|e| TlsError::General(e.to_string()))?;
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, TlsError> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, TlsError> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pins_for() {
+        let pins = HashMap::from([
+            (
+                "api.example.com".to_string(),
+                vec!["sha256/exact".to_string()],
+            ),
+            (
+                "*.example.com".to_string(),
+                vec!["sha256/wildcard".to_string()],
+            ),
+        ]);
+
+        assert_eq!(
+            pins_for(&pins, "https://API.example.com/users"),
+            Some(&["sha256/exact".to_string()][..])
+        );
+        assert_eq!(
+            pins_for(&pins, "https://auth.example.com:8443"),
+            Some(&["sha256/wildcard".to_string()][..])
+        );
+        assert!(pins_for(&pins, "https://example.com").is_none());
+        assert!(pins_for(&pins, "https://other.com").is_none());
+    }
+
+    #[test]
+    fn test_check() {
+        let certificate = |spki_sha256: &str| CertificateInfo {
+            subject: String::default(),
+            issuer: String::default(),
+            not_before: chrono::Utc::now(),
+            not_after: chrono::Utc::now(),
+            sans: vec![],
+            spki_sha256: spki_sha256.into(),
+        };
+        let chain = vec![certificate("leaf="), certificate("intermediate=")];
+
+        assert!(check("example.com", &["sha256/leaf=".into()], &chain).is_ok());
+        assert!(check("example.com", &["intermediate=".into()], &chain).is_ok());
+        let error = check("example.com", &["sha256/other=".into()], &chain).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "the certificate of example.com doesn't match its pins, it presented the keys sha256/leaf=, sha256/intermediate="
+        );
+    }
+
+    #[test]
+    fn test_check_handshake() {
+        let element = |tag: u8, parts: &[&[u8]]| {
+            let value = parts.concat();
+            [vec![tag, value.len() as u8], value].concat()
+        };
+        let key = element(0x30, &[&element(0x03, &[&[0x00, 0xff]])]);
+        let validity = element(
+            0x30,
+            &[
+                &element(0x17, &[b"240101000000Z"]),
+                &element(0x17, &[b"250101000000Z"]),
+            ],
+        );
+        let tbs = element(
+            0x30,
+            &[
+                &element(0x02, &[&[0x01]]),
+                &element(0x30, &[]),
+                &element(0x30, &[]),
+                &validity,
+                &element(0x30, &[]),
+                &key,
+            ],
+        );
+        let der = element(
+            0x30,
+            &[&tbs, &element(0x30, &[]), &element(0x03, &[&[0x00]])],
+        );
+        let certificate = CertificateDer::from(der);
+        let spki = CertificateInfo::parse(&certificate).unwrap().spki_sha256;
+
+        let pins = HashMap::from([
+            ("example.com".to_string(), vec![format!("sha256/{spki}")]),
+            ("other.com".to_string(), vec!["sha256/other=".to_string()]),
+        ]);
+        let name = |host: &str| ServerName::try_from(host.to_string()).unwrap();
+        assert!(check_handshake(&pins, &name("example.com"), &certificate, &[]).is_ok());
+        assert!(check_handshake(&pins, &name("unpinned.com"), &certificate, &[]).is_ok());
+        assert!(check_handshake(&pins, &name("other.com"), &certificate, &[]).is_err());
+    }
+}
@@ -0,0 +1,169 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+
+/// quota information a server sent along with a response, either through the
+/// standard `RateLimit-*` headers or the widespread `X-RateLimit-*` ones
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RateLimit {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    /// how long until the quota is reset
+    pub reset: Option<Duration>,
+    /// how long the server asked us to wait before sending another request
+    pub retry_after: Option<Duration>,
+}
+
+impl RateLimit {
+    /// reads the rate limit headers of a response, returning `None` when the
+    /// response has none of them
+    pub fn from_headers(headers: &HeaderMap) -> Option<RateLimit> {
+        let rate_limit = RateLimit {
+            limit: header_number(headers, &["ratelimit-limit", "x-ratelimit-limit"]),
+            remaining: header_number(headers, &["ratelimit-remaining", "x-ratelimit-remaining"]),
+            reset: header_number(headers, &["ratelimit-reset", "x-ratelimit-reset"])
+                .map(reset_as_duration),
+            retry_after: headers
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_retry_after),
+        };
+
+        rate_limit.ne(&RateLimit::default()).then_some(rate_limit)
+    }
+
+    /// whether we are not allowed to send more requests until the reset
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining.is_some_and(|remaining| remaining.eq(&0))
+    }
+}
+
+/// how long to wait before retrying a request that got the given response,
+/// `None` means the request was not rate limited, or the server didn't say for
+/// how long we should wait
+pub fn retry_delay(status: StatusCode, headers: &HeaderMap) -> Option<Duration> {
+    if !matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+    ) {
+        return None;
+    }
+
+    let rate_limit = RateLimit::from_headers(headers)?;
+    rate_limit.retry_after.or_else(|| {
+        rate_limit
+            .is_exhausted()
+            .then_some(rate_limit.reset)
+            .flatten()
+    })
+}
+
+fn header_number(headers: &HeaderMap, names: &[&str]) -> Option<u64> {
+    names
+        .iter()
+        .find_map(|name| headers.get(*name))
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+}
+
+/// some servers send the reset as an unix timestamp instead of an amount of
+/// seconds, anything larger than a year is considered a timestamp
+fn reset_as_duration(reset: u64) -> Duration {
+    const ONE_YEAR: u64 = 365 * 24 * 60 * 60;
+    if reset.gt(&ONE_YEAR) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        return Duration::from_secs(reset.saturating_sub(now));
+    }
+    Duration::from_secs(reset)
+}
+
+/// `Retry-After` can be either an amount of seconds or an HTTP date
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    if let Ok(seconds) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let retry_at = parse_http_date(value)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    Some(Duration::from_secs(retry_at.saturating_sub(now)))
+}
+
+/// parses an IMF-fixdate, eg: `Sun, 06 Nov 1994 08:49:37 GMT`, into seconds
+/// since the unix epoch
+fn parse_http_date(value: &str) -> Option<u64> {
+    let (_, date) = value.trim().split_once(", ")?;
+    let parts = date.split_whitespace().collect::<Vec<_>>();
+    let [day, month, year, time, "GMT"] = parts.as_slice() else {
+        return None;
+    };
+
+    let day: i64 = day.parse().ok()?;
+    let year: i64 = year.parse().ok()?;
+    let month = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ]
+    .iter()
+    .position(|m| m.eq(month))? as i64
+        + 1;
+    let mut time = time.split(':').map(|part| part.parse::<i64>().ok());
+    let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+
+    // days from civil, shifting the year to start on march so leap days are
+    // the last day of the year
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+
+    u64::try_from(days * 86400 + hour * 3600 + minute * 60 + second).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(784111777)
+        );
+        assert_eq!(
+            parse_retry_after("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("0"));
+        headers.insert("x-ratelimit-reset", HeaderValue::from_static("30"));
+
+        assert_eq!(
+            retry_delay(StatusCode::TOO_MANY_REQUESTS, &headers),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(retry_delay(StatusCode::OK, &headers), None);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("5"));
+        assert_eq!(
+            retry_delay(StatusCode::TOO_MANY_REQUESTS, &headers),
+            Some(Duration::from_secs(5))
+        );
+        assert!(RateLimit::from_headers(&HeaderMap::new()).is_none());
+    }
+}
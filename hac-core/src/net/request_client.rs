@@ -1,40 +1,154 @@
-use crate::collection::types::Request;
+use crate::collection::types::{AutomaticHeader, BodyType, Request};
+use crate::net::charset::{charset_from_content_type, encode_body, encoding_for_label};
+use crate::net::{network_conditions, soap};
+use crate::text_object::hex_buffer::decode_body;
+
+/// sent by requests that don't set their own `User-Agent`
+pub const USER_AGENT: &str = concat!("hac/", env!("CARGO_PKG_VERSION"));
 
 #[derive(Debug)]
 pub struct RequestClient {
     client: reqwest::Client,
+    /// the body already encoded as a protobuf message, sent in place of the
+    /// body of the request when set
+    protobuf_body: Option<Vec<u8>>,
 }
 
 impl RequestClient {
     pub fn new() -> Self {
         RequestClient {
             client: reqwest::Client::new(),
+            protobuf_body: None,
+        }
+    }
+
+    pub fn with_client(client: reqwest::Client) -> Self {
+        RequestClient {
+            client,
+            protobuf_body: None,
         }
     }
 
+    pub fn with_protobuf_body(mut self, body: Option<Vec<u8>>) -> Self {
+        self.protobuf_body = body;
+        self
+    }
+
     pub fn get(&self, request: &Request) -> reqwest::RequestBuilder {
         let request_builder = self.client.get(&request.uri);
-        self.append_headers(request, request_builder)
+        self.prepare(request, request_builder)
     }
 
     pub fn post(&self, request: &Request) -> reqwest::RequestBuilder {
         let request_builder = self.client.post(&request.uri);
-        self.append_headers(request, request_builder)
+        self.prepare(request, request_builder)
     }
 
     pub fn put(&self, request: &Request) -> reqwest::RequestBuilder {
         let request_builder = self.client.put(&request.uri);
-        self.append_headers(request, request_builder)
+        self.prepare(request, request_builder)
     }
 
     pub fn patch(&self, request: &Request) -> reqwest::RequestBuilder {
         let request_builder = self.client.patch(&request.uri);
-        self.append_headers(request, request_builder)
+        self.prepare(request, request_builder)
     }
 
     pub fn delete(&self, request: &Request) -> reqwest::RequestBuilder {
         let request_builder = self.client.delete(&request.uri);
-        self.append_headers(request, request_builder)
+        self.prepare(request, request_builder)
+    }
+
+    /// attaches the body of the request, when the request specifies a charset
+    /// other than UTF-8, on its encoding or on its `Content-Type` header, the
+    /// body is encoded with it before being sent. Bodies encoded as protobuf
+    /// are sent as is, and so are the bytes of binary bodies
+    pub fn with_body(
+        &self,
+        request: &Request,
+        request_builder: reqwest::RequestBuilder,
+    ) -> reqwest::RequestBuilder {
+        let content_type = request
+            .headers
+            .iter()
+            .flatten()
+            .filter(|header| header.enabled)
+            .find(|header| header.pair.0.eq_ignore_ascii_case("content-type"));
+
+        let (body, default_content_type) = match self.protobuf_body.clone() {
+            Some(bytes) => (bytes, Some("application/x-protobuf")),
+            None => {
+                let body = request.body.clone().unwrap_or_default();
+                let charset = request
+                    .encoding
+                    .as_deref()
+                    .and_then(encoding_for_label)
+                    .or_else(|| {
+                        content_type.and_then(|header| charset_from_content_type(&header.pair.1))
+                    });
+                match charset {
+                    _ if request.body_type.eq(&Some(BodyType::Binary)) => {
+                        (decode_body(&body), Some("application/octet-stream"))
+                    }
+                    Some(encoding) if encoding.ne(encoding_rs::UTF_8) => {
+                        (encode_body(&body, encoding), None)
+                    }
+                    _ if soap::is_raw_body(request) => (body.into_bytes(), None),
+                    _ => (body.into_bytes(), Some("application/json")),
+                }
+            }
+        };
+
+        let request_builder = match default_content_type {
+            Some(value)
+                if content_type.is_none() && !request.omits(AutomaticHeader::ContentType) =>
+            {
+                request_builder.header("Content-Type", value)
+            }
+            _ => request_builder,
+        };
+
+        // throttled bodies are streamed, so their length is set up front
+        // unless it was omitted
+        if let Some(rate) = request.network.as_ref().and_then(|n| n.upload_rate) {
+            let has_length = request
+                .headers
+                .iter()
+                .flatten()
+                .filter(|header| header.enabled)
+                .any(|header| header.pair.0.eq_ignore_ascii_case("content-length"));
+            let request_builder = match has_length || request.omits(AutomaticHeader::ContentLength)
+            {
+                true => request_builder,
+                false => request_builder.header("Content-Length", body.len()),
+            };
+            let body =
+                futures_util::stream::once(std::future::ready(Ok::<_, std::io::Error>(body)));
+            return request_builder.body(reqwest::Body::wrap_stream(network_conditions::throttle(
+                body, rate,
+            )));
+        }
+
+        // a body without a known length is sent chunked
+        match request.omits(AutomaticHeader::ContentLength) {
+            true => request_builder.body(reqwest::Body::wrap_stream(futures_util::stream::once(
+                std::future::ready(Ok::<_, std::io::Error>(body)),
+            ))),
+            false => request_builder.body(body),
+        }
+    }
+
+    /// attaches the headers of the request and the timeout it sets
+    fn prepare(
+        &self,
+        request: &Request,
+        request_builder: reqwest::RequestBuilder,
+    ) -> reqwest::RequestBuilder {
+        let request_builder = self.append_headers(request, request_builder);
+        match request.timeout_ms {
+            Some(timeout) => request_builder.timeout(std::time::Duration::from_millis(timeout)),
+            None => request_builder,
+        }
     }
 
     fn append_headers(
@@ -49,13 +163,131 @@ impl RequestClient {
                 request_builder = request_builder.header(header_name, header_value);
             }
         }
+        for (header_name, header_value) in automatic_headers(request) {
+            request_builder = request_builder.header(header_name, header_value);
+        }
 
         request_builder
     }
 }
 
+/// headers added to the request because it didn't set them, leaving out
+/// the ones the request omits
+pub fn automatic_headers(request: &Request) -> Vec<(String, String)> {
+    let has_user_agent = request
+        .headers
+        .iter()
+        .flatten()
+        .any(|header| header.enabled && header.pair.0.eq_ignore_ascii_case("user-agent"));
+
+    let mut headers = vec![];
+    if !has_user_agent && !request.omits(AutomaticHeader::UserAgent) {
+        headers.push(("User-Agent".into(), USER_AGENT.into()));
+    }
+    headers.extend(
+        soap::missing_headers(request)
+            .into_iter()
+            .filter(|(name, _)| {
+                !(name.eq_ignore_ascii_case("content-type")
+                    && request.omits(AutomaticHeader::ContentType))
+            }),
+    );
+    headers
+}
+
 impl Default for RequestClient {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::fixtures;
+    use crate::collection::types::{HeaderMap, SoapOperation};
+
+    fn sent_body(request: &Request) -> Vec<u8> {
+        let client = RequestClient::new();
+        let built = client
+            .with_body(request, client.post(request))
+            .build()
+            .unwrap();
+        built
+            .body()
+            .and_then(|body| body.as_bytes())
+            .unwrap()
+            .to_vec()
+    }
+
+    #[test]
+    fn test_with_body() {
+        let mut request = fixtures::request("create", "http://localhost/users");
+        request.body = Some("{\"name\": \"café\"}".into());
+        assert_eq!(sent_body(&request), "{\"name\": \"café\"}".as_bytes());
+
+        request.encoding = Some("latin1".into());
+        assert_eq!(sent_body(&request), b"{\"name\": \"caf\xe9\"}".as_slice());
+
+        // unknown charsets fall back to UTF-8
+        request.encoding = Some("klingon".into());
+        assert_eq!(sent_body(&request), "{\"name\": \"café\"}".as_bytes());
+    }
+
+    #[test]
+    fn test_automatic_headers() {
+        let mut request = fixtures::request("soap", "http://localhost/service");
+        request.soap = Some(SoapOperation {
+            action: "urn:GetUser".into(),
+            ..Default::default()
+        });
+        let names = |request: &Request| {
+            automatic_headers(request)
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(&request),
+            ["User-Agent", "Content-Type", "SOAPAction"]
+        );
+
+        request.omit_headers = vec![AutomaticHeader::UserAgent, AutomaticHeader::ContentType];
+        assert!(request.omits(AutomaticHeader::UserAgent));
+        assert!(!request.omits(AutomaticHeader::ContentLength));
+        assert_eq!(names(&request), ["SOAPAction"]);
+
+        // headers set on the request are never added again
+        request.omit_headers.clear();
+        request.headers = Some(vec![HeaderMap {
+            pair: ("user-agent".into(), "custom".into()),
+            enabled: true,
+        }]);
+        assert_eq!(names(&request), ["Content-Type", "SOAPAction"]);
+    }
+
+    #[test]
+    fn test_omitted_body_headers() {
+        let mut request = fixtures::request("create", "http://localhost/users");
+        request.body = Some("{}".into());
+        let client = RequestClient::new();
+        let build = |request: &Request| {
+            client
+                .with_body(request, client.post(request))
+                .build()
+                .unwrap()
+        };
+
+        let built = build(&request);
+        assert_eq!(built.headers()["content-type"], "application/json");
+        assert!(!built.headers().contains_key("accept-encoding"));
+        assert!(built.body().and_then(|body| body.as_bytes()).is_some());
+
+        // without a length the body is streamed, which is sent chunked
+        request.omit_headers = vec![AutomaticHeader::ContentType, AutomaticHeader::ContentLength];
+        let built = build(&request);
+        assert!(!built.headers().contains_key("content-type"));
+        assert!(!built.headers().contains_key("content-length"));
+        assert!(built.body().and_then(|body| body.as_bytes()).is_none());
+    }
+}
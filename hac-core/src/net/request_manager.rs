@@ -1,14 +1,28 @@
-use crate::collection::types::{BodyType, Request};
+use crate::collection::conditions::{self, RunContext, RunDecision};
+use crate::collection::environment::resolve_request;
+use crate::collection::session;
+use crate::collection::types::{BodyType, ExampleResponse, NetworkConditions, Request};
+use crate::net::body_limit::BodyLimit;
+use crate::net::curl_config::CurlDefaults;
+use crate::net::expect_continue::{self, InterimResponse};
+use crate::net::rate_limit::retry_delay;
+use crate::net::request_strategies::websocket_strategy::WebSocketStrategy;
 use crate::net::request_strategies::{http_strategy::HttpResponse, RequestStrategy};
+use crate::net::tls_inspection::TlsInfo;
+use crate::net::{network_conditions, offline};
+use crate::protobuf::Schema;
+use crate::syntax::jsonc::strip_jsonc;
 use crate::text_object::{Readonly, TextObject};
 
-use std::sync::{Arc, RwLock};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 
-use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use tokio::sync::mpsc::UnboundedSender;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Default, PartialEq)]
 pub struct Response {
     pub body: Option<String>,
     pub pretty_body: Option<TextObject<Readonly>>,
@@ -20,6 +34,122 @@ pub struct Response {
     pub size: Option<u64>,
     pub is_error: bool,
     pub cause: Option<String>,
+    /// whether the body is still arriving, a response with more of it
+    /// follows until one that isn't streaming
+    pub streaming: bool,
+    /// `1xx` responses received before this one, like the `100 Continue` of
+    /// uploads that waited for the server to accept them
+    pub interim: Vec<InterimResponse>,
+    /// name of the example served in place of a real response while
+    /// offline, nothing was sent when this is set
+    pub offline: Option<String>,
+    /// what was negotiated with the server of https requests, found out on
+    /// a handshake of its own when the session is inspected
+    pub tls: Option<TlsInfo>,
+    /// where the whole body was written when it was too large to keep in
+    /// memory, `body` only holds its start then
+    pub body_file: Option<PathBuf>,
+    /// whether `pretty_body` only holds the start of the body, the rest of
+    /// it is pretty printed once the whole body is asked for
+    pub pretty_truncated: bool,
+    /// the body as it was received, only kept for binary content types, like
+    /// images, that are not shown as text
+    pub raw_body: Option<Vec<u8>>,
+}
+
+impl Response {
+    /// a response carrying a plain text body, such as a transcript of the
+    /// messages exchanged over a socket
+    pub fn from_text(body: String) -> Response {
+        Response {
+            pretty_body: Some(TextObject::from(&body)),
+            body_size: Some(body.len() as u64),
+            size: Some(body.len() as u64),
+            body: Some(body),
+            ..Default::default()
+        }
+    }
+
+    /// a response for a request that could not be sent at all
+    pub fn from_error(cause: impl Into<String>) -> Response {
+        Response {
+            is_error: true,
+            cause: Some(cause.into()),
+            ..Default::default()
+        }
+    }
+
+    /// captures this response as a named example to be stored on a request,
+    /// responses that failed before reaching the server have nothing to store
+    pub fn to_example(&self, name: impl Into<String>) -> Option<ExampleResponse> {
+        let status = self.status?;
+        let headers = self
+            .headers
+            .as_ref()
+            .map(|headers| {
+                headers
+                    .iter()
+                    .map(|(name, value)| {
+                        (
+                            name.to_string(),
+                            String::from_utf8_lossy(value.as_bytes()).to_string(),
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(ExampleResponse {
+            name: name.into(),
+            status: status.as_u16(),
+            headers,
+            body: self.body.clone(),
+        })
+    }
+
+    /// builds a response out of a stored example, so it can be displayed the
+    /// same way a real response is, without sending anything
+    pub fn from_example(example: &ExampleResponse) -> Response {
+        let headers = example
+            .headers
+            .iter()
+            .filter_map(|(name, value)| {
+                let name = HeaderName::from_bytes(name.as_bytes()).ok()?;
+                let value = HeaderValue::from_str(value).ok()?;
+                Some((name, value))
+            })
+            .collect::<HeaderMap>();
+        let headers_size = example
+            .headers
+            .iter()
+            .map(|(name, value)| name.len() + value.len() + 4)
+            .sum::<usize>() as u64;
+        let body_size = example.body.as_ref().map(String::len).unwrap_or_default() as u64;
+        let pretty_body = example.body.as_ref().map(|body| {
+            let pretty = jsonxf::pretty_print(body).unwrap_or_else(|_| body.clone());
+            TextObject::from(&pretty)
+        });
+
+        Response {
+            body: example.body.clone(),
+            pretty_body,
+            headers: Some(headers),
+            duration: Duration::default(),
+            status: reqwest::StatusCode::from_u16(example.status).ok(),
+            headers_size: Some(headers_size),
+            body_size: Some(body_size),
+            size: Some(headers_size + body_size),
+            is_error: false,
+            streaming: false,
+            interim: vec![],
+            offline: None,
+            tls: None,
+            body_file: None,
+            pretty_truncated: false,
+            raw_body: None,
+            cause: None,
+        }
+    }
 }
 
 pub struct RequestManager;
@@ -46,7 +176,12 @@ impl From<&str> for ContentType {
     fn from(value: &str) -> Self {
         match value {
             _ if value.to_ascii_lowercase().contains("application/json") => Self::ApplicationJson,
-            _ if value.to_ascii_lowercase().contains("application/xml") => Self::ApplicationXml,
+            // SOAP services answer with `text/xml` or `application/soap+xml`
+            _ if value.to_ascii_lowercase().contains("/xml")
+                || value.to_ascii_lowercase().contains("+xml") =>
+            {
+                Self::ApplicationXml
+            }
             _ if value.to_ascii_lowercase().contains("text/plain") => Self::TextPlain,
             _ if value.to_ascii_lowercase().contains("text/plain") => Self::TextPlain,
             _ if value.to_ascii_lowercase().contains("text/html") => Self::TextHtml,
@@ -57,21 +192,230 @@ impl From<&str> for ContentType {
     }
 }
 
+/// sends the request after its prerequisites. Values captured from their
+/// responses are used by the requests sent after them, and every captured
+/// value is reported through `captures_tx` to be kept on the session. Only
+/// the body of the request itself is limited by `body_limit`
 #[tracing::instrument(skip_all)]
-pub fn handle_request(request: &Arc<RwLock<Request>>, response_tx: UnboundedSender<Response>) {
-    let request = request.read().unwrap().clone();
+#[allow(clippy::too_many_arguments)]
+pub fn handle_request(
+    request: Request,
+    dependencies: Vec<Request>,
+    context: RunContext,
+    config: &hac_config::Config,
+    protobuf: Option<Arc<Schema>>,
+    body_limit: Option<BodyLimit>,
+    response_tx: UnboundedSender<Response>,
+    captures_tx: UnboundedSender<BTreeMap<String, String>>,
+) {
+    let curl = CurlDefaults::load(&config.curl);
+    let mut request = prepare_request(request, config);
+    curl.authorize(&mut request);
+    let dependencies = dependencies
+        .into_iter()
+        .map(|dependency| {
+            let mut dependency = prepare_request(dependency, config);
+            curl.authorize(&mut dependency);
+            dependency
+        })
+        .collect::<Vec<_>>();
+    let rate_limit = config.rate_limit.clone();
+    let offline = config.offline;
+    let strategy = match curl.client(&config.connection, &config.pins) {
+        Ok(client) => HttpResponse {
+            client,
+            protobuf,
+            progress_tx: None,
+            pins: config.pins.clone(),
+            body_limit: None,
+        },
+        Err(e) => {
+            let cause = format!("failed to apply the settings of .curlrc: {e}");
+            _ = response_tx.send(Response::from_error(cause));
+            return;
+        }
+    };
+
     tokio::spawn(async move {
-        let response = match request.body_type.as_ref() {
-            // if we dont have a body type, this is a GET request, so we use HTTP strategy
-            None => RequestManager::handle(HttpResponse, request).await,
-            Some(body_type) => match body_type {
-                BodyType::Json => RequestManager::handle(HttpResponse, request).await,
-            },
-        };
+        let mut context = context;
+        let mut captured = BTreeMap::default();
 
+        // prerequisites are sent one by one, in order, and the first one that
+        // fails is reported instead of sending the request itself, unless
+        // their conditions say otherwise
+        for dependency in dependencies {
+            // variables left unresolved may have been captured by now
+            let dependency = resolve_request(&dependency, &captured);
+            if let RunDecision::Skip(reason) = conditions::evaluate(&dependency, &context) {
+                tracing::debug!("skipping prerequisite {:?}: {reason}", dependency.name);
+                continue;
+            }
+
+            let mut response =
+                send_request(dependency.clone(), &strategy, &rate_limit, offline).await;
+            let values = session::capture(&dependency.captures, &response);
+            context.variables.extend(values.clone());
+            captured.extend(values);
+            let failed = response.is_error || !response.status.is_some_and(|s| s.is_success());
+            if failed && conditions::continues_on_failure(&dependency) {
+                tracing::debug!("prerequisite {:?} failed, continuing", dependency.name);
+                continue;
+            }
+            if failed {
+                let reason = match (response.cause.take(), response.status) {
+                    (Some(cause), _) => cause,
+                    (None, Some(status)) => format!("it responded with {status}"),
+                    (None, None) => "it got no response".into(),
+                };
+                response.is_error = true;
+                response.cause = Some(format!(
+                    "prerequisite request {:?} failed: {reason}",
+                    dependency.name
+                ));
+                _ = captures_tx.send(captured);
+                response_tx
+                    .send(response)
+                    .is_err()
+                    .then(|| std::process::abort());
+                return;
+            }
+        }
+
+        // only the body of the request itself is shown while it arrives
+        let strategy = HttpResponse {
+            progress_tx: Some(response_tx.clone()),
+            body_limit,
+            ..strategy
+        };
+        let request = resolve_request(&request, &captured);
+        let response = send_request(request.clone(), &strategy, &rate_limit, offline).await;
+        captured.extend(session::capture(&request.captures, &response));
+        // captures are sent first, so they are kept by the time the response
+        // is shown
+        _ = captures_tx.send(captured);
         response_tx
             .send(response)
             .is_err()
             .then(|| std::process::abort());
     });
 }
+
+pub(crate) fn prepare_request(mut request: Request, config: &hac_config::Config) -> Request {
+    // JSON bodies are edited as JSONC, so we strip comments and trailing
+    // commas right before sending, the stored body is kept untouched. Other
+    // bodies are sent as they are, as `//` is valid on them
+    let is_json = matches!(request.body_type, None | Some(BodyType::Json));
+    if config.strip_body_comments && is_json {
+        request.body = request.body.map(|body| strip_jsonc(&body));
+    }
+    expect_continue::expect_large_upload(&mut request, config.expect_continue_bytes);
+    // conditions of the request take precedence over the ones of the config
+    if request.network.is_none() {
+        let conditions = NetworkConditions::from(&config.network);
+        request.network = (!conditions.is_empty()).then_some(conditions);
+    }
+    request
+}
+
+pub(crate) async fn send_request(
+    request: Request,
+    strategy: &HttpResponse,
+    rate_limit: &hac_config::RateLimitConfig,
+    offline: bool,
+) -> Response {
+    if offline {
+        return offline::respond(&request);
+    }
+
+    let mut retries = 0;
+    loop {
+        let strategy = strategy.clone();
+        // the added latency counts towards the duration, as it would on a
+        // slow link
+        let latency = request
+            .network
+            .as_ref()
+            .map(network_conditions::delay)
+            .unwrap_or_default();
+        tokio::time::sleep(latency).await;
+        let mut response = match request.body_type.as_ref() {
+            _ if request.is_websocket() => {
+                RequestManager::handle(WebSocketStrategy, request.clone()).await
+            }
+            // if we dont have a body type, this is a GET request, so we use HTTP strategy
+            None => RequestManager::handle(strategy, request.clone()).await,
+            Some(body_type) => match body_type {
+                BodyType::Json | BodyType::Xml | BodyType::GraphQl | BodyType::Binary => {
+                    RequestManager::handle(strategy, request.clone()).await
+                }
+            },
+        };
+        response.duration += latency;
+
+        // when rate limited, we wait for as long as the server asked us to and
+        // try again, as long as the user allowed it and the wait is reasonable
+        let delay = response
+            .status
+            .zip(response.headers.as_ref())
+            .and_then(|(status, headers)| retry_delay(status, headers))
+            .filter(|delay| delay.as_secs().le(&rate_limit.max_wait_secs));
+        match delay {
+            Some(delay) if rate_limit.auto_retry && retries.lt(&rate_limit.max_retries) => {
+                retries += 1;
+                tracing::debug!("rate limited, retrying in {delay:?} ({retries})");
+                tokio::time::sleep(delay).await;
+            }
+            _ => break response,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example_round_trip() {
+        let example = ExampleResponse {
+            name: "created".into(),
+            status: 201,
+            headers: vec![("content-type".into(), "application/json".into())],
+            body: Some(r#"{"id":1}"#.into()),
+        };
+
+        let response = Response::from_example(&example);
+        assert_eq!(response.status, Some(reqwest::StatusCode::CREATED));
+        assert_eq!(
+            response.pretty_body.as_ref().unwrap().to_string(),
+            "{\n  \"id\": 1\n}"
+        );
+        assert_eq!(response.to_example("created"), Some(example));
+    }
+
+    #[test]
+    fn test_prepare_request_strips_json_only() {
+        let mut config = hac_config::load_config();
+        config.strip_body_comments = true;
+
+        let json = Request {
+            body: Some("{\n  // the id\n  \"id\": 1,\n}".into()),
+            ..Default::default()
+        };
+        let json = prepare_request(json, &config);
+        assert!(!json.body.unwrap().contains("//"));
+
+        let xml = r#"<a href="http://localhost/users">// not a comment</a>"#;
+        let binary = "ab//cd+/ef//==";
+        for (body, body_type) in [(xml, BodyType::Xml), (binary, BodyType::Binary)] {
+            let request = Request {
+                body: Some(body.into()),
+                body_type: Some(body_type),
+                ..Default::default()
+            };
+            assert_eq!(
+                prepare_request(request, &config).body.as_deref(),
+                Some(body)
+            );
+        }
+    }
+}
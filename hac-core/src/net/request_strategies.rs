@@ -1,4 +1,5 @@
 pub mod http_strategy;
+pub mod websocket_strategy;
 
 use std::future::Future;
 
@@ -1,14 +1,85 @@
-use crate::collection::types::{Request, RequestMethod};
+use crate::collection::types::{AutomaticHeader, HeaderMap, Request, RequestMethod};
+use crate::net::auth::{self, Handshake};
+use crate::net::body_limit::BodyLimit;
+use crate::net::expect_continue::{self, CONTINUE_TIMEOUT};
+use crate::net::network_conditions;
+use crate::net::pinning;
 use crate::net::request_client::RequestClient;
 use crate::net::request_manager::Response;
 use crate::net::request_strategies::RequestStrategy;
-use crate::net::response_decoders::{decoder_from_headers, ResponseDecoder};
+use crate::net::response_decoders::{
+    decoder_from_headers, is_stream, Decoder, ProtobufDecoder, ResponseDecoder, StreamDecoder,
+};
+use crate::protobuf::Schema;
 
-pub struct HttpResponse;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use tokio::sync::mpsc::UnboundedSender;
+
+/// requests sent while authenticating, NTLM takes two and kerberos usually
+/// one, a third leg covers servers that ask for mutual authentication
+const MAX_HANDSHAKE_LEGS: usize = 3;
+
+#[derive(Clone)]
+pub struct HttpResponse {
+    /// shared between requests so connections are reused, and configured
+    /// with the proxy and certificates the user set up
+    pub client: reqwest::Client,
+    /// messages of the collection, used by requests exchanging protobuf
+    pub protobuf: Option<Arc<Schema>>,
+    /// where streamed bodies are sent as they arrive, when they should be
+    /// shown before the response is complete
+    pub progress_tx: Option<UnboundedSender<Response>>,
+    /// public keys hosts are pinned to, keyed by host
+    pub pins: HashMap<String, Vec<String>>,
+    /// how much of large bodies is kept in memory and pretty printed, every
+    /// body is kept whole when there is no limit
+    pub body_limit: Option<BodyLimit>,
+}
 
 impl RequestStrategy for HttpResponse {
     async fn handle(&self, request: Request) -> Response {
-        let client = RequestClient::default();
+        // pins are checked by the client on the handshake, which plain http
+        // doesn't have
+        if pinning::pins_for(&self.pins, &request.uri).is_some() && !is_https(&request.uri) {
+            let host = reqwest::Url::parse(request.uri.trim())
+                .ok()
+                .and_then(|url| url.host_str().map(String::from))
+                .unwrap_or_default();
+            let cause = format!("{host} is pinned, so it can only be reached over https");
+            return Response::from_error(cause);
+        }
+        if request.omits(AutomaticHeader::Accept) && !has_header(&request, "accept") {
+            let cause = "the http client sends `Accept: */*` on requests without one, so it can't be omitted, set the `Accept` the server expects instead";
+            return Response::from_error(cause);
+        }
+        self.send(request).await
+    }
+}
+
+impl HttpResponse {
+    async fn send(&self, mut request: Request) -> Response {
+        let handshake = match Handshake::start(&mut request) {
+            Ok(handshake) => handshake,
+            Err(e) => return Response::from_error(e.to_string()),
+        };
+        if let Err(e) = auth::sign(&mut request) {
+            return Response::from_error(e.to_string());
+        }
+        let body = match self.encode_body(&request) {
+            Ok(body) => body,
+            Err(e) => return Response::from_error(e.to_string()),
+        };
+        let client = RequestClient::with_client(self.client.clone()).with_protobuf_body(body);
+
+        if let Some(handshake) = handshake {
+            return self.handle_handshake(client, request, handshake).await;
+        }
+        if expect_continue::expects_continue(&request) {
+            return self.handle_expect_continue(client, request).await;
+        }
 
         match request.method {
             RequestMethod::Get => self.handle_get_request(client, request).await,
@@ -18,136 +89,364 @@ impl RequestStrategy for HttpResponse {
             RequestMethod::Delete => self.handle_delete_request(client, request).await,
         }
     }
-}
 
-impl HttpResponse {
+    /// encodes the JSON body as the protobuf message of the request, if it
+    /// has one
+    fn encode_body(&self, request: &Request) -> anyhow::Result<Option<Vec<u8>>> {
+        let Some(protobuf) = request.protobuf.as_ref() else {
+            return Ok(None);
+        };
+        if protobuf.request.is_none() && protobuf.response.is_none() {
+            return Ok(None);
+        }
+        let Some(schema) = self.protobuf.as_ref() else {
+            anyhow::bail!("the collection has no .proto files describing the messages");
+        };
+        let Some(message) = protobuf.request.as_deref() else {
+            return Ok(None);
+        };
+
+        let body = request.body.as_deref().unwrap_or_default();
+        let json = match body.trim().is_empty() {
+            true => serde_json::Value::Object(Default::default()),
+            false => serde_json::from_str(body)
+                .map_err(|e| anyhow::anyhow!("the body is not valid JSON: {e}"))?,
+        };
+        let bytes = schema
+            .encode(message, &json)
+            .map_err(|e| anyhow::anyhow!("failed to encode the body as {message}: {e}"))?;
+        Ok(Some(bytes))
+    }
+
+    async fn decode(
+        &self,
+        mut response: reqwest::Response,
+        request: &Request,
+        now: Instant,
+    ) -> Response {
+        let download_rate = request.network.as_ref().and_then(|n| n.download_rate);
+        if let Some(rate) = download_rate {
+            let streamed = is_stream(response.headers());
+            response = network_conditions::throttle_response(response, rate, streamed).await;
+        }
+        let message = request
+            .protobuf
+            .as_ref()
+            .and_then(|protobuf| protobuf.response.clone());
+        match (self.protobuf.clone(), message) {
+            (Some(schema), Some(message)) => {
+                ProtobufDecoder { schema, message }
+                    .decode(response, now)
+                    .await
+            }
+            _ => {
+                let decoder = decoder_from_headers(response.headers(), self.body_limit.as_ref());
+                match is_stream(response.headers()) && !matches!(decoder, Decoder::Xml(_)) {
+                    true => {
+                        let progress_tx = self.progress_tx.clone();
+                        StreamDecoder { progress_tx }.decode(response, now).await
+                    }
+                    false => decoder.decode(response, now).await,
+                }
+            }
+        }
+    }
+
+    /// sends the request with the tokens of the handshake, answering every
+    /// challenge the server sends until it accepts or rejects the request.
+    /// the same client is used so the connection, which NTLM authenticates,
+    /// is reused between legs, which requires reading the body of every
+    /// challenge as the connection is only pooled again after that
+    async fn handle_handshake(
+        &self,
+        client: RequestClient,
+        request: Request,
+        mut handshake: Handshake,
+    ) -> Response {
+        let now = std::time::Instant::now();
+        let mut authorization = match handshake.initial() {
+            Ok(authorization) => authorization,
+            Err(e) => return Response::from_error(e.to_string()),
+        };
+
+        for leg in 1..=MAX_HANDSHAKE_LEGS {
+            let mut leg_request = request.clone();
+            leg_request
+                .headers
+                .get_or_insert_with(Vec::new)
+                .push(HeaderMap {
+                    pair: ("Authorization".into(), authorization),
+                    enabled: true,
+                });
+            let response = match builder(&client, &leg_request).send().await {
+                Ok(response) => response,
+                Err(e) => {
+                    let mut response = Response::from_error(describe(&e));
+                    response.duration = now.elapsed();
+                    return response;
+                }
+            };
+            if response.status() != reqwest::StatusCode::UNAUTHORIZED || leg == MAX_HANDSHAKE_LEGS {
+                return self.decode(response, &request, now).await;
+            }
+
+            authorization = match handshake.respond(response.headers()) {
+                Ok(Some(authorization)) => authorization,
+                Ok(None) => return self.decode(response, &request, now).await,
+                Err(e) => return Response::from_error(e.to_string()),
+            };
+            _ = response.bytes().await;
+        }
+
+        unreachable!("the last leg always yields its response")
+    }
+
+    /// sends the request holding its body until the server accepts it, the
+    /// interim responses received along the way are kept on the response
+    async fn handle_expect_continue(&self, client: RequestClient, request: Request) -> Response {
+        let now = std::time::Instant::now();
+        let sent = match builder(&client, &request).build() {
+            Ok(built) => expect_continue::send(built, CONTINUE_TIMEOUT, &self.pins).await,
+            Err(e) => Err(e.into()),
+        };
+
+        match sent {
+            Ok((response, exchange)) => {
+                let mut response = self.decode(response, &request, now).await;
+                response.interim = exchange.interim;
+                response
+            }
+            Err(e) => {
+                let mut response = Response::from_error(e.to_string());
+                response.duration = now.elapsed();
+                response
+            }
+        }
+    }
+
     async fn handle_get_request(&self, client: RequestClient, request: Request) -> Response {
         let now = std::time::Instant::now();
         match client.get(&request).send().await {
-            Ok(response) => {
-                let decoder = decoder_from_headers(response.headers());
-                decoder.decode(response, now).await
+            Ok(response) => self.decode(response, &request, now).await,
+            Err(e) => {
+                let mut response = Response::from_error(describe(&e));
+                response.duration = now.elapsed();
+                response
             }
-            Err(e) => Response {
-                is_error: true,
-                cause: Some(e.to_string()),
-                body: None,
-                pretty_body: None,
-                body_size: None,
-                size: None,
-                headers_size: None,
-                status: None,
-                headers: None,
-                duration: now.elapsed(),
-            },
         }
     }
 
     async fn handle_post_request(&self, client: RequestClient, request: Request) -> Response {
         let now = std::time::Instant::now();
         match client
-            .post(&request)
-            .json(&request.body.unwrap_or_default())
+            .with_body(&request, client.post(&request))
             .send()
             .await
         {
-            Ok(response) => {
-                let decoder = decoder_from_headers(response.headers());
-                decoder.decode(response, now).await
+            Ok(response) => self.decode(response, &request, now).await,
+            Err(e) => {
+                let mut response = Response::from_error(describe(&e));
+                response.duration = now.elapsed();
+                response
             }
-            Err(e) => Response {
-                is_error: true,
-                cause: Some(e.to_string()),
-                body: None,
-                pretty_body: None,
-                body_size: None,
-                size: None,
-                headers_size: None,
-                status: None,
-                headers: None,
-                duration: now.elapsed(),
-            },
         }
     }
 
     async fn handle_put_request(&self, client: RequestClient, request: Request) -> Response {
         let now = std::time::Instant::now();
         match client
-            .put(&request)
-            .json(&request.body.unwrap_or_default())
+            .with_body(&request, client.put(&request))
             .send()
             .await
         {
-            Ok(response) => {
-                let decoder = decoder_from_headers(response.headers());
-                decoder.decode(response, now).await
+            Ok(response) => self.decode(response, &request, now).await,
+            Err(e) => {
+                let mut response = Response::from_error(describe(&e));
+                response.duration = now.elapsed();
+                response
             }
-            Err(e) => Response {
-                is_error: true,
-                cause: Some(e.to_string()),
-                body: None,
-                pretty_body: None,
-                body_size: None,
-                size: None,
-                headers_size: None,
-                status: None,
-                headers: None,
-                duration: now.elapsed(),
-            },
         }
     }
 
     async fn handle_patch_request(&self, client: RequestClient, request: Request) -> Response {
         let now = std::time::Instant::now();
         match client
-            .patch(&request)
-            .json(&request.body.unwrap_or_default())
+            .with_body(&request, client.patch(&request))
             .send()
             .await
         {
-            Ok(response) => {
-                let decoder = decoder_from_headers(response.headers());
-                decoder.decode(response, now).await
+            Ok(response) => self.decode(response, &request, now).await,
+            Err(e) => {
+                let mut response = Response::from_error(describe(&e));
+                response.duration = now.elapsed();
+                response
             }
-            Err(e) => Response {
-                is_error: true,
-                cause: Some(e.to_string()),
-                body: None,
-                pretty_body: None,
-                body_size: None,
-                size: None,
-                headers_size: None,
-                status: None,
-                headers: None,
-                duration: now.elapsed(),
-            },
         }
     }
 
     async fn handle_delete_request(&self, client: RequestClient, request: Request) -> Response {
         let now = std::time::Instant::now();
         match client
-            .delete(&request)
-            .json(&request.body.unwrap_or_default())
+            .with_body(&request, client.delete(&request))
             .send()
             .await
         {
-            Ok(response) => {
-                let decoder = decoder_from_headers(response.headers());
-                decoder.decode(response, now).await
+            Ok(response) => self.decode(response, &request, now).await,
+            Err(e) => {
+                let mut response = Response::from_error(describe(&e));
+                response.duration = now.elapsed();
+                response
             }
-            Err(e) => Response {
-                is_error: true,
-                cause: Some(e.to_string()),
-                body: None,
-                pretty_body: None,
-                body_size: None,
-                size: None,
-                headers_size: None,
-                status: None,
-                headers: None,
-                duration: now.elapsed(),
-            },
         }
     }
 }
+
+/// the request with its headers, and body when its method sends one
+pub(crate) fn builder(client: &RequestClient, request: &Request) -> reqwest::RequestBuilder {
+    match request.method {
+        RequestMethod::Get => client.get(request),
+        RequestMethod::Post => client.with_body(request, client.post(request)),
+        RequestMethod::Put => client.with_body(request, client.put(request)),
+        RequestMethod::Patch => client.with_body(request, client.patch(request)),
+        RequestMethod::Delete => client.with_body(request, client.delete(request)),
+    }
+}
+
+fn has_header(request: &Request, name: &str) -> bool {
+    request
+        .headers
+        .iter()
+        .flatten()
+        .any(|header| header.enabled && header.pair.0.eq_ignore_ascii_case(name))
+}
+
+fn is_https(uri: &str) -> bool {
+    uri.trim_start().starts_with("https://")
+}
+
+/// the error along with what caused it, as reqwest only says the request
+/// failed while the reason, like a certificate not matching its pins, is
+/// on its sources
+fn describe(e: &reqwest::Error) -> String {
+    let mut cause = e.to_string();
+    let mut source = std::error::Error::source(e);
+    while let Some(e) = source {
+        cause.push_str(&format!(": {e}"));
+        source = e.source();
+    }
+    cause
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use base64::Engine;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    /// a NTLM challenge without target info, which is all the handshake
+    /// needs to answer it
+    fn ntlm_challenge() -> String {
+        let mut challenge = b"NTLMSSP\0".to_vec();
+        challenge.extend_from_slice(&2u32.to_le_bytes());
+        challenge.extend_from_slice(&[0; 8]);
+        challenge.extend_from_slice(&0x0008_8201u32.to_le_bytes());
+        challenge.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        challenge.extend_from_slice(&[0; 16]);
+        base64::engine::general_purpose::STANDARD.encode(challenge)
+    }
+
+    /// challenges the first request of every connection with a `401` that
+    /// has a body, like IIS does, and only accepts the request answering
+    /// the challenge when it comes on the same connection
+    async fn serve() -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (socket, _) = listener.accept().await.unwrap();
+                tokio::spawn(async move {
+                    let mut socket = BufReader::new(socket);
+                    for leg in 0.. {
+                        let mut head = String::new();
+                        while !head.ends_with("\r\n\r\n") {
+                            if socket.read_line(&mut head).await.unwrap_or_default() == 0 {
+                                return;
+                            }
+                        }
+                        let socket = socket.get_mut();
+                        if leg.gt(&0) {
+                            let ok = "HTTP/1.1 200 OK\r\ncontent-length: 2\r\n\r\nok";
+                            socket.write_all(ok.as_bytes()).await.unwrap();
+                            continue;
+                        }
+                        let head = format!(
+                            "HTTP/1.1 401 Unauthorized\r\nwww-authenticate: NTLM {}\r\ncontent-length: 12\r\n\r\n",
+                            ntlm_challenge()
+                        );
+                        socket.write_all(head.as_bytes()).await.unwrap();
+                        // the body comes after the headers were handled
+                        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                        socket.write_all(b"Unauthorized").await.unwrap();
+                    }
+                });
+            }
+        });
+        address
+    }
+
+    #[tokio::test]
+    async fn test_handshake_reuses_the_connection() {
+        let address = serve().await;
+        let request: Request = serde_json::from_value(serde_json::json!({
+            "id": "ntlm",
+            "method": RequestMethod::Get,
+            "name": "ntlm",
+            "uri": format!("http://user:password@{address}/"),
+            "auth_method": "Ntlm",
+        }))
+        .unwrap();
+        let strategy = HttpResponse {
+            client: reqwest::Client::new(),
+            protobuf: None,
+            progress_tx: None,
+            pins: HashMap::new(),
+            body_limit: None,
+        };
+
+        let response = strategy.handle(request).await;
+        assert_eq!(response.status, Some(reqwest::StatusCode::OK));
+    }
+
+    #[tokio::test]
+    async fn test_omitting_accept_is_refused() {
+        let mut request: Request = serde_json::from_value(serde_json::json!({
+            "id": "accept",
+            "method": RequestMethod::Get,
+            "name": "accept",
+            "uri": "http://localhost/",
+            "omitHeaders": ["Accept", "Accept-Encoding"],
+        }))
+        .unwrap();
+        let strategy = HttpResponse {
+            client: reqwest::Client::new(),
+            protobuf: None,
+            progress_tx: None,
+            pins: HashMap::new(),
+            body_limit: None,
+        };
+
+        let response = strategy.handle(request.clone()).await;
+        assert!(response.cause.is_some_and(|cause| cause.contains("Accept")));
+
+        // the request can still set the one it wants
+        request.uri = format!("http://{}/", serve().await);
+        request.headers = Some(vec![HeaderMap {
+            pair: ("Accept".into(), "application/json".into()),
+            enabled: true,
+        }]);
+        let response = strategy.handle(request).await;
+        assert!(response.status.is_some());
+    }
+}
@@ -0,0 +1,289 @@
+use crate::collection::types::{Expectation, Request, ScenarioStep};
+use crate::net::request_manager::Response;
+use crate::net::request_strategies::RequestStrategy;
+
+use std::time::{Duration, Instant};
+
+use futures_util::{Sink, SinkExt, Stream, StreamExt};
+use reqwest::header::{HeaderName, HeaderValue};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::{Error, Message};
+
+/// how long an expected message is awaited when the step doesn't say
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// opens a WebSocket connection and runs the scenario of the request on it.
+/// The body of the response is a transcript of the messages exchanged, and
+/// the response is an error when a step fails
+#[derive(Clone)]
+pub struct WebSocketStrategy;
+
+impl RequestStrategy for WebSocketStrategy {
+    async fn handle(&self, request: Request) -> Response {
+        let now = Instant::now();
+        let mut handshake = match request.uri.trim().into_client_request() {
+            Ok(handshake) => handshake,
+            Err(e) => return Response::from_error(e.to_string()),
+        };
+        for header in request.headers.iter().flatten().filter(|h| h.enabled) {
+            let name = HeaderName::from_bytes(header.pair.0.as_bytes());
+            let value = HeaderValue::from_str(&header.pair.1);
+            if let (Ok(name), Ok(value)) = (name, value) {
+                handshake.headers_mut().insert(name, value);
+            }
+        }
+
+        let (mut stream, handshake) = match tokio_tungstenite::connect_async(handshake).await {
+            Ok(connection) => connection,
+            Err(e) => return Response::from_error(e.to_string()),
+        };
+        let mut transcript = vec![];
+        let failure = run_scenario(&mut stream, &request.scenario, &mut transcript).await;
+        _ = stream.close(None).await;
+
+        Response {
+            headers: Some(handshake.headers().clone()),
+            duration: now.elapsed(),
+            status: Some(handshake.status()),
+            is_error: failure.is_some(),
+            cause: failure,
+            ..Response::from_text(transcript.join("\n"))
+        }
+    }
+}
+
+/// runs the steps in order, writing every message sent and received to the
+/// transcript, and stops at the first step that fails, returning why
+async fn run_scenario<S>(
+    stream: &mut S,
+    steps: &[ScenarioStep],
+    transcript: &mut Vec<String>,
+) -> Option<String>
+where
+    S: Sink<Message, Error = Error> + Stream<Item = Result<Message, Error>> + Unpin,
+{
+    for (idx, step) in steps.iter().enumerate() {
+        let result = match step {
+            ScenarioStep::Send(text) => {
+                transcript.push(format!("-> {text}"));
+                stream
+                    .send(Message::text(text.as_str()))
+                    .await
+                    .map_err(|e| format!("failed to send: {e}"))
+            }
+            ScenarioStep::Expect(expectation) => expect(stream, expectation, transcript).await,
+        };
+
+        if let Err(reason) = result {
+            let failure = format!("step {}: {reason}", idx + 1);
+            transcript.push(format!("failed {failure}"));
+            return Some(failure);
+        }
+    }
+
+    transcript.push(format!("passed {} steps", steps.len()));
+    None
+}
+
+/// waits for a message that matches the expectation, skipping the ones
+/// that don't
+async fn expect<S>(
+    stream: &mut S,
+    expectation: &Expectation,
+    transcript: &mut Vec<String>,
+) -> Result<(), String>
+where
+    S: Stream<Item = Result<Message, Error>> + Unpin,
+{
+    let timeout = expectation
+        .timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_TIMEOUT);
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    loop {
+        let message = match tokio::time::timeout_at(deadline, stream.next()).await {
+            Ok(None) | Ok(Some(Ok(Message::Close(_)))) => {
+                return Err(format!(
+                    "the connection closed before a message {}",
+                    describe(expectation)
+                ))
+            }
+            Ok(Some(Ok(message))) => message,
+            Ok(Some(Err(e))) => return Err(e.to_string()),
+            Err(_) => {
+                return Err(format!(
+                    "no message {} within {}ms",
+                    describe(expectation),
+                    timeout.as_millis()
+                ))
+            }
+        };
+
+        let text = match message {
+            Message::Text(text) => text.to_string(),
+            Message::Binary(bytes) => String::from_utf8_lossy(&bytes).to_string(),
+            _ => continue,
+        };
+        transcript.push(format!("<- {text}"));
+        if matches(expectation, &text) {
+            return Ok(());
+        }
+    }
+}
+
+/// whether the message passes every matcher of the expectation
+pub fn matches(expectation: &Expectation, message: &str) -> bool {
+    if expectation
+        .equals
+        .as_ref()
+        .is_some_and(|equals| equals.ne(message))
+    {
+        return false;
+    }
+    if expectation
+        .contains
+        .as_ref()
+        .is_some_and(|contains| !message.contains(contains.as_str()))
+    {
+        return false;
+    }
+
+    let Some(pointer) = expectation.pointer.as_ref() else {
+        return true;
+    };
+    let json = serde_json::from_str::<serde_json::Value>(message).ok();
+    let Some(value) = json.as_ref().and_then(|json| json.pointer(pointer)) else {
+        return false;
+    };
+    match expectation.value.as_ref() {
+        Some(expected) => match value {
+            serde_json::Value::String(value) => value.eq(expected),
+            value => value.to_string().eq(expected),
+        },
+        None => true,
+    }
+}
+
+/// the expectation in words, like `containing "ack"`
+fn describe(expectation: &Expectation) -> String {
+    let mut parts = vec![];
+    if let Some(equals) = expectation.equals.as_ref() {
+        parts.push(format!("equal to {equals:?}"));
+    }
+    if let Some(contains) = expectation.contains.as_ref() {
+        parts.push(format!("containing {contains:?}"));
+    }
+    match (expectation.pointer.as_ref(), expectation.value.as_ref()) {
+        (Some(pointer), Some(value)) => parts.push(format!("with {pointer} = {value:?}")),
+        (Some(pointer), None) => parts.push(format!("with {pointer}")),
+        _ => {}
+    }
+
+    match parts.is_empty() {
+        true => "of any kind".into(),
+        false => parts.join(" and "),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        let message = r#"{"type":"ack","id":7}"#;
+        let expect = |expectation: Expectation| matches(&expectation, message);
+
+        assert!(expect(Expectation::default()));
+        assert!(expect(Expectation {
+            contains: Some("ack".into()),
+            ..Default::default()
+        }));
+        assert!(!expect(Expectation {
+            equals: Some("ack".into()),
+            ..Default::default()
+        }));
+        assert!(expect(Expectation {
+            pointer: Some("/type".into()),
+            value: Some("ack".into()),
+            ..Default::default()
+        }));
+        assert!(expect(Expectation {
+            pointer: Some("/id".into()),
+            value: Some("7".into()),
+            contains: Some("type".into()),
+            ..Default::default()
+        }));
+        assert!(!expect(Expectation {
+            pointer: Some("/missing".into()),
+            ..Default::default()
+        }));
+        assert!(!matches(
+            &Expectation {
+                pointer: Some("/type".into()),
+                ..Default::default()
+            },
+            "not json"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_run_scenario() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut stream = tokio_tungstenite::accept_async(socket).await.unwrap();
+            stream
+                .send(Message::text(r#"{"type":"hello"}"#))
+                .await
+                .unwrap();
+            while let Some(Ok(Message::Text(text))) = stream.next().await {
+                stream
+                    .send(Message::text(format!(
+                        r#"{{"type":"echo","text":{:?}}}"#,
+                        text.as_str()
+                    )))
+                    .await
+                    .unwrap();
+            }
+        });
+
+        let (mut stream, _) = tokio_tungstenite::connect_async(format!("ws://{address}"))
+            .await
+            .unwrap();
+        let echo = |text: &str| {
+            ScenarioStep::Expect(Expectation {
+                pointer: Some("/text".into()),
+                value: Some(text.into()),
+                timeout_ms: Some(1000),
+                ..Default::default()
+            })
+        };
+        let steps = vec![
+            ScenarioStep::Send("ping".into()),
+            echo("ping"),
+            ScenarioStep::Send("pong".into()),
+            echo("missing"),
+        ];
+
+        let mut transcript = vec![];
+        let failure = run_scenario(&mut stream, &steps, &mut transcript).await;
+        assert_eq!(
+            failure.as_deref(),
+            Some(r#"step 4: no message with /text = "missing" within 1000ms"#)
+        );
+        assert_eq!(
+            transcript,
+            [
+                "-> ping",
+                r#"<- {"type":"hello"}"#,
+                r#"<- {"type":"echo","text":"ping"}"#,
+                "-> pong",
+                r#"<- {"type":"echo","text":"pong"}"#,
+                r#"failed step 4: no message with /text = "missing" within 1000ms"#,
+            ]
+        );
+    }
+}
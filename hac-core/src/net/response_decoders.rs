@@ -1,7 +1,18 @@
+mod binary_decoder;
 mod json_decoder;
+mod protobuf_decoder;
+mod stream_decoder;
+mod xml_decoder;
 
+use crate::net::body_limit::BodyLimit;
+use crate::net::mime::MimeRegistry;
 use crate::net::request_manager::{ContentType, Response};
+use crate::net::response_decoders::binary_decoder::BinaryDecoder;
 use crate::net::response_decoders::json_decoder::JsonDecoder;
+use crate::net::response_decoders::xml_decoder::XmlDecoder;
+
+pub use protobuf_decoder::ProtobufDecoder;
+pub use stream_decoder::{is_stream, ChunkLog, StreamDecoder};
 
 use std::future::Future;
 use std::time::Instant;
@@ -16,12 +27,36 @@ pub trait ResponseDecoder {
     ) -> impl Future<Output = Response> + Send;
 }
 
-pub fn decoder_from_headers(headers: &HeaderMap) -> impl ResponseDecoder {
+/// the decoder for the content type of a response
+pub enum Decoder {
+    Json(JsonDecoder),
+    Xml(XmlDecoder),
+    Binary(BinaryDecoder),
+}
+
+impl ResponseDecoder for Decoder {
+    async fn decode(&self, response: reqwest::Response, start: Instant) -> Response {
+        match self {
+            Decoder::Json(decoder) => decoder.decode(response, start).await,
+            Decoder::Xml(decoder) => decoder.decode(response, start).await,
+            Decoder::Binary(decoder) => decoder.decode(response, start).await,
+        }
+    }
+}
+
+/// the decoder for the content type of a response, bodies decoded as text
+/// are kept in memory and pretty printed up to the limit
+pub fn decoder_from_headers(headers: &HeaderMap, limit: Option<&BodyLimit>) -> Decoder {
+    if MimeRegistry::default().for_headers(headers).binary {
+        return Decoder::Binary(BinaryDecoder);
+    }
+    let limit = limit.cloned();
     match headers.get("Content-Type") {
         Some(header) => match ContentType::from(header.to_str().unwrap_or_default()) {
-            ContentType::ApplicationJson => JsonDecoder,
-            _ => JsonDecoder,
+            ContentType::ApplicationJson => Decoder::Json(JsonDecoder { limit }),
+            ContentType::ApplicationXml => Decoder::Xml(XmlDecoder { limit }),
+            _ => Decoder::Json(JsonDecoder { limit }),
         },
-        None => JsonDecoder,
+        None => Decoder::Json(JsonDecoder { limit }),
     }
 }
@@ -0,0 +1,48 @@
+use crate::net::{request_manager::Response, response_decoders::ResponseDecoder};
+
+use std::{ops::Add, time::Instant};
+
+/// keeps bodies that are not text, like images and documents, as they were
+/// received, as decoding them as text would mangle them
+pub struct BinaryDecoder;
+
+impl ResponseDecoder for BinaryDecoder {
+    async fn decode(&self, response: reqwest::Response, start: Instant) -> Response {
+        let duration = start.elapsed();
+        let headers = Some(response.headers().to_owned());
+        let status = Some(response.status());
+        let headers_size: u64 = response
+            .headers()
+            .iter()
+            .map(|(k, v)| k.as_str().len().add(v.as_bytes().len()).add(4) as u64)
+            .sum();
+
+        let raw_body = response
+            .bytes()
+            .await
+            .ok()
+            .filter(|bytes| !bytes.is_empty())
+            .map(|bytes| bytes.to_vec());
+        let body_size = raw_body.as_ref().map(Vec::len).unwrap_or_default() as u64;
+
+        Response {
+            body: None,
+            pretty_body: None,
+            headers,
+            duration,
+            status,
+            size: Some(headers_size.add(body_size)),
+            headers_size: Some(headers_size),
+            body_size: Some(body_size),
+            cause: None,
+            is_error: false,
+            streaming: false,
+            interim: vec![],
+            offline: None,
+            tls: None,
+            body_file: None,
+            pretty_truncated: false,
+            raw_body,
+        }
+    }
+}
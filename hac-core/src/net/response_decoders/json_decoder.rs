@@ -1,9 +1,15 @@
+use crate::net::body_limit::{self, BodyLimit};
+use crate::net::charset::decode_body;
 use crate::net::{request_manager::Response, response_decoders::ResponseDecoder};
 use crate::text_object::TextObject;
 
 use std::{ops::Add, time::Instant};
 
-pub struct JsonDecoder;
+/// pretty prints JSON bodies, large bodies only have their start pretty
+/// printed and kept in memory as given by the limit
+pub struct JsonDecoder {
+    pub limit: Option<BodyLimit>,
+}
 
 impl ResponseDecoder for JsonDecoder {
     async fn decode(&self, response: reqwest::Response, start: Instant) -> Response {
@@ -18,16 +24,27 @@ impl ResponseDecoder for JsonDecoder {
 
         let mut body: Option<String> = None;
         let mut pretty_body = None;
+        let mut body_size = 0;
+        let mut body_file = None;
+        let mut pretty_truncated = false;
 
         if response.content_length().is_some_and(|len| len.gt(&0)) {
-            if let Ok(body_str) = response.text().await {
-                let pretty_body_str = jsonxf::pretty_print(&body_str).unwrap_or_default();
+            // bodies are decoded with the charset of the response, instead of
+            // assuming everything is UTF-8
+            let headers = response.headers().to_owned();
+            if let Ok(bytes) = response.bytes().await {
+                body_size = bytes.len() as u64;
+                let body_str = decode_body(&bytes, &headers);
+                let (body_str, spilled_to) = body_limit::spill(self.limit.as_ref(), body_str).await;
+                let shown = body_limit::shown(self.limit.as_ref(), &body_str);
+                let pretty_body_str = jsonxf::pretty_print(shown).unwrap_or_default();
                 pretty_body = Some(TextObject::from(&pretty_body_str));
+                pretty_truncated = shown.len().lt(&body_str.len());
+                body_file = spilled_to;
                 body = Some(body_str);
             };
         }
 
-        let body_size = body.as_ref().map(|body| body.len()).unwrap_or_default() as u64;
         let size = headers_size.add(body_size);
 
         Response {
@@ -41,6 +58,13 @@ impl ResponseDecoder for JsonDecoder {
             body_size: Some(body_size),
             cause: None,
             is_error: false,
+            streaming: false,
+            interim: vec![],
+            offline: None,
+            tls: None,
+            body_file,
+            pretty_truncated,
+            raw_body: None,
         }
     }
 }
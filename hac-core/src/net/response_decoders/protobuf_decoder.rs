@@ -0,0 +1,72 @@
+use crate::net::{request_manager::Response, response_decoders::ResponseDecoder};
+use crate::protobuf::Schema;
+use crate::text_object::TextObject;
+
+use std::sync::Arc;
+use std::{ops::Add, time::Instant};
+
+/// decodes bodies encoded as a protobuf message into JSON, so they can be
+/// displayed like any other response
+pub struct ProtobufDecoder {
+    pub schema: Arc<Schema>,
+    pub message: String,
+}
+
+impl ResponseDecoder for ProtobufDecoder {
+    async fn decode(&self, response: reqwest::Response, start: Instant) -> Response {
+        let duration = start.elapsed();
+        let headers = Some(response.headers().to_owned());
+        let status = Some(response.status());
+        let headers_size: u64 = response
+            .headers()
+            .iter()
+            .map(|(k, v)| k.as_str().len().add(v.as_bytes().len()).add(4) as u64)
+            .sum();
+
+        let mut body: Option<String> = None;
+        let mut pretty_body = None;
+        let mut body_size = 0;
+        let mut cause = None;
+
+        if let Ok(bytes) = response.bytes().await {
+            body_size = bytes.len() as u64;
+            match self.schema.decode(&self.message, &bytes) {
+                Ok(json) => {
+                    let pretty = serde_json::to_string_pretty(&json).unwrap_or_default();
+                    pretty_body = Some(TextObject::from(&pretty));
+                    body = Some(json.to_string());
+                }
+                Err(e) => {
+                    // the raw body is still shown, as it may tell why it
+                    // isn't the expected message, like an error page
+                    let raw = String::from_utf8_lossy(&bytes).to_string();
+                    pretty_body = Some(TextObject::from(&raw));
+                    body = Some(raw);
+                    cause = Some(format!("failed to decode {}: {e}", self.message));
+                }
+            }
+        }
+
+        let size = headers_size.add(body_size);
+
+        Response {
+            body,
+            pretty_body,
+            headers,
+            duration,
+            status,
+            size: Some(size),
+            headers_size: Some(headers_size),
+            body_size: Some(body_size),
+            is_error: cause.is_some(),
+            streaming: false,
+            interim: vec![],
+            offline: None,
+            tls: None,
+            body_file: None,
+            pretty_truncated: false,
+            raw_body: None,
+            cause,
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use crate::net::charset::decode_body;
+use crate::net::{request_manager::Response, response_decoders::ResponseDecoder};
+use crate::text_object::TextObject;
+
+use std::{ops::Add, time::Instant};
+
+use chrono::{DateTime, Local};
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// content types of bodies made of one JSON document per line
+const LINE_DELIMITED: &[&str] = &[
+    "application/x-ndjson",
+    "application/ndjson",
+    "application/jsonl",
+    "application/x-jsonlines",
+    "application/json-seq",
+    "application/stream+json",
+    "text/event-stream",
+];
+
+/// whether the body of the response arrives over time, either because it is
+/// line delimited JSON or because it is sent in chunks without a length
+pub fn is_stream(headers: &HeaderMap) -> bool {
+    let chunked = headers
+        .get("Transfer-Encoding")
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.to_ascii_lowercase().contains("chunked"));
+    chunked || is_line_delimited(headers)
+}
+
+fn is_line_delimited(headers: &HeaderMap) -> bool {
+    headers
+        .get("Content-Type")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_ascii_lowercase)
+        .is_some_and(|content_type| {
+            LINE_DELIMITED
+                .iter()
+                .any(|kind| content_type.contains(kind))
+        })
+}
+
+/// reads the body chunk by chunk, sending a response with everything that
+/// arrived so far through `progress_tx` after each one, so it is shown
+/// before the connection closes. Every chunk, or line of line delimited
+/// bodies, is shown with the time it arrived
+pub struct StreamDecoder {
+    pub progress_tx: Option<UnboundedSender<Response>>,
+}
+
+impl ResponseDecoder for StreamDecoder {
+    async fn decode(&self, mut response: reqwest::Response, start: Instant) -> Response {
+        let headers = response.headers().to_owned();
+        let progress = Progress {
+            status: response.status(),
+            headers_size: headers
+                .iter()
+                .map(|(k, v)| k.as_str().len().add(v.as_bytes().len()).add(4) as u64)
+                .sum(),
+            line_delimited: is_line_delimited(&headers),
+            headers,
+            start,
+        };
+
+        let mut log = ChunkLog::new(progress.line_delimited);
+        let mut bytes = vec![];
+        let cause = loop {
+            match response.chunk().await {
+                Ok(Some(chunk)) => {
+                    bytes.extend_from_slice(&chunk);
+                    log.push(Local::now(), &chunk);
+                    if let Some(progress_tx) = self.progress_tx.as_ref() {
+                        _ = progress_tx.send(progress.partial(&bytes, &log));
+                    }
+                }
+                Ok(None) => break None,
+                Err(e) => break Some(e.to_string()),
+            }
+        };
+        log.finish();
+
+        progress.complete(&bytes, &log, cause)
+    }
+}
+
+struct Progress {
+    headers: HeaderMap,
+    status: StatusCode,
+    headers_size: u64,
+    line_delimited: bool,
+    start: Instant,
+}
+
+impl Progress {
+    fn partial(&self, bytes: &[u8], log: &ChunkLog) -> Response {
+        let mut response = self.response(decode_body(bytes, &self.headers), log.render());
+        response.streaming = true;
+        response
+    }
+
+    /// once every chunk arrived, bodies that are a single JSON document are
+    /// pretty printed like any other, the rest keep showing their chunks
+    fn complete(&self, bytes: &[u8], log: &ChunkLog, cause: Option<String>) -> Response {
+        let body = decode_body(bytes, &self.headers);
+        let pretty_body = match self.line_delimited {
+            false if serde_json::from_str::<serde_json::Value>(&body).is_ok() => {
+                jsonxf::pretty_print(&body).unwrap_or_else(|_| log.render())
+            }
+            _ => log.render(),
+        };
+        let mut response = self.response(body, pretty_body);
+        response.is_error = cause.is_some();
+        response.cause = cause;
+        response
+    }
+
+    fn response(&self, body: String, pretty_body: String) -> Response {
+        let body_size = body.len() as u64;
+        Response {
+            pretty_body: (!pretty_body.is_empty()).then(|| TextObject::from(&pretty_body)),
+            body: (!body.is_empty()).then_some(body),
+            headers: Some(self.headers.clone()),
+            duration: self.start.elapsed(),
+            status: Some(self.status),
+            size: Some(self.headers_size.add(body_size)),
+            headers_size: Some(self.headers_size),
+            body_size: Some(body_size),
+            cause: None,
+            is_error: false,
+            streaming: false,
+            interim: vec![],
+            offline: None,
+            tls: None,
+            body_file: None,
+            pretty_truncated: false,
+            raw_body: None,
+        }
+    }
+}
+
+/// the chunks of a body along with when they arrived. Line delimited bodies
+/// are split into lines instead, as chunks rarely end where a line does
+#[derive(Debug, Default)]
+pub struct ChunkLog {
+    by_line: bool,
+    entries: Vec<(DateTime<Local>, String)>,
+    /// the start of a line that didn't end yet, and when it started arriving
+    partial: Option<(DateTime<Local>, String)>,
+    /// bytes of a character split between chunks
+    pending: Vec<u8>,
+}
+
+impl ChunkLog {
+    pub fn new(by_line: bool) -> Self {
+        ChunkLog {
+            by_line,
+            ..Default::default()
+        }
+    }
+
+    pub fn push(&mut self, at: DateTime<Local>, chunk: &[u8]) {
+        self.pending.extend_from_slice(chunk);
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            // the character may be completed by the next chunk
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => self.pending.len(),
+        };
+        let text = String::from_utf8_lossy(&self.pending[..valid]).to_string();
+        self.pending.drain(..valid);
+
+        if !self.by_line {
+            let text = text.trim_end_matches(['\r', '\n']);
+            if !text.trim().is_empty() {
+                self.entries.push((at, text.to_string()));
+            }
+            return;
+        }
+
+        let (started, mut line) = self.partial.take().unwrap_or((at, String::new()));
+        line.push_str(&text);
+        let mut lines = line.split('\n').collect::<Vec<_>>();
+        let unfinished = lines.pop().unwrap_or_default();
+        for (idx, line) in lines.into_iter().enumerate() {
+            let line = line.trim_end_matches('\r');
+            if !line.trim().is_empty() {
+                let arrived = if idx == 0 { started } else { at };
+                self.entries.push((arrived, line.to_string()));
+            }
+        }
+        if !unfinished.is_empty() {
+            let started = if line.contains('\n') { at } else { started };
+            self.partial = Some((started, unfinished.to_string()));
+        }
+    }
+
+    /// keeps whatever is left once the body is over, even if it doesn't end
+    /// with a line break
+    pub fn finish(&mut self) {
+        if let Some((at, line)) = self.partial.take() {
+            if !line.trim().is_empty() {
+                self.entries.push((at, line));
+            }
+        }
+        if !self.pending.is_empty() {
+            let text = String::from_utf8_lossy(&self.pending).to_string();
+            self.entries.push((Local::now(), text));
+            self.pending.clear();
+        }
+    }
+
+    /// every entry on its own line, prefixed by the time it arrived
+    pub fn render(&self) -> String {
+        self.entries
+            .iter()
+            .chain(self.partial.as_ref())
+            .map(|(at, text)| format!("[{}] {text}", at.format("%H:%M:%S%.3f")))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn at(time: &str) -> DateTime<Local> {
+        chrono::NaiveDateTime::parse_from_str(
+            &format!("2024-01-01 {time}"),
+            "%Y-%m-%d %H:%M:%S%.3f",
+        )
+        .unwrap()
+        .and_local_timezone(Local)
+        .unwrap()
+    }
+
+    #[test]
+    fn test_chunk_log() {
+        let mut log = ChunkLog::new(true);
+        log.push(at("12:00:01.000"), b"{\"n\":1}\n{\"n\":");
+        assert_eq!(
+            log.render(),
+            "[12:00:01.000] {\"n\":1}\n[12:00:01.000] {\"n\":"
+        );
+        log.push(at("12:00:02.500"), b"2}\r\n\n{\"name\":\"jo\xc3");
+        log.push(at("12:00:03.000"), b"\xa3o\"}");
+        log.finish();
+        assert_eq!(
+            log.render(),
+            [
+                "[12:00:01.000] {\"n\":1}",
+                "[12:00:01.000] {\"n\":2}",
+                "[12:00:02.500] {\"name\":\"joão\"}",
+            ]
+            .join("\n")
+        );
+
+        let mut log = ChunkLog::new(false);
+        log.push(at("12:00:01.000"), b"{\"items\": [");
+        log.push(at("12:00:01.200"), b"\n");
+        log.push(at("12:00:01.400"), b"1, 2]}\n");
+        assert_eq!(
+            log.render(),
+            "[12:00:01.000] {\"items\": [\n[12:00:01.400] 1, 2]}"
+        );
+    }
+
+    #[test]
+    fn test_is_stream() {
+        let headers = |pairs: &[(&'static str, &'static str)]| {
+            pairs
+                .iter()
+                .map(|(name, value)| (name.parse().unwrap(), HeaderValue::from_static(value)))
+                .collect::<HeaderMap>()
+        };
+
+        assert!(is_stream(&headers(&[(
+            "Content-Type",
+            "application/x-ndjson; charset=utf-8"
+        )])));
+        assert!(is_stream(&headers(&[
+            ("Content-Type", "application/json"),
+            ("Transfer-Encoding", "chunked"),
+        ])));
+        assert!(!is_stream(&headers(&[
+            ("Content-Type", "application/json"),
+            ("Content-Length", "12"),
+        ])));
+        assert!(!is_line_delimited(&headers(&[(
+            "Transfer-Encoding",
+            "chunked"
+        )])));
+    }
+}
@@ -0,0 +1,75 @@
+use crate::net::body_limit::{self, BodyLimit};
+use crate::net::charset::decode_body;
+use crate::net::{request_manager::Response, response_decoders::ResponseDecoder};
+use crate::syntax::xml;
+use crate::text_object::TextObject;
+
+use std::{ops::Add, time::Instant};
+
+/// pretty prints XML bodies, like the ones of SOAP services, falling back to
+/// the body as is when it is not valid XML. Large bodies only have their
+/// start pretty printed and kept in memory as given by the limit
+pub struct XmlDecoder {
+    pub limit: Option<BodyLimit>,
+}
+
+impl ResponseDecoder for XmlDecoder {
+    async fn decode(&self, response: reqwest::Response, start: Instant) -> Response {
+        let duration = start.elapsed();
+        let headers = Some(response.headers().to_owned());
+        let status = Some(response.status());
+        let headers_size: u64 = response
+            .headers()
+            .iter()
+            .map(|(k, v)| k.as_str().len().add(v.as_bytes().len()).add(4) as u64)
+            .sum();
+
+        let mut body: Option<String> = None;
+        let mut pretty_body = None;
+        let mut body_size = 0;
+        let mut body_file = None;
+        let mut pretty_truncated = false;
+
+        // SOAP services commonly stream their responses, so the body is read
+        // even without a `Content-Length`
+        let response_headers = response.headers().to_owned();
+        if let Some(bytes) = response
+            .bytes()
+            .await
+            .ok()
+            .filter(|bytes| !bytes.is_empty())
+        {
+            body_size = bytes.len() as u64;
+            let body_str = decode_body(&bytes, &response_headers);
+            let (body_str, spilled_to) = body_limit::spill(self.limit.as_ref(), body_str).await;
+            let shown = body_limit::shown(self.limit.as_ref(), &body_str);
+            let pretty_body_str = xml::pretty_print(shown).unwrap_or_else(|| shown.to_string());
+            pretty_body = Some(TextObject::from(&pretty_body_str));
+            pretty_truncated = shown.len().lt(&body_str.len());
+            body_file = spilled_to;
+            body = Some(body_str);
+        }
+
+        let size = headers_size.add(body_size);
+
+        Response {
+            body,
+            pretty_body,
+            headers,
+            duration,
+            status,
+            size: Some(size),
+            headers_size: Some(headers_size),
+            body_size: Some(body_size),
+            cause: None,
+            is_error: false,
+            streaming: false,
+            interim: vec![],
+            offline: None,
+            tls: None,
+            body_file,
+            pretty_truncated,
+            raw_body: None,
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use crate::collection::types::{BodyType, Request, SoapVersion};
+
+const SOAP_11_CONTENT_TYPE: &str = "text/xml; charset=utf-8";
+const SOAP_12_CONTENT_TYPE: &str = "application/soap+xml; charset=utf-8";
+
+/// headers a SOAP request needs that weren't set on the request itself. SOAP
+/// 1.1 carries the action on the `SOAPAction` header, while SOAP 1.2 carries
+/// it on the content type
+pub fn missing_headers(request: &Request) -> Vec<(String, String)> {
+    let Some(operation) = request.soap.as_ref() else {
+        return vec![];
+    };
+    let has_header = |name: &str| {
+        request
+            .headers
+            .iter()
+            .flatten()
+            .any(|header| header.enabled && header.pair.0.eq_ignore_ascii_case(name))
+    };
+
+    let mut headers = vec![];
+    match operation.version {
+        SoapVersion::V11 => {
+            if !has_header("content-type") {
+                headers.push(("Content-Type".into(), SOAP_11_CONTENT_TYPE.into()));
+            }
+            if !has_header("soapaction") {
+                headers.push(("SOAPAction".into(), format!("\"{}\"", operation.action)));
+            }
+        }
+        SoapVersion::V12 if !has_header("content-type") => {
+            let content_type = match operation.action.is_empty() {
+                true => SOAP_12_CONTENT_TYPE.to_string(),
+                false => format!("{SOAP_12_CONTENT_TYPE}; action=\"{}\"", operation.action),
+            };
+            headers.push(("Content-Type".into(), content_type));
+        }
+        SoapVersion::V12 => {}
+    }
+
+    headers
+}
+
+/// whether the body is sent as written rather than as JSON
+pub fn is_raw_body(request: &Request) -> bool {
+    request.soap.is_some() || request.body_type.eq(&Some(BodyType::Xml))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collection::types::{HeaderMap, RequestMethod, SoapOperation};
+
+    #[test]
+    fn test_missing_headers() {
+        let mut request = Request {
+            id: "id".into(),
+            method: RequestMethod::Post,
+            name: "GetPrice".into(),
+            uri: "http://localhost/shop".into(),
+            body_type: Some(BodyType::Xml),
+            soap: Some(SoapOperation {
+                action: "urn:GetPrice".into(),
+                version: SoapVersion::V11,
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            missing_headers(&request),
+            vec![
+                ("Content-Type".into(), SOAP_11_CONTENT_TYPE.into()),
+                ("SOAPAction".into(), "\"urn:GetPrice\"".into()),
+            ]
+        );
+
+        request.headers = Some(vec![HeaderMap {
+            pair: ("soapaction".into(), "custom".into()),
+            enabled: true,
+        }]);
+        request.soap.as_mut().unwrap().version = SoapVersion::V12;
+        assert_eq!(
+            missing_headers(&request),
+            vec![(
+                "Content-Type".into(),
+                "application/soap+xml; charset=utf-8; action=\"urn:GetPrice\"".into()
+            )]
+        );
+
+        request.soap = None;
+        assert!(missing_headers(&request).is_empty());
+        assert!(is_raw_body(&request));
+    }
+}
@@ -0,0 +1,293 @@
+/// what a status code means, what usually causes it and which headers are
+/// worth checking when you get it
+#[derive(Debug, PartialEq)]
+pub struct StatusInfo {
+    pub code: u16,
+    pub name: &'static str,
+    pub meaning: &'static str,
+    pub causes: &'static [&'static str],
+    pub headers: &'static [&'static str],
+}
+
+impl StatusInfo {
+    const fn new(
+        code: u16,
+        name: &'static str,
+        meaning: &'static str,
+        causes: &'static [&'static str],
+        headers: &'static [&'static str],
+    ) -> Self {
+        StatusInfo {
+            code,
+            name,
+            meaning,
+            causes,
+            headers,
+        }
+    }
+}
+
+pub static STATUS_CODES: &[StatusInfo] = &[
+    StatusInfo::new(200, "OK", "The request succeeded.", &[], &["Content-Type"]),
+    StatusInfo::new(
+        201,
+        "Created",
+        "The request succeeded and a new resource was created.",
+        &[],
+        &["Location"],
+    ),
+    StatusInfo::new(
+        202,
+        "Accepted",
+        "The request was accepted but it is still being processed, possibly asynchronously.",
+        &["the server queued the work instead of doing it right away"],
+        &["Location", "Retry-After"],
+    ),
+    StatusInfo::new(
+        204,
+        "No Content",
+        "The request succeeded and there is no body to send back.",
+        &["updates and deletes commonly return no body"],
+        &[],
+    ),
+    StatusInfo::new(
+        301,
+        "Moved Permanently",
+        "The resource was permanently moved to another URI.",
+        &["the endpoint was renamed", "http being redirected to https"],
+        &["Location"],
+    ),
+    StatusInfo::new(
+        302,
+        "Found",
+        "The resource temporarily lives at another URI.",
+        &["login redirects", "load balancers redirecting traffic"],
+        &["Location"],
+    ),
+    StatusInfo::new(
+        304,
+        "Not Modified",
+        "The cached version of the resource is still valid.",
+        &["the request sent `If-None-Match` or `If-Modified-Since` and nothing changed"],
+        &["ETag", "Last-Modified", "Cache-Control"],
+    ),
+    StatusInfo::new(
+        307,
+        "Temporary Redirect",
+        "The resource temporarily lives at another URI, the method and body must not change.",
+        &["maintenance or traffic being shifted between servers"],
+        &["Location"],
+    ),
+    StatusInfo::new(
+        308,
+        "Permanent Redirect",
+        "The resource was permanently moved, the method and body must not change.",
+        &["the endpoint was renamed"],
+        &["Location"],
+    ),
+    StatusInfo::new(
+        400,
+        "Bad Request",
+        "The server could not understand the request.",
+        &[
+            "malformed JSON body",
+            "missing or invalid query parameters",
+            "a `Content-Type` that doesn't match the body",
+        ],
+        &["Content-Type", "Content-Length"],
+    ),
+    StatusInfo::new(
+        401,
+        "Unauthorized",
+        "The request lacks valid authentication credentials.",
+        &[
+            "missing or expired token",
+            "wrong authentication scheme, eg: `Basic` instead of `Bearer`",
+        ],
+        &["WWW-Authenticate", "Authorization"],
+    ),
+    StatusInfo::new(
+        403,
+        "Forbidden",
+        "The server understood who you are, but you are not allowed to do this.",
+        &[
+            "the token is valid but lacks the required scopes",
+            "IP allowlists or CORS rules",
+        ],
+        &["Authorization"],
+    ),
+    StatusInfo::new(
+        404,
+        "Not Found",
+        "The server could not find the requested resource.",
+        &[
+            "a typo on the URI",
+            "the resource id doesn't exist",
+            "missing or extra trailing slash",
+        ],
+        &[],
+    ),
+    StatusInfo::new(
+        405,
+        "Method Not Allowed",
+        "The resource exists but doesn't support the request method.",
+        &["sending a POST to a GET only endpoint"],
+        &["Allow"],
+    ),
+    StatusInfo::new(
+        406,
+        "Not Acceptable",
+        "The server can't produce a response matching the `Accept` header.",
+        &["requesting a format the endpoint doesn't support"],
+        &["Accept", "Content-Type"],
+    ),
+    StatusInfo::new(
+        408,
+        "Request Timeout",
+        "The server timed out waiting for the request.",
+        &["slow network or a very large body"],
+        &["Connection"],
+    ),
+    StatusInfo::new(
+        409,
+        "Conflict",
+        "The request conflicts with the current state of the resource.",
+        &["creating something that already exists", "concurrent edits"],
+        &["ETag"],
+    ),
+    StatusInfo::new(
+        410,
+        "Gone",
+        "The resource was removed and will not come back.",
+        &["deprecated endpoints"],
+        &[],
+    ),
+    StatusInfo::new(
+        413,
+        "Content Too Large",
+        "The request body is larger than the server is willing to process.",
+        &["uploading a file over the size limit"],
+        &["Content-Length", "Retry-After"],
+    ),
+    StatusInfo::new(
+        414,
+        "URI Too Long",
+        "The URI is longer than the server is willing to interpret.",
+        &[
+            "too many query parameters",
+            "data that should be on the body sent on the URI",
+        ],
+        &[],
+    ),
+    StatusInfo::new(
+        415,
+        "Unsupported Media Type",
+        "The server doesn't support the format of the body.",
+        &[
+            "missing `Content-Type`",
+            "sending form data to a JSON only endpoint",
+        ],
+        &["Content-Type", "Content-Encoding"],
+    ),
+    StatusInfo::new(
+        422,
+        "Unprocessable Content",
+        "The body is well formed, but its contents are invalid.",
+        &["validation errors, the response body usually says which fields"],
+        &["Content-Type"],
+    ),
+    StatusInfo::new(
+        429,
+        "Too Many Requests",
+        "You sent too many requests in a given amount of time.",
+        &["rate limiting", "a retry loop without backoff"],
+        &[
+            "Retry-After",
+            "RateLimit-Limit",
+            "RateLimit-Remaining",
+            "RateLimit-Reset",
+            "X-RateLimit-Remaining",
+        ],
+    ),
+    StatusInfo::new(
+        431,
+        "Request Header Fields Too Large",
+        "The headers of the request are too large.",
+        &[
+            "too many or too large cookies",
+            "a huge token on `Authorization`",
+        ],
+        &["Cookie", "Authorization"],
+    ),
+    StatusInfo::new(
+        500,
+        "Internal Server Error",
+        "The server hit an unexpected error.",
+        &["a bug on the server, usually not something the request can fix"],
+        &[],
+    ),
+    StatusInfo::new(
+        501,
+        "Not Implemented",
+        "The server doesn't support the functionality required.",
+        &["an unsupported method"],
+        &["Allow"],
+    ),
+    StatusInfo::new(
+        502,
+        "Bad Gateway",
+        "A gateway or proxy got an invalid response from the upstream server.",
+        &["the upstream service crashed or is restarting"],
+        &["Via", "Server"],
+    ),
+    StatusInfo::new(
+        503,
+        "Service Unavailable",
+        "The server is not ready to handle the request.",
+        &["maintenance", "the server is overloaded"],
+        &["Retry-After"],
+    ),
+    StatusInfo::new(
+        504,
+        "Gateway Timeout",
+        "A gateway or proxy didn't get a response from the upstream server in time.",
+        &[
+            "a slow upstream service",
+            "a timeout on the proxy shorter than the request takes",
+        ],
+        &["Via", "Server"],
+    ),
+];
+
+/// finds the information about a status code, codes that are not on the
+/// knowledge base can still be described by their class with `describe_class`
+pub fn lookup(code: u16) -> Option<&'static StatusInfo> {
+    STATUS_CODES.iter().find(|info| info.code.eq(&code))
+}
+
+/// a general description of a class of status codes
+pub fn describe_class(code: u16) -> &'static str {
+    match code {
+        100..=199 => "Informational: the request was received and is still being processed.",
+        200..=299 => "Success: the request was received, understood and accepted.",
+        300..=399 => "Redirection: further action is needed to complete the request.",
+        400..=499 => "Client error: the request contains an error and cannot be fulfilled.",
+        500..=599 => "Server error: the server failed to fulfill a valid request.",
+        _ => "Unknown: this status code is outside of the ranges defined by HTTP.",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup() {
+        let info = lookup(431).unwrap();
+
+        assert_eq!(info.name, "Request Header Fields Too Large");
+        assert!(info.headers.contains(&"Cookie"));
+        assert!(lookup(418).is_none());
+        assert!(describe_class(418).starts_with("Client error"));
+    }
+}
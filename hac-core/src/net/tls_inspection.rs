@@ -0,0 +1,444 @@
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use base64::Engine;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use sha2::{Digest, Sha256};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use tokio_rustls::rustls::client::WebPkiServerVerifier;
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use tokio_rustls::rustls::{
+    ClientConfig, DigitallySignedStruct, Error as TlsError, RootCertStore, SignatureScheme,
+};
+use tokio_rustls::TlsConnector;
+
+/// how long the handshake made to inspect a server may take
+const INSPECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// `subjectAltName` extension
+const SAN_OID: &[u8] = &[0x55, 0x1d, 0x11];
+/// attributes of distinguished names, in the order they are usually shown
+const NAME_ATTRIBUTES: &[(&[u8], &str)] = &[
+    (&[0x55, 0x04, 0x03], "CN"),
+    (&[0x55, 0x04, 0x0b], "OU"),
+    (&[0x55, 0x04, 0x0a], "O"),
+    (&[0x55, 0x04, 0x07], "L"),
+    (&[0x55, 0x04, 0x08], "ST"),
+    (&[0x55, 0x04, 0x06], "C"),
+];
+
+/// what was negotiated with the server, and the certificates it presented
+#[derive(Debug, Clone, PartialEq)]
+pub struct TlsInfo {
+    /// like `TLS 1.3`
+    pub version: String,
+    /// like `TLS13_AES_256_GCM_SHA384`
+    pub cipher_suite: String,
+    /// the application protocol agreed on, when the server supports ALPN
+    pub alpn: Option<String>,
+    /// starting from the certificate of the server itself
+    pub chain: Vec<CertificateInfo>,
+    /// why the chain is not trusted by the roots of the system, if it isn't
+    pub untrusted: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+    /// dns names and addresses the certificate is valid for
+    pub sans: Vec<String>,
+    /// base64 of the SHA-256 of the public key info, what hosts are pinned
+    /// to
+    pub spki_sha256: String,
+}
+
+impl CertificateInfo {
+    /// reads the fields of a DER encoded X.509 certificate that matter when
+    /// diagnosing a handshake
+    pub fn parse(der: &[u8]) -> anyhow::Result<CertificateInfo> {
+        parse_certificate(der).ok_or_else(|| anyhow::anyhow!("the certificate is not valid DER"))
+    }
+
+    /// days until the certificate expires, negative once it did
+    pub fn days_left(&self, now: DateTime<Utc>) -> i64 {
+        self.not_after.signed_duration_since(now).num_days()
+    }
+}
+
+/// handshakes with the server of an https uri to find out what a request
+/// to it negotiates. The chain is read even when it isn't trusted, the
+/// reason why is kept instead of failing
+pub async fn inspect(uri: &str) -> anyhow::Result<TlsInfo> {
+    let url = reqwest::Url::parse(uri)?;
+    if url.scheme() != "https" {
+        anyhow::bail!("only https urls use TLS");
+    }
+    let host = url
+        .host_str()
+        .ok_or_else(|| anyhow::anyhow!("the url has no host"))?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    let port = url.port_or_known_default().unwrap_or(443);
+
+    tokio::time::timeout(INSPECTION_TIMEOUT, handshake(host, port))
+        .await
+        .map_err(|_| anyhow::anyhow!("the handshake timed out"))?
+}
+
+async fn handshake(host: String, port: u16) -> anyhow::Result<TlsInfo> {
+    let mut roots = RootCertStore::empty();
+    roots.add_parsable_certificates(rustls_native_certs::load_native_certs().certs);
+    let verifier = Arc::new(RecordingVerifier {
+        inner: WebPkiServerVerifier::builder(Arc::new(roots)).build()?,
+        untrusted: Mutex::default(),
+    });
+    let mut config = ClientConfig::builder()
+        .dangerous()
+        .with_custom_certificate_verifier(verifier.clone())
+        .with_no_client_auth();
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    let tcp = TcpStream::connect((host.as_str(), port)).await?;
+    let server_name = ServerName::try_from(host)?;
+    let stream = TlsConnector::from(Arc::new(config))
+        .connect(server_name, tcp)
+        .await?;
+    let (_, connection) = stream.get_ref();
+
+    let version = connection
+        .protocol_version()
+        .map(|version| {
+            format!("{version:?}")
+                .replace("TLSv", "TLS ")
+                .replace('_', ".")
+        })
+        .unwrap_or_default();
+    let cipher_suite = connection
+        .negotiated_cipher_suite()
+        .map(|suite| format!("{:?}", suite.suite()))
+        .unwrap_or_default();
+    let alpn = connection
+        .alpn_protocol()
+        .map(|protocol| String::from_utf8_lossy(protocol).to_string());
+    let chain = connection
+        .peer_certificates()
+        .unwrap_or_default()
+        .iter()
+        .map(|cert| CertificateInfo::parse(cert))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let untrusted = verifier.untrusted.lock().unwrap().take();
+
+    Ok(TlsInfo {
+        version,
+        cipher_suite,
+        alpn,
+        chain,
+        untrusted,
+    })
+}
+
+/// accepts every certificate so the handshake goes through, keeping why the
+/// system wouldn't have. Signatures are still checked
+#[derive(Debug)]
+struct RecordingVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+    untrusted: Mutex<Option<String>>,
+}
+
+impl ServerCertVerifier for RecordingVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, TlsError> {
+        let verified = self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        );
+        if let Err(e) = verified {
+            *self.untrusted.lock().unwrap() = Some(e.to_string());
+        }
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, TlsError> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, TlsError> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+/// a DER element, its tag and contents
+struct Element<'a> {
+    tag: u8,
+    value: &'a [u8],
+    /// the whole element, tag and length included
+    raw: &'a [u8],
+}
+
+fn read_element<'a>(input: &mut &'a [u8]) -> Option<Element<'a>> {
+    let start = *input;
+    let (&tag, rest) = input.split_first()?;
+    let (&first, mut rest) = rest.split_first()?;
+    let len = match first {
+        len if len < 0x80 => len as usize,
+        0x81..=0x84 => {
+            let count = (first & 0x7f) as usize;
+            let (bytes, remaining) = rest.split_at_checked(count)?;
+            rest = remaining;
+            bytes
+                .iter()
+                .fold(0usize, |len, byte| (len << 8) | *byte as usize)
+        }
+        _ => return None,
+    };
+    let (value, rest) = rest.split_at_checked(len)?;
+    let raw = &start[..start.len() - rest.len()];
+    *input = rest;
+    Some(Element { tag, value, raw })
+}
+
+/// the elements inside a constructed one
+fn children(mut value: &[u8]) -> impl Iterator<Item = Element<'_>> {
+    std::iter::from_fn(move || read_element(&mut value))
+}
+
+fn parse_certificate(der: &[u8]) -> Option<CertificateInfo> {
+    let certificate = children(der).next().filter(|e| e.tag == 0x30)?;
+    let tbs = children(certificate.value)
+        .next()
+        .filter(|e| e.tag == 0x30)?;
+    let mut fields = children(tbs.value).skip_while(|field| field.tag == 0xa0);
+    let _serial = fields.next()?;
+    let _signature = fields.next()?;
+    let issuer = fields.next()?;
+    let validity = fields.next()?;
+    let subject = fields.next()?;
+    let key = fields.next()?;
+    let extensions = fields.find(|field| field.tag == 0xa3);
+
+    let mut validity = children(validity.value);
+    let not_before = parse_time(&validity.next()?)?;
+    let not_after = parse_time(&validity.next()?)?;
+
+    let sans = extensions
+        .and_then(|extensions| children(extensions.value).next())
+        .map(|extensions| {
+            children(extensions.value)
+                .filter_map(|extension| {
+                    let mut parts = children(extension.value);
+                    let oid = parts.next()?;
+                    // the critical flag comes before the value when set
+                    let value = parts.find(|part| part.tag == 0x04)?;
+                    (oid.value == SAN_OID).then(|| parse_sans(value.value))
+                })
+                .flatten()
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    Some(CertificateInfo {
+        subject: parse_name(subject.value),
+        issuer: parse_name(issuer.value),
+        not_before,
+        not_after,
+        sans,
+        spki_sha256: base64::engine::general_purpose::STANDARD.encode(Sha256::digest(key.raw)),
+    })
+}
+
+fn parse_name(name: &[u8]) -> String {
+    let attributes = children(name)
+        .flat_map(|set| children(set.value))
+        .filter_map(|attribute| {
+            let mut parts = children(attribute.value);
+            let oid = parts.next()?;
+            let value = parts.next()?;
+            let (_, label) = NAME_ATTRIBUTES.iter().find(|(id, _)| *id == oid.value)?;
+            Some((*label, String::from_utf8_lossy(value.value).to_string()))
+        })
+        .collect::<Vec<_>>();
+
+    NAME_ATTRIBUTES
+        .iter()
+        .flat_map(|(_, label)| attributes.iter().filter(move |(l, _)| l == label))
+        .map(|(label, value)| format!("{label}={value}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn parse_sans(value: &[u8]) -> Vec<String> {
+    let Some(names) = children(value).next() else {
+        return vec![];
+    };
+    children(names.value)
+        .filter_map(|name| match name.tag {
+            // dNSName
+            0x82 => Some(String::from_utf8_lossy(name.value).to_string()),
+            // iPAddress
+            0x87 => match name.value.len() {
+                4 => Some(IpAddr::from(<[u8; 4]>::try_from(name.value).ok()?).to_string()),
+                16 => Some(IpAddr::from(<[u8; 16]>::try_from(name.value).ok()?).to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+fn parse_time(element: &Element) -> Option<DateTime<Utc>> {
+    let text = std::str::from_utf8(element.value).ok()?;
+    let text = match element.tag {
+        // UTCTime has two digit years, from 1950 to 2049
+        0x17 => match text.get(..2)?.parse::<u8>().ok()? {
+            year if year >= 50 => format!("19{text}"),
+            _ => format!("20{text}"),
+        },
+        0x18 => text.to_string(),
+        _ => return None,
+    };
+    NaiveDateTime::parse_from_str(&text, "%Y%m%d%H%M%SZ")
+        .ok()
+        .map(|time| time.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(tag: u8, parts: &[&[u8]]) -> Vec<u8> {
+        let value = parts.concat();
+        let mut bytes = vec![tag];
+        match value.len() {
+            len if len < 0x80 => bytes.push(len as u8),
+            len => {
+                bytes.extend([0x82, (len >> 8) as u8, len as u8]);
+            }
+        }
+        bytes.extend(value);
+        bytes
+    }
+
+    fn name(common_name: &str, organization: &str) -> Vec<u8> {
+        let attribute = |oid: &[u8], value: &str| {
+            element(
+                0x31,
+                &[&element(
+                    0x30,
+                    &[&element(0x06, &[oid]), &element(0x0c, &[value.as_bytes()])],
+                )],
+            )
+        };
+        element(
+            0x30,
+            &[
+                &attribute(&[0x55, 0x04, 0x0a], organization),
+                &attribute(&[0x55, 0x04, 0x03], common_name),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_parse_certificate() {
+        let sans = element(
+            0x30,
+            &[
+                &element(0x82, &[b"example.com"]),
+                &element(0x82, &[b"*.example.com"]),
+                &element(0x87, &[&[127, 0, 0, 1]]),
+            ],
+        );
+        let extensions = element(
+            0xa3,
+            &[&element(
+                0x30,
+                &[&element(
+                    0x30,
+                    &[
+                        &element(0x06, &[SAN_OID]),
+                        &element(0x01, &[&[0x00]]),
+                        &element(0x04, &[&sans]),
+                    ],
+                )],
+            )],
+        );
+        let tbs = element(
+            0x30,
+            &[
+                &element(0xa0, &[&element(0x02, &[&[2]])]),
+                &element(0x02, &[&[0x01, 0x23]]),
+                &element(0x30, &[&element(0x06, &[&[0x2a, 0x86, 0x48]])]),
+                &name("Example CA", "Example"),
+                &element(
+                    0x30,
+                    &[
+                        &element(0x17, &[b"240101000000Z"]),
+                        &element(0x18, &[b"20250401120000Z"]),
+                    ],
+                ),
+                &name("example.com", "Example"),
+                &element(0x30, &[&element(0x03, &[&[0x00, 0xff]])]),
+                &extensions,
+            ],
+        );
+        let der = element(
+            0x30,
+            &[&tbs, &element(0x30, &[]), &element(0x03, &[&[0x00; 200]])],
+        );
+
+        let certificate = CertificateInfo::parse(&der).unwrap();
+        assert_eq!(certificate.subject, "CN=example.com, O=Example");
+        assert_eq!(certificate.issuer, "CN=Example CA, O=Example");
+        assert_eq!(
+            certificate.not_before.to_rfc3339(),
+            "2024-01-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            certificate.not_after.to_rfc3339(),
+            "2025-04-01T12:00:00+00:00"
+        );
+        assert_eq!(
+            certificate.sans,
+            vec!["example.com", "*.example.com", "127.0.0.1"]
+        );
+        assert_eq!(certificate.days_left(certificate.not_before), 456);
+        // hash of the `30 04 03 02 00 ff` public key info
+        assert_eq!(
+            certificate.spki_sha256,
+            base64::engine::general_purpose::STANDARD
+                .encode(Sha256::digest([0x30, 0x04, 0x03, 0x02, 0x00, 0xff]))
+        );
+
+        assert!(CertificateInfo::parse(&der[..der.len() - 10]).is_err());
+    }
+}
@@ -0,0 +1,44 @@
+use crate::transform::Transform;
+
+/// line breaks of pasted text as `\n`, as some terminals and clipboards send
+/// `\r\n` or `\r` instead
+pub fn normalize_line_breaks(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// pasted text made to fit on inputs of a single line, like the url or a
+/// header value. Lines are joined by a space, without the whitespace around
+/// them, and tabs become spaces
+pub fn single_line(text: &str) -> String {
+    normalize_line_breaks(text)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('\t', " ")
+}
+
+/// pasted text as it is inserted inside of a JSON string, with its quotes,
+/// backslashes and line breaks escaped
+pub fn json_string(text: &str) -> String {
+    let text = normalize_line_breaks(text);
+    Transform::JsonEscape.apply(&text).unwrap_or(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_pasted_text() {
+        let pasted = "  https://api.dev/users?\r\n\tpage=1 \r\n\r\n";
+        assert_eq!(single_line(pasted), "https://api.dev/users? page=1");
+        assert_eq!(single_line("a\tb"), "a b");
+        assert_eq!(normalize_line_breaks("a\r\nb\rc"), "a\nb\nc");
+        assert_eq!(
+            json_string("say \"hi\"\r\nC:\\temp"),
+            "say \\\"hi\\\"\\nC:\\\\temp"
+        );
+    }
+}
@@ -0,0 +1,321 @@
+mod parser;
+mod wire;
+
+use crate::collection::Collection;
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// every message and enum declared on the `.proto` files of a collection,
+/// keyed by their fully qualified name, like `shop.v1.Order`
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Schema {
+    messages: HashMap<String, Message>,
+    enums: HashMap<String, Enum>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Message {
+    fields: Vec<Field>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Field {
+    name: String,
+    number: u32,
+    kind: FieldKind,
+    repeated: bool,
+}
+
+impl Field {
+    /// name of the field on the JSON representation of the message
+    fn json_name(&self) -> String {
+        let mut name = String::with_capacity(self.name.len());
+        let mut upper = false;
+        for c in self.name.chars() {
+            match c {
+                '_' => upper = true,
+                c if upper => {
+                    name.push(c.to_ascii_uppercase());
+                    upper = false;
+                }
+                c => name.push(c),
+            }
+        }
+        name
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum FieldKind {
+    Double,
+    Float,
+    Int32,
+    Int64,
+    Uint32,
+    Uint64,
+    Sint32,
+    Sint64,
+    Fixed32,
+    Fixed64,
+    Sfixed32,
+    Sfixed64,
+    Bool,
+    String,
+    Bytes,
+    /// a type referenced by name, replaced by a message or an enum once
+    /// every file is parsed
+    Named {
+        name: String,
+        scope: String,
+    },
+    Message(String),
+    Enum(String),
+    Map(Box<FieldKind>, Box<FieldKind>),
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Enum {
+    values: Vec<(String, i32)>,
+}
+
+impl Schema {
+    /// parses the contents of a single `.proto` file, imports are ignored
+    pub fn parse(contents: &str) -> anyhow::Result<Schema> {
+        let mut schema = Schema::default();
+        parser::parse(contents, &mut schema)?;
+        schema.resolve()?;
+        Ok(schema)
+    }
+
+    /// parses the files along with every file they import, imports are
+    /// looked up relative to the file importing them
+    pub fn load(paths: &[PathBuf]) -> anyhow::Result<Schema> {
+        let mut schema = Schema::default();
+        let mut pending = paths.to_vec();
+        let mut loaded = HashSet::new();
+
+        while let Some(path) = pending.pop() {
+            if !loaded.insert(path.clone()) {
+                continue;
+            }
+            let contents = std::fs::read_to_string(&path)
+                .map_err(|e| anyhow::anyhow!("failed to read {:?}: {e}", path))?;
+            let imports = parser::parse(&contents, &mut schema)
+                .map_err(|e| anyhow::anyhow!("{}: {e}", path.to_string_lossy()))?;
+            let dir = path.parent().unwrap_or(Path::new(""));
+            for import in imports {
+                let path = dir.join(&import);
+                // well known types ship with protoc rather than with the
+                // files, only the ones available are loaded
+                if import.starts_with("google/protobuf/") && !path.exists() {
+                    continue;
+                }
+                pending.push(path);
+            }
+        }
+
+        schema.resolve()?;
+        Ok(schema)
+    }
+
+    /// encodes a JSON value as the message with the given name
+    pub fn encode(&self, message: &str, json: &serde_json::Value) -> anyhow::Result<Vec<u8>> {
+        let message = self.find_message(message)?;
+        wire::encode_message(self, message, json)
+    }
+
+    /// decodes a message with the given name into its JSON representation
+    pub fn decode(&self, message: &str, bytes: &[u8]) -> anyhow::Result<serde_json::Value> {
+        let message = self.find_message(message)?;
+        wire::decode_message(self, message, bytes)
+    }
+
+    fn find_message(&self, name: &str) -> anyhow::Result<&Message> {
+        self.messages
+            .get(name.trim_start_matches('.'))
+            .ok_or_else(|| anyhow::anyhow!("no message named {:?} was found", name))
+    }
+
+    /// replaces every type referenced by name with what it points to,
+    /// following the scoping rules of protobuf
+    fn resolve(&mut self) -> anyhow::Result<()> {
+        let mut messages = self.messages.clone();
+        for message in messages.values_mut() {
+            for field in message.fields.iter_mut() {
+                field.kind = self.resolve_kind(&field.kind)?;
+            }
+        }
+        self.messages = messages;
+        Ok(())
+    }
+
+    fn resolve_kind(&self, kind: &FieldKind) -> anyhow::Result<FieldKind> {
+        match kind {
+            FieldKind::Named { name, scope } => {
+                let candidates = match name.strip_prefix('.') {
+                    Some(name) => vec![name.to_string()],
+                    None => {
+                        let mut scope = scope.as_str();
+                        let mut candidates = vec![];
+                        loop {
+                            match scope.is_empty() {
+                                true => candidates.push(name.clone()),
+                                false => candidates.push(format!("{scope}.{name}")),
+                            }
+                            if scope.is_empty() {
+                                break candidates;
+                            }
+                            scope = scope.rsplit_once('.').map(|(s, _)| s).unwrap_or_default();
+                        }
+                    }
+                };
+
+                candidates
+                    .into_iter()
+                    .find_map(|candidate| {
+                        if self.messages.contains_key(&candidate) {
+                            Some(FieldKind::Message(candidate))
+                        } else if self.enums.contains_key(&candidate) {
+                            Some(FieldKind::Enum(candidate))
+                        } else {
+                            None
+                        }
+                    })
+                    .ok_or_else(|| anyhow::anyhow!("unknown type {:?}", name))
+            }
+            FieldKind::Map(key, value) => Ok(FieldKind::Map(
+                Box::new(self.resolve_kind(key)?),
+                Box::new(self.resolve_kind(value)?),
+            )),
+            kind => Ok(kind.clone()),
+        }
+    }
+}
+
+/// loads the `.proto` files attached to the collection, relative paths are
+/// relative to the collection file. Collections without any yield nothing
+pub fn load_schema(collection: &Collection) -> anyhow::Result<Option<Arc<Schema>>> {
+    if collection.protos.is_empty() {
+        return Ok(None);
+    }
+
+    let dir = collection.path.parent().unwrap_or(Path::new(""));
+    let paths = collection
+        .protos
+        .iter()
+        .map(|path| dir.join(path))
+        .collect::<Vec<_>>();
+    Ok(Some(Arc::new(Schema::load(&paths)?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROTO: &str = r#"
+syntax = "proto3";
+package shop.v1;
+
+import "google/protobuf/empty.proto";
+
+// an order placed by a customer
+message Order {
+  enum Status {
+    STATUS_UNSPECIFIED = 0;
+    STATUS_PAID = 1;
+  }
+
+  message Item {
+    string sku = 1;
+    uint32 quantity = 2;
+  }
+
+  int64 id = 1;
+  string customer_name = 2;
+  repeated Item items = 3;
+  Status status = 4;
+  map<string, int32> totals = 5;
+  bytes signature = 6;
+  repeated sint32 deltas = 7 [packed = true];
+  oneof payment {
+    string card = 8;
+    double credit = 9;
+  }
+  /* kept for old clients */
+  reserved 10, 11;
+}
+
+service Orders {
+  rpc Get (Order) returns (Order) {}
+}
+"#;
+
+    #[test]
+    fn test_round_trip() {
+        let schema = Schema::parse(PROTO).unwrap();
+        let json = serde_json::json!({
+            "id": "9007199254740993",
+            "customerName": "john",
+            "items": [{ "sku": "a-1", "quantity": 2 }, { "sku": "b-2" }],
+            "status": "STATUS_PAID",
+            "totals": { "brl": -150 },
+            "signature": "aGFj",
+            "deltas": [-1, 0, 2],
+            "credit": 1.5,
+        });
+
+        let bytes = schema.encode("shop.v1.Order", &json).unwrap();
+        let decoded = schema.decode(".shop.v1.Order", &bytes).unwrap();
+        assert_eq!(
+            decoded,
+            serde_json::json!({
+                "id": "9007199254740993",
+                "customerName": "john",
+                "items": [{ "sku": "a-1", "quantity": 2 }, { "sku": "b-2" }],
+                "status": "STATUS_PAID",
+                "totals": { "brl": -150 },
+                "signature": "aGFj",
+                "deltas": [-1, 0, 2],
+                "credit": 1.5,
+            })
+        );
+
+        // field names from the .proto file are accepted as well
+        let snake = serde_json::json!({ "customer_name": "john" });
+        assert_eq!(
+            schema.encode("shop.v1.Order", &snake).unwrap(),
+            vec![0x12, 4, b'j', b'o', b'h', b'n']
+        );
+
+        assert!(schema
+            .encode("shop.v1.Order", &serde_json::json!({ "unknown": 1 }))
+            .is_err());
+        assert!(schema.decode("shop.v1.Missing", &bytes).is_err());
+        assert!(Schema::parse("message A { Missing b = 1; }").is_err());
+    }
+
+    #[test]
+    fn test_load_imports() {
+        let dir = std::env::temp_dir().join(format!("hac-protobuf-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("common")).unwrap();
+        std::fs::write(
+            dir.join("common/money.proto"),
+            "syntax = \"proto3\";\npackage common;\nmessage Money { int64 cents = 1; }",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("order.proto"),
+            "import \"common/money.proto\";\nimport \"google/protobuf/timestamp.proto\";\nmessage Order { common.Money total = 1; }",
+        )
+        .unwrap();
+
+        let schema = Schema::load(&[dir.join("order.proto")]).unwrap();
+        let json = serde_json::json!({ "total": { "cents": "150" } });
+        let bytes = schema.encode("Order", &json).unwrap();
+        assert_eq!(schema.decode("Order", &bytes).unwrap(), json);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
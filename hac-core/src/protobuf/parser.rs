@@ -0,0 +1,365 @@
+use crate::protobuf::{Enum, Field, FieldKind, Message, Schema};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Int(i64),
+    Str(String),
+    Symbol(char),
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+/// parses a `.proto` file into the schema, returning the files it imports.
+/// Services, extensions and options are skipped as only messages matter to
+/// encode bodies
+pub(super) fn parse(contents: &str, schema: &mut Schema) -> anyhow::Result<Vec<String>> {
+    let mut parser = Parser {
+        tokens: tokenize(contents)?,
+        pos: 0,
+    };
+    let mut package = String::new();
+    let mut imports = vec![];
+
+    while let Some(token) = parser.next() {
+        match token {
+            Token::Ident(word) if word.eq("syntax") || word.eq("edition") => {
+                parser.skip_statement();
+            }
+            Token::Ident(word) if word.eq("package") => {
+                package = parser.full_ident()?;
+                parser.expect(';')?;
+            }
+            Token::Ident(word) if word.eq("import") => {
+                if let Some(Token::Ident(modifier)) = parser.peek() {
+                    if modifier.eq("public") || modifier.eq("weak") {
+                        parser.next();
+                    }
+                }
+                match parser.next() {
+                    Some(Token::Str(path)) => imports.push(path),
+                    _ => anyhow::bail!("expected the path of the import"),
+                }
+                parser.expect(';')?;
+            }
+            Token::Ident(word) if word.eq("option") => parser.skip_statement(),
+            Token::Ident(word) if word.eq("message") => {
+                parser.message(&package, schema)?;
+            }
+            Token::Ident(word) if word.eq("enum") => parser.enumeration(&package, schema)?,
+            Token::Ident(word) if word.eq("service") || word.eq("extend") => {
+                parser.skip_statement();
+            }
+            Token::Symbol(';') => {}
+            token => anyhow::bail!("unexpected {}", describe(&token)),
+        }
+    }
+
+    Ok(imports)
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn expect(&mut self, symbol: char) -> anyhow::Result<()> {
+        match self.next() {
+            Some(Token::Symbol(found)) if found.eq(&symbol) => Ok(()),
+            Some(token) => anyhow::bail!("expected `{symbol}`, found {}", describe(&token)),
+            None => anyhow::bail!("expected `{symbol}`, found the end of the file"),
+        }
+    }
+
+    fn ident(&mut self) -> anyhow::Result<String> {
+        match self.next() {
+            Some(Token::Ident(ident)) => Ok(ident),
+            Some(token) => anyhow::bail!("expected a name, found {}", describe(&token)),
+            None => anyhow::bail!("expected a name, found the end of the file"),
+        }
+    }
+
+    /// a dotted name, like `google.protobuf.Timestamp`
+    fn full_ident(&mut self) -> anyhow::Result<String> {
+        let mut ident = String::new();
+        if self.peek().eq(&Some(&Token::Symbol('.'))) {
+            self.next();
+            ident.push('.');
+        }
+        ident.push_str(&self.ident()?);
+        while self.peek().eq(&Some(&Token::Symbol('.'))) {
+            self.next();
+            ident.push('.');
+            ident.push_str(&self.ident()?);
+        }
+        Ok(ident)
+    }
+
+    fn int(&mut self) -> anyhow::Result<i64> {
+        match self.next() {
+            Some(Token::Symbol('-')) => Ok(-self.int()?),
+            Some(Token::Int(value)) => Ok(value),
+            Some(token) => anyhow::bail!("expected a number, found {}", describe(&token)),
+            None => anyhow::bail!("expected a number, found the end of the file"),
+        }
+    }
+
+    /// skips everything up to the end of the statement, including whole
+    /// blocks like the ones of services
+    fn skip_statement(&mut self) {
+        let mut depth = 0;
+        while let Some(token) = self.next() {
+            match token {
+                Token::Symbol('{') => depth += 1,
+                Token::Symbol('}') => {
+                    depth -= 1;
+                    if depth <= 0 {
+                        return;
+                    }
+                }
+                Token::Symbol(';') if depth == 0 => return,
+                _ => {}
+            }
+        }
+    }
+
+    /// skips field options, like `[packed = true]`
+    fn skip_options(&mut self) {
+        if self.peek().ne(&Some(&Token::Symbol('['))) {
+            return;
+        }
+        while let Some(token) = self.next() {
+            if token.eq(&Token::Symbol(']')) {
+                return;
+            }
+        }
+    }
+
+    fn message(&mut self, scope: &str, schema: &mut Schema) -> anyhow::Result<()> {
+        let name = qualify(scope, &self.ident()?);
+        self.expect('{')?;
+        let mut message = Message::default();
+        self.message_body(&name, &mut message, schema)?;
+        schema.messages.insert(name, message);
+        Ok(())
+    }
+
+    fn message_body(
+        &mut self,
+        name: &str,
+        message: &mut Message,
+        schema: &mut Schema,
+    ) -> anyhow::Result<()> {
+        loop {
+            let Some(token) = self.peek().cloned() else {
+                anyhow::bail!("message {name} is never closed");
+            };
+            match token {
+                Token::Symbol('}') => {
+                    self.next();
+                    return Ok(());
+                }
+                Token::Symbol(';') => {
+                    self.next();
+                }
+                Token::Ident(word) => match word.as_str() {
+                    "message" => {
+                        self.next();
+                        self.message(name, schema)?;
+                    }
+                    "enum" => {
+                        self.next();
+                        self.enumeration(name, schema)?;
+                    }
+                    "oneof" => {
+                        self.next();
+                        self.ident()?;
+                        self.expect('{')?;
+                        // fields of a oneof are regular fields on the wire
+                        self.message_body(name, message, schema)?;
+                    }
+                    "option" | "reserved" | "extensions" | "extend" => self.skip_statement(),
+                    _ => message.fields.push(self.field(name)?),
+                },
+                token => anyhow::bail!("unexpected {} on message {name}", describe(&token)),
+            }
+        }
+    }
+
+    fn field(&mut self, scope: &str) -> anyhow::Result<Field> {
+        let mut repeated = false;
+        let kind = match self.full_ident()?.as_str() {
+            "repeated" => {
+                repeated = true;
+                self.kind(scope)?
+            }
+            "optional" | "required" => self.kind(scope)?,
+            "map" => {
+                self.expect('<')?;
+                let key = self.kind(scope)?;
+                self.expect(',')?;
+                let value = self.kind(scope)?;
+                self.expect('>')?;
+                FieldKind::Map(Box::new(key), Box::new(value))
+            }
+            other => kind_from_name(other, scope),
+        };
+        let name = self.ident()?;
+        self.expect('=')?;
+        let number = u32::try_from(self.int()?)
+            .map_err(|_| anyhow::anyhow!("field {name} has an invalid number"))?;
+        self.skip_options();
+        self.expect(';')?;
+
+        Ok(Field {
+            name,
+            number,
+            kind,
+            repeated,
+        })
+    }
+
+    fn kind(&mut self, scope: &str) -> anyhow::Result<FieldKind> {
+        Ok(kind_from_name(&self.full_ident()?, scope))
+    }
+
+    fn enumeration(&mut self, scope: &str, schema: &mut Schema) -> anyhow::Result<()> {
+        let name = qualify(scope, &self.ident()?);
+        self.expect('{')?;
+        let mut enumeration = Enum::default();
+
+        loop {
+            match self.next() {
+                Some(Token::Symbol('}')) => break,
+                Some(Token::Symbol(';')) => {}
+                Some(Token::Ident(word)) if word.eq("option") || word.eq("reserved") => {
+                    self.skip_statement()
+                }
+                Some(Token::Ident(value)) => {
+                    self.expect('=')?;
+                    let number = i32::try_from(self.int()?)
+                        .map_err(|_| anyhow::anyhow!("value {value} has an invalid number"))?;
+                    self.skip_options();
+                    self.expect(';')?;
+                    enumeration.values.push((value, number));
+                }
+                Some(token) => anyhow::bail!("unexpected {} on enum {name}", describe(&token)),
+                None => anyhow::bail!("enum {name} is never closed"),
+            }
+        }
+
+        schema.enums.insert(name, enumeration);
+        Ok(())
+    }
+}
+
+fn qualify(scope: &str, name: &str) -> String {
+    match scope.is_empty() {
+        true => name.to_string(),
+        false => format!("{scope}.{name}"),
+    }
+}
+
+fn kind_from_name(name: &str, scope: &str) -> FieldKind {
+    match name {
+        "double" => FieldKind::Double,
+        "float" => FieldKind::Float,
+        "int32" => FieldKind::Int32,
+        "int64" => FieldKind::Int64,
+        "uint32" => FieldKind::Uint32,
+        "uint64" => FieldKind::Uint64,
+        "sint32" => FieldKind::Sint32,
+        "sint64" => FieldKind::Sint64,
+        "fixed32" => FieldKind::Fixed32,
+        "fixed64" => FieldKind::Fixed64,
+        "sfixed32" => FieldKind::Sfixed32,
+        "sfixed64" => FieldKind::Sfixed64,
+        "bool" => FieldKind::Bool,
+        "string" => FieldKind::String,
+        "bytes" => FieldKind::Bytes,
+        name => FieldKind::Named {
+            name: name.to_string(),
+            scope: scope.to_string(),
+        },
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Ident(ident) => format!("`{ident}`"),
+        Token::Int(value) => format!("`{value}`"),
+        Token::Str(value) => format!("{value:?}"),
+        Token::Symbol(symbol) => format!("`{symbol}`"),
+    }
+}
+
+fn tokenize(contents: &str) -> anyhow::Result<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut chars = contents.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '/' if chars.peek().eq(&Some(&'/')) => {
+                for c in chars.by_ref() {
+                    if c.eq(&'\n') {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek().eq(&Some(&'*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous.eq(&'*') && c.eq(&'/') {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            '"' | '\'' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => value.extend(chars.next()),
+                        Some(end) if end.eq(&c) => break,
+                        Some(c) => value.push(c),
+                        None => anyhow::bail!("a string is never closed"),
+                    }
+                }
+                tokens.push(Token::Str(value));
+            }
+            c if c.is_ascii_digit() => {
+                let mut value = String::from(c);
+                while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || c.eq(&'.')) {
+                    value.push(c);
+                }
+                let parsed = match value.strip_prefix("0x").or(value.strip_prefix("0X")) {
+                    Some(hex) => i64::from_str_radix(hex, 16).ok(),
+                    None => value.parse().ok(),
+                };
+                // floats only show up on options, which are skipped
+                tokens.push(parsed.map(Token::Int).unwrap_or(Token::Ident(value)));
+            }
+            c if c.is_alphabetic() || c.eq(&'_') => {
+                let mut ident = String::from(c);
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || c.eq(&'_')) {
+                    ident.push(c);
+                }
+                tokens.push(Token::Ident(ident));
+            }
+            c => tokens.push(Token::Symbol(c)),
+        }
+    }
+
+    Ok(tokens)
+}
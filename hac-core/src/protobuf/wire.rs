@@ -0,0 +1,500 @@
+use crate::protobuf::{Field, FieldKind, Message, Schema};
+
+use std::collections::HashMap;
+
+use base64::Engine;
+use serde_json::Value;
+
+const VARINT: u32 = 0;
+const FIXED64: u32 = 1;
+const LENGTH_DELIMITED: u32 = 2;
+const FIXED32: u32 = 5;
+
+/// encodes a JSON object as a message, fields are matched by either their
+/// name on the `.proto` file or its lowerCamelCase version
+pub(super) fn encode_message(
+    schema: &Schema,
+    message: &Message,
+    json: &Value,
+) -> anyhow::Result<Vec<u8>> {
+    let Value::Object(object) = json else {
+        anyhow::bail!("expected an object, found {json}");
+    };
+    let mut out = vec![];
+
+    for (key, value) in object {
+        let field = message
+            .fields
+            .iter()
+            .find(|field| field.name.eq(key) || field.json_name().eq(key))
+            .ok_or_else(|| anyhow::anyhow!("unknown field {:?}", key))?;
+        if value.is_null() {
+            continue;
+        }
+        encode_field(schema, field, value, &mut out)
+            .map_err(|e| anyhow::anyhow!("{}: {e}", field.name))?;
+    }
+
+    Ok(out)
+}
+
+fn encode_field(
+    schema: &Schema,
+    field: &Field,
+    value: &Value,
+    out: &mut Vec<u8>,
+) -> anyhow::Result<()> {
+    if let FieldKind::Map(key_kind, value_kind) = &field.kind {
+        let Value::Object(entries) = value else {
+            anyhow::bail!("expected an object, found {value}");
+        };
+        for (key, value) in entries {
+            let mut entry = vec![];
+            encode_value(schema, key_kind, 1, &map_key(key_kind, key), &mut entry)?;
+            encode_value(schema, value_kind, 2, value, &mut entry)?;
+            write_tag(field.number, LENGTH_DELIMITED, out);
+            write_bytes(&entry, out);
+        }
+        return Ok(());
+    }
+
+    if !field.repeated {
+        return encode_value(schema, &field.kind, field.number, value, out);
+    }
+
+    let Value::Array(values) = value else {
+        anyhow::bail!("expected a list, found {value}");
+    };
+    match is_packable(&field.kind) {
+        true => {
+            // repeated numbers are packed together, skipping their tags
+            let mut packed = vec![];
+            for value in values {
+                encode_scalar(schema, &field.kind, value, &mut packed)?;
+            }
+            write_tag(field.number, LENGTH_DELIMITED, out);
+            write_bytes(&packed, out);
+        }
+        false => {
+            for value in values {
+                encode_value(schema, &field.kind, field.number, value, out)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// keys of JSON objects are always strings, while keys of maps can be
+/// numbers or booleans as well
+fn map_key(kind: &FieldKind, key: &str) -> Value {
+    match kind {
+        FieldKind::Bool => Value::Bool(key.eq("true")),
+        _ => Value::String(key.to_string()),
+    }
+}
+
+fn encode_value(
+    schema: &Schema,
+    kind: &FieldKind,
+    number: u32,
+    value: &Value,
+    out: &mut Vec<u8>,
+) -> anyhow::Result<()> {
+    match kind {
+        FieldKind::String => {
+            let Value::String(string) = value else {
+                anyhow::bail!("expected a string, found {value}");
+            };
+            write_tag(number, LENGTH_DELIMITED, out);
+            write_bytes(string.as_bytes(), out);
+        }
+        FieldKind::Bytes => {
+            let Value::String(string) = value else {
+                anyhow::bail!("expected a base64 string, found {value}");
+            };
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(string)
+                .or_else(|_| base64::engine::general_purpose::URL_SAFE.decode(string))
+                .map_err(|_| anyhow::anyhow!("expected a base64 string, found {value}"))?;
+            write_tag(number, LENGTH_DELIMITED, out);
+            write_bytes(&bytes, out);
+        }
+        FieldKind::Message(name) => {
+            let message = schema.find_message(name)?;
+            let bytes = encode_message(schema, message, value)?;
+            write_tag(number, LENGTH_DELIMITED, out);
+            write_bytes(&bytes, out);
+        }
+        kind => {
+            write_tag(number, wire_type(kind), out);
+            encode_scalar(schema, kind, value, out)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// encodes a number, boolean or enum without its tag
+fn encode_scalar(
+    schema: &Schema,
+    kind: &FieldKind,
+    value: &Value,
+    out: &mut Vec<u8>,
+) -> anyhow::Result<()> {
+    match kind {
+        FieldKind::Double => out.extend(float(value)?.to_le_bytes()),
+        FieldKind::Float => out.extend((float(value)? as f32).to_le_bytes()),
+        FieldKind::Int32 => write_varint(ranged(value, i32::MIN, i32::MAX)? as i64 as u64, out),
+        FieldKind::Int64 => write_varint(ranged(value, i64::MIN, i64::MAX)? as i64 as u64, out),
+        FieldKind::Uint32 => write_varint(ranged(value, 0, u32::MAX)? as u64, out),
+        FieldKind::Uint64 => write_varint(ranged(value, 0, u64::MAX)? as u64, out),
+        FieldKind::Sint32 => {
+            let value = ranged(value, i32::MIN, i32::MAX)? as i64;
+            write_varint(zigzag(value), out)
+        }
+        FieldKind::Sint64 => {
+            let value = ranged(value, i64::MIN, i64::MAX)? as i64;
+            write_varint(zigzag(value), out)
+        }
+        FieldKind::Fixed32 => out.extend((ranged(value, 0, u32::MAX)? as u32).to_le_bytes()),
+        FieldKind::Fixed64 => out.extend((ranged(value, 0, u64::MAX)? as u64).to_le_bytes()),
+        FieldKind::Sfixed32 => {
+            out.extend((ranged(value, i32::MIN, i32::MAX)? as i32).to_le_bytes())
+        }
+        FieldKind::Sfixed64 => {
+            out.extend((ranged(value, i64::MIN, i64::MAX)? as i64).to_le_bytes())
+        }
+        FieldKind::Bool => match value {
+            Value::Bool(value) => write_varint(*value as u64, out),
+            value => anyhow::bail!("expected a boolean, found {value}"),
+        },
+        FieldKind::Enum(name) => {
+            let number = match value {
+                Value::String(variant) => schema.enums[name]
+                    .values
+                    .iter()
+                    .find(|(candidate, _)| candidate.eq(variant))
+                    .map(|(_, number)| *number)
+                    .ok_or_else(|| anyhow::anyhow!("{name} has no value named {variant:?}"))?,
+                value => ranged(value, i32::MIN, i32::MAX)? as i32,
+            };
+            write_varint(number as i64 as u64, out)
+        }
+        kind => anyhow::bail!("{kind:?} is not a scalar"),
+    }
+
+    Ok(())
+}
+
+fn is_packable(kind: &FieldKind) -> bool {
+    !matches!(
+        kind,
+        FieldKind::String | FieldKind::Bytes | FieldKind::Message(_) | FieldKind::Map(..)
+    )
+}
+
+fn wire_type(kind: &FieldKind) -> u32 {
+    match kind {
+        FieldKind::Double | FieldKind::Fixed64 | FieldKind::Sfixed64 => FIXED64,
+        FieldKind::Float | FieldKind::Fixed32 | FieldKind::Sfixed32 => FIXED32,
+        FieldKind::String | FieldKind::Bytes | FieldKind::Message(_) | FieldKind::Map(..) => {
+            LENGTH_DELIMITED
+        }
+        _ => VARINT,
+    }
+}
+
+/// integers can be given as numbers or strings, as 64-bit integers don't fit
+/// the numbers of JSON
+fn ranged<T: Into<i128>>(value: &Value, min: T, max: T) -> anyhow::Result<i128> {
+    let parsed = match value {
+        Value::Number(number) => number
+            .as_i64()
+            .map(i128::from)
+            .or(number.as_u64().map(i128::from))
+            .or(number
+                .as_f64()
+                .filter(|float| float.fract() == 0.0)
+                .map(|float| float as i128)),
+        Value::String(string) => string.trim().parse::<i128>().ok(),
+        _ => None,
+    };
+    let parsed = parsed.ok_or_else(|| anyhow::anyhow!("expected an integer, found {value}"))?;
+    match (min.into()..=max.into()).contains(&parsed) {
+        true => Ok(parsed),
+        false => anyhow::bail!("{parsed} is out of range"),
+    }
+}
+
+fn float(value: &Value) -> anyhow::Result<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(string) => match string.as_str() {
+            "NaN" => Some(f64::NAN),
+            "Infinity" => Some(f64::INFINITY),
+            "-Infinity" => Some(f64::NEG_INFINITY),
+            string => string.trim().parse().ok(),
+        },
+        _ => None,
+    }
+    .ok_or_else(|| anyhow::anyhow!("expected a number, found {value}"))
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+fn write_tag(number: u32, wire_type: u32, out: &mut Vec<u8>) {
+    write_varint(((number << 3) | wire_type) as u64, out);
+}
+
+fn write_varint(mut value: u64, out: &mut Vec<u8>) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn write_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+    write_varint(bytes.len() as u64, out);
+    out.extend_from_slice(bytes);
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn is_empty(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn varint(&mut self) -> anyhow::Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte < 0x80 {
+                return Ok(value);
+            }
+        }
+        anyhow::bail!("varint is too long")
+    }
+
+    fn take(&mut self, len: usize) -> anyhow::Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| anyhow::anyhow!("message ended unexpectedly"))?;
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn length_delimited(&mut self) -> anyhow::Result<&'a [u8]> {
+        let len = self.varint()? as usize;
+        self.take(len)
+    }
+
+    fn fixed32(&mut self) -> anyhow::Result<[u8; 4]> {
+        Ok(self.take(4)?.try_into().expect("took 4 bytes"))
+    }
+
+    fn fixed64(&mut self) -> anyhow::Result<[u8; 8]> {
+        Ok(self.take(8)?.try_into().expect("took 8 bytes"))
+    }
+
+    fn skip(&mut self, wire_type: u32) -> anyhow::Result<()> {
+        match wire_type {
+            VARINT => self.varint().map(|_| ()),
+            FIXED64 => self.take(8).map(|_| ()),
+            LENGTH_DELIMITED => self.length_delimited().map(|_| ()),
+            FIXED32 => self.take(4).map(|_| ()),
+            wire_type => anyhow::bail!("unsupported wire type {wire_type}"),
+        }
+    }
+}
+
+/// decodes a message into JSON following the conventions of protobuf:
+/// lowerCamelCase names, 64-bit integers as strings, bytes as base64 and
+/// enums by name. Fields missing from the schema are skipped
+pub(super) fn decode_message(
+    schema: &Schema,
+    message: &Message,
+    bytes: &[u8],
+) -> anyhow::Result<Value> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let mut values: HashMap<u32, Value> = HashMap::new();
+
+    while !reader.is_empty() {
+        let tag = reader.varint()?;
+        let number = (tag >> 3) as u32;
+        let wire_type = (tag & 0x7) as u32;
+        let Some(field) = message.fields.iter().find(|field| field.number == number) else {
+            reader.skip(wire_type)?;
+            continue;
+        };
+
+        let decoded = decode_field(schema, field, wire_type, &mut reader)
+            .map_err(|e| anyhow::anyhow!("{}: {e}", field.name))?;
+        match (&field.kind, field.repeated) {
+            (FieldKind::Map(..), _) => {
+                let entries = values
+                    .entry(number)
+                    .or_insert_with(|| Value::Object(Default::default()));
+                if let (Value::Object(entries), Value::Object(entry)) = (entries, decoded) {
+                    entries.extend(entry);
+                }
+            }
+            (_, true) => {
+                let items = values.entry(number).or_insert_with(|| Value::Array(vec![]));
+                if let (Value::Array(items), Value::Array(decoded)) = (items, decoded) {
+                    items.extend(decoded);
+                }
+            }
+            (_, false) => {
+                values.insert(number, decoded);
+            }
+        }
+    }
+
+    let mut object = serde_json::Map::new();
+    for field in message.fields.iter() {
+        if let Some(value) = values.remove(&field.number) {
+            object.insert(field.json_name(), value);
+        }
+    }
+
+    Ok(Value::Object(object))
+}
+
+/// decodes one occurrence of the field. Repeated fields yield a list, as
+/// packed ones hold many values at once, and map entries yield an object
+fn decode_field(
+    schema: &Schema,
+    field: &Field,
+    wire_type: u32,
+    reader: &mut Reader,
+) -> anyhow::Result<Value> {
+    if let FieldKind::Map(key_kind, value_kind) = &field.kind {
+        let mut entry = Reader {
+            bytes: reader.length_delimited()?,
+            pos: 0,
+        };
+        let mut key = None;
+        let mut value = None;
+        while !entry.is_empty() {
+            let tag = entry.varint()?;
+            match tag >> 3 {
+                1 => key = Some(decode_value(schema, key_kind, &mut entry)?),
+                2 => value = Some(decode_value(schema, value_kind, &mut entry)?),
+                _ => entry.skip((tag & 0x7) as u32)?,
+            }
+        }
+        let key = match key {
+            Some(Value::String(key)) => key,
+            Some(key) => key.to_string(),
+            None => String::new(),
+        };
+        let value = value.unwrap_or_else(|| default_value(schema, value_kind));
+        return Ok(Value::Object([(key, value)].into_iter().collect()));
+    }
+
+    if !field.repeated {
+        return decode_value(schema, &field.kind, reader);
+    }
+
+    match (is_packable(&field.kind), wire_type) {
+        (true, LENGTH_DELIMITED) => {
+            let mut packed = Reader {
+                bytes: reader.length_delimited()?,
+                pos: 0,
+            };
+            let mut values = vec![];
+            while !packed.is_empty() {
+                values.push(decode_value(schema, &field.kind, &mut packed)?);
+            }
+            Ok(Value::Array(values))
+        }
+        _ => Ok(Value::Array(vec![decode_value(
+            schema,
+            &field.kind,
+            reader,
+        )?])),
+    }
+}
+
+fn decode_value(schema: &Schema, kind: &FieldKind, reader: &mut Reader) -> anyhow::Result<Value> {
+    Ok(match kind {
+        FieldKind::Double => float_value(f64::from_le_bytes(reader.fixed64()?)),
+        FieldKind::Float => float_value(f32::from_le_bytes(reader.fixed32()?) as f64),
+        FieldKind::Int32 => Value::from(reader.varint()? as i32),
+        FieldKind::Int64 => Value::String((reader.varint()? as i64).to_string()),
+        FieldKind::Uint32 => Value::from(reader.varint()? as u32),
+        FieldKind::Uint64 => Value::String(reader.varint()?.to_string()),
+        FieldKind::Sint32 => Value::from(unzigzag(reader.varint()?) as i32),
+        FieldKind::Sint64 => Value::String(unzigzag(reader.varint()?).to_string()),
+        FieldKind::Fixed32 => Value::from(u32::from_le_bytes(reader.fixed32()?)),
+        FieldKind::Fixed64 => Value::String(u64::from_le_bytes(reader.fixed64()?).to_string()),
+        FieldKind::Sfixed32 => Value::from(i32::from_le_bytes(reader.fixed32()?)),
+        FieldKind::Sfixed64 => Value::String(i64::from_le_bytes(reader.fixed64()?).to_string()),
+        FieldKind::Bool => Value::Bool(reader.varint()? != 0),
+        FieldKind::String => {
+            Value::String(String::from_utf8_lossy(reader.length_delimited()?).into_owned())
+        }
+        FieldKind::Bytes => Value::String(
+            base64::engine::general_purpose::STANDARD.encode(reader.length_delimited()?),
+        ),
+        FieldKind::Enum(name) => {
+            let number = reader.varint()? as i32;
+            schema.enums[name]
+                .values
+                .iter()
+                .find(|(_, candidate)| *candidate == number)
+                .map(|(variant, _)| Value::String(variant.clone()))
+                .unwrap_or(Value::from(number))
+        }
+        FieldKind::Message(name) => {
+            let message = schema.find_message(name)?;
+            decode_message(schema, message, reader.length_delimited()?)?
+        }
+        kind => anyhow::bail!("{kind:?} cannot be decoded"),
+    })
+}
+
+/// the value a map entry holds when its value is left out of the wire
+fn default_value(schema: &Schema, kind: &FieldKind) -> Value {
+    match kind {
+        FieldKind::String | FieldKind::Bytes => Value::String(String::new()),
+        FieldKind::Bool => Value::Bool(false),
+        FieldKind::Int64
+        | FieldKind::Uint64
+        | FieldKind::Sint64
+        | FieldKind::Fixed64
+        | FieldKind::Sfixed64 => Value::String("0".into()),
+        FieldKind::Enum(name) => schema.enums[name]
+            .values
+            .first()
+            .map(|(variant, _)| Value::String(variant.clone()))
+            .unwrap_or(Value::from(0)),
+        FieldKind::Message(_) => Value::Object(Default::default()),
+        _ => Value::from(0),
+    }
+}
+
+fn float_value(value: f64) -> Value {
+    match value {
+        value if value.is_nan() => Value::String("NaN".into()),
+        value if value == f64::INFINITY => Value::String("Infinity".into()),
+        value if value == f64::NEG_INFINITY => Value::String("-Infinity".into()),
+        value => Value::from(value),
+    }
+}
@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+
+/// snippets that are always available, user defined snippets with the same
+/// trigger take precedence over these
+pub static BUILTIN_SNIPPETS: &[(&str, &str)] = &[
+    (
+        "page",
+        "{\n  \"data\": [${1}],\n  \"page\": ${2:1},\n  \"per_page\": ${3:20},\n  \"total\": ${4:0}\n}$0",
+    ),
+    (
+        "gql",
+        "{\n  \"query\": \"query ${1:Name}(${2}) { ${3:field} }\",\n  \"variables\": {${4}}\n}$0",
+    ),
+    ("kv", "\"${1:key}\": \"${2:value}\"$0"),
+    ("obj", "{\n  ${1}\n}$0"),
+    ("arr", "[\n  ${1}\n]$0"),
+];
+
+/// a place on an expanded snippet the cursor can jump to, positions are
+/// relative to where the snippet was inserted
+#[derive(Debug, Clone, PartialEq)]
+pub struct TabStop {
+    pub index: usize,
+    /// char offset from the start of the snippet
+    pub offset: usize,
+    /// length in chars of the placeholder text, 0 when there is none
+    pub len: usize,
+}
+
+/// a parsed snippet, ready to be inserted on a buffer
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snippet {
+    pub text: String,
+    /// tab stops in the order they should be visited, `$0` is always last
+    pub tab_stops: Vec<TabStop>,
+}
+
+impl Snippet {
+    /// parses a snippet body, supported syntax is:
+    ///
+    /// - `$1`, `$2`, ...: a tab stop without placeholder text
+    /// - `${1:text}`: a tab stop with placeholder text
+    /// - `$0`: the final cursor position
+    /// - `\$`: a literal dollar sign
+    pub fn parse(body: &str) -> Snippet {
+        let chars = body.chars().collect::<Vec<_>>();
+        let mut text = String::new();
+        let mut text_len = 0;
+        let mut tab_stops = vec![];
+        let mut i = 0;
+
+        while i < chars.len() {
+            match (chars[i], chars.get(i + 1)) {
+                ('\\', Some('$')) => {
+                    text.push('$');
+                    text_len += 1;
+                    i += 2;
+                }
+                ('$', Some(c)) if c.is_ascii_digit() => {
+                    let digits = chars[i + 1..]
+                        .iter()
+                        .take_while(|c| c.is_ascii_digit())
+                        .collect::<String>();
+                    tab_stops.push(TabStop {
+                        index: digits.parse().unwrap_or_default(),
+                        offset: text_len,
+                        len: 0,
+                    });
+                    i += 1 + digits.len();
+                }
+                ('$', Some('{')) => {
+                    let rest = &chars[i + 2..];
+                    let digits = rest
+                        .iter()
+                        .take_while(|c| c.is_ascii_digit())
+                        .collect::<String>();
+                    let close = rest.iter().position(|c| c.eq(&'}'));
+                    match (digits.is_empty(), close) {
+                        (false, Some(close)) => {
+                            let placeholder = rest[digits.len()..close]
+                                .iter()
+                                .skip_while(|c| c.eq(&&':'))
+                                .collect::<String>();
+                            let len = placeholder.chars().count();
+                            tab_stops.push(TabStop {
+                                index: digits.parse().unwrap_or_default(),
+                                offset: text_len,
+                                len,
+                            });
+                            text.push_str(&placeholder);
+                            text_len += len;
+                            i += 2 + close + 1;
+                        }
+                        _ => {
+                            text.push('$');
+                            text_len += 1;
+                            i += 1;
+                        }
+                    }
+                }
+                (c, _) => {
+                    text.push(c);
+                    text_len += 1;
+                    i += 1;
+                }
+            }
+        }
+
+        // `$0` is the exit point of a snippet, so it must be visited last
+        tab_stops.sort_by_key(|stop| {
+            if stop.index.eq(&0) {
+                usize::MAX
+            } else {
+                stop.index
+            }
+        });
+
+        Snippet { text, tab_stops }
+    }
+
+    /// indents every line after the first with `indentation`, so multiline
+    /// snippets follow the indentation of the line they were inserted at
+    pub fn indented(&self, indentation: &str) -> Snippet {
+        if indentation.is_empty() {
+            return self.clone();
+        }
+
+        let mut text = String::new();
+        let mut shifts = vec![];
+        let mut added = 0;
+
+        for c in self.text.chars() {
+            shifts.push(added);
+            text.push(c);
+            if c.eq(&'\n') {
+                text.push_str(indentation);
+                added += indentation.chars().count();
+            }
+        }
+        shifts.push(added);
+
+        let tab_stops = self
+            .tab_stops
+            .iter()
+            .map(|stop| TabStop {
+                offset: stop.offset + shifts[stop.offset],
+                ..stop.clone()
+            })
+            .collect();
+
+        Snippet { text, tab_stops }
+    }
+}
+
+/// all available snippets, merging builtin snippets with the user defined
+/// ones, where user snippets win when both define the same trigger
+#[derive(Debug, Clone, Default)]
+pub struct SnippetRegistry {
+    snippets: HashMap<String, String>,
+}
+
+impl SnippetRegistry {
+    pub fn new(user_snippets: &HashMap<String, String>) -> Self {
+        let mut snippets = BUILTIN_SNIPPETS
+            .iter()
+            .map(|(trigger, body)| (trigger.to_string(), body.to_string()))
+            .collect::<HashMap<_, _>>();
+        snippets.extend(user_snippets.clone());
+
+        SnippetRegistry { snippets }
+    }
+
+    pub fn get(&self, trigger: &str) -> Option<Snippet> {
+        self.snippets.get(trigger).map(|body| Snippet::parse(body))
+    }
+
+    pub fn triggers(&self) -> Vec<&str> {
+        let mut triggers = self.snippets.keys().map(String::as_str).collect::<Vec<_>>();
+        triggers.sort();
+        triggers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tab_stops() {
+        let snippet = Snippet::parse("{\"a\": ${1:one}, \"b\": $2}$0");
+
+        assert_eq!(snippet.text, "{\"a\": one, \"b\": }");
+        assert_eq!(
+            snippet.tab_stops,
+            vec![
+                TabStop {
+                    index: 1,
+                    offset: 6,
+                    len: 3
+                },
+                TabStop {
+                    index: 2,
+                    offset: 16,
+                    len: 0
+                },
+                TabStop {
+                    index: 0,
+                    offset: 17,
+                    len: 0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_escaped_dollar() {
+        let snippet = Snippet::parse("\\$1 costs $1");
+
+        assert_eq!(snippet.text, "$1 costs ");
+        assert_eq!(snippet.tab_stops.len(), 1);
+        assert_eq!(snippet.tab_stops[0].offset, 9);
+    }
+
+    #[test]
+    fn test_indented_shifts_tab_stops() {
+        let snippet = Snippet::parse("{\n  $1\n}$0").indented("    ");
+
+        assert_eq!(snippet.text, "{\n      \n    }");
+        assert_eq!(snippet.tab_stops[0].offset, 8);
+        assert_eq!(snippet.tab_stops[1].offset, 14);
+    }
+
+    #[test]
+    fn test_user_snippets_override_builtins() {
+        let user = HashMap::from([("kv".to_string(), "$1 = $2".to_string())]);
+        let registry = SnippetRegistry::new(&user);
+
+        assert_eq!(registry.get("kv").unwrap().text, " = ");
+        assert!(registry.get("page").is_some());
+        assert!(registry.get("unknown").is_none());
+    }
+}
@@ -1 +1,4 @@
+pub mod format;
 pub mod highlighter;
+pub mod jsonc;
+pub mod xml;
@@ -0,0 +1,42 @@
+use crate::collection::types::BodyType;
+use crate::syntax::jsonc::has_jsonc_extensions;
+use crate::syntax::xml;
+
+/// the body written the way its language usually is, JSON and the JSON of
+/// GraphQL operations indented by two spaces and XML reindented. Nothing is
+/// returned for bodies that can't be parsed, like the ones still being
+/// typed, or whose comments would be lost
+pub fn format_body(body: &str, body_type: Option<&BodyType>) -> Option<String> {
+    match body_type.unwrap_or(&BodyType::Json) {
+        BodyType::Json | BodyType::GraphQl => {
+            if has_jsonc_extensions(body) {
+                return None;
+            }
+            serde_json::from_str::<serde_json::Value>(body).ok()?;
+            jsonxf::pretty_print(body).ok()
+        }
+        BodyType::Xml => xml::pretty_print(body),
+        BodyType::Binary => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_body() {
+        assert_eq!(
+            format_body("{\"b\":1,\"a\":[true]}", None).as_deref(),
+            Some("{\n  \"b\": 1,\n  \"a\": [\n    true\n  ]\n}")
+        );
+        assert_eq!(
+            format_body("<a><b>text</b></a>", Some(&BodyType::Xml)).as_deref(),
+            Some("<a>\n  <b>text</b>\n</a>")
+        );
+        assert!(format_body("{\"a\": ", None).is_none());
+        assert!(format_body("{\"id\": {{id}}}", None).is_none());
+        assert!(format_body("{\n  // kept\n  \"a\": 1\n}", None).is_none());
+        assert!(format_body("AAEC", Some(&BodyType::Binary)).is_none());
+    }
+}
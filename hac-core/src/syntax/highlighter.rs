@@ -1,3 +1,5 @@
+use crate::collection::environment::variable_spans;
+
 use std::collections::{HashMap, VecDeque};
 use std::fmt::Debug;
 use std::sync::RwLock;
@@ -78,7 +80,10 @@ impl Highlighter {
             }
         }
 
-        colors
+        match tokens.get("variable") {
+            Some(style) => highlight_variables(buffer, colors, *style),
+            None => colors,
+        }
     }
 
     pub fn find_indentation_level(tree: &Tree, cursor_byte_idx: usize) -> usize {
@@ -99,3 +104,93 @@ impl Highlighter {
         indent_level.saturating_sub(1)
     }
 }
+
+/// styles every `{{variable}}` on the buffer with the given style. Captures
+/// that overlap a variable, like the json string that contains it, are split
+/// around it so the result is still sorted and free of overlaps
+pub fn highlight_variables(
+    buffer: &str,
+    colors: VecDeque<ColorInfo>,
+    style: Style,
+) -> VecDeque<ColorInfo> {
+    let spans = variable_spans(buffer);
+    if spans.is_empty() {
+        return colors;
+    }
+
+    let mut result = colors
+        .into_iter()
+        .flat_map(|color| {
+            let mut pieces = vec![];
+            let mut start = color.start;
+            for span in spans
+                .iter()
+                .filter(|span| span.start.lt(&color.end) && span.end.gt(&color.start))
+            {
+                if span.start.gt(&start) {
+                    pieces.push(ColorInfo {
+                        start,
+                        end: span.start,
+                        style: color.style,
+                    });
+                }
+                start = start.max(span.end);
+            }
+            if start.lt(&color.end) {
+                pieces.push(ColorInfo {
+                    start,
+                    end: color.end,
+                    style: color.style,
+                });
+            }
+            pieces
+        })
+        .chain(spans.iter().map(|span| ColorInfo {
+            start: span.start,
+            end: span.end,
+            style,
+        }))
+        .collect::<Vec<_>>();
+
+    result.sort_by_key(|color| color.start);
+    result.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    #[test]
+    fn test_highlight_variables() {
+        let string = Style::new().fg(Color::Green);
+        let variable = Style::new().fg(Color::Cyan);
+        let buffer = r#""Bearer {{token}}!""#;
+        let colors = VecDeque::from([ColorInfo {
+            start: 0,
+            end: buffer.len(),
+            style: string,
+        }]);
+
+        assert_eq!(
+            highlight_variables(buffer, colors, variable),
+            VecDeque::from([
+                ColorInfo {
+                    start: 0,
+                    end: 8,
+                    style: string,
+                },
+                ColorInfo {
+                    start: 8,
+                    end: 17,
+                    style: variable,
+                },
+                ColorInfo {
+                    start: 17,
+                    end: 19,
+                    style: string,
+                },
+            ])
+        );
+    }
+}
@@ -0,0 +1,125 @@
+/// removes everything that makes a JSONC (JSON with comments) document invalid
+/// JSON, so annotated bodies can be sent to servers that only accept strict JSON.
+///
+/// - line comments (`// ...`) are removed up to the end of the line
+/// - block comments (`/* ... */`) are removed, line breaks inside of them are kept
+///   so the line count of the document doesn't change
+/// - trailing commas before a closing `}` or `]` are removed
+///
+/// anything inside of a string literal is left untouched
+pub fn strip_jsonc(content: &str) -> String {
+    let chars = content.chars().collect::<Vec<_>>();
+    let mut stripped = String::with_capacity(content.len());
+    // index on `stripped` of the last comma we found that was not followed by
+    // anything other than whitespace or comments, if we find a closing token
+    // while this is set, the comma was a trailing comma
+    let mut pending_comma: Option<usize> = None;
+    let mut in_string = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if in_string {
+            stripped.push(c);
+            match c {
+                '\\' => {
+                    if let Some(escaped) = chars.get(i + 1) {
+                        stripped.push(*escaped);
+                        i += 1;
+                    }
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            i += 1;
+            continue;
+        }
+
+        match (c, chars.get(i + 1)) {
+            ('/', Some('/')) => {
+                while i < chars.len() && !matches!(chars[i], '\n' | '\r') {
+                    i += 1;
+                }
+                continue;
+            }
+            ('/', Some('*')) => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    if matches!(chars[i], '\n' | '\r') {
+                        stripped.push(chars[i]);
+                    }
+                    i += 1;
+                }
+                i += 2;
+                continue;
+            }
+            ('"', _) => {
+                pending_comma = None;
+                in_string = true;
+                stripped.push(c);
+            }
+            (',', _) => {
+                pending_comma = Some(stripped.len());
+                stripped.push(c);
+            }
+            ('}' | ']', _) => {
+                if let Some(idx) = pending_comma.take() {
+                    stripped.remove(idx);
+                }
+                stripped.push(c);
+            }
+            (c, _) if c.is_whitespace() => stripped.push(c),
+            _ => {
+                pending_comma = None;
+                stripped.push(c);
+            }
+        }
+
+        i += 1;
+    }
+
+    stripped
+}
+
+/// whether a given content has anything that would be stripped by `strip_jsonc`
+pub fn has_jsonc_extensions(content: &str) -> bool {
+    strip_jsonc(content).ne(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_line_comments() {
+        let content = "{\n  // the user id\n  \"id\": 1 // trailing\n}";
+        let expected = "{\n  \n  \"id\": 1 \n}";
+
+        assert_eq!(strip_jsonc(content), expected);
+    }
+
+    #[test]
+    fn test_strip_block_comments_keep_lines() {
+        let content = "{ /* multi\nline */ \"id\": 1 }";
+        let expected = "{ \n \"id\": 1 }";
+
+        assert_eq!(strip_jsonc(content), expected);
+    }
+
+    #[test]
+    fn test_strip_trailing_commas() {
+        let content = "{ \"list\": [1, 2,], \"obj\": { \"a\": 1, /* note */ }, }";
+        let expected = "{ \"list\": [1, 2], \"obj\": { \"a\": 1  } }";
+
+        assert_eq!(strip_jsonc(content), expected);
+    }
+
+    #[test]
+    fn test_strings_are_left_untouched() {
+        let content = r#"{ "url": "http://example.com/*", "escaped": "\"//,]" }"#;
+
+        assert_eq!(strip_jsonc(content), content);
+        assert!(!has_jsonc_extensions(content));
+    }
+}
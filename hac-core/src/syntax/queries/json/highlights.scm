@@ -33,3 +33,5 @@
 ((escape_sequence) @conceal
   (#eq? @conceal "\\\"")
   (#set! conceal "\""))
+
+(comment) @comment
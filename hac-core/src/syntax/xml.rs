@@ -0,0 +1,547 @@
+use crate::syntax::highlighter::{highlight_variables, ColorInfo};
+
+use std::collections::{HashMap, VecDeque};
+
+use ratatui::style::Style;
+
+/// a parsed XML document, declarations and doctypes before the root element
+/// are kept as they were written
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document {
+    pub prolog: Vec<String>,
+    pub root: Element,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Element {
+    /// name of the element including its prefix, like `soap:Envelope`
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Element(Element),
+    Text(String),
+    CData(String),
+    Comment(String),
+}
+
+impl Element {
+    /// name of the element without its namespace prefix
+    pub fn local_name(&self) -> &str {
+        local_name(&self.name)
+    }
+
+    /// value of the attribute with the given name, prefixes are ignored
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(candidate, _)| local_name(candidate).eq(name) && !candidate.eq("xmlns"))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// every child element, in document order
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|child| match child {
+            Node::Element(element) => Some(element),
+            _ => None,
+        })
+    }
+
+    /// every child element with the given local name
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.elements()
+            .filter(move |element| element.local_name().eq(name))
+    }
+
+    /// the first child element with the given local name
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.elements()
+            .find(|element| element.local_name().eq(name))
+    }
+
+    /// text of the element and its descendants
+    pub fn text(&self) -> String {
+        self.children
+            .iter()
+            .map(|child| match child {
+                Node::Element(element) => element.text(),
+                Node::Text(text) | Node::CData(text) => text.clone(),
+                Node::Comment(_) => String::new(),
+            })
+            .collect()
+    }
+
+    /// every namespace declared on this element and its descendants, keyed
+    /// by prefix. The first declaration of a prefix wins
+    pub fn namespaces(&self) -> HashMap<String, String> {
+        let mut namespaces = HashMap::new();
+        self.collect_namespaces(&mut namespaces);
+        namespaces
+    }
+
+    fn collect_namespaces(&self, namespaces: &mut HashMap<String, String>) {
+        for (name, value) in self.attributes.iter() {
+            let prefix = match name.split_once(':') {
+                Some(("xmlns", prefix)) => prefix,
+                None if name.eq("xmlns") => "",
+                _ => continue,
+            };
+            namespaces
+                .entry(prefix.to_string())
+                .or_insert_with(|| value.clone());
+        }
+        self.elements()
+            .for_each(|element| element.collect_namespaces(namespaces));
+    }
+}
+
+/// the part of a qualified name after its prefix, `tns:Order` becomes `Order`
+pub fn local_name(name: &str) -> &str {
+    name.rsplit_once(':').map(|(_, name)| name).unwrap_or(name)
+}
+
+/// the prefix of a qualified name, `tns:Order` becomes `tns`
+pub fn prefix(name: &str) -> &str {
+    name.split_once(':')
+        .map(|(prefix, _)| prefix)
+        .unwrap_or_default()
+}
+
+/// whether the buffer is most likely XML rather than JSON
+pub fn looks_like_xml(buffer: &str) -> bool {
+    buffer.trim_start().starts_with('<')
+}
+
+pub fn parse(buffer: &str) -> anyhow::Result<Document> {
+    let mut parser = Parser { buffer, pos: 0 };
+    let mut prolog = vec![];
+
+    loop {
+        parser.skip_whitespace();
+        if parser.rest().starts_with("<?") || parser.rest().starts_with("<!DOCTYPE") {
+            let end = match parser.rest().starts_with("<?") {
+                true => parser.find("?>")? + 2,
+                false => parser.find(">")? + 1,
+            };
+            prolog.push(parser.rest()[..end].to_string());
+            parser.pos += end;
+        } else if parser.rest().starts_with("<!--") {
+            let end = parser.find("-->")? + 3;
+            prolog.push(parser.rest()[..end].to_string());
+            parser.pos += end;
+        } else {
+            break;
+        }
+    }
+
+    if !parser.rest().starts_with('<') {
+        anyhow::bail!("expected the root element at {}", parser.position());
+    }
+    let root = parser.element()?;
+    Ok(Document { prolog, root })
+}
+
+/// reindents the document with two spaces, elements holding only text are
+/// kept on a single line. Invalid documents yield nothing
+pub fn pretty_print(buffer: &str) -> Option<String> {
+    let document = parse(buffer).ok()?;
+    let mut out = String::new();
+    for line in document.prolog.iter() {
+        out.push_str(line);
+        out.push('\n');
+    }
+    write_element(&document.root, 0, &mut out);
+    Some(out.trim_end().to_string())
+}
+
+/// writes the element the same way `pretty_print` does
+pub fn render(element: &Element) -> String {
+    let mut out = String::new();
+    write_element(element, 0, &mut out);
+    out.trim_end().to_string()
+}
+
+fn write_element(element: &Element, depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth);
+    out.push_str(&format!("{indent}<{}", element.name));
+    for (name, value) in element.attributes.iter() {
+        out.push_str(&format!(" {name}=\"{}\"", escape(value, true)));
+    }
+
+    let children = element
+        .children
+        .iter()
+        .filter(|child| !matches!(child, Node::Text(text) if text.trim().is_empty()))
+        .collect::<Vec<_>>();
+
+    match children.as_slice() {
+        [] => out.push_str("/>\n"),
+        [Node::Text(text)] => out.push_str(&format!(
+            ">{}</{}>\n",
+            escape(text.trim(), false),
+            element.name
+        )),
+        children => {
+            out.push_str(">\n");
+            for child in children {
+                match child {
+                    Node::Element(element) => write_element(element, depth + 1, out),
+                    Node::Text(text) => {
+                        out.push_str(&format!("{indent}  {}\n", escape(text.trim(), false)))
+                    }
+                    Node::CData(text) => out.push_str(&format!("{indent}  <![CDATA[{text}]]>\n")),
+                    Node::Comment(text) => out.push_str(&format!("{indent}  <!--{text}-->\n")),
+                }
+            }
+            out.push_str(&format!("{indent}</{}>\n", element.name));
+        }
+    }
+}
+
+pub fn escape(text: &str, attribute: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if attribute => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(idx) = rest.find('&') {
+        out.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+struct Parser<'a> {
+    buffer: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.buffer[self.pos..]
+    }
+
+    fn position(&self) -> String {
+        let line = self.buffer[..self.pos].matches('\n').count() + 1;
+        format!("line {line}")
+    }
+
+    fn find(&self, pattern: &str) -> anyhow::Result<usize> {
+        self.rest()
+            .find(pattern)
+            .ok_or_else(|| anyhow::anyhow!("expected `{pattern}` after {}", self.position()))
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn name(&mut self) -> anyhow::Result<String> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '=' | '<'))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            anyhow::bail!("expected a name at {}", self.position());
+        }
+        self.pos += len;
+        Ok(rest[..len].to_string())
+    }
+
+    /// parses an element starting at its `<`
+    fn element(&mut self) -> anyhow::Result<Element> {
+        self.pos += 1;
+        let mut element = Element {
+            name: self.name()?,
+            ..Default::default()
+        };
+
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.pos += 2;
+                return Ok(element);
+            }
+            if rest.starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            if rest.is_empty() {
+                anyhow::bail!("element {} is never closed", element.name);
+            }
+
+            let name = self.name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                anyhow::bail!("expected a value for {name} at {}", self.position());
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let quote = self
+                .rest()
+                .chars()
+                .next()
+                .filter(|c| matches!(c, '"' | '\''));
+            let Some(quote) = quote else {
+                anyhow::bail!("expected a quoted value for {name} at {}", self.position());
+            };
+            self.pos += 1;
+            let end = self.find(&quote.to_string())?;
+            element
+                .attributes
+                .push((name, unescape(&self.rest()[..end])));
+            self.pos += end + 1;
+        }
+
+        loop {
+            let rest = self.rest();
+            if rest.is_empty() {
+                anyhow::bail!("element {} is never closed", element.name);
+            }
+            if let Some(closing) = rest.strip_prefix("</") {
+                let end = self.find(">")?;
+                let name = closing[..end - 2].trim();
+                if name.ne(&element.name) {
+                    anyhow::bail!(
+                        "expected </{}> but found </{name}> at {}",
+                        element.name,
+                        self.position()
+                    );
+                }
+                self.pos += end + 1;
+                return Ok(element);
+            }
+            if let Some(comment) = rest.strip_prefix("<!--") {
+                let end = self.find("-->")?;
+                element
+                    .children
+                    .push(Node::Comment(comment[..end - 4].to_string()));
+                self.pos += end + 3;
+                continue;
+            }
+            if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+                let end = self.find("]]>")?;
+                element
+                    .children
+                    .push(Node::CData(cdata[..end - 9].to_string()));
+                self.pos += end + 3;
+                continue;
+            }
+            if rest.starts_with("<?") {
+                self.pos += self.find("?>")? + 2;
+                continue;
+            }
+            if rest.starts_with('<') {
+                let child = self.element()?;
+                element.children.push(Node::Element(child));
+                continue;
+            }
+
+            let end = rest.find('<').unwrap_or(rest.len());
+            element.children.push(Node::Text(unescape(&rest[..end])));
+            self.pos += end;
+        }
+    }
+}
+
+/// highlights tags, attributes and comments. Unlike JSON there is no grammar
+/// for XML, so the buffer is scanned directly, which also copes with the
+/// incomplete documents found while editing
+pub fn highlight(buffer: &str, tokens: &HashMap<String, Style>) -> VecDeque<ColorInfo> {
+    let mut colors = VecDeque::new();
+    let mut push = |start: usize, end: usize, token: &str| {
+        if let (Some(style), true) = (tokens.get(token), start.lt(&end)) {
+            colors.push_back(ColorInfo {
+                start,
+                end,
+                style: *style,
+            });
+        }
+    };
+
+    let bytes = buffer.as_bytes();
+    let mut pos = 0;
+    while let Some(offset) = buffer[pos..].find('<') {
+        let start = pos + offset;
+        let rest = &buffer[start..];
+
+        let special = [
+            ("<!--", "-->"),
+            ("<![CDATA[", "]]>"),
+            ("<?", "?>"),
+            ("<!", ">"),
+        ]
+        .into_iter()
+        .find(|(open, _)| rest.starts_with(open));
+        if let Some((open, close)) = special {
+            let end = rest[open.len()..]
+                .find(close)
+                .map(|end| start + open.len() + end + close.len())
+                .unwrap_or(buffer.len());
+            match open {
+                "<![CDATA[" => push(start, end, "string"),
+                _ => push(start, end, "comment"),
+            }
+            pos = end;
+            continue;
+        }
+
+        let name_start = match rest.starts_with("</") {
+            true => start + 2,
+            false => start + 1,
+        };
+        push(start, name_start, "punctuation.bracket");
+        let mut idx = name_start;
+        while idx < bytes.len() && !is_tag_delimiter(bytes[idx]) {
+            idx += 1;
+        }
+        push(name_start, idx, "tag");
+
+        // attributes, up to the end of the tag
+        while idx < bytes.len() && bytes[idx] != b'>' && bytes[idx] != b'<' {
+            match bytes[idx] {
+                b'/' => {
+                    push(idx, idx + 1, "punctuation.bracket");
+                    idx += 1;
+                }
+                b'=' => {
+                    push(idx, idx + 1, "punctuation.delimiter");
+                    idx += 1;
+                }
+                quote @ (b'"' | b'\'') => {
+                    let end = buffer[idx + 1..]
+                        .find(quote as char)
+                        .map(|end| idx + end + 2)
+                        .unwrap_or(buffer.len());
+                    push(idx, end, "string");
+                    idx = end;
+                }
+                c if c.is_ascii_whitespace() => idx += 1,
+                _ => {
+                    let attribute_start = idx;
+                    while idx < bytes.len() && !is_tag_delimiter(bytes[idx]) && bytes[idx] != b'=' {
+                        idx += 1;
+                    }
+                    push(attribute_start, idx, "property");
+                }
+            }
+        }
+        if idx < bytes.len() && bytes[idx] == b'>' {
+            push(idx, idx + 1, "punctuation.bracket");
+            idx += 1;
+        }
+        pos = idx;
+    }
+
+    match tokens.get("variable") {
+        Some(style) => highlight_variables(buffer, colors, *style),
+        None => colors,
+    }
+}
+
+fn is_tag_delimiter(byte: u8) -> bool {
+    byte.is_ascii_whitespace() || matches!(byte, b'>' | b'/' | b'<')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_pretty_print() {
+        let buffer = r#"<?xml version="1.0"?><soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/"><soap:Body><m:Price xmlns:m="urn:shop"><m:Item a="1 &amp; 2">tea &lt;green&gt;</m:Item><!-- free --><m:Empty></m:Empty><![CDATA[<raw>]]></m:Price></soap:Body></soap:Envelope>"#;
+
+        let document = parse(buffer).unwrap();
+        assert_eq!(document.prolog, vec![r#"<?xml version="1.0"?>"#]);
+        let price = document.root.child("Body").unwrap().child("Price").unwrap();
+        let item = price.child("Item").unwrap();
+        assert_eq!(item.attribute("a"), Some("1 & 2"));
+        assert_eq!(item.text(), "tea <green>");
+        assert_eq!(document.root.namespaces()["m"], "urn:shop");
+
+        assert_eq!(
+            pretty_print(buffer).unwrap(),
+            r#"<?xml version="1.0"?>
+<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">
+  <soap:Body>
+    <m:Price xmlns:m="urn:shop">
+      <m:Item a="1 &amp; 2">tea &lt;green&gt;</m:Item>
+      <!-- free -->
+      <m:Empty/>
+      <![CDATA[<raw>]]>
+    </m:Price>
+  </soap:Body>
+</soap:Envelope>"#
+        );
+
+        assert!(parse("<a><b></a>").is_err());
+        assert!(pretty_print("{\"json\": true}").is_none());
+    }
+
+    #[test]
+    fn test_highlight() {
+        let tokens = [
+            "tag",
+            "property",
+            "string",
+            "comment",
+            "punctuation.bracket",
+        ]
+        .into_iter()
+        .map(|token| (token.to_string(), Style::new()))
+        .collect::<HashMap<_, _>>();
+        let buffer = r#"<a x="1"><!-- c --></a>"#;
+
+        let ranges = highlight(buffer, &tokens)
+            .into_iter()
+            .map(|color| &buffer[color.start..color.end])
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ranges,
+            vec!["<", "a", "x", "\"1\"", ">", "<!-- c -->", "</", "a", ">"]
+        );
+    }
+}
@@ -1,5 +1,29 @@
 pub mod cursor;
+pub mod edit_recorder;
+pub mod hex_buffer;
+pub mod line_numbers;
+pub mod macros;
+pub mod marks;
+pub mod registers;
+pub mod scope;
+pub mod search;
+pub mod selection;
+pub mod string_edit;
+pub mod substitute;
 #[allow(clippy::module_inception)]
 mod text_object;
+pub mod viewport;
 
+pub use edit_recorder::EditRecorder;
+pub use hex_buffer::{HexBuffer, HexWrite};
+pub use line_numbers::LineNumber;
+pub use macros::Macros;
+pub use marks::Marks;
+pub use registers::{Register, Registers};
+pub use scope::TextScope;
+pub use search::{SearchDirection, SearchMatch};
+pub use selection::{Selection, SelectionKind};
+pub use string_edit::StringEdit;
+pub use substitute::{Substitute, SubstituteMatch, SubstituteScope};
 pub use text_object::{Readonly, TextObject, Write};
+pub use viewport::Viewport;
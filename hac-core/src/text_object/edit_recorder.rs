@@ -0,0 +1,109 @@
+use hac_config::{Action, EditorMode};
+
+/// records the last edit made on normal mode so it can be repeated with `.`
+/// like on vim. An edit is every action of a key press, so keymaps that
+/// move before changing the buffer are repeated whole, and edits that enter
+/// insert mode also hold everything typed until normal mode is back
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EditRecorder {
+    last: Vec<Action>,
+    /// the edit being recorded while on insert mode, if any
+    recording: Option<Vec<Action>>,
+}
+
+impl EditRecorder {
+    /// records the actions of a key press, with the mode of the editor
+    /// before and after they ran
+    pub fn record(&mut self, actions: &[Action], before: &EditorMode, after: &EditorMode) {
+        if let Some(recording) = self.recording.as_mut() {
+            recording.extend_from_slice(actions);
+            if after.eq(&EditorMode::Normal) {
+                self.last = self.recording.take().unwrap_or_default();
+            }
+            return;
+        }
+
+        if before.ne(&EditorMode::Normal) || !actions.iter().any(is_repeatable) {
+            return;
+        }
+
+        match after {
+            EditorMode::Insert => self.recording = Some(actions.to_vec()),
+            EditorMode::Normal => self.last = actions.to_vec(),
+        }
+    }
+
+    /// records text inserted at once while on insert mode, like a paste, as
+    /// if it was typed
+    pub fn record_text(&mut self, text: &str) {
+        let Some(recording) = self.recording.as_mut() else {
+            return;
+        };
+        recording.extend(text.chars().map(|c| match c {
+            '\n' => Action::InsertLine,
+            c => Action::InsertChar(c),
+        }));
+    }
+
+    /// the actions of the last complete edit, empty when nothing was edited
+    pub fn last_edit(&self) -> &[Action] {
+        &self.last
+    }
+}
+
+/// actions that change the buffer but aren't edits to be repeated, as they
+/// undo, open something else or repeat themselves
+fn is_repeatable(action: &Action) -> bool {
+    action.modifies_buffer()
+        && !matches!(
+            action,
+            Action::Undo
+                | Action::EditString
+                | Action::ToggleHexMode
+                | Action::CommandLine
+                | Action::RepeatEdit
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_last_edit() {
+        let normal = EditorMode::Normal;
+        let insert = EditorMode::Insert;
+        let mut recorder = EditRecorder::default();
+
+        recorder.record(&[Action::MoveDown], &normal, &normal);
+        assert!(recorder.last_edit().is_empty());
+
+        // a motion and an operator on the same key are a single edit
+        let delete = [Action::MoveDown, Action::DeleteLine];
+        recorder.record(&delete, &normal, &normal);
+        assert_eq!(recorder.last_edit(), delete);
+
+        // the edit isn't complete until insert mode is left
+        recorder.record(&[Action::InsertLineBelow], &normal, &insert);
+        recorder.record(&[Action::InsertChar('a')], &insert, &insert);
+        recorder.record_text("b\nc");
+        assert_eq!(recorder.last_edit(), delete);
+        recorder.record(&[Action::EnterMode(normal.clone())], &insert, &normal);
+        assert_eq!(
+            recorder.last_edit(),
+            [
+                Action::InsertLineBelow,
+                Action::InsertChar('a'),
+                Action::InsertChar('b'),
+                Action::InsertLine,
+                Action::InsertChar('c'),
+                Action::EnterMode(EditorMode::Normal),
+            ]
+        );
+
+        recorder.record(&[Action::Undo], &normal, &normal);
+        recorder.record(&[Action::RepeatEdit], &normal, &normal);
+        recorder.record_text("ignored");
+        assert_eq!(recorder.last_edit().len(), 6);
+    }
+}
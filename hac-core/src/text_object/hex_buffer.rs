@@ -0,0 +1,211 @@
+use std::ops::{Add, Sub};
+
+use base64::Engine;
+
+/// how many bytes are shown on each row of the hex editor
+pub const BYTES_PER_ROW: usize = 16;
+
+/// whether typed bytes replace the one under the cursor or are inserted
+/// before it
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum HexWrite {
+    #[default]
+    Overwrite,
+    Insert,
+}
+
+/// the bytes of a binary body being edited one nibble at a time, with the
+/// cursor on a byte. The cursor can sit right after the last byte, where
+/// typed bytes are appended
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct HexBuffer {
+    bytes: Vec<u8>,
+    cursor: usize,
+    /// whether the high nibble of the byte under the cursor was just typed,
+    /// so the next digit completes it
+    half_typed: bool,
+}
+
+/// a row of the hex editor
+#[derive(Debug, PartialEq)]
+pub struct HexRow<'a> {
+    /// position of the first byte of the row on the buffer
+    pub offset: usize,
+    pub bytes: &'a [u8],
+}
+
+impl HexRow<'_> {
+    /// the bytes as printable ASCII, with a `.` for everything else
+    pub fn ascii(&self) -> String {
+        self.bytes
+            .iter()
+            .map(|byte| match byte.is_ascii_graphic() || byte.eq(&b' ') {
+                true => char::from(*byte),
+                false => '.',
+            })
+            .collect()
+    }
+}
+
+impl HexBuffer {
+    pub fn new(bytes: Vec<u8>) -> HexBuffer {
+        HexBuffer {
+            bytes,
+            ..Default::default()
+        }
+    }
+
+    /// the bytes of a body stored as base64, bodies that aren't base64 are
+    /// taken as text
+    pub fn from_base64(body: &str) -> HexBuffer {
+        HexBuffer::new(decode_body(body))
+    }
+
+    /// how the bytes are stored on the body of the request
+    pub fn to_base64(&self) -> String {
+        base64::engine::general_purpose::STANDARD.encode(&self.bytes)
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// whether only the high nibble of the byte under the cursor was typed
+    pub fn is_half_typed(&self) -> bool {
+        self.half_typed
+    }
+
+    pub fn rows(&self) -> Vec<HexRow<'_>> {
+        self.bytes
+            .chunks(BYTES_PER_ROW)
+            .enumerate()
+            .map(|(idx, bytes)| HexRow {
+                offset: idx * BYTES_PER_ROW,
+                bytes,
+            })
+            .collect()
+    }
+
+    /// moves the cursor by `amount` bytes, negative amounts go backwards
+    pub fn move_by(&mut self, amount: isize) {
+        self.move_to(self.cursor.saturating_add_signed(amount));
+    }
+
+    /// moves the cursor to the byte, or after the last one
+    pub fn move_to(&mut self, position: usize) {
+        self.cursor = position.min(self.bytes.len());
+        self.half_typed = false;
+    }
+
+    pub fn move_to_row_start(&mut self) {
+        self.move_to(self.cursor.sub(self.cursor % BYTES_PER_ROW));
+    }
+
+    pub fn move_to_row_end(&mut self) {
+        let row_start = self.cursor.sub(self.cursor % BYTES_PER_ROW);
+        self.move_to(row_start.add(BYTES_PER_ROW).sub(1));
+    }
+
+    /// writes a hex digit on the byte under the cursor, the high nibble
+    /// first. Anything other than a hex digit is ignored
+    pub fn type_nibble(&mut self, digit: char, write: HexWrite) {
+        let Some(nibble) = digit.to_digit(16).map(|nibble| nibble as u8) else {
+            return;
+        };
+
+        if self.half_typed {
+            self.bytes[self.cursor] = self.bytes[self.cursor] & 0xf0 | nibble;
+            self.half_typed = false;
+            self.cursor = self.cursor.add(1);
+            return;
+        }
+
+        match (write, self.bytes.get_mut(self.cursor)) {
+            (HexWrite::Overwrite, Some(byte)) => *byte = *byte & 0x0f | nibble << 4,
+            _ => self.bytes.insert(self.cursor, nibble << 4),
+        }
+        self.half_typed = true;
+    }
+
+    /// writes the bytes of a char typed on the ASCII gutter
+    pub fn type_char(&mut self, c: char, write: HexWrite) {
+        let mut buf = [0; 4];
+        for byte in c.encode_utf8(&mut buf).bytes() {
+            match (write, self.bytes.get_mut(self.cursor)) {
+                (HexWrite::Overwrite, Some(current)) => *current = byte,
+                _ => self.bytes.insert(self.cursor, byte),
+            }
+            self.cursor = self.cursor.add(1);
+        }
+        self.half_typed = false;
+    }
+
+    /// removes the byte under the cursor
+    pub fn delete(&mut self) {
+        if self.cursor.lt(&self.bytes.len()) {
+            self.bytes.remove(self.cursor);
+        }
+        self.half_typed = false;
+    }
+
+    /// removes the byte before the cursor
+    pub fn delete_previous(&mut self) {
+        if self.cursor.gt(&0) {
+            self.move_by(-1);
+            self.delete();
+        }
+    }
+}
+
+/// the bytes sent for a binary body, which is stored as base64. Bodies that
+/// aren't base64 are sent as the text they hold
+pub fn decode_body(body: &str) -> Vec<u8> {
+    let compact = body.split_whitespace().collect::<String>();
+    base64::engine::general_purpose::STANDARD
+        .decode(compact)
+        .unwrap_or_else(|_| body.as_bytes().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_bytes() {
+        let mut buffer = HexBuffer::from_base64("AAEC");
+        assert_eq!(buffer.bytes(), &[0, 1, 2]);
+
+        buffer.type_nibble('f', HexWrite::Overwrite);
+        buffer.type_nibble('F', HexWrite::Overwrite);
+        buffer.type_nibble('a', HexWrite::Insert);
+        buffer.type_nibble('z', HexWrite::Insert);
+        buffer.type_nibble('b', HexWrite::Insert);
+        assert_eq!(buffer.bytes(), &[0xff, 0xab, 1, 2]);
+        assert_eq!(buffer.cursor(), 2);
+
+        buffer.move_by(10);
+        buffer.type_char('h', HexWrite::Overwrite);
+        buffer.type_char('i', HexWrite::Overwrite);
+        buffer.delete_previous();
+        buffer.move_to(1);
+        buffer.delete();
+        assert_eq!(buffer.bytes(), b"\xff\x01\x02h");
+        assert_eq!(buffer.to_base64(), "/wECaA==");
+
+        let buffer = HexBuffer::new(b"0123456789abcdef\x00tail".to_vec());
+        let rows = buffer.rows();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].offset, 16);
+        assert_eq!(rows[1].ascii(), ".tail");
+
+        assert_eq!(decode_body("aGk=\n"), b"hi");
+        assert_eq!(
+            decode_body("{\"not\": \"base64\"}"),
+            b"{\"not\": \"base64\"}"
+        );
+    }
+}
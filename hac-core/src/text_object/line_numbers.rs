@@ -0,0 +1,83 @@
+use hac_config::LineNumbers;
+
+/// the number of a line drawn on the gutter of the editor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineNumber {
+    /// the row of the line, starting at 0
+    pub row: usize,
+    /// the number of the line, starting at 1
+    pub absolute: usize,
+    /// how many lines it is away from the line of the cursor
+    pub relative: usize,
+}
+
+impl LineNumber {
+    pub fn new(row: usize, cursor_row: usize) -> Self {
+        LineNumber {
+            row,
+            absolute: row + 1,
+            relative: row.abs_diff(cursor_row),
+        }
+    }
+
+    pub fn is_cursor_line(&self) -> bool {
+        self.relative.eq(&0)
+    }
+
+    /// the number shown for the line, right aligned to the width of the
+    /// gutter without its separating space
+    pub fn label(&self, numbers: LineNumbers, width: usize) -> String {
+        let number = match numbers {
+            LineNumbers::Off => return String::default(),
+            LineNumbers::Absolute => self.absolute,
+            LineNumbers::Relative => self.relative,
+            LineNumbers::Hybrid if self.is_cursor_line() => self.absolute,
+            LineNumbers::Hybrid => self.relative,
+        };
+        format!("{number:>width$}", width = width.saturating_sub(1))
+    }
+}
+
+/// how many columns the gutter takes for a buffer of `len_lines`, with a
+/// space separating the numbers from the text. Like vim, numbers always get
+/// room for at least 3 digits
+pub fn gutter_width(numbers: LineNumbers, len_lines: usize) -> usize {
+    match numbers {
+        LineNumbers::Off => 0,
+        _ => len_lines.max(1).to_string().len().max(3) + 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text_object::cursor::Cursor;
+    use crate::text_object::TextObject;
+
+    #[test]
+    fn test_line_numbers_in_view() {
+        let body = (0..1200).map(|i| i.to_string()).collect::<Vec<_>>();
+        let text = TextObject::from(&body.join("\n")).with_write();
+        let mut cursor = Cursor::default();
+        cursor.move_to_row(1000);
+
+        let numbers = text.line_numbers(998, 4, &cursor);
+        assert_eq!(numbers.len(), 4);
+        assert_eq!(numbers[0], LineNumber::new(998, 1000));
+        assert_eq!(numbers[0].relative, 2);
+        assert!(numbers[2].is_cursor_line());
+
+        let width = gutter_width(LineNumbers::Hybrid, text.len_lines());
+        assert_eq!(width, 5);
+        assert_eq!(numbers[0].label(LineNumbers::Absolute, width), " 999");
+        assert_eq!(numbers[0].label(LineNumbers::Relative, width), "   2");
+        assert_eq!(numbers[2].label(LineNumbers::Relative, width), "   0");
+        assert_eq!(numbers[2].label(LineNumbers::Hybrid, width), "1001");
+        assert_eq!(numbers[3].label(LineNumbers::Hybrid, width), "   1");
+
+        // past the end of the buffer there is nothing to number
+        assert_eq!(text.line_numbers(1198, 10, &cursor).len(), 2);
+        assert_eq!(gutter_width(LineNumbers::Absolute, 1), 4);
+        assert_eq!(gutter_width(LineNumbers::Off, 1200), 0);
+    }
+}
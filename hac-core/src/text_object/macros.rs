@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+/// keys recorded on registers to be played back, like the macros of vim.
+/// `qa` starts recording on `a`, `q` stops it and `@a` plays it. Keys are
+/// whatever the editor feeds itself, so they are kept as they were given
+#[derive(Debug, Clone)]
+pub struct Macros<K> {
+    registers: HashMap<char, Vec<K>>,
+    /// the register being recorded and the keys pressed so far
+    recording: Option<(char, Vec<K>)>,
+    /// the register played last, played again by `@@`
+    last_played: Option<char>,
+}
+
+impl<K> Default for Macros<K> {
+    fn default() -> Self {
+        Macros {
+            registers: HashMap::default(),
+            recording: None,
+            last_played: None,
+        }
+    }
+}
+
+impl<K: Clone> Macros<K> {
+    /// starts recording on the register, `a` to `z`. An uppercase name
+    /// appends to what the register holds, like yanks do
+    pub fn start(&mut self, name: char) -> bool {
+        if !name.is_ascii_alphabetic() {
+            return false;
+        }
+        let keys = match name.is_ascii_uppercase() {
+            true => self.get(name).map(<[K]>::to_vec).unwrap_or_default(),
+            false => vec![],
+        };
+        self.recording = Some((name.to_ascii_lowercase(), keys));
+        true
+    }
+
+    /// stops recording, storing the keys on the register
+    pub fn stop(&mut self) {
+        if let Some((name, keys)) = self.recording.take() {
+            self.registers.insert(name, keys);
+        }
+    }
+
+    pub fn record(&mut self, key: K) {
+        if let Some((_, keys)) = self.recording.as_mut() {
+            keys.push(key);
+        }
+    }
+
+    /// the register being recorded, if any
+    pub fn recording(&self) -> Option<char> {
+        self.recording.as_ref().map(|(name, _)| *name)
+    }
+
+    pub fn get(&self, name: char) -> Option<&[K]> {
+        self.registers
+            .get(&name.to_ascii_lowercase())
+            .map(Vec::as_slice)
+    }
+
+    /// the keys to be played for the register, `@` is the one played last
+    pub fn play(&mut self, name: char) -> Option<Vec<K>> {
+        let name = match name {
+            '@' => self.last_played?,
+            name => name.to_ascii_lowercase(),
+        };
+        let keys = self.get(name)?.to_vec();
+        self.last_played = Some(name);
+        Some(keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_play_macros() {
+        let mut macros = Macros::<&str>::default();
+        assert!(!macros.start('1'));
+        assert!(macros.play('@').is_none());
+
+        assert!(macros.start('a'));
+        assert_eq!(macros.recording(), Some('a'));
+        ["j", "0", "x"]
+            .into_iter()
+            .for_each(|key| macros.record(key));
+        macros.stop();
+        assert_eq!(macros.recording(), None);
+        macros.record("ignored");
+        assert_eq!(macros.get('a'), Some(["j", "0", "x"].as_slice()));
+
+        // uppercase appends to the register
+        macros.start('A');
+        macros.record("w");
+        macros.stop();
+        assert_eq!(macros.play('a').unwrap(), ["j", "0", "x", "w"]);
+        assert_eq!(macros.play('@').unwrap(), ["j", "0", "x", "w"]);
+
+        // recording again replaces it
+        macros.start('a');
+        macros.stop();
+        assert_eq!(macros.play('A').unwrap(), Vec::<&str>::new());
+        assert!(macros.play('b').is_none());
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use std::ops::{Add, Sub};
+
+/// how many positions the jumplist remembers
+const MAX_JUMPS: usize = 100;
+
+/// the named marks and the jumplist of a buffer, like the ones of vim.
+/// Positions are char indexes anchored to the content, so text inserted or
+/// removed before them moves them along with the text they point at
+#[derive(Debug, Default, Clone)]
+pub struct Marks {
+    named: HashMap<char, usize>,
+    jumps: Vec<usize>,
+    /// where on the jumplist we are, its length when not going through it
+    jump_idx: usize,
+    /// the content positions are anchored to
+    content: String,
+}
+
+impl Marks {
+    /// moves every position to where its text is on the content, which
+    /// changed from the one they were anchored to. Positions inside of text
+    /// that was removed go to where it was
+    pub fn anchor(&mut self, content: &str) {
+        if self.content.eq(content) {
+            return;
+        }
+
+        let (at, removed, inserted) = changed_range(&self.content, content);
+        self.named
+            .values_mut()
+            .chain(self.jumps.iter_mut())
+            .for_each(|position| {
+                *position = match *position {
+                    position if position.lt(&at) => position,
+                    position if position.ge(&at.add(removed)) => {
+                        position.sub(removed).add(inserted)
+                    }
+                    _ => at,
+                }
+            });
+        self.content = content.to_string();
+    }
+
+    /// marks the position with a name from `a` to `z`
+    pub fn set(&mut self, name: char, position: usize) -> bool {
+        if !name.is_ascii_lowercase() {
+            return false;
+        }
+        self.named.insert(name, position);
+        true
+    }
+
+    /// the position of the mark, `` ` `` is where the last jump started
+    pub fn get(&self, name: char) -> Option<usize> {
+        match name {
+            '`' => self.jumps.get(self.jump_idx.saturating_sub(1)).copied(),
+            name => self.named.get(&name).copied(),
+        }
+    }
+
+    /// remembers the position a jump starts from. Jumping from the middle
+    /// of the jumplist forgets the positions that were ahead
+    pub fn push_jump(&mut self, from: usize) {
+        self.jumps.truncate(self.jump_idx);
+        self.jumps.retain(|jump| jump.ne(&from));
+        self.jumps.push(from);
+        if self.jumps.len().gt(&MAX_JUMPS) {
+            self.jumps.remove(0);
+        }
+        self.jump_idx = self.jumps.len();
+    }
+
+    /// the position before the current one on the jumplist, like `ctrl-o`.
+    /// Where we are is remembered first, so jumping forward comes back here
+    pub fn jump_back(&mut self, current: usize) -> Option<usize> {
+        if self.jump_idx.eq(&0) {
+            return None;
+        }
+        if self.jump_idx.eq(&self.jumps.len()) {
+            self.push_jump(current);
+            self.jump_idx = self.jumps.len().sub(1);
+        }
+        self.jump_idx = self.jump_idx.saturating_sub(1);
+        self.jumps.get(self.jump_idx).copied()
+    }
+
+    /// the position after the current one on the jumplist, like `ctrl-i`
+    pub fn jump_forward(&mut self) -> Option<usize> {
+        if self.jump_idx.add(1).ge(&self.jumps.len()) {
+            return None;
+        }
+        self.jump_idx = self.jump_idx.add(1);
+        self.jumps.get(self.jump_idx).copied()
+    }
+}
+
+/// where the content changed, as the char index of the change, how many
+/// chars were removed there and how many were inserted
+fn changed_range(before: &str, after: &str) -> (usize, usize, usize) {
+    let before = before.chars().collect::<Vec<_>>();
+    let after = after.chars().collect::<Vec<_>>();
+    let prefix = before
+        .iter()
+        .zip(after.iter())
+        .take_while(|(a, b)| a.eq(b))
+        .count();
+    let suffix = before[prefix..]
+        .iter()
+        .rev()
+        .zip(after[prefix..].iter().rev())
+        .take_while(|(a, b)| a.eq(b))
+        .count();
+    (
+        prefix,
+        before.len().sub(prefix).sub(suffix),
+        after.len().sub(prefix).sub(suffix),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_marks_follow_edits() {
+        let body = "{\n  \"id\": 1,\n  \"name\": \"a\"\n}";
+        let mut marks = Marks::default();
+        marks.anchor(body);
+        assert!(!marks.set('1', 0));
+        marks.set('a', body.find("name").unwrap());
+        marks.set('b', body.find("1,").unwrap());
+
+        // text inserted before a mark moves it along
+        let edited = body.replace("\"id\": 1", "\"id\": 12345");
+        marks.anchor(&edited);
+        assert_eq!(marks.get('a'), edited.find("name"));
+        assert_eq!(marks.get('b'), edited.find("12345"));
+
+        // a mark inside of removed text goes to where it was
+        let edited = edited.replace("  \"id\": 12345,\n", "");
+        marks.anchor(&edited);
+        assert_eq!(marks.get('a'), edited.find("name"));
+        assert_eq!(marks.get('b'), edited.find("name"));
+        assert_eq!(marks.get('c'), None);
+    }
+
+    #[test]
+    fn test_jumplist() {
+        let mut marks = Marks::default();
+        assert_eq!(marks.jump_back(0), None);
+
+        marks.push_jump(10);
+        marks.push_jump(20);
+        assert_eq!(marks.get('`'), Some(20));
+        assert_eq!(marks.jump_back(30), Some(20));
+        assert_eq!(marks.jump_back(20), Some(10));
+        assert_eq!(marks.jump_back(10), None);
+        assert_eq!(marks.jump_forward(), Some(20));
+        assert_eq!(marks.jump_forward(), Some(30));
+        assert_eq!(marks.jump_forward(), None);
+
+        // jumping from the middle forgets what was ahead
+        marks.jump_back(30);
+        marks.push_jump(40);
+        assert_eq!(marks.jump_back(50), Some(40));
+        assert_eq!(marks.jump_back(40), Some(10));
+        assert_eq!(marks.jump_back(10), None);
+    }
+}
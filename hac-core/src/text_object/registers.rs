@@ -0,0 +1,133 @@
+use std::collections::{HashMap, VecDeque};
+
+/// how many deletes are kept on the numbered registers, `1` to `9`
+const DELETE_HISTORY: usize = 9;
+
+/// text that was yanked or deleted
+#[derive(Debug, Clone, PartialEq)]
+pub struct Register {
+    pub text: String,
+    /// whether the text is made of whole lines, which are pasted on their
+    /// own lines instead of inside of the current one
+    pub linewise: bool,
+}
+
+/// where yanked and deleted text goes, like the registers of vim:
+///
+/// - the default register, `"`, holds whatever was yanked or deleted last
+/// - `0` holds the last yank, and `1` to `9` the last deletes, newest first
+/// - `a` to `z` are only written when named, `A` to `Z` append to them
+#[derive(Debug, Default)]
+pub struct Registers {
+    unnamed: Option<Register>,
+    yanked: Option<Register>,
+    deleted: VecDeque<Register>,
+    named: HashMap<char, Register>,
+}
+
+impl Registers {
+    /// stores yanked text on the named register, or on `0` when there is
+    /// no name
+    pub fn yank(&mut self, name: Option<char>, register: Register) {
+        match name.filter(|name| name.is_ascii_alphabetic()) {
+            Some(name) => self.write_named(name, register),
+            None => {
+                self.yanked = Some(register.clone());
+                self.unnamed = Some(register);
+            }
+        }
+    }
+
+    /// stores deleted text on the named register, or on the delete history
+    /// when there is no name
+    pub fn delete(&mut self, name: Option<char>, register: Register) {
+        match name.filter(|name| name.is_ascii_alphabetic()) {
+            Some(name) => self.write_named(name, register),
+            None => {
+                self.deleted.push_front(register.clone());
+                self.deleted.truncate(DELETE_HISTORY);
+                self.unnamed = Some(register);
+            }
+        }
+    }
+
+    /// the contents of the register, the default one when there is no name
+    pub fn get(&self, name: Option<char>) -> Option<&Register> {
+        match name.unwrap_or('"') {
+            '"' => self.unnamed.as_ref(),
+            '0' => self.yanked.as_ref(),
+            name @ '1'..='9' => {
+                let idx = name.to_digit(10).unwrap_or_default() as usize;
+                self.deleted.get(idx.saturating_sub(1))
+            }
+            name => self.named.get(&name.to_ascii_lowercase()),
+        }
+    }
+
+    fn write_named(&mut self, name: char, register: Register) {
+        let key = name.to_ascii_lowercase();
+        let register = match (name.is_ascii_uppercase(), self.named.remove(&key)) {
+            (true, Some(mut existing)) => {
+                // appending whole lines to text makes all of it whole lines
+                if register.linewise && !existing.linewise {
+                    existing.text.push('\n');
+                }
+                existing.text.push_str(&register.text);
+                existing.linewise |= register.linewise;
+                existing
+            }
+            _ => register,
+        };
+        self.named.insert(key, register.clone());
+        self.unnamed = Some(register);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text_object::cursor::Cursor;
+    use crate::text_object::{Selection, SelectionKind, TextObject};
+
+    fn cursor(col: usize, row: usize) -> Cursor {
+        let mut cursor = Cursor::default();
+        cursor.move_to_col(col);
+        cursor.move_to_row(row);
+        cursor
+    }
+
+    #[test]
+    fn test_yank_delete_and_paste() {
+        let mut registers = Registers::default();
+        let mut text = TextObject::from("{\n  \"a\": 1,\n  \"b\": 2\n}\n").with_write();
+
+        let mut selection = Selection::new(SelectionKind::Line, &cursor(0, 1));
+        text.yank_into(&selection, &mut registers, Some('a'));
+        selection.set_kind(SelectionKind::Char);
+        selection.extend_to(&cursor(8, 1));
+        text.yank_into(&selection, &mut registers, Some('A'));
+        assert_eq!(
+            registers.get(Some('a')).unwrap().text,
+            "  \"a\": 1,\n  \"a\": 1,"
+        );
+
+        text.delete_line(2, &mut registers);
+        text.delete_line(1, &mut registers);
+        assert_eq!(text.to_string(), "{\n}\n");
+        assert_eq!(registers.get(Some('1')).unwrap().text, "  \"a\": 1,\n");
+        assert_eq!(registers.get(Some('2')).unwrap().text, "  \"b\": 2\n");
+        assert!(registers.get(Some('0')).is_none());
+
+        let register = registers.get(Some('2')).unwrap().clone();
+        assert_eq!(text.paste_after(&cursor(0, 0), &register), (0, 1));
+        let register = registers.get(None).unwrap().clone();
+        assert_eq!(text.paste_before(&cursor(0, 1), &register), (0, 1));
+        assert_eq!(text.to_string(), "{\n  \"a\": 1,\n  \"b\": 2\n}\n");
+
+        text.erase_until_eol(&cursor(6, 1), &mut registers);
+        let register = registers.get(None).unwrap().clone();
+        assert_eq!(register.text, " 1,");
+        assert_eq!(text.paste_after(&cursor(0, 3), &register), (3, 3));
+        assert_eq!(text.to_string(), "{\n  \"a\":\n  \"b\": 2\n} 1,\n");
+    }
+}
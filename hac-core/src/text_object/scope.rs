@@ -0,0 +1,97 @@
+/// what a text object of vim selects, like the `w` of `iw` or the `{` of
+/// `a{`. Operators act on the inside of it, or around it with `a`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextScope {
+    /// a run of word chars, of other symbols or of whitespace. Around it also
+    /// takes the whitespace that follows it
+    Word,
+    /// text between two of the same quote on the line of the cursor
+    Quote(char),
+    /// text between a pair of brackets, nested pairs are skipped
+    Pair(char, char),
+    /// text between an opening and closing xml tag with the same name
+    Tag,
+}
+
+impl TextScope {
+    /// the scope of the char typed after `i` or `a`, brackets can be typed
+    /// either opened or closed, `b` and `B` are `(` and `{` like on vim
+    pub fn from_char(c: char) -> Option<TextScope> {
+        match c {
+            'w' => Some(TextScope::Word),
+            '"' | '\'' | '`' => Some(TextScope::Quote(c)),
+            '(' | ')' | 'b' => Some(TextScope::Pair('(', ')')),
+            '{' | '}' | 'B' => Some(TextScope::Pair('{', '}')),
+            '[' | ']' => Some(TextScope::Pair('[', ']')),
+            '<' | '>' => Some(TextScope::Pair('<', '>')),
+            't' => Some(TextScope::Tag),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text_object::cursor::Cursor;
+    use crate::text_object::TextObject;
+
+    fn select(content: &str, col: usize, row: usize, scope: char, around: bool) -> Option<String> {
+        let text = TextObject::from(content).with_write();
+        let mut cursor = Cursor::default();
+        cursor.move_to_row(row);
+        cursor.move_to_col(col);
+        let range = text.scope_range(&cursor, TextScope::from_char(scope)?, around)?;
+        Some(
+            content
+                .chars()
+                .skip(range.start)
+                .take(range.len())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_scope_ranges() {
+        let json = "{\n  \"user\": {\"name\": \"o\\\"neil\", \"tags\": [1, 2]}\n}";
+        assert_eq!(select(json, 4, 1, 'w', false).as_deref(), Some("user"));
+        assert_eq!(
+            select("get  the user", 6, 0, 'w', true).as_deref(),
+            Some("the ")
+        );
+        assert_eq!(
+            select(json, 22, 1, '"', false).as_deref(),
+            Some("o\\\"neil")
+        );
+        assert_eq!(
+            select(json, 22, 1, '"', true).as_deref(),
+            Some("\"o\\\"neil\"")
+        );
+        // outside of quotes, the next pair of the line is used
+        assert_eq!(select(json, 0, 1, '"', false).as_deref(), Some("user"));
+        assert_eq!(select(json, 40, 1, ']', false).as_deref(), Some("1, 2"));
+        assert_eq!(select(json, 44, 1, ']', false), None);
+        assert_eq!(
+            select(json, 44, 1, 'B', true).as_deref(),
+            Some("{\"name\": \"o\\\"neil\", \"tags\": [1, 2]}")
+        );
+        // the cursor on the bracket itself selects the pair it opens
+        assert_eq!(
+            select(json, 0, 0, '{', false).map(|s| s.len()),
+            Some(json.len() - 2)
+        );
+        assert_eq!(select(json, 2, 1, '(', false), None);
+
+        let xml = "<user><name>hac</name><tags><tag>a</tag></tags></user>";
+        assert_eq!(select(xml, 13, 0, 't', false).as_deref(), Some("hac"));
+        assert_eq!(
+            select(xml, 13, 0, 't', true).as_deref(),
+            Some("<name>hac</name>")
+        );
+        assert_eq!(
+            select(xml, 23, 0, 't', false).as_deref(),
+            Some("<tag>a</tag>")
+        );
+        assert_eq!(select("no tags here", 3, 0, 't', false), None);
+    }
+}
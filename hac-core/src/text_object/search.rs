@@ -0,0 +1,117 @@
+use std::ops::Add;
+
+/// which way a search looks for the next match from the cursor, `/` or `?`
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum SearchDirection {
+    #[default]
+    Forward,
+    Backward,
+}
+
+impl SearchDirection {
+    pub fn reverse(&self) -> SearchDirection {
+        match self {
+            SearchDirection::Forward => SearchDirection::Backward,
+            SearchDirection::Backward => SearchDirection::Forward,
+        }
+    }
+}
+
+/// a match of a search on a single line, `start` and `end` are the columns of
+/// its first char and of the char after its last one
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchMatch {
+    pub row: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// columns of every match of the pattern on the line. Patterns without
+/// uppercase chars ignore case, like the smartcase of vim
+pub fn find_in_line(line: &str, pattern: &str) -> Vec<(usize, usize)> {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    if pattern.is_empty() {
+        return vec![];
+    }
+
+    let ignore_case = !pattern.iter().any(|c| c.is_uppercase());
+    let chars_eq = |a: &char, b: &char| match ignore_case {
+        true => a.to_lowercase().eq(b.to_lowercase()),
+        false => a.eq(b),
+    };
+
+    let line = line.chars().collect::<Vec<_>>();
+    let mut matches = vec![];
+    let mut col = 0;
+    while col.add(pattern.len()).le(&line.len()) {
+        let window = &line[col..col.add(pattern.len())];
+        match window
+            .iter()
+            .zip(pattern.iter())
+            .all(|(a, b)| chars_eq(a, b))
+        {
+            true => {
+                matches.push((col, col.add(pattern.len())));
+                col = col.add(pattern.len());
+            }
+            false => col = col.add(1),
+        }
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text_object::cursor::Cursor;
+    use crate::text_object::TextObject;
+
+    fn cursor(col: usize, row: usize) -> Cursor {
+        let mut cursor = Cursor::default();
+        cursor.move_to_col(col);
+        cursor.move_to_row(row);
+        cursor
+    }
+
+    #[test]
+    fn test_search_matches() {
+        let text =
+            TextObject::from("{\n  \"Name\": \"name\",\n  \"nickname\": \"Ñame\"\n}").with_write();
+
+        let matches = text.search_matches("name");
+        assert_eq!(
+            matches,
+            vec![
+                SearchMatch {
+                    row: 1,
+                    start: 3,
+                    end: 7
+                },
+                SearchMatch {
+                    row: 1,
+                    start: 11,
+                    end: 15
+                },
+                SearchMatch {
+                    row: 2,
+                    start: 7,
+                    end: 11
+                },
+            ]
+        );
+        // uppercase patterns match case
+        assert_eq!(text.search_matches("Name").len(), 1);
+        assert_eq!(text.search_matches("ñame").len(), 1);
+        assert!(text.search_matches("").is_empty());
+
+        let forward = SearchDirection::Forward;
+        assert_eq!(text.search("name", &cursor(0, 0), forward), Some((3, 1)));
+        assert_eq!(text.search("name", &cursor(3, 1), forward), Some((11, 1)));
+        // searches wrap around the ends of the body
+        assert_eq!(text.search("name", &cursor(7, 2), forward), Some((3, 1)));
+        let backward = forward.reverse();
+        assert_eq!(text.search("name", &cursor(11, 1), backward), Some((3, 1)));
+        assert_eq!(text.search("name", &cursor(3, 1), backward), Some((7, 2)));
+        assert_eq!(text.search("missing", &cursor(0, 0), backward), None);
+    }
+}
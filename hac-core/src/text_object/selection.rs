@@ -0,0 +1,135 @@
+use crate::text_object::cursor::Cursor;
+
+/// how a selection spans the content, like the visual modes of vim
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelectionKind {
+    /// every char from one end to the other, `v`
+    Char,
+    /// every line from one end to the other, `V`
+    Line,
+    /// the same columns on every line from one end to the other, `<C-v>`
+    Block,
+}
+
+/// a region of the content between where the selection started, the
+/// anchor, and where the cursor is now, the head. Both ends are included
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selection {
+    kind: SelectionKind,
+    /// `(col, row)` of where the selection started
+    anchor: (usize, usize),
+    /// `(col, row)` of the cursor
+    head: (usize, usize),
+}
+
+impl Selection {
+    pub fn new(kind: SelectionKind, cursor: &Cursor) -> Selection {
+        let position = (cursor.col(), cursor.row());
+        Selection {
+            kind,
+            anchor: position,
+            head: position,
+        }
+    }
+
+    /// moves the end of the selection that follows the cursor
+    pub fn extend_to(&mut self, cursor: &Cursor) {
+        self.head = (cursor.col(), cursor.row());
+    }
+
+    /// switches between visual modes keeping what was selected
+    pub fn set_kind(&mut self, kind: SelectionKind) {
+        self.kind = kind;
+    }
+
+    pub fn kind(&self) -> SelectionKind {
+        self.kind
+    }
+
+    /// the `(col, row)` of the end that comes first on the content
+    pub fn start(&self) -> (usize, usize) {
+        let (anchor, head) = (self.anchor, self.head);
+        match self.kind {
+            SelectionKind::Char => match (anchor.1, anchor.0) < (head.1, head.0) {
+                true => anchor,
+                false => head,
+            },
+            SelectionKind::Line => (0, anchor.1.min(head.1)),
+            SelectionKind::Block => (anchor.0.min(head.0), anchor.1.min(head.1)),
+        }
+    }
+
+    /// the `(col, row)` of the end that comes last on the content
+    pub fn end(&self) -> (usize, usize) {
+        let (anchor, head) = (self.anchor, self.head);
+        match self.kind {
+            SelectionKind::Char => match (anchor.1, anchor.0) < (head.1, head.0) {
+                true => head,
+                false => anchor,
+            },
+            SelectionKind::Line => (0, anchor.1.max(head.1)),
+            SelectionKind::Block => (anchor.0.max(head.0), anchor.1.max(head.1)),
+        }
+    }
+
+    /// whether the char at `col` of `row` is selected, used to highlight
+    /// the selection
+    pub fn contains(&self, col: usize, row: usize) -> bool {
+        let ((start_col, start_row), (end_col, end_row)) = (self.start(), self.end());
+        if row.lt(&start_row) || row.gt(&end_row) {
+            return false;
+        }
+        match self.kind {
+            SelectionKind::Char => {
+                (row.ne(&start_row) || col.ge(&start_col)) && (row.ne(&end_row) || col.le(&end_col))
+            }
+            SelectionKind::Line => true,
+            SelectionKind::Block => col.ge(&start_col) && col.le(&end_col),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text_object::TextObject;
+
+    fn cursor(col: usize, row: usize) -> Cursor {
+        let mut cursor = Cursor::default();
+        cursor.move_to_col(col);
+        cursor.move_to_row(row);
+        cursor
+    }
+
+    #[test]
+    fn test_selection_ranges() {
+        let content = "first line\nsecond line\nthird\n";
+
+        // selecting backwards from "line" on the second row to "line" on the first
+        let mut selection = Selection::new(SelectionKind::Char, &cursor(3, 1));
+        selection.extend_to(&cursor(6, 0));
+        assert_eq!(selection.start(), (6, 0));
+        assert!(selection.contains(0, 1) && !selection.contains(4, 1));
+        let mut text = TextObject::from(content).with_write();
+        assert_eq!(text.yank_range(&selection), "line\nseco");
+        assert_eq!(text.delete_range(&selection), "line\nseco");
+        assert_eq!(text.to_string(), "first nd line\nthird\n");
+
+        selection.set_kind(SelectionKind::Line);
+        let mut text = TextObject::from(content).with_write();
+        assert_eq!(text.yank_range(&selection), "first line\nsecond line\n");
+        text.replace_range(&selection, "only line");
+        assert_eq!(text.to_string(), "only line\nthird\n");
+
+        // the columns of "irs", "eco" and "hir", lines too short are skipped
+        let mut selection = Selection::new(SelectionKind::Block, &cursor(1, 0));
+        selection.extend_to(&cursor(3, 3));
+        let mut text = TextObject::from(content).with_write();
+        assert_eq!(text.yank_range(&selection), "irs\neco\nhir");
+        text.replace_range(&selection, "-");
+        assert_eq!(text.to_string(), "f-t line\ns-nd line\nt-d\n");
+        let mut text = TextObject::from(content).with_write();
+        text.delete_range(&selection);
+        assert_eq!(text.to_string(), "ft line\nsnd line\ntd\n");
+    }
+}
@@ -0,0 +1,90 @@
+use std::ops::Range;
+
+use crate::text_object::cursor::Cursor;
+use crate::text_object::{TextObject, TextScope, Write};
+use crate::transform::Transform;
+
+/// a string value of a JSON body opened to be edited without its escapes,
+/// like an embedded JSON document or a multiline script. The text is escaped
+/// back into the body it came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct StringEdit {
+    /// the body holding the string, as it was when the string was opened
+    parent: String,
+    /// chars of the body between the quotes of the string
+    range: Range<usize>,
+    /// where the cursor was on the body
+    cursor: Cursor,
+}
+
+impl StringEdit {
+    /// opens the string under the cursor, or the next one on its line,
+    /// returning its unescaped text. Strings with invalid escapes are not
+    /// opened
+    pub fn open(body: &TextObject<Write>, cursor: &Cursor) -> Option<(StringEdit, String)> {
+        let range = body.scope_range(cursor, TextScope::Quote('"'), false)?;
+        let parent = body.to_string();
+        let escaped = parent
+            .chars()
+            .skip(range.start)
+            .take(range.len())
+            .collect::<String>();
+        let text = Transform::JsonUnescape.apply(&escaped).ok()?;
+
+        let edit = StringEdit {
+            parent,
+            range,
+            cursor: cursor.clone(),
+        };
+        Some((edit, text))
+    }
+
+    /// the body with the text escaped in place of the string
+    pub fn apply(&self, text: &str) -> String {
+        let escaped = Transform::JsonEscape
+            .apply(text)
+            .unwrap_or_else(|_| text.to_string());
+        let before = self.parent.chars().take(self.range.start);
+        let after = self.parent.chars().skip(self.range.end);
+        before.chain(escaped.chars()).chain(after).collect()
+    }
+
+    /// where the cursor was on the body when the string was opened
+    pub fn cursor(&self) -> &Cursor {
+        &self.cursor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_string_unescaped() {
+        let body = "{\n  \"script\": \"let a = \\\"b\\\";\\nreturn a;\",\n  \"é\": 1\n}";
+        let mut cursor = Cursor::default();
+        cursor.move_to_row(1);
+        cursor.move_to_col(2);
+        let text = TextObject::from(body).with_write();
+
+        // the key is the first string of the line
+        let (edit, key) = StringEdit::open(&text, &cursor).unwrap();
+        assert_eq!(key, "script");
+        assert_eq!(edit.apply("run"), body.replace("script", "run"));
+
+        cursor.move_to_col(14);
+        let (edit, script) = StringEdit::open(&text, &cursor).unwrap();
+        assert_eq!(script, "let a = \"b\";\nreturn a;");
+        assert_eq!(edit.cursor(), &cursor);
+        assert_eq!(edit.apply(&script), body);
+        assert_eq!(
+            edit.apply("say(\"hi\")\n\treturn;"),
+            "{\n  \"script\": \"say(\\\"hi\\\")\\n\\treturn;\",\n  \"é\": 1\n}"
+        );
+
+        let text = TextObject::from("{\"bad\": \"\\q\"}").with_write();
+        cursor.move_to_row(0);
+        cursor.move_to_col(10);
+        assert!(StringEdit::open(&text, &cursor).is_none());
+    }
+}
@@ -0,0 +1,185 @@
+use std::ops::Add;
+
+use regex::{Regex, RegexBuilder};
+
+/// which lines a substitution runs on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SubstituteScope {
+    /// only the line on the row, `:s`
+    Line(usize),
+    /// every line of the content, `:%s`
+    Buffer,
+}
+
+/// a find and replace like `:%s/foo/bar/g` of vim. The replacement can
+/// refer to groups of the pattern as `$1` or `${name}`
+#[derive(Debug, Clone)]
+pub struct Substitute {
+    pub pattern: Regex,
+    pub replacement: String,
+    pub scope: SubstituteScope,
+    /// whether every match of a line is replaced, or only its first one
+    pub global: bool,
+    /// whether each match should be confirmed before it is replaced
+    pub confirm: bool,
+}
+
+/// a match of a substitution on a single line, with the text it is replaced
+/// by. `start` and `end` are the columns of its first char and of the char
+/// after its last one
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubstituteMatch {
+    pub row: usize,
+    pub start: usize,
+    pub end: usize,
+    pub replacement: String,
+}
+
+impl Substitute {
+    /// parses a command like `s/foo/bar/g` or `%s#a/b#c#`, typed after the
+    /// `:`. The char after `s` separates the parts and can be escaped with a
+    /// backslash. Flags are `g` for every match of the line, `c` to confirm
+    /// each match and `i` to ignore case. Without `%` only the line on `row`
+    /// is changed
+    pub fn parse(command: &str, row: usize) -> anyhow::Result<Substitute> {
+        let command = command.trim_start();
+        let (scope, rest) = match command.strip_prefix('%') {
+            Some(rest) => (SubstituteScope::Buffer, rest),
+            None => (SubstituteScope::Line(row), command),
+        };
+        let Some(rest) = rest.strip_prefix('s') else {
+            anyhow::bail!("not a substitution, expected `s/pattern/replacement/`");
+        };
+        let mut chars = rest.chars();
+        let Some(delimiter) = chars.next().filter(|c| !c.is_alphanumeric() && c.ne(&'\\')) else {
+            anyhow::bail!("missing the delimiter after `s`");
+        };
+
+        let parts = split_parts(chars.as_str(), delimiter);
+        let pattern = parts.first().cloned().unwrap_or_default();
+        if pattern.is_empty() {
+            anyhow::bail!("the pattern of the substitution is empty");
+        }
+        let replacement = parts.get(1).cloned().unwrap_or_default();
+        let flags = parts.get(2).cloned().unwrap_or_default();
+        if let Some(flag) = flags.chars().find(|flag| !matches!(flag, 'g' | 'c' | 'i')) {
+            anyhow::bail!("unknown flag `{flag}`, expected `g`, `c` or `i`");
+        }
+
+        let pattern = RegexBuilder::new(&pattern)
+            .case_insensitive(flags.contains('i'))
+            .build()?;
+        Ok(Substitute {
+            pattern,
+            replacement,
+            scope,
+            global: flags.contains('g'),
+            confirm: flags.contains('c'),
+        })
+    }
+
+    /// the matches of the line with what replaces each of them
+    pub fn find_in_line(&self, row: usize, line: &str) -> Vec<SubstituteMatch> {
+        let mut matches = vec![];
+        for captures in self.pattern.captures_iter(line) {
+            let Some(found) = captures.get(0) else {
+                continue;
+            };
+            let mut replacement = String::default();
+            captures.expand(&self.replacement, &mut replacement);
+            let start = line[..found.start()].chars().count();
+            matches.push(SubstituteMatch {
+                row,
+                start,
+                end: start.add(found.as_str().chars().count()),
+                replacement,
+            });
+            if !self.global {
+                break;
+            }
+        }
+        matches
+    }
+
+    /// whether the line on the row is changed by the substitution
+    pub fn covers(&self, row: usize) -> bool {
+        match self.scope {
+            SubstituteScope::Line(line) => line.eq(&row),
+            SubstituteScope::Buffer => true,
+        }
+    }
+}
+
+/// splits the command on unescaped delimiters, escaped delimiters lose their
+/// backslash while other escapes are kept for the regex
+fn split_parts(text: &str, delimiter: char) -> Vec<String> {
+    let mut parts = vec![String::default()];
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let part = parts.last_mut().expect("there is always a part");
+        match c {
+            '\\' if chars.next_if_eq(&delimiter).is_some() => part.push(delimiter),
+            '\\' => {
+                part.push(c);
+                if let Some(next) = chars.next() {
+                    part.push(next);
+                }
+            }
+            c if c.eq(&delimiter) => parts.push(String::default()),
+            c => part.push(c),
+        }
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text_object::TextObject;
+
+    #[test]
+    fn test_substitute() {
+        let body =
+            "{\n  \"url\": \"http://dev.api/users/12\",\n  \"Host\": \"dev.api\",\n  \"id\": 12\n}";
+
+        let mut text = TextObject::from(body).with_write();
+        let substitute = Substitute::parse("%s/dev\\.api/prod.api/g", 0).unwrap();
+        assert_eq!(text.substitute(&substitute), 2);
+        assert!(text.to_string().contains("http://prod.api/users/12"));
+        assert!(text.to_string().contains("\"Host\": \"prod.api\""));
+
+        // capture groups, and a scope of the line on the row only
+        let mut text = TextObject::from(body).with_write();
+        let substitute = Substitute::parse("s#/users/(?<id>\\d+)#/accounts/${id}#", 1).unwrap();
+        assert_eq!(text.substitute(&substitute), 1);
+        assert!(text.to_string().contains("http://dev.api/accounts/12\""));
+        assert!(text.to_string().contains("\"id\": 12"));
+
+        // confirming, the caller picks which matches are replaced
+        let mut text = TextObject::from(body).with_write();
+        let substitute = Substitute::parse("%s/12/99/gc", 0).unwrap();
+        assert!(substitute.confirm);
+        let matches = text.substitute_matches(&substitute);
+        assert_eq!(matches.len(), 2);
+        assert_eq!((matches[1].row, matches[1].start), (3, 8));
+        text.apply_substitutions(&matches[1..]);
+        assert!(text.to_string().contains("users/12\""));
+        assert!(text.to_string().contains("\"id\": 99"));
+
+        let substitute = Substitute::parse("%s/host/h\\/h/i", 0).unwrap();
+        assert_eq!(substitute.replacement, "h/h");
+        assert_eq!(
+            TextObject::from(body)
+                .with_write()
+                .substitute_matches(&substitute)[0]
+                .start,
+            3
+        );
+        assert!(!substitute.global);
+
+        assert!(Substitute::parse("d/a/b/", 0).is_err());
+        assert!(Substitute::parse("s//b/", 0).is_err());
+        assert!(Substitute::parse("s/a/b/x", 0).is_err());
+        assert!(Substitute::parse("s/(/b/", 0).is_err());
+    }
+}
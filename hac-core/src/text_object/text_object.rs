@@ -1,7 +1,14 @@
+use crate::collection::environment::variable_spans;
+use crate::text_object::line_numbers::LineNumber;
+use crate::text_object::registers::{Register, Registers};
+use crate::text_object::scope::TextScope;
+use crate::text_object::search::{self, SearchDirection, SearchMatch};
+use crate::text_object::selection::{Selection, SelectionKind};
+use crate::text_object::substitute::{Substitute, SubstituteMatch};
 use crate::{syntax::highlighter::Highlighter, text_object::cursor::Cursor};
 
 use std::collections::HashMap;
-use std::ops::{Add, Sub};
+use std::ops::{Add, Range, Sub};
 
 use ropey::Rope;
 use tree_sitter::Tree;
@@ -91,6 +98,550 @@ impl TextObject<Write> {
             .insert(col_offset, &self.line_break.to_string());
     }
 
+    pub fn insert_str(&mut self, text: &str, cursor: &Cursor) {
+        let col_offset = self.char_idx(cursor);
+        self.content.insert(col_offset, text);
+    }
+
+    /// removes every char between `start` and `end`, both being char indexes
+    /// on the whole content
+    pub fn remove_range(&mut self, start: usize, end: usize) {
+        self.content.try_remove(start..end).ok();
+    }
+
+    /// the text inside of the selection, the lines of a block selection
+    /// are joined by line breaks
+    pub fn yank_range(&self, selection: &Selection) -> String {
+        self.selected_ranges(selection)
+            .into_iter()
+            .map(|range| self.content.slice(range).to_string())
+            .collect::<Vec<_>>()
+            .join(&self.line_break.to_string())
+    }
+
+    /// removes everything inside of the selection, returning what was removed
+    pub fn delete_range(&mut self, selection: &Selection) -> String {
+        let removed = self.yank_range(selection);
+        for range in self.selected_ranges(selection).into_iter().rev() {
+            self.content.try_remove(range).ok();
+        }
+        removed
+    }
+
+    /// replaces the selection with `text`, on block selections every line of
+    /// the block is replaced by it
+    pub fn replace_range(&mut self, selection: &Selection, text: &str) {
+        let line_break = self.line_break.to_string();
+        for range in self.selected_ranges(selection).into_iter().rev() {
+            let removed = self.content.slice(range.clone()).to_string();
+            self.content.try_remove(range.clone()).ok();
+            // lines replaced by text without a line break would be joined to
+            // the line after them
+            let keeps_line_break = selection.kind().eq(&SelectionKind::Line)
+                && removed.ends_with(&line_break)
+                && !text.ends_with(&line_break);
+            match keeps_line_break {
+                true => self
+                    .content
+                    .insert(range.start, &format!("{text}{line_break}")),
+                false => self.content.insert(range.start, text),
+            }
+        }
+    }
+
+    /// copies the selection into the named register, or the default one
+    pub fn yank_into(&self, selection: &Selection, registers: &mut Registers, name: Option<char>) {
+        let register = Register {
+            text: self.yank_range(selection),
+            linewise: selection.kind().eq(&SelectionKind::Line),
+        };
+        registers.yank(name, register);
+    }
+
+    /// removes the selection, keeping it on the named register or on the
+    /// delete history
+    pub fn delete_into(
+        &mut self,
+        selection: &Selection,
+        registers: &mut Registers,
+        name: Option<char>,
+    ) {
+        let register = Register {
+            text: self.delete_range(selection),
+            linewise: selection.kind().eq(&SelectionKind::Line),
+        };
+        registers.delete(name, register);
+    }
+
+    /// puts the register after the cursor, or below the line of the cursor
+    /// when it holds whole lines. Returns the `(col, row)` the cursor should
+    /// move to
+    pub fn paste_after(&mut self, cursor: &Cursor, register: &Register) -> (usize, usize) {
+        if !register.linewise {
+            let col = cursor.col().add(1).min(self.line_chars(cursor.row()));
+            return self.paste_text(col, cursor.row(), &register.text);
+        }
+
+        let row = cursor.row().add(1);
+        if row.lt(&self.content.len_lines()) {
+            let text = self.with_line_break(&register.text);
+            self.content.insert(self.content.line_to_char(row), &text);
+        } else {
+            // the last line has no line break to paste after
+            let text = register.text.trim_end_matches(['\r', '\n']);
+            let text = format!("{}{text}", self.line_break);
+            self.content.insert(self.content.len_chars(), &text);
+        }
+        (0, row)
+    }
+
+    /// puts the register before the cursor, or above the line of the cursor
+    /// when it holds whole lines. Returns the `(col, row)` the cursor should
+    /// move to
+    pub fn paste_before(&mut self, cursor: &Cursor, register: &Register) -> (usize, usize) {
+        if !register.linewise {
+            let col = cursor.col().min(self.line_chars(cursor.row()));
+            return self.paste_text(col, cursor.row(), &register.text);
+        }
+
+        let row = cursor.row().min(self.content.len_lines().saturating_sub(1));
+        let text = self.with_line_break(&register.text);
+        self.content.insert(self.content.line_to_char(row), &text);
+        (0, row)
+    }
+
+    /// inserts the text at the position, returning the position of its last
+    /// char
+    fn paste_text(&mut self, col: usize, row: usize, text: &str) -> (usize, usize) {
+        let at = self.content.line_to_char(row).add(col);
+        self.content.insert(at, text);
+        self.position_of(at.add(text.chars().count()).saturating_sub(1).max(at))
+    }
+
+    fn with_line_break(&self, text: &str) -> String {
+        match text.ends_with('\n') {
+            true => text.to_string(),
+            false => format!("{text}{}", self.line_break),
+        }
+    }
+
+    /// how many chars the line has, not counting its line break
+    fn line_chars(&self, row: usize) -> usize {
+        self.content
+            .get_line(row)
+            .map(|line| {
+                line.chars()
+                    .take_while(|c| !matches!(c, '\n' | '\r'))
+                    .count()
+            })
+            .unwrap_or_default()
+    }
+
+    /// every match of the pattern, in the order they appear on the content.
+    /// Matches don't span lines
+    pub fn search_matches(&self, pattern: &str) -> Vec<SearchMatch> {
+        self.content
+            .lines()
+            .enumerate()
+            .flat_map(|(row, line)| {
+                search::find_in_line(&line.to_string(), pattern)
+                    .into_iter()
+                    .map(move |(start, end)| SearchMatch { row, start, end })
+            })
+            .collect()
+    }
+
+    /// the `(col, row)` of the next match of the pattern from the cursor,
+    /// wrapping around the ends of the content
+    pub fn search(
+        &self,
+        pattern: &str,
+        from: &Cursor,
+        direction: SearchDirection,
+    ) -> Option<(usize, usize)> {
+        let matches = self.search_matches(pattern);
+        let from = (from.row(), from.col());
+        let found = match direction {
+            SearchDirection::Forward => matches
+                .iter()
+                .find(|m| (m.row, m.start).gt(&from))
+                .or(matches.first()),
+            SearchDirection::Backward => matches
+                .iter()
+                .rev()
+                .find(|m| (m.row, m.start).lt(&from))
+                .or(matches.last()),
+        };
+        found.map(|m| (m.start, m.row))
+    }
+
+    /// the char range of the text object around the cursor, like the word
+    /// of `iw` or the braces of `a{`. `around` takes the delimiters too, or
+    /// the whitespace after a word
+    pub fn scope_range(
+        &self,
+        cursor: &Cursor,
+        scope: TextScope,
+        around: bool,
+    ) -> Option<Range<usize>> {
+        match scope {
+            TextScope::Word => self.word_range(cursor, around),
+            TextScope::Quote(quote) => self.quote_range(cursor, quote, around),
+            TextScope::Pair(open, close) => self.pair_range(cursor, open, close, around),
+            TextScope::Tag => self.tag_range(cursor, around),
+        }
+    }
+
+    fn word_range(&self, cursor: &Cursor, around: bool) -> Option<Range<usize>> {
+        let line = self.content.get_line(cursor.row())?;
+        let chars = line
+            .chars()
+            .take_while(|c| !matches!(c, '\n' | '\r'))
+            .collect::<Vec<_>>();
+        let class = |c: &char| match c {
+            c if c.is_whitespace() => 0,
+            c if c.is_alphanumeric() || c.eq(&'_') => 1,
+            _ => 2,
+        };
+        let col = cursor.col().min(chars.len().checked_sub(1)?);
+        let kind = class(&chars[col]);
+
+        let mut start = col;
+        while start.gt(&0) && class(&chars[start.sub(1)]).eq(&kind) {
+            start = start.sub(1);
+        }
+        let mut end = col.add(1);
+        while end.lt(&chars.len()) && class(&chars[end]).eq(&kind) {
+            end = end.add(1);
+        }
+
+        // around a word takes the whitespace after it, or the one before it
+        // when it ends the line
+        if around && kind.ne(&0) {
+            let trailing = chars[end..]
+                .iter()
+                .take_while(|c| c.is_whitespace())
+                .count();
+            match trailing {
+                0 => {
+                    while start.gt(&0) && chars[start.sub(1)].is_whitespace() {
+                        start = start.sub(1);
+                    }
+                }
+                trailing => end = end.add(trailing),
+            }
+        }
+
+        let line_start = self.content.line_to_char(cursor.row());
+        Some(line_start.add(start)..line_start.add(end))
+    }
+
+    /// quotes are paired in order along the line, escaped ones are skipped.
+    /// When the cursor is not inside of a pair, the next one is used
+    fn quote_range(&self, cursor: &Cursor, quote: char, around: bool) -> Option<Range<usize>> {
+        let chars = self
+            .content
+            .get_line(cursor.row())?
+            .chars()
+            .collect::<Vec<_>>();
+        let mut quotes = vec![];
+        let mut escaped = false;
+        for (idx, c) in chars.iter().enumerate() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                c if c.eq(&quote) => quotes.push(idx),
+                _ => {}
+            }
+        }
+
+        let pairs = quotes
+            .iter()
+            .step_by(2)
+            .copied()
+            .zip(quotes.iter().skip(1).step_by(2).copied())
+            .collect::<Vec<_>>();
+        let (start, end) = pairs
+            .iter()
+            .find(|(start, end)| cursor.col().ge(start) && cursor.col().le(end))
+            .or_else(|| pairs.iter().find(|(start, _)| start.gt(&cursor.col())))
+            .copied()?;
+
+        let line_start = self.content.line_to_char(cursor.row());
+        match around {
+            true => Some(line_start.add(start)..line_start.add(end).add(1)),
+            false => Some(line_start.add(start).add(1)..line_start.add(end)),
+        }
+    }
+
+    /// the closest pair of brackets holding the cursor, or the pair opened or
+    /// closed by the bracket under it
+    fn pair_range(
+        &self,
+        cursor: &Cursor,
+        open: char,
+        close: char,
+        around: bool,
+    ) -> Option<Range<usize>> {
+        let idx = self.char_idx(cursor);
+        let open_idx = match self.content.get_char(idx)? {
+            c if c.eq(&open) => idx,
+            _ => {
+                let mut depth = 0usize;
+                let mut found = None;
+                for at in (0..idx).rev() {
+                    match self.content.char(at) {
+                        c if c.eq(&close) => depth = depth.add(1),
+                        c if c.eq(&open) && depth.eq(&0) => {
+                            found = Some(at);
+                            break;
+                        }
+                        c if c.eq(&open) => depth = depth.sub(1),
+                        _ => {}
+                    }
+                }
+                found?
+            }
+        };
+
+        let (col, row) = self.position_of(open_idx);
+        let mut open_cursor = Cursor::default();
+        open_cursor.move_to_row(row);
+        open_cursor.move_to_col(col);
+        let (col, row) = self.find_oposing_token(&open_cursor);
+        let close_idx = self.content.line_to_char(row).add(col);
+        if close_idx.eq(&open_idx) {
+            return None;
+        }
+
+        match around {
+            true => Some(open_idx..close_idx.add(1)),
+            false => Some(open_idx.add(1)..close_idx),
+        }
+    }
+
+    /// the innermost xml element holding the cursor, a cursor on one of its
+    /// tags is inside of it
+    fn tag_range(&self, cursor: &Cursor, around: bool) -> Option<Range<usize>> {
+        let chars = self.content.chars().collect::<Vec<_>>();
+        let idx = self.char_idx(cursor);
+
+        // every element as the range of its opening and closing tags
+        let mut open_tags: Vec<(String, Range<usize>)> = vec![];
+        let mut elements: Vec<(Range<usize>, Range<usize>)> = vec![];
+        let mut at = 0;
+        while let Some(start) = chars[at..]
+            .iter()
+            .position(|c| c.eq(&'<'))
+            .map(|pos| pos.add(at))
+        {
+            let Some(end) = chars[start..]
+                .iter()
+                .position(|c| c.eq(&'>'))
+                .map(|pos| pos.add(start).add(1))
+            else {
+                break;
+            };
+            at = end;
+            let tag = chars[start.add(1)..end.sub(1)].iter().collect::<String>();
+            if tag.starts_with(['?', '!']) || tag.ends_with('/') {
+                continue;
+            }
+            let closing = tag.starts_with('/');
+            let name = tag
+                .trim_start_matches('/')
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            if !closing {
+                open_tags.push((name, start..end));
+                continue;
+            }
+            if let Some(position) = open_tags.iter().rposition(|(open, _)| open.eq(&name)) {
+                let (_, open) = open_tags.remove(position);
+                open_tags.truncate(position);
+                elements.push((open, start..end));
+            }
+        }
+
+        let (open, close) = elements
+            .into_iter()
+            .filter(|(open, close)| open.start.le(&idx) && close.end.gt(&idx))
+            .min_by_key(|(open, close)| close.end.sub(open.start))?;
+        match around {
+            true => Some(open.start..close.end),
+            false => Some(open.end..close.start),
+        }
+    }
+
+    /// every match of the substitution on the lines it covers, for the
+    /// caller to confirm before applying them
+    pub fn substitute_matches(&self, substitute: &Substitute) -> Vec<SubstituteMatch> {
+        self.content
+            .lines()
+            .enumerate()
+            .filter(|(row, _)| substitute.covers(*row))
+            .flat_map(|(row, line)| {
+                let line = line.to_string();
+                let line = line.trim_end_matches(['\n', '\r']);
+                substitute.find_in_line(row, line)
+            })
+            .collect()
+    }
+
+    /// replaces the matches, which must not overlap. Matches are applied from
+    /// the last one so the positions of the others stay valid
+    pub fn apply_substitutions(&mut self, matches: &[SubstituteMatch]) {
+        let mut matches = matches.iter().collect::<Vec<_>>();
+        matches.sort_by_key(|m| (m.row, m.start));
+        for found in matches.into_iter().rev() {
+            let line_start = self.content.line_to_char(found.row);
+            let (start, end) = (line_start.add(found.start), line_start.add(found.end));
+            self.content.try_remove(start..end).ok();
+            self.content.insert(start, &found.replacement);
+        }
+    }
+
+    /// replaces every match of the substitution, returning how many were
+    pub fn substitute(&mut self, substitute: &Substitute) -> usize {
+        let matches = self.substitute_matches(substitute);
+        self.apply_substitutions(&matches);
+        matches.len()
+    }
+
+    /// removes the spaces and tabs at the end of every line, returning how
+    /// many lines had them
+    pub fn strip_trailing_whitespace(&mut self) -> usize {
+        let ranges = self
+            .content
+            .lines()
+            .enumerate()
+            .filter_map(|(row, line)| {
+                let line = line.to_string();
+                let line = line.trim_end_matches(['\n', '\r']);
+                let trailing = line
+                    .chars()
+                    .rev()
+                    .take_while(|c| matches!(c, ' ' | '\t'))
+                    .count();
+                let end = self.content.line_to_char(row).add(line.chars().count());
+                trailing.gt(&0).then(|| end.sub(trailing)..end)
+            })
+            .collect::<Vec<_>>();
+
+        for range in ranges.iter().rev() {
+            self.content.try_remove(range.clone()).ok();
+        }
+        ranges.len()
+    }
+
+    /// the line break ending the line, empty on the last one
+    pub fn line_ending(&self, row: usize) -> &'static str {
+        let Some(line) = self.content.get_line(row) else {
+            return "";
+        };
+        let from_end = |n: usize| line.len_chars().checked_sub(n).map(|idx| line.char(idx));
+        match (from_end(2), from_end(1)) {
+            (Some('\r'), Some('\n')) => "\r\n",
+            (_, Some('\n')) => "\n",
+            _ => "",
+        }
+    }
+
+    /// the char ranges inside of the selection, one for every line of block
+    /// selections and a single one otherwise
+    fn selected_ranges(&self, selection: &Selection) -> Vec<Range<usize>> {
+        let ((start_col, start_row), (end_col, end_row)) = (selection.start(), selection.end());
+        let last_row = self.content.len_lines().saturating_sub(1);
+        let len_chars = self.content.len_chars();
+        let idx_at = |col: usize, row: usize| {
+            self.content
+                .line_to_char(row.min(last_row))
+                .add(col)
+                .min(len_chars)
+        };
+
+        match selection.kind() {
+            SelectionKind::Char => {
+                let range = idx_at(start_col, start_row)..idx_at(end_col.add(1), end_row);
+                vec![range]
+            }
+            SelectionKind::Line => {
+                let end = match end_row.lt(&last_row) {
+                    true => self.content.line_to_char(end_row.add(1)),
+                    false => len_chars,
+                };
+                let range = idx_at(0, start_row)..end;
+                vec![range]
+            }
+            SelectionKind::Block => (start_row..=end_row.min(last_row))
+                .filter_map(|row| {
+                    let line_len = self.line_chars(row);
+                    let line_start = self.content.line_to_char(row);
+                    // lines shorter than the block have nothing selected
+                    start_col.lt(&line_len).then(|| {
+                        line_start.add(start_col)..line_start.add(end_col.add(1).min(line_len))
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /// the absolute char index of the cursor on the content
+    pub fn char_idx(&self, cursor: &Cursor) -> usize {
+        self.content.line_to_char(cursor.row()).add(cursor.col())
+    }
+
+    /// converts an absolute char index into a `(col, row)` pair
+    pub fn position_of(&self, char_idx: usize) -> (usize, usize) {
+        let char_idx = char_idx.min(self.content.len_chars());
+        let row = self.content.char_to_line(char_idx);
+        let col = char_idx.sub(self.content.line_to_char(row));
+        (col, row)
+    }
+
+    pub fn len_chars(&self) -> usize {
+        self.content.len_chars()
+    }
+
+    /// the word immediately before the cursor, a word being any sequence of
+    /// alphanumeric characters, `_` or `-`
+    /// the text of the cursor line up to the cursor
+    pub fn text_before_cursor(&self, cursor: &Cursor) -> String {
+        let line_start = self.content.line_to_char(cursor.row());
+        let col_offset = self.char_idx(cursor);
+        self.content.slice(line_start..col_offset).to_string()
+    }
+
+    pub fn word_before_cursor(&self, cursor: &Cursor) -> String {
+        let line_start = self.content.line_to_char(cursor.row());
+        let col_offset = self.char_idx(cursor);
+        let mut word = self
+            .content
+            .slice(line_start..col_offset)
+            .chars()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .take_while(|c| c.is_alphanumeric() || matches!(c, '_' | '-'))
+            .collect::<Vec<_>>();
+        word.reverse();
+        word.into_iter().collect()
+    }
+
+    /// leading whitespace of a given line
+    pub fn line_indentation(&self, line: usize) -> String {
+        self.content
+            .get_line(line)
+            .map(|line| {
+                line.chars()
+                    .take_while(|c| c.is_whitespace() && !matches!(c, '\n' | '\r'))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     pub fn erase_backwards_up_to_line_start(&mut self, cursor: &Cursor) {
         if cursor.col().eq(&0) {
             return;
@@ -136,13 +687,27 @@ impl TextObject<Write> {
             .unwrap_or_default()
     }
 
-    pub fn erase_until_eol(&mut self, cursor: &Cursor) {
+    pub fn erase_until_eol(&mut self, cursor: &Cursor, registers: &mut Registers) {
         let line = self.content.line_to_char(cursor.row());
         let next_line = self.content.line_to_char(cursor.row().add(1));
         let col_offset = line + cursor.col();
-        self.content
-            .try_remove(col_offset..next_line.saturating_sub(1))
-            .ok();
+        self.remove_into(col_offset..next_line.saturating_sub(1), registers, false);
+    }
+
+    /// removes the range, keeping what was removed on the delete history
+    fn remove_into(&mut self, range: Range<usize>, registers: &mut Registers, linewise: bool) {
+        let Some(text) = self
+            .content
+            .get_slice(range.clone())
+            .map(|text| text.to_string())
+        else {
+            return;
+        };
+        if text.is_empty() {
+            return;
+        }
+        self.content.remove(range);
+        registers.delete(None, Register { text, linewise });
     }
 
     pub fn find_char_after_whitespace(&self, cursor: &Cursor) -> (usize, usize) {
@@ -287,21 +852,67 @@ impl TextObject<Write> {
         usize::min(new_row, len_lines.saturating_sub(1))
     }
 
+    /// the `(col, row)` of the first `{{variable}}` that starts after the
+    /// cursor, if any
+    pub fn find_next_variable(&self, cursor: &Cursor) -> Option<(usize, usize)> {
+        let cursor_idx = self.char_idx(cursor);
+        self.variable_starts()
+            .into_iter()
+            .find(|start| start.gt(&cursor_idx))
+            .map(|start| self.position_of(start))
+    }
+
+    /// the `(col, row)` of the closest `{{variable}}` that starts before the
+    /// cursor, if any
+    pub fn find_previous_variable(&self, cursor: &Cursor) -> Option<(usize, usize)> {
+        let cursor_idx = self.char_idx(cursor);
+        self.variable_starts()
+            .into_iter()
+            .rev()
+            .find(|start| start.lt(&cursor_idx))
+            .map(|start| self.position_of(start))
+    }
+
+    /// char indexes where every `{{variable}}` on the content starts
+    fn variable_starts(&self) -> Vec<usize> {
+        variable_spans(&self.content.to_string())
+            .into_iter()
+            .map(|span| self.content.byte_to_char(span.start))
+            .collect()
+    }
+
     pub fn len_lines(&self) -> usize {
         self.content.len_lines()
     }
 
-    pub fn delete_line(&mut self, line: usize) {
-        let start = self.content.line_to_char(line);
-        let end = self.content.line_to_char(line.add(1));
-        self.content.try_remove(start..end).ok();
+    /// the numbers of the lines in a view of `height` rows starting at
+    /// `first_row`, relative to the line of the cursor
+    pub fn line_numbers(
+        &self,
+        first_row: usize,
+        height: usize,
+        cursor: &Cursor,
+    ) -> Vec<LineNumber> {
+        (first_row..first_row.add(height).min(self.content.len_lines()))
+            .map(|row| LineNumber::new(row, cursor.row()))
+            .collect()
+    }
+
+    pub fn delete_line(&mut self, line: usize, registers: &mut Registers) {
+        let Ok(start) = self.content.try_line_to_char(line) else {
+            return;
+        };
+        let end = self
+            .content
+            .line_to_char(line.add(1).min(self.content.len_lines()));
+        self.remove_into(start..end, registers, true);
     }
 
     /// deletes a word forward in one of two ways:
     ///
     /// - if the current character is alphanumeric, then this delete up to the first non alphanumeric character
     /// - if the current character is non alphanumeric, then delete up to the first alphanumeric character
-    pub fn delete_word(&mut self, cursor: &Cursor) {
+    pub fn delete_word(&mut self, cursor: &Cursor, registers: &mut Registers) {
         let start_idx = self.content.line_to_char(cursor.row()).add(cursor.col());
         let mut end_idx = start_idx.saturating_sub(1);
 
@@ -321,7 +932,7 @@ impl TextObject<Write> {
                 }
             }
 
-            self.content.try_remove(start_idx..end_idx).ok();
+            self.remove_into(start_idx..end_idx, registers, false);
         }
     }
 
@@ -354,18 +965,18 @@ impl TextObject<Write> {
     pub fn insert_line_below(&mut self, cursor: &Cursor, tree: Option<&Tree>) {
         let indentation = self.get_scope_aware_indentation(cursor, tree);
         let next_line = self.content.line_to_char(cursor.row().add(1));
-        let line_with_indentation = format!("{}{}", indentation, &self.line_break.to_string());
+        let line_with_indentation = format!("{}{}", indentation, self.line_break);
         self.content.insert(next_line, &line_with_indentation);
     }
 
     pub fn insert_line_above(&mut self, cursor: &Cursor, tree: Option<&Tree>) {
         let indentation = self.get_scope_aware_indentation(cursor, tree);
         let curr_line = self.content.line_to_char(cursor.row());
-        let line_with_indentation = format!("{}{}", indentation, &self.line_break.to_string());
+        let line_with_indentation = format!("{}{}", indentation, self.line_break);
         self.content.insert(curr_line, &line_with_indentation);
     }
 
-    pub fn find_oposing_token(&mut self, cursor: &Cursor) -> (usize, usize) {
+    pub fn find_oposing_token(&self, cursor: &Cursor) -> (usize, usize) {
         let start_idx = self.content.line_to_char(cursor.row()).add(cursor.col());
         let mut combinations = HashMap::new();
         let pairs = [('<', '>'), ('(', ')'), ('[', ']'), ('{', '}')];
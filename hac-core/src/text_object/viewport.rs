@@ -0,0 +1,153 @@
+use std::ops::{Add, Div, Sub};
+
+/// the part of a buffer shown on screen, scrolled to keep the cursor in view.
+/// Rows and columns are of the buffer, the screen position of a cursor is
+/// found by taking the top row and left column out of it
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    /// the first row shown
+    top: usize,
+    /// the first column shown
+    left: usize,
+    height: usize,
+    width: usize,
+    /// rows kept visible above and below the cursor, like the `scrolloff`
+    /// of vim
+    scrolloff: usize,
+}
+
+impl Viewport {
+    pub fn new(height: usize, width: usize, scrolloff: usize) -> Self {
+        Viewport {
+            top: 0,
+            left: 0,
+            height,
+            width,
+            scrolloff,
+        }
+    }
+
+    pub fn top(&self) -> usize {
+        self.top
+    }
+
+    pub fn left(&self) -> usize {
+        self.left
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn resize(&mut self, height: usize, width: usize) {
+        self.height = height;
+        self.width = width;
+    }
+
+    /// scrolls back to the start of the buffer
+    pub fn reset(&mut self) {
+        self.top = 0;
+        self.left = 0;
+    }
+
+    /// scrolls the least needed for the cursor to be shown with the rows of
+    /// the scrolloff around it, except past the end of the buffer
+    pub fn follow(&mut self, row: usize, col: usize, len_lines: usize) {
+        let scrolloff = self.scrolloff();
+        let last_row = len_lines.saturating_sub(1).max(row);
+        let (above, below) = (
+            row.saturating_sub(scrolloff),
+            row.add(scrolloff).min(last_row),
+        );
+        if above.lt(&self.top) {
+            self.top = above;
+        }
+        if below.ge(&self.top.add(self.height)) {
+            self.top = below.add(1).saturating_sub(self.height);
+        }
+
+        if col.lt(&self.left) {
+            self.left = col;
+        }
+        if col.ge(&self.left.add(self.width)) {
+            self.left = col.add(1).saturating_sub(self.width);
+        }
+    }
+
+    /// scrolls the row to the middle of the screen, like `zz`
+    pub fn center(&mut self, row: usize) {
+        self.top = row.saturating_sub(self.height.saturating_sub(1).div(2));
+    }
+
+    /// scrolls the row to the top of the screen, but the scrolloff, like `zt`
+    pub fn scroll_to_top(&mut self, row: usize) {
+        self.top = row.saturating_sub(self.scrolloff());
+    }
+
+    /// scrolls the row to the bottom of the screen, but the scrolloff, like
+    /// `zb`
+    pub fn scroll_to_bottom(&mut self, row: usize) {
+        self.top = row.add(self.scrolloff()).add(1).saturating_sub(self.height);
+    }
+
+    /// where the buffer position is on the screen, as `(col, row)`, when it
+    /// is in view
+    pub fn screen_position(&self, row: usize, col: usize) -> Option<(usize, usize)> {
+        let in_view = (self.top..self.top.add(self.height)).contains(&row)
+            && (self.left..self.left.add(self.width)).contains(&col);
+        in_view.then(|| (col.sub(self.left), row.sub(self.top)))
+    }
+
+    /// the scrolloff, shrunk for screens too short to keep it on both sides
+    fn scrolloff(&self) -> usize {
+        self.scrolloff.min(self.height.saturating_sub(1).div(2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_viewport_scrolling() {
+        let mut viewport = Viewport::new(10, 20, 3);
+
+        // moving inside of the scrolloff doesn't scroll
+        viewport.follow(6, 0, 100);
+        assert_eq!(viewport.top(), 0);
+        viewport.follow(7, 0, 100);
+        assert_eq!(viewport.top(), 1);
+        viewport.follow(50, 0, 100);
+        assert_eq!(
+            (viewport.top(), viewport.screen_position(50, 0)),
+            (44, Some((0, 6)))
+        );
+        viewport.follow(46, 0, 100);
+        assert_eq!(viewport.top(), 43);
+        // the end of the buffer doesn't need the scrolloff below
+        viewport.follow(99, 0, 100);
+        assert_eq!(viewport.top(), 90);
+
+        viewport.center(50);
+        assert_eq!(viewport.top(), 46);
+        viewport.scroll_to_top(50);
+        assert_eq!(viewport.top(), 47);
+        viewport.scroll_to_bottom(50);
+        assert_eq!(viewport.top(), 44);
+        viewport.scroll_to_top(1);
+        assert_eq!(viewport.top(), 0);
+
+        viewport.follow(0, 25, 100);
+        assert_eq!(viewport.left(), 6);
+        assert_eq!(viewport.screen_position(0, 5), None);
+        viewport.follow(0, 2, 100);
+        assert_eq!(viewport.left(), 2);
+
+        // a scrolloff larger than half of the screen keeps the cursor centered
+        let mut viewport = Viewport::new(5, 20, 10);
+        viewport.follow(20, 0, 100);
+        assert_eq!(viewport.screen_position(20, 0), Some((0, 2)));
+        viewport.reset();
+        assert_eq!((viewport.top(), viewport.left()), (0, 0));
+    }
+}
@@ -0,0 +1,136 @@
+use chrono::{DateTime, Local, NaiveDateTime, SecondsFormat, Utc};
+
+/// epochs before 2000 or after 2100 are most likely ids or counters, so they
+/// are not treated as timestamps
+const MIN_EPOCH_SECS: i64 = 946_684_800;
+const MAX_EPOCH_SECS: i64 = 4_102_444_800;
+
+/// an epoch timestamp found on a text, `start` and `end` are byte offsets
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timestamp {
+    pub datetime: DateTime<Utc>,
+    pub is_millis: bool,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// interprets a number as epoch seconds or milliseconds, when it falls on a
+/// plausible range for either
+pub fn from_epoch(value: i64) -> Option<(DateTime<Utc>, bool)> {
+    if (MIN_EPOCH_SECS..MAX_EPOCH_SECS).contains(&value) {
+        return DateTime::from_timestamp(value, 0).map(|datetime| (datetime, false));
+    }
+    if (MIN_EPOCH_SECS * 1000..MAX_EPOCH_SECS * 1000).contains(&value) {
+        return DateTime::from_timestamp_millis(value).map(|datetime| (datetime, true));
+    }
+    None
+}
+
+/// every number on the text that looks like an epoch timestamp, digits that
+/// are part of a larger word or a decimal number are ignored
+pub fn find_timestamps(text: &str) -> Vec<Timestamp> {
+    let bytes = text.as_bytes();
+    let mut timestamps = vec![];
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        if !bytes[idx].is_ascii_digit() {
+            idx += 1;
+            continue;
+        }
+
+        let start = idx;
+        while idx < bytes.len() && bytes[idx].is_ascii_digit() {
+            idx += 1;
+        }
+
+        let is_word = |byte: Option<&u8>| {
+            byte.is_some_and(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'.' | b'_'))
+        };
+        let standalone =
+            !is_word(start.checked_sub(1).and_then(|i| bytes.get(i))) && !is_word(bytes.get(idx));
+
+        if let Some((datetime, is_millis)) = standalone
+            .then(|| text[start..idx].parse().ok())
+            .flatten()
+            .and_then(from_epoch)
+        {
+            timestamps.push(Timestamp {
+                datetime,
+                is_millis,
+                start,
+                end: idx,
+            });
+        }
+    }
+
+    timestamps
+}
+
+/// a readable version of the timestamp, both in UTC and local time
+pub fn describe(datetime: &DateTime<Utc>) -> String {
+    let local = datetime.with_timezone(&Local);
+    format!(
+        "{} UTC ({})",
+        datetime.format("%Y-%m-%d %H:%M:%S"),
+        local.format("%Y-%m-%d %H:%M:%S %:z")
+    )
+}
+
+/// converts an epoch into an RFC 3339 date, or an RFC 3339 date (or a
+/// `YYYY-MM-DD HH:MM:SS` date in UTC) into epoch seconds. Meant to flip a
+/// value back and forth while editing a body
+pub fn convert(value: &str) -> Option<String> {
+    let value = value.trim();
+
+    if let Some((datetime, is_millis)) = value.parse().ok().and_then(from_epoch) {
+        let format = match is_millis {
+            true => SecondsFormat::Millis,
+            false => SecondsFormat::Secs,
+        };
+        return Some(datetime.to_rfc3339_opts(format, true));
+    }
+
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Some(datetime.timestamp().to_string());
+    }
+
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|datetime| datetime.and_utc().timestamp().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timestamps() {
+        let body =
+            r#"{"id": 42, "created": 1700000000, "updated": 1700000000123, "v": 1.1700000000}"#;
+        let found = find_timestamps(body);
+        assert_eq!(found.len(), 2);
+        assert_eq!(&body[found[0].start..found[0].end], "1700000000");
+        assert!(!found[0].is_millis);
+        assert!(found[1].is_millis);
+        assert!(describe(&found[0].datetime).starts_with("2023-11-14 22:13:20 UTC"));
+
+        assert_eq!(
+            convert("1700000000").as_deref(),
+            Some("2023-11-14T22:13:20Z")
+        );
+        assert_eq!(
+            convert("1700000000123").as_deref(),
+            Some("2023-11-14T22:13:20.123Z")
+        );
+        assert_eq!(
+            convert("2023-11-14T22:13:20Z").as_deref(),
+            Some("1700000000")
+        );
+        assert_eq!(
+            convert("2023-11-14 22:13:20").as_deref(),
+            Some("1700000000")
+        );
+        assert_eq!(convert("42"), None);
+    }
+}
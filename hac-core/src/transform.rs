@@ -0,0 +1,110 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+/// encodings that can be applied to a piece of text on the editor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transform {
+    Base64Encode,
+    Base64Decode,
+    UrlEncode,
+    UrlDecode,
+    JsonEscape,
+    JsonUnescape,
+}
+
+impl Transform {
+    pub fn apply(&self, text: &str) -> anyhow::Result<String> {
+        match self {
+            Transform::Base64Encode => Ok(STANDARD.encode(text)),
+            Transform::Base64Decode => {
+                let bytes = STANDARD.decode(text.trim())?;
+                Ok(String::from_utf8(bytes)?)
+            }
+            Transform::UrlEncode => Ok(url_encode(text)),
+            Transform::UrlDecode => url_decode(text),
+            Transform::JsonEscape => {
+                let quoted = serde_json::to_string(text)?;
+                Ok(quoted[1..quoted.len() - 1].to_string())
+            }
+            Transform::JsonUnescape => {
+                let unquoted = text
+                    .strip_prefix('"')
+                    .and_then(|text| text.strip_suffix('"'))
+                    .unwrap_or(text);
+                Ok(serde_json::from_str(&format!("\"{unquoted}\""))?)
+            }
+        }
+    }
+}
+
+/// percent encodes every byte that is not an unreserved character, as
+/// described by RFC 3986
+fn url_encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+/// decodes percent encoded bytes, `+` is treated as a space as forms encode
+/// spaces that way
+fn url_decode(text: &str) -> anyhow::Result<String> {
+    let mut bytes = vec![];
+    let mut chars = text.bytes();
+
+    while let Some(byte) = chars.next() {
+        match byte {
+            b'%' => {
+                let hex = [chars.next(), chars.next()];
+                let [Some(high), Some(low)] = hex else {
+                    anyhow::bail!("incomplete percent encoding at the end of the text");
+                };
+                let hex = std::str::from_utf8(&[high, low])?.to_string();
+                bytes.push(u8::from_str_radix(&hex, 16)?);
+            }
+            b'+' => bytes.push(b' '),
+            byte => bytes.push(byte),
+        }
+    }
+
+    Ok(String::from_utf8(bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transforms() {
+        let text = "user:pässword & more";
+        let round_trips = [
+            (Transform::Base64Encode, Transform::Base64Decode),
+            (Transform::UrlEncode, Transform::UrlDecode),
+            (Transform::JsonEscape, Transform::JsonUnescape),
+        ];
+        for (encode, decode) in round_trips {
+            let encoded = encode.apply(text).unwrap();
+            assert_eq!(decode.apply(&encoded).unwrap(), text);
+        }
+
+        assert_eq!(
+            Transform::UrlEncode.apply("a b/c?").unwrap(),
+            "a%20b%2Fc%3F"
+        );
+        assert_eq!(Transform::UrlDecode.apply("a+b%21").unwrap(), "a b!");
+        assert_eq!(
+            Transform::JsonEscape.apply("{\"a\": \"b\"}\n").unwrap(),
+            r#"{\"a\": \"b\"}\n"#
+        );
+        assert_eq!(
+            Transform::JsonUnescape.apply(r#""line\tbreak""#).unwrap(),
+            "line\tbreak"
+        );
+        assert!(Transform::Base64Decode.apply("not base64!").is_err());
+        assert!(Transform::UrlDecode.apply("%4").is_err());
+    }
+}
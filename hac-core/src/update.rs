@@ -0,0 +1,123 @@
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+/// the latest release of hac, as the github api describes it
+pub const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/wllfaria/hac/releases/latest";
+
+/// how long the release found on a check is trusted before asking again
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60 * 24);
+
+/// when we last asked for the latest release and what it was. This is
+/// persisted on the state directory so we don't ask on every start
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UpdateCheck {
+    /// seconds since the unix epoch
+    pub checked_at: u64,
+    pub latest: Option<String>,
+}
+
+impl UpdateCheck {
+    pub fn load<P>(path: P) -> UpdateCheck
+    where
+        P: AsRef<Path>,
+    {
+        std::fs::read_to_string(path.as_ref())
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save<P>(&self, path: P) -> anyhow::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        std::fs::write(path.as_ref(), serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn is_stale(&self, now: u64) -> bool {
+        now.saturating_sub(self.checked_at) >= CHECK_INTERVAL.as_secs()
+    }
+}
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+}
+
+/// whether `latest` is a greater version than `current`, a leading `v` is
+/// ignored and so are pre-release suffixes like `-beta.1`
+pub fn is_newer(current: &str, latest: &str) -> bool {
+    let parse = |version: &str| {
+        version
+            .trim()
+            .trim_start_matches('v')
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|part| part.parse::<u64>().unwrap_or_default())
+            .collect::<Vec<_>>()
+    };
+    parse(latest) > parse(current)
+}
+
+/// asks github for the version of the latest release
+pub async fn fetch_latest(client: &reqwest::Client) -> anyhow::Result<String> {
+    let release = client
+        .get(LATEST_RELEASE_URL)
+        // the github api refuses requests without an user agent
+        .header(reqwest::header::USER_AGENT, "hac")
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<Release>()
+        .await?;
+    Ok(release.tag_name.trim_start_matches('v').to_string())
+}
+
+/// the latest release when it is newer than `current`. Github is asked at
+/// most once every `CHECK_INTERVAL`, the release found last is used in
+/// between
+pub async fn check(path: &Path, current: &str) -> anyhow::Result<Option<String>> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let mut last_check = UpdateCheck::load(path);
+
+    if last_check.is_stale(now) {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()?;
+        last_check = UpdateCheck {
+            checked_at: now,
+            latest: Some(fetch_latest(&client).await?),
+        };
+        last_check.save(path)?;
+    }
+
+    Ok(last_check.latest.filter(|latest| is_newer(current, latest)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("0.2.0", "0.3.0"));
+        assert!(is_newer("0.2.0", "v0.2.1"));
+        assert!(is_newer("0.9.0", "0.10.0"));
+        assert!(!is_newer("0.2.0", "0.2.0"));
+        assert!(!is_newer("0.2.0", "0.2.0-beta.1"));
+        assert!(!is_newer("1.0.0", "0.9.9"));
+
+        let check = UpdateCheck {
+            checked_at: 1_000,
+            latest: None,
+        };
+        assert!(!check.is_stale(1_000 + 60));
+        assert!(check.is_stale(1_000 + CHECK_INTERVAL.as_secs()));
+    }
+}
@@ -0,0 +1,177 @@
+use crate::collection::environment::{substitute, variable_spans};
+
+use std::collections::BTreeMap;
+
+/// the components of a uri as typed, variables included, so each of them
+/// can be edited on its own
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UrlParts {
+    pub scheme: String,
+    /// the host along with its port, or the variable holding the base url
+    pub host: String,
+    pub path: String,
+    /// the query without the leading `?`
+    pub query: String,
+    /// the fragment without the leading `#`
+    pub fragment: String,
+}
+
+impl UrlParts {
+    pub fn split(uri: &str) -> UrlParts {
+        let (scheme, rest) = match uri.split_once("://") {
+            Some((scheme, rest)) if !scheme.contains(['/', '?', '#']) => (scheme, rest),
+            _ => ("", uri),
+        };
+        let (rest, fragment) = rest.split_once('#').unwrap_or((rest, ""));
+        let (rest, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let (host, path) = match rest.find('/') {
+            Some(idx) => rest.split_at(idx),
+            None => (rest, ""),
+        };
+
+        UrlParts {
+            scheme: scheme.into(),
+            host: host.into(),
+            path: path.into(),
+            query: query.into(),
+            fragment: fragment.into(),
+        }
+    }
+
+    pub fn join(&self) -> String {
+        let mut uri = String::default();
+        if !self.scheme.is_empty() {
+            uri.push_str(&self.scheme);
+            uri.push_str("://");
+        }
+        uri.push_str(&self.host);
+        uri.push_str(&self.path);
+        if !self.query.is_empty() {
+            uri.push('?');
+            uri.push_str(&self.query);
+        }
+        if !self.fragment.is_empty() {
+            uri.push('#');
+            uri.push_str(&self.fragment);
+        }
+        uri
+    }
+}
+
+/// the uri that is sent, with its variables resolved and every character
+/// that needs it percent-encoded
+pub fn preview(uri: &str, variables: &BTreeMap<String, String>) -> Result<reqwest::Url, String> {
+    let resolved = substitute(uri, variables);
+    if let Some(span) = variable_spans(&resolved).first() {
+        return Err(format!("{{{{{}}}}} is not defined", span.name));
+    }
+    reqwest::Url::parse(resolved.trim()).map_err(|e| e.to_string())
+}
+
+/// mistakes on the uri that still make a valid url, just not the intended
+/// one
+pub fn warnings(uri: &str, variables: &BTreeMap<String, String>) -> Vec<String> {
+    let mut warnings = vec![];
+
+    // a base url variable followed right away by the path, like
+    // `{{base_url}}users`, glues the path to the host
+    for span in variable_spans(uri) {
+        let Some(value) = variables.get(&span.name) else {
+            continue;
+        };
+        let glued = uri[span.end..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_alphanumeric());
+        if glued && value.contains("://") && !value.ends_with('/') {
+            warnings.push(format!("missing / after {{{{{}}}}}", span.name));
+        }
+    }
+
+    let resolved = substitute(uri, variables);
+    let parts = UrlParts::split(&resolved);
+    if parts.path.contains("//") {
+        warnings.push("the path has an empty segment, //".into());
+    }
+
+    let bytes = resolved.as_bytes();
+    let encoded_twice = bytes.windows(5).find(|window| {
+        window.starts_with(b"%25") && window[3..].iter().all(|b| b.is_ascii_hexdigit())
+    });
+    if let Some(window) = encoded_twice {
+        let sequence = String::from_utf8_lossy(window);
+        warnings.push(format!(
+            "{sequence} looks encoded twice, %{} was probably meant",
+            &sequence[3..]
+        ));
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_and_join() {
+        let parts = UrlParts::split("https://api.example.com:8080/users/1?page=2&q=a b#top");
+        assert_eq!(
+            parts,
+            UrlParts {
+                scheme: "https".into(),
+                host: "api.example.com:8080".into(),
+                path: "/users/1".into(),
+                query: "page=2&q=a b".into(),
+                fragment: "top".into(),
+            }
+        );
+        assert_eq!(
+            parts.join(),
+            "https://api.example.com:8080/users/1?page=2&q=a b#top"
+        );
+
+        let parts = UrlParts::split("{{base_url}}/users?redirect=http://other");
+        assert_eq!(parts.scheme, "");
+        assert_eq!(parts.host, "{{base_url}}");
+        assert_eq!(parts.path, "/users");
+        assert_eq!(parts.query, "redirect=http://other");
+        assert_eq!(parts.join(), "{{base_url}}/users?redirect=http://other");
+    }
+
+    #[test]
+    fn test_preview() {
+        let variables = BTreeMap::from([("base_url".into(), "https://example.com".into())]);
+        assert_eq!(
+            preview("{{base_url}}/search?q=a b&tag=ü", &variables)
+                .unwrap()
+                .as_str(),
+            "https://example.com/search?q=a%20b&tag=%C3%BC"
+        );
+        assert_eq!(
+            preview("{{host}}/users", &variables),
+            Err("{{host}} is not defined".into())
+        );
+    }
+
+    #[test]
+    fn test_warnings() {
+        let variables = BTreeMap::from([
+            ("base_url".into(), "https://example.com".into()),
+            ("root".into(), "https://example.com/".into()),
+        ]);
+        assert_eq!(
+            warnings("{{base_url}}users", &variables),
+            vec!["missing / after {{base_url}}"]
+        );
+        assert_eq!(
+            warnings("{{root}}/users", &variables),
+            vec!["the path has an empty segment, //"]
+        );
+        assert_eq!(
+            warnings("{{base_url}}/search?q=a%2520b", &variables),
+            vec!["%2520 looks encoded twice, %20 was probably meant"]
+        );
+        assert!(warnings("{{base_url}}/users?q=a%20b", &variables).is_empty());
+    }
+}
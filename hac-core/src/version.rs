@@ -0,0 +1,80 @@
+use std::fmt::Write;
+
+use serde::Serialize;
+
+/// what identifies a build of hac, to be included in bug reports
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct VersionInfo {
+    pub version: String,
+    /// the commit it was built from, when built from a git checkout
+    pub commit: Option<String>,
+    /// the operating system and architecture it was built for
+    pub target: String,
+    /// optional cargo features it was built with
+    pub features: Vec<&'static str>,
+    /// the tree-sitter library and grammars used for highlighting, with
+    /// their versions
+    pub grammars: Vec<(String, String)>,
+}
+
+impl VersionInfo {
+    /// the build running now, `version` is the one of the binary
+    pub fn current(version: &str) -> VersionInfo {
+        let grammars = env!("HAC_GRAMMAR_VERSIONS")
+            .split(',')
+            .filter_map(|grammar| grammar.split_once('='))
+            .map(|(name, version)| (name.to_string(), version.to_string()))
+            .collect();
+
+        VersionInfo {
+            version: version.to_string(),
+            commit: option_env!("HAC_COMMIT_HASH").map(Into::into),
+            target: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+            features: [("negotiate", cfg!(feature = "negotiate"))]
+                .into_iter()
+                .filter_map(|(feature, enabled)| enabled.then_some(feature))
+                .collect(),
+            grammars,
+        }
+    }
+
+    /// the version alone, or everything about the build when `verbose`
+    pub fn render(&self, verbose: bool) -> String {
+        let mut out = format!("hac {}\n", self.version);
+        if !verbose {
+            return out;
+        }
+
+        _ = writeln!(
+            out,
+            "commit: {}",
+            self.commit.as_deref().unwrap_or("unknown")
+        );
+        _ = writeln!(out, "target: {}", self.target);
+        let features = match self.features.is_empty() {
+            true => "none".to_string(),
+            false => self.features.join(", "),
+        };
+        _ = writeln!(out, "features: {features}");
+        _ = writeln!(out, "tree-sitter abi: {}", tree_sitter::LANGUAGE_VERSION);
+        for (name, version) in self.grammars.iter() {
+            _ = writeln!(out, "{name}: {version}");
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_version() {
+        let info = VersionInfo::current("0.2.0");
+        assert_eq!(info.render(false), "hac 0.2.0\n");
+
+        let verbose = info.render(true);
+        assert!(verbose.starts_with("hac 0.2.0\ncommit: "));
+        assert!(verbose.contains("\ntree-sitter-json: "));
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// the tabs of the response viewer
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ViewerTab {
+    #[default]
+    Pretty,
+    Tree,
+    Table,
+    Raw,
+    Headers,
+    Cookies,
+    Status,
+}
+
+/// how the user last looked at the responses of a request
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ViewerPreferences {
+    #[serde(default)]
+    pub tab: ViewerTab,
+    /// whether long lines of the pretty body wrap instead of being cut
+    #[serde(default)]
+    pub wrap: bool,
+    /// whether epoch timestamps are annotated with a readable date
+    #[serde(default)]
+    pub timestamps: bool,
+    /// JSONPath the body is narrowed down to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+}
+
+/// the viewer preferences of every request, keyed by request id. This is
+/// persisted on the data directory so reopening a request shows its
+/// responses the same way
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ViewerPreferencesStore {
+    requests: HashMap<String, ViewerPreferences>,
+}
+
+impl ViewerPreferencesStore {
+    /// loads the preferences from disk, a missing or corrupted file means
+    /// every request starts with the defaults
+    pub fn load<P>(path: P) -> ViewerPreferencesStore
+    where
+        P: AsRef<Path>,
+    {
+        std::fs::read_to_string(path.as_ref())
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save<P>(&self, path: P) -> anyhow::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        std::fs::write(path.as_ref(), serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// whether the user changed how the responses of the request are shown
+    pub fn contains(&self, request_id: &str) -> bool {
+        self.requests.contains_key(request_id)
+    }
+
+    pub fn get(&self, request_id: &str) -> ViewerPreferences {
+        self.requests.get(request_id).cloned().unwrap_or_default()
+    }
+
+    /// stores the preferences of the request, requests left with the
+    /// defaults are not kept
+    pub fn set(&mut self, request_id: &str, preferences: ViewerPreferences) {
+        match preferences.eq(&ViewerPreferences::default()) {
+            true => self.requests.remove(request_id),
+            false => self.requests.insert(request_id.into(), preferences),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_and_persist() {
+        let mut store = ViewerPreferencesStore::default();
+        let preferences = ViewerPreferences {
+            tab: ViewerTab::Table,
+            wrap: true,
+            timestamps: false,
+            filter: Some("$.data".into()),
+        };
+        store.set("request", preferences.clone());
+        assert_eq!(store.get("request"), preferences);
+        assert_eq!(store.get("other"), ViewerPreferences::default());
+
+        let path = std::env::temp_dir().join(format!("hac-viewer-{}.json", std::process::id()));
+        store.save(&path).unwrap();
+        assert_eq!(ViewerPreferencesStore::load(&path), store);
+        std::fs::remove_file(path).unwrap();
+
+        store.set("request", ViewerPreferences::default());
+        assert_eq!(store, ViewerPreferencesStore::default());
+    }
+}